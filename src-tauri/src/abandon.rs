@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::{Run, Split};
+
+/// Why a run was reset before it finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbandonReason {
+    Death,
+    BadLayout,
+    BrickedGear,
+    Irl,
+    ServerIssues,
+    Other,
+}
+
+impl AbandonReason {
+    pub const ALL: [AbandonReason; 6] = [
+        AbandonReason::Death,
+        AbandonReason::BadLayout,
+        AbandonReason::BrickedGear,
+        AbandonReason::Irl,
+        AbandonReason::ServerIssues,
+        AbandonReason::Other,
+    ];
+
+    /// The value stored in `runs.abandon_reason`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AbandonReason::Death => "death",
+            AbandonReason::BadLayout => "bad_layout",
+            AbandonReason::BrickedGear => "bricked_gear",
+            AbandonReason::Irl => "irl",
+            AbandonReason::ServerIssues => "server_issues",
+            AbandonReason::Other => "other",
+        }
+    }
+
+    /// Unknown stored values count as `Other`
+    pub fn from_stored(value: &str) -> Self {
+        AbandonReason::ALL
+            .into_iter()
+            .find(|reason| reason.as_str() == value)
+            .unwrap_or(AbandonReason::Other)
+    }
+}
+
+/// Runs abandoned for one reason
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReasonCount {
+    pub reason: AbandonReason,
+    pub count: i64,
+    /// Fraction of the abandoned runs
+    pub share: f64,
+    /// How far into the run, on average, it was abandoned
+    pub average_abandoned_at_ms: Option<i64>,
+}
+
+/// Result of `get_abandonment_stats`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbandonmentStats {
+    pub total_runs: i64,
+    pub completed_runs: i64,
+    pub abandoned_runs: i64,
+    /// Unfinished runs without a reason: reset from the hotkey, recorded
+    /// before reasons existed, or still in progress
+    pub unexplained_runs: i64,
+    /// Most common reason first; reasons no run was abandoned for are left out
+    pub by_reason: Vec<ReasonCount>,
+}
+
+/// Tally the abandonment reasons of `runs`
+pub fn stats(runs: &[Run]) -> AbandonmentStats {
+    let abandoned: Vec<(AbandonReason, Option<i64>)> = runs
+        .iter()
+        .filter(|run| !run.is_completed)
+        .filter_map(|run| Some((AbandonReason::from_stored(run.abandon_reason.as_deref()?), run.abandoned_at_ms)))
+        .collect();
+    let completed_runs = runs.iter().filter(|run| run.is_completed).count() as i64;
+
+    let mut by_reason: Vec<ReasonCount> = AbandonReason::ALL
+        .into_iter()
+        .filter_map(|reason| {
+            let of_reason: Vec<Option<i64>> =
+                abandoned.iter().filter(|(r, _)| *r == reason).map(|(_, at)| *at).collect();
+            if of_reason.is_empty() {
+                return None;
+            }
+            let times: Vec<i64> = of_reason.iter().flatten().copied().collect();
+            Some(ReasonCount {
+                reason,
+                count: of_reason.len() as i64,
+                share: of_reason.len() as f64 / abandoned.len() as f64,
+                average_abandoned_at_ms: (!times.is_empty()).then(|| times.iter().sum::<i64>() / times.len() as i64),
+            })
+        })
+        .collect();
+    // Stable, so ties keep the taxonomy's order
+    by_reason.sort_by_key(|r| std::cmp::Reverse(r.count));
+
+    AbandonmentStats {
+        total_runs: runs.len() as i64,
+        completed_runs,
+        abandoned_runs: abandoned.len() as i64,
+        unexplained_runs: runs.len() as i64 - completed_runs - abandoned.len() as i64,
+        by_reason,
+    }
+}
+
+/// Resets at one split: the last one the runs reached
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitResets {
+    /// `None` for runs reset before their first split
+    pub breakpoint_name: Option<String>,
+    pub breakpoint_key: Option<String>,
+    pub act: Option<i32>,
+    pub count: i64,
+    /// Fraction of the resets
+    pub share: f64,
+}
+
+/// Resets during one act
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActResets {
+    pub act: i32,
+    pub count: i64,
+    pub share: f64,
+}
+
+/// Result of `get_reset_stats`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetStats {
+    /// Runs started, finished or not
+    pub attempts: i64,
+    pub resets: i64,
+    /// Most resets first
+    pub by_split: Vec<SplitResets>,
+    /// Most resets first; resets after a split without an act (custom
+    /// splits) are left out
+    pub by_act: Vec<ActResets>,
+}
+
+/// Where `runs` were reset. A run counts once `abandon_run` marked it; the
+/// act is its last split's, or Act 1 before the first split. A split named
+/// differently by other presets keeps the name the first of `runs` gave it,
+/// and ties keep the order of `runs` (newest first from `get_filtered`).
+pub fn reset_stats(runs: &[Run]) -> ResetStats {
+    let resets: Vec<&Run> = runs
+        .iter()
+        .filter(|run| !run.is_completed && run.abandoned_at_ms.is_some())
+        .collect();
+    let share = |count: i64| count as f64 / resets.len() as f64;
+
+    let mut by_split: Vec<SplitResets> = Vec::new();
+    for run in &resets {
+        // Same split when the keys match, by name when either has none
+        let existing = by_split.iter_mut().find(|entry| match (&entry.breakpoint_key, &run.reset_split_key) {
+            (Some(key), Some(other)) => key == other,
+            _ => entry.breakpoint_name == run.reset_split,
+        });
+        match existing {
+            Some(entry) => entry.count += 1,
+            None => by_split.push(SplitResets {
+                breakpoint_name: run.reset_split.clone(),
+                breakpoint_key: run.reset_split_key.clone(),
+                act: match (&run.reset_split, &run.reset_split_key) {
+                    (None, _) => Some(1),
+                    (Some(_), key) => key.as_deref().and_then(Split::act_of_key),
+                },
+                count: 1,
+                share: 0.0,
+            }),
+        }
+    }
+    by_split.sort_by_key(|entry| std::cmp::Reverse(entry.count));
+
+    let mut by_act: Vec<ActResets> = Vec::new();
+    for entry in &by_split {
+        let Some(act) = entry.act else { continue };
+        match by_act.iter_mut().find(|a| a.act == act) {
+            Some(a) => a.count += entry.count,
+            None => by_act.push(ActResets { act, count: entry.count, share: 0.0 }),
+        }
+    }
+    by_act.sort_by_key(|a| (std::cmp::Reverse(a.count), a.act));
+
+    for entry in &mut by_split {
+        entry.share = share(entry.count);
+    }
+    for entry in &mut by_act {
+        entry.share = share(entry.count);
+    }
+
+    ResetStats { attempts: runs.len() as i64, resets: resets.len() as i64, by_split, by_act }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reasons_round_trip_through_storage() {
+        for reason in AbandonReason::ALL {
+            assert_eq!(AbandonReason::from_stored(reason.as_str()), reason);
+            let json = serde_json::to_string(&reason).unwrap();
+            assert_eq!(json, format!("\"{}\"", reason.as_str()));
+        }
+        assert_eq!(AbandonReason::from_stored("rage_quit"), AbandonReason::Other);
+    }
+}
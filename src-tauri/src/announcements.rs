@@ -0,0 +1,129 @@
+use serde::Serialize;
+
+use crate::db::Settings;
+
+/// Why a split is being announced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementKind {
+    Gold,
+    Ahead,
+    Behind,
+}
+
+/// Payload of the `split-announcement` event. The frontend plays the tone and/or
+/// speaks `message`; whether to announce at all is decided here.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitAnnouncement {
+    pub kind: AnnouncementKind,
+    pub breakpoint_name: String,
+    pub delta_ms: Option<i64>,
+    pub message: String,
+    pub sound: bool,
+    pub speech: bool,
+}
+
+/// Decide whether a split should be announced under the user's thresholds.
+/// Golds are always announced; otherwise the delta against the PB must be at
+/// least `announce_min_behind_ms` behind or `announce_min_ahead_ms` ahead.
+pub fn evaluate(
+    settings: &Settings,
+    breakpoint_name: &str,
+    delta_ms: Option<i64>,
+    is_gold: bool,
+) -> Option<SplitAnnouncement> {
+    if !settings.announce_sound && !settings.announce_speech {
+        return None;
+    }
+
+    let kind = if is_gold {
+        AnnouncementKind::Gold
+    } else if settings.announce_golds_only {
+        return None;
+    } else {
+        match delta_ms? {
+            d if d > 0 && d >= settings.announce_min_behind_ms => AnnouncementKind::Behind,
+            d if d < 0 && -d >= settings.announce_min_ahead_ms => AnnouncementKind::Ahead,
+            _ => return None,
+        }
+    };
+
+    let message = match (kind, delta_ms) {
+        (AnnouncementKind::Gold, _) => format!("{}, gold split", breakpoint_name),
+        (AnnouncementKind::Behind, Some(d)) => format!("{}, {} behind", breakpoint_name, spoken_duration(d)),
+        (_, d) => format!("{}, {} ahead", breakpoint_name, spoken_duration(d.unwrap_or(0))),
+    };
+
+    Some(SplitAnnouncement {
+        kind,
+        breakpoint_name: breakpoint_name.to_string(),
+        delta_ms,
+        message,
+        sound: settings.announce_sound,
+        speech: settings.announce_speech,
+    })
+}
+
+/// "1 minute 5 seconds" style duration for text-to-speech
+fn spoken_duration(ms: i64) -> String {
+    let secs = ms.abs() / 1000;
+    let (mins, secs) = (secs / 60, secs % 60);
+    let unit = |n: i64, name: &str| format!("{} {}{}", n, name, if n == 1 { "" } else { "s" });
+    match (mins, secs) {
+        (0, s) => unit(s, "second"),
+        (m, 0) => unit(m, "minute"),
+        (m, s) => format!("{} {}", unit(m, "minute"), unit(s, "second")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(golds_only: bool, min_behind_ms: i64, min_ahead_ms: i64) -> Settings {
+        Settings {
+            announce_sound: true,
+            announce_golds_only: golds_only,
+            announce_min_behind_ms: min_behind_ms,
+            announce_min_ahead_ms: min_ahead_ms,
+            ..Default::default()
+        }
+    }
+
+    fn kind(settings: &Settings, delta_ms: Option<i64>, is_gold: bool) -> Option<AnnouncementKind> {
+        evaluate(settings, "The Coast", delta_ms, is_gold).map(|a| a.kind)
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        assert_eq!(evaluate(&Settings::default(), "The Coast", Some(60_000), true), None);
+    }
+
+    #[test]
+    fn test_behind_threshold() {
+        let s = settings(false, 30_000, 0);
+        assert_eq!(kind(&s, Some(29_999), false), None);
+        assert_eq!(kind(&s, Some(30_000), false), Some(AnnouncementKind::Behind));
+        assert_eq!(kind(&s, Some(-1_000), false), Some(AnnouncementKind::Ahead));
+        assert_eq!(kind(&s, None, false), None);
+    }
+
+    #[test]
+    fn test_golds_only() {
+        let s = settings(true, 0, 0);
+        assert_eq!(kind(&s, Some(90_000), false), None);
+        assert_eq!(kind(&s, Some(90_000), true), Some(AnnouncementKind::Gold));
+    }
+
+    #[test]
+    fn test_message() {
+        let s = settings(false, 30_000, 10_000);
+        let behind = evaluate(&s, "The Coast", Some(35_400), false).unwrap();
+        assert_eq!(behind.message, "The Coast, 35 seconds behind");
+        let ahead = evaluate(&s, "The Ledge", Some(-61_000), false).unwrap();
+        assert_eq!(ahead.message, "The Ledge, 1 minute 1 second ahead");
+        let gold = evaluate(&s, "The Ledge", Some(5_000), true).unwrap();
+        assert_eq!(gold.message, "The Ledge, gold split");
+    }
+}
@@ -1,59 +1,107 @@
 use anyhow::Result;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+use crate::db::{OAuthToken, Settings};
+use crate::game::Game;
+use crate::oauth;
+use crate::pob_builder;
+use crate::rate_limit::{self, RateLimits};
+
 const POE_API_BASE: &str = "https://www.pathofexile.com";
-const USER_AGENT: &str = "POE-Watcher/0.2.0 (https://github.com/kburke8/poe-watcher; Discord: beerdz)";
-
-/// Rate limiter using token bucket algorithm
-struct RateLimiter {
-    tokens: f64,
-    max_tokens: f64,
-    refill_rate: f64, // tokens per second
-    last_update: Instant,
+/// Authenticated API, used instead of the public character-window endpoints
+/// when the user has signed in (see `oauth.rs`)
+const POE_OAUTH_API_BASE: &str = "https://api.pathofexile.com";
+pub(crate) const USER_AGENT: &str = "POE-Watcher/0.2.0 (https://github.com/kburke8/poe-watcher; Discord: beerdz)";
+/// GGG requires OAuth clients to identify as `OAuth {client_id}/{version}`
+const OAUTH_USER_AGENT: &str = "OAuth poewatcher/0.2.0 (contact: https://github.com/kburke8/poe-watcher)";
+
+/// Typed failures from the POE API that callers need to tell apart
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("Profile is private. Please set your POE profile to public in account settings.")]
+    PrivateProfile,
+    #[error("Rate limited. Please try again later.")]
+    RateLimited,
+    #[error("Account not found")]
+    AccountNotFound,
+    #[error("Path of Exile sign-in expired. Sign in again in Settings.")]
+    Unauthorized,
 }
 
-impl RateLimiter {
-    fn new(max_tokens: f64, refill_rate: f64) -> Self {
-        RateLimiter {
-            tokens: max_tokens,
-            max_tokens,
-            refill_rate,
-            last_update: Instant::now(),
-        }
-    }
+/// Most ladder entries the public ladder returns per request
+const LADDER_LIMIT: usize = 200;
 
-    fn try_acquire(&mut self) -> bool {
-        self.refill();
-        if self.tokens >= 1.0 {
-            self.tokens -= 1.0;
-            true
-        } else {
-            false
-        }
-    }
+/// Retries of a request answered with 429 before giving up
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+/// Longer `Retry-After` penalties fail the request instead of stalling captures
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(120);
+/// Characters of a response body quoted in a parse error
+const EXCERPT_CHARS: usize = 200;
 
-    fn refill(&mut self) {
-        let now = Instant::now();
-        let elapsed = now.duration_since(self.last_update).as_secs_f64();
-        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.max_tokens);
-        self.last_update = now;
-    }
+/// Start of a response body for an error message, cut on a character boundary
+pub(crate) fn excerpt(text: &str) -> String {
+    text.chars().take(EXCERPT_CHARS).collect()
+}
 
-    fn time_until_available(&self) -> Duration {
-        if self.tokens >= 1.0 {
-            Duration::ZERO
-        } else {
-            let needed = 1.0 - self.tokens;
-            Duration::from_secs_f64(needed / self.refill_rate)
+/// Cache key of a GET: the URL, plus a hash of the bearer token for
+/// authenticated requests, so a response is only reused for the same sign-in
+fn cache_key(url: &str, bearer: Option<&str>) -> String {
+    match bearer {
+        Some(token) => {
+            let hash: String = Sha256::digest(token.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+            format!("{} bearer:{}", url, hash)
         }
+        None => url.to_string(),
     }
 }
 
+/// Character-window endpoints used for snapshots.
+///
+/// Implemented by `PoeApiClient` for the live API and by a fixture-backed fake in
+/// tests, so snapshot capture can be exercised without hitting pathofexile.com.
+pub trait PoeApi: Send + Sync {
+    /// Get characters for an account
+    fn get_characters(&self, account_name: &str) -> impl Future<Output = Result<Vec<PoeCharacter>>> + Send;
+
+    /// Get equipped and inventory items for a character
+    fn get_items(
+        &self,
+        account_name: &str,
+        character_name: &str,
+    ) -> impl Future<Output = Result<CharacterItems>> + Send;
+
+    /// Get allocated passive skills for a character
+    fn get_passive_skills(
+        &self,
+        account_name: &str,
+        character_name: &str,
+    ) -> impl Future<Output = Result<PassiveSkills>> + Send;
+
+    /// Get the account's public profile page (HTML), visible even when the
+    /// characters tab is hidden
+    fn get_profile_page(&self, account_name: &str) -> impl Future<Output = Result<String>> + Send;
+}
+
+/// Raw response kept by a recording client (see `PoeApiClient::recording`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedResponse {
+    pub url: String,
+    /// HTTP status, or `None` when the response came from the cache
+    pub status: Option<u16>,
+    pub body: String,
+}
+
+/// Responses seen by a recording client, in request order
+pub type ResponseLog = Arc<std::sync::Mutex<Vec<RecordedResponse>>>;
+
 /// Response cache entry
 struct CacheEntry<T> {
     data: T,
@@ -63,8 +111,13 @@ struct CacheEntry<T> {
 /// POE API client with rate limiting and caching
 pub struct PoeApiClient {
     client: Client,
-    rate_limiter: Arc<Mutex<RateLimiter>>,
+    rate_limits: Arc<Mutex<RateLimits>>,
     cache: Arc<Mutex<HashMap<String, CacheEntry<String>>>>,
+    recorder: Option<ResponseLog>,
+    /// Signed-in account's tokens, if any
+    oauth: Arc<Mutex<Option<OAuthToken>>>,
+    /// Game whose characters the character endpoints look up
+    game: Arc<Mutex<Game>>,
 }
 
 impl PoeApiClient {
@@ -77,30 +130,250 @@ impl PoeApiClient {
 
         PoeApiClient {
             client,
-            // 5 requests per second with burst of 10
-            rate_limiter: Arc::new(Mutex::new(RateLimiter::new(10.0, 5.0))),
+            rate_limits: Arc::new(Mutex::new(RateLimits::new(Instant::now()))),
             cache: Arc::new(Mutex::new(HashMap::new())),
+            recorder: None,
+            oauth: Arc::new(Mutex::new(None)),
+            game: Arc::new(Mutex::new(Game::default())),
         }
     }
 
-    /// Wait for rate limiter before making a request
-    async fn wait_for_rate_limit(&self) {
-        loop {
-            let wait_time = {
-                let mut limiter = self.rate_limiter.lock().await;
-                if limiter.try_acquire() {
-                    return;
+    /// Start out signed in with a stored token
+    pub fn with_oauth_token(self, token: Option<OAuthToken>) -> Self {
+        PoeApiClient {
+            oauth: Arc::new(Mutex::new(token)),
+            ..self
+        }
+    }
+
+    /// Sign in or out. Clears the cache so nothing read with the old
+    /// credentials is served afterwards.
+    pub async fn set_oauth_token(&self, token: Option<OAuthToken>) {
+        *self.oauth.lock().await = token;
+        self.cache.lock().await.clear();
+    }
+
+    /// Start out looking up `game`'s characters
+    pub fn with_game(self, game: Game) -> Self {
+        PoeApiClient {
+            game: Arc::new(Mutex::new(game)),
+            ..self
+        }
+    }
+
+    /// Switch games. Clears the cache so the other game's characters aren't
+    /// served afterwards.
+    pub async fn set_game(&self, game: Game) {
+        let mut current = self.game.lock().await;
+        if *current != game {
+            *current = game;
+            self.cache.lock().await.clear();
+        }
+    }
+
+    /// Path segment and query parameter selecting the game's realm: `/poe2`
+    /// on the authenticated API, `&realm=poe2` on character-window
+    async fn realm(&self) -> (String, String) {
+        match self.game.lock().await.realm() {
+            Some(realm) => (format!("/{}", realm), format!("&realm={}", realm)),
+            None => (String::new(), String::new()),
+        }
+    }
+
+    /// Underlying HTTP client, for the OAuth token endpoint
+    pub fn http(&self) -> &Client {
+        &self.client
+    }
+
+    /// A client sharing this one's rate limiter and cache that also keeps every
+    /// raw response it returns, for diagnostics bundles
+    pub fn recording(&self) -> (PoeApiClient, ResponseLog) {
+        let log = ResponseLog::default();
+        let client = PoeApiClient {
+            client: self.client.clone(),
+            rate_limits: self.rate_limits.clone(),
+            cache: self.cache.clone(),
+            recorder: Some(log.clone()),
+            oauth: self.oauth.clone(),
+            game: self.game.clone(),
+        };
+        (client, log)
+    }
+
+    fn record(&self, url: &str, status: Option<u16>, body: &str) {
+        if let Some(recorder) = &self.recorder {
+            if let Ok(mut log) = recorder.lock() {
+                log.push(RecordedResponse {
+                    url: url.to_string(),
+                    status,
+                    body: body.to_string(),
+                });
+            }
+        }
+    }
+
+    /// GET a character-window URL through the cache and rate limiter
+    async fn fetch_text(&self, url: &str, ttl: Duration) -> Result<String> {
+        self.fetch(url, ttl, None).await
+    }
+
+    /// GET through the cache and rate limiter, authenticated with `bearer` if given
+    async fn fetch(&self, url: &str, ttl: Duration, bearer: Option<&str>) -> Result<String> {
+        let key = cache_key(url, bearer);
+        if let Some(cached) = self.get_cached(&key).await {
+            self.record(url, None, &cached);
+            return Ok(cached);
+        }
+
+        // Honor the limits the API publishes; a 429 waits out its Retry-After
+        let mut retries = 0;
+        let response = loop {
+            self.wait_for_rate_limit(url).await?;
+
+            let request = match bearer {
+                Some(token) => self.client.get(url).bearer_auth(token).header("User-Agent", OAUTH_USER_AGENT),
+                None => self.client.get(url),
+            };
+            let response = request.send().await?;
+            self.rate_limits.lock().await.update(url, response.headers(), Instant::now());
+            if response.status() != 429 {
+                break response;
+            }
+
+            self.record(url, Some(429), "");
+            let wait = rate_limit::retry_after(response.headers())
+                .unwrap_or_else(|| Duration::from_secs(2u64.pow(retries + 1)));
+            let give_up = retries == MAX_RATE_LIMIT_RETRIES || wait > MAX_RETRY_WAIT;
+            // Stored even when giving up, so later requests fail fast instead of being refused again
+            self.rate_limits.lock().await.restrict(url, wait, Instant::now());
+            if give_up {
+                return Err(ApiError::RateLimited.into());
+            }
+            retries += 1;
+        };
+        let status = response.status();
+
+        if status == 401 {
+            self.record(url, Some(status.as_u16()), "");
+            return Err(ApiError::Unauthorized.into());
+        }
+
+        if status == 403 {
+            self.record(url, Some(status.as_u16()), "");
+            return Err(ApiError::PrivateProfile.into());
+        }
+
+        if status == 404 {
+            self.record(url, Some(status.as_u16()), "");
+            return Err(ApiError::AccountNotFound.into());
+        }
+
+        let text = response.text().await?;
+        self.record(url, Some(status.as_u16()), &text);
+        self.cache_response(&key, text.clone(), ttl).await;
+
+        Ok(text)
+    }
+
+    /// Access token for reading `account_name`'s characters, refreshed (and
+    /// saved) first when it is about to expire. `None` when not signed in as
+    /// that account, or when the sign-in can no longer be refreshed.
+    async fn bearer_token(&self, account_name: &str) -> Result<Option<String>> {
+        let mut guard = self.oauth.lock().await;
+        let Some(token) = guard.as_ref() else {
+            return Ok(None);
+        };
+        if !oauth::same_account(&token.account_name, account_name) {
+            return Ok(None);
+        }
+
+        if oauth::needs_refresh(token, chrono::Utc::now().timestamp()) {
+            match oauth::refresh(&self.client, token).await {
+                Ok(fresh) => {
+                    Settings::save_oauth_token(&fresh)?;
+                    *guard = Some(fresh);
                 }
-                limiter.time_until_available()
+                Err(e) if matches!(e.downcast_ref::<ApiError>(), Some(ApiError::Unauthorized)) => {
+                    // Revoked or past the refresh token's lifetime: sign out and
+                    // let the public endpoints have a go
+                    Settings::clear_oauth_token()?;
+                    *guard = None;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(guard.as_ref().map(|t| t.access_token.clone()))
+    }
+
+    /// Full character (items and passives) from the authenticated API
+    async fn get_oauth_character(&self, token: &str, character_name: &str) -> Result<OAuthCharacter> {
+        let (realm, _) = self.realm().await;
+        let url = format!("{}/character{}/{}", POE_OAUTH_API_BASE, realm, urlencoding::encode(character_name));
+        let text = self.fetch(&url, Duration::from_secs(30), Some(token)).await?;
+        let response: OAuthCharacterResponse = serde_json::from_str(&text).map_err(|e| {
+            anyhow::anyhow!("Failed to parse character: {} - Response: {}", e, excerpt(&text))
+        })?;
+        Ok(response.character)
+    }
+
+    /// Top 200 of a league's ladder, optionally only characters of `class`
+    /// (a base class also matches its ascendancies). Cached for a minute; the
+    /// ladder itself only refreshes every few minutes.
+    pub async fn fetch_ladder(&self, league: &str, class: Option<&str>) -> Result<Vec<LadderEntry>> {
+        let url = format!(
+            "{}/api/ladders?offset=0&limit={}&id={}&type=league&realm=pc",
+            POE_API_BASE,
+            LADDER_LIMIT,
+            urlencoding::encode(league)
+        );
+        let text = self.fetch_text(&url, Duration::from_secs(60)).await.map_err(|e| match e.downcast_ref::<ApiError>() {
+            Some(ApiError::AccountNotFound) => anyhow::anyhow!("League {} has no ladder", league),
+            _ => e,
+        })?;
+        let ladder: Ladder = serde_json::from_str(&text).map_err(|e| {
+            anyhow::anyhow!("Failed to parse ladder: {} - Response: {}", e, excerpt(&text))
+        })?;
+
+        Ok(ladder
+            .entries
+            .into_iter()
+            .filter(|entry| class.is_none_or(|class| pob_builder::class_matches(class, &entry.character.class)))
+            .collect())
+    }
+
+    /// When a league started, from the public league list. `None` for leagues
+    /// the list doesn't have or that have no start (Standard).
+    pub async fn fetch_league_start(&self, league: &str) -> Result<Option<String>> {
+        let url = format!("{}/api/leagues?type=main&realm=pc", POE_API_BASE);
+        let text = self.fetch_text(&url, Duration::from_secs(3600)).await?;
+        let leagues: Vec<PoeLeague> = serde_json::from_str(&text).map_err(|e| {
+            anyhow::anyhow!("Failed to parse leagues: {} - Response: {}", e, excerpt(&text))
+        })?;
+        Ok(leagues
+            .into_iter()
+            .find(|l| l.id.eq_ignore_ascii_case(league))
+            .and_then(|l| l.start_at))
+    }
+
+    /// Wait until the rate limits allow a request to `url`. A wait past
+    /// `MAX_RETRY_WAIT` (a long restriction) fails with `RateLimited` instead.
+    async fn wait_for_rate_limit(&self, url: &str) -> Result<()> {
+        loop {
+            let wait_time = match self.rate_limits.lock().await.try_acquire(url, Instant::now()) {
+                Ok(()) => return Ok(()),
+                Err(wait) => wait,
             };
+            if wait_time > MAX_RETRY_WAIT {
+                return Err(ApiError::RateLimited.into());
+            }
             tokio::time::sleep(wait_time).await;
         }
     }
 
-    /// Check cache for a URL
-    async fn get_cached(&self, url: &str) -> Option<String> {
+    /// Check cache for a `cache_key`
+    async fn get_cached(&self, key: &str) -> Option<String> {
         let cache = self.cache.lock().await;
-        if let Some(entry) = cache.get(url) {
+        if let Some(entry) = cache.get(key) {
             if entry.expires_at > Instant::now() {
                 return Some(entry.data.clone());
             }
@@ -108,11 +381,11 @@ impl PoeApiClient {
         None
     }
 
-    /// Add response to cache
-    async fn cache_response(&self, url: &str, data: String, ttl: Duration) {
+    /// Add response to cache under its `cache_key`
+    async fn cache_response(&self, key: &str, data: String, ttl: Duration) {
         let mut cache = self.cache.lock().await;
         cache.insert(
-            url.to_string(),
+            key.to_string(),
             CacheEntry {
                 data,
                 expires_at: Instant::now() + ttl,
@@ -120,126 +393,152 @@ impl PoeApiClient {
         );
     }
 
-    /// Get characters for an account (public API)
-    pub async fn get_characters(&self, account_name: &str) -> Result<Vec<PoeCharacter>> {
-        let url = format!(
-            "{}/character-window/get-characters?accountName={}",
-            POE_API_BASE,
-            urlencoding::encode(account_name)
-        );
-
-        // Check cache first
-        if let Some(cached) = self.get_cached(&url).await {
-            return Ok(serde_json::from_str(&cached)?);
-        }
-
-        self.wait_for_rate_limit().await;
-
-        let response = self.client.get(&url).send().await?;
+}
 
-        if response.status() == 403 {
-            return Err(anyhow::anyhow!(
-                "Profile is private. Please set your POE profile to public in account settings."
-            ));
+impl PoeApi for PoeApiClient {
+    /// Get characters for an account (authenticated API when signed in as it, public otherwise)
+    async fn get_characters(&self, account_name: &str) -> Result<Vec<PoeCharacter>> {
+        let (realm, realm_param) = self.realm().await;
+        if let Some(token) = self.bearer_token(account_name).await? {
+            let url = format!("{}/character{}", POE_OAUTH_API_BASE, realm);
+            let text = self.fetch(&url, Duration::from_secs(60), Some(&token)).await?;
+            return Ok(serde_json::from_str::<OAuthCharacterList>(&text)?.characters);
         }
 
-        if response.status() == 429 {
-            return Err(anyhow::anyhow!("Rate limited. Please try again later."));
-        }
+        let url = format!(
+            "{}/character-window/get-characters?accountName={}{}",
+            POE_API_BASE,
+            urlencoding::encode(account_name),
+            realm_param
+        );
 
-        let text = response.text().await?;
-        self.cache_response(&url, text.clone(), Duration::from_secs(60)).await;
+        let text = self.fetch_text(&url, Duration::from_secs(60)).await?;
 
         Ok(serde_json::from_str(&text)?)
     }
 
-    /// Get items for a character (public API)
-    pub async fn get_items(
+    /// Get items for a character (authenticated API when signed in, public otherwise)
+    async fn get_items(
         &self,
         account_name: &str,
         character_name: &str,
     ) -> Result<CharacterItems> {
+        if let Some(token) = self.bearer_token(account_name).await? {
+            let character = self.get_oauth_character(&token, character_name).await?;
+            let mut items = character.equipment;
+            items.extend(character.inventory);
+            return Ok(CharacterItems { items, character: character.info });
+        }
+
+        let (_, realm_param) = self.realm().await;
         let url = format!(
-            "{}/character-window/get-items?accountName={}&character={}",
+            "{}/character-window/get-items?accountName={}&character={}{}",
             POE_API_BASE,
             urlencoding::encode(account_name),
-            urlencoding::encode(character_name)
+            urlencoding::encode(character_name),
+            realm_param
         );
 
-        if let Some(cached) = self.get_cached(&url).await {
-            return Ok(serde_json::from_str(&cached)?);
-        }
-
-        self.wait_for_rate_limit().await;
-
-        let response = self.client.get(&url).send().await?;
-
-        if response.status() == 403 {
-            return Err(anyhow::anyhow!(
-                "Profile is private. Please set your POE profile to public in account settings."
-            ));
-        }
-
-        if response.status() == 429 {
-            return Err(anyhow::anyhow!("Rate limited. Please try again later."));
-        }
-
-        let text = response.text().await?;
-
-        self.cache_response(&url, text.clone(), Duration::from_secs(30)).await;
+        let text = self.fetch_text(&url, Duration::from_secs(30)).await?;
 
         // Try to parse, with better error context
         serde_json::from_str(&text).map_err(|e| {
-            anyhow::anyhow!("Failed to parse items: {} - Response: {}", e, &text[..text.len().min(200)])
+            anyhow::anyhow!("Failed to parse items: {} - Response: {}", e, excerpt(&text))
         })
     }
 
-    /// Get passive skills for a character (public API)
-    pub async fn get_passive_skills(
+    /// Get passive skills for a character (authenticated API when signed in, public otherwise)
+    async fn get_passive_skills(
         &self,
         account_name: &str,
         character_name: &str,
     ) -> Result<PassiveSkills> {
+        if let Some(token) = self.bearer_token(account_name).await? {
+            let character = self.get_oauth_character(&token, character_name).await?;
+            return character
+                .passives
+                .ok_or_else(|| anyhow::anyhow!("Character {} has no passives in the response", character_name));
+        }
+
+        let (_, realm_param) = self.realm().await;
         let url = format!(
-            "{}/character-window/get-passive-skills?accountName={}&character={}",
+            "{}/character-window/get-passive-skills?accountName={}&character={}{}",
             POE_API_BASE,
             urlencoding::encode(account_name),
-            urlencoding::encode(character_name)
+            urlencoding::encode(character_name),
+            realm_param
         );
 
-        if let Some(cached) = self.get_cached(&url).await {
-            return Ok(serde_json::from_str(&cached)?);
-        }
-
-        self.wait_for_rate_limit().await;
-
-        let response = self.client.get(&url).send().await?;
-
-        if response.status() == 403 {
-            return Err(anyhow::anyhow!(
-                "Profile is private. Please set your POE profile to public in account settings."
-            ));
-        }
-
-        if response.status() == 429 {
-            return Err(anyhow::anyhow!("Rate limited. Please try again later."));
-        }
-
-        let text = response.text().await?;
-
-        self.cache_response(&url, text.clone(), Duration::from_secs(30)).await;
+        let text = self.fetch_text(&url, Duration::from_secs(30)).await?;
 
         // Try to parse, with better error context
         serde_json::from_str(&text).map_err(|e| {
-            anyhow::anyhow!("Failed to parse passive skills: {} - Response: {}", e, &text[..text.len().min(200)])
+            anyhow::anyhow!("Failed to parse passive skills: {} - Response: {}", e, excerpt(&text))
         })
     }
+
+    /// Get the account's profile page (public website)
+    async fn get_profile_page(&self, account_name: &str) -> Result<String> {
+        let url = format!(
+            "{}/account/view-profile/{}",
+            POE_API_BASE,
+            urlencoding::encode(account_name)
+        );
+
+        self.fetch_text(&url, Duration::from_secs(60)).await
+    }
 }
 
 // ============================================================================
 // API Response Types
 // ============================================================================
 
+/// Response of the public ladder endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct Ladder {
+    #[serde(default)]
+    pub entries: Vec<LadderEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LadderEntry {
+    #[serde(default)]
+    pub rank: u32,
+    #[serde(default)]
+    pub dead: bool,
+    pub character: LadderCharacter,
+    /// Missing for accounts that hide it
+    pub account: Option<LadderAccount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LadderCharacter {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub level: u32,
+    /// Ascendancy once ascended, base class before
+    #[serde(default)]
+    pub class: String,
+    #[serde(default)]
+    pub experience: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LadderAccount {
+    #[serde(default)]
+    pub name: String,
+}
+
+/// Entry of the public league list
+#[derive(Debug, Clone, Deserialize)]
+struct PoeLeague {
+    #[serde(default)]
+    id: String,
+    #[serde(rename = "startAt")]
+    start_at: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PoeCharacter {
     #[serde(default)]
@@ -297,6 +596,10 @@ pub struct PoeItem {
     pub inventory_id: String,
     #[serde(default)]
     pub socketed_items: Vec<PoeItem>,
+    /// For an item in another's `socketed_items`, the index of its socket in
+    /// that item's `sockets`
+    #[serde(default)]
+    pub socket: Option<u32>,
     #[serde(default)]
     pub sockets: Vec<PoeSocket>,
     #[serde(default)]
@@ -339,6 +642,33 @@ pub struct PoeSocket {
     pub attr: String,
 }
 
+/// `GET /character` on the authenticated API
+#[derive(Debug, Deserialize)]
+struct OAuthCharacterList {
+    #[serde(default)]
+    characters: Vec<PoeCharacter>,
+}
+
+/// `GET /character/<name>` on the authenticated API
+#[derive(Debug, Deserialize)]
+struct OAuthCharacterResponse {
+    character: OAuthCharacter,
+}
+
+/// Character with equipment and passives in one response. The class is
+/// reported by name (e.g. "Necromancer"); there are no class ids.
+#[derive(Debug, Deserialize)]
+struct OAuthCharacter {
+    #[serde(flatten)]
+    info: PoeCharacterInfo,
+    #[serde(default)]
+    equipment: Vec<PoeItem>,
+    #[serde(default)]
+    inventory: Vec<PoeItem>,
+    #[serde(default)]
+    passives: Option<PassiveSkills>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PassiveSkills {
     #[serde(default)]
@@ -409,3 +739,24 @@ impl Default for PoeApiClient {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excerpt_cuts_on_char_boundary() {
+        let text = "é".repeat(300);
+        assert_eq!(excerpt(&text).chars().count(), EXCERPT_CHARS);
+        assert_eq!(excerpt("short"), "short");
+    }
+
+    #[test]
+    fn test_cache_key_separates_sign_ins() {
+        let url = "https://api.pathofexile.com/character/Runner";
+        assert_eq!(cache_key(url, None), url);
+        assert_ne!(cache_key(url, Some("token-a")), cache_key(url, None));
+        assert_ne!(cache_key(url, Some("token-a")), cache_key(url, Some("token-b")));
+        assert_eq!(cache_key(url, Some("token-a")), cache_key(url, Some("token-a")));
+    }
+}
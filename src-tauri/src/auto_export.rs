@@ -0,0 +1,133 @@
+use std::path::{Path, PathBuf};
+
+use crate::commands::{render_run_json, render_run_livesplit};
+use crate::db::{Run, Settings, Split};
+use crate::error::{AppError, AppResult};
+use crate::table_export::{self, ExportLocale, TableFormat};
+use crate::webhook::{self, WebhookPayload};
+
+/// Files `auto_export_formats` can list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Lss,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Json, ExportFormat::Csv, ExportFormat::Lss];
+
+    /// The format's name in the setting, and its file extension
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Lss => "lss",
+        }
+    }
+
+    /// Formats in a comma-separated setting, in `ALL` order; unknown names are skipped
+    pub fn parse_list(list: &str) -> Vec<ExportFormat> {
+        let names: Vec<String> = list.split(',').map(|name| name.trim().to_ascii_lowercase()).collect();
+        ExportFormat::ALL
+            .into_iter()
+            .filter(|format| names.iter().any(|name| name == format.extension()))
+            .collect()
+    }
+}
+
+/// `<start date>_<category>_<class>_run<id>.<ext>`
+pub fn file_name(run: &Run, format: ExportFormat) -> String {
+    format!("{}.{}", file_stem(&run.started_at, &run.category, &run.class, run.id), format.extension())
+}
+
+/// The file name without its extension, with characters file systems reject replaced
+fn file_stem(started_at: &str, category: &str, class: &str, run_id: i64) -> String {
+    let date = started_at.get(..10).unwrap_or(started_at);
+    format!("{}_{}_{}_run{}", date, category, class, run_id)
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '-',
+            c if c.is_whitespace() => '-',
+            c => c,
+        })
+        .collect()
+}
+
+/// Whether a completed run is exported at all under `settings`
+pub fn wanted(settings: &Settings, is_pb: bool) -> bool {
+    !settings.auto_export_dir.trim().is_empty() && (is_pb || !settings.auto_export_pbs_only)
+}
+
+/// Write a completed run's exports to the auto-export folder, returning the
+/// files written. Nothing is written without a folder.
+pub fn write_exports(run: &Run, settings: &Settings) -> AppResult<Vec<PathBuf>> {
+    let dir = settings.auto_export_dir.trim();
+    if dir.is_empty() {
+        return Ok(Vec::new());
+    }
+    std::fs::create_dir_all(dir).map_err(|e| AppError::Io(format!("Failed to create {}: {}", dir, e)))?;
+
+    let mut written = Vec::new();
+    for format in ExportFormat::parse_list(&settings.auto_export_formats) {
+        let contents = match format {
+            ExportFormat::Json => render_run_json(run.id)?,
+            ExportFormat::Lss => render_run_livesplit(run.id)?,
+            ExportFormat::Csv => {
+                let splits = Split::get_by_run(run.id)?;
+                table_export::render(TableFormat::Csv, run, &splits, &ExportLocale::for_tag(&settings.locale))
+            }
+        };
+        let path = Path::new(dir).join(file_name(run, format));
+        std::fs::write(&path, contents)
+            .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Export a run that just completed per the auto-export settings, then send
+/// the completion webhook listing the files written. Runs in the background;
+/// failures are logged.
+pub fn run_completed(run_id: i64, is_pb: bool) {
+    tauri::async_runtime::spawn(async move {
+        let result: AppResult<()> = async {
+            let settings = Settings::load()?;
+            let Some(run) = Run::get_by_id(run_id)? else {
+                return Ok(());
+            };
+            // A failed export still sends the webhook, just without files
+            let files = if wanted(&settings, is_pb) {
+                write_exports(&run, &settings).unwrap_or_else(|e| {
+                    eprintln!("[auto_export] Failed to export run {}: {}", run_id, e);
+                    Vec::new()
+                })
+            } else {
+                Vec::new()
+            };
+            webhook::send(&settings, &WebhookPayload::run_completed(&run, is_pb).with_files(&files)).await;
+            Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            eprintln!("[auto_export] Failed to load run {}: {}", run_id, e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_and_file_names() {
+        assert_eq!(ExportFormat::parse_list("lss, JSON,xml"), vec![ExportFormat::Json, ExportFormat::Lss]);
+        assert_eq!(ExportFormat::parse_list(""), vec![]);
+
+        assert_eq!(
+            file_stem("2026-01-15T12:00:00Z", "Act 10 Any%", "Witch", 42),
+            "2026-01-15_Act-10-Any%_Witch_run42"
+        );
+        assert_eq!(file_stem("2026-01-15T12:00:00Z", "a/b: c?", "Witch", 42), "2026-01-15_a-b--c-_Witch_run42");
+    }
+}
@@ -0,0 +1,150 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::breakpoint_engine;
+use crate::commands::start_new_run;
+use crate::db::{NewRun, Run, Settings};
+use crate::error::AppResult;
+use crate::log_watcher::LogEvent;
+
+/// Spots a new character entering the starting zone. Client.txt doesn't log
+/// character levels on zone entry, but a new character always loads a freshly
+/// generated instance of it, while walking back in reuses the instance it
+/// left (same seed).
+#[derive(Debug, Default)]
+pub struct StartDetector {
+    /// Seed of the area generated for the zone entry that follows
+    loading_seed: Option<u64>,
+    /// Instances of the starting zone already entered
+    seen_seeds: HashSet<u64>,
+}
+
+impl StartDetector {
+    /// Feed a log event; true when it is a new character entering `start_zone`
+    pub fn observe(&mut self, event: &LogEvent, start_zone: &str) -> bool {
+        match event {
+            LogEvent::AreaGenerated { seed, .. } => {
+                self.loading_seed = Some(*seed);
+                false
+            }
+            LogEvent::ZoneEnter { zone_name, .. } => {
+                let seed = self.loading_seed.take();
+                zone_name.eq_ignore_ascii_case(start_zone.trim())
+                    && seed.is_some_and(|seed| self.seen_seeds.insert(seed))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Payload of the `run-auto-started` event
+#[derive(Debug, Clone, Serialize)]
+pub struct RunAutoStarted {
+    pub run_id: i64,
+    pub zone_name: String,
+    /// Log timestamp of the zone entry
+    pub timestamp: String,
+    pub run: Run,
+}
+
+static DETECTOR: Mutex<Option<StartDetector>> = Mutex::new(None);
+
+/// Create a run when a new character enters the configured starting zone,
+/// if auto-start is on and no run is being timed. The character's name is
+/// only logged from its first level-up, so the run starts as "Unknown" and
+/// is renamed then like any other; category, league and breakpoints carry
+/// over from the latest run.
+pub fn start_for(event: &LogEvent) -> AppResult<Option<RunAutoStarted>> {
+    if !matches!(event, LogEvent::AreaGenerated { .. } | LogEvent::ZoneEnter { .. }) {
+        return Ok(None);
+    }
+    let settings = Settings::load()?;
+    let is_new_character = DETECTOR
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(StartDetector::default)
+        .observe(event, &settings.auto_start_zone);
+    if !is_new_character || !settings.auto_start_runs || breakpoint_engine::is_active() {
+        return Ok(None);
+    }
+    let LogEvent::ZoneEnter { timestamp, zone_name } = event else {
+        return Ok(None);
+    };
+
+    let latest = Run::latest()?;
+    let run_id = start_new_run(NewRun {
+        character_name: "Unknown".to_string(),
+        account_name: settings.account_name,
+        class: "Unknown".to_string(),
+        ascendancy: None,
+        league: latest.as_ref().map(|run| run.league.clone()).unwrap_or_else(|| "Standard".to_string()),
+        category: latest.as_ref().map(|run| run.category.clone()).unwrap_or_else(|| "any%".to_string()),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        breakpoint_preset: latest.as_ref().and_then(|run| run.breakpoint_preset.clone()),
+        enabled_breakpoints: latest.as_ref().and_then(|run| run.enabled_breakpoints.clone()),
+        game_version: None,
+    })?;
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Ok(None);
+    };
+    Ok(Some(RunAutoStarted {
+        run_id,
+        zone_name: zone_name.clone(),
+        timestamp: timestamp.clone(),
+        run,
+    }))
+}
+
+/// Auto-start a run from a log event, emitting `run-auto-started`. The
+/// frontend takes the run over and starts its timer and breakpoint engine.
+pub fn handle_event(app_handle: &AppHandle, event: &LogEvent) {
+    match start_for(event) {
+        Ok(Some(started)) => {
+            let _ = app_handle.emit("run-auto-started", &started);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[auto_start] Failed to auto-start run: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(seed: u64) -> LogEvent {
+        LogEvent::AreaGenerated {
+            timestamp: String::new(),
+            area_level: 1,
+            area_id: "1_1_1".to_string(),
+            seed,
+        }
+    }
+
+    fn zone(name: &str) -> LogEvent {
+        LogEvent::ZoneEnter { timestamp: String::new(), zone_name: name.to_string() }
+    }
+
+    #[test]
+    fn test_detects_new_instances_of_the_starting_zone() {
+        let mut detector = StartDetector::default();
+        let strand = "The Twilight Strand";
+
+        assert!(!detector.observe(&area(1), strand));
+        assert!(detector.observe(&zone(strand), strand));
+        // Walking back from Lioneye's Watch into the same instance
+        assert!(!detector.observe(&area(2), strand));
+        assert!(!detector.observe(&zone("Lioneye's Watch"), strand));
+        assert!(!detector.observe(&area(1), strand));
+        assert!(!detector.observe(&zone(strand), strand));
+        // A zone entry without a generated area isn't a new instance
+        assert!(!detector.observe(&zone(strand), strand));
+
+        // The next character's Strand
+        assert!(!detector.observe(&area(3), strand));
+        assert!(detector.observe(&zone(strand), strand));
+        assert!(!detector.observe(&area(4), "The Coast"));
+        assert!(!detector.observe(&zone(strand), "The Coast"));
+    }
+}
@@ -0,0 +1,124 @@
+use serde::Serialize;
+
+use crate::db::RunEvent;
+use crate::zone_time::{classify_zone, ZoneKind};
+
+/// How often a run entered one zone
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneVisits {
+    pub zone_name: String,
+    pub kind: ZoneKind,
+    pub entries: u32,
+    pub backtracks: u32,
+    /// Run-clock time spent in the zone after backtracking into it
+    pub backtrack_time_ms: i64,
+}
+
+/// A run's zone entries, with the backtracks among them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacktrackReport {
+    pub run_id: i64,
+    pub backtracks: u32,
+    pub backtrack_time_ms: i64,
+    /// Every zone entered, in order of first entry
+    pub zones: Vec<ZoneVisits>,
+}
+
+/// Count the zone entries among a run's recorded events. Re-entering a zone
+/// is a backtrack when another zone was played in between; going to town or
+/// hideout and coming back (portals, vendoring) is not. The last zone's stay
+/// ends at `end_ms`, the run's total time when it's finished.
+pub fn build(run_id: i64, events: &[RunEvent], end_ms: Option<i64>) -> BacktrackReport {
+    let entries: Vec<(&str, i64)> = events
+        .iter()
+        .filter(|event| event.kind == "zone_enter")
+        .filter_map(|event| Some((event.detail.as_deref()?, event.elapsed_ms)))
+        .collect();
+
+    let mut zones: Vec<ZoneVisits> = Vec::new();
+    let mut last_field: Option<&str> = None;
+    for (index, &(zone_name, elapsed_ms)) in entries.iter().enumerate() {
+        let kind = classify_zone(zone_name);
+        let left_ms = entries.get(index + 1).map(|&(_, next_ms)| next_ms).or(end_ms).unwrap_or(elapsed_ms);
+
+        let visits = match zones.iter().position(|zone| zone.zone_name == zone_name) {
+            Some(position) => &mut zones[position],
+            None => {
+                zones.push(ZoneVisits {
+                    zone_name: zone_name.to_string(),
+                    kind,
+                    entries: 0,
+                    backtracks: 0,
+                    backtrack_time_ms: 0,
+                });
+                zones.last_mut().unwrap()
+            }
+        };
+        let backtrack = kind == ZoneKind::Field && visits.entries > 0 && last_field != Some(zone_name);
+        visits.entries += 1;
+        if backtrack {
+            visits.backtracks += 1;
+            visits.backtrack_time_ms += (left_ms - elapsed_ms).max(0);
+        }
+        if kind == ZoneKind::Field {
+            last_field = Some(zone_name);
+        }
+    }
+
+    BacktrackReport {
+        run_id,
+        backtracks: zones.iter().map(|zone| zone.backtracks).sum(),
+        backtrack_time_ms: zones.iter().map(|zone| zone.backtrack_time_ms).sum(),
+        zones,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(name: &str, elapsed_ms: i64) -> RunEvent {
+        RunEvent {
+            id: 0,
+            run_id: 1,
+            kind: "zone_enter".to_string(),
+            elapsed_ms,
+            detail: Some(name.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_backtracks_skip_town_returns() {
+        let events = vec![
+            zone("The Coast", 0),
+            // Portal to town and back
+            zone("Lioneye's Watch", 60_000),
+            zone("The Coast", 80_000),
+            zone("The Tidal Island", 100_000),
+            // Walked back through a cleared zone
+            zone("The Coast", 150_000),
+            zone("The Mud Flats", 170_000),
+            zone("Lioneye's Watch", 200_000),
+        ];
+        let report = build(1, &events, Some(230_000));
+
+        let visits: Vec<(&str, u32, u32, i64)> = report
+            .zones
+            .iter()
+            .map(|z| (z.zone_name.as_str(), z.entries, z.backtracks, z.backtrack_time_ms))
+            .collect();
+        assert_eq!(
+            visits,
+            vec![
+                ("The Coast", 3, 1, 20_000),
+                ("Lioneye's Watch", 2, 0, 0),
+                ("The Tidal Island", 1, 0, 0),
+                ("The Mud Flats", 1, 0, 0),
+            ]
+        );
+        assert_eq!((report.backtracks, report.backtrack_time_ms), (1, 20_000));
+        assert_eq!(report.zones[1].kind, ZoneKind::Town);
+    }
+}
@@ -0,0 +1,1004 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+use crate::category_detect;
+use crate::commands::{capture_level_snapshot, split_and_capture, AddSplitRequest};
+use crate::comparison::SplitComparison;
+use crate::db::{
+    Death, NewSplit, Pause, PresetBreakpoint, QuestProgress, Run, RunEvent, Settings, SkippedSplit, Split, ZoneTime,
+};
+use crate::error::AppResult;
+use crate::log_watcher::LogEvent;
+use crate::pause::{self, PauseReason};
+use crate::pob_builder;
+use crate::snapshot_queue::SnapshotRequest;
+use crate::zone_time::{classify_zone, ZoneKind};
+
+/// How a breakpoint is triggered, as configured in the frontend or a
+/// breakpoint preset
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakpointTrigger {
+    /// `zone`, `level`, `kitava`, `boss` or `quest`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub zone_name: Option<String>,
+    pub act: Option<i32>,
+    pub level: Option<u32>,
+    pub penalty: Option<i32>,
+    /// Objective (`quest_state::OBJECTIVES` id) a quest trigger waits for
+    pub quest: Option<String>,
+    pub rule: Option<TriggerRule>,
+}
+
+/// Canonical key for the location a trigger fires at, as the frontend's
+/// `getBreakpointKey` makes it (`zone:a2:the_southern_forest`, `level:40`)
+pub fn breakpoint_key(trigger: &BreakpointTrigger) -> Option<String> {
+    let slug = |s: &str| {
+        let mut slug = String::new();
+        for c in s.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('_') {
+                slug.push('_');
+            }
+        }
+        slug.trim_end_matches('_').to_string()
+    };
+    match trigger.kind.as_str() {
+        "level" => trigger.level.map(|level| format!("level:{}", level)),
+        "kitava" => trigger.act.map(|act| format!("kitava:a{}", act)),
+        "quest" => trigger.quest.as_ref().map(|quest| format!("quest:{}", quest)),
+        _ => {
+            let zone = slug(trigger.zone_name.as_deref().filter(|zone| !zone.is_empty())?);
+            Some(match trigger.act {
+                Some(act) => format!("zone:a{}:{}", act, zone),
+                None => format!("zone:{}", zone),
+            })
+        }
+    }
+}
+
+/// Extra conditions a trigger must meet, for zones that are re-entered
+/// legitimately (town hubs, backtracking). Stored on the preset's breakpoints.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerRule {
+    /// Only split on this entry into the zone during the run (1 = first entry only)
+    pub entry: Option<u32>,
+    /// Only split once this quest objective (`quest_state::OBJECTIVES` id) is complete
+    pub after_quest: Option<String>,
+}
+
+/// An enabled breakpoint of the run's preset, in split order
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineBreakpoint {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub breakpoint_type: String,
+    pub trigger: BreakpointTrigger,
+    /// Canonical key from the frontend's `getBreakpointKey`
+    pub key: Option<String>,
+    pub capture_snapshot: bool,
+}
+
+impl From<&PresetBreakpoint> for EngineBreakpoint {
+    fn from(preset: &PresetBreakpoint) -> Self {
+        let bp = &preset.breakpoint;
+        let rule = (bp.entry.is_some() || bp.after_quest.is_some()).then(|| TriggerRule {
+            entry: bp.entry,
+            after_quest: bp.after_quest.clone(),
+        });
+        let trigger = BreakpointTrigger {
+            kind: bp.trigger_type.clone(),
+            zone_name: bp.zone_name.clone(),
+            act: bp.act,
+            level: bp.level,
+            penalty: bp.penalty,
+            quest: bp.quest.clone(),
+            rule,
+        };
+        EngineBreakpoint {
+            name: bp.name.clone(),
+            breakpoint_type: bp.breakpoint_type.clone(),
+            key: breakpoint_key(&trigger),
+            trigger,
+            capture_snapshot: bp.capture_snapshot,
+        }
+    }
+}
+
+/// Run timer kept in the backend, so split times don't depend on the webview
+#[derive(Debug, Clone, Copy)]
+struct Clock {
+    base_ms: i64,
+    since: Option<Instant>,
+}
+
+impl Clock {
+    fn elapsed_ms(&self, now: Instant) -> i64 {
+        self.base_ms + self.since.map_or(0, |since| now.duration_since(since).as_millis() as i64)
+    }
+}
+
+/// Decides which breakpoint a log event completes for the active run. Zone and
+/// Kitava breakpoints match sequentially (only the next one of their kind), so
+/// a town visited in Act 1 can't split its Act 6 counterpart. Trigger rules
+/// narrow that further to a given entry or to after a quest. Quest breakpoints
+/// also match in order, on the first event after their objective is complete.
+#[derive(Debug, Clone)]
+pub struct BreakpointEngine {
+    run_id: i64,
+    breakpoints: Vec<EngineBreakpoint>,
+    clock: Clock,
+    /// Times each zone (lowercased) has been entered since the engine started
+    zone_entries: HashMap<String, u32>,
+    /// Whether the run's category was checked against its first telling event
+    category_checked: bool,
+    /// Other characters seen leveling up, each reported once
+    other_characters: HashSet<String>,
+    /// The last of them, until the run is reassigned
+    mismatch: Option<CharacterMismatch>,
+}
+
+impl BreakpointEngine {
+    /// Start following `run_id` with its timer running at `elapsed_ms`
+    pub fn new(run_id: i64, breakpoints: Vec<EngineBreakpoint>, elapsed_ms: i64, now: Instant) -> Self {
+        BreakpointEngine {
+            run_id,
+            breakpoints,
+            clock: Clock { base_ms: elapsed_ms, since: Some(now) },
+            zone_entries: HashMap::new(),
+            category_checked: false,
+            other_characters: HashSet::new(),
+            mismatch: None,
+        }
+    }
+
+    /// Count zone entries for `entry` rules. Called for every event before
+    /// matching, whether or not the timer is running.
+    pub fn observe(&mut self, event: &LogEvent) {
+        if let LogEvent::ZoneEnter { zone_name, .. } = event {
+            *self.zone_entries.entry(zone_name.to_lowercase()).or_default() += 1;
+        }
+    }
+
+    /// Pause or resume the timer, resyncing it to the frontend's elapsed time
+    pub fn set_running(&mut self, running: bool, elapsed_ms: i64, now: Instant) {
+        self.clock = Clock {
+            base_ms: elapsed_ms,
+            since: running.then_some(now),
+        };
+    }
+
+    pub fn elapsed_ms(&self, now: Instant) -> i64 {
+        self.clock.elapsed_ms(now)
+    }
+
+    /// The breakpoint `event` completes, given the names already split and the
+    /// quest objectives the run has completed
+    pub fn matching(&self, event: &LogEvent, completed: &[String], quests: &[String]) -> Option<&EngineBreakpoint> {
+        // A paused timer doesn't split
+        self.clock.since?;
+        let mut pending = self.breakpoints.iter().filter(|bp| !completed.contains(&bp.name));
+        let matched = match event {
+            LogEvent::ZoneEnter { zone_name, .. } => pending.find(|bp| bp.trigger.kind == "zone").filter(|bp| {
+                bp.trigger.zone_name.as_deref().is_some_and(|zone| zone.eq_ignore_ascii_case(zone_name))
+            }),
+            LogEvent::LevelUp { level, .. } => {
+                pending.find(|bp| bp.trigger.kind == "level" && bp.trigger.level == Some(*level))
+            }
+            LogEvent::KitavaAffliction { penalty, .. } => pending
+                .find(|bp| bp.trigger.kind == "kitava")
+                .filter(|bp| bp.trigger.penalty == Some(*penalty)),
+            // Backs up the town zone match when the town name differs from the
+            // breakpoint's. Entry rules count the zone itself, so they opt out.
+            LogEvent::ActComplete { act, .. } => pending.next().filter(|bp| {
+                bp.breakpoint_type == "act"
+                    && bp.trigger.kind == "zone"
+                    && bp.trigger.act == Some(act + 1)
+                    && bp.trigger.rule.as_ref().is_none_or(|rule| rule.entry.is_none())
+            }),
+            _ => None,
+        };
+        matched.filter(|bp| self.rule_allows(bp, quests)).or_else(|| {
+            self.breakpoints
+                .iter()
+                .filter(|bp| !completed.contains(&bp.name))
+                .find(|bp| bp.trigger.kind == "quest")
+                .filter(|bp| bp.trigger.quest.as_ref().is_some_and(|quest| quests.contains(quest)))
+                .filter(|bp| self.rule_allows(bp, quests))
+        })
+    }
+
+    fn rule_allows(&self, breakpoint: &EngineBreakpoint, quests: &[String]) -> bool {
+        let Some(rule) = &breakpoint.trigger.rule else {
+            return true;
+        };
+        if let Some(entry) = rule.entry {
+            let zone = breakpoint.trigger.zone_name.as_deref().unwrap_or_default().to_lowercase();
+            if self.zone_entries.get(&zone).copied().unwrap_or(0) != entry {
+                return false;
+            }
+        }
+        rule.after_quest.as_ref().is_none_or(|quest| quests.contains(quest))
+    }
+
+    /// Whether the next breakpoint fires inside a zone (Kitava's affliction)
+    /// rather than on a zone change, so the log is worth polling closely
+    pub fn next_is_in_zone(&self, completed: &[String]) -> bool {
+        self.breakpoints
+            .iter()
+            .find(|bp| !completed.contains(&bp.name))
+            .is_some_and(|bp| bp.trigger.kind == "kitava")
+    }
+
+    /// Act of the preset's last breakpoint
+    pub fn last_act(&self) -> Option<i32> {
+        self.breakpoints.iter().filter_map(|bp| bp.trigger.act).max()
+    }
+
+    /// Whether every breakpoint is split once `completed` includes the latest one
+    pub fn is_finished(&self, completed: &[String]) -> bool {
+        self.breakpoints.iter().all(|bp| completed.contains(&bp.name))
+    }
+}
+
+/// A split the engine decided on, ready for `commands::record_split`
+#[derive(Debug, Clone)]
+pub struct EngineSplit {
+    pub split: NewSplit,
+    pub capture_snapshot: bool,
+    /// Character for the snapshot, from the run or the start fallback
+    pub character_name: Option<String>,
+    /// The run's last breakpoint
+    pub is_last: bool,
+}
+
+/// Engine for the run being timed, with the character name to fall back on
+static ACTIVE: Mutex<Option<(BreakpointEngine, Option<String>)>> = Mutex::new(None);
+
+/// `BreakpointEngine::next_is_in_zone` for the active run, as of its last split
+static NEAR_BREAKPOINT: AtomicBool = AtomicBool::new(false);
+
+fn active() -> std::sync::MutexGuard<'static, Option<(BreakpointEngine, Option<String>)>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start splitting `run_id` from log events
+pub fn start_run(run_id: i64, breakpoints: Vec<EngineBreakpoint>, elapsed_ms: i64, fallback_character: Option<String>) {
+    let engine = BreakpointEngine::new(run_id, breakpoints, elapsed_ms, Instant::now());
+    let completed = completed_breakpoints(run_id).unwrap_or_default();
+    NEAR_BREAKPOINT.store(engine.next_is_in_zone(&completed), Ordering::Relaxed);
+    *active() = Some((engine, fallback_character));
+}
+
+/// Whether a run is being timed
+pub fn is_active() -> bool {
+    active().is_some()
+}
+
+/// Run clock of `run_id` if it is the run being timed
+pub fn elapsed_for(run_id: i64) -> Option<i64> {
+    active()
+        .as_ref()
+        .filter(|(engine, _)| engine.run_id == run_id)
+        .map(|(engine, _)| engine.elapsed_ms(Instant::now()))
+}
+
+/// Names of a run's split and skipped breakpoints
+fn completed_breakpoints(run_id: i64) -> AppResult<Vec<String>> {
+    let mut completed: Vec<String> = Split::get_by_run(run_id)?.into_iter().map(|s| s.breakpoint_name).collect();
+    completed.extend(SkippedSplit::get_by_run(run_id)?.into_iter().map(|s| s.breakpoint_name));
+    Ok(completed)
+}
+
+/// Whether the active run's next split comes from a line logged inside a
+/// zone, for the log watcher's poll interval
+pub fn near_breakpoint() -> bool {
+    NEAR_BREAKPOINT.load(Ordering::Relaxed)
+}
+
+/// Pause or resume the active run's timer from the timer controls
+pub fn set_running(running: bool, elapsed_ms: i64) -> AppResult<()> {
+    set_running_for(running, elapsed_ms, PauseReason::Manual)
+}
+
+/// Pause or resume the active run's timer. Changes are kept for the run
+/// timeline, and pauses in `pauses` with why the timer stopped.
+pub fn set_running_for(running: bool, elapsed_ms: i64, reason: PauseReason) -> AppResult<()> {
+    let mut guard = active();
+    let Some((engine, _)) = guard.as_mut() else {
+        return Ok(());
+    };
+    let changed = engine.clock.since.is_some() != running;
+    engine.set_running(running, elapsed_ms, Instant::now());
+    if changed {
+        RunEvent::insert(engine.run_id, if running { "resume" } else { "pause" }, elapsed_ms, None)?;
+        if running {
+            Pause::end(engine.run_id)?;
+        } else {
+            Pause::start(engine.run_id, elapsed_ms, reason.as_str())?;
+        }
+    }
+    Ok(())
+}
+
+/// The active run's id, whether its timer is running, and its run clock
+pub fn active_clock() -> Option<(i64, bool, i64)> {
+    active()
+        .as_ref()
+        .map(|(engine, _)| (engine.run_id, engine.clock.since.is_some(), engine.elapsed_ms(Instant::now())))
+}
+
+/// Payload of `main-window-restored`: the run being timed as the backend has
+/// it, for a main window catching up after being hidden
+#[derive(Debug, Clone, Serialize)]
+pub struct RunResync {
+    /// The run clock, in the `run-paused` / `run-resumed` shape
+    pub clock: pause::PauseChanged,
+    pub splits: Vec<Split>,
+    pub skipped_splits: Vec<SkippedSplit>,
+}
+
+/// The active run's clock, splits and skipped breakpoints. None without one.
+pub fn resync() -> AppResult<Option<RunResync>> {
+    let Some(clock) = pause::current() else {
+        return Ok(None);
+    };
+    Ok(Some(RunResync {
+        splits: Split::get_by_run(clock.run_id)?,
+        skipped_splits: SkippedSplit::get_by_run(clock.run_id)?,
+        clock,
+    }))
+}
+
+/// Stop splitting (run ended or reset), closing a pause the run ended in
+pub fn stop() {
+    if let Some((engine, _)) = active().take() {
+        if let Err(e) = Pause::end(engine.run_id) {
+            eprintln!("[breakpoints] Failed to close pause: {}", e);
+        }
+    }
+    NEAR_BREAKPOINT.store(false, Ordering::Relaxed);
+}
+
+/// The split `event` triggers for the active run, if any. Already-split and
+/// skipped breakpoints and completed quests come from the database, so undone
+/// splits can fire again.
+pub fn split_for(event: &LogEvent) -> AppResult<Option<EngineSplit>> {
+    // Observe under the lock, then work from a copy so the database queries
+    // don't hold up other users of the engine
+    let (engine, fallback_character) = {
+        let mut guard = active();
+        let Some((engine, fallback_character)) = guard.as_mut() else {
+            return Ok(None);
+        };
+        engine.observe(event);
+        (engine.clone(), fallback_character.clone())
+    };
+
+    let run = Run::get_by_id(engine.run_id)?;
+    // Party members' level-ups are logged too; only the run's character's reach level breakpoints
+    if let (LogEvent::LevelUp { character_name, .. }, Some(run)) = (event, &run) {
+        if !is_run_character(run, character_name, fallback_character.as_deref()) {
+            return Ok(None);
+        }
+    }
+
+    let splits = Split::get_by_run(engine.run_id)?;
+    let mut completed = completed_breakpoints(engine.run_id)?;
+    let quests = QuestProgress::load(engine.run_id)?.map(|p| p.completed).unwrap_or_default();
+    let Some(breakpoint) = engine.matching(event, &completed, &quests) else {
+        // Undone splits put earlier breakpoints back in front
+        NEAR_BREAKPOINT.store(engine.next_is_in_zone(&completed), Ordering::Relaxed);
+        return Ok(None);
+    };
+
+    let split_time_ms = engine.elapsed_ms(Instant::now());
+    let previous_ms = splits.iter().map(|s| s.split_time_ms).max().unwrap_or(0);
+    completed.push(breakpoint.name.clone());
+    NEAR_BREAKPOINT.store(engine.next_is_in_zone(&completed), Ordering::Relaxed);
+
+    let character_name = run
+        .map(|run| run.character_name)
+        .filter(|name| !name.is_empty() && name != "Unknown")
+        .or(fallback_character);
+
+    Ok(Some(EngineSplit {
+        split: NewSplit {
+            run_id: engine.run_id,
+            breakpoint_type: breakpoint.breakpoint_type.clone(),
+            breakpoint_name: breakpoint.name.clone(),
+            breakpoint_key: breakpoint.key.clone(),
+            split_time_ms,
+            delta_ms: None,
+            segment_time_ms: split_time_ms - previous_ms,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+        },
+        capture_snapshot: breakpoint.capture_snapshot,
+        character_name,
+        is_last: engine.is_finished(&completed),
+    }))
+}
+
+/// Payload of the `split-recorded` event
+#[derive(Debug, Clone, Serialize)]
+pub struct SplitRecorded {
+    pub run_id: i64,
+    pub split_id: i64,
+    pub breakpoint_name: String,
+    pub breakpoint_type: String,
+    pub breakpoint_key: Option<String>,
+    pub split_time_ms: i64,
+    pub segment_time_ms: i64,
+    pub town_time_ms: i64,
+    pub hideout_time_ms: i64,
+    pub is_last: bool,
+    pub comparison: SplitComparison,
+}
+
+/// Payload of the `run-class-updated` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunClassUpdated {
+    pub run_id: i64,
+    pub class: String,
+    pub ascendancy: Option<String>,
+}
+
+/// Payload of the `character-mismatch` event: a character other than the
+/// run's leveled up while it was being timed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CharacterMismatch {
+    pub run_id: i64,
+    pub run_character: String,
+    pub character_name: String,
+    pub character_class: String,
+    pub level: u32,
+}
+
+/// Payload of the `death-recorded` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeathRecorded {
+    pub run_id: i64,
+    pub death_id: i64,
+    pub zone_name: Option<String>,
+    pub elapsed_ms: i64,
+    pub character_level: Option<i32>,
+    /// Deaths of the run so far, this one included
+    pub deaths: i64,
+}
+
+/// Payload of the `run-category-updated` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunCategoryUpdated {
+    pub run_id: i64,
+    pub category: String,
+}
+
+/// Run clock within which the first telling event can relabel the run
+const CATEGORY_WINDOW_MS: i64 = 10 * 60 * 1000;
+
+/// Whether a level-up or death of `character_name` is the run's character.
+/// Party members' are logged too. A placeholder name is about to be replaced
+/// with this character by the frontend.
+fn is_run_character(run: &Run, character_name: &str, fallback_character: Option<&str>) -> bool {
+    let placeholder = run.character_name.is_empty()
+        || run.character_name == "Unknown"
+        || fallback_character == Some(run.character_name.as_str());
+    placeholder || run.character_name == character_name
+}
+
+/// Keep the active run's zone entries, level-ups and deaths, at run-clock
+/// time, for its timeline. Zone entries also start a zone visit, ending the
+/// previous one.
+pub fn record_event(event: &LogEvent) -> AppResult<()> {
+    let (kind, detail, character) = match event {
+        LogEvent::ZoneEnter { zone_name, .. } => ("zone_enter", zone_name.clone(), None),
+        LogEvent::LevelUp { character_name, level, .. } => ("level_up", level.to_string(), Some(character_name)),
+        LogEvent::Death { character_name, .. } => ("death", character_name.clone(), Some(character_name)),
+        _ => return Ok(()),
+    };
+    let Some((run_id, elapsed_ms, fallback_character)) = active()
+        .as_ref()
+        .map(|(engine, fallback)| (engine.run_id, engine.elapsed_ms(Instant::now()), fallback.clone()))
+    else {
+        return Ok(());
+    };
+    if let Some(character) = character {
+        let Some(run) = Run::get_by_id(run_id)? else {
+            return Ok(());
+        };
+        if !is_run_character(&run, character, fallback_character.as_deref()) {
+            return Ok(());
+        }
+    }
+    RunEvent::insert(run_id, kind, elapsed_ms, Some(&detail))?;
+    if let LogEvent::ZoneEnter { zone_name, .. } = event {
+        let kind = classify_zone(zone_name);
+        ZoneTime::enter(run_id, zone_name, elapsed_ms, kind == ZoneKind::Town, kind == ZoneKind::Hideout)?;
+    }
+    Ok(())
+}
+
+/// Correct the active run's class and ascendancy from a level-up of its
+/// character. Level-ups name the ascendancy once ascended, so this covers
+/// private profiles and runs without snapshots, which the API never fills in.
+pub fn class_for(event: &LogEvent) -> AppResult<Option<RunClassUpdated>> {
+    let LogEvent::LevelUp { character_name, character_class, .. } = event else {
+        return Ok(None);
+    };
+    let Some((run_id, fallback_character)) = active()
+        .as_ref()
+        .map(|(engine, fallback)| (engine.run_id, fallback.clone()))
+    else {
+        return Ok(None);
+    };
+    let Some((class, ascendancy)) = pob_builder::parse_class(character_class) else {
+        return Ok(None);
+    };
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Ok(None);
+    };
+
+    if !is_run_character(&run, character_name, fallback_character.as_deref()) {
+        return Ok(None);
+    }
+    if run.class == class && ascendancy.is_none_or(|a| run.ascendancy.as_deref() == Some(a)) {
+        return Ok(None);
+    }
+
+    Run::set_class(run_id, class, ascendancy)?;
+    Ok(Some(RunClassUpdated {
+        run_id,
+        class: class.to_string(),
+        ascendancy: ascendancy.map(str::to_string).or(run.ascendancy),
+    }))
+}
+
+/// A level-up of a character other than the active run's, the first time that
+/// character is seen. Party members level up in the log too, so this only
+/// warns; `reassign_run_character` pauses the run or moves it to the character.
+pub fn mismatch_for(event: &LogEvent) -> AppResult<Option<CharacterMismatch>> {
+    let LogEvent::LevelUp { character_name, character_class, level, .. } = event else {
+        return Ok(None);
+    };
+    // Look the run up without holding the engine, like `split_for`
+    let Some((run_id, fallback_character)) = active()
+        .as_ref()
+        .filter(|(engine, _)| !engine.other_characters.contains(character_name))
+        .map(|(engine, fallback)| (engine.run_id, fallback.clone()))
+    else {
+        return Ok(None);
+    };
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Ok(None);
+    };
+    if is_run_character(&run, character_name, fallback_character.as_deref()) {
+        return Ok(None);
+    }
+
+    // The run may have ended meanwhile, or the character been reported already
+    let mut guard = active();
+    let Some((engine, _)) = guard.as_mut().filter(|(engine, _)| engine.run_id == run_id) else {
+        return Ok(None);
+    };
+    if !engine.other_characters.insert(character_name.clone()) {
+        return Ok(None);
+    }
+    let mismatch = CharacterMismatch {
+        run_id: run.id,
+        run_character: run.character_name,
+        character_name: character_name.clone(),
+        character_class: character_class.clone(),
+        level: *level,
+    };
+    engine.mismatch = Some(mismatch.clone());
+    Ok(Some(mismatch))
+}
+
+/// The active run's last character mismatch, clearing it
+pub fn take_mismatch(run_id: i64) -> Option<CharacterMismatch> {
+    active()
+        .as_mut()
+        .filter(|(engine, _)| engine.run_id == run_id)
+        .and_then(|(engine, _)| engine.mismatch.take())
+}
+
+/// Record a death of the active run's character, in the zone it last
+/// entered and at the level of its last level-up
+pub fn death_for(event: &LogEvent) -> AppResult<Option<DeathRecorded>> {
+    let LogEvent::Death { timestamp, character_name } = event else {
+        return Ok(None);
+    };
+    let Some((run_id, elapsed_ms, fallback_character)) = active()
+        .as_ref()
+        .map(|(engine, fallback)| (engine.run_id, engine.elapsed_ms(Instant::now()), fallback.clone()))
+    else {
+        return Ok(None);
+    };
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Ok(None);
+    };
+    if !is_run_character(&run, character_name, fallback_character.as_deref()) {
+        return Ok(None);
+    }
+
+    let zone_name = ZoneTime::get_by_run(run_id)?.pop().map(|visit| visit.zone_name);
+    let character_level = RunEvent::get_by_run(run_id)?
+        .into_iter()
+        .rev()
+        .find(|event| event.kind == "level_up")
+        .and_then(|event| event.detail?.parse().ok());
+    let death_id = Death::insert(run_id, timestamp, zone_name.as_deref(), elapsed_ms, character_level)?;
+    Ok(Some(DeathRecorded {
+        run_id,
+        death_id,
+        zone_name,
+        elapsed_ms,
+        character_level,
+        deaths: Death::count_by_run(run_id)?,
+    }))
+}
+
+/// The snapshot to capture for a level-up of the active run's character,
+/// every `snapshot_level_interval` levels while the timer runs. These don't
+/// depend on the preset's breakpoints, so they belong to no split.
+pub fn level_snapshot_for(event: &LogEvent) -> AppResult<Option<(SnapshotRequest, u32)>> {
+    let LogEvent::LevelUp { character_name, level, .. } = event else {
+        return Ok(None);
+    };
+    let settings = Settings::load()?;
+    let interval = u32::try_from(settings.snapshot_level_interval).unwrap_or(0);
+    if interval == 0 || level % interval != 0 || settings.account_name.is_empty() {
+        return Ok(None);
+    }
+    let Some((run_id, elapsed_ms, fallback_character)) = active()
+        .as_ref()
+        .filter(|(engine, _)| engine.clock.since.is_some())
+        .map(|(engine, fallback)| (engine.run_id, engine.elapsed_ms(Instant::now()), fallback.clone()))
+    else {
+        return Ok(None);
+    };
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Ok(None);
+    };
+    if !is_run_character(&run, character_name, fallback_character.as_deref()) {
+        return Ok(None);
+    }
+
+    let request = SnapshotRequest {
+        run_id,
+        split_id: None,
+        elapsed_time_ms: elapsed_ms,
+        account_name: settings.account_name,
+        character_name: character_name.clone(),
+        is_final: false,
+    };
+    Ok(Some((request, *level)))
+}
+
+/// Relabel the active run from the first event that says what it is (a new
+/// character, a map, the labyrinth) within its first minutes, unless its
+/// category was already detected or set by hand
+pub fn category_for(event: &LogEvent) -> AppResult<Option<RunCategoryUpdated>> {
+    let Some(activity) = category_detect::activity_of(event) else {
+        return Ok(None);
+    };
+    let (run_id, last_act) = {
+        let mut guard = active();
+        let Some((engine, _)) = guard.as_mut() else {
+            return Ok(None);
+        };
+        if engine.category_checked {
+            return Ok(None);
+        }
+        engine.category_checked = true;
+        if engine.elapsed_ms(Instant::now()) > CATEGORY_WINDOW_MS {
+            return Ok(None);
+        }
+        (engine.run_id, engine.last_act())
+    };
+
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Ok(None);
+    };
+    if run.category_source != "selected" {
+        return Ok(None);
+    }
+    let Some(category) = category_detect::category_for(activity, &run.category, last_act) else {
+        return Ok(None);
+    };
+    Run::set_category(run_id, &category, "detected")?;
+    Ok(Some(RunCategoryUpdated { run_id, category }))
+}
+
+/// Record the split a log event triggers, if any, and emit `split-recorded`.
+/// Level-ups also correct the run's class (`run-class-updated`), early events
+/// its category (`run-category-updated`), the character's deaths are stored
+/// (`death-recorded`), and the event is kept for the run timeline; once a
+/// level-up is kept, `level-pace-changed` says `get_level_pace` has moved.
+/// Level milestones queue a snapshot (`snapshot_level_interval`).
+/// AFK and disconnect lines pause the timer when `auto_pause` is on
+/// (`run-paused` / `run-resumed`).
+pub fn handle_event(app_handle: &AppHandle, event: &LogEvent) {
+    match pause::auto_pause_for(event) {
+        Ok(Some(changed)) => {
+            let _ = app_handle.emit(changed.event_name(), &changed);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[breakpoints] Failed to auto-pause: {}", e),
+    }
+    match record_event(event) {
+        Ok(()) => {
+            if let LogEvent::LevelUp { .. } = event {
+                let _ = app_handle.emit("level-pace-changed", ());
+            }
+        }
+        Err(e) => eprintln!("[breakpoints] Failed to record run event: {}", e),
+    }
+    match class_for(event) {
+        Ok(Some(updated)) => {
+            let _ = app_handle.emit("run-class-updated", &updated);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[breakpoints] Failed to update run class: {}", e),
+    }
+    match mismatch_for(event) {
+        Ok(Some(mismatch)) => {
+            let _ = app_handle.emit("character-mismatch", &mismatch);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[breakpoints] Failed to check run character: {}", e),
+    }
+    match category_for(event) {
+        Ok(Some(updated)) => {
+            let _ = app_handle.emit("run-category-updated", &updated);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[breakpoints] Failed to detect run category: {}", e),
+    }
+    match death_for(event) {
+        Ok(Some(recorded)) => {
+            let _ = app_handle.emit("death-recorded", &recorded);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[breakpoints] Failed to record death: {}", e),
+    }
+    // Queued before the split's snapshot, which supersedes it while it waits
+    match level_snapshot_for(event) {
+        Ok(Some((request, level))) => capture_level_snapshot(app_handle, request, level),
+        Ok(None) => {}
+        Err(e) => eprintln!("[breakpoints] Failed to queue level snapshot: {}", e),
+    }
+
+    let recorded = split_for(event).and_then(|pending| {
+        let Some(pending) = pending else {
+            return Ok(None);
+        };
+        let run_id = pending.split.run_id;
+        let account_name = Settings::load()?.account_name;
+        let added = split_and_capture(app_handle, AddSplitRequest {
+            split: pending.split,
+            capture_snapshot: pending.capture_snapshot,
+            account_name: Some(account_name).filter(|name| !name.is_empty()),
+            character_name: pending.character_name,
+        })?;
+        // Read back the stored row for the clamped times and backend town/hideout totals
+        let stored = Split::get_by_run(run_id)?.into_iter().find(|split| split.id == added.split_id);
+        Ok(stored.map(|split| SplitRecorded {
+            run_id,
+            split_id: added.split_id,
+            breakpoint_name: split.breakpoint_name,
+            breakpoint_type: split.breakpoint_type,
+            breakpoint_key: split.breakpoint_key,
+            split_time_ms: split.split_time_ms,
+            segment_time_ms: split.segment_time_ms,
+            town_time_ms: split.town_time_ms,
+            hideout_time_ms: split.hideout_time_ms,
+            is_last: pending.is_last,
+            comparison: added.comparison,
+        }))
+    });
+
+    match recorded {
+        Ok(Some(recorded)) => {
+            let _ = app_handle.emit("split-recorded", &recorded);
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[breakpoints] Failed to record split: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn breakpoint(name: &str, kind: &str, trigger: BreakpointTrigger) -> EngineBreakpoint {
+        EngineBreakpoint {
+            name: name.to_string(),
+            breakpoint_type: kind.to_string(),
+            trigger,
+            key: None,
+            capture_snapshot: false,
+        }
+    }
+
+    fn zone_trigger(zone: &str, act: i32) -> BreakpointTrigger {
+        BreakpointTrigger {
+            kind: "zone".to_string(),
+            zone_name: Some(zone.to_string()),
+            act: Some(act),
+            level: None,
+            penalty: None,
+            quest: None,
+            rule: None,
+        }
+    }
+
+    fn engine() -> BreakpointEngine {
+        let level = BreakpointTrigger {
+            kind: "level".to_string(),
+            zone_name: None,
+            act: None,
+            level: Some(12),
+            penalty: None,
+            quest: None,
+            rule: None,
+        };
+        let kitava = BreakpointTrigger { kind: "kitava".to_string(), penalty: Some(30), level: None, ..zone_trigger("", 5) };
+        BreakpointEngine::new(
+            1,
+            vec![
+                breakpoint("The Coast", "zone", zone_trigger("The Coast", 1)),
+                breakpoint("Level 12", "level", level),
+                breakpoint("The Forest Encampment", "act", zone_trigger("The Forest Encampment", 2)),
+                breakpoint("Kitava (A5)", "boss", kitava),
+                breakpoint("Lioneyes Watch (A6)", "act", zone_trigger("Lioneye's Watch", 6)),
+            ],
+            0,
+            Instant::now(),
+        )
+    }
+
+    fn zone(name: &str) -> LogEvent {
+        LogEvent::ZoneEnter { timestamp: String::new(), zone_name: name.to_string() }
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_zone_breakpoints_match_in_order() {
+        let engine = engine();
+        let matched =
+            |event: &LogEvent, done: &[&str]| engine.matching(event, &names(done), &[]).map(|bp| bp.name.clone());
+
+        assert_eq!(matched(&zone("the coast"), &[]).as_deref(), Some("The Coast"));
+        // Act 6's town can't split before the Act 2 town
+        assert_eq!(matched(&zone("Lioneye's Watch"), &["The Coast"]), None);
+        assert_eq!(matched(&zone("The Forest Encampment"), &["The Coast"]).as_deref(), Some("The Forest Encampment"));
+
+        // Levels match whenever they are reached
+        let level = LogEvent::LevelUp {
+            timestamp: String::new(),
+            character_name: "TestChar".to_string(),
+            character_class: "Witch".to_string(),
+            level: 12,
+        };
+        assert_eq!(matched(&level, &[]).as_deref(), Some("Level 12"));
+        assert_eq!(matched(&level, &["Level 12"]), None);
+
+        let kitava = LogEvent::KitavaAffliction { timestamp: String::new(), penalty: 30 };
+        assert_eq!(matched(&kitava, &[]).as_deref(), Some("Kitava (A5)"));
+        let act_complete = LogEvent::ActComplete { timestamp: String::new(), act: 5 };
+        assert_eq!(matched(&act_complete, &["The Coast", "Level 12", "The Forest Encampment"]), None);
+        assert_eq!(
+            matched(&act_complete, &["The Coast", "Level 12", "The Forest Encampment", "Kitava (A5)"]).as_deref(),
+            Some("Lioneyes Watch (A6)")
+        );
+        // Only Kitava's affliction is logged inside a zone
+        assert!(!engine.next_is_in_zone(&names(&["The Coast", "Level 12"])));
+        assert!(engine.next_is_in_zone(&names(&["The Coast", "Level 12", "The Forest Encampment"])));
+        assert!(engine.is_finished(&names(&[
+            "The Coast", "Level 12", "The Forest Encampment", "Kitava (A5)", "Lioneyes Watch (A6)"
+        ])));
+    }
+
+    #[test]
+    fn test_paused_timer_neither_splits_nor_advances() {
+        let t0 = Instant::now();
+        let mut engine = engine();
+        engine.set_running(true, 5_000, t0);
+        assert_eq!(engine.elapsed_ms(t0 + Duration::from_secs(2)), 7_000);
+
+        engine.set_running(false, 7_000, t0 + Duration::from_secs(2));
+        assert_eq!(engine.elapsed_ms(t0 + Duration::from_secs(60)), 7_000);
+        assert_eq!(engine.matching(&zone("The Coast"), &[], &[]), None);
+    }
+
+    #[test]
+    fn test_trigger_rules() {
+        let rule = |entry: Option<u32>, after_quest: Option<&str>| TriggerRule {
+            entry,
+            after_quest: after_quest.map(str::to_string),
+        };
+        let mut engine = BreakpointEngine::new(
+            1,
+            vec![
+                breakpoint(
+                    "Lioneye's Watch (return)",
+                    "zone",
+                    BreakpointTrigger { rule: Some(rule(Some(2), None)), ..zone_trigger("Lioneye's Watch", 1) },
+                ),
+                breakpoint(
+                    "The Submerged Passage",
+                    "zone",
+                    BreakpointTrigger {
+                        rule: Some(rule(None, Some("dweller"))),
+                        ..zone_trigger("The Submerged Passage", 1)
+                    },
+                ),
+            ],
+            0,
+            Instant::now(),
+        );
+        let mut enter = |name: &str, done: &[&str], quests: &[&str]| {
+            let event = zone(name);
+            engine.observe(&event);
+            engine.matching(&event, &names(done), &names(quests)).map(|bp| bp.name.clone())
+        };
+
+        // Second entry only: the first visit to town doesn't split
+        assert_eq!(enter("Lioneye's Watch", &[], &[]), None);
+        assert_eq!(enter("Lioneye's Watch", &[], &[]).as_deref(), Some("Lioneye's Watch (return)"));
+
+        let done = &["Lioneye's Watch (return)"];
+        assert_eq!(enter("The Submerged Passage", done, &[]), None);
+        assert_eq!(enter("The Submerged Passage", done, &["dweller"]).as_deref(), Some("The Submerged Passage"));
+
+        let parsed: BreakpointTrigger =
+            serde_json::from_str(r#"{"type": "zone", "zoneName": "The Coast", "rule": {"entry": 1}}"#).unwrap();
+        assert_eq!(parsed.rule, Some(rule(Some(1), None)));
+        let parsed: BreakpointTrigger = serde_json::from_str(r#"{"type": "level", "level": 12}"#).unwrap();
+        assert_eq!(parsed.rule, None);
+    }
+
+    #[test]
+    fn test_quest_breakpoints() {
+        let quest = |id: &str| BreakpointTrigger { kind: "quest".to_string(), quest: Some(id.to_string()), ..zone_trigger("", 1) };
+        let engine = BreakpointEngine::new(
+            1,
+            vec![
+                breakpoint("Dweller", "boss", quest("dweller")),
+                breakpoint("The Ship Graveyard", "zone", zone_trigger("The Ship Graveyard", 1)),
+                breakpoint("Bandits", "custom", quest("bandits")),
+            ],
+            0,
+            Instant::now(),
+        );
+        let matched = |event: &LogEvent, done: &[&str], quests: &[&str]| {
+            engine.matching(event, &names(done), &names(quests)).map(|bp| bp.name.clone())
+        };
+
+        // Any event after the objective is done splits it
+        assert_eq!(matched(&zone("The Flooded Depths"), &[], &[]), None);
+        assert_eq!(matched(&zone("The Flooded Depths"), &[], &["dweller"]).as_deref(), Some("Dweller"));
+        // In order among quests: a later quest waits for the one before it
+        assert_eq!(matched(&zone("The Broken Bridge"), &[], &["bandits"]), None);
+        assert_eq!(matched(&zone("The Broken Bridge"), &["Dweller"], &["dweller", "bandits"]).as_deref(), Some("Bandits"));
+        assert_eq!(matched(&zone("The Ship Graveyard"), &["Dweller"], &["dweller"]).as_deref(), Some("The Ship Graveyard"));
+
+        assert_eq!(breakpoint_key(&quest("bandits")).as_deref(), Some("quest:bandits"));
+        assert_eq!(breakpoint_key(&zone_trigger("Lioneye's Watch", 1)).as_deref(), Some("zone:a1:lioneye_s_watch"));
+        assert_eq!(breakpoint_key(&BreakpointTrigger { act: None, ..zone_trigger("The Coast", 1) }).as_deref(), Some("zone:the_coast"));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::game::Game;
+use crate::log_watcher::LogEvent;
+
+pub const MAPPING: &str = "Mapping";
+pub const LABYRINTH: &str = "Labyrinth";
+
+/// What a run turns out to be, from its first telling log event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    /// A new character: the first zone or the first level-up
+    Campaign,
+    /// An area generated from a map
+    Mapping,
+    /// The Aspirants' Plaza
+    Labyrinth,
+}
+
+/// The activity `event` gives away, if any
+pub fn activity_of(event: &LogEvent) -> Option<Activity> {
+    match event {
+        LogEvent::ZoneEnter { zone_name, .. }
+            if [Game::Poe1, Game::Poe2].iter().any(|game| zone_name == game.campaign_start()) =>
+        {
+            Some(Activity::Campaign)
+        }
+        LogEvent::LevelUp { level: 2, .. } => Some(Activity::Campaign),
+        LogEvent::AreaGenerated { area_id, .. } if area_id.starts_with("Map") => Some(Activity::Mapping),
+        LogEvent::LabStart { .. } => Some(Activity::Labyrinth),
+        _ => None,
+    }
+}
+
+/// The category a run of `activity` should have, or `None` when `current`
+/// already fits. Any category but mapping and labyrinth is a campaign
+/// variant; one that isn't becomes Any% to the act of the preset's last
+/// breakpoint.
+pub fn category_for(activity: Activity, current: &str, last_act: Option<i32>) -> Option<String> {
+    let is_campaign = !current.eq_ignore_ascii_case(MAPPING) && !current.eq_ignore_ascii_case(LABYRINTH);
+    match activity {
+        Activity::Campaign if is_campaign => None,
+        Activity::Campaign => Some(format!("Act {} Any%", last_act.filter(|act| *act > 0).unwrap_or(10))),
+        Activity::Mapping if current.eq_ignore_ascii_case(MAPPING) => None,
+        Activity::Mapping => Some(MAPPING.to_string()),
+        Activity::Labyrinth if current.eq_ignore_ascii_case(LABYRINTH) => None,
+        Activity::Labyrinth => Some(LABYRINTH.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_from_first_events() {
+        let strand = LogEvent::ZoneEnter { timestamp: String::new(), zone_name: "The Twilight Strand".into() };
+        let map = LogEvent::AreaGenerated {
+            timestamp: String::new(),
+            area_level: 83,
+            area_id: "MapWorldsStrand".into(),
+            seed: 1,
+        };
+        let hideout = LogEvent::ZoneEnter { timestamp: String::new(), zone_name: "Coastal Hideout".into() };
+        assert_eq!(activity_of(&strand), Some(Activity::Campaign));
+        assert_eq!(activity_of(&map), Some(Activity::Mapping));
+        assert_eq!(activity_of(&hideout), None);
+
+        // Campaign variants are left alone
+        assert_eq!(category_for(Activity::Campaign, "Act 5 100%", Some(5)), None);
+        assert_eq!(category_for(Activity::Campaign, "glitchless", Some(10)), None);
+        assert_eq!(category_for(Activity::Campaign, "Mapping", Some(5)).as_deref(), Some("Act 5 Any%"));
+        assert_eq!(category_for(Activity::Campaign, "Labyrinth", None).as_deref(), Some("Act 10 Any%"));
+
+        assert_eq!(category_for(Activity::Mapping, "Act 10 Any%", Some(10)).as_deref(), Some("Mapping"));
+        assert_eq!(category_for(Activity::Mapping, "mapping", Some(10)), None);
+        assert_eq!(category_for(Activity::Labyrinth, "any%", None).as_deref(), Some("Labyrinth"));
+    }
+}
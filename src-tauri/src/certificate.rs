@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::db::{Run, Split};
+use crate::error::{AppError, AppResult};
+use crate::run_json::RunExport;
+
+/// Certificate format; bump when the hashed fields change
+pub const CERTIFICATE_VERSION: u32 = 1;
+
+/// A split as the certificate records it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CertifiedSplit {
+    pub name: String,
+    pub key: Option<String>,
+    pub time_ms: i64,
+}
+
+/// Compact summary of a completed run with a SHA-256 over its fields. It
+/// makes edits to a shared file evident to anyone who checks it, unless the
+/// editor also recomputes the hash: tamper-evidence, not a signature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCertificate {
+    pub version: u32,
+    /// App version that issued the certificate
+    pub app_version: String,
+    pub character: String,
+    pub account_name: String,
+    pub class: String,
+    pub category: String,
+    pub league: String,
+    pub started_at: String,
+    pub total_time_ms: i64,
+    pub splits: Vec<CertifiedSplit>,
+    /// Lowercase hex SHA-256 over every field above
+    pub hash: String,
+}
+
+/// Result of checking a certificate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateStatus {
+    Valid,
+    /// The hash doesn't match the fields, or the fields don't match the run
+    /// the certificate came with
+    Tampered,
+    /// Issued by a newer app with a format this one can't check
+    UnsupportedVersion,
+}
+
+/// Certificate for a completed run and its splits
+pub fn issue(run: &Run, splits: &[Split]) -> AppResult<RunCertificate> {
+    let total_time_ms = run
+        .total_time_ms
+        .filter(|_| run.is_completed)
+        .ok_or_else(|| AppError::InvalidInput(format!("Run {} isn't completed", run.id)))?;
+
+    let mut certificate = RunCertificate {
+        version: CERTIFICATE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        character: run.character_name.clone(),
+        account_name: run.account_name.clone(),
+        class: run.class.clone(),
+        category: run.category.clone(),
+        league: run.league.clone(),
+        started_at: run.started_at.clone(),
+        total_time_ms,
+        splits: splits
+            .iter()
+            .map(|split| CertifiedSplit {
+                name: split.breakpoint_name.clone(),
+                key: split.breakpoint_key.clone(),
+                time_ms: split.split_time_ms,
+            })
+            .collect(),
+        hash: String::new(),
+    };
+    certificate.hash = digest(&certificate);
+    Ok(certificate)
+}
+
+/// SHA-256 over the certified fields, serialized as one JSON array so field
+/// boundaries can't be shifted
+fn digest(certificate: &RunCertificate) -> String {
+    let fields = serde_json::json!([
+        certificate.version,
+        certificate.app_version,
+        certificate.character,
+        certificate.account_name,
+        certificate.class,
+        certificate.category,
+        certificate.league,
+        certificate.started_at,
+        certificate.total_time_ms,
+        certificate.splits.iter().map(|s| serde_json::json!([s.name, s.key, s.time_ms])).collect::<Vec<_>>(),
+    ]);
+    Sha256::digest(fields.to_string().as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Check a certificate's hash against its fields
+pub fn verify(certificate: &RunCertificate) -> CertificateStatus {
+    if certificate.version > CERTIFICATE_VERSION {
+        CertificateStatus::UnsupportedVersion
+    } else if digest(certificate).eq_ignore_ascii_case(&certificate.hash) {
+        CertificateStatus::Valid
+    } else {
+        CertificateStatus::Tampered
+    }
+}
+
+/// Check the certificate embedded in a run export, and that it certifies
+/// that export's run and split times rather than some other run
+pub fn verify_export(export: &RunExport) -> Option<CertificateStatus> {
+    let certificate = export.certificate.as_ref()?;
+    let status = verify(certificate);
+    if status != CertificateStatus::Valid {
+        return Some(status);
+    }
+    let run = &export.run;
+    let matches = certificate.character == run.character
+        && certificate.class == run.class
+        && certificate.category == run.category
+        && certificate.started_at == run.started_at
+        && Some(certificate.total_time_ms) == run.total_time_ms
+        && certificate.splits.len() == export.splits.len()
+        && certificate
+            .splits
+            .iter()
+            .zip(&export.splits)
+            .all(|(certified, split)| certified.name == split.breakpoint_name && certified.time_ms == split.split_time_ms);
+    Some(if matches { CertificateStatus::Valid } else { CertificateStatus::Tampered })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn certificate() -> RunCertificate {
+        let mut certificate = RunCertificate {
+            version: CERTIFICATE_VERSION,
+            app_version: "0.3.2".to_string(),
+            character: "Runner".to_string(),
+            account_name: "Me#1234".to_string(),
+            class: "Witch".to_string(),
+            category: "Act 10 Any%".to_string(),
+            league: "Standard".to_string(),
+            started_at: "2026-01-01T00:00:00Z".to_string(),
+            total_time_ms: 110_000,
+            splits: vec![
+                CertifiedSplit { name: "The Coast".to_string(), key: None, time_ms: 60_000 },
+                CertifiedSplit { name: "The Mud Flats".to_string(), key: None, time_ms: 110_000 },
+            ],
+            hash: String::new(),
+        };
+        certificate.hash = digest(&certificate);
+        certificate
+    }
+
+    #[test]
+    fn test_hash_detects_edits() {
+        let valid = certificate();
+        assert_eq!(valid.hash.len(), 64);
+        assert_eq!(verify(&valid), CertificateStatus::Valid);
+        assert_eq!(verify(&RunCertificate { hash: valid.hash.to_uppercase(), ..valid.clone() }), CertificateStatus::Valid);
+
+        let mut faster = valid.clone();
+        faster.splits[1].time_ms = 100_000;
+        assert_eq!(verify(&faster), CertificateStatus::Tampered);
+        assert_eq!(verify(&RunCertificate { total_time_ms: 100_000, ..valid.clone() }), CertificateStatus::Tampered);
+
+        // Moving text between fields changes the hash too
+        let shifted = RunCertificate { character: "Runne".to_string(), account_name: "rMe#1234".to_string(), ..valid.clone() };
+        assert_eq!(verify(&shifted), CertificateStatus::Tampered);
+
+        assert_eq!(verify(&RunCertificate { version: CERTIFICATE_VERSION + 1, ..valid }), CertificateStatus::UnsupportedVersion);
+    }
+}
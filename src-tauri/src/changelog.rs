@@ -0,0 +1,284 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+use crate::db::{self, Settings};
+use crate::error::{AppError, AppResult};
+
+/// The repo's CHANGELOG.md (Keep a Changelog format), bundled at build time
+const CHANGELOG: &str = include_str!("../../CHANGELOG.md");
+
+/// Migrations that change data the user already has, and what that means for
+/// them. Migrations that only add tables or settings need no notice.
+const MIGRATION_NOTICES: &[(&str, &str)] = &[
+    (
+        "008_add_class_to_gold_splits",
+        "Gold splits are now kept per class. Existing golds were moved to an \"Unknown\" class.",
+    ),
+    (
+        "009_add_breakpoint_keys",
+        "Splits and gold splits were linked to canonical zone keys, so presets with different split names share golds and stats.",
+    ),
+    (
+        "031_add_run_category_source",
+        "Existing runs keep the category they were started with; new runs can have theirs detected from the log.",
+    ),
+];
+
+/// One `### Added`/`### Fixed`/... list of a release
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeGroup {
+    pub heading: String,
+    pub entries: Vec<String>,
+}
+
+/// One `## [x.y.z] - date` section of the changelog
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Release {
+    pub version: String,
+    pub date: Option<String>,
+    pub groups: Vec<ChangeGroup>,
+}
+
+/// A migration applied at startup
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationNotice {
+    pub name: String,
+    /// What changed for the user, for migrations that rewrite existing data
+    pub notice: Option<String>,
+}
+
+/// Result of `get_app_changelog_state`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppChangelogState {
+    pub current_version: String,
+    /// Version that last ran against the database; None on a new database or
+    /// when upgrading from a version that didn't record it
+    pub previous_version: Option<String>,
+    /// A new database: nothing to tell the user about
+    pub is_first_run: bool,
+    /// The app was updated since it last ran
+    pub is_upgrade: bool,
+    /// Releases since the previous version, newest first
+    pub releases: Vec<Release>,
+    pub migrations: Vec<MigrationNotice>,
+}
+
+/// Parse Keep a Changelog markdown into releases, in file order. Entries
+/// wrapped over several lines are joined.
+pub fn parse(markdown: &str) -> Vec<Release> {
+    let mut releases: Vec<Release> = Vec::new();
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if let Some(heading) = trimmed.strip_prefix("## ") {
+            let (version, date) = heading.split_once(" - ").unwrap_or((heading, ""));
+            releases.push(Release {
+                version: version.trim().trim_start_matches('[').trim_end_matches(']').to_string(),
+                date: Some(date.trim().to_string()).filter(|d| !d.is_empty()),
+                groups: Vec::new(),
+            });
+            continue;
+        }
+        let Some(release) = releases.last_mut() else {
+            continue;
+        };
+        if let Some(heading) = trimmed.strip_prefix("### ") {
+            release.groups.push(ChangeGroup { heading: heading.trim().to_string(), entries: Vec::new() });
+        } else if let Some(entry) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+            if release.groups.is_empty() {
+                release.groups.push(ChangeGroup { heading: String::new(), entries: Vec::new() });
+            }
+            if let Some(group) = release.groups.last_mut() {
+                group.entries.push(entry.trim().to_string());
+            }
+        } else if !trimmed.is_empty() && line.starts_with(char::is_whitespace) {
+            if let Some(last) = release.groups.last_mut().and_then(|group| group.entries.last_mut()) {
+                last.push(' ');
+                last.push_str(trimmed);
+            }
+        }
+    }
+    releases
+}
+
+/// `major.minor.patch` of a version string; pre-release and build suffixes
+/// are ignored, and anything else (e.g. `Unreleased`) has no version
+fn version_key(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().trim_start_matches('v').split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let key = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(key)
+}
+
+/// Releases after `previous` up to and including `current`, newest first.
+/// Without a previous version only the current release is included.
+pub fn releases_between(releases: &[Release], previous: Option<&str>, current: &str) -> Vec<Release> {
+    let Some(current) = version_key(current) else {
+        return Vec::new();
+    };
+    let previous = previous.and_then(version_key);
+    let mut between: Vec<Release> = releases
+        .iter()
+        .filter(|release| {
+            version_key(&release.version).is_some_and(|key| {
+                key <= current && previous.map_or(key == current, |previous| key > previous)
+            })
+        })
+        .cloned()
+        .collect();
+    between.sort_by_key(|release| std::cmp::Reverse(version_key(&release.version)));
+    between
+}
+
+/// What changed between the version that last ran and this one
+pub fn build_state(previous: Option<&str>, current: &str, applied_migrations: &[String], changelog: &str) -> AppChangelogState {
+    let is_first_run = previous.is_none() && applied_migrations.iter().any(|name| name == "001_initial_schema");
+    let is_upgrade = !is_first_run
+        && match (previous.and_then(version_key), version_key(current)) {
+            (Some(previous), Some(current)) => current > previous,
+            (None, _) => true,
+            (Some(_), None) => false,
+        };
+
+    AppChangelogState {
+        current_version: current.to_string(),
+        previous_version: previous.map(str::to_string),
+        is_first_run,
+        is_upgrade,
+        releases: if is_upgrade { releases_between(&parse(changelog), previous, current) } else { Vec::new() },
+        migrations: if is_first_run {
+            Vec::new()
+        } else {
+            applied_migrations
+                .iter()
+                .map(|name| MigrationNotice {
+                    name: name.clone(),
+                    notice: MIGRATION_NOTICES
+                        .iter()
+                        .find(|(notice_name, _)| notice_name == name)
+                        .map(|(_, notice)| notice.to_string()),
+                })
+                .collect()
+        },
+    }
+}
+
+static STATE: Mutex<Option<AppChangelogState>> = Mutex::new(None);
+
+/// Compare the running version with the one recorded in the database, then
+/// record this one. Call once at startup, right after the database opens; the
+/// result is kept for `get_app_changelog_state` for the rest of the session.
+pub fn record_startup() -> AppResult<AppChangelogState> {
+    let current = env!("CARGO_PKG_VERSION");
+    let previous = Settings::get_last_app_version()?;
+    let previous = Some(previous.as_str()).filter(|version| !version.is_empty());
+
+    let state = build_state(previous, current, &db::applied_migrations(), CHANGELOG);
+    Settings::set_last_app_version(current)?;
+    *STATE.lock().unwrap_or_else(|e| e.into_inner()) = Some(state.clone());
+    Ok(state)
+}
+
+/// The state recorded at startup
+pub fn state() -> AppResult<AppChangelogState> {
+    STATE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or_else(|| AppError::Internal("App version was not recorded at startup".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "# Changelog
+
+## [Unreleased]
+
+- Not out yet
+
+## [0.3.0] - 2026-03-01
+
+### Added
+
+- Auto-export of completed runs
+  to a folder
+
+### Fixed
+
+- Overlay flicker
+
+## [0.2.0] - 2026-02-01
+
+### Changed
+
+- Faster startup
+
+## [0.1.0] - 2025-01-01
+
+### Added
+
+- Speedrun timer
+";
+
+    #[test]
+    fn test_parse_changelog() {
+        let releases = parse(SAMPLE);
+        let versions: Vec<&str> = releases.iter().map(|r| r.version.as_str()).collect();
+        assert_eq!(versions, vec!["Unreleased", "0.3.0", "0.2.0", "0.1.0"]);
+        assert_eq!(releases[1].date.as_deref(), Some("2026-03-01"));
+        assert_eq!(releases[0].date, None);
+        assert_eq!(
+            releases[1].groups,
+            vec![
+                ChangeGroup { heading: "Added".to_string(), entries: vec!["Auto-export of completed runs to a folder".to_string()] },
+                ChangeGroup { heading: "Fixed".to_string(), entries: vec!["Overlay flicker".to_string()] },
+            ]
+        );
+        assert_eq!(releases[0].groups[0].heading, "");
+
+        // The bundled changelog parses
+        assert!(parse(CHANGELOG).iter().any(|r| version_key(&r.version).is_some()));
+    }
+
+    #[test]
+    fn test_releases_between_versions() {
+        let releases = parse(SAMPLE);
+        let versions = |previous, current| -> Vec<String> {
+            releases_between(&releases, previous, current).into_iter().map(|r| r.version).collect()
+        };
+        assert_eq!(versions(Some("0.1.0"), "0.3.0"), vec!["0.3.0", "0.2.0"]);
+        assert_eq!(versions(Some("0.2.0"), "v0.3.0"), vec!["0.3.0"]);
+        assert_eq!(versions(None, "0.2.0"), vec!["0.2.0"]);
+        assert_eq!(versions(Some("0.3.0"), "0.3.0"), Vec::<String>::new());
+        assert_eq!(version_key("0.3.1-beta.2"), Some((0, 3, 1)));
+        assert_eq!(version_key("0.3"), None);
+    }
+
+    #[test]
+    fn test_state_for_new_database_and_upgrades() {
+        let all: Vec<String> = ["001_initial_schema", "008_add_class_to_gold_splits"].map(String::from).to_vec();
+        let fresh = build_state(None, "0.3.0", &all, SAMPLE);
+        assert!(fresh.is_first_run && !fresh.is_upgrade);
+        assert!(fresh.releases.is_empty() && fresh.migrations.is_empty());
+
+        let migrated = vec!["008_add_class_to_gold_splits".to_string(), "036_add_auto_export".to_string()];
+        let upgrade = build_state(Some("0.1.0"), "0.3.0", &migrated, SAMPLE);
+        assert!(upgrade.is_upgrade && !upgrade.is_first_run);
+        assert_eq!(upgrade.releases.len(), 2);
+        assert!(upgrade.migrations[0].notice.is_some());
+        assert_eq!(upgrade.migrations[1].notice, None);
+
+        // Updated from a version that didn't record itself
+        let unrecorded = build_state(None, "0.3.0", &migrated, SAMPLE);
+        assert!(unrecorded.is_upgrade);
+        assert_eq!(unrecorded.releases[0].version, "0.3.0");
+
+        let same = build_state(Some("0.3.0"), "0.3.0", &[], SAMPLE);
+        assert!(!same.is_upgrade && same.releases.is_empty() && same.migrations.is_empty());
+    }
+}
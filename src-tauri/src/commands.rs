@@ -1,28 +1,88 @@
-use crate::api_client::PoeApiClient;
+use crate::abandon::{self, AbandonReason, AbandonmentStats, ResetStats};
+use crate::announcements;
+use crate::auto_export;
+use crate::breakpoint_engine::{self, EngineBreakpoint};
+use crate::certificate::{self, CertificateStatus, RunCertificate};
+use crate::changelog::{self, AppChangelogState};
+use crate::comparison::{self, ComparisonKind, ComparisonRow, SplitComparison};
+use crate::diagnostics::{self, SnapshotRecording};
+use crate::api_client::{PoeApi, PoeApiClient};
+use crate::backtrack::{self, BacktrackReport};
 use crate::db::{
-    NewRun, NewSplit, NewSnapshot, PersonalBest, Run, Settings, Snapshot, Split, GoldSplit,
-    RunFilters, RunStats, SplitStat, ReferenceRunData,
+    NewRun, NewSplit, NewSnapshot, PersonalBest, Run, Settings, Snapshot, SnapshotSummary, Split, GoldSplit, ProvisionalGold,
+    ArchiveReport, RunFilters, RunPage, RunReview, RunStats, SplitStat, SplitTrendPoint, ReferenceRunData, ReferenceSplitData,
+    SplitNote, NewSplitNote, RunTag, TimeLossReport, ActTownTime, QuestProgress, LevelingGuide, RepairReport, CompactReport, SnapshotRetention, RunEvent, StatusServerConfig,
+    ObsServerConfig, SkippedSplit, Pause, CategoryDefaults, BreakpointPreset, PresetBreakpoint, NewPresetBreakpoint, OverlayComponents, OverlayProfile, MapSession, Death, ZoneTime, ZoneTimeStat, ZoneHeatmapEntry,
 };
-use crate::log_watcher::{detect_log_path, LogWatcher};
+use crate::error::{AppError, AppResult};
+use crate::game::Game;
+use crate::game_process;
+use crate::guide::{self, Guide, GuideProgress};
+use crate::image_cache::{self, ImageCacheStats};
+use crate::ladder::{self, LadderRacer};
+use crate::level_pace::{self, ExperiencePoint, LevelPace};
+use crate::livesplit;
+use crate::log_backfill;
+use crate::log_watcher::{detect_log_path, resolve_path_case, LogWatcher};
+use crate::map_session;
+use crate::oauth;
+use crate::obs_server;
+use crate::pause::{self, PauseReason};
+use crate::pob_builder;
+use crate::poeninja::{NinjaBuild, NinjaCharacter, PoeNinjaClient};
+use crate::privacy::{self, ProfilePrivacy};
+use crate::reference_import;
+use crate::ruleset::Ruleset;
+use crate::run_json;
+use crate::run_timeline::{self, TimelineEntry};
+use crate::snapshot_analysis::{self, GemHistory, ReprocessReport};
+use crate::snapshot_diff::{self, SnapshotDiff};
+use crate::snapshot_queue::{PendingSnapshot, SnapshotQueue, SnapshotRequest};
+use crate::share_card::{self, ShareCard};
+use crate::splits_image;
+use crate::stats::{self, BestPossibleTime, SumOfBest};
+use crate::table_export::{self, ExportLocale, TableFormat};
+use crate::quest_state::{self, ObjectiveStatus, QuestState};
+use crate::race_lobby::{self, RaceStatus};
+use crate::readiness::{self, Checklist, ReadinessState, RunReadiness};
+use crate::status_server;
+use crate::updater::{self, UpdateChannel, UpdateInfo};
+use crate::twitch::{self, ChatConfig};
+use crate::webhook;
+use crate::zone_time::{self, ZoneTimeReport};
 use crate::HotkeyMap;
-use anyhow::Result;
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, LogicalSize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, LogicalSize};
+use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tauri_plugin_opener::OpenerExt;
 
 // Global state
 static LOG_WATCHER: OnceCell<Mutex<Option<LogWatcher>>> = OnceCell::new();
 static API_CLIENT: OnceCell<PoeApiClient> = OnceCell::new();
+static SNAPSHOT_QUEUE: Mutex<SnapshotQueue> = Mutex::new(SnapshotQueue::new());
+/// Abort handle of the capture in flight, so deleting its run can cancel it
+static SNAPSHOT_TASK: Mutex<Option<tokio::task::AbortHandle>> = Mutex::new(None);
+
+/// Longest a snapshot capture may take, rate-limit waits included, before it
+/// is abandoned and the queue moves on
+const SNAPSHOT_DEADLINE: Duration = Duration::from_secs(120);
 
 fn get_log_watcher() -> &'static Mutex<Option<LogWatcher>> {
     LOG_WATCHER.get_or_init(|| Mutex::new(None))
 }
 
 fn get_api_client() -> &'static PoeApiClient {
-    API_CLIENT.get_or_init(PoeApiClient::new)
+    API_CLIENT.get_or_init(|| {
+        PoeApiClient::new()
+            .with_oauth_token(Settings::get_oauth_token().ok().flatten())
+            .with_game(Game::current())
+    })
 }
 
 // ============================================================================
@@ -30,22 +90,65 @@ fn get_api_client() -> &'static PoeApiClient {
 // ============================================================================
 
 #[tauri::command]
-pub async fn get_settings() -> Result<Settings, String> {
-    Settings::load().map_err(|e| e.to_string())
+pub async fn get_settings() -> AppResult<Settings> {
+    Settings::load().map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn save_settings(settings: Settings) -> Result<(), String> {
-    Settings::save(&settings).map_err(|e| e.to_string())
+pub async fn save_settings(app_handle: AppHandle, settings: Settings) -> AppResult<()> {
+    webhook::validate_url(&settings.webhook_url)?;
+    for name in [&settings.twitch_channel, &settings.twitch_username] {
+        if !twitch::is_valid_name(name) {
+            return Err(AppError::InvalidInput(format!("Not a Twitch channel or account name: {}", name.trim())));
+        }
+    }
+    if UpdateChannel::parse(&settings.update_channel).is_none() {
+        return Err(AppError::InvalidInput(format!("Unknown update channel: {}", settings.update_channel)));
+    }
+    if SnapshotRetention::parse(&settings.snapshot_retention, settings.snapshot_retention_runs).is_none() {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid snapshot retention: {} (runs: {})",
+            settings.snapshot_retention, settings.snapshot_retention_runs
+        )));
+    }
+    if !(0..=100).contains(&settings.snapshot_level_interval) {
+        return Err(AppError::InvalidInput(format!(
+            "Snapshot level interval must be between 0 and 100: {}",
+            settings.snapshot_level_interval
+        )));
+    }
+    if image_cache::parse_hosts(&settings.image_proxy_hosts).is_none() {
+        return Err(AppError::InvalidInput(format!(
+            "Trusted image hosts must be a comma-separated list of host names: {}",
+            settings.image_proxy_hosts
+        )));
+    }
+    Settings::save(&settings)?;
+    get_api_client().set_game(Game::from_setting(&settings.game)).await;
+    apply_follow_game(&app_handle, settings.follow_game_process);
+    sync_launch_on_boot(&app_handle, settings.launch_on_boot)
+}
+
+/// Register or unregister the app with the OS autostart mechanism (registry
+/// Run key, LaunchAgent, or XDG autostart entry) to match the setting
+pub(crate) fn sync_launch_on_boot(app_handle: &AppHandle, enabled: bool) -> AppResult<()> {
+    let autolaunch = app_handle.autolaunch();
+    let is_enabled = autolaunch.is_enabled().unwrap_or(false);
+    let result = match (enabled, is_enabled) {
+        (true, false) => autolaunch.enable(),
+        (false, true) => autolaunch.disable(),
+        _ => Ok(()),
+    };
+    result.map_err(|e| AppError::Internal(format!("Failed to update launch on boot: {}", e)))
 }
 
 #[tauri::command]
-pub async fn detect_log_path_cmd() -> Result<Option<String>, String> {
+pub async fn detect_log_path_cmd() -> AppResult<Option<String>> {
     Ok(detect_log_path().map(|p| p.to_string_lossy().to_string()))
 }
 
 #[tauri::command]
-pub async fn browse_log_path() -> Result<Option<String>, String> {
+pub async fn browse_log_path() -> AppResult<Option<String>> {
     // Note: In a real implementation, this would use tauri-plugin-dialog
     // For now, just return None to indicate the user should manually enter the path
     Ok(None)
@@ -56,33 +159,37 @@ pub async fn browse_log_path() -> Result<Option<String>, String> {
 // ============================================================================
 
 #[tauri::command]
-pub async fn start_log_watcher(app_handle: AppHandle, log_path: String) -> Result<(), String> {
-    let path = PathBuf::from(&log_path);
+pub async fn start_log_watcher(app_handle: AppHandle, log_path: String) -> AppResult<()> {
+    let path = resolve_path_case(Path::new(&log_path));
     if !path.exists() {
-        return Err(format!("Log file not found: {}", log_path));
+        return Err(AppError::NotFound(format!("Log file {}", log_path)));
+    }
+    // Following the game client, the watcher starts when the client does
+    if game_process::waiting_for_game() {
+        return Ok(());
     }
 
     // Stop any existing watcher first
     {
-        let mut guard = get_log_watcher().lock().map_err(|e| e.to_string())?;
+        let mut guard = get_log_watcher().lock()?;
         if let Some(ref mut existing) = *guard {
             existing.stop();
         }
         *guard = None;
     }
 
-    let mut watcher = LogWatcher::new(path);
-    watcher.start(app_handle).map_err(|e| e.to_string())?;
+    let mut watcher = LogWatcher::new(path).with_game(Game::current());
+    watcher.start(app_handle)?;
 
-    let mut guard = get_log_watcher().lock().map_err(|e| e.to_string())?;
+    let mut guard = get_log_watcher().lock()?;
     *guard = Some(watcher);
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn stop_log_watcher() -> Result<(), String> {
-    let mut guard = get_log_watcher().lock().map_err(|e| e.to_string())?;
+pub async fn stop_log_watcher() -> AppResult<()> {
+    let mut guard = get_log_watcher().lock()?;
     if let Some(ref mut watcher) = *guard {
         watcher.stop();
     }
@@ -91,372 +198,2182 @@ pub async fn stop_log_watcher() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn set_log_poll_fast(enabled: bool) -> Result<(), String> {
-    let guard = get_log_watcher().lock().map_err(|e| e.to_string())?;
+pub async fn set_log_poll_fast(enabled: bool) -> AppResult<()> {
+    let guard = get_log_watcher().lock()?;
     if let Some(ref watcher) = *guard {
         watcher.set_fast_polling(enabled);
     }
     Ok(())
 }
 
+/// Follow the game client or stop following it, per `follow_game_process`
+pub(crate) fn apply_follow_game(app_handle: &AppHandle, enabled: bool) {
+    if enabled {
+        game_process::follow(game_listener(app_handle.clone()));
+    } else {
+        game_process::unfollow();
+    }
+}
+
+/// When the game starts, start the log watcher on the saved log path (the
+/// client's own when none is saved) and show the overlays; when it exits,
+/// stop the watcher and hide them
+fn game_listener(app_handle: AppHandle) -> game_process::Listener {
+    Arc::new(move |game| {
+        for (_, window) in overlay_windows(&app_handle) {
+            let shown = if game.is_some() { window.show() } else { window.hide() };
+            if let Err(e) = shown {
+                eprintln!("[game] Failed to show or hide the overlay: {}", e);
+            }
+        }
+        let handle = app_handle.clone();
+        let game_log = game.map(|game| game.log_path.clone());
+        tauri::async_runtime::spawn(async move {
+            let result = match game_log {
+                None => stop_log_watcher().await,
+                Some(game_log) => {
+                    let saved = Settings::load().map(|settings| settings.poe_log_path).unwrap_or_default();
+                    let log_path = Some(saved)
+                        .filter(|path| !path.is_empty())
+                        .or_else(|| game_log.map(|path| path.to_string_lossy().into_owned()));
+                    match log_path {
+                        Some(log_path) => start_log_watcher(handle, log_path).await,
+                        None => Ok(()),
+                    }
+                }
+            };
+            if let Err(e) = result {
+                eprintln!("[game] Failed to follow the game client: {}", e);
+            }
+        });
+    })
+}
+
 // ============================================================================
 // Run Commands
 // ============================================================================
 
 #[tauri::command]
-pub async fn create_run(run: NewRun) -> Result<i64, String> {
-    Run::insert(&run).map_err(|e| e.to_string())
+pub async fn create_run(run: NewRun) -> AppResult<i64> {
+    start_new_run(run)
+}
+
+/// Store a new run and start its town/hideout and quest tracking, applying
+/// the category's saved defaults. Shared with runs auto-started from the log.
+pub(crate) fn start_new_run(mut run: NewRun) -> AppResult<i64> {
+    if run.game_version.is_none() {
+        run.game_version = detected_game_version();
+    }
+    if let Some(defaults) = CategoryDefaults::get(&run.category)? {
+        if defaults.breakpoint_preset.is_some() {
+            run.breakpoint_preset = defaults.breakpoint_preset.clone();
+        }
+        if defaults.enabled_breakpoints.is_some() {
+            run.enabled_breakpoints = defaults.enabled_breakpoints.clone();
+        }
+        let mut settings = Settings::load()?;
+        defaults.apply_to(&mut settings);
+        Settings::save(&settings)?;
+    }
+    let run_id = Run::insert(&run)?;
+    race_lobby::run_started(&run);
+    tag_ruleset(run_id, &run.league)?;
+    Run::set_start_offset(run_id, Settings::load()?.timer_start_offset_ms.max(0))?;
+    flag_warmup(run_id)?;
+    zone_time::start_run(run_id);
+    quest_state::start_run(run_id);
+    guide::start_run(run_id);
+    Ok(run_id)
+}
+
+/// Record the run's ruleset from its league name, when the league is known
+fn tag_ruleset(run_id: i64, league: &str) -> AppResult<()> {
+    if let Some(ruleset) = Ruleset::from_league(league) {
+        Run::set_ruleset(run_id, ruleset.as_str())?;
+    }
+    Ok(())
+}
+
+/// Saved per-category setups, applied by `create_run` when a run of the category starts
+#[tauri::command]
+pub async fn get_category_defaults() -> AppResult<Vec<CategoryDefaults>> {
+    CategoryDefaults::get_all().map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn update_run_character(run_id: i64, character_name: String, class: String) -> Result<(), String> {
-    Run::update_character(run_id, &character_name, &class).map_err(|e| e.to_string())
+pub async fn set_category_defaults(defaults: CategoryDefaults) -> AppResult<()> {
+    if defaults.category.trim().is_empty() {
+        return Err(AppError::InvalidInput("Category is required".to_string()));
+    }
+    CategoryDefaults::save(&defaults).map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn complete_run(run_id: i64, total_time_ms: i64) -> Result<bool, String> {
-    Run::complete(run_id, total_time_ms).map_err(|e| e.to_string())?;
+pub async fn delete_category_defaults(category: String) -> AppResult<()> {
+    if CategoryDefaults::delete(&category)? == 0 {
+        return Err(AppError::NotFound(format!("Category defaults for {}", category)));
+    }
+    Ok(())
+}
 
-    // Check if this is a new personal best
-    if let Ok(Some(run)) = Run::get_by_id(run_id) {
-        let category = format!("{}", run.category);
-        let is_pb = PersonalBest::get_or_create(&category, &run.class, run_id, total_time_ms)
-            .map_err(|e| e.to_string())?;
-        return Ok(is_pb);
+/// Flag a new run as a warm-up if it is the first run after the configured session gap
+pub(crate) fn flag_warmup(run_id: i64) -> AppResult<bool> {
+    let settings = Settings::load()?;
+    if !settings.warmup_detection_enabled {
+        return Ok(false);
     }
 
-    Ok(false)
+    let is_warmup = match Run::minutes_since_previous(run_id)? {
+        Some(gap) => gap >= settings.session_gap_minutes as f64,
+        None => true,
+    };
+    if is_warmup {
+        Run::set_warmup(run_id, true)?;
+    }
+    Ok(is_warmup)
 }
 
+/// Manually mark or unmark a run as a warm-up
 #[tauri::command]
-pub async fn get_runs() -> Result<Vec<Run>, String> {
-    Run::get_all().map_err(|e| e.to_string())
+pub async fn set_run_warmup(run_id: i64, is_warmup: bool) -> AppResult<()> {
+    if Run::set_warmup(run_id, is_warmup)? == 0 {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn get_run(run_id: i64) -> Result<Option<Run>, String> {
-    Run::get_by_id(run_id).map_err(|e| e.to_string())
+pub async fn update_run_character(run_id: i64, character_name: String, class: Option<String>) -> AppResult<()> {
+    Run::update_character(run_id, &character_name, class.as_deref()).map_err(AppError::from)
 }
 
+/// Override a run's category. Detection leaves it alone from then on. Finished
+/// runs keep theirs, since their PB and golds are filed under it.
 #[tauri::command]
-pub async fn delete_run(run_id: i64) -> Result<(), String> {
-    Run::delete(run_id).map_err(|e| e.to_string())
+pub async fn set_run_category(run_id: i64, category: String) -> AppResult<()> {
+    let category = category.trim();
+    if category.is_empty() {
+        return Err(AppError::InvalidInput("Category can't be empty".to_string()));
+    }
+    let run = Run::get_by_id(run_id)?.ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    if run.is_completed && !run.is_reference {
+        return Err(AppError::InvalidInput("A finished run's category can't be changed".to_string()));
+    }
+    Run::set_category(run_id, category, "manual")?;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn get_runs_filtered(filters: RunFilters) -> Result<Vec<Run>, String> {
-    Run::get_filtered(&filters).map_err(|e| e.to_string())
+pub async fn get_run_review(run_id: i64) -> AppResult<RunReview> {
+    let run = Run::get_by_id(run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    Ok(RunReview::from(&run))
 }
 
+/// Attach a VOD link, timer-start offset, and review notes to a run
 #[tauri::command]
-pub async fn get_run_stats(filters: RunFilters) -> Result<RunStats, String> {
-    Run::get_stats(&filters).map_err(|e| e.to_string())
+pub async fn set_run_review(run_id: i64, review: RunReview) -> AppResult<()> {
+    let review = normalize_review(review)?;
+    if Run::update_review(run_id, &review)? == 0 {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    Ok(())
 }
 
-#[tauri::command]
-pub async fn get_split_stats(filters: RunFilters) -> Result<Vec<SplitStat>, String> {
-    Split::get_stats(&filters).map_err(|e| e.to_string())
+fn normalize_review(review: RunReview) -> AppResult<RunReview> {
+    let non_empty = |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+
+    let video_url = non_empty(review.video_url);
+    if let Some(url) = &video_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err(AppError::InvalidInput(format!("Video URL must start with http(s)://: {}", url)));
+        }
+    }
+    if review.vod_offset_ms.is_some_and(|ms| ms < 0) {
+        return Err(AppError::InvalidInput("VOD offset cannot be negative".to_string()));
+    }
+
+    Ok(RunReview {
+        video_url,
+        vod_offset_ms: review.vod_offset_ms,
+        review_notes: non_empty(review.review_notes),
+    })
 }
 
 #[tauri::command]
-pub async fn create_reference_run(data: ReferenceRunData) -> Result<i64, String> {
-    // Insert the reference run
-    let run_id = Run::insert_reference(&data).map_err(|e| e.to_string())?;
+pub async fn complete_run(app_handle: AppHandle, run_id: i64, total_time_ms: i64) -> AppResult<bool> {
+    let is_pb = finish_run(run_id, total_time_ms)?;
+    // Exports and announcements are best-effort: the run is already saved
+    auto_export::run_completed(run_id, is_pb);
+    if let Err(e) = twitch::run_completed(run_id, is_pb) {
+        eprintln!("[twitch] Failed to announce run {}: {}", run_id, e);
+    }
+    race_lobby::run_finished(total_time_ms);
 
-    // Insert all splits for the reference run
-    let mut prev_time = 0i64;
-    for split_data in &data.splits {
-        let segment_time = split_data.split_time_ms - prev_time;
-        let new_split = NewSplit {
+    // Capture the final build so every completed run has a shareable snapshot
+    if let Some(target) = final_snapshot_target(run_id)? {
+        let _ = app_handle.emit("snapshot-capturing", serde_json::json!({
+            "split_id": target.split_id,
+            "is_final": true,
+        }));
+
+        queue_snapshot(&app_handle, SnapshotRequest {
             run_id,
-            breakpoint_type: split_data.breakpoint_type.clone(),
-            breakpoint_name: split_data.breakpoint_name.clone(),
-            split_time_ms: split_data.split_time_ms,
-            delta_ms: None,
-            segment_time_ms: segment_time,
-            town_time_ms: 0,
-            hideout_time_ms: 0,
-        };
-        Split::insert(&new_split).map_err(|e| e.to_string())?;
-        prev_time = split_data.split_time_ms;
+            split_id: Some(target.split_id),
+            elapsed_time_ms: total_time_ms,
+            account_name: target.account_name,
+            character_name: target.character_name,
+            is_final: true,
+        });
     }
 
-    Ok(run_id)
+    Ok(is_pb)
 }
 
-// ============================================================================
-// Split Commands
-// ============================================================================
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AddSplitRequest {
-    pub split: NewSplit,
-    pub capture_snapshot: bool,
-    pub account_name: Option<String>,
-    pub character_name: Option<String>,
+/// Where and for whom to capture a completed run's final build snapshot
+#[derive(Debug, PartialEq)]
+pub(crate) struct FinalSnapshotTarget {
+    pub split_id: i64,
+    pub account_name: String,
+    pub character_name: String,
 }
 
-#[tauri::command]
-pub async fn add_split(
-    app_handle: AppHandle,
-    request: AddSplitRequest,
-) -> Result<i64, String> {
-    let split = request.split;
-    let split_id = Split::insert(&split).map_err(|e| e.to_string())?;
+/// Resolve the final snapshot for a run: attached to its last split, using the
+/// run's character and account (falling back to the account in settings).
+/// Returns `None` if the run already has one or there is nothing to capture.
+pub(crate) fn final_snapshot_target(run_id: i64) -> AppResult<Option<FinalSnapshotTarget>> {
+    if Snapshot::get_final(run_id)?.is_some() {
+        return Ok(None);
+    }
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Ok(None);
+    };
+    let Some(last_split) = Split::get_by_run(run_id)?.pop() else {
+        return Ok(None);
+    };
+    if run.character_name.is_empty() || run.character_name == "Unknown" {
+        return Ok(None);
+    }
 
-    // Check if this is a gold split
-    let run = Run::get_by_id(split.run_id).map_err(|e| e.to_string())?;
-    if let Some(ref run) = run {
-        let category = format!("{}", run.category);
-        let _ = GoldSplit::update_if_better(&category, &run.class, &split.breakpoint_name, split.segment_time_ms);
+    let account_name = if run.account_name.is_empty() {
+        Settings::load()?.account_name
+    } else {
+        run.account_name
+    };
+    if account_name.is_empty() {
+        return Ok(None);
     }
 
-    // Capture snapshot if requested
-    if request.capture_snapshot {
-        if let (Some(account_name), Some(character_name), Some(run)) =
-            (request.account_name, request.character_name, run)
-        {
-            let handle = app_handle.clone();
-            let run_id = run.id;
-            let elapsed_time_ms = split.split_time_ms;
+    Ok(Some(FinalSnapshotTarget {
+        split_id: last_split.id,
+        account_name,
+        character_name: run.character_name,
+    }))
+}
 
-            // Emit capturing event
-            let _ = handle.emit("snapshot-capturing", serde_json::json!({
-                "split_id": split_id,
-                "breakpoint_name": split.breakpoint_name,
-            }));
+/// Mark a run as completed and return whether it set a new personal best.
+///
+/// The all-time PB and the PB for the run's major patch are both kept up to
+/// date; the return value follows `separate_pbs_by_patch`.
+pub(crate) fn finish_run(run_id: i64, total_time_ms: i64) -> AppResult<bool> {
+    Run::complete(run_id, total_time_ms)?;
+    ZoneTime::close(run_id, total_time_ms)?;
 
-            // Spawn async task to capture snapshot
-            tokio::spawn(async move {
-                capture_snapshot_for_split(
-                    handle,
-                    run_id,
-                    split_id,
-                    elapsed_time_ms,
-                    account_name,
-                    character_name,
-                ).await;
-            });
-        }
+    // Check if this is a new personal best
+    if let Ok(Some(run)) = Run::get_by_id(run_id) {
+        let is_all_time_pb = PersonalBest::get_or_create(&run, "", total_time_ms)?;
+        let patch = run.game_version.as_deref().and_then(PersonalBest::major_patch);
+        let Some(patch) = patch else {
+            return Ok(is_all_time_pb);
+        };
+        let is_patch_pb = PersonalBest::get_or_create(&run, &patch, total_time_ms)?;
+        return Ok(if Settings::load()?.separate_pbs_by_patch { is_patch_pb } else { is_all_time_pb });
     }
 
-    Ok(split_id)
+    Ok(false)
 }
 
+/// Mark an unfinished run as reset `abandoned_at_ms` into it, at the last
+/// split it reached, and why when the user picked a reason
 #[tauri::command]
-pub async fn get_splits(run_id: i64) -> Result<Vec<Split>, String> {
-    Split::get_by_run(run_id).map_err(|e| e.to_string())
+pub async fn abandon_run(run_id: i64, reason: Option<AbandonReason>, abandoned_at_ms: i64) -> AppResult<()> {
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    };
+    if run.is_completed {
+        return Err(AppError::InvalidInput("A finished run can't be abandoned".to_string()));
+    }
+    Run::abandon(run_id, reason.map(AbandonReason::as_str), abandoned_at_ms.max(0))?;
+    ZoneTime::close(run_id, abandoned_at_ms.max(0))?;
+    Ok(())
+}
+
+/// Game version reported by the running log watcher, if it has seen one
+fn detected_game_version() -> Option<String> {
+    let guard = get_log_watcher().lock().ok()?;
+    guard.as_ref().and_then(|watcher| watcher.game_version())
 }
 
 #[tauri::command]
-pub async fn manual_split() -> Result<(), String> {
-    // This is a placeholder - the actual split logic is handled by the frontend
-    // when it receives breakpoint triggers from the log watcher
-    Ok(())
+pub async fn get_runs() -> AppResult<Vec<Run>> {
+    Run::get_all().map_err(AppError::from)
 }
 
-// ============================================================================
-// Snapshot Commands
-// ============================================================================
+#[tauri::command]
+pub async fn get_run(run_id: i64) -> AppResult<Option<Run>> {
+    Run::get_by_id(run_id).map_err(AppError::from)
+}
 
-/// Map ascendancy class ID to ascendancy name
-fn get_ascendancy_name(class: &str, ascendancy_class: u32) -> Option<String> {
-    // ascendancy_class 0 means no ascendancy
-    if ascendancy_class == 0 {
-        return None;
-    }
+/// Delete a run, cancelling its queued and in-flight snapshot captures first
+#[tauri::command]
+pub async fn delete_run(app_handle: AppHandle, run_id: i64) -> AppResult<()> {
+    cancel_snapshots(&app_handle, run_id);
+    remove_run(run_id)
+}
 
-    // Mapping based on PoE class/ascendancy structure
-    // Each class has 3 ascendancies (1, 2, 3)
-    let ascendancies: &[&str] = match class {
-        "Scion" => &["Ascendant"],
-        "Marauder" => &["Juggernaut", "Berserker", "Chieftain"],
-        "Ranger" => &["Warden", "Deadeye", "Pathfinder"], // Warden replaced Raider
-        "Witch" => &["Necromancer", "Elementalist", "Occultist"],
-        "Duelist" => &["Slayer", "Gladiator", "Champion"],
-        "Templar" => &["Inquisitor", "Hierophant", "Guardian"],
-        "Shadow" => &["Assassin", "Saboteur", "Trickster"],
-        _ => return None,
+/// Delete a run; if it held golds, its category's golds are rebuilt from the
+/// runs that remain
+pub(crate) fn remove_run(run_id: i64) -> AppResult<()> {
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Ok(());
     };
+    let held_gold = GoldSplit::held_by_run(run_id)?;
+    Run::delete(run_id)?;
+    if held_gold {
+        GoldSplit::recompute(&run.category)?;
+    }
+    Ok(())
+}
 
-    let index = (ascendancy_class as usize).saturating_sub(1);
-    ascendancies.get(index).map(|s| s.to_string())
+#[tauri::command]
+pub async fn get_runs_filtered(filters: RunFilters) -> AppResult<Vec<Run>> {
+    Run::get_filtered(&filters).map_err(AppError::from)
 }
 
-/// Async function to capture a snapshot for a split
-async fn capture_snapshot_for_split(
-    app_handle: AppHandle,
-    run_id: i64,
-    split_id: i64,
-    elapsed_time_ms: i64,
-    account_name: String,
-    character_name: String,
-) {
-    let client = get_api_client();
+/// `limit` runs matching the filters from `offset`, newest first, with the total matching
+#[tauri::command]
+pub async fn get_runs_paged(offset: i64, limit: i64, filters: RunFilters) -> AppResult<RunPage> {
+    if limit <= 0 {
+        return Err(AppError::InvalidInput("Page size must be positive".to_string()));
+    }
+    Run::get_paged(&filters, offset, limit).map_err(AppError::from)
+}
 
-    // Fetch items
-    let items_result = client.get_items(&account_name, &character_name).await;
-    let (items_json, character_level, char_class, ascendancy_class, league) = match items_result {
-        Ok(data) => {
-            let items_json = serde_json::to_string(&data.items).unwrap_or_else(|_| "[]".to_string());
-            (
-                items_json,
-                data.character.level as i32,
-                data.character.class.clone(),
-                data.character.ascendancy_class,
-                data.character.league.clone(),
-            )
-        }
-        Err(e) => {
-            let _ = app_handle.emit("snapshot-failed", serde_json::json!({
-                "split_id": split_id,
-                "error": e.to_string(),
-            }));
-            return;
-        }
-    };
+#[tauri::command]
+pub async fn get_run_stats(filters: RunFilters) -> AppResult<RunStats> {
+    Run::get_stats(&filters).map_err(AppError::from)
+}
 
-    // Update run's class/ascendancy if we got valid data from API
-    if !char_class.is_empty() && char_class != "Unknown" {
-        let ascendancy_name = get_ascendancy_name(&char_class, ascendancy_class);
-        let league_opt = if league.is_empty() { None } else { Some(league.as_str()) };
-        let _ = Run::update_class_info(run_id, &char_class, ascendancy_name.as_deref(), league_opt);
-    }
+/// How many of the filtered runs were abandoned, and for what reasons
+#[tauri::command]
+pub async fn get_abandonment_stats(filters: RunFilters) -> AppResult<AbandonmentStats> {
+    Ok(abandon::stats(&Run::get_filtered(&filters)?))
+}
 
-    // Fetch passive skills
-    let passives_result = client.get_passive_skills(&account_name, &character_name).await;
-    let passive_tree_json = match passives_result {
-        Ok(data) => serde_json::to_string(&data).unwrap_or_else(|_| "{}".to_string()),
-        Err(e) => {
-            let _ = app_handle.emit("snapshot-failed", serde_json::json!({
-                "split_id": split_id,
-                "error": e.to_string(),
-            }));
-            return;
-        }
-    };
+/// Where the filtered runs were reset: per last split reached and per act
+#[tauri::command]
+pub async fn get_reset_stats(filters: RunFilters) -> AppResult<ResetStats> {
+    Ok(abandon::reset_stats(&Run::get_filtered(&filters)?))
+}
 
-    // Extract skills from socketed items (gems)
-    let skills_json = "[]".to_string(); // Skills are in socketed_items within items_json
+#[tauri::command]
+pub async fn get_split_stats(filters: RunFilters) -> AppResult<Vec<SplitStat>> {
+    Split::get_stats(&filters).map_err(AppError::from)
+}
 
-    // Create snapshot record
-    let snapshot = NewSnapshot {
-        run_id,
-        split_id,
-        timestamp: chrono::Utc::now().to_rfc3339(),
-        elapsed_time_ms,
-        character_level,
-        items_json,
-        skills_json,
-        passive_tree_json,
-        stats_json: "{}".to_string(),
-        pob_code: None,
-    };
+/// Runs plotted by `get_split_trends`, and how many each rolling average covers
+const TREND_RUNS: i64 = 30;
+const TREND_WINDOW: i64 = 5;
 
-    match Snapshot::insert(&snapshot) {
-        Ok(snapshot_id) => {
-            let _ = app_handle.emit("snapshot-complete", serde_json::json!({
-                "split_id": split_id,
-                "snapshot_id": snapshot_id,
-                "character_level": character_level,
-            }));
-        }
-        Err(e) => {
-            let _ = app_handle.emit("snapshot-failed", serde_json::json!({
-                "split_id": split_id,
-                "error": e.to_string(),
-            }));
-        }
+/// Whether a breakpoint's time is improving: its split time over the latest
+/// filtered runs that reached it, oldest first, with a rolling average
+#[tauri::command]
+pub async fn get_split_trends(filters: RunFilters, breakpoint_name: String) -> AppResult<Vec<SplitTrendPoint>> {
+    if breakpoint_name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Breakpoint name is required".to_string()));
     }
+    Split::get_trends(&filters, &breakpoint_name, TREND_RUNS, TREND_WINDOW).map_err(AppError::from)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CaptureSnapshotRequest {
-    pub run_id: i64,
-    pub split_id: i64,
-    pub elapsed_time_ms: i64,
-    pub account_name: String,
-    pub character_name: String,
+/// Time lost against gold splits for the filtered runs, broken down by mistake type
+#[tauri::command]
+pub async fn get_time_loss_report(filters: RunFilters) -> AppResult<TimeLossReport> {
+    TimeLossReport::build(&filters).map_err(AppError::from)
 }
 
-/// Manual snapshot capture command (for retry)
+/// Act reached and mandatory campaign objectives (skill points, bandits, lab) for a run
 #[tauri::command]
-pub async fn capture_snapshot(
-    app_handle: AppHandle,
-    request: CaptureSnapshotRequest,
-) -> Result<(), String> {
-    // Emit capturing event
-    let _ = app_handle.emit("snapshot-capturing", serde_json::json!({
-        "split_id": request.split_id,
-    }));
-
-    let handle = app_handle.clone();
-    tokio::spawn(async move {
-        capture_snapshot_for_split(
-            handle,
-            request.run_id,
-            request.split_id,
-            request.elapsed_time_ms,
-            request.account_name,
-            request.character_name,
-        ).await;
-    });
+pub async fn get_quest_state(run_id: i64) -> AppResult<QuestState> {
+    let progress = QuestProgress::load(run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    Ok(QuestState::build(run_id, progress.act, &progress.completed))
+}
 
-    Ok(())
+/// Import a leveling guide (an Exile Leveling route or the plain steps format),
+/// replacing the current one; the run being timed starts it from the top
+#[tauri::command]
+pub async fn import_guide(path: String) -> AppResult<Guide> {
+    let contents = std::fs::read_to_string(&path)?;
+    let stem = Path::new(&path).file_stem().and_then(|s| s.to_str()).unwrap_or("Leveling guide");
+    let guide = guide::parse(&contents, stem)?;
+    let steps_json = serde_json::to_string(&guide.steps).map_err(|e| AppError::Internal(e.to_string()))?;
+    LevelingGuide::save(&guide.name, &steps_json)?;
+    guide::reload()?;
+    Ok(guide)
 }
 
 #[tauri::command]
-pub async fn create_snapshot(snapshot: NewSnapshot) -> Result<i64, String> {
-    Snapshot::insert(&snapshot).map_err(|e| e.to_string())
+pub async fn get_guide() -> AppResult<Option<Guide>> {
+    guide::load()
 }
 
 #[tauri::command]
-pub async fn get_snapshots(run_id: i64) -> Result<Vec<Snapshot>, String> {
-    Snapshot::get_by_run(run_id).map_err(|e| e.to_string())
+pub async fn clear_guide() -> AppResult<()> {
+    LevelingGuide::clear()?;
+    guide::reload()
 }
 
+/// The guide step a run is on, and the one after it
 #[tauri::command]
-pub async fn get_snapshot(snapshot_id: i64) -> Result<Option<Snapshot>, String> {
-    Snapshot::get_by_id(snapshot_id).map_err(|e| e.to_string())
+pub async fn get_current_guide_step(run_id: i64) -> AppResult<GuideProgress> {
+    guide::progress(run_id)
 }
 
-// ============================================================================
-// Personal Best Commands
-// ============================================================================
+/// Skip ahead or go back in the guide; `guide-step-changed` carries the new step
+#[tauri::command]
+pub async fn set_guide_step(app_handle: AppHandle, run_id: i64, step_index: usize) -> AppResult<GuideProgress> {
+    let progress = guide::set_step(run_id, step_index)?;
+    let _ = app_handle.emit("guide-step-changed", &progress);
+    Ok(progress)
+}
 
+/// Every tracked campaign objective, for "after quest" breakpoint trigger rules
 #[tauri::command]
-pub async fn get_personal_bests() -> Result<Vec<PersonalBest>, String> {
-    PersonalBest::get_all().map_err(|e| e.to_string())
+pub async fn get_quest_objectives() -> AppResult<Vec<ObjectiveStatus>> {
+    Ok(ObjectiveStatus::list(&[]))
 }
 
-// ============================================================================
-// Gold Split Commands
-// ============================================================================
+/// Average town/hideout time per act for the filtered runs
+#[tauri::command]
+pub async fn get_town_time_report(filters: RunFilters) -> AppResult<Vec<ActTownTime>> {
+    ActTownTime::build(&filters).map_err(AppError::from)
+}
 
+/// Average time per zone over the filtered runs, slowest first
 #[tauri::command]
-pub async fn get_gold_splits() -> Result<Vec<GoldSplit>, String> {
-    GoldSplit::get_all().map_err(|e| e.to_string())
+pub async fn get_zone_time_stats(filters: RunFilters) -> AppResult<Vec<ZoneTimeStat>> {
+    ZoneTimeStat::build(&filters).map_err(AppError::from)
 }
 
-// ============================================================================
-// API Commands
-// ============================================================================
+/// Total and average time and deaths per zone over the filtered runs, costliest
+/// zones first
+#[tauri::command]
+pub async fn get_zone_heatmap(filters: RunFilters) -> AppResult<Vec<ZoneHeatmapEntry>> {
+    ZoneHeatmapEntry::build(&filters).map_err(AppError::from)
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CharacterListResponse {
-    pub characters: Vec<crate::api_client::PoeCharacter>,
+/// Zone entries, level-ups, deaths, pauses, splits and snapshots of a run in
+/// run-clock order
+#[tauri::command]
+pub async fn get_run_timeline(run_id: i64) -> AppResult<Vec<TimelineEntry>> {
+    if Run::get_by_id(run_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    let events = RunEvent::get_by_run(run_id)?;
+    let splits = Split::get_by_run(run_id)?;
+    let snapshots = Snapshot::get_summaries(run_id)?;
+    Ok(run_timeline::build(&events, &splits, &snapshots))
 }
 
+/// How often a run entered each zone, and the backtracks into zones already played
 #[tauri::command]
-pub async fn fetch_characters(account_name: String) -> Result<CharacterListResponse, String> {
+pub async fn get_backtrack_report(run_id: i64) -> AppResult<BacktrackReport> {
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    };
+    let events = RunEvent::get_by_run(run_id)?;
+    Ok(backtrack::build(run_id, &events, run.total_time_ms))
+}
+
+/// A run's level now and at each breakpoint against its PB's, its XP rate,
+/// and its level on entering each zone
+#[tauri::command]
+pub async fn get_level_pace(run_id: i64) -> AppResult<LevelPace> {
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    };
+    let events = RunEvent::get_by_run(run_id)?;
+    let splits = Split::get_by_run(run_id)?;
+
+    // Now on the run clock while it's live, else where the run ended
+    let elapsed_ms = breakpoint_engine::elapsed_for(run_id)
+        .or(run.total_time_ms)
+        .or_else(|| {
+            let last_event = events.last().map(|e| e.elapsed_ms);
+            let last_split = splits.last().map(|s| s.split_time_ms);
+            last_event.max(last_split)
+        })
+        .unwrap_or(0);
+
+    let pb = current_personal_best(&run)?.filter(|pb| pb.run_id != run.id);
+    let pb_data = match &pb {
+        Some(pb) => Some((pb.run_id, RunEvent::get_by_run(pb.run_id)?, Split::get_by_run(pb.run_id)?)),
+        None => None,
+    };
+    let pb_ref = pb_data
+        .as_ref()
+        .map(|(pb_run_id, events, splits)| (*pb_run_id, events.as_slice(), splits.as_slice()));
+    Ok(level_pace::build(run_id, elapsed_ms, &events, &splits, pb_ref))
+}
+
+/// A run's character level and experience at each snapshot that recorded it,
+/// for charting XP progression against other runs
+#[tauri::command]
+pub async fn get_experience_curve(run_id: i64) -> AppResult<Vec<ExperiencePoint>> {
+    if Run::get_by_id(run_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    Ok(level_pace::experience_curve(&Snapshot::get_summaries(run_id)?))
+}
+
+/// A run's deaths in run-clock order
+#[tauri::command]
+pub async fn get_deaths(run_id: i64) -> AppResult<Vec<Death>> {
+    if Run::get_by_id(run_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    Death::get_by_run(run_id).map_err(AppError::from)
+}
+
+/// Every zone visit of a run, and the zones played between each pair of splits
+#[tauri::command]
+pub async fn get_zone_times(run_id: i64) -> AppResult<ZoneTimeReport> {
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    };
+    let visits = ZoneTime::get_by_run(run_id)?;
+    let splits = Split::get_by_run(run_id)?;
+    let segments = zone_time::by_segment(&visits, &splits, run.total_time_ms);
+    Ok(ZoneTimeReport { run_id, visits, segments })
+}
+
+/// A run's splits against its PB, the golds, its recent average, or another run
+#[tauri::command]
+pub async fn get_comparison(run_id: i64, comparison_kind: ComparisonKind) -> AppResult<Vec<ComparisonRow>> {
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    };
+    let splits = Split::get_by_run(run_id)?;
+    let golds = splits
+        .iter()
+        .map(|s| GoldSplit::get_best(&run.category, &run.class, &s.breakpoint_name, s.breakpoint_key.as_deref()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let points = match comparison_kind {
+        ComparisonKind::PersonalBest => match current_personal_best(&run)? {
+            Some(pb) => comparison::from_run(&Split::get_by_run(pb.run_id)?),
+            None => Vec::new(),
+        },
+        ComparisonKind::Gold => comparison::from_golds(&splits, &golds),
+        ComparisonKind::Average { count } => {
+            if count == 0 {
+                return Err(AppError::InvalidInput("Average needs at least one run".to_string()));
+            }
+            let recent = Run::get_filtered(&RunFilters {
+                category: Some(run.category.clone()),
+                class: Some(run.class.clone()),
+                is_completed: Some(true),
+                ..Default::default()
+            })?;
+            let recent_splits = recent
+                .iter()
+                .filter(|r| r.id != run_id)
+                .take(count)
+                .map(|r| Split::get_by_run(r.id))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            comparison::average(&recent_splits)
+        }
+        ComparisonKind::Reference { run_id: other_id } => {
+            if Run::get_by_id(other_id)?.is_none() {
+                return Err(AppError::NotFound(format!("Run {}", other_id)));
+            }
+            comparison::from_run(&Split::get_by_run(other_id)?)
+        }
+    };
+
+    Ok(comparison::rows(&splits, &points, &golds))
+}
+
+#[tauri::command]
+pub async fn create_reference_run(data: ReferenceRunData) -> AppResult<i64> {
+    insert_reference_run(&data)
+}
+
+/// Parse a pasted split table so the UI can preview it before saving
+#[tauri::command]
+pub async fn parse_reference_splits(text: String) -> AppResult<Vec<ReferenceSplitData>> {
+    reference_import::parse_split_text(&text)
+}
+
+/// Create a reference run from a pasted split table (tab/comma separated or a
+/// LiveSplit text export). `data` supplies the run metadata; its splits and
+/// total time are replaced by the parsed table.
+#[tauri::command]
+pub async fn create_reference_run_from_text(
+    text: String,
+    mut data: ReferenceRunData,
+) -> AppResult<i64> {
+    data.splits = reference_import::parse_split_text(&text)?;
+    data.total_time_ms = data.splits.last().map(|s| s.split_time_ms).unwrap_or(0);
+    insert_reference_run(&data)
+}
+
+/// Parse a split file exported by another tool (LiveSplit `.lss`, Exilence Next
+/// or tracker JSON, a Path of Building build, or a text table) so the UI can
+/// preview it before saving
+#[tauri::command]
+pub async fn parse_reference_file(path: String) -> AppResult<Vec<ReferenceSplitData>> {
+    let contents = std::fs::read_to_string(&path)?;
+    reference_import::parse_import_file(Path::new(&path), &contents)
+}
+
+/// Create a reference run from a split file exported by another tool. `data`
+/// supplies the run metadata; its splits and total time come from the file.
+#[tauri::command]
+pub async fn create_reference_run_from_file(
+    path: String,
+    mut data: ReferenceRunData,
+) -> AppResult<i64> {
+    let contents = std::fs::read_to_string(&path)?;
+    data.splits = reference_import::parse_import_file(Path::new(&path), &contents)?;
+    data.total_time_ms = data.splits.last().map(|s| s.split_time_ms).unwrap_or(0);
+    insert_reference_run(&data)
+}
+
+/// A league's ladder, optionally one class. Each fetch records the characters'
+/// levels, which `create_reference_run_from_ladder` times milestones from.
+#[tauri::command]
+pub async fn fetch_ladder(league: String, class: Option<String>) -> AppResult<Vec<LadderRacer>> {
+    let entries = get_api_client().fetch_ladder(&league, class.as_deref()).await?;
+    ladder::record(&league, &entries, &chrono::Utc::now().to_rfc3339())?;
+    ladder::racers(&league, &entries)
+}
+
+/// Create a reference run from a ladder character's level milestones, timed
+/// from the league start by the fetch that first saw each one reached. `data`
+/// supplies the run metadata; its splits and total time are replaced.
+#[tauri::command]
+pub async fn create_reference_run_from_ladder(
+    league: String,
+    character_name: String,
+    data: ReferenceRunData,
+) -> AppResult<i64> {
+    let start = get_api_client()
+        .fetch_league_start(&league)
+        .await?
+        .ok_or_else(|| AppError::InvalidInput(format!("League {} has no start time to time milestones from", league)))?;
+    insert_reference_run(&ladder::reference_run(&character_name, &league, &start, data)?)
+}
+
+/// Top characters of a league's poe.ninja build overview, optionally one class
+#[tauri::command]
+pub async fn fetch_ninja_builds(league: String, class: Option<String>) -> AppResult<Vec<NinjaBuild>> {
+    Ok(PoeNinjaClient::new().fetch_builds(&league, class.as_deref()).await?)
+}
+
+/// What `create_reference_snapshot_from_ninja` created
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NinjaReference {
+    pub run_id: i64,
+    pub snapshot_id: i64,
+}
+
+/// Save a poe.ninja character's gear and passives as the snapshot of a new
+/// reference run, for `diff_snapshots` against a run's own snapshots
+#[tauri::command]
+pub async fn create_reference_snapshot_from_ninja(
+    league: String,
+    account_name: String,
+    character_name: String,
+) -> AppResult<NinjaReference> {
+    let character = PoeNinjaClient::new().fetch_character(&league, &account_name, &character_name).await?;
+    insert_ninja_reference(&character)
+}
+
+/// Reference run and snapshot of a poe.ninja character, analyzed the way a
+/// captured snapshot is
+pub(crate) fn insert_ninja_reference(character: &NinjaCharacter) -> AppResult<NinjaReference> {
+    let run_id = insert_reference_run(&character.reference_run())?;
+    let split_id = Split::get_by_run(run_id)?
+        .first()
+        .map(|split| split.id)
+        .ok_or_else(|| AppError::Internal(format!("Reference run {} has no split", run_id)))?;
+
+    let items = character.items();
+    let passives = character.passives();
+    let analysis = snapshot_analysis::analyze(&items, &passives);
+    let snapshot_id = Snapshot::insert(&NewSnapshot {
+        run_id,
+        split_id: Some(split_id),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        elapsed_time_ms: 0,
+        character_level: character.level as i32,
+        experience: None,
+        items_json: serde_json::to_string(&items)?,
+        skills_json: analysis.skills_json(),
+        passive_tree_json: serde_json::to_string(&passives)?,
+        stats_json: analysis.stats_json(),
+        pob_code: character.pob_code(),
+    })?;
+    Ok(NinjaReference { run_id, snapshot_id })
+}
+
+/// What `import_livesplit` created
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSplitImport {
+    /// Reference run holding the file's Personal Best
+    pub run_id: i64,
+    /// Best Segments that beat (or filled in) a gold split
+    pub golds_imported: usize,
+}
+
+/// Import a LiveSplit `.lss` file: its Personal Best becomes a reference run in
+/// the file's category, and its Best Segments are merged into the gold splits
+/// when the file names a class (as files exported by `export_run_livesplit` do)
+#[tauri::command]
+pub async fn import_livesplit(path: String) -> AppResult<LiveSplitImport> {
+    let contents = std::fs::read_to_string(&path)?;
+    let lss = livesplit::parse(&contents)?;
+    let category = lss.category.clone().unwrap_or_else(|| "Imported".to_string());
+    let class = lss.class.clone().unwrap_or_default();
+
+    let splits = reference_import::parse_livesplit_lss(&contents)?;
+    let stem = Path::new(&path).file_stem().and_then(|s| s.to_str()).unwrap_or("splits");
+    let run_id = insert_reference_run(&ReferenceRunData {
+        source_name: format!("LiveSplit ({})", stem),
+        character_name: None,
+        class: class.clone(),
+        ascendancy: None,
+        category: category.clone(),
+        league: None,
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        total_time_ms: splits.last().map(|s| s.split_time_ms).unwrap_or(0),
+        splits,
+    })?;
+
+    let mut golds_imported = 0;
+    if !class.is_empty() {
+        for segment in &lss.segments {
+            if let Some(best) = segment.best_segment_ms.filter(|ms| *ms > 0) {
+                if GoldSplit::update_if_better(&category, &class, &segment.name, None, best)?.is_some() {
+                    golds_imported += 1;
+                }
+            }
+        }
+    }
+
+    Ok(LiveSplitImport { run_id, golds_imported })
+}
+
+fn insert_reference_run(data: &ReferenceRunData) -> AppResult<i64> {
+    // Insert the reference run
+    let run_id = Run::insert_reference(data)?;
+
+    // Insert all splits for the reference run
+    let mut prev_time = 0i64;
+    for split_data in &data.splits {
+        let segment_time = split_data.split_time_ms - prev_time;
+        let new_split = NewSplit {
+            run_id,
+            breakpoint_type: split_data.breakpoint_type.clone(),
+            breakpoint_name: split_data.breakpoint_name.clone(),
+            breakpoint_key: split_data.breakpoint_key.clone(),
+            split_time_ms: split_data.split_time_ms,
+            delta_ms: None,
+            segment_time_ms: segment_time,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+        };
+        Split::insert(&new_split)?;
+        prev_time = split_data.split_time_ms;
+    }
+
+    Ok(run_id)
+}
+
+/// Breakpoints a log import splits on, as `start_breakpoint_engine` takes them:
+/// the enabled built-in ones, or a custom preset's
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogImportPreset {
+    pub name: String,
+    pub category: String,
+    #[serde(default)]
+    pub breakpoints: Vec<EngineBreakpoint>,
+    pub preset_id: Option<i64>,
+}
+
+/// Local log time as the UTC timestamp runs are stored with
+fn log_time_utc(time: chrono::NaiveDateTime) -> String {
+    use chrono::TimeZone;
+    chrono::Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|local| local.with_timezone(&chrono::Utc))
+        .unwrap_or_else(|| time.and_utc())
+        .to_rfc3339()
+}
+
+/// Replay the part of an existing Client.txt between `start_time` and
+/// `end_time` (local, as logged) through the breakpoint engine and store it as
+/// a run, for a race the app wasn't running for. A run that reached every
+/// breakpoint is completed (PBs and golds included); otherwise it's kept as
+/// reset at its last split.
+#[tauri::command]
+pub async fn import_run_from_log(
+    log_path: String,
+    start_time: String,
+    end_time: String,
+    preset: LogImportPreset,
+) -> AppResult<i64> {
+    let parse = |text: &str| {
+        log_backfill::parse_time(text).ok_or_else(|| AppError::InvalidInput(format!("Unrecognized time: {}", text)))
+    };
+    let (start, end) = (parse(&start_time)?, parse(&end_time)?);
+    if end <= start {
+        return Err(AppError::InvalidInput("The end time must be after the start time".to_string()));
+    }
+    if preset.category.trim().is_empty() {
+        return Err(AppError::InvalidInput("Category is required".to_string()));
+    }
+    let breakpoints = match preset.preset_id {
+        Some(preset_id) => {
+            preset_exists(preset_id)?;
+            PresetBreakpoint::get_by_preset(preset_id)?.iter().map(EngineBreakpoint::from).collect()
+        }
+        None => preset.breakpoints,
+    };
+    if breakpoints.is_empty() {
+        return Err(AppError::InvalidInput("The preset has no breakpoints to split on".to_string()));
+    }
+    let names: Vec<&str> = breakpoints.iter().map(|bp| bp.name.as_str()).collect();
+    let enabled_breakpoints = serde_json::to_string(&names).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let file = std::fs::File::open(resolve_path_case(Path::new(&log_path)))?;
+    let backfill = log_backfill::backfill(std::io::BufReader::new(file), Game::current(), start, end, breakpoints)?;
+    if backfill.splits.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "No breakpoints were reached between {} and {}",
+            start_time, end_time
+        )));
+    }
+
+    let (character_name, class, ascendancy) = match &backfill.character {
+        Some((name, logged_class)) => match pob_builder::parse_class(logged_class) {
+            Some((class, ascendancy)) => (name.clone(), class.to_string(), ascendancy.map(str::to_string)),
+            None => (name.clone(), "Unknown".to_string(), None),
+        },
+        None => ("Unknown".to_string(), "Unknown".to_string(), None),
+    };
+    let run_id = Run::insert(&NewRun {
+        character_name,
+        account_name: Settings::load()?.account_name,
+        class: class.clone(),
+        ascendancy,
+        league: String::new(),
+        category: preset.category.clone(),
+        started_at: log_time_utc(start),
+        breakpoint_preset: Some(preset.name),
+        enabled_breakpoints: Some(enabled_breakpoints),
+        game_version: backfill.game_version.clone(),
+    })?;
+
+    let mut previous_ms = 0;
+    for split in &backfill.splits {
+        let segment_time_ms = split.split_time_ms - previous_ms;
+        let split_id = Split::insert(&NewSplit {
+            run_id,
+            breakpoint_type: split.breakpoint_type.clone(),
+            breakpoint_name: split.breakpoint_name.clone(),
+            breakpoint_key: split.breakpoint_key.clone(),
+            split_time_ms: split.split_time_ms,
+            delta_ms: None,
+            segment_time_ms,
+            town_time_ms: split.town_time_ms,
+            hideout_time_ms: split.hideout_time_ms,
+        })?;
+        if split.load_time_ms > 0 {
+            Split::set_load_time(split_id, split.load_time_ms)?;
+        }
+        if segment_time_ms > 0 && class != "Unknown" {
+            GoldSplit::update_if_better(
+                &preset.category,
+                &class,
+                &split.breakpoint_name,
+                split.breakpoint_key.as_deref(),
+                segment_time_ms,
+            )?;
+        }
+        previous_ms = split.split_time_ms;
+    }
+    if backfill.load_time_ms > 0 {
+        Run::add_load_time(run_id, backfill.load_time_ms)?;
+    }
+
+    let ended_at = log_time_utc(start + chrono::Duration::milliseconds(backfill.total_time_ms));
+    if backfill.finished {
+        finish_run(run_id, backfill.total_time_ms)?;
+    } else {
+        Run::abandon(run_id, None, backfill.total_time_ms)?;
+    }
+    Run::set_ended_at(run_id, &ended_at)?;
+    Ok(run_id)
+}
+
+// ============================================================================
+// Split Commands
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddSplitRequest {
+    pub split: NewSplit,
+    pub capture_snapshot: bool,
+    pub account_name: Option<String>,
+    pub character_name: Option<String>,
+}
+
+/// What `add_split` recorded
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddedSplit {
+    pub split_id: i64,
+    pub comparison: SplitComparison,
+}
+
+#[tauri::command]
+pub async fn add_split(
+    app_handle: AppHandle,
+    request: AddSplitRequest,
+) -> AppResult<AddedSplit> {
+    split_and_capture(&app_handle, request)
+}
+
+/// Record a split, announce it, and queue its snapshot. Shared by `add_split`
+/// and the breakpoint engine's log-driven splits.
+pub(crate) fn split_and_capture(app_handle: &AppHandle, request: AddSplitRequest) -> AppResult<AddedSplit> {
+    // Compare before recording, while the golds are still the ones to beat
+    let comparison = compare_split(&request.split)?;
+    let split = NewSplit {
+        delta_ms: comparison.delta_ms,
+        ..request.split
+    };
+    let (split_id, run, is_gold) = record_split(&split)?;
+    race_lobby::split(&split);
+
+    // Announce the split if it clears the user's thresholds
+    if let Some(announcement) =
+        announcements::evaluate(&Settings::load()?, &split.breakpoint_name, split.delta_ms, is_gold)
+    {
+        let _ = app_handle.emit("split-announcement", announcement);
+    }
+    if is_gold {
+        if let Some(run) = &run {
+            webhook::gold_split(run, &split);
+            twitch::gold_split(run, &split);
+        }
+    }
+
+    // Capture snapshot if requested
+    if request.capture_snapshot {
+        if let (Some(account_name), Some(character_name), Some(run)) =
+            (request.account_name, request.character_name, run)
+        {
+            // Emit capturing event
+            let _ = app_handle.emit("snapshot-capturing", serde_json::json!({
+                "split_id": split_id,
+                "breakpoint_name": split.breakpoint_name,
+            }));
+
+            queue_snapshot(app_handle, SnapshotRequest {
+                run_id: run.id,
+                split_id: Some(split_id),
+                elapsed_time_ms: split.split_time_ms,
+                account_name,
+                character_name,
+                is_final: false,
+            });
+        }
+    }
+
+    Ok(AddedSplit { split_id, comparison })
+}
+
+/// Compare a split about to be recorded with the run's category/class/league PB (the
+/// one `get_personal_bests` shows) and gold, and with the run's last delta
+pub(crate) fn compare_split(split: &NewSplit) -> AppResult<SplitComparison> {
+    let Some(run) = Run::get_by_id(split.run_id)? else {
+        return Ok(SplitComparison::neutral());
+    };
+
+    let pb = current_personal_best(&run)?.filter(|pb| pb.run_id != run.id);
+    let pb_split_ms = match pb {
+        Some(pb) => Split::get_by_run(pb.run_id)?
+            .into_iter()
+            .find(|s| s.breakpoint_name == split.breakpoint_name)
+            .map(|s| s.split_time_ms),
+        None => None,
+    };
+    let last_delta_ms = Split::get_by_run(run.id)?.iter().rev().find_map(|s| s.delta_ms);
+    let gold_ms = if spans_skip(split)? {
+        None
+    } else {
+        GoldSplit::get_best(&run.category, &run.class, &split.breakpoint_name, split.breakpoint_key.as_deref())?
+    };
+
+    Ok(comparison::compare(
+        split.split_time_ms.max(0),
+        split.segment_time_ms.max(0),
+        pb_split_ms,
+        last_delta_ms,
+        gold_ms,
+    ))
+}
+
+/// Whether a split's segment runs over a skipped breakpoint. It then covers
+/// more than its own breakpoint, so it can't be compared with or set a gold.
+fn spans_skip(split: &NewSplit) -> AppResult<bool> {
+    let segment_start_ms = split.split_time_ms - split.segment_time_ms;
+    Ok(SkippedSplit::get_by_run(split.run_id)?
+        .iter()
+        .any(|skipped| skipped.elapsed_ms >= segment_start_ms && skipped.elapsed_ms <= split.split_time_ms))
+}
+
+/// Insert a split and update the gold split for its run's category/class.
+/// Also returns whether the segment beat an existing gold.
+pub(crate) fn record_split(split: &NewSplit) -> AppResult<(i64, Option<Run>, bool)> {
+    // Splits taken before a start countdown ran out count as time zero
+    let split = &NewSplit {
+        split_time_ms: split.split_time_ms.max(0),
+        segment_time_ms: split.segment_time_ms.max(0),
+        ..split.clone()
+    };
+
+    // Town/hideout time comes from the backend's zone clock while it is tracking this run
+    let split_id = match zone_time::totals_for_run(split.run_id) {
+        Some((town_time_ms, hideout_time_ms)) => Split::insert(&NewSplit {
+            town_time_ms,
+            hideout_time_ms,
+            ..split.clone()
+        })?,
+        None => Split::insert(split)?,
+    };
+
+    // Loading screens so far, from the run's load clock
+    let run = Run::get_by_id(split.run_id)?;
+    if let Some(run) = run.as_ref().filter(|run| run.load_time_ms > 0) {
+        Split::set_load_time(split_id, run.load_time_ms)?;
+    }
+
+    // Level from the run's last level-up; a snapshot replaces it with the API's
+    let events = RunEvent::get_by_run(split.run_id)?;
+    if let Some(level) = level_pace::level_at(&level_pace::samples(&events, &[]), split.split_time_ms) {
+        Split::set_character_level(split_id, level)?;
+    }
+
+    // Check if this is a gold split
+    let mut is_gold = false;
+    // An empty segment (e.g. split during the countdown) would be an unbeatable gold
+    let eligible = split.segment_time_ms > 0 && !spans_skip(split)?;
+    if let Some(run) = run.as_ref().filter(|_| eligible) {
+        // Infer the layout from how this segment compares to earlier attempts
+        if split.breakpoint_type != "custom" && !run.is_reference {
+            let history = Split::segment_history(
+                &run.category,
+                &run.class,
+                &split.breakpoint_name,
+                split.breakpoint_key.as_deref(),
+                run.id,
+            )?;
+            if let Some(variant) = classify_layout(split.segment_time_ms, &history) {
+                Split::set_layout(split_id, Some(variant), true)?;
+            }
+        }
+
+        let previous = GoldSplit::get_best(
+            &run.category,
+            &run.class,
+            &split.breakpoint_name,
+            split.breakpoint_key.as_deref(),
+        )?;
+        is_gold = previous.is_some_and(|best| split.segment_time_ms < best);
+
+        // Remember the change so undoing this split can revert it
+        if let Ok(Some(change)) = GoldSplit::update_if_better(
+            &run.category,
+            &run.class,
+            &split.breakpoint_name,
+            split.breakpoint_key.as_deref(),
+            split.segment_time_ms,
+        ) {
+            ProvisionalGold::record(run.id, split_id, &change, chrono::Utc::now().timestamp_millis())?;
+        }
+    }
+
+    Ok((split_id, run, is_gold))
+}
+
+/// Start of the gold protection window: golds set after this are provisional
+fn gold_protection_since_ms() -> AppResult<i64> {
+    let window_ms = Settings::load()?.gold_protection_secs.max(0) * 1000;
+    Ok(chrono::Utc::now().timestamp_millis() - window_ms)
+}
+
+/// Remove a run's most recent split (e.g. a mis-split), or its most recent
+/// skip if that came after it. Golds the split set within the gold protection
+/// window are reverted. Returns the number of golds reverted.
+#[tauri::command]
+pub async fn undo_last_split(run_id: i64) -> AppResult<usize> {
+    let split = Split::get_last(run_id)?;
+    if let Some(skipped) = SkippedSplit::get_by_run(run_id)?.pop() {
+        if split.as_ref().is_none_or(|s| skipped.elapsed_ms >= s.split_time_ms) {
+            SkippedSplit::delete(skipped.id)?;
+            return Ok(0);
+        }
+    }
+
+    let split = split.ok_or_else(|| AppError::NotFound(format!("Splits for run {}", run_id)))?;
+    let reverted = ProvisionalGold::revert(run_id, Some(split.id), gold_protection_since_ms()?)?;
+    Split::delete(split.id)?;
+    Ok(reverted)
+}
+
+/// Pass over a breakpoint the run won't split (missed, or taken out of order).
+/// Log-triggered and manual splits move on to the next breakpoint, and the
+/// run's next split, whose segment includes the skipped one, can't set a gold.
+/// `undo_last_split` takes the skip back.
+#[tauri::command]
+pub async fn skip_split(run_id: i64, breakpoint_name: String) -> AppResult<SkippedSplit> {
+    if Run::get_by_id(run_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    let done = Split::get_by_run(run_id)?.iter().any(|s| s.breakpoint_name == breakpoint_name)
+        || SkippedSplit::get_by_run(run_id)?.iter().any(|s| s.breakpoint_name == breakpoint_name);
+    if done {
+        return Err(AppError::InvalidInput(format!("{} is already split or skipped", breakpoint_name)));
+    }
+
+    // The engine's clock while it follows the run, else the time of the last split
+    let elapsed_ms = match breakpoint_engine::elapsed_for(run_id) {
+        Some(elapsed_ms) => elapsed_ms,
+        None => Split::get_last(run_id)?.map_or(0, |s| s.split_time_ms),
+    };
+    SkippedSplit::insert(run_id, &breakpoint_name, elapsed_ms).map_err(AppError::from)
+}
+
+/// Revert golds a run set within the gold protection window, called when the
+/// timer is reset. Returns the number of golds reverted.
+#[tauri::command]
+pub async fn revert_provisional_golds(run_id: i64) -> AppResult<usize> {
+    ProvisionalGold::revert(run_id, None, gold_protection_since_ms()?).map_err(AppError::from)
+}
+
+/// Start recording log-triggered splits for a run. `breakpoints` are the run's
+/// enabled breakpoints in order, unless `preset_id` names a breakpoint preset
+/// to run instead; `elapsed_ms` is the timer's current value.
+#[tauri::command]
+pub async fn start_breakpoint_engine(
+    run_id: i64,
+    breakpoints: Vec<EngineBreakpoint>,
+    preset_id: Option<i64>,
+    elapsed_ms: i64,
+    fallback_character_name: Option<String>,
+) -> AppResult<()> {
+    if Run::get_by_id(run_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    let breakpoints = match preset_id {
+        Some(preset_id) => {
+            preset_exists(preset_id)?;
+            PresetBreakpoint::get_by_preset(preset_id)?.iter().map(EngineBreakpoint::from).collect()
+        }
+        None => breakpoints,
+    };
+    breakpoint_engine::start_run(run_id, breakpoints, elapsed_ms, fallback_character_name);
+    Ok(())
+}
+
+/// Pause or resume the breakpoint engine's timer along with the frontend's
+#[tauri::command]
+pub async fn set_breakpoint_engine_running(running: bool, elapsed_ms: i64) -> AppResult<()> {
+    breakpoint_engine::set_running(running, elapsed_ms)
+}
+
+/// Pause the timer of the run being timed; `run-paused` carries the run clock
+#[tauri::command]
+pub async fn pause_run(app_handle: AppHandle, run_id: i64) -> AppResult<()> {
+    if let Some(changed) = pause::pause(run_id, PauseReason::Manual)? {
+        let _ = app_handle.emit(changed.event_name(), &changed);
+    }
+    Ok(())
+}
+
+/// Resume the timer of the run being timed; `run-resumed` carries the run clock
+#[tauri::command]
+pub async fn resume_run(app_handle: AppHandle, run_id: i64) -> AppResult<()> {
+    if let Some(changed) = pause::resume(run_id)? {
+        let _ = app_handle.emit(changed.event_name(), &changed);
+    }
+    Ok(())
+}
+
+/// Act on the run's last `character-mismatch`: pause the run, or with `rebind`
+/// move it to the character that leveled up (taking its class from the log).
+/// Returns the run as it now stands.
+#[tauri::command]
+pub async fn reassign_run_character(app_handle: AppHandle, run_id: i64, rebind: bool) -> AppResult<Run> {
+    if Run::get_by_id(run_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    let mismatch = breakpoint_engine::take_mismatch(run_id)
+        .ok_or_else(|| AppError::InvalidInput(format!("Run {} has no character mismatch", run_id)))?;
+
+    if rebind {
+        Run::update_character(run_id, &mismatch.character_name, None)?;
+        if let Some((class, ascendancy)) = pob_builder::parse_class(&mismatch.character_class) {
+            Run::set_class(run_id, class, ascendancy)?;
+        }
+    } else if let Some(changed) = pause::pause(run_id, PauseReason::CharacterMismatch)? {
+        let _ = app_handle.emit(changed.event_name(), &changed);
+    }
+
+    Run::get_by_id(run_id)?.ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))
+}
+
+/// Pauses of a run, oldest first
+#[tauri::command]
+pub async fn get_pauses(run_id: i64) -> AppResult<Vec<Pause>> {
+    Pause::get_by_run(run_id).map_err(AppError::from)
+}
+
+/// Stop log-triggered splits (run ended or reset)
+#[tauri::command]
+pub async fn stop_breakpoint_engine() -> AppResult<()> {
+    breakpoint_engine::stop();
+    Ok(())
+}
+
+/// Minimum earlier attempts at a location before a layout is inferred
+const LAYOUT_MIN_SAMPLES: usize = 3;
+/// How far from the median a segment must be to count as a fast/slow layout
+const LAYOUT_THRESHOLD: f64 = 0.10;
+
+/// Classify a segment as a "fast", "typical", or "slow" layout relative to the
+/// median of earlier segments at the same location
+pub(crate) fn classify_layout(segment_ms: i64, history: &[i64]) -> Option<&'static str> {
+    if history.len() < LAYOUT_MIN_SAMPLES {
+        return None;
+    }
+    let mut sorted = history.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    let median = if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
+    } else {
+        sorted[mid] as f64
+    };
+
+    let ratio = segment_ms as f64 / median;
+    Some(if ratio < 1.0 - LAYOUT_THRESHOLD {
+        "fast"
+    } else if ratio > 1.0 + LAYOUT_THRESHOLD {
+        "slow"
+    } else {
+        "typical"
+    })
+}
+
+/// Tag the layout variant seen at a split (e.g. from a hotkey), replacing any
+/// inferred value. `None` clears the tag.
+#[tauri::command]
+pub async fn set_split_layout(split_id: i64, layout_variant: Option<String>) -> AppResult<()> {
+    let variant = layout_variant
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if Split::set_layout(split_id, variant.as_deref(), false)? == 0 {
+        return Err(AppError::NotFound(format!("Split {}", split_id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_splits(run_id: i64) -> AppResult<Vec<Split>> {
+    Split::get_by_run(run_id).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn manual_split() -> AppResult<()> {
+    // This is a placeholder - the actual split logic is handled by the frontend
+    // when it receives breakpoint triggers from the log watcher
+    Ok(())
+}
+
+// ============================================================================
+// Split Note Commands
+// ============================================================================
+
+/// Attach a review note to a split, optionally tagged with a mistake type
+/// (normalized to snake_case, e.g. "Bad Layout" -> "bad_layout")
+#[tauri::command]
+pub async fn add_split_note(note: NewSplitNote) -> AppResult<i64> {
+    let mistake_type = note
+        .mistake_type
+        .map(|t| t.split_whitespace().collect::<Vec<_>>().join("_").to_lowercase())
+        .filter(|t| !t.is_empty());
+    let text = note.note.trim().to_string();
+    if text.is_empty() && mistake_type.is_none() {
+        return Err(AppError::InvalidInput("Note or mistake type is required".to_string()));
+    }
+
+    SplitNote::insert(&NewSplitNote {
+        split_id: note.split_id,
+        note: text,
+        mistake_type,
+    })
+    .map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_split_notes(run_id: i64) -> AppResult<Vec<SplitNote>> {
+    SplitNote::get_by_run(run_id).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn delete_split_note(note_id: i64) -> AppResult<()> {
+    if SplitNote::delete(note_id)? == 0 {
+        return Err(AppError::NotFound(format!("Split note {}", note_id)));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Run Note and Tag Commands
+// ============================================================================
+
+/// Set or (when empty) clear the user's note on a run
+#[tauri::command]
+pub async fn set_run_note(run_id: i64, note: Option<String>) -> AppResult<()> {
+    let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    if Run::set_notes(run_id, note.as_deref())? == 0 {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    Ok(())
+}
+
+/// Tag a run, or one of its splits when `split_id` is given. Tags are
+/// lowercased with whitespace collapsed; adding one twice is a no-op.
+#[tauri::command]
+pub async fn add_run_tag(run_id: i64, tag: String, split_id: Option<i64>) -> AppResult<RunTag> {
+    let tag = RunTag::normalize(&tag);
+    if tag.is_empty() {
+        return Err(AppError::InvalidInput("Tag is required".to_string()));
+    }
+    if Run::get_by_id(run_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    if let Some(split_id) = split_id {
+        if !Split::get_by_run(run_id)?.iter().any(|s| s.id == split_id) {
+            return Err(AppError::NotFound(format!("Split {} of run {}", split_id, run_id)));
+        }
+    }
+    RunTag::add(run_id, split_id, &tag).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn remove_run_tag(tag_id: i64) -> AppResult<()> {
+    if RunTag::delete(tag_id)? == 0 {
+        return Err(AppError::NotFound(format!("Run tag {}", tag_id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_run_tags(run_id: i64) -> AppResult<Vec<RunTag>> {
+    RunTag::get_by_run(run_id).map_err(AppError::from)
+}
+
+/// Every tag in use, for the History tag filter
+#[tauri::command]
+pub async fn get_run_tag_names() -> AppResult<Vec<String>> {
+    RunTag::names().map_err(AppError::from)
+}
+
+/// Runs tagged with `tag` on the run or one of its splits, reference runs
+/// included, newest first
+#[tauri::command]
+pub async fn get_runs_by_tag(tag: String) -> AppResult<Vec<Run>> {
+    Run::get_filtered(&RunFilters {
+        tag: Some(tag),
+        include_reference: Some(true),
+        ..RunFilters::default()
+    })
+    .map_err(AppError::from)
+}
+
+// ============================================================================
+// Breakpoint Preset Commands
+// ============================================================================
+
+const BREAKPOINT_TYPES: &[&str] = &["zone", "level", "boss", "act", "lab", "custom"];
+
+fn validate_preset_name(name: &str, preset_id: Option<i64>) -> AppResult<()> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Preset name is empty".to_string()));
+    }
+    if BreakpointPreset::get_by_name(name.trim())?.is_some_and(|preset| Some(preset.id) != preset_id) {
+        return Err(AppError::InvalidInput(format!("A preset named \"{}\" already exists", name.trim())));
+    }
+    Ok(())
+}
+
+/// Check a breakpoint's trigger can fire, and that its name is unique in the
+/// preset: the engine tells split breakpoints apart by name
+pub(crate) fn validate_preset_breakpoint(
+    preset_id: i64,
+    breakpoint: &NewPresetBreakpoint,
+    breakpoint_id: Option<i64>,
+) -> AppResult<()> {
+    let invalid = |message: String| Err(AppError::InvalidInput(message));
+    let is_objective = |id: &str| quest_state::OBJECTIVES.iter().any(|objective| objective.id == id);
+    let name = breakpoint.name.trim();
+    if name.is_empty() {
+        return invalid("Breakpoint name is empty".to_string());
+    }
+    if !BREAKPOINT_TYPES.contains(&breakpoint.breakpoint_type.as_str()) {
+        return invalid(format!("Unknown breakpoint type: {}", breakpoint.breakpoint_type));
+    }
+    match breakpoint.trigger_type.as_str() {
+        "zone" => {
+            if breakpoint.zone_name.as_deref().is_none_or(|zone| zone.trim().is_empty()) {
+                return invalid(format!("{} needs a zone to enter", name));
+            }
+        }
+        "level" => {
+            if !breakpoint.level.is_some_and(|level| (2..=100).contains(&level)) {
+                return invalid(format!("{} needs a level from 2 to 100", name));
+            }
+        }
+        "quest" => {
+            if !breakpoint.quest.as_deref().is_some_and(is_objective) {
+                return invalid(format!("{} needs a quest objective", name));
+            }
+        }
+        "kitava" => {
+            if !matches!(breakpoint.penalty, Some(30 | 60)) {
+                return invalid(format!("{} needs Kitava's Act 5 (30) or Act 10 (60) resistance penalty", name));
+            }
+        }
+        other => return invalid(format!("Unknown trigger type: {}", other)),
+    }
+    if breakpoint.entry.is_some() && breakpoint.trigger_type != "zone" {
+        return invalid(format!("{}: only zone breakpoints can split on a given entry", name));
+    }
+    if breakpoint.entry == Some(0) {
+        return invalid(format!("{}: entries count from 1", name));
+    }
+    if let Some(quest) = breakpoint.after_quest.as_deref().filter(|quest| !is_objective(quest)) {
+        return invalid(format!("Unknown quest objective: {}", quest));
+    }
+
+    let taken = PresetBreakpoint::get_by_preset(preset_id)?
+        .iter()
+        .any(|other| Some(other.id) != breakpoint_id && other.breakpoint.name.eq_ignore_ascii_case(name));
+    if taken {
+        return invalid(format!("The preset already has a breakpoint named \"{}\"", name));
+    }
+    Ok(())
+}
+
+fn preset_exists(preset_id: i64) -> AppResult<()> {
+    match BreakpointPreset::get(preset_id)? {
+        Some(_) => Ok(()),
+        None => Err(AppError::NotFound(format!("Breakpoint preset {}", preset_id))),
+    }
+}
+
+/// Trim the breakpoint's text and drop fields its trigger doesn't use. A
+/// Kitava breakpoint's act follows from its penalty.
+fn normalize_preset_breakpoint(breakpoint: NewPresetBreakpoint) -> NewPresetBreakpoint {
+    let text = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let trigger = breakpoint.trigger_type.as_str();
+    let penalty = breakpoint.penalty.filter(|_| trigger == "kitava");
+    NewPresetBreakpoint {
+        name: breakpoint.name.trim().to_string(),
+        zone_name: text(breakpoint.zone_name).filter(|_| trigger == "zone"),
+        act: match penalty {
+            Some(30) => Some(5),
+            Some(60) => Some(10),
+            _ => breakpoint.act,
+        },
+        level: breakpoint.level.filter(|_| trigger == "level"),
+        quest: text(breakpoint.quest).filter(|_| trigger == "quest"),
+        penalty,
+        after_quest: text(breakpoint.after_quest),
+        ..breakpoint
+    }
+}
+
+#[tauri::command]
+pub async fn get_breakpoint_presets() -> AppResult<Vec<BreakpointPreset>> {
+    Ok(BreakpointPreset::get_all()?)
+}
+
+/// Create an empty preset, for the current game unless `game` says otherwise
+#[tauri::command]
+pub async fn create_breakpoint_preset(name: String, game: Option<String>) -> AppResult<BreakpointPreset> {
+    validate_preset_name(&name, None)?;
+    let game = match game {
+        Some(game) => game,
+        None => Settings::load()?.game,
+    };
+    if game != "poe1" && game != "poe2" {
+        return Err(AppError::InvalidInput(format!("Unknown game: {}", game)));
+    }
+    Ok(BreakpointPreset::create(name.trim(), &game)?)
+}
+
+#[tauri::command]
+pub async fn rename_breakpoint_preset(preset_id: i64, name: String) -> AppResult<()> {
+    validate_preset_name(&name, Some(preset_id))?;
+    if BreakpointPreset::rename(preset_id, name.trim())? == 0 {
+        return Err(AppError::NotFound(format!("Breakpoint preset {}", preset_id)));
+    }
+    Ok(())
+}
+
+/// Delete a preset and its breakpoints. Runs keep the preset name they were recorded with.
+#[tauri::command]
+pub async fn delete_breakpoint_preset(preset_id: i64) -> AppResult<()> {
+    if BreakpointPreset::delete(preset_id)? == 0 {
+        return Err(AppError::NotFound(format!("Breakpoint preset {}", preset_id)));
+    }
+    Ok(())
+}
+
+/// A preset's breakpoints, in split order
+#[tauri::command]
+pub async fn get_preset_breakpoints(preset_id: i64) -> AppResult<Vec<PresetBreakpoint>> {
+    preset_exists(preset_id)?;
+    Ok(PresetBreakpoint::get_by_preset(preset_id)?)
+}
+
+/// Add a breakpoint at the end of a preset
+#[tauri::command]
+pub async fn add_breakpoint(preset_id: i64, breakpoint: NewPresetBreakpoint) -> AppResult<PresetBreakpoint> {
+    preset_exists(preset_id)?;
+    let breakpoint = normalize_preset_breakpoint(breakpoint);
+    validate_preset_breakpoint(preset_id, &breakpoint, None)?;
+    let id = PresetBreakpoint::insert(preset_id, &breakpoint)?;
+    PresetBreakpoint::get_by_preset(preset_id)?
+        .into_iter()
+        .find(|bp| bp.id == id)
+        .ok_or_else(|| AppError::Internal(format!("Breakpoint {} not found after insert", id)))
+}
+
+#[tauri::command]
+pub async fn update_breakpoint(preset_id: i64, breakpoint_id: i64, breakpoint: NewPresetBreakpoint) -> AppResult<()> {
+    let breakpoint = normalize_preset_breakpoint(breakpoint);
+    if !PresetBreakpoint::get_by_preset(preset_id)?.iter().any(|bp| bp.id == breakpoint_id) {
+        return Err(AppError::NotFound(format!("Breakpoint {} in preset {}", breakpoint_id, preset_id)));
+    }
+    validate_preset_breakpoint(preset_id, &breakpoint, Some(breakpoint_id))?;
+    PresetBreakpoint::update(breakpoint_id, &breakpoint)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_breakpoint(breakpoint_id: i64) -> AppResult<()> {
+    if PresetBreakpoint::delete(breakpoint_id)? == 0 {
+        return Err(AppError::NotFound(format!("Breakpoint {}", breakpoint_id)));
+    }
+    Ok(())
+}
+
+/// Put a preset's breakpoints in the order of `breakpoint_ids`, which lists each of them once
+#[tauri::command]
+pub async fn reorder_breakpoints(preset_id: i64, breakpoint_ids: Vec<i64>) -> AppResult<()> {
+    preset_exists(preset_id)?;
+    let mut current: Vec<i64> = PresetBreakpoint::get_by_preset(preset_id)?.iter().map(|bp| bp.id).collect();
+    let mut requested = breakpoint_ids.clone();
+    current.sort_unstable();
+    requested.sort_unstable();
+    if current != requested {
+        return Err(AppError::InvalidInput(
+            "The new order must list each of the preset's breakpoints once".to_string(),
+        ));
+    }
+    Ok(PresetBreakpoint::reorder(preset_id, &breakpoint_ids)?)
+}
+
+// ============================================================================
+// Snapshot Commands
+// ============================================================================
+
+/// Map the API's class ID to the base class name. Once a character ascends the
+/// API reports the ascendancy as its `class`, but the class ID stays the base class.
+pub(crate) fn get_base_class_name(class_id: u32) -> Option<&'static str> {
+    match class_id {
+        0 => Some("Scion"),
+        1 => Some("Marauder"),
+        2 => Some("Ranger"),
+        3 => Some("Witch"),
+        4 => Some("Duelist"),
+        5 => Some("Templar"),
+        6 => Some("Shadow"),
+        _ => None,
+    }
+}
+
+/// Map ascendancy class ID to ascendancy name
+pub(crate) fn get_ascendancy_name(class: &str, ascendancy_class: u32) -> Option<String> {
+    // ascendancy_class 0 means no ascendancy
+    if ascendancy_class == 0 {
+        return None;
+    }
+
+    // Mapping based on PoE class/ascendancy structure
+    // Each class has 3 ascendancies (1, 2, 3)
+    let ascendancies: &[&str] = match class {
+        "Scion" => &["Ascendant"],
+        "Marauder" => &["Juggernaut", "Berserker", "Chieftain"],
+        "Ranger" => &["Warden", "Deadeye", "Pathfinder"], // Warden replaced Raider
+        "Witch" => &["Necromancer", "Elementalist", "Occultist"],
+        "Duelist" => &["Slayer", "Gladiator", "Champion"],
+        "Templar" => &["Inquisitor", "Hierophant", "Guardian"],
+        "Shadow" => &["Assassin", "Saboteur", "Trickster"],
+        _ => return None,
+    };
+
+    let index = (ascendancy_class as usize).saturating_sub(1);
+    ascendancies.get(index).map(|s| s.to_string())
+}
+
+/// Queue a snapshot capture and start the queue if it is idle. Waiting captures
+/// the new one supersedes are reported with `snapshot-skipped` so they can be retried.
+fn queue_snapshot(app_handle: &AppHandle, request: SnapshotRequest) {
+    let split_id = request.split_id;
+    let superseded = match SNAPSHOT_QUEUE.lock() {
+        Ok(mut queue) => queue.push(request),
+        Err(_) => return,
+    };
+    for skipped in superseded {
+        let _ = app_handle.emit("snapshot-skipped", serde_json::json!({
+            "split_id": skipped.split_id,
+            "superseded_by": split_id,
+        }));
+    }
+    start_next_snapshot(app_handle);
+}
+
+/// Queue the snapshot of a level milestone the breakpoint engine saw. It
+/// belongs to no split, so `snapshot-capturing` carries the level instead.
+pub(crate) fn capture_level_snapshot(app_handle: &AppHandle, request: SnapshotRequest, level: u32) {
+    let _ = app_handle.emit("snapshot-capturing", serde_json::json!({
+        "split_id": null,
+        "level": level,
+    }));
+    queue_snapshot(app_handle, request);
+}
+
+/// Capture the newest queued snapshot unless one is in flight, then move on to the next
+fn start_next_snapshot(app_handle: &AppHandle) {
+    let (next, state) = match SNAPSHOT_QUEUE.lock() {
+        Ok(mut queue) => (queue.start_next(), queue.state()),
+        Err(_) => return,
+    };
+    let _ = app_handle.emit("snapshot-queue", &state);
+
+    if let Some(request) = next {
+        let handle = app_handle.clone();
+        // Splits from the log watcher's thread queue captures outside the runtime
+        tauri::async_runtime::spawn(async move {
+            let split_id = request.split_id;
+            let capture = tokio::spawn(capture_snapshot_for_split(handle.clone(), request));
+            let abort = capture.abort_handle();
+            if let Ok(mut task) = SNAPSHOT_TASK.lock() {
+                *task = Some(abort.clone());
+            }
+
+            match tokio::time::timeout(SNAPSHOT_DEADLINE, capture).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) if e.is_cancelled() => {
+                    let _ = handle.emit("snapshot-cancelled", serde_json::json!({ "split_id": split_id }));
+                }
+                Ok(Err(e)) => emit_snapshot_failed(&handle, split_id, &AppError::Internal(e.to_string())),
+                Err(_) => {
+                    abort.abort();
+                    let error = AppError::Timeout(format!("Snapshot capture ({}s)", SNAPSHOT_DEADLINE.as_secs()));
+                    emit_snapshot_failed(&handle, split_id, &error);
+                }
+            }
+
+            if let Ok(mut task) = SNAPSHOT_TASK.lock() {
+                *task = None;
+            }
+            if let Ok(mut queue) = SNAPSHOT_QUEUE.lock() {
+                queue.finish();
+            }
+            start_next_snapshot(&handle);
+        });
+    }
+}
+
+/// Drop a run's waiting captures and abort its capture in flight
+fn cancel_snapshots(app_handle: &AppHandle, run_id: i64) {
+    let (cancelled, in_flight, state) = match SNAPSHOT_QUEUE.lock() {
+        Ok(mut queue) => (queue.cancel_run(run_id), queue.capturing_run() == Some(run_id), queue.state()),
+        Err(_) => return,
+    };
+    for request in cancelled {
+        let _ = app_handle.emit("snapshot-cancelled", serde_json::json!({ "split_id": request.split_id }));
+    }
+    if in_flight {
+        if let Some(task) = SNAPSHOT_TASK.lock().ok().and_then(|task| task.clone()) {
+            task.abort();
+        }
+    }
+    let _ = app_handle.emit("snapshot-queue", &state);
+}
+
+fn emit_snapshot_failed(app_handle: &AppHandle, split_id: Option<i64>, error: &AppError) {
+    let _ = app_handle.emit("snapshot-failed", serde_json::json!({
+        "split_id": split_id,
+        "error": error.to_string(),
+        "code": error.code(),
+    }));
+}
+
+/// Captures in flight and waiting, with their state
+#[tauri::command]
+pub async fn get_pending_snapshots() -> AppResult<Vec<PendingSnapshot>> {
+    SNAPSHOT_QUEUE
+        .lock()
+        .map(|queue| queue.pending())
+        .map_err(|_| AppError::Internal("Snapshot queue unavailable".to_string()))
+}
+
+/// Async function to capture a snapshot for a split
+async fn capture_snapshot_for_split(app_handle: AppHandle, request: SnapshotRequest) {
+    let SnapshotRequest {
+        run_id,
+        split_id,
+        elapsed_time_ms,
+        account_name,
+        character_name,
+        is_final,
+    } = request;
+    let settings = Settings::load().unwrap_or_default();
+    let result = if settings.record_api_responses {
+        let (client, responses) = get_api_client().recording();
+        let result = take_snapshot(&client, run_id, split_id, elapsed_time_ms, &account_name, &character_name).await;
+        let parsed = match &result {
+            Ok((snapshot_id, _)) => Snapshot::get_by_id(*snapshot_id).ok().flatten().map(|s| diagnostics::parsed_snapshot(&s)),
+            Err(_) => None,
+        };
+        diagnostics::record(
+            SnapshotRecording {
+                recorded_at: chrono::Utc::now().to_rfc3339(),
+                run_id,
+                split_id,
+                account_name: account_name.clone(),
+                character_name: character_name.clone(),
+                responses: responses.lock().map(|r| r.clone()).unwrap_or_default(),
+                parsed,
+                error: result.as_ref().err().map(|e| e.to_string()),
+            },
+            settings.api_recording_limit,
+        );
+        result
+    } else {
+        take_snapshot(get_api_client(), run_id, split_id, elapsed_time_ms, &account_name, &character_name).await
+    };
+
+    let result = result.and_then(|(snapshot_id, character_level)| {
+        if is_final {
+            Snapshot::set_final(snapshot_id)?;
+        }
+        Ok((snapshot_id, character_level))
+    });
+
+    match result {
+        Ok((snapshot_id, character_level)) => {
+            let _ = app_handle.emit("snapshot-complete", serde_json::json!({
+                "split_id": split_id,
+                "snapshot_id": snapshot_id,
+                "character_level": character_level,
+                "is_final": is_final,
+            }));
+        }
+        Err(e) => emit_snapshot_failed(&app_handle, split_id, &e),
+    }
+}
+
+/// Fetch the character from `api` and store it as a snapshot for the split,
+/// or a level milestone when there is none. Returns the new snapshot ID and
+/// the character level.
+pub(crate) async fn take_snapshot(
+    api: &impl PoeApi,
+    run_id: i64,
+    split_id: Option<i64>,
+    elapsed_time_ms: i64,
+    account_name: &str,
+    character_name: &str,
+) -> AppResult<(i64, i32)> {
+    // Fetch items
+    let data = api.get_items(account_name, character_name).await?;
+    let items_json = serde_json::to_string(&data.items).unwrap_or_else(|_| "[]".to_string());
+    let character_level = data.character.level as i32;
+    let experience = data.character.experience as i64;
+    let league = data.character.league;
+
+    // Runs, PBs and class filters key on the base class, and the ascendancy is
+    // derived from it below, so prefer the class ID over the reported class
+    let char_class = get_base_class_name(data.character.class_id)
+        .map(|c| c.to_string())
+        .unwrap_or(data.character.class);
+
+    // Update run's class/ascendancy if we got valid data from API
+    if !char_class.is_empty() && char_class != "Unknown" {
+        let ascendancy_name = get_ascendancy_name(&char_class, data.character.ascendancy_class);
+        let league_opt = if league.is_empty() { None } else { Some(league.as_str()) };
+        let _ = Run::update_class_info(run_id, &char_class, ascendancy_name.as_deref(), league_opt);
+    }
+    // The API's league is authoritative over the one the run was started with
+    let _ = tag_ruleset(run_id, &league);
+
+    // Fetch passive skills
+    let passives = api.get_passive_skills(account_name, character_name).await?;
+    let passive_tree_json = serde_json::to_string(&passives).unwrap_or_else(|_| "{}".to_string());
+
+    // Gems and build numbers; reprocess_snapshots re-derives these for old snapshots
+    let analysis = snapshot_analysis::analyze(&data.items, &passives);
+
+    // Create snapshot record
+    let snapshot = NewSnapshot {
+        run_id,
+        split_id,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        elapsed_time_ms,
+        character_level,
+        // Missing from the response when the API omits it; don't record that as zero
+        experience: (experience > 0).then_some(experience),
+        items_json,
+        skills_json: analysis.skills_json(),
+        passive_tree_json,
+        stats_json: analysis.stats_json(),
+        pob_code: None,
+    };
+
+    let snapshot_id = Snapshot::insert(&snapshot)?;
+    if let Some(split_id) = split_id {
+        if experience > 0 {
+            Split::set_experience(split_id, experience)?;
+        }
+        if character_level > 0 {
+            Split::set_character_level(split_id, character_level)?;
+        }
+    }
+    Ok((snapshot_id, character_level))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureSnapshotRequest {
+    pub run_id: i64,
+    pub split_id: i64,
+    pub elapsed_time_ms: i64,
+    pub account_name: String,
+    pub character_name: String,
+}
+
+/// Manual snapshot capture command (for retry)
+#[tauri::command]
+pub async fn capture_snapshot(
+    app_handle: AppHandle,
+    request: CaptureSnapshotRequest,
+) -> AppResult<()> {
+    // Emit capturing event
+    let _ = app_handle.emit("snapshot-capturing", serde_json::json!({
+        "split_id": request.split_id,
+    }));
+
+    queue_snapshot(&app_handle, SnapshotRequest {
+        run_id: request.run_id,
+        split_id: Some(request.split_id),
+        elapsed_time_ms: request.elapsed_time_ms,
+        account_name: request.account_name,
+        character_name: request.character_name,
+        is_final: false,
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn create_snapshot(snapshot: NewSnapshot) -> AppResult<i64> {
+    Snapshot::insert(&snapshot).map_err(AppError::from)
+}
+
+/// Store the PoB code generated by the frontend for a snapshot
+#[tauri::command]
+pub async fn set_snapshot_pob_code(snapshot_id: i64, pob_code: String) -> AppResult<()> {
+    Snapshot::set_pob_code(snapshot_id, &pob_code).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_snapshots(run_id: i64) -> AppResult<Vec<Snapshot>> {
+    Snapshot::get_by_run(run_id).map_err(AppError::from)
+}
+
+/// A run's snapshots without their JSON; `get_snapshot` loads one in full
+#[tauri::command]
+pub async fn get_snapshot_summaries(run_id: i64) -> AppResult<Vec<SnapshotSummary>> {
+    Snapshot::get_summaries(run_id).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub async fn get_snapshot(snapshot_id: i64) -> AppResult<Option<Snapshot>> {
+    Snapshot::get_by_id(snapshot_id).map_err(AppError::from)
+}
+
+/// What changed from `snapshot_a` to `snapshot_b`: gear per slot, gems, passives and level
+#[tauri::command]
+pub async fn diff_snapshots(snapshot_a: i64, snapshot_b: i64) -> AppResult<SnapshotDiff> {
+    let load = |id: i64| Snapshot::get_by_id(id)?.ok_or_else(|| AppError::NotFound(format!("Snapshot {}", id)));
+    snapshot_diff::diff_snapshots(&load(snapshot_a)?, &load(snapshot_b)?)
+}
+
+/// Each gem a run's snapshots saw socketed: when it was first equipped and its
+/// level at each split after
+#[tauri::command]
+pub async fn get_gem_timeline(run_id: i64) -> AppResult<Vec<GemHistory>> {
+    if Run::get_by_id(run_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    }
+    Ok(snapshot_analysis::gem_timeline(&Snapshot::get_by_run(run_id)?, &Split::get_by_run(run_id)?))
+}
+
+/// Re-derive `skills_json` and `stats_json` of the filtered runs' snapshots
+/// from their stored items and passives, so snapshots taken before the
+/// analysis improved get its results. Snapshots that fail to parse are skipped.
+#[tauri::command]
+pub async fn reprocess_snapshots(filters: RunFilters) -> AppResult<ReprocessReport> {
+    let mut report = ReprocessReport::default();
+    for run in Run::get_filtered(&filters)? {
+        report.runs += 1;
+        for snapshot in Snapshot::get_by_run(run.id)? {
+            report.snapshots += 1;
+            let analysis = match snapshot_analysis::analyze_snapshot(&snapshot) {
+                Ok(analysis) => analysis,
+                Err(e) => {
+                    eprintln!("[reprocess] {}", e);
+                    report.failed += 1;
+                    continue;
+                }
+            };
+            let (skills_json, stats_json) = (analysis.skills_json(), analysis.stats_json());
+            if skills_json != snapshot.skills_json || stats_json != snapshot.stats_json {
+                Snapshot::set_analysis(snapshot.id, &skills_json, &stats_json)?;
+                report.updated += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+// ============================================================================
+// Personal Best Commands
+// ============================================================================
+
+/// PBs to compare against: all-time, or only the current major patch's when
+/// `separate_pbs_by_patch` is on (current = detected version, else the latest run's)
+#[tauri::command]
+pub async fn get_personal_bests() -> AppResult<Vec<PersonalBest>> {
+    current_personal_bests()
+}
+
+/// The PB for a category, class and league, on the current major patch when
+/// PBs are kept per patch
+#[tauri::command]
+pub async fn get_personal_best(category: String, class: String, league: String) -> AppResult<Option<PersonalBest>> {
+    PersonalBest::get(&category, &class, &league, &current_pb_patch()?).map_err(AppError::from)
+}
+
+/// Rebuild every PB from the completed runs, returning how many there are
+#[tauri::command]
+pub async fn recompute_personal_bests() -> AppResult<usize> {
+    crate::db::recompute_personal_bests().map_err(AppError::from)
+}
+
+/// PBs for the current major patch when they're kept per patch, else all-time
+fn current_personal_bests() -> AppResult<Vec<PersonalBest>> {
+    PersonalBest::get_for_patch(&current_pb_patch()?).map_err(AppError::from)
+}
+
+/// The PB a run compares against: its category, class and league's, per `current_pb_patch`
+fn current_personal_best(run: &Run) -> AppResult<Option<PersonalBest>> {
+    PersonalBest::get(&run.category, &run.class, &run.league, &current_pb_patch()?).map_err(AppError::from)
+}
+
+/// Current major patch when `separate_pbs_by_patch` is on, else "" (all-time)
+fn current_pb_patch() -> AppResult<String> {
+    if !Settings::load()?.separate_pbs_by_patch {
+        return Ok(String::new());
+    }
+    let version = match detected_game_version() {
+        Some(version) => Some(version),
+        None => Run::latest_game_version()?,
+    };
+    Ok(version.as_deref().and_then(PersonalBest::major_patch).unwrap_or_default())
+}
+
+// ============================================================================
+// Gold Split Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_gold_splits() -> AppResult<Vec<GoldSplit>> {
+    GoldSplit::get_all().map_err(AppError::from)
+}
+
+/// Rebuild a category's gold splits from the segments its remaining runs
+/// recorded, returning how many golds changed
+#[tauri::command]
+pub async fn recompute_gold_splits(category: String) -> AppResult<usize> {
+    GoldSplit::recompute(&category).map_err(AppError::from)
+}
+
+/// Sum of best for the breakpoints a preset enables, from one class's golds
+/// or without `class` the best of any
+#[tauri::command]
+pub async fn get_sum_of_best(category: String, preset: String, class: Option<String>) -> AppResult<SumOfBest> {
+    stats::get_sum_of_best(&category, &preset, class.as_deref())
+}
+
+/// The fastest a run can still finish, from its splits so far and the golds
+/// of the breakpoints it has left
+#[tauri::command]
+pub async fn get_best_possible_time(run_id: i64) -> AppResult<BestPossibleTime> {
+    stats::best_possible_time(run_id)
+}
+
+// ============================================================================
+// Map Session Commands
+// ============================================================================
+
+/// Start timing maps against the time between them, ending any running session
+#[tauri::command]
+pub async fn start_map_session() -> AppResult<MapSession> {
+    map_session::start()
+}
+
+#[tauri::command]
+pub async fn stop_map_session() -> AppResult<Option<MapSession>> {
+    map_session::stop()
+}
+
+/// The running session with its totals up to now
+#[tauri::command]
+pub async fn get_current_map_session() -> AppResult<Option<MapSession>> {
+    Ok(map_session::current())
+}
+
+/// Every map session, newest first; the running one has live totals
+#[tauri::command]
+pub async fn get_map_sessions() -> AppResult<Vec<MapSession>> {
+    let current = map_session::current();
+    let sessions = MapSession::get_all()?
+        .into_iter()
+        .map(|session| match &current {
+            Some(live) if live.id == session.id => live.clone(),
+            _ => session,
+        })
+        .collect();
+    Ok(sessions)
+}
+
+#[tauri::command]
+pub async fn delete_map_session(session_id: i64) -> AppResult<()> {
+    if map_session::current().is_some_and(|session| session.id == session_id) {
+        map_session::stop()?;
+    }
+    MapSession::delete(session_id)?;
+    Ok(())
+}
+
+// ============================================================================
+// API Commands
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CharacterListResponse {
+    pub characters: Vec<crate::api_client::PoeCharacter>,
+}
+
+#[tauri::command]
+pub async fn fetch_characters(account_name: String) -> AppResult<CharacterListResponse> {
     let client = get_api_client();
     let characters = client
         .get_characters(&account_name)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
     Ok(CharacterListResponse { characters })
 }
 
+/// Whether the public API can read the account's characters, with steps to fix
+/// it when it can't. Run on setup and before a run starts.
+#[tauri::command]
+pub async fn check_profile_privacy(account: String) -> AppResult<ProfilePrivacy> {
+    let account = account.trim();
+    if account.is_empty() {
+        return Err(AppError::InvalidInput("Account name is required".to_string()));
+    }
+    privacy::check(get_api_client(), account).await
+}
+
+/// Evaluate the pre-run checklist from settings for a run of `category` with
+/// the selected breakpoint preset. In block mode the frontend won't start the
+/// run while `blocked` is set.
+#[tauri::command]
+pub async fn get_run_readiness(
+    app_handle: AppHandle,
+    category: String,
+    breakpoint_preset: Option<String>,
+) -> AppResult<RunReadiness> {
+    let settings = Settings::load()?;
+    let checklist = Checklist::from_settings(&settings);
+    let log_watcher_running = get_log_watcher().lock()?.is_some();
+
+    let account_name = settings.account_name.trim().to_string();
+    let privacy = if checklist.needs_privacy() && !account_name.is_empty() {
+        Some(privacy::check(get_api_client(), &account_name).await)
+    } else {
+        None
+    };
+
+    Ok(readiness::evaluate(
+        &checklist,
+        ReadinessState {
+            log_watcher_running,
+            overlay_open: app_handle.get_webview_window("overlay").is_some(),
+            account_name,
+            privacy,
+            selected_preset: breakpoint_preset,
+            pb_preset: if checklist.preset { readiness::pb_preset(&category)? } else { None },
+        },
+    ))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CharacterDataResponse {
     pub items: Vec<crate::api_client::PoeItem>,
@@ -468,12 +2385,11 @@ pub struct CharacterDataResponse {
 pub async fn fetch_character_data(
     account_name: String,
     character_name: String,
-) -> Result<CharacterDataResponse, String> {
+) -> AppResult<CharacterDataResponse> {
     let client = get_api_client();
     let data = client
         .get_items(&account_name, &character_name)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     Ok(CharacterDataResponse {
         items: data.items,
@@ -491,27 +2407,102 @@ pub struct PassiveTreeResponse {
 pub async fn fetch_passive_tree(
     account_name: String,
     character_name: String,
-) -> Result<PassiveTreeResponse, String> {
+) -> AppResult<PassiveTreeResponse> {
     let client = get_api_client();
     let data = client
         .get_passive_skills(&account_name, &character_name)
-        .await
-        .map_err(|e| e.to_string())?;
+        .await?;
 
     Ok(PassiveTreeResponse { hashes: data.hashes })
 }
 
+// ============================================================================
+// Path of Exile Sign-in Commands
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoeAuthStatus {
+    pub connected: bool,
+    pub account_name: Option<String>,
+    /// Unix seconds; refreshed automatically before it passes
+    pub expires_at: Option<i64>,
+}
+
+impl PoeAuthStatus {
+    fn from_token(token: Option<&crate::db::OAuthToken>) -> Self {
+        PoeAuthStatus {
+            connected: token.is_some(),
+            account_name: token.map(|t| t.account_name.clone()),
+            expires_at: token.map(|t| t.expires_at),
+        }
+    }
+}
+
+/// Sign in with a Path of Exile account (OAuth authorization code + PKCE) so
+/// snapshots work with a private profile. Opens the browser and waits for its
+/// redirect to a one-off loopback listener.
+#[tauri::command]
+pub async fn poe_login(app_handle: AppHandle) -> AppResult<PoeAuthStatus> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let redirect_uri = format!("http://127.0.0.1:{}{}", listener.local_addr()?.port(), oauth::REDIRECT_PATH);
+    let pkce = oauth::Pkce::new()?;
+
+    app_handle
+        .opener()
+        .open_url(oauth::authorize_url(&redirect_uri, &pkce), None::<&str>)
+        .map_err(|e| AppError::ExternalService(format!("Failed to open the browser: {}", e)))?;
+
+    let state = pkce.state.clone();
+    let code = tauri::async_runtime::spawn_blocking(move || oauth::wait_for_code(listener, &state, oauth::LOGIN_TIMEOUT))
+        .await
+        .map_err(|e| AppError::Internal(format!("Sign-in listener failed: {}", e)))??;
+
+    let client = get_api_client();
+    let token = oauth::exchange_code(client.http(), &code, &pkce.verifier, &redirect_uri).await?;
+    Settings::save_oauth_token(&token)?;
+    let status = PoeAuthStatus::from_token(Some(&token));
+    client.set_oauth_token(Some(token)).await;
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_poe_auth_status() -> AppResult<PoeAuthStatus> {
+    Ok(PoeAuthStatus::from_token(Settings::get_oauth_token()?.as_ref()))
+}
+
+/// Forget the stored tokens; the public endpoints are used from then on
+#[tauri::command]
+pub async fn poe_logout() -> AppResult<PoeAuthStatus> {
+    Settings::clear_oauth_token()?;
+    get_api_client().set_oauth_token(None).await;
+    Ok(PoeAuthStatus::from_token(None))
+}
+
 // ============================================================================
 // PoB Export Commands
 // ============================================================================
 
+/// Build a PoB code from a snapshot's stored items and passives and save it on the snapshot
+#[tauri::command]
+pub async fn generate_pob_code(snapshot_id: i64) -> AppResult<String> {
+    let snapshot = Snapshot::get_by_id(snapshot_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Snapshot {}", snapshot_id)))?;
+    let run = Run::get_by_id(snapshot.run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", snapshot.run_id)))?;
+
+    let pob_code = pob_builder::snapshot_code(&snapshot, &run)?;
+    Snapshot::set_pob_code(snapshot_id, &pob_code)?;
+    Ok(pob_code)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PobbInResponse {
     pub url: String,
 }
 
 #[tauri::command]
-pub async fn upload_to_pobbin(pob_code: String) -> Result<PobbInResponse, String> {
+pub async fn upload_to_pobbin(pob_code: String) -> AppResult<PobbInResponse> {
     let client = reqwest::Client::new();
 
     // pobb.in expects a POST to /pob with the raw PoB code as text/plain
@@ -522,127 +2513,582 @@ pub async fn upload_to_pobbin(pob_code: String) -> Result<PobbInResponse, String
         .body(pob_code)
         .send()
         .await
-        .map_err(|e| format!("Failed to upload: {}", e))?;
+        .map_err(|e| AppError::Network(format!("Failed to upload: {}", e)))?;
 
     let status = response.status();
-    let text = response.text().await.map_err(|e| e.to_string())?;
+    let text = response.text().await?;
     let text = text.trim();
 
     // Check for HTTP errors
     if !status.is_success() {
-        return Err(format!("pobb.in error ({}): {}", status, text));
+        return Err(AppError::ExternalService(format!("pobb.in error ({}): {}", status, text)));
+    }
+
+    // pobb.in returns the ID directly as plain text (e.g., "WtDNCT-adpMf")
+    // If it looks like an ID (alphanumeric with possible hyphen), use it directly
+    if !text.is_empty() && !text.starts_with('{') && !text.starts_with('<') {
+        return Ok(PobbInResponse {
+            url: format!("https://pobb.in/{}", text),
+        });
+    }
+
+    // Try parsing as JSON (fallback for future API changes)
+    if let Ok(data) = serde_json::from_str::<serde_json::Value>(text) {
+        // Check for error response
+        if let Some(code) = data["code"].as_i64() {
+            if code >= 400 {
+                let message = data["message"].as_str().unwrap_or("Unknown error");
+                return Err(AppError::ExternalService(format!("pobb.in error: {}", message)));
+            }
+        }
+
+        // Success - get the ID from response
+        if let Some(id) = data["id"].as_str() {
+            return Ok(PobbInResponse {
+                url: format!("https://pobb.in/{}", id),
+            });
+        }
+
+        // Also check for url field
+        if let Some(url) = data["url"].as_str() {
+            return Ok(PobbInResponse {
+                url: url.to_string(),
+            });
+        }
+    }
+
+    Err(AppError::ExternalService(format!("pobb.in returned unexpected response ({}): {}",
+        status,
+        text.chars().take(200).collect::<String>()
+    )))
+}
+
+// ============================================================================
+// Image Proxy Commands (for CORS bypass)
+// ============================================================================
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+#[tauri::command]
+pub async fn proxy_image(url: String) -> AppResult<String> {
+    // Only allow proxying from trusted domains - parse URL to prevent bypass
+    let parsed = reqwest::Url::parse(&url).map_err(|_| AppError::InvalidInput("Invalid URL".to_string()))?;
+    let settings = Settings::load()?;
+    let hosts = image_cache::parse_hosts(&settings.image_proxy_hosts)
+        .or_else(|| image_cache::parse_hosts(&Settings::default().image_proxy_hosts))
+        .unwrap_or_default();
+    if !image_cache::is_allowed(&parsed, &hosts) {
+        return Err(AppError::InvalidInput(format!(
+            "{} is not in the trusted image hosts",
+            parsed.host_str().unwrap_or(&url)
+        )));
+    }
+
+    if let Some(cached) = image_cache::lookup(&url) {
+        return Ok(cached);
+    }
+
+    // Redirects are followed only to trusted hosts too, so an open redirect on
+    // one of them can't send the fetch anywhere else
+    let redirect_hosts = hosts.clone();
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() < 5 && image_cache::is_allowed(attempt.url(), &redirect_hosts) {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        }))
+        .build()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut response = client
+        .get(&url)
+        .header("User-Agent", "POE-Watcher/0.2.0 (https://github.com/kburke8/poe-watcher; Discord: beerdz)")
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to fetch image: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::ExternalService(format!("Image fetch failed with status: {}", response.status())));
+    }
+
+    // Get content type; anything but an image is refused
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("image/png")
+        .to_string();
+    if !content_type.to_ascii_lowercase().starts_with("image/") {
+        return Err(AppError::ExternalService(format!("Not an image: {}", content_type)));
+    }
+    let too_large = || AppError::ExternalService(format!("Image is over the {} byte limit", image_cache::MAX_IMAGE_BYTES));
+    if response.content_length().is_some_and(|len| len > image_cache::MAX_IMAGE_BYTES) {
+        return Err(too_large());
+    }
+
+    // Read in chunks, giving up as soon as the limit is passed: a response
+    // without a Content-Length would otherwise be buffered whole
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to read image bytes: {}", e)))?
+    {
+        if (bytes.len() + chunk.len()) as u64 > image_cache::MAX_IMAGE_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let base64_data = BASE64.encode(&bytes);
+
+    // Return as data URL, caching it so the same icon isn't fetched again
+    let data_url = format!("data:{};base64,{}", content_type, base64_data);
+    image_cache::store(&url, &data_url);
+    Ok(data_url)
+}
+
+/// Cached image count and size on disk, plus hits and misses this session
+#[tauri::command]
+pub async fn get_image_cache_stats() -> AppResult<ImageCacheStats> {
+    Ok(image_cache::stats())
+}
+
+/// Delete every cached image, returning what the cache held before
+#[tauri::command]
+pub async fn clear_image_cache() -> AppResult<ImageCacheStats> {
+    image_cache::clear()
+}
+
+// ============================================================================
+// Maintenance Commands
+// ============================================================================
+
+/// Find and fix orphaned rows, incomplete runs, and stale PB/gold entries,
+/// reporting how many of each were fixed
+#[tauri::command]
+pub async fn repair_database() -> AppResult<RepairReport> {
+    crate::db::repair().map_err(AppError::from)
+}
+
+/// Prune snapshots the `snapshot_retention` setting doesn't keep, compress
+/// ones stored uncompressed, and VACUUM the database
+#[tauri::command]
+pub async fn compact_database() -> AppResult<CompactReport> {
+    let retention = SnapshotRetention::from_settings(&Settings::load()?);
+    crate::db::compact(retention).map_err(AppError::from)
+}
+
+/// Move runs started before `date` (YYYY-MM-DD), other than reference and PB
+/// runs, into the archive database
+#[tauri::command]
+pub async fn archive_runs_older_than(date: String) -> AppResult<ArchiveReport> {
+    let date = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::InvalidInput(format!("Invalid date: {}", date)))?;
+    crate::db::archive_runs_older_than(&date.to_string()).map_err(AppError::from)
+}
+
+/// One page of the archived runs, newest first, loaded only when asked for
+#[tauri::command]
+pub async fn get_archived_runs(offset: i64, limit: i64) -> AppResult<RunPage> {
+    if limit <= 0 {
+        return Err(AppError::InvalidInput("Page size must be positive".to_string()));
+    }
+    crate::db::get_archived_paged(offset, limit).map_err(AppError::from)
+}
+
+/// Move an archived run back into the main database
+#[tauri::command]
+pub async fn restore_archived_run(run_id: i64) -> AppResult<()> {
+    if !crate::db::restore_archived_run(run_id)? {
+        return Err(AppError::NotFound(format!("Archived run {}", run_id)));
+    }
+    Ok(())
+}
+
+/// What changed since the app last ran: the version it was updated from, the
+/// changelog releases since then, and the database migrations applied at startup
+#[tauri::command]
+pub async fn get_app_changelog_state() -> AppResult<AppChangelogState> {
+    changelog::state()
+}
+
+/// Check for a newer version on `channel` ("stable" or "beta"), or the
+/// `update_channel` setting's when omitted. None when up to date.
+#[tauri::command]
+pub async fn check_for_update(app_handle: AppHandle, channel: Option<String>) -> AppResult<Option<UpdateInfo>> {
+    let channel = match channel {
+        Some(channel) => UpdateChannel::parse(&channel)
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown update channel: {}", channel)))?,
+        None => UpdateChannel::current(),
+    };
+    updater::check(&app_handle, channel).await
+}
+
+/// Download and install the update `check_for_update` found (or the saved
+/// channel's latest) without prompting, then restart. Emits `update-progress`
+/// while downloading; returns false when already up to date.
+#[tauri::command]
+pub async fn install_update_now(app_handle: AppHandle) -> AppResult<bool> {
+    updater::install(&app_handle).await
+}
+
+/// Write a bug-report bundle: app/game version, settings, and the snapshot
+/// attempts recorded while `record_api_responses` was on
+#[tauri::command]
+pub async fn export_diagnostics(path: String) -> AppResult<()> {
+    let bundle = diagnostics::bundle(&Settings::load()?, detected_game_version(), &diagnostics::recordings());
+    let json_str = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize diagnostics: {}", e)))?;
+
+    std::fs::write(&path, json_str)
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path, e)))?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Status Page Commands
+// ============================================================================
+
+/// Status page config plus the URLs a phone can open (empty while disabled)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusServerInfo {
+    #[serde(flatten)]
+    pub config: StatusServerConfig,
+    pub urls: Vec<String>,
+}
+
+impl From<StatusServerConfig> for StatusServerInfo {
+    fn from(config: StatusServerConfig) -> Self {
+        let urls = if config.enabled {
+            let mut hosts = vec!["localhost".to_string()];
+            if let Some(ip) = status_server::local_ip() {
+                hosts.insert(0, ip.to_string());
+            }
+            hosts
+                .into_iter()
+                .map(|host| format!("http://{}:{}/?token={}", host, config.port, config.token))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        StatusServerInfo { config, urls }
+    }
+}
+
+#[tauri::command]
+pub async fn get_status_server() -> AppResult<StatusServerInfo> {
+    Ok(Settings::get_status_server()?.into())
+}
+
+/// Enable or disable the phone status page. A pairing token is generated the
+/// first time it is enabled.
+#[tauri::command]
+pub async fn set_status_server(enabled: bool, port: u16) -> AppResult<StatusServerInfo> {
+    if port < 1024 {
+        return Err(AppError::InvalidInput("Port must be 1024 or higher".to_string()));
+    }
+
+    let mut config = Settings::get_status_server()?;
+    config.enabled = enabled;
+    config.port = port;
+    if config.token.is_empty() {
+        config.token = status_server::generate_token()?;
+    }
+
+    apply_status_server(&config)?;
+    Settings::save_status_server(&config)?;
+    Ok(config.into())
+}
+
+/// Replace the pairing token, disconnecting any paired phone
+#[tauri::command]
+pub async fn regenerate_status_token() -> AppResult<StatusServerInfo> {
+    let mut config = Settings::get_status_server()?;
+    config.token = status_server::generate_token()?;
+
+    apply_status_server(&config)?;
+    Settings::save_status_server(&config)?;
+    Ok(config.into())
+}
+
+/// Start, restart, or stop the status server to match `config`
+pub(crate) fn apply_status_server(config: &StatusServerConfig) -> AppResult<()> {
+    if !config.enabled {
+        status_server::stop();
+        return Ok(());
+    }
+    status_server::start(config.port, config.token.clone())
+        .map_err(|e| AppError::Io(format!("Could not start status page on port {}: {}", config.port, e)))?;
+    Ok(())
+}
+
+// ============================================================================
+// OBS Server Commands
+// ============================================================================
+
+/// OBS server config plus the browser source URL (empty while disabled)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsServerInfo {
+    #[serde(flatten)]
+    pub config: ObsServerConfig,
+    pub url: String,
+}
+
+impl From<ObsServerConfig> for ObsServerInfo {
+    fn from(config: ObsServerConfig) -> Self {
+        let url = if config.enabled {
+            format!("http://localhost:{}/", config.port)
+        } else {
+            String::new()
+        };
+        ObsServerInfo { config, url }
+    }
+}
+
+#[tauri::command]
+pub async fn get_obs_server() -> AppResult<ObsServerInfo> {
+    Ok(Settings::get_obs_server()?.into())
+}
+
+/// Enable or disable the local server OBS browser sources read the overlay
+/// state from
+#[tauri::command]
+pub async fn set_obs_server(enabled: bool, port: u16) -> AppResult<ObsServerInfo> {
+    if port < 1024 {
+        return Err(AppError::InvalidInput("Port must be 1024 or higher".to_string()));
+    }
+
+    let config = ObsServerConfig { enabled, port };
+    apply_obs_server(&config)?;
+    Settings::save_obs_server(&config)?;
+    Ok(config.into())
+}
+
+/// Start, restart, or stop the OBS server to match `config`
+pub(crate) fn apply_obs_server(config: &ObsServerConfig) -> AppResult<()> {
+    if !config.enabled {
+        obs_server::stop();
+        return Ok(());
     }
+    obs_server::start(config.port)
+        .map_err(|e| AppError::Io(format!("Could not start OBS server on port {}: {}", config.port, e)))?;
+    Ok(())
+}
+
+// ============================================================================
+// Twitch Chat Commands
+// ============================================================================
+
+/// Post a test message with the saved chat settings, so a bad token or
+/// channel shows up before a PB goes unannounced
+#[tauri::command]
+pub async fn send_twitch_test_message() -> AppResult<()> {
+    let Some(config) = ChatConfig::from_settings(&Settings::load()?) else {
+        return Err(AppError::InvalidInput("Set a Twitch chat token and channel first".to_string()));
+    };
+    tauri::async_runtime::spawn_blocking(move || twitch::post(&config, "POE Watcher is connected to chat"))
+        .await
+        .map_err(|e| AppError::Internal(format!("Twitch chat test failed: {}", e)))?
+}
+
+// ============================================================================
+// Race Lobby Commands
+// ============================================================================
+
+/// Forward each remote racer's event to the frontend, which refreshes the
+/// lobby and a comparison against that racer
+fn race_listener(app_handle: AppHandle) -> race_lobby::Listener {
+    Arc::new(move |event| {
+        let _ = app_handle.emit("race-event", event);
+    })
+}
 
-    // pobb.in returns the ID directly as plain text (e.g., "WtDNCT-adpMf")
-    // If it looks like an ID (alphanumeric with possible hyphen), use it directly
-    if !text.is_empty() && !text.starts_with('{') && !text.starts_with('<') {
-        return Ok(PobbInResponse {
-            url: format!("https://pobb.in/{}", text),
-        });
+fn racer_name(name: &str) -> AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput("Pick a racer name".to_string()));
     }
+    Ok(name.to_string())
+}
 
-    // Try parsing as JSON (fallback for future API changes)
-    if let Ok(data) = serde_json::from_str::<serde_json::Value>(&text) {
-        // Check for error response
-        if let Some(code) = data["code"].as_i64() {
-            if code >= 400 {
-                let message = data["message"].as_str().unwrap_or("Unknown error");
-                return Err(format!("pobb.in error: {}", message));
-            }
-        }
+/// Host a race lobby friends on the network can join. Everyone's splits are
+/// shared, and other racers show up as live reference runs to compare against.
+#[tauri::command]
+pub async fn host_race(app_handle: AppHandle, port: u16, name: String) -> AppResult<RaceStatus> {
+    if port < 1024 {
+        return Err(AppError::InvalidInput("Port must be 1024 or higher".to_string()));
+    }
+    let name = racer_name(&name)?;
+    race_lobby::host(port, &name, race_listener(app_handle))
+        .map_err(|e| AppError::Io(format!("Could not host a race on port {}: {}", port, e)))?;
+    Ok(race_lobby::status())
+}
 
-        // Success - get the ID from response
-        if let Some(id) = data["id"].as_str() {
-            return Ok(PobbInResponse {
-                url: format!("https://pobb.in/{}", id),
-            });
-        }
+/// Join a race lobby, or a relay, at its `ws://host:port/` address
+#[tauri::command]
+pub async fn join_race(app_handle: AppHandle, url: String, name: String) -> AppResult<RaceStatus> {
+    let name = racer_name(&name)?;
+    race_lobby::join(&url, &name, race_listener(app_handle))
+        .map_err(|e| AppError::Io(format!("Could not join {}: {}", url.trim(), e)))?;
+    Ok(race_lobby::status())
+}
 
-        // Also check for url field
-        if let Some(url) = data["url"].as_str() {
-            return Ok(PobbInResponse {
-                url: url.to_string(),
-            });
-        }
-    }
+/// Leave the lobby, or close it when hosting. Racers' runs are kept.
+#[tauri::command]
+pub async fn leave_race() -> AppResult<()> {
+    race_lobby::leave();
+    Ok(())
+}
 
-    Err(format!("pobb.in returned unexpected response ({}): {}",
-        status,
-        text.chars().take(200).collect::<String>()
-    ))
+#[tauri::command]
+pub async fn get_race_status() -> AppResult<RaceStatus> {
+    Ok(race_lobby::status())
 }
 
 // ============================================================================
-// Image Proxy Commands (for CORS bypass)
+// JSON Export Commands
 // ============================================================================
 
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-
+/// Save a run's splits table as CSV, Markdown or HTML (picked from the file
+/// extension), with numbers, times and dates formatted for the `locale` setting
 #[tauri::command]
-pub async fn proxy_image(url: String) -> Result<String, String> {
-    // Only allow proxying from trusted domains - parse URL to prevent bypass
-    let parsed = reqwest::Url::parse(&url).map_err(|_| "Invalid URL".to_string())?;
-    if parsed.host_str() != Some("web.poecdn.com") {
-        return Err("Only web.poecdn.com URLs are allowed".to_string());
-    }
+pub async fn export_run_table(run_id: i64, file_path: String) -> AppResult<()> {
+    let format = Path::new(&file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(TableFormat::from_extension)
+        .ok_or_else(|| AppError::InvalidInput("Export file must end in .csv, .md or .html".to_string()))?;
+    let run = Run::get_by_id(run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    let splits = Split::get_by_run(run_id)?;
+    let locale = ExportLocale::for_tag(&Settings::load()?.locale);
+
+    let table = table_export::render(format, &run, &splits, &locale);
+    std::fs::write(&file_path, table)
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", file_path, e)))?;
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "POE-Watcher/0.2.0 (https://github.com/kburke8/poe-watcher; Discord: beerdz)")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch image: {}", e))?;
+    Ok(())
+}
 
-    if !response.status().is_success() {
-        return Err(format!("Image fetch failed with status: {}", response.status()));
+/// Save the runs matching `filters` as CSV, one row per run, returning how
+/// many were written
+#[tauri::command]
+pub async fn export_runs_csv(filters: RunFilters, path: String) -> AppResult<usize> {
+    let runs = Run::get_filtered(&filters)?;
+    let locale = ExportLocale::for_tag(&Settings::load()?.locale);
+    std::fs::write(&path, table_export::runs_csv(&runs, &locale))
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path, e)))?;
+    Ok(runs.len())
+}
+
+/// Save the splits of `run_ids` as CSV, one row per split, returning how
+/// many were written
+#[tauri::command]
+pub async fn export_splits_csv(run_ids: Vec<i64>, path: String) -> AppResult<usize> {
+    let mut runs = Vec::with_capacity(run_ids.len());
+    for run_id in run_ids {
+        let run = Run::get_by_id(run_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+        runs.push((run, Split::get_by_run(run_id)?));
     }
+    let locale = ExportLocale::for_tag(&Settings::load()?.locale);
+    std::fs::write(&path, table_export::splits_csv(&runs, &locale))
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path, e)))?;
+    Ok(runs.iter().map(|(_, splits)| splits.len()).sum())
+}
 
-    // Get content type
-    let content_type = response
-        .headers()
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("image/png")
-        .to_string();
+/// Save a run as a LiveSplit `.lss` file, with the category/class/league all-time PB
+/// as the Personal Best comparison and the gold splits as Best Segments
+#[tauri::command]
+pub async fn export_run_livesplit(run_id: i64, path: String) -> AppResult<()> {
+    std::fs::write(&path, render_run_livesplit(run_id)?)
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path, e)))?;
+    Ok(())
+}
 
-    // Get bytes and convert to base64
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read image bytes: {}", e))?;
+/// A run's `.lss` file contents, as saved by `export_run_livesplit`
+pub(crate) fn render_run_livesplit(run_id: i64) -> AppResult<String> {
+    let run = Run::get_by_id(run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    let splits = Split::get_by_run(run_id)?;
 
-    let base64_data = BASE64.encode(&bytes);
+    let pb_run_id = PersonalBest::get(&run.category, &run.class, &run.league, "")?.map(|pb| pb.run_id);
+    let pb_splits = match pb_run_id {
+        Some(pb_run_id) => Split::get_by_run(pb_run_id)?,
+        None => Vec::new(),
+    };
+    let golds = splits
+        .iter()
+        .map(|s| GoldSplit::get_best(&run.category, &run.class, &s.breakpoint_name, s.breakpoint_key.as_deref()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
-    // Return as data URL
-    Ok(format!("data:{};base64,{}", content_type, base64_data))
+    Ok(livesplit::render(&run, &splits, &pb_splits, &golds))
 }
 
-// ============================================================================
-// JSON Export Commands
-// ============================================================================
+/// Tamper-evident summary of a completed run (splits, times, app version),
+/// also embedded in its JSON export
+#[tauri::command]
+pub async fn get_run_certificate(run_id: i64) -> AppResult<RunCertificate> {
+    let run = Run::get_by_id(run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    certificate::issue(&run, &Split::get_by_run(run_id)?)
+}
+
+/// Check a certificate someone shared against its hash
+#[tauri::command]
+pub async fn verify_run_certificate(certificate: RunCertificate) -> AppResult<CertificateStatus> {
+    Ok(certificate::verify(&certificate))
+}
 
 #[tauri::command]
-pub async fn export_run_json(run_id: i64, file_path: String) -> Result<(), String> {
-    let run = Run::get_by_id(run_id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| format!("Run {} not found", run_id))?;
+pub async fn export_run_json(run_id: i64, file_path: String) -> AppResult<()> {
+    std::fs::write(&file_path, render_run_json(run_id)?)
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", file_path, e)))?;
 
-    let splits = Split::get_by_run(run_id).map_err(|e| e.to_string())?;
-    let snapshots = Snapshot::get_by_run(run_id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// A run's JSON export (format `run_json::VERSION`), as saved by `export_run_json`
+pub(crate) fn render_run_json(run_id: i64) -> AppResult<String> {
+    let run = Run::get_by_id(run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+
+    let splits = Split::get_by_run(run_id)?;
+    let snapshots = Snapshot::get_by_run(run_id)?;
+    let tags = RunTag::get_by_run(run_id)?;
+    let notes = SplitNote::get_by_run(run_id)?;
+    let tags_of = |split_id: Option<i64>| -> Vec<&str> {
+        tags.iter().filter(|t| t.split_id == split_id).map(|t| t.tag.as_str()).collect()
+    };
 
     // Build splits array
     let splits_json: Vec<serde_json::Value> = splits
         .iter()
         .map(|s| {
+            let split_notes: Vec<serde_json::Value> = notes
+                .iter()
+                .filter(|n| n.split_id == s.id)
+                .map(|n| serde_json::json!({ "note": n.note, "mistakeType": n.mistake_type }))
+                .collect();
             serde_json::json!({
                 "breakpointName": s.breakpoint_name,
                 "breakpointType": s.breakpoint_type,
+                "breakpointKey": s.breakpoint_key,
                 "splitTimeMs": s.split_time_ms,
                 "segmentTimeMs": s.segment_time_ms,
                 "deltaMs": s.delta_ms,
                 "townTimeMs": s.town_time_ms,
                 "hideoutTimeMs": s.hideout_time_ms,
+                "loadTimeMs": s.load_time_ms,
+                // Where this split happens in the VOD, when the run has one
+                "vodTimeMs": run.vod_offset_ms.map(|offset| offset + s.split_time_ms),
+                "tags": tags_of(Some(s.id)),
+                "notes": split_notes,
             })
         })
         .collect();
@@ -654,31 +3100,40 @@ pub async fn export_run_json(run_id: i64, file_path: String) -> Result<(), Strin
             // Find the split name for this snapshot
             let split_name = splits
                 .iter()
-                .find(|s| s.id == snap.split_id)
+                .find(|s| Some(s.id) == snap.split_id)
                 .map(|s| s.breakpoint_name.as_str())
                 .unwrap_or("Unknown");
 
             let items: serde_json::Value = serde_json::from_str(&snap.items_json)
                 .unwrap_or(serde_json::Value::Array(vec![]));
+            let skills: serde_json::Value = serde_json::from_str(&snap.skills_json)
+                .unwrap_or(serde_json::Value::Array(vec![]));
             let passive_tree: serde_json::Value = serde_json::from_str(&snap.passive_tree_json)
                 .unwrap_or(serde_json::json!({}));
+            let stats: serde_json::Value = serde_json::from_str(&snap.stats_json)
+                .unwrap_or(serde_json::json!({}));
 
             serde_json::json!({
                 "splitName": split_name,
                 "elapsedTimeMs": snap.elapsed_time_ms,
                 "characterLevel": snap.character_level,
+                "experience": snap.experience,
                 "items": items,
+                "skills": skills,
                 "passiveTree": passive_tree,
+                "stats": stats,
                 "pobCode": snap.pob_code,
+                "isFinal": snap.is_final,
             })
         })
         .collect();
 
     let export = serde_json::json!({
-        "version": "0.2.0",
+        "version": run_json::VERSION,
         "exportedAt": chrono::Utc::now().to_rfc3339(),
         "run": {
             "character": run.character_name,
+            "accountName": run.account_name,
             "class": run.class,
             "ascendancy": run.ascendancy,
             "league": run.league,
@@ -686,19 +3141,273 @@ pub async fn export_run_json(run_id: i64, file_path: String) -> Result<(), Strin
             "startedAt": run.started_at,
             "endedAt": run.ended_at,
             "totalTimeMs": run.total_time_ms,
+            "loadTimeMs": run.load_time_ms,
             "isCompleted": run.is_completed,
             "isPersonalBest": run.is_personal_best,
             "breakpointPreset": run.breakpoint_preset,
+            "gameVersion": run.game_version,
+            "ruleset": run.ruleset,
+            "videoUrl": run.video_url,
+            "vodOffsetMs": run.vod_offset_ms,
+            "reviewNotes": run.review_notes,
+            "notes": run.notes,
+            "tags": tags_of(None),
         },
         "splits": splits_json,
         "snapshots": snapshots_json,
+        "certificate": certificate::issue(&run, &splits).ok(),
+    });
+
+    serde_json::to_string_pretty(&export)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize JSON: {}", e)))
+}
+
+/// What `import_run_json` created
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunJsonImport {
+    pub run_id: i64,
+    /// Recorded by another account, so kept out of PBs and golds
+    pub is_reference: bool,
+    pub snapshots_imported: usize,
+    /// Check of the export's run certificate; None when it has none
+    pub certificate: Option<CertificateStatus>,
+}
+
+/// Import a run file written by `export_run_json`: the run, its splits, and its
+/// snapshots. A run recorded by the configured account is restored as one of
+/// the user's own (counting toward PBs and golds); anyone else's, or one from
+/// an export that doesn't name its account, becomes a reference run.
+#[tauri::command]
+pub async fn import_run_json(file_path: String) -> AppResult<RunJsonImport> {
+    let contents = std::fs::read_to_string(&file_path)?;
+    let export = run_json::parse(&contents)?;
+    let certificate = certificate::verify_export(&export);
+    let exported = &export.run;
+    let total_time_ms = exported
+        .total_time_ms
+        .or_else(|| export.splits.last().map(|s| s.split_time_ms))
+        .unwrap_or(0);
+
+    let is_reference = !run_json::is_own(exported, &Settings::load()?.account_name);
+    let run_id = if is_reference {
+        let account = exported.account_name.as_deref().map(str::trim).filter(|a| !a.is_empty());
+        insert_reference_run(&ReferenceRunData {
+            source_name: format!("{} ({})", account.unwrap_or("JSON import"), exported.character),
+            character_name: Some(exported.character.clone()),
+            class: exported.class.clone(),
+            ascendancy: exported.ascendancy.clone(),
+            category: exported.category.clone(),
+            league: Some(exported.league.clone()),
+            breakpoint_preset: exported.breakpoint_preset.clone(),
+            enabled_breakpoints: None,
+            total_time_ms,
+            splits: export
+                .splits
+                .iter()
+                .map(|s| ReferenceSplitData {
+                    breakpoint_name: s.breakpoint_name.clone(),
+                    breakpoint_type: s.breakpoint_type.clone(),
+                    breakpoint_key: s.breakpoint_key.clone(),
+                    split_time_ms: s.split_time_ms,
+                })
+                .collect(),
+        })?
+    } else {
+        let already_imported = Run::get_all()?.iter().any(|run| {
+            !run.is_reference && run.character_name == exported.character && run.started_at == exported.started_at
+        });
+        if already_imported {
+            return Err(AppError::InvalidInput(format!(
+                "{}'s run from {} is already in the history",
+                exported.character, exported.started_at
+            )));
+        }
+        import_own_run(&export, total_time_ms)?
+    };
+
+    Run::update_review(run_id, &RunReview {
+        video_url: exported.video_url.clone(),
+        vod_offset_ms: exported.vod_offset_ms,
+        review_notes: exported.review_notes.clone(),
+    })?;
+
+    // Notes and tags, on splits matched by name like the snapshots below
+    Run::set_notes(run_id, exported.notes.as_deref())?;
+    let splits = Split::get_by_run(run_id)?;
+    for tag in &exported.tags {
+        RunTag::add(run_id, None, &RunTag::normalize(tag))?;
+    }
+    for exported_split in &export.splits {
+        let Some(split) = splits.iter().find(|s| s.breakpoint_name == exported_split.breakpoint_name) else {
+            continue;
+        };
+        for tag in &exported_split.tags {
+            RunTag::add(run_id, Some(split.id), &RunTag::normalize(tag))?;
+        }
+        for note in &exported_split.notes {
+            SplitNote::insert(&NewSplitNote {
+                split_id: split.id,
+                note: note.note.clone(),
+                mistake_type: note.mistake_type.clone(),
+            })?;
+        }
+    }
+
+    // Snapshots follow their split by name; unmatched ones (level milestones,
+    // or the final snapshot of an export with no splits left) have none
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    let mut snapshots_imported = 0;
+    for snap in &export.snapshots {
+        let split_id = splits.iter().find(|s| s.breakpoint_name == snap.split_name).map(|s| s.id);
+        let snapshot_id = Snapshot::insert(&NewSnapshot {
+            run_id,
+            split_id,
+            timestamp: timestamp.clone(),
+            elapsed_time_ms: snap.elapsed_time_ms,
+            character_level: snap.character_level,
+            experience: snap.experience,
+            items_json: run_json::json_field(&snap.items, "[]"),
+            skills_json: run_json::json_field(&snap.skills, "[]"),
+            passive_tree_json: run_json::json_field(&snap.passive_tree, "{}"),
+            stats_json: run_json::json_field(&snap.stats, "{}"),
+            pob_code: snap.pob_code.clone(),
+        })?;
+        if snap.is_final {
+            Snapshot::set_final(snapshot_id)?;
+        }
+        snapshots_imported += 1;
+    }
+
+    Ok(RunJsonImport { run_id, is_reference, snapshots_imported, certificate })
+}
+
+/// Insert an exported run of the user's own as a regular run, with its golds
+/// and (when it was completed) its PB
+fn import_own_run(export: &run_json::RunExport, total_time_ms: i64) -> AppResult<i64> {
+    let exported = &export.run;
+    let run_id = Run::insert(&NewRun {
+        character_name: exported.character.clone(),
+        account_name: exported.account_name.clone().unwrap_or_default(),
+        class: exported.class.clone(),
+        ascendancy: exported.ascendancy.clone(),
+        league: exported.league.clone(),
+        category: exported.category.clone(),
+        started_at: exported.started_at.clone(),
+        breakpoint_preset: exported.breakpoint_preset.clone(),
+        enabled_breakpoints: None,
+        game_version: exported.game_version.clone(),
+    })?;
+    tag_ruleset(run_id, &exported.league)?;
+
+    if exported.load_time_ms > 0 {
+        Run::add_load_time(run_id, exported.load_time_ms)?;
+    }
+    for split in &export.splits {
+        let split_id = Split::insert(&NewSplit {
+            run_id,
+            breakpoint_type: split.breakpoint_type.clone(),
+            breakpoint_name: split.breakpoint_name.clone(),
+            breakpoint_key: split.breakpoint_key.clone(),
+            split_time_ms: split.split_time_ms,
+            delta_ms: split.delta_ms,
+            segment_time_ms: split.segment_time_ms,
+            town_time_ms: split.town_time_ms,
+            hideout_time_ms: split.hideout_time_ms,
+        })?;
+        if split.load_time_ms > 0 {
+            Split::set_load_time(split_id, split.load_time_ms)?;
+        }
+        if split.segment_time_ms > 0 && !exported.class.is_empty() {
+            GoldSplit::update_if_better(
+                &exported.category,
+                &exported.class,
+                &split.breakpoint_name,
+                split.breakpoint_key.as_deref(),
+                split.segment_time_ms,
+            )?;
+        }
+    }
+
+    if exported.is_completed {
+        finish_run(run_id, total_time_ms)?;
+        if let Some(ended_at) = &exported.ended_at {
+            Run::set_ended_at(run_id, ended_at)?;
+        }
+    }
+    Ok(run_id)
+}
+
+// ============================================================================
+// Image Export Commands
+// ============================================================================
+
+/// Render the run's splits table as a PNG, with deltas against the category/class/league
+/// all-time PB (or the run's own recorded deltas) and best segments in gold
+#[tauri::command]
+pub async fn render_splits_image(run_id: i64, path: String) -> AppResult<()> {
+    let run = Run::get_by_id(run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    let splits = Split::get_by_run(run_id)?;
+
+    let pb_run_id = PersonalBest::get(&run.category, &run.class, &run.league, "")?
+        .map(|pb| pb.run_id)
+        .filter(|&pb_run_id| pb_run_id != run_id);
+    let comparison = match pb_run_id {
+        Some(pb_run_id) => Split::get_by_run(pb_run_id)?,
+        None => Vec::new(),
+    };
+
+    let rows = splits_image::build_rows(&splits, &comparison, |split| {
+        GoldSplit::get_best(&run.category, &run.class, &split.breakpoint_name, split.breakpoint_key.as_deref())
+            .ok()
+            .flatten()
     });
 
-    let json_str = serde_json::to_string_pretty(&export)
-        .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+    let title = match &run.ascendancy {
+        Some(ascendancy) => format!("{} ({}) - {}", run.class, ascendancy, run.category),
+        None => format!("{} - {}", run.class, run.category),
+    };
+    let total = run
+        .total_time_ms
+        .or_else(|| splits.last().map(|s| s.split_time_ms))
+        .map(splits_image::format_time)
+        .unwrap_or_else(|| "-".to_string());
+    let subtitle = format!("{} | {} | {}", run.character_name, run.league, total);
+
+    let png = splits_image::render_png(&title, &subtitle, &rows)
+        .map_err(|e| AppError::Internal(format!("Failed to encode PNG: {}", e)))?;
+    std::fs::write(&path, png)
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path, e)))?;
+
+    Ok(())
+}
+
+/// Render a social-media-sized card for a completed run: final time, class,
+/// category, date, and a badge when it was a personal best
+#[tauri::command]
+pub async fn generate_share_card(run_id: i64, path: String) -> AppResult<()> {
+    let run = Run::get_by_id(run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    let total_time_ms = run
+        .total_time_ms
+        .filter(|_| run.is_completed)
+        .ok_or_else(|| AppError::InvalidInput("Only completed runs have a share card".to_string()))?;
+
+    let card = ShareCard {
+        category: run.category,
+        class: run.class,
+        ascendancy: run.ascendancy,
+        character_name: run.character_name,
+        total_time_ms,
+        date: share_card::format_date(run.ended_at.as_deref().unwrap_or(&run.started_at)),
+        is_personal_best: run.is_personal_best,
+    };
 
-    std::fs::write(&file_path, json_str)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+    let png = share_card::render_png(&card)
+        .map_err(|e| AppError::Internal(format!("Failed to encode PNG: {}", e)))?;
+    std::fs::write(&path, png)
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path, e)))?;
 
     Ok(())
 }
@@ -716,79 +3425,224 @@ pub struct HotkeySettings {
     pub toggle_overlay: String,
     pub toggle_overlay_lock: String,
     pub manual_split: String,
+    pub undo_split: String,
+    pub skip_split: String,
 }
 
-#[tauri::command]
-pub async fn get_hotkeys() -> Result<HotkeySettings, String> {
-    let settings = Settings::load().map_err(|e| e.to_string())?;
-    Ok(HotkeySettings {
-        toggle_timer: settings.hotkey_toggle_timer,
-        reset_timer: settings.hotkey_reset_timer,
-        manual_snapshot: settings.hotkey_manual_snapshot,
-        toggle_overlay: settings.hotkey_toggle_overlay,
-        toggle_overlay_lock: settings.hotkey_toggle_overlay_lock,
-        manual_split: settings.hotkey_manual_split,
-    })
+impl From<&Settings> for HotkeySettings {
+    fn from(settings: &Settings) -> Self {
+        HotkeySettings {
+            toggle_timer: settings.hotkey_toggle_timer.clone(),
+            reset_timer: settings.hotkey_reset_timer.clone(),
+            manual_snapshot: settings.hotkey_manual_snapshot.clone(),
+            toggle_overlay: settings.hotkey_toggle_overlay.clone(),
+            toggle_overlay_lock: settings.hotkey_toggle_overlay_lock.clone(),
+            manual_split: settings.hotkey_manual_split.clone(),
+            undo_split: settings.hotkey_undo_split.clone(),
+            skip_split: settings.hotkey_skip_split.clone(),
+        }
+    }
 }
 
-#[tauri::command]
-pub async fn update_hotkeys(app_handle: AppHandle, hotkeys: HotkeySettings) -> Result<(), String> {
-    // Define the action mappings
-    let new_bindings = vec![
-        (hotkeys.toggle_timer.clone(), "toggle-timer"),
-        (hotkeys.reset_timer.clone(), "reset-timer"),
-        (hotkeys.manual_snapshot.clone(), "manual-snapshot"),
-        (hotkeys.toggle_overlay.clone(), "toggle-overlay"),
-        (hotkeys.toggle_overlay_lock.clone(), "toggle-overlay-lock"),
-        (hotkeys.manual_split.clone(), "manual-split"),
-    ];
+impl HotkeySettings {
+    /// (field name as the frontend sends it, `global-shortcut` action, shortcut)
+    fn bindings(&self) -> [(&'static str, &'static str, &str); 8] {
+        [
+            ("toggleTimer", "toggle-timer", &self.toggle_timer),
+            ("resetTimer", "reset-timer", &self.reset_timer),
+            ("manualSnapshot", "manual-snapshot", &self.manual_snapshot),
+            ("toggleOverlay", "toggle-overlay", &self.toggle_overlay),
+            ("toggleOverlayLock", "toggle-overlay-lock", &self.toggle_overlay_lock),
+            ("manualSplit", "manual-split", &self.manual_split),
+            ("undoSplit", "undo-split", &self.undo_split),
+            ("skipSplit", "skip-split", &self.skip_split),
+        ]
+    }
+}
+
+/// Whether one hotkey is registered as a global shortcut
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyRegistration {
+    /// `HotkeySettings` field, e.g. `toggleTimer`
+    pub hotkey: String,
+    pub shortcut: String,
+    pub registered: bool,
+    /// Why it isn't: an invalid or repeated shortcut, or one another app holds
+    pub error: Option<String>,
+    /// `AppError` code of `error`: `invalid_input` for a shortcut that doesn't
+    /// parse or is repeated, `shortcut_unavailable` when registering it failed
+    pub error_code: Option<&'static str>,
+}
+
+/// A hotkey after `check_hotkeys`: its parsed shortcut, or why it has none
+pub(crate) struct CheckedHotkey {
+    pub(crate) registration: HotkeyRegistration,
+    action: &'static str,
+    shortcut: Option<Shortcut>,
+}
 
-    // Validate: parse all new shortcuts first
-    let mut parsed: Vec<(Shortcut, String, &str)> = Vec::new();
-    for (shortcut_str, action) in &new_bindings {
-        let shortcut: Shortcut = shortcut_str.parse()
-            .map_err(|_| format!("Invalid shortcut format: {}", shortcut_str))?;
-        parsed.push((shortcut, shortcut_str.clone(), action));
+/// Outcome of the last registration, at startup or from `update_hotkeys`
+static HOTKEY_REGISTRATIONS: Mutex<Vec<HotkeyRegistration>> = Mutex::new(Vec::new());
+
+/// Parse each hotkey, flagging ones that don't parse and ones bound to the
+/// same shortcut as an earlier hotkey
+pub(crate) fn check_hotkeys(hotkeys: &HotkeySettings) -> Vec<CheckedHotkey> {
+    let mut checked: Vec<CheckedHotkey> = Vec::new();
+    for (hotkey, action, shortcut_str) in hotkeys.bindings() {
+        let parsed = shortcut_str.parse::<Shortcut>().ok();
+        let error = match &parsed {
+            None => Some(AppError::InvalidInput(format!("Invalid shortcut format: {}", shortcut_str))),
+            Some(shortcut) => checked
+                .iter()
+                .find(|other| other.shortcut.as_ref() == Some(shortcut))
+                .map(|other| AppError::InvalidInput(format!("{} is already bound to {}", shortcut_str, other.registration.hotkey))),
+        };
+        checked.push(CheckedHotkey {
+            registration: HotkeyRegistration {
+                hotkey: hotkey.to_string(),
+                shortcut: shortcut_str.to_string(),
+                registered: false,
+                error: error.as_ref().map(|e| e.to_string()),
+                error_code: error.as_ref().map(|e| e.code()),
+            },
+            action,
+            shortcut: parsed.filter(|_| error.is_none()),
+        });
     }
+    checked
+}
 
-    // Validate: check for duplicates
-    let mut seen = std::collections::HashSet::new();
-    for (_, shortcut_str, _) in &parsed {
-        if !seen.insert(shortcut_str.clone()) {
-            return Err(format!("Duplicate shortcut: {}", shortcut_str));
+/// Replace every registered global shortcut, and the shared map the shortcut
+/// handler reads, with the checked hotkeys. Each is registered on its own, so
+/// one taken by another app doesn't stop the rest from working.
+pub(crate) fn register_hotkeys(
+    app_handle: &AppHandle,
+    map: &Mutex<HashMap<String, String>>,
+    checked: Vec<CheckedHotkey>,
+) -> AppResult<Vec<HotkeyRegistration>> {
+    let mut map = map.lock()?;
+    let _ = app_handle.global_shortcut().unregister_all();
+    map.clear();
+
+    let mut registrations = Vec::new();
+    for CheckedHotkey { mut registration, action, shortcut } in checked {
+        if let Some(shortcut) = shortcut {
+            match app_handle.global_shortcut().register(shortcut) {
+                Ok(()) => {
+                    // Canonical Shortcut::to_string() as key so it matches the
+                    // handler's shortcut_ref.to_string() lookup format
+                    map.insert(shortcut.to_string(), action.to_string());
+                    registration.registered = true;
+                }
+                Err(e) => {
+                    let error =
+                        AppError::ShortcutUnavailable(format!("Could not register {}: {}", registration.shortcut, e));
+                    registration.error = Some(error.to_string());
+                    registration.error_code = Some(error.code());
+                }
+            }
         }
+        if let Some(error) = &registration.error {
+            eprintln!("[hotkeys] {}: {}", registration.hotkey, error);
+        }
+        registrations.push(registration);
     }
 
-    // Get the shared hotkey map
-    let hotkey_map = app_handle.state::<HotkeyMap>();
+    *HOTKEY_REGISTRATIONS.lock()? = registrations.clone();
+    Ok(registrations)
+}
 
-    // Unregister all old shortcuts, then register new ones
-    {
-        let mut map = hotkey_map.0.lock().map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn get_hotkeys() -> AppResult<HotkeySettings> {
+    Ok(HotkeySettings::from(&Settings::load()?))
+}
 
-        // Unregister all old shortcuts
-        let _ = app_handle.global_shortcut().unregister_all();
-        map.clear();
+/// Which hotkeys are registered, so settings can show ones that failed at startup
+#[tauri::command]
+pub async fn get_hotkey_registrations() -> AppResult<Vec<HotkeyRegistration>> {
+    Ok(HOTKEY_REGISTRATIONS.lock()?.clone())
+}
 
-        // Register new shortcuts using canonical Shortcut::to_string() as key
-        // so it matches the handler's shortcut_ref.to_string() lookup format.
-        for (shortcut, shortcut_str, action) in &parsed {
-            app_handle.global_shortcut().register(shortcut.clone())
-                .map_err(|e| format!("Failed to register {}: {}", shortcut_str, e))?;
-            map.insert(shortcut.to_string(), action.to_string());
-        }
+/// Rebind the global shortcuts without a restart. Nothing changes while any
+/// hotkey is invalid or repeated; otherwise the hotkeys are saved and each one
+/// is registered, and the result says which ones another app holds.
+#[tauri::command]
+pub async fn update_hotkeys(app_handle: AppHandle, hotkeys: HotkeySettings) -> AppResult<Vec<HotkeyRegistration>> {
+    let checked = check_hotkeys(&hotkeys);
+    if checked.iter().any(|c| c.registration.error.is_some()) {
+        return Ok(checked.into_iter().map(|c| c.registration).collect());
     }
 
-    // Persist to database
-    let mut settings = Settings::load().map_err(|e| e.to_string())?;
+    let hotkey_map = app_handle.state::<HotkeyMap>();
+    let registrations = register_hotkeys(&app_handle, &hotkey_map.0, checked)?;
+
+    // Saved even if another app holds a shortcut: it is tried again at startup
+    let mut settings = Settings::load()?;
     settings.hotkey_toggle_timer = hotkeys.toggle_timer;
     settings.hotkey_reset_timer = hotkeys.reset_timer;
     settings.hotkey_manual_snapshot = hotkeys.manual_snapshot;
     settings.hotkey_toggle_overlay = hotkeys.toggle_overlay;
     settings.hotkey_toggle_overlay_lock = hotkeys.toggle_overlay_lock;
     settings.hotkey_manual_split = hotkeys.manual_split;
-    Settings::save(&settings).map_err(|e| e.to_string())?;
+    settings.hotkey_undo_split = hotkeys.undo_split;
+    settings.hotkey_skip_split = hotkeys.skip_split;
+    Settings::save(&settings)?;
+
+    Ok(registrations)
+}
+
+// ============================================================================
+// Main Window Commands
+// ============================================================================
+
+/// Show, restore, and focus the main window. Coming back from hidden, it gets
+/// `main-window-restored` with the run clock, splits and skipped breakpoints:
+/// its timer kept going, but a hidden webview's timers are throttled and
+/// events it missed while suspended would leave its splits behind.
+pub(crate) fn focus_main_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let was_hidden = !window.is_visible().unwrap_or(true);
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        if was_hidden {
+            let resync = breakpoint_engine::resync().unwrap_or_else(|e| {
+                eprintln!("[window] Failed to read the run for resync: {}", e);
+                None
+            });
+            let _ = window.emit("main-window-restored", resync);
+        }
+    }
+}
+
+/// Close the overlays and exit the process so it doesn't linger
+pub(crate) fn quit(app_handle: &AppHandle) {
+    for (_, overlay) in overlay_windows(app_handle) {
+        let _ = overlay.close();
+    }
+    app_handle.exit(0);
+}
+
+/// Bring the main window back from the tray
+#[tauri::command]
+pub async fn show_main_window(app_handle: AppHandle) -> AppResult<()> {
+    focus_main_window(&app_handle);
+    Ok(())
+}
+
+/// Hide the main window in the tray; the log watcher and overlays keep running
+#[tauri::command]
+pub async fn hide_to_tray(app_handle: AppHandle) -> AppResult<()> {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        window.hide()?;
+    }
+    Ok(())
+}
 
+#[tauri::command]
+pub async fn quit_app(app_handle: AppHandle) -> AppResult<()> {
+    quit(&app_handle);
     Ok(())
 }
 
@@ -796,18 +3650,49 @@ pub async fn update_hotkeys(app_handle: AppHandle, hotkeys: HotkeySettings) -> R
 // Overlay Commands
 // ============================================================================
 
+/// Window label of the main overlay (`None`) or of a profile's
+fn overlay_label(profile_id: Option<&str>) -> String {
+    match profile_id {
+        Some(id) => format!("overlay-{}", id),
+        None => "overlay".to_string(),
+    }
+}
+
+/// Open overlay windows with their profile ids, `None` for the main overlay
+fn overlay_windows(app_handle: &AppHandle) -> Vec<(Option<String>, WebviewWindow)> {
+    app_handle
+        .webview_windows()
+        .into_iter()
+        .filter_map(|(label, window)| match label.strip_prefix("overlay-") {
+            Some(id) => Some((Some(id.to_string()), window)),
+            None if label == "overlay" => Some((None, window)),
+            None => None,
+        })
+        .collect()
+}
+
+/// `state` as a profile's overlay shows it: the profile's own opacity, when
+/// it has one, replaces the shared setting
+pub(crate) fn overlay_state_for(state: &serde_json::Value, profile: Option<&OverlayProfile>) -> serde_json::Value {
+    let mut state = state.clone();
+    if let (Some(opacity), Some(fields)) = (profile.and_then(|p| p.opacity), state.as_object_mut()) {
+        fields.insert("opacity".to_string(), opacity.into());
+    }
+    state
+}
+
+/// Open the main overlay, or with `profile_id` one of the extra overlay windows
 #[tauri::command]
-pub async fn open_overlay(app_handle: AppHandle) -> Result<(), String> {
-    // Check if overlay already exists
-    if app_handle.get_webview_window("overlay").is_some() {
-        if let Some(window) = app_handle.get_webview_window("overlay") {
-            window.set_focus().map_err(|e| e.to_string())?;
-        }
+pub async fn open_overlay(app_handle: AppHandle, profile_id: Option<String>) -> AppResult<()> {
+    let label = overlay_label(profile_id.as_deref());
+
+    // Check if overlay already exists; it may be hidden while the game isn't running
+    if let Some(window) = app_handle.get_webview_window(&label) {
+        window.show()?;
+        window.set_focus()?;
         return Ok(());
     }
 
-    // Load saved position and settings
-    let (saved_x, saved_y) = Settings::get_overlay_position().unwrap_or((None, None));
     let settings = Settings::load().unwrap_or_default();
 
     // Determine size from scale setting
@@ -817,99 +3702,228 @@ pub async fn open_overlay(app_handle: AppHandle) -> Result<(), String> {
         _ => (320.0, 180.0), // medium (default)
     };
 
-    // Build the overlay window
+    // Load saved position, and for a profile its size
+    let (title, (saved_x, saved_y), (width, height)) = match &profile_id {
+        Some(id) => {
+            let profile = OverlayProfile::get(id)?
+                .ok_or_else(|| AppError::NotFound(format!("Overlay profile {}", id)))?;
+            (
+                format!("POE Watcher Overlay - {}", profile.name),
+                (profile.x, profile.y),
+                profile.width.zip(profile.height).unwrap_or((width, height)),
+            )
+        }
+        None => (
+            "POE Watcher Overlay".to_string(),
+            Settings::get_overlay_position().unwrap_or((None, None)),
+            (width, height),
+        ),
+    };
+
+    // Build the overlay window. Profile windows are sized by hand instead
+    // of following the scale setting.
     let mut builder = WebviewWindowBuilder::new(
         &app_handle,
-        "overlay",
+        &label,
         WebviewUrl::App("overlay.html".into()),
     )
-    .title("POE Watcher Overlay")
+    .title(title)
     .inner_size(width, height)
     .decorations(false)
     .transparent(true)
     .always_on_top(settings.overlay_always_on_top)
     .skip_taskbar(true)
-    .resizable(false);
+    .resizable(profile_id.is_some());
 
     // Set position if saved
     if let (Some(x), Some(y)) = (saved_x, saved_y) {
         builder = builder.position(x as f64, y as f64);
     }
 
-    builder.build().map_err(|e| e.to_string())?;
+    builder.build()?;
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn close_overlay(app_handle: AppHandle) -> Result<(), String> {
-    if let Some(window) = app_handle.get_webview_window("overlay") {
-        window.close().map_err(|e| e.to_string())?;
+pub async fn close_overlay(app_handle: AppHandle, profile_id: Option<String>) -> AppResult<()> {
+    if let Some(window) = app_handle.get_webview_window(&overlay_label(profile_id.as_deref())) {
+        window.close()?;
     }
     // Notify main window that overlay was closed
-    let _ = app_handle.emit("overlay-closed", ());
+    let _ = app_handle.emit("overlay-closed", profile_id);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn toggle_overlay(app_handle: AppHandle) -> Result<bool, String> {
-    if let Some(window) = app_handle.get_webview_window("overlay") {
-        // Window exists - close it
-        window.close().map_err(|e| e.to_string())?;
+pub async fn toggle_overlay(app_handle: AppHandle, profile_id: Option<String>) -> AppResult<bool> {
+    let window = app_handle.get_webview_window(&overlay_label(profile_id.as_deref()));
+    if let Some(window) = window.filter(|window| window.is_visible().unwrap_or(true)) {
+        // Window is showing - close it
+        window.close()?;
         Ok(false)
     } else {
-        // Window doesn't exist - open it
-        open_overlay(app_handle).await?;
+        // Window doesn't exist or was hidden with the game - open it
+        open_overlay(app_handle, profile_id).await?;
         Ok(true)
     }
 }
 
 #[tauri::command]
-pub async fn set_overlay_position(x: i32, y: i32) -> Result<(), String> {
-    Settings::save_overlay_position(x, y).map_err(|e| e.to_string())?;
+pub async fn set_overlay_position(x: i32, y: i32, profile_id: Option<String>) -> AppResult<()> {
+    match profile_id {
+        Some(id) => OverlayProfile::save_position(&id, x, y)?,
+        None => Settings::save_overlay_position(x, y)?,
+    }
+    Ok(())
+}
+
+/// Remember the size a profile's overlay window was resized to
+#[tauri::command]
+pub async fn set_overlay_size(profile_id: String, width: f64, height: f64) -> AppResult<()> {
+    OverlayProfile::save_size(&profile_id, width, height)?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_overlay_position() -> Result<(Option<i32>, Option<i32>), String> {
-    Settings::get_overlay_position().map_err(|e| e.to_string())
+pub async fn get_overlay_position() -> AppResult<(Option<i32>, Option<i32>)> {
+    Settings::get_overlay_position().map_err(AppError::from)
 }
 
 #[tauri::command]
-pub async fn sync_overlay_state(app_handle: AppHandle, state: serde_json::Value) -> Result<(), String> {
-    if app_handle.get_webview_window("overlay").is_some() {
-        app_handle.emit_to("overlay", "overlay-state-update", state).map_err(|e| e.to_string())?;
+pub async fn sync_overlay_state(app_handle: AppHandle, state: serde_json::Value) -> AppResult<()> {
+    // The phone status page and OBS browser sources read the same state
+    status_server::update_live_state(state.clone());
+    obs_server::publish(&state);
+
+    let overlays = overlay_windows(&app_handle);
+    let profiles = if overlays.iter().any(|(id, _)| id.is_some()) {
+        OverlayProfile::get_all()?
+    } else {
+        Vec::new()
+    };
+
+    // A hidden or minimized overlay catches up from the next sync once shown
+    for (profile_id, overlay) in overlays {
+        let shown = overlay.is_visible().unwrap_or(true) && !overlay.is_minimized().unwrap_or(false);
+        if shown {
+            let profile = profile_id.and_then(|id| profiles.iter().find(|p| p.id == id));
+            app_handle.emit_to(overlay.label(), "overlay-state-update", overlay_state_for(&state, profile))?;
+        }
     }
     Ok(())
 }
 
 #[tauri::command]
-pub async fn overlay_ready(app_handle: AppHandle) -> Result<(), String> {
-    app_handle.emit_to("main", "overlay-ready", ()).map_err(|e| e.to_string())?;
+pub async fn overlay_ready(app_handle: AppHandle) -> AppResult<()> {
+    app_handle.emit_to("main", "overlay-ready", ())?;
+    Ok(())
+}
+
+/// Resize the overlay window that asks
+#[tauri::command]
+pub async fn resize_overlay(window: WebviewWindow, width: f64, height: f64) -> AppResult<()> {
+    window.set_size(LogicalSize::new(width, height))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_overlay_always_on_top(window: WebviewWindow, enabled: bool) -> AppResult<()> {
+    window.set_always_on_top(enabled)?;
     Ok(())
 }
 
+/// Move every overlay back onto the primary monitor, profiles staggered so
+/// they don't stack
 #[tauri::command]
-pub async fn resize_overlay(app_handle: AppHandle, width: f64, height: f64) -> Result<(), String> {
+pub async fn reset_overlay_position(app_handle: AppHandle) -> AppResult<()> {
     if let Some(overlay) = app_handle.get_webview_window("overlay") {
-        overlay.set_size(LogicalSize::new(width, height)).map_err(|e| e.to_string())?;
+        overlay.set_position(tauri::LogicalPosition::new(100.0, 100.0))?;
+    }
+    Settings::save_overlay_position(100, 100)?;
+
+    for (i, profile) in OverlayProfile::get_all()?.iter().enumerate() {
+        let offset = 100 + 40 * (i as i32 + 1);
+        if let Some(overlay) = app_handle.get_webview_window(&overlay_label(Some(&profile.id))) {
+            overlay.set_position(tauri::LogicalPosition::new(offset as f64, offset as f64))?;
+        }
+        OverlayProfile::save_position(&profile.id, offset, offset)?;
     }
     Ok(())
 }
 
+/// Which parts of the overlay are shown
 #[tauri::command]
-pub async fn set_overlay_always_on_top(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
-    if let Some(overlay) = app_handle.get_webview_window("overlay") {
-        overlay.set_always_on_top(enabled).map_err(|e| e.to_string())?;
+pub async fn get_overlay_layout() -> AppResult<OverlayComponents> {
+    Ok(OverlayComponents::load()?)
+}
+
+/// Save the overlay layout and push it to every open overlay window
+#[tauri::command]
+pub async fn set_overlay_layout(app_handle: AppHandle, layout: OverlayComponents) -> AppResult<()> {
+    validate_overlay_layout(&layout)?;
+    OverlayComponents::save(&layout)?;
+    for (_, overlay) in overlay_windows(&app_handle) {
+        app_handle.emit_to(overlay.label(), "overlay-layout-changed", &layout)?;
+    }
+    Ok(())
+}
+
+/// An overlay showing nothing, or a delta without the split it belongs to, is
+/// almost certainly a mistake
+pub(crate) fn validate_overlay_layout(layout: &OverlayComponents) -> AppResult<()> {
+    let OverlayComponents { timer, current_split, delta, next_split, death_count, level_pace } = *layout;
+    if !(timer || current_split || delta || next_split || death_count || level_pace) {
+        return Err(AppError::InvalidInput("The overlay layout must show at least one component".to_string()));
+    }
+    if delta && !current_split {
+        return Err(AppError::InvalidInput("The delta is shown with the current split".to_string()));
     }
     Ok(())
 }
 
+// ============================================================================
+// Overlay Profile Commands
+// ============================================================================
+
 #[tauri::command]
-pub async fn reset_overlay_position(app_handle: AppHandle) -> Result<(), String> {
-    if let Some(overlay) = app_handle.get_webview_window("overlay") {
-        overlay.set_position(tauri::LogicalPosition::new(100.0, 100.0)).map_err(|e| e.to_string())?;
+pub async fn get_overlay_profiles() -> AppResult<Vec<OverlayProfile>> {
+    Ok(OverlayProfile::get_all()?)
+}
+
+#[tauri::command]
+pub async fn create_overlay_profile(name: String) -> AppResult<OverlayProfile> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Overlay profile name is empty".to_string()));
+    }
+    Ok(OverlayProfile::create(&name)?)
+}
+
+/// Rename a profile or change its opacity
+#[tauri::command]
+pub async fn update_overlay_profile(profile: OverlayProfile) -> AppResult<()> {
+    if profile.name.trim().is_empty() {
+        return Err(AppError::InvalidInput("Overlay profile name is empty".to_string()));
+    }
+    if let Some(opacity) = profile.opacity {
+        if !(0.1..=1.0).contains(&opacity) {
+            return Err(AppError::InvalidInput(format!("Opacity {} is outside 0.1-1.0", opacity)));
+        }
+    }
+    if OverlayProfile::save(&profile)? == 0 {
+        return Err(AppError::NotFound(format!("Overlay profile {}", profile.id)));
+    }
+    Ok(())
+}
+
+/// Delete a profile, closing its window if it's open
+#[tauri::command]
+pub async fn delete_overlay_profile(app_handle: AppHandle, id: String) -> AppResult<()> {
+    if OverlayProfile::delete(&id)? == 0 {
+        return Err(AppError::NotFound(format!("Overlay profile {}", id)));
+    }
+    if let Some(window) = app_handle.get_webview_window(&overlay_label(Some(&id))) {
+        window.close()?;
     }
-    Settings::save_overlay_position(100, 100).map_err(|e| e.to_string())?;
     Ok(())
 }
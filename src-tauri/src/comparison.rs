@@ -0,0 +1,419 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::Split;
+
+/// Runs averaged by the `average` comparison when no count is given
+pub const AVERAGE_RUNS: usize = 5;
+
+/// How a split compares to the PB and golds, as LiveSplit colors it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitColor {
+    /// Best segment ever for the category/class
+    Gold,
+    /// Ahead of the PB, and the segment gained time on it
+    AheadGaining,
+    /// Still ahead of the PB, but the segment lost time on it
+    AheadLosing,
+    /// Behind the PB, but the segment won some time back
+    BehindGaining,
+    /// Behind the PB and falling further behind
+    BehindLosing,
+    /// Nothing to compare against
+    Neutral,
+}
+
+/// A split measured against the PB and golds as they stood before it. Every
+/// UI colors splits from this instead of redoing the comparison.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitComparison {
+    /// Split time minus the PB's split time for the same breakpoint
+    pub delta_ms: Option<i64>,
+    /// Time gained (negative) or lost over this segment, when an earlier split
+    /// of the run has a delta
+    pub segment_delta_ms: Option<i64>,
+    pub is_gold: bool,
+    pub color: SplitColor,
+}
+
+impl SplitComparison {
+    /// For splits with nothing to compare against (custom snapshot splits)
+    pub fn neutral() -> Self {
+        SplitComparison {
+            delta_ms: None,
+            segment_delta_ms: None,
+            is_gold: false,
+            color: SplitColor::Neutral,
+        }
+    }
+}
+
+/// Compare a split with LiveSplit's rules: a segment is gold when it beats
+/// the best segment or there isn't one yet; otherwise ahead/behind follows
+/// the sign of the delta (zero counts as behind), and gaining/losing whether
+/// it moved toward or away from the last delta the run had.
+pub fn compare(
+    split_time_ms: i64,
+    segment_time_ms: i64,
+    pb_split_ms: Option<i64>,
+    last_delta_ms: Option<i64>,
+    gold_ms: Option<i64>,
+) -> SplitComparison {
+    let delta_ms = pb_split_ms.map(|pb| split_time_ms - pb);
+    let segment_delta_ms = delta_ms.zip(last_delta_ms).map(|(delta, last)| delta - last);
+    // An empty segment (split during the countdown) is never a gold
+    let is_gold = segment_time_ms > 0 && gold_ms.is_none_or(|gold| segment_time_ms < gold);
+
+    let color = color(is_gold, delta_ms, segment_delta_ms);
+
+    SplitComparison { delta_ms, segment_delta_ms, is_gold, color }
+}
+
+fn color(is_gold: bool, delta_ms: Option<i64>, segment_delta_ms: Option<i64>) -> SplitColor {
+    match (is_gold, delta_ms, segment_delta_ms) {
+        (true, _, _) => SplitColor::Gold,
+        (false, None, _) => SplitColor::Neutral,
+        (false, Some(delta), Some(change)) if delta < 0 && change > 0 => SplitColor::AheadLosing,
+        (false, Some(delta), _) if delta < 0 => SplitColor::AheadGaining,
+        (false, Some(_), Some(change)) if change < 0 => SplitColor::BehindGaining,
+        (false, Some(_), _) => SplitColor::BehindLosing,
+    }
+}
+
+/// What `get_comparison` measures a run against
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ComparisonKind {
+    /// The category/class PB run
+    PersonalBest,
+    /// Gold splits, with the sum of best as split times
+    Gold,
+    /// The mean of the last `count` completed runs of the category/class
+    Average {
+        #[serde(default = "default_average_runs")]
+        count: usize,
+    },
+    /// Any other run, usually a reference run
+    Reference {
+        #[serde(rename = "runId")]
+        run_id: i64,
+    },
+}
+
+fn default_average_runs() -> usize {
+    AVERAGE_RUNS
+}
+
+/// One split of a comparison: the times to beat
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonPoint {
+    pub breakpoint_name: String,
+    pub breakpoint_key: Option<String>,
+    pub split_time_ms: Option<i64>,
+    pub segment_time_ms: Option<i64>,
+    /// Loading screen time up to the split; unknown for golds
+    pub load_time_ms: Option<i64>,
+}
+
+impl ComparisonPoint {
+    /// Split time without loading screens, when both are known
+    pub fn load_removed_ms(&self) -> Option<i64> {
+        self.split_time_ms.zip(self.load_time_ms).map(|(split_ms, load_ms)| (split_ms - load_ms).max(0))
+    }
+}
+
+/// A run's split next to the comparison's. Splits the comparison has but the
+/// run hasn't reached come last, with only the comparison times.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonRow {
+    pub breakpoint_name: String,
+    pub breakpoint_key: Option<String>,
+    pub split_time_ms: Option<i64>,
+    pub segment_time_ms: Option<i64>,
+    pub comparison_split_ms: Option<i64>,
+    pub comparison_segment_ms: Option<i64>,
+    /// Run split time minus the comparison's
+    pub delta_ms: Option<i64>,
+    /// Change in delta since the last split that had one
+    pub segment_delta_ms: Option<i64>,
+    /// The segment is (or ties) the current gold
+    pub is_gold: bool,
+    pub color: SplitColor,
+    /// Split times without loading screens, and the delta between them
+    pub load_removed_split_ms: Option<i64>,
+    pub comparison_load_removed_ms: Option<i64>,
+    pub load_removed_delta_ms: Option<i64>,
+}
+
+/// Splits are the same breakpoint when their keys match, or by name when
+/// either has no key (custom splits, runs from before keys)
+fn same_split(name: &str, key: Option<&str>, other_name: &str, other_key: Option<&str>) -> bool {
+    match (key, other_key) {
+        (Some(key), Some(other_key)) => key == other_key,
+        _ => name == other_name,
+    }
+}
+
+/// A recorded run as a comparison
+pub fn from_run(splits: &[Split]) -> Vec<ComparisonPoint> {
+    splits
+        .iter()
+        .map(|split| ComparisonPoint {
+            breakpoint_name: split.breakpoint_name.clone(),
+            breakpoint_key: split.breakpoint_key.clone(),
+            split_time_ms: Some(split.split_time_ms),
+            segment_time_ms: Some(split.segment_time_ms),
+            load_time_ms: Some(split.load_time_ms),
+        })
+        .collect()
+}
+
+/// Golds for `splits` (in order) as a comparison. Split times are the running
+/// sum of best, unknown from the first split without a gold.
+pub fn from_golds(splits: &[Split], golds: &[Option<i64>]) -> Vec<ComparisonPoint> {
+    let mut sum_of_best = Some(0);
+    splits
+        .iter()
+        .zip(golds)
+        .map(|(split, gold)| {
+            sum_of_best = sum_of_best.zip(*gold).map(|(sum, gold)| sum + gold);
+            ComparisonPoint {
+                breakpoint_name: split.breakpoint_name.clone(),
+                breakpoint_key: split.breakpoint_key.clone(),
+                split_time_ms: sum_of_best,
+                segment_time_ms: *gold,
+                load_time_ms: None,
+            }
+        })
+        .collect()
+}
+
+/// The mean split and segment times of `runs`, each split averaged over the
+/// runs that reached it, in the order the runs first reach them
+pub fn average(runs: &[Vec<Split>]) -> Vec<ComparisonPoint> {
+    let mut totals: Vec<(ComparisonPoint, i64, i64, i64, i64)> = Vec::new();
+    for split in runs.iter().flatten() {
+        let position = totals.iter().position(|(point, ..)| {
+            same_split(
+                &point.breakpoint_name,
+                point.breakpoint_key.as_deref(),
+                &split.breakpoint_name,
+                split.breakpoint_key.as_deref(),
+            )
+        });
+        let index = position.unwrap_or_else(|| {
+            totals.push((
+                ComparisonPoint {
+                    breakpoint_name: split.breakpoint_name.clone(),
+                    breakpoint_key: split.breakpoint_key.clone(),
+                    split_time_ms: None,
+                    segment_time_ms: None,
+                    load_time_ms: None,
+                },
+                0,
+                0,
+                0,
+                0,
+            ));
+            totals.len() - 1
+        });
+        let (_, split_total, segment_total, load_total, count) = &mut totals[index];
+        *split_total += split.split_time_ms;
+        *segment_total += split.segment_time_ms;
+        *load_total += split.load_time_ms;
+        *count += 1;
+    }
+    totals
+        .into_iter()
+        .map(|(point, split_total, segment_total, load_total, count)| ComparisonPoint {
+            split_time_ms: Some(split_total / count),
+            segment_time_ms: Some(segment_total / count),
+            load_time_ms: Some(load_total / count),
+            ..point
+        })
+        .collect()
+}
+
+/// Line a run's splits up with a comparison. `golds` holds the current best
+/// segment for each of `splits`.
+pub fn rows(splits: &[Split], comparison: &[ComparisonPoint], golds: &[Option<i64>]) -> Vec<ComparisonRow> {
+    let mut matched = vec![false; comparison.len()];
+    let mut last_delta_ms = None;
+    let mut rows: Vec<ComparisonRow> = splits
+        .iter()
+        .enumerate()
+        .map(|(index, split)| {
+            let position = comparison.iter().enumerate().position(|(i, point)| {
+                !matched[i]
+                    && same_split(
+                        &split.breakpoint_name,
+                        split.breakpoint_key.as_deref(),
+                        &point.breakpoint_name,
+                        point.breakpoint_key.as_deref(),
+                    )
+            });
+            let point = position.map(|i| {
+                matched[i] = true;
+                &comparison[i]
+            });
+            let comparison_split_ms = point.and_then(|p| p.split_time_ms);
+            let delta_ms = comparison_split_ms.map(|ms| split.split_time_ms - ms);
+            let segment_delta_ms = delta_ms.zip(last_delta_ms).map(|(delta, last)| delta - last);
+            last_delta_ms = delta_ms.or(last_delta_ms);
+            let is_gold = split.segment_time_ms > 0
+                && golds.get(index).copied().flatten().is_some_and(|gold| split.segment_time_ms <= gold);
+            let load_removed_split_ms = split.load_removed_time_ms();
+            let comparison_load_removed_ms = point.and_then(ComparisonPoint::load_removed_ms);
+            ComparisonRow {
+                breakpoint_name: split.breakpoint_name.clone(),
+                breakpoint_key: split.breakpoint_key.clone(),
+                split_time_ms: Some(split.split_time_ms),
+                segment_time_ms: Some(split.segment_time_ms),
+                comparison_split_ms,
+                comparison_segment_ms: point.and_then(|p| p.segment_time_ms),
+                delta_ms,
+                segment_delta_ms,
+                is_gold,
+                color: color(is_gold, delta_ms, segment_delta_ms),
+                load_removed_split_ms: Some(load_removed_split_ms),
+                comparison_load_removed_ms,
+                load_removed_delta_ms: comparison_load_removed_ms.map(|ms| load_removed_split_ms - ms),
+            }
+        })
+        .collect();
+
+    // What's left of the comparison is still ahead of the run
+    rows.extend(comparison.iter().zip(&matched).filter(|(_, matched)| !**matched).map(|(point, _)| {
+        ComparisonRow {
+            breakpoint_name: point.breakpoint_name.clone(),
+            breakpoint_key: point.breakpoint_key.clone(),
+            split_time_ms: None,
+            segment_time_ms: None,
+            comparison_split_ms: point.split_time_ms,
+            comparison_segment_ms: point.segment_time_ms,
+            delta_ms: None,
+            segment_delta_ms: None,
+            is_gold: false,
+            color: SplitColor::Neutral,
+            load_removed_split_ms: None,
+            comparison_load_removed_ms: point.load_removed_ms(),
+            load_removed_delta_ms: None,
+        }
+    }));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_livesplit_colors() {
+        let color = |split, pb, last, gold| compare(split, 60_000, pb, last, gold).color;
+
+        // Golds win over the delta, including the first time a segment is run
+        assert_eq!(color(100_000, Some(90_000), None, Some(70_000)), SplitColor::Gold);
+        assert_eq!(color(100_000, None, None, None), SplitColor::Gold);
+        assert!(!compare(0, 0, None, None, None).is_gold);
+
+        assert_eq!(color(100_000, None, None, Some(50_000)), SplitColor::Neutral);
+        // The first delta of the run has nothing to gain or lose against
+        assert_eq!(color(95_000, Some(100_000), None, Some(50_000)), SplitColor::AheadGaining);
+        assert_eq!(color(105_000, Some(100_000), None, Some(50_000)), SplitColor::BehindLosing);
+        assert_eq!(color(100_000, Some(100_000), None, Some(50_000)), SplitColor::BehindLosing);
+
+        assert_eq!(color(95_000, Some(100_000), Some(-8_000), Some(50_000)), SplitColor::AheadLosing);
+        assert_eq!(color(95_000, Some(100_000), Some(-2_000), Some(50_000)), SplitColor::AheadGaining);
+        assert_eq!(color(105_000, Some(100_000), Some(9_000), Some(50_000)), SplitColor::BehindGaining);
+        assert_eq!(color(105_000, Some(100_000), Some(1_000), Some(50_000)), SplitColor::BehindLosing);
+
+        let comparison = compare(95_000, 60_000, Some(100_000), Some(-8_000), Some(50_000));
+        assert_eq!((comparison.delta_ms, comparison.segment_delta_ms), (Some(-5_000), Some(3_000)));
+    }
+
+    fn split(name: &str, key: Option<&str>, split_time_ms: i64, segment_time_ms: i64) -> Split {
+        Split {
+            id: 0,
+            run_id: 1,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: key.map(str::to_string),
+            split_time_ms,
+            delta_ms: None,
+            segment_time_ms,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+            segment_town_time_ms: 0,
+            segment_hideout_time_ms: 0,
+            layout_variant: None,
+            layout_inferred: false,
+            experience: None,
+            character_level: None,
+            load_time_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_comparison_rows() {
+        let pb = vec![
+            split("The Coast", Some("zone:a1:coast"), 60_000, 60_000),
+            split("Mud Flats", Some("zone:a1:mud_flats"), 110_000, 50_000),
+            split("The Tidal Island", None, 150_000, 40_000),
+        ];
+        // Renamed in this run's preset, but the key matches
+        let run = vec![
+            split("The Coast", Some("zone:a1:coast"), 62_000, 62_000),
+            split("The Mud Flats", Some("zone:a1:mud_flats"), 107_000, 45_000),
+        ];
+        let rows = rows(&run, &from_run(&pb), &[Some(58_000), Some(45_000)]);
+
+        let deltas: Vec<_> = rows.iter().map(|r| (r.delta_ms, r.segment_delta_ms, r.color)).collect();
+        assert_eq!(
+            deltas,
+            vec![
+                (Some(2_000), None, SplitColor::BehindLosing),
+                (Some(-3_000), Some(-5_000), SplitColor::Gold),
+                (None, None, SplitColor::Neutral),
+            ]
+        );
+        assert_eq!(rows[2].breakpoint_name, "The Tidal Island");
+        assert_eq!((rows[2].split_time_ms, rows[2].comparison_split_ms), (None, Some(150_000)));
+
+        // Sum of best stops at the first split without a gold
+        let golds = from_golds(&pb, &[Some(58_000), Some(45_000), None]);
+        let sums: Vec<_> = golds.iter().map(|p| p.split_time_ms).collect();
+        assert_eq!(sums, vec![Some(58_000), Some(103_000), None]);
+
+        let other = vec![
+            split("The Coast", Some("zone:a1:coast"), 70_000, 70_000),
+            split("Mud Flats", Some("zone:a1:mud_flats"), 130_000, 60_000),
+        ];
+        let mean = average(&[pb.clone(), other]);
+        let times: Vec<_> = mean.iter().map(|p| (p.split_time_ms, p.segment_time_ms)).collect();
+        assert_eq!(times, vec![(Some(65_000), Some(65_000)), (Some(120_000), Some(55_000)), (Some(150_000), Some(40_000))]);
+
+        // Slower with loads, but ahead once they're removed
+        let loaded = |split: Split, load_time_ms: i64| Split { load_time_ms, ..split };
+        let pb = vec![loaded(split("The Coast", None, 60_000, 60_000), 5_000)];
+        let run = vec![loaded(split("The Coast", None, 62_000, 62_000), 9_000)];
+        let row = &super::rows(&run, &from_run(&pb), &[])[0];
+        assert_eq!(row.delta_ms, Some(2_000));
+        assert_eq!(
+            (row.load_removed_split_ms, row.comparison_load_removed_ms, row.load_removed_delta_ms),
+            (Some(53_000), Some(55_000), Some(-2_000))
+        );
+        // Golds have no load times to remove
+        assert_eq!(from_golds(&pb, &[Some(58_000)])[0].load_removed_ms(), None);
+    }
+
+    #[test]
+    fn test_comparison_kind_from_json() {
+        let kind = |json: &str| serde_json::from_str::<ComparisonKind>(json).unwrap();
+        assert_eq!(kind(r#"{"kind":"personal_best"}"#), ComparisonKind::PersonalBest);
+        assert_eq!(kind(r#"{"kind":"average"}"#), ComparisonKind::Average { count: AVERAGE_RUNS });
+        assert_eq!(kind(r#"{"kind":"average","count":10}"#), ComparisonKind::Average { count: 10 });
+        assert_eq!(kind(r#"{"kind":"reference","runId":7}"#), ComparisonKind::Reference { run_id: 7 });
+    }
+}
@@ -0,0 +1,135 @@
+use rusqlite::{params, Connection};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{get_db, Run, RunPage};
+
+/// Tables holding a run and everything recorded for it, parents first, each
+/// with the condition picking the rows of the runs in `temp.moving_runs`.
+/// `{db}` is the database the rows are read from.
+const RUN_TABLES: &[(&str, &str)] = &[
+    ("runs", "id IN (SELECT id FROM temp.moving_runs)"),
+    ("splits", "run_id IN (SELECT id FROM temp.moving_runs)"),
+    ("split_notes", "split_id IN (SELECT id FROM {db}.splits WHERE run_id IN (SELECT id FROM temp.moving_runs))"),
+    ("run_tags", "run_id IN (SELECT id FROM temp.moving_runs)"),
+    ("snapshots", "run_id IN (SELECT id FROM temp.moving_runs)"),
+    ("quest_progress", "run_id IN (SELECT id FROM temp.moving_runs)"),
+    ("run_events", "run_id IN (SELECT id FROM temp.moving_runs)"),
+    ("zone_times", "run_id IN (SELECT id FROM temp.moving_runs)"),
+    ("deaths", "run_id IN (SELECT id FROM temp.moving_runs)"),
+    ("skipped_splits", "run_id IN (SELECT id FROM temp.moving_runs)"),
+    ("pauses", "run_id IN (SELECT id FROM temp.moving_runs)"),
+];
+
+/// What `archive_runs_older_than` moved into the archive database
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveReport {
+    pub runs_archived: usize,
+    pub snapshots_archived: usize,
+}
+
+/// Create the archive's copies of the run tables, and add the columns later
+/// migrations gave the main ones. The archive has no constraints; rows only
+/// pass through it.
+pub(super) fn sync_schema(conn: &Connection) -> Result<()> {
+    for (table, _) in RUN_TABLES {
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS archive.{table} AS SELECT * FROM main.{table} WHERE 0"
+        ))?;
+        let archived: Vec<String> = columns(conn, "archive", table)?.into_iter().map(|(name, _)| name).collect();
+        for (name, kind) in columns(conn, "main", table)? {
+            if !archived.contains(&name) {
+                conn.execute_batch(&format!("ALTER TABLE archive.{table} ADD COLUMN \"{name}\" {kind}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Name and declared type of each column of `schema.table`
+fn columns(conn: &Connection, schema: &str, table: &str) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {schema}.table_info({table})"))?;
+    let columns = stmt
+        .query_map([], |row| Ok((row.get(1)?, row.get(2)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(columns)
+}
+
+/// Copy the runs in `temp.moving_runs` and their rows from one database to
+/// the other, then delete them from the first
+fn move_runs(conn: &Connection, from: &str, to: &str) -> Result<()> {
+    for (table, condition) in RUN_TABLES {
+        // The main table's columns: the archive has them all after `sync_schema`
+        let columns = columns(conn, "main", table)?
+            .into_iter()
+            .map(|(name, _)| format!("\"{name}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let condition = condition.replace("{db}", from);
+        conn.execute(
+            &format!("INSERT INTO {to}.{table} ({columns}) SELECT {columns} FROM {from}.{table} WHERE {condition}"),
+            [],
+        )?;
+    }
+    // Children first, while the split notes can still find their splits
+    for (table, condition) in RUN_TABLES.iter().rev() {
+        let condition = condition.replace("{db}", from);
+        conn.execute(&format!("DELETE FROM {from}.{table} WHERE {condition}"), [])?;
+    }
+    Ok(())
+}
+
+/// Move runs started before `before` (a date or datetime), with their splits,
+/// snapshots and events, into the archive database. Reference runs and runs
+/// holding a personal best stay. Runs in a single transaction.
+pub fn archive_runs_older_than(before: &str) -> Result<ArchiveReport> {
+    let mut conn = get_db()?;
+    let tx = conn.transaction()?;
+    tx.execute("CREATE TEMP TABLE moving_runs (id INTEGER PRIMARY KEY)", [])?;
+    let runs_archived = tx.execute(
+        "INSERT INTO temp.moving_runs
+         SELECT id FROM main.runs
+         WHERE julianday(started_at) < julianday(?1) AND is_reference = 0
+           AND id NOT IN (SELECT run_id FROM main.personal_bests)",
+        params![before],
+    )?;
+    let snapshots_archived: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM main.snapshots WHERE run_id IN (SELECT id FROM temp.moving_runs)",
+        [],
+        |row| row.get(0),
+    )?;
+    move_runs(&tx, "main", "archive")?;
+    tx.execute("DROP TABLE temp.moving_runs", [])?;
+    tx.commit()?;
+    Ok(ArchiveReport { runs_archived, snapshots_archived: snapshots_archived as usize })
+}
+
+/// One page of the archived runs, newest first, with how many there are
+pub fn get_archived_paged(offset: i64, limit: i64) -> Result<RunPage> {
+    let conn = get_db()?;
+    let total = conn.query_row("SELECT COUNT(*) FROM archive.runs", [], |row| row.get(0))?;
+    let mut stmt = conn.prepare("SELECT * FROM archive.runs ORDER BY started_at DESC LIMIT ?1 OFFSET ?2")?;
+    let runs = stmt
+        .query_map(params![limit.max(0), offset.max(0)], Run::from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(RunPage { runs, total })
+}
+
+/// Move an archived run and its rows back into the main database. Returns
+/// whether the archive had the run.
+pub fn restore_archived_run(run_id: i64) -> Result<bool> {
+    let mut conn = get_db()?;
+    let tx = conn.transaction()?;
+    tx.execute("CREATE TEMP TABLE moving_runs (id INTEGER PRIMARY KEY)", [])?;
+    let found = tx.execute(
+        "INSERT INTO temp.moving_runs SELECT id FROM archive.runs WHERE id = ?1",
+        params![run_id],
+    )?;
+    move_runs(&tx, "archive", "main")?;
+    tx.execute("DROP TABLE temp.moving_runs", [])?;
+    tx.commit()?;
+    Ok(found > 0)
+}
@@ -0,0 +1,142 @@
+use rusqlite::{params, Connection};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::compress::{pack, unpack, MIN_PACKED_LEN};
+use super::{get_db, Settings};
+
+/// Snapshot JSON columns, which `compact` re-stores compressed
+const JSON_COLUMNS: [&str; 4] = ["items_json", "skills_json", "passive_tree_json", "stats_json"];
+
+/// Runs whose snapshots are never pruned: reference runs, runs holding a
+/// personal best, and the run in progress
+const PROTECTED_RUNS: &str = "SELECT id FROM runs
+     WHERE is_reference = 1 OR ended_at IS NULL OR id IN (SELECT run_id FROM personal_bests)";
+
+/// Which snapshots `compact` keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotRetention {
+    All,
+    /// Each run's final build, or its last snapshot when none was marked final
+    FinalOnly,
+    /// Every snapshot of the N most recently started runs, none of older ones
+    LastRuns(i64),
+}
+
+impl SnapshotRetention {
+    /// The policy a `snapshot_retention` setting names, None for an unknown one
+    pub fn parse(value: &str, runs: i64) -> Option<SnapshotRetention> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "all" => Some(SnapshotRetention::All),
+            "final" => Some(SnapshotRetention::FinalOnly),
+            "recent" if runs >= 1 => Some(SnapshotRetention::LastRuns(runs)),
+            _ => None,
+        }
+    }
+
+    /// The policy the saved settings ask for; anything unknown keeps all
+    pub fn from_settings(settings: &Settings) -> SnapshotRetention {
+        SnapshotRetention::parse(&settings.snapshot_retention, settings.snapshot_retention_runs)
+            .unwrap_or(SnapshotRetention::All)
+    }
+}
+
+/// What `compact` pruned and compressed, and the database size around it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactReport {
+    pub snapshots_pruned: usize,
+    /// Snapshots written before compression whose JSON was compressed now
+    pub snapshots_compressed: usize,
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+}
+
+/// Delete the snapshots `retention` doesn't keep, compress the JSON of ones
+/// stored as text, then VACUUM so the file actually shrinks. Pruning and
+/// compression run in a single transaction; VACUUM can't run in one.
+pub fn compact(retention: SnapshotRetention) -> Result<CompactReport> {
+    let mut conn = get_db()?;
+    let bytes_before = database_bytes(&conn)?;
+
+    let tx = conn.transaction()?;
+    let snapshots_pruned = prune(&tx, retention)?;
+    let snapshots_compressed = compress_text_rows(&tx)?;
+    tx.commit()?;
+
+    conn.execute_batch("VACUUM")?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    let bytes_after = database_bytes(&conn)?;
+
+    Ok(CompactReport { snapshots_pruned, snapshots_compressed, bytes_before, bytes_after })
+}
+
+fn database_bytes(conn: &Connection) -> Result<i64> {
+    let bytes = conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(bytes)
+}
+
+fn prune(conn: &Connection, retention: SnapshotRetention) -> Result<usize> {
+    let pruned = match retention {
+        SnapshotRetention::All => 0,
+        SnapshotRetention::FinalOnly => conn.execute(
+            &format!(
+                "DELETE FROM snapshots
+                 WHERE run_id NOT IN ({PROTECTED_RUNS})
+                   AND id NOT IN (
+                       SELECT id FROM (
+                           SELECT id, ROW_NUMBER() OVER (
+                               PARTITION BY run_id ORDER BY is_final DESC, elapsed_time_ms DESC, id DESC
+                           ) AS pos
+                           FROM snapshots
+                       ) WHERE pos = 1
+                   )"
+            ),
+            [],
+        )?,
+        SnapshotRetention::LastRuns(runs) => conn.execute(
+            &format!(
+                "DELETE FROM snapshots
+                 WHERE run_id NOT IN ({PROTECTED_RUNS})
+                   AND run_id NOT IN (
+                       SELECT id FROM runs WHERE is_reference = 0
+                       ORDER BY started_at DESC, id DESC LIMIT ?1
+                   )"
+            ),
+            params![runs],
+        )?,
+    };
+    Ok(pruned)
+}
+
+/// Re-store JSON written as text before compression; short JSON stays text
+fn compress_text_rows(conn: &Connection) -> Result<usize> {
+    let long_text = JSON_COLUMNS
+        .iter()
+        .map(|column| format!("(typeof({column}) = 'text' AND length({column}) >= {MIN_PACKED_LEN})"))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    let ids: Vec<i64> = conn
+        .prepare(&format!("SELECT id FROM snapshots WHERE {long_text}"))?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut select = conn.prepare(&format!("SELECT {} FROM snapshots WHERE id = ?1", JSON_COLUMNS.join(", ")))?;
+    let mut update = conn.prepare(
+        "UPDATE snapshots SET items_json = ?1, skills_json = ?2, passive_tree_json = ?3, stats_json = ?4
+         WHERE id = ?5",
+    )?;
+    for id in &ids {
+        // One snapshot's JSON in memory at a time; columns already compressed
+        // are unpacked and packed again unchanged
+        let json = select.query_row([id], |row| {
+            (0..JSON_COLUMNS.len()).map(|i| unpack(row.get(i)?)).collect::<rusqlite::Result<Vec<String>>>()
+        })?;
+        update.execute(params![pack(&json[0]), pack(&json[1]), pack(&json[2]), pack(&json[3]), id])?;
+    }
+    Ok(ids.len())
+}
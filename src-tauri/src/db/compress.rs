@@ -0,0 +1,59 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::types::{Type, Value};
+use std::io::{Read, Write};
+
+/// Snapshot JSON shorter than this stays text; gzip wouldn't save anything on it
+pub(super) const MIN_PACKED_LEN: usize = 256;
+
+/// A snapshot JSON column as stored: gzipped into a BLOB when it's long enough
+/// to be worth it, otherwise the text itself
+pub(super) fn pack(json: &str) -> Value {
+    if json.len() < MIN_PACKED_LEN {
+        return Value::Text(json.to_string());
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    match encoder.write_all(json.as_bytes()).and_then(|_| encoder.finish()) {
+        Ok(bytes) => Value::Blob(bytes),
+        Err(_) => Value::Text(json.to_string()),
+    }
+}
+
+/// The JSON in a stored snapshot column: text as written before compression
+/// (and short JSON since), a BLOB gunzipped
+pub(super) fn unpack(value: Value) -> rusqlite::Result<String> {
+    match value {
+        Value::Text(json) => Ok(json),
+        Value::Blob(bytes) => {
+            let mut json = String::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_string(&mut json)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, Box::new(e)))?;
+            Ok(json)
+        }
+        other => Err(rusqlite::Error::InvalidColumnType(0, "snapshot JSON".to_string(), other.data_type())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_round_trip() {
+        // Short JSON is kept as text
+        assert_eq!(pack("[]"), Value::Text("[]".to_string()));
+        assert_eq!(unpack(pack("{}")).unwrap(), "{}");
+
+        let items = format!("[{}]", vec![r#"{"name":"Wand","typeLine":"Driftwood Wand"}"#; 50].join(","));
+        let packed = pack(&items);
+        let Value::Blob(bytes) = &packed else {
+            panic!("long JSON should be compressed");
+        };
+        assert!(bytes.len() < items.len() / 4);
+        assert_eq!(unpack(packed).unwrap(), items);
+
+        assert!(unpack(Value::Blob(b"not gzip".to_vec())).is_err());
+    }
+}
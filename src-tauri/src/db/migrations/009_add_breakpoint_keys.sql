@@ -0,0 +1,188 @@
+-- Canonical breakpoint keys so differently named presets share gold splits and stats.
+-- Keys are derived from the breakpoint trigger: zone:a<act>:<zone_slug>, kitava:a<act>, level:<n>
+ALTER TABLE splits ADD COLUMN breakpoint_key TEXT;
+ALTER TABLE gold_splits ADD COLUMN breakpoint_key TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_splits_breakpoint_key ON splits(breakpoint_key);
+
+-- Backfill keys for split names used by the built-in and wizard presets.
+-- Names that appear in more than one act (e.g. The Fellshrine Ruins) are left unmapped.
+CREATE TEMP TABLE breakpoint_key_map (breakpoint_name TEXT PRIMARY KEY, breakpoint_key TEXT NOT NULL);
+INSERT INTO breakpoint_key_map (breakpoint_name, breakpoint_key) VALUES
+  ('Twilight Strand', 'zone:a1:the_twilight_strand'),
+  ('The Coast', 'zone:a1:the_coast'),
+  ('The Tidal Island', 'zone:a1:the_tidal_island'),
+  ('The Mud Flats', 'zone:a1:the_mud_flats'),
+  ('The Fetid Pool', 'zone:a1:the_fetid_pool'),
+  ('The Submerged Passage', 'zone:a1:the_submerged_passage'),
+  ('The Flooded Depths', 'zone:a1:the_flooded_depths'),
+  ('The Ledge', 'zone:a1:the_ledge'),
+  ('The Climb', 'zone:a1:the_climb'),
+  ('The Lower Prison', 'zone:a1:the_lower_prison'),
+  ('The Upper Prison', 'zone:a1:the_upper_prison'),
+  ('Prisoners Gate', 'zone:a1:prisoner_s_gate'),
+  ('The Ship Graveyard', 'zone:a1:the_ship_graveyard'),
+  ('The Ship Graveyard Cave', 'zone:a1:the_ship_graveyard_cave'),
+  ('The Cavern of Wrath', 'zone:a1:the_cavern_of_wrath'),
+  ('The Cavern of Anger', 'zone:a1:the_cavern_of_anger'),
+  ('The Southern Forest', 'zone:a2:the_southern_forest'),
+  ('The Forest Encampment', 'zone:a2:the_forest_encampment'),
+  ('The Old Fields', 'zone:a2:the_old_fields'),
+  ('The Crossroads', 'zone:a2:the_crossroads'),
+  ('The Chamber of Sins 1', 'zone:a2:the_chamber_of_sins_level_1'),
+  ('The Chamber of Sins 2', 'zone:a2:the_chamber_of_sins_level_2'),
+  ('The Riverways', 'zone:a2:the_riverways'),
+  ('The Western Forest', 'zone:a2:the_western_forest'),
+  ('The Weaver Chambers', 'zone:a2:the_weaver_s_chambers'),
+  ('The Wetlands', 'zone:a2:the_wetlands'),
+  ('The Vaal Ruins', 'zone:a2:the_vaal_ruins'),
+  ('The Northern Forest', 'zone:a2:the_northern_forest'),
+  ('The Caverns', 'zone:a2:the_caverns'),
+  ('The Ancient Pyramid', 'zone:a2:the_ancient_pyramid'),
+  ('The City of Sarn', 'zone:a3:the_city_of_sarn'),
+  ('The Sarn Encampment', 'zone:a3:the_sarn_encampment'),
+  ('The Slums', 'zone:a3:the_slums'),
+  ('The Crematorium', 'zone:a3:the_crematorium'),
+  ('The Sewers', 'zone:a3:the_sewers'),
+  ('The Marketplace', 'zone:a3:the_marketplace'),
+  ('The Catacombs', 'zone:a3:the_catacombs'),
+  ('The Battlefront', 'zone:a3:the_battlefront'),
+  ('The Docks', 'zone:a3:the_docks'),
+  ('The Solaris Temple 1', 'zone:a3:the_solaris_temple_level_1'),
+  ('The Solaris Temple 2', 'zone:a3:the_solaris_temple_level_2'),
+  ('The Ebony Barracks', 'zone:a3:the_ebony_barracks'),
+  ('The Lunaris Temple 1', 'zone:a3:the_lunaris_temple_level_1'),
+  ('The Lunaris Temple 2', 'zone:a3:the_lunaris_temple_level_2'),
+  ('The Imperial Gardens', 'zone:a3:the_imperial_gardens'),
+  ('The Library', 'zone:a3:the_library'),
+  ('The Sceptre of God', 'zone:a3:the_sceptre_of_god'),
+  ('The Upper Sceptre of God', 'zone:a3:the_upper_sceptre_of_god'),
+  ('The Aqueduct', 'zone:a4:the_aqueduct'),
+  ('Highgate (A4)', 'zone:a4:highgate'),
+  ('The Dried Lake', 'zone:a4:the_dried_lake'),
+  ('The Mines 1', 'zone:a4:the_mines_level_1'),
+  ('The Mines 2', 'zone:a4:the_mines_level_2'),
+  ('The Crystal Veins', 'zone:a4:the_crystal_veins'),
+  ('Kaoms Dream', 'zone:a4:kaom_s_dream'),
+  ('Kaoms Stronghold', 'zone:a4:kaom_s_stronghold'),
+  ('Daressos Dream', 'zone:a4:daresso_s_dream'),
+  ('The Grand Arena', 'zone:a4:the_grand_arena'),
+  ('The Belly of the Beast 1', 'zone:a4:the_belly_of_the_beast_level_1'),
+  ('The Belly of the Beast 2', 'zone:a4:the_belly_of_the_beast_level_2'),
+  ('The Harvest', 'zone:a4:the_harvest'),
+  ('The Slave Pens', 'zone:a5:the_slave_pens'),
+  ('Overseer Tower', 'zone:a5:overseer_s_tower'),
+  ('The Control Blocks', 'zone:a5:the_control_blocks'),
+  ('Oriath Square', 'zone:a5:oriath_square'),
+  ('The Templar Courts', 'zone:a5:the_templar_courts'),
+  ('The Chamber of Innocence', 'zone:a5:the_chamber_of_innocence'),
+  ('The Torched Courts', 'zone:a5:the_torched_courts'),
+  ('The Ruined Square', 'zone:a5:the_ruined_square'),
+  ('The Reliquary', 'zone:a5:the_reliquary'),
+  ('The Ossuary', 'zone:a5:the_ossuary'),
+  ('The Cathedral Rooftop', 'zone:a5:the_cathedral_rooftop'),
+  ('Kitava (Act 5)', 'kitava:a5'),
+  ('Twilight Strand (A6)', 'zone:a6:the_twilight_strand'),
+  ('Lioneyes Watch (A6)', 'zone:a6:lioneye_s_watch'),
+  ('The Coast (A6)', 'zone:a6:the_coast'),
+  ('The Mud Flats (A6)', 'zone:a6:the_mud_flats'),
+  ('The Karui Fortress', 'zone:a6:the_karui_fortress'),
+  ('The Ridge', 'zone:a6:the_ridge'),
+  ('The Lower Prison (A6)', 'zone:a6:the_lower_prison'),
+  ('Shavronne Tower', 'zone:a6:shavronne_s_tower'),
+  ('Prisoners Gate (A6)', 'zone:a6:prisoner_s_gate'),
+  ('The Western Forest (A6)', 'zone:a6:the_western_forest'),
+  ('The Riverways (A6)', 'zone:a6:the_riverways'),
+  ('The Wetlands (A6)', 'zone:a6:the_wetlands'),
+  ('The Southern Forest (A6)', 'zone:a6:the_southern_forest'),
+  ('The Cavern of Anger (A6)', 'zone:a6:the_cavern_of_anger'),
+  ('The Beacon', 'zone:a6:the_beacon'),
+  ('The Brine King Reef', 'zone:a6:the_brine_king_s_reef'),
+  ('The Broken Bridge', 'zone:a7:the_broken_bridge'),
+  ('The Crossroads (A7)', 'zone:a7:the_crossroads'),
+  ('The Crypt (A7)', 'zone:a7:the_crypt'),
+  ('The Chamber of Sins 1 (A7)', 'zone:a7:the_chamber_of_sins_level_1'),
+  ('The Chamber of Sins 2 (A7)', 'zone:a7:the_chamber_of_sins_level_2'),
+  ('The Den', 'zone:a7:the_den'),
+  ('The Ashen Fields', 'zone:a7:the_ashen_fields'),
+  ('The Northern Forest (A7)', 'zone:a7:the_northern_forest'),
+  ('The Dread Thicket', 'zone:a7:the_dread_thicket'),
+  ('The Causeway', 'zone:a7:the_causeway'),
+  ('The Vaal City', 'zone:a7:the_vaal_city'),
+  ('The Temple of Decay 1', 'zone:a7:the_temple_of_decay_level_1'),
+  ('The Temple of Decay 2', 'zone:a7:the_temple_of_decay_level_2'),
+  ('The Bridge Encampment', 'zone:a7:the_bridge_encampment'),
+  ('The Sarn Ramparts', 'zone:a8:the_sarn_ramparts'),
+  ('The Sarn Encampment (A8)', 'zone:a8:the_sarn_encampment'),
+  ('The Toxic Conduits', 'zone:a8:the_toxic_conduits'),
+  ('Doedres Cesspool', 'zone:a8:doedre_s_cesspool'),
+  ('The Quay', 'zone:a8:the_quay'),
+  ('The Grain Gate', 'zone:a8:the_grain_gate'),
+  ('The Imperial Fields', 'zone:a8:the_imperial_fields'),
+  ('The Hidden Underbelly', 'zone:a8:the_hidden_underbelly'),
+  ('The Grand Promenade', 'zone:a8:the_grand_promenade'),
+  ('The High Gardens', 'zone:a8:the_high_gardens'),
+  ('The Bath House', 'zone:a8:the_bath_house'),
+  ('The Lunaris Concourse', 'zone:a8:the_lunaris_concourse'),
+  ('The Lunaris Temple 1 (A8)', 'zone:a8:the_lunaris_temple_level_1'),
+  ('The Lunaris Temple 2 (A8)', 'zone:a8:the_lunaris_temple_level_2'),
+  ('The Solaris Concourse', 'zone:a8:the_solaris_concourse'),
+  ('The Solaris Temple 1 (A8)', 'zone:a8:the_solaris_temple_level_1'),
+  ('The Solaris Temple 2 (A8)', 'zone:a8:the_solaris_temple_level_2'),
+  ('The Harbour Bridge', 'zone:a8:the_harbour_bridge'),
+  ('The Blood Aqueduct', 'zone:a9:the_blood_aqueduct'),
+  ('Highgate (A9)', 'zone:a9:highgate'),
+  ('The Descent', 'zone:a9:the_descent'),
+  ('The Vastiri Desert', 'zone:a9:the_vastiri_desert'),
+  ('The Oasis', 'zone:a9:the_oasis'),
+  ('The Foothills', 'zone:a9:the_foothills'),
+  ('The Boiling Lake', 'zone:a9:the_boiling_lake'),
+  ('The Tunnel', 'zone:a9:the_tunnel'),
+  ('The Quarry', 'zone:a9:the_quarry'),
+  ('The Refinery', 'zone:a9:the_refinery'),
+  ('The Belly of the Beast (A9)', 'zone:a9:the_belly_of_the_beast'),
+  ('The Rotting Core', 'zone:a9:the_rotting_core'),
+  ('The Cathedral Rooftop (A10)', 'zone:a10:the_cathedral_rooftop'),
+  ('Oriath Docks', 'zone:a10:oriath_docks'),
+  ('The Ravaged Square', 'zone:a10:the_ravaged_square'),
+  ('The Torched Courts (A10)', 'zone:a10:the_torched_courts'),
+  ('The Desecrated Chambers', 'zone:a10:the_desecrated_chambers'),
+  ('The Canals', 'zone:a10:the_canals'),
+  ('The Feeding Trough', 'zone:a10:the_feeding_trough'),
+  ('Kitava (Act 10)', 'kitava:a10'),
+  ('Level 10', 'level:10'),
+  ('Level 20', 'level:20'),
+  ('Level 30', 'level:30'),
+  ('Level 40', 'level:40'),
+  ('Level 50', 'level:50'),
+  ('Level 60', 'level:60'),
+  ('Level 70', 'level:70'),
+  ('Level 80', 'level:80'),
+  ('Level 90', 'level:90'),
+  ('The Crypt Level 1', 'zone:a2:the_crypt_level_1'),
+  ('The Crypt Level 2', 'zone:a2:the_crypt_level_2'),
+  ('Lioneye''s Watch', 'zone:a1:lioneye_s_watch');
+
+UPDATE splits SET breakpoint_key = (
+  SELECT breakpoint_key FROM breakpoint_key_map m WHERE m.breakpoint_name = splits.breakpoint_name
+) WHERE breakpoint_key IS NULL;
+
+UPDATE gold_splits SET breakpoint_key = (
+  SELECT breakpoint_key FROM breakpoint_key_map m WHERE m.breakpoint_name = gold_splits.breakpoint_name
+) WHERE breakpoint_key IS NULL;
+
+DROP TABLE breakpoint_key_map;
+
+-- Golds recorded under different names for the same location collapse to the best one
+DELETE FROM gold_splits
+WHERE breakpoint_key IS NOT NULL AND EXISTS (
+  SELECT 1 FROM gold_splits o
+  WHERE o.category = gold_splits.category
+    AND o.class = gold_splits.class
+    AND o.breakpoint_key = gold_splits.breakpoint_key
+    AND (o.best_segment_ms < gold_splits.best_segment_ms
+      OR (o.best_segment_ms = gold_splits.best_segment_ms AND o.id < gold_splits.id))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS gold_splits_category_class_key
+  ON gold_splits(category, class, breakpoint_key);
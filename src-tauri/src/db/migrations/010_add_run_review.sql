@@ -0,0 +1,4 @@
+-- VOD link and review notes per run
+ALTER TABLE runs ADD COLUMN video_url TEXT;
+ALTER TABLE runs ADD COLUMN vod_offset_ms INTEGER;
+ALTER TABLE runs ADD COLUMN review_notes TEXT;
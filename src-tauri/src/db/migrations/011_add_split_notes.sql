@@ -0,0 +1,11 @@
+-- Review notes and mistake tags attached to individual splits
+CREATE TABLE IF NOT EXISTS split_notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    split_id INTEGER NOT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    mistake_type TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (split_id) REFERENCES splits(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_split_notes_split ON split_notes(split_id);
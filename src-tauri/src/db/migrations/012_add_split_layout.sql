@@ -0,0 +1,3 @@
+-- Layout variant seen for the zone at each split (manual tag or inferred from segment time)
+ALTER TABLE splits ADD COLUMN layout_variant TEXT;
+ALTER TABLE splits ADD COLUMN layout_inferred INTEGER NOT NULL DEFAULT 0;
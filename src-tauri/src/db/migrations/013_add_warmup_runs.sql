@@ -0,0 +1,5 @@
+-- Warm-up runs (first run of a session) can be excluded from stats
+ALTER TABLE runs ADD COLUMN is_warmup INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE settings ADD COLUMN warmup_detection_enabled INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE settings ADD COLUMN session_gap_minutes INTEGER NOT NULL DEFAULT 120;
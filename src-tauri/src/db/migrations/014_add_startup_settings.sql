@@ -0,0 +1,3 @@
+-- Startup behavior: register with the OS to launch on boot, and start hidden in the tray
+ALTER TABLE settings ADD COLUMN launch_on_boot INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN start_minimized INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,23 @@
+-- Game version each run was played on, so PBs can be kept per major patch
+ALTER TABLE runs ADD COLUMN game_version TEXT;
+
+-- Personal bests gain a patch ('' = across all patches). SQLite can't alter a
+-- UNIQUE constraint, so rebuild the table with the new key.
+CREATE TABLE personal_bests_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category TEXT NOT NULL,
+    class TEXT NOT NULL,
+    patch TEXT NOT NULL DEFAULT '',
+    run_id INTEGER NOT NULL,
+    total_time_ms INTEGER NOT NULL,
+    UNIQUE(category, class, patch),
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+INSERT INTO personal_bests_new (id, category, class, run_id, total_time_ms)
+SELECT id, category, class, run_id, total_time_ms FROM personal_bests;
+
+DROP TABLE personal_bests;
+ALTER TABLE personal_bests_new RENAME TO personal_bests;
+
+ALTER TABLE settings ADD COLUMN separate_pbs_by_patch INTEGER NOT NULL DEFAULT 0;
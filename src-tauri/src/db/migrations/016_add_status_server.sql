@@ -0,0 +1,4 @@
+-- Read-only phone status page served on the local network
+ALTER TABLE settings ADD COLUMN status_server_enabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN status_server_port INTEGER NOT NULL DEFAULT 8733;
+ALTER TABLE settings ADD COLUMN status_server_token TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,6 @@
+-- Split announcements: how to announce (tone / speech) and when (golds only, delta thresholds)
+ALTER TABLE settings ADD COLUMN announce_sound INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN announce_speech INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN announce_golds_only INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN announce_min_behind_ms INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN announce_min_ahead_ms INTEGER NOT NULL DEFAULT 0;
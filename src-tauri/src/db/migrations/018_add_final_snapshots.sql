@@ -0,0 +1,2 @@
+-- Final build snapshot captured automatically when a run completes
+ALTER TABLE snapshots ADD COLUMN is_final INTEGER NOT NULL DEFAULT 0;
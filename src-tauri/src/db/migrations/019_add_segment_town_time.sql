@@ -0,0 +1,18 @@
+-- Per-segment town/hideout time alongside the cumulative values
+ALTER TABLE splits ADD COLUMN segment_town_time_ms INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE splits ADD COLUMN segment_hideout_time_ms INTEGER NOT NULL DEFAULT 0;
+
+-- Backfill from the previous split's cumulative values in the same run
+UPDATE splits SET
+    segment_town_time_ms = MAX(0, town_time_ms - COALESCE((
+        SELECT p.town_time_ms FROM splits p
+        WHERE p.run_id = splits.run_id
+          AND (p.split_time_ms < splits.split_time_ms OR (p.split_time_ms = splits.split_time_ms AND p.id < splits.id))
+        ORDER BY p.split_time_ms DESC, p.id DESC LIMIT 1
+    ), 0)),
+    segment_hideout_time_ms = MAX(0, hideout_time_ms - COALESCE((
+        SELECT p.hideout_time_ms FROM splits p
+        WHERE p.run_id = splits.run_id
+          AND (p.split_time_ms < splits.split_time_ms OR (p.split_time_ms = splits.split_time_ms AND p.id < splits.id))
+        ORDER BY p.split_time_ms DESC, p.id DESC LIMIT 1
+    ), 0));
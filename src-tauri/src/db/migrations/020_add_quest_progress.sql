@@ -0,0 +1,10 @@
+-- Campaign quest progress per run, updated from log events
+ALTER TABLE runs ADD COLUMN quest_act INTEGER NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS quest_progress (
+    run_id INTEGER NOT NULL,
+    objective TEXT NOT NULL,
+    completed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (run_id, objective),
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
@@ -0,0 +1,17 @@
+-- Golds set shortly before an undo/reset are provisional and can be reverted
+ALTER TABLE settings ADD COLUMN gold_protection_secs INTEGER NOT NULL DEFAULT 10;
+
+CREATE TABLE IF NOT EXISTS provisional_golds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    split_id INTEGER NOT NULL,
+    gold_split_id INTEGER NOT NULL,
+    -- NULL when the split created the gold row
+    previous_best_ms INTEGER,
+    segment_ms INTEGER NOT NULL,
+    -- Unix milliseconds
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_provisional_golds_run_id ON provisional_golds(run_id);
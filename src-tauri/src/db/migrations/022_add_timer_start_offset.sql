@@ -0,0 +1,3 @@
+-- Countdown before the run timer reaches zero, e.g. for time spent on character creation
+ALTER TABLE settings ADD COLUMN timer_start_offset_ms INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE runs ADD COLUMN start_offset_ms INTEGER NOT NULL DEFAULT 0;
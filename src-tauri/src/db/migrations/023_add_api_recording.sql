@@ -0,0 +1,3 @@
+-- Debug mode that keeps raw API responses of recent snapshot attempts for diagnostics bundles
+ALTER TABLE settings ADD COLUMN record_api_responses INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN api_recording_limit INTEGER NOT NULL DEFAULT 10;
@@ -0,0 +1,2 @@
+-- Character experience at each split, from the split's snapshot
+ALTER TABLE splits ADD COLUMN experience INTEGER;
@@ -0,0 +1,11 @@
+-- Setup applied when a run of the category starts; NULL columns leave the current setting alone
+CREATE TABLE IF NOT EXISTS category_defaults (
+    category TEXT PRIMARY KEY,
+    breakpoint_preset TEXT,
+    -- JSON arrays of breakpoint names
+    enabled_breakpoints TEXT,
+    snapshot_breakpoints TEXT,
+    separate_pbs_by_patch INTEGER,
+    -- JSON OverlayLayout
+    overlay_layout TEXT
+);
@@ -0,0 +1,2 @@
+-- Number, time and date conventions for CSV/Markdown/HTML exports
+ALTER TABLE settings ADD COLUMN locale TEXT NOT NULL DEFAULT 'en-US';
@@ -0,0 +1,7 @@
+-- Pre-run checklist: what failing checks do ('off', 'warn', 'block') and which checks run
+ALTER TABLE settings ADD COLUMN readiness_mode TEXT NOT NULL DEFAULT 'warn';
+ALTER TABLE settings ADD COLUMN readiness_log_watcher INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE settings ADD COLUMN readiness_account INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE settings ADD COLUMN readiness_overlay INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN readiness_preset INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE settings ADD COLUMN readiness_profile_public INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,5 @@
+-- api.pathofexile.com sign-in (OAuth PKCE) for reading private profiles
+ALTER TABLE settings ADD COLUMN oauth_access_token TEXT NOT NULL DEFAULT '';
+ALTER TABLE settings ADD COLUMN oauth_refresh_token TEXT;
+ALTER TABLE settings ADD COLUMN oauth_expires_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN oauth_account_name TEXT NOT NULL DEFAULT '';
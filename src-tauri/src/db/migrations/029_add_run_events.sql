@@ -0,0 +1,13 @@
+-- Log events of the active run (zone entries, level-ups, deaths) and timer
+-- pauses, at run-clock time, for the run timeline
+CREATE TABLE IF NOT EXISTS run_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    elapsed_ms INTEGER NOT NULL,
+    detail TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_events_run ON run_events(run_id);
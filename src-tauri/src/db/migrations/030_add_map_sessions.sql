@@ -0,0 +1,12 @@
+-- Endgame map sessions: wall-clock time inside maps and between them
+-- (hideout, town, other areas), kept up to date while the session runs
+CREATE TABLE IF NOT EXISTS map_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    started_at TEXT NOT NULL,
+    ended_at TEXT,
+    maps_run INTEGER NOT NULL DEFAULT 0,
+    map_time_ms INTEGER NOT NULL DEFAULT 0,
+    hideout_time_ms INTEGER NOT NULL DEFAULT 0,
+    town_time_ms INTEGER NOT NULL DEFAULT 0,
+    other_time_ms INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,4 @@
+-- Where a run's category came from: 'selected' when the run started,
+-- 'detected' from its first log events, or 'manual' (set_run_category).
+-- Detection only replaces a selected category.
+ALTER TABLE runs ADD COLUMN category_source TEXT NOT NULL DEFAULT 'selected';
@@ -0,0 +1,3 @@
+-- Why an unfinished run was reset (abandon_run), and how far into it
+ALTER TABLE runs ADD COLUMN abandon_reason TEXT;
+ALTER TABLE runs ADD COLUMN abandoned_at_ms INTEGER;
@@ -0,0 +1,15 @@
+-- Every zone visit of a run at run-clock time, from zone-enter events. The
+-- visit in progress has no duration until the next zone entry or the end of
+-- the run closes it.
+CREATE TABLE IF NOT EXISTS zone_times (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    zone_name TEXT NOT NULL,
+    entered_at_ms INTEGER NOT NULL,
+    duration_ms INTEGER,
+    is_town INTEGER NOT NULL DEFAULT 0,
+    is_hideout INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_zone_times_run ON zone_times(run_id);
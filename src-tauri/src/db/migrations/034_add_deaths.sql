@@ -0,0 +1,13 @@
+-- Deaths of a run's character, recorded by the breakpoint engine: the log
+-- timestamp, the zone and level at the time, and the run clock
+CREATE TABLE IF NOT EXISTS deaths (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    timestamp TEXT NOT NULL,
+    zone_name TEXT,
+    elapsed_ms INTEGER NOT NULL,
+    character_level INTEGER,
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_deaths_run ON deaths(run_id);
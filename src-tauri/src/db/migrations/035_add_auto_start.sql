@@ -0,0 +1,3 @@
+-- Start a run by itself when a new character enters the starting zone
+ALTER TABLE settings ADD COLUMN auto_start_runs INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN auto_start_zone TEXT NOT NULL DEFAULT 'The Twilight Strand';
@@ -0,0 +1,5 @@
+-- Exports written (and a webhook fired) whenever a run completes, or only on PBs
+ALTER TABLE settings ADD COLUMN auto_export_dir TEXT NOT NULL DEFAULT '';
+ALTER TABLE settings ADD COLUMN auto_export_formats TEXT NOT NULL DEFAULT 'json';
+ALTER TABLE settings ADD COLUMN auto_export_pbs_only INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN auto_export_webhook_url TEXT NOT NULL DEFAULT '';
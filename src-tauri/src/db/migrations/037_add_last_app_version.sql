@@ -0,0 +1,2 @@
+-- App version that last ran against this database, for the "what's new" notice
+ALTER TABLE settings ADD COLUMN last_app_version TEXT NOT NULL DEFAULT '';
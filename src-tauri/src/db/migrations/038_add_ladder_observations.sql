@@ -0,0 +1,17 @@
+-- Ladder characters' levels as seen by each fetch_ladder, so the time a
+-- league racer reached a level can be told later: the public ladder only
+-- shows where everyone is now. A row is only added when a character's level
+-- or experience changed since its last one.
+CREATE TABLE IF NOT EXISTS ladder_observations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    league TEXT NOT NULL,
+    character_name TEXT NOT NULL,
+    account_name TEXT NOT NULL DEFAULT '',
+    class TEXT NOT NULL DEFAULT '',
+    level INTEGER NOT NULL,
+    experience INTEGER NOT NULL DEFAULT 0,
+    rank INTEGER NOT NULL,
+    observed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_ladder_observations_character ON ladder_observations(league, character_name);
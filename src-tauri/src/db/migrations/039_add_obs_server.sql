@@ -0,0 +1,3 @@
+-- Overlay data served to OBS browser sources on localhost
+ALTER TABLE settings ADD COLUMN obs_server_enabled INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE settings ADD COLUMN obs_server_port INTEGER NOT NULL DEFAULT 8734;
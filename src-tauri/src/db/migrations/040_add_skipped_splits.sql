@@ -0,0 +1,14 @@
+-- Breakpoints skipped during a run (missed, or split on a different route).
+-- The run's next split spans them, so its segment can't set a gold.
+CREATE TABLE IF NOT EXISTS skipped_splits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    breakpoint_name TEXT NOT NULL,
+    -- Run clock when skipped
+    elapsed_ms INTEGER NOT NULL,
+    UNIQUE(run_id, breakpoint_name),
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+ALTER TABLE settings ADD COLUMN hotkey_undo_split TEXT NOT NULL DEFAULT 'Ctrl+Shift+Z';
+ALTER TABLE settings ADD COLUMN hotkey_skip_split TEXT NOT NULL DEFAULT 'Ctrl+Shift+K';
@@ -0,0 +1,13 @@
+-- Extra overlay windows, e.g. a compact timer on a second monitor. The main
+-- overlay keeps its position in settings.
+CREATE TABLE IF NOT EXISTS overlay_profiles (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    x INTEGER,
+    y INTEGER,
+    width REAL,
+    height REAL,
+    -- NULL follows the overlay opacity setting
+    opacity REAL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
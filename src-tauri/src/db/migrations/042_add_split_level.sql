@@ -0,0 +1,2 @@
+-- Character level at each split, from the run's last level-up or the split's snapshot
+ALTER TABLE splits ADD COLUMN character_level INTEGER;
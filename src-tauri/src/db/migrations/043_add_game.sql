@@ -0,0 +1,2 @@
+-- Which game's client is followed: 'poe1' or 'poe2'
+ALTER TABLE settings ADD COLUMN game TEXT NOT NULL DEFAULT 'poe1';
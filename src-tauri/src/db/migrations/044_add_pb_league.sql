@@ -0,0 +1,23 @@
+-- Personal bests are kept per league, so a Standard run can't replace a league
+-- PB, and record the PB run's ascendancy. Rebuild the table for the new key.
+CREATE TABLE personal_bests_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category TEXT NOT NULL,
+    class TEXT NOT NULL,
+    ascendancy TEXT,
+    league TEXT NOT NULL DEFAULT '',
+    patch TEXT NOT NULL DEFAULT '',
+    run_id INTEGER NOT NULL,
+    total_time_ms INTEGER NOT NULL,
+    UNIQUE(category, class, league, patch),
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+-- Existing PBs move to their run's league; `recompute_personal_bests` fills
+-- in the leagues they hid
+INSERT INTO personal_bests_new (id, category, class, ascendancy, league, patch, run_id, total_time_ms)
+SELECT pb.id, pb.category, pb.class, r.ascendancy, COALESCE(r.league, ''), pb.patch, pb.run_id, pb.total_time_ms
+FROM personal_bests pb JOIN runs r ON r.id = pb.run_id;
+
+DROP TABLE personal_bests;
+ALTER TABLE personal_bests_new RENAME TO personal_bests;
@@ -0,0 +1,10 @@
+-- Last split an unfinished run reached before it was reset (abandon_run);
+-- both stay NULL for a run reset before its first split
+ALTER TABLE runs ADD COLUMN reset_split TEXT;
+ALTER TABLE runs ADD COLUMN reset_split_key TEXT;
+
+-- Runs abandoned before the split was recorded
+UPDATE runs SET
+    reset_split = (SELECT breakpoint_name FROM splits WHERE splits.run_id = runs.id ORDER BY split_time_ms DESC LIMIT 1),
+    reset_split_key = (SELECT breakpoint_key FROM splits WHERE splits.run_id = runs.id ORDER BY split_time_ms DESC LIMIT 1)
+WHERE abandoned_at_ms IS NOT NULL AND is_completed = 0;
@@ -0,0 +1,21 @@
+-- Times a run's timer stood still: paused from the timer or pause_run, or
+-- automatically on an AFK or disconnect line. The run clock doesn't move
+-- while paused, so split times and total_time_ms already leave pauses out.
+CREATE TABLE IF NOT EXISTS pauses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    -- Run clock when paused
+    elapsed_ms INTEGER NOT NULL,
+    -- 'manual', 'afk' or 'disconnect'
+    reason TEXT NOT NULL,
+    paused_at TEXT NOT NULL,
+    resumed_at TEXT,
+    -- Wall-clock length, set on resume
+    duration_ms INTEGER,
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_pauses_run ON pauses(run_id);
+
+-- Pause the timer on AFK and disconnect lines, resuming when they clear
+ALTER TABLE settings ADD COLUMN auto_pause INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- Character experience the snapshot's get-items response reported; NULL
+-- when the API left it out
+ALTER TABLE snapshots ADD COLUMN experience INTEGER;
+
+-- Earlier snapshots stored it on their split only
+UPDATE snapshots SET experience = (SELECT experience FROM splits WHERE splits.id = snapshots.split_id);
@@ -0,0 +1,10 @@
+-- The imported leveling guide, one at a time, as parsed steps (guide.rs)
+CREATE TABLE IF NOT EXISTS guides (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    name TEXT NOT NULL,
+    steps_json TEXT NOT NULL,
+    imported_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Guide step a run is on; the steps before it are done
+ALTER TABLE runs ADD COLUMN guide_step INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,4 @@
+-- Notification webhook POSTed on run completion, PBs and gold splits
+-- (`webhook.rs`); a non-empty secret signs each body with HMAC-SHA256
+ALTER TABLE settings ADD COLUMN webhook_url TEXT NOT NULL DEFAULT '';
+ALTER TABLE settings ADD COLUMN webhook_secret TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,2 @@
+-- Release channel the in-app updater follows: 'stable' or 'beta' (`updater.rs`)
+ALTER TABLE settings ADD COLUMN update_channel TEXT NOT NULL DEFAULT 'stable';
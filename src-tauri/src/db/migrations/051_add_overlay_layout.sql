@@ -0,0 +1,3 @@
+-- JSON OverlayComponents: which parts of the overlay are shown (timer,
+-- current split, delta, next split, death count, level pace). NULL shows all.
+ALTER TABLE settings ADD COLUMN overlay_layout TEXT;
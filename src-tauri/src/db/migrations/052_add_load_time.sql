@@ -0,0 +1,6 @@
+-- Time spent on loading screens, from "Connecting to instance server" to the
+-- zone entry after it (`load_time.rs`). Split times minus these give
+-- load-removed times; runs from before tracking have none.
+ALTER TABLE runs ADD COLUMN load_time_ms INTEGER NOT NULL DEFAULT 0;
+-- Cumulative at the split
+ALTER TABLE splits ADD COLUMN load_time_ms INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,31 @@
+-- Split layouts built in the app, run by the backend breakpoint engine in
+-- place of the frontend's built-in breakpoint list
+CREATE TABLE IF NOT EXISTS breakpoint_presets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    game TEXT NOT NULL DEFAULT 'poe1',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS breakpoints (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    preset_id INTEGER NOT NULL,
+    -- Split order within the preset, from 0
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    breakpoint_type TEXT NOT NULL,
+    -- zone (entering zone_name), level (reaching level) or quest (completing
+    -- the quest_state objective in quest)
+    trigger_type TEXT NOT NULL,
+    zone_name TEXT,
+    act INTEGER,
+    level INTEGER,
+    quest TEXT,
+    -- Trigger rule: split on this entry into the zone only / after this quest
+    entry INTEGER,
+    after_quest TEXT,
+    capture_snapshot INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (preset_id) REFERENCES breakpoint_presets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_breakpoints_preset ON breakpoints(preset_id, position);
@@ -0,0 +1,13 @@
+-- Ruleset (SC, HC, SSF or HCSSF) from the run's league name (`ruleset.rs`),
+-- set again when the character API reports the league. Existing runs are
+-- backfilled from the league they already have.
+ALTER TABLE runs ADD COLUMN ruleset TEXT;
+
+UPDATE runs SET ruleset = CASE
+    WHEN (' ' || UPPER(league) || ' ' LIKE '% HC %' OR UPPER(league) LIKE '%HARDCORE%')
+         AND (' ' || UPPER(league) || ' ' LIKE '% SSF %' OR UPPER(league) LIKE '%SOLO SELF-FOUND%') THEN 'HCSSF'
+    WHEN ' ' || UPPER(league) || ' ' LIKE '% HC %' OR UPPER(league) LIKE '%HARDCORE%' THEN 'HC'
+    WHEN ' ' || UPPER(league) || ' ' LIKE '% SSF %' OR UPPER(league) LIKE '%SOLO SELF-FOUND%' THEN 'SSF'
+    ELSE 'SC'
+END
+WHERE league IS NOT NULL AND league != '';
@@ -0,0 +1,7 @@
+-- Split statistics are aggregated in SQL (`Split::get_stats`); these let the
+-- matching runs and their splits be read from the indexes alone.
+CREATE INDEX IF NOT EXISTS idx_runs_stats ON runs(is_reference, category, class, is_completed);
+CREATE INDEX IF NOT EXISTS idx_splits_stats ON splits(
+    run_id, split_time_ms, breakpoint_key, breakpoint_name,
+    town_time_ms, segment_time_ms, experience, layout_variant
+);
@@ -0,0 +1,17 @@
+-- A free-form note on a run, and tags on runs or on one of their splits
+-- ("new build test", "lag spike"), so runs can be found again by tag
+ALTER TABLE runs ADD COLUMN notes TEXT;
+
+CREATE TABLE IF NOT EXISTS run_tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    -- NULL for a tag on the whole run
+    split_id INTEGER,
+    tag TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (split_id) REFERENCES splits(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_run_tags_unique ON run_tags(run_id, COALESCE(split_id, 0), tag);
+CREATE INDEX IF NOT EXISTS idx_run_tags_tag ON run_tags(tag);
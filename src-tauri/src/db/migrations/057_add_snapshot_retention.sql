@@ -0,0 +1,4 @@
+-- Which snapshots `compact_database` keeps (`db/compact.rs`): "all", "final"
+-- (each run's final build) or "recent" (the last `snapshot_retention_runs` runs)
+ALTER TABLE settings ADD COLUMN snapshot_retention TEXT NOT NULL DEFAULT 'all';
+ALTER TABLE settings ADD COLUMN snapshot_retention_runs INTEGER NOT NULL DEFAULT 20;
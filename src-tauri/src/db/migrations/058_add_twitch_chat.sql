@@ -0,0 +1,9 @@
+-- Twitch chat announcements (`twitch.rs`): the chat token, the account it
+-- belongs to (the channel's owner when empty), the channel, and a message
+-- template per event; an empty template posts nothing for that event
+ALTER TABLE settings ADD COLUMN twitch_token TEXT NOT NULL DEFAULT '';
+ALTER TABLE settings ADD COLUMN twitch_username TEXT NOT NULL DEFAULT '';
+ALTER TABLE settings ADD COLUMN twitch_channel TEXT NOT NULL DEFAULT '';
+ALTER TABLE settings ADD COLUMN twitch_pb_message TEXT NOT NULL DEFAULT 'New {category} PB: {time}!';
+ALTER TABLE settings ADD COLUMN twitch_gold_message TEXT NOT NULL DEFAULT 'New {split} gold: {splitTime}!';
+ALTER TABLE settings ADD COLUMN twitch_run_message TEXT NOT NULL DEFAULT '';
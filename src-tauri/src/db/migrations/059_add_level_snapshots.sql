@@ -0,0 +1,32 @@
+-- Snapshots taken every `snapshot_level_interval` levels (0 = off) belong to
+-- no split, so `split_id` becomes nullable. Rebuild the table to drop NOT NULL.
+CREATE TABLE snapshots_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id INTEGER NOT NULL,
+    split_id INTEGER,
+    timestamp TEXT NOT NULL DEFAULT (datetime('now')),
+    elapsed_time_ms INTEGER NOT NULL,
+    character_level INTEGER NOT NULL,
+    items_json TEXT NOT NULL DEFAULT '[]',
+    skills_json TEXT NOT NULL DEFAULT '[]',
+    passive_tree_json TEXT NOT NULL DEFAULT '{}',
+    stats_json TEXT NOT NULL DEFAULT '{}',
+    pob_code TEXT,
+    is_final INTEGER NOT NULL DEFAULT 0,
+    experience INTEGER,
+    FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (split_id) REFERENCES splits(id) ON DELETE CASCADE
+);
+
+INSERT INTO snapshots_new (id, run_id, split_id, timestamp, elapsed_time_ms, character_level, items_json,
+                           skills_json, passive_tree_json, stats_json, pob_code, is_final, experience)
+SELECT id, run_id, split_id, timestamp, elapsed_time_ms, character_level, items_json,
+       skills_json, passive_tree_json, stats_json, pob_code, is_final, experience
+FROM snapshots;
+
+DROP TABLE snapshots;
+ALTER TABLE snapshots_new RENAME TO snapshots;
+CREATE INDEX IF NOT EXISTS idx_snapshots_run_id ON snapshots(run_id);
+CREATE INDEX IF NOT EXISTS idx_snapshots_split_id ON snapshots(split_id);
+
+ALTER TABLE settings ADD COLUMN snapshot_level_interval INTEGER NOT NULL DEFAULT 0;
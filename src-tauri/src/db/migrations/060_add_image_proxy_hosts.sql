@@ -0,0 +1,4 @@
+-- Hosts `proxy_image` may fetch images from, comma-separated. The official
+-- CDN serves both games' icons; PoE 2 passive tree and item art also comes
+-- from the game sites and poe2db.
+ALTER TABLE settings ADD COLUMN image_proxy_hosts TEXT NOT NULL DEFAULT 'web.poecdn.com, www.pathofexile.com, pathofexile2.com, cdn.poe2db.tw';
@@ -0,0 +1,3 @@
+-- Closing or minimizing the main window hides it in the tray, keeping the
+-- log watcher and overlays running
+ALTER TABLE settings ADD COLUMN minimize_to_tray INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- Background mode: closing the main window hides it instead of exiting, so
+-- the log watcher, breakpoint engine and overlays keep timing the run
+ALTER TABLE settings ADD COLUMN background_mode INTEGER NOT NULL DEFAULT 0;
+
+-- minimize_to_tray used to cover closing too; keep that for those who had it on
+UPDATE settings SET background_mode = 1 WHERE minimize_to_tray = 1;
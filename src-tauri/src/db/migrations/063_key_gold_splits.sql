@@ -0,0 +1,22 @@
+-- Golds are unique per location key; names only have to be unique among golds
+-- recorded without a key, so a zone name can hold a gold in two acts. Rebuild
+-- the table, since the initial schema made the name unique as a table constraint.
+CREATE TABLE gold_splits_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category TEXT NOT NULL,
+    class TEXT NOT NULL DEFAULT 'Unknown',
+    breakpoint_name TEXT NOT NULL,
+    breakpoint_key TEXT,
+    best_segment_ms INTEGER NOT NULL
+);
+
+INSERT INTO gold_splits_new (id, category, class, breakpoint_name, breakpoint_key, best_segment_ms)
+SELECT id, category, class, breakpoint_name, breakpoint_key, best_segment_ms FROM gold_splits;
+
+DROP TABLE gold_splits;
+ALTER TABLE gold_splits_new RENAME TO gold_splits;
+
+CREATE UNIQUE INDEX gold_splits_category_class_key
+  ON gold_splits(category, class, breakpoint_key);
+CREATE UNIQUE INDEX gold_splits_category_class_unkeyed
+  ON gold_splits(category, class, breakpoint_name) WHERE breakpoint_key IS NULL;
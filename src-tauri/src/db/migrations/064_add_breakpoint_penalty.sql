@@ -0,0 +1,3 @@
+-- Kitava breakpoints: the resistance penalty (30 in Act 5, 60 in Act 10) whose
+-- affliction message splits them
+ALTER TABLE breakpoints ADD COLUMN penalty INTEGER;
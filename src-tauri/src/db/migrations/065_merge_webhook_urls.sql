@@ -0,0 +1,6 @@
+-- Completion webhooks all go to webhook_url (`webhook.rs`), listing any
+-- auto-export files; carry over an auto-export webhook set on its own
+UPDATE settings SET webhook_url = auto_export_webhook_url
+WHERE webhook_url = '' AND auto_export_webhook_url != '';
+
+ALTER TABLE settings DROP COLUMN auto_export_webhook_url;
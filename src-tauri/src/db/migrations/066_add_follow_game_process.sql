@@ -0,0 +1,3 @@
+-- Start the log watcher and show the overlays when the game client starts,
+-- stop and hide them when it exits (`game_process.rs`, Linux only for now)
+ALTER TABLE settings ADD COLUMN follow_game_process INTEGER NOT NULL DEFAULT 0;
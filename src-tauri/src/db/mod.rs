@@ -1,53 +1,180 @@
+mod archive;
+mod compact;
+mod compress;
+mod repair;
 mod schema;
 
 use anyhow::Result;
-use once_cell::sync::OnceCell;
 use rusqlite::Connection;
+use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
 
+pub use archive::{archive_runs_older_than, get_archived_paged, restore_archived_run, ArchiveReport};
+pub use compact::{compact, CompactReport, SnapshotRetention};
+pub use repair::{recompute_personal_bests, repair, RepairReport};
 pub use schema::{
-    Run, NewRun, RunFilters, RunStats, ReferenceRunData,
-    Split, NewSplit, SplitStat,
-    Snapshot, NewSnapshot,
-    PersonalBest, GoldSplit, Settings,
+    Run, NewRun, RunFilters, RunPage, RunReview, RunStats, ReferenceRunData, ReferenceSplitData,
+    Split, NewSplit, SplitStat, SplitTrendPoint, SplitNote, NewSplitNote, RunTag,
+    Snapshot, SnapshotSummary, NewSnapshot,
+    PersonalBest, GoldSplit, ProvisionalGold, SkippedSplit, Pause, TimeLossReport, ActTownTime, QuestProgress, LevelingGuide, RunEvent, Death, ZoneTime, ZoneTimeStat, ZoneHeatmapEntry, MapSession, LadderObservation, NewLadderObservation, Settings, StatusServerConfig, ObsServerConfig,
+    CategoryDefaults, BreakpointPreset, PresetBreakpoint, NewPresetBreakpoint, OverlayComponents, OverlayLayout, OverlayProfile, OAuthToken,
 };
 
-static DB: OnceCell<Mutex<Connection>> = OnceCell::new();
+/// Idle connections kept open for reuse; more are opened while all are busy
+const POOL_SIZE: usize = 4;
+
+/// How long a connection waits for another's write to finish before the
+/// query fails with "database is locked"
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connections to one database file. Each query checks a connection out, so
+/// a history page read doesn't queue behind a snapshot insert the way it did
+/// behind a single shared connection; WAL mode lets them run side by side.
+struct Pool {
+    /// Path (or in tests, shared in-memory URI) every connection opens
+    target: String,
+    /// Archive database every connection attaches as `archive`
+    archive: String,
+    idle: Vec<Connection>,
+    /// Bumped when the database is replaced, so connections to the old one
+    /// are closed instead of returned
+    generation: u64,
+}
+
+static POOL: Mutex<Option<Pool>> = Mutex::new(None);
+
+/// Migrations applied when the connection was opened
+static APPLIED_MIGRATIONS: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
 /// Initialize the database connection
 pub fn init_db(app_data_dir: PathBuf) -> Result<()> {
     let db_path = app_data_dir.join("poe_watcher.db");
+    let archive_path = app_data_dir.join("poe_watcher_archive.db");
 
     // Create parent directories if they don't exist
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
-    let conn = Connection::open(&db_path)?;
+    if lock_pool().is_some() {
+        return Err(anyhow::anyhow!("Database already initialized"));
+    }
+    install(
+        db_path.to_string_lossy().into_owned(),
+        archive_path.to_string_lossy().into_owned(),
+    )
+}
+
+/// Initialize a fresh in-memory database, replacing any existing connection.
+/// Used by tests so every case starts from an empty, fully migrated schema.
+#[cfg(test)]
+pub fn init_in_memory() -> Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    // Shared cache, so every connection of the pool sees the same database
+    let name = format!("poe_watcher_test_{}_{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed));
+    install(
+        format!("file:{}?mode=memory&cache=shared", name),
+        format!("file:{}_archive?mode=memory&cache=shared", name),
+    )
+}
+
+/// Migrate the database at `target` and make it the one `get_db` hands out
+fn install(target: String, archive: String) -> Result<()> {
+    let conn = open_connection(&target, &archive)?;
+
+    // Readers keep going while a write commits; the mode sticks to the file.
+    // In-memory databases stay in "memory" mode.
+    let _mode: String = conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))?;
+
+    // Run migrations
+    let applied = run_migrations(&conn)?;
+    *APPLIED_MIGRATIONS.lock().unwrap_or_else(|e| e.into_inner()) = applied;
+    archive::sync_schema(&conn)?;
+
+    let mut pool = lock_pool();
+    let generation = pool.as_ref().map_or(0, |pool| pool.generation + 1);
+    *pool = Some(Pool { target, archive, idle: vec![conn], generation });
+    Ok(())
+}
+
+/// Open a connection with the pragmas every connection needs
+fn open_connection(target: &str, archive: &str) -> Result<Connection> {
+    let conn = Connection::open(target)?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
 
     // Enable foreign keys
     conn.execute("PRAGMA foreign_keys = ON", [])?;
 
-    // Run migrations
-    run_migrations(&conn)?;
+    // Archived runs live in their own file, so the main one stays small
+    conn.execute("ATTACH DATABASE ?1 AS archive", [archive])?;
 
-    DB.set(Mutex::new(conn))
-        .map_err(|_| anyhow::anyhow!("Database already initialized"))?;
+    Ok(conn)
+}
 
-    Ok(())
+/// Names of the migrations this launch applied, oldest first. All of them on
+/// a new database.
+pub fn applied_migrations() -> Vec<String> {
+    APPLIED_MIGRATIONS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn lock_pool() -> MutexGuard<'static, Option<Pool>> {
+    POOL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A connection checked out of the pool; it goes back when dropped
+pub struct DbConnection {
+    conn: Option<Connection>,
+    generation: u64,
 }
 
-/// Get a reference to the database connection
-pub fn get_db() -> Result<std::sync::MutexGuard<'static, Connection>> {
-    DB.get()
-        .ok_or_else(|| anyhow::anyhow!("Database not initialized"))?
-        .lock()
-        .map_err(|_| anyhow::anyhow!("Failed to lock database"))
+impl Deref for DbConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection is only taken on drop")
+    }
 }
 
-/// Run database migrations
-fn run_migrations(conn: &Connection) -> Result<()> {
+impl DerefMut for DbConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("connection is only taken on drop")
+    }
+}
+
+impl Drop for DbConnection {
+    fn drop(&mut self) {
+        let Some(conn) = self.conn.take() else {
+            return;
+        };
+        let mut pool = lock_pool();
+        if let Some(pool) = pool.as_mut().filter(|pool| pool.generation == self.generation) {
+            if pool.idle.len() < POOL_SIZE {
+                pool.idle.push(conn);
+            }
+        }
+    }
+}
+
+/// Get a database connection, reusing an idle one when there is one.
+/// Hold it only as long as the queries need it.
+pub fn get_db() -> Result<DbConnection> {
+    let (target, archive, generation) = {
+        let mut pool = lock_pool();
+        let pool = pool.as_mut().ok_or_else(|| anyhow::anyhow!("Database not initialized"))?;
+        if let Some(conn) = pool.idle.pop() {
+            return Ok(DbConnection { conn: Some(conn), generation: pool.generation });
+        }
+        (pool.target.clone(), pool.archive.clone(), pool.generation)
+    };
+    Ok(DbConnection { conn: Some(open_connection(&target, &archive)?), generation })
+}
+
+/// Run database migrations, returning the names of those applied
+fn run_migrations(conn: &Connection) -> Result<Vec<String>> {
     // Create migrations table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS migrations (
@@ -67,14 +194,16 @@ fn run_migrations(conn: &Connection) -> Result<()> {
     drop(stmt);
 
     // Apply pending migrations
+    let mut newly_applied = Vec::new();
     for (name, sql) in MIGRATIONS {
         if !applied.contains(&name.to_string()) {
             conn.execute_batch(sql)?;
             conn.execute("INSERT INTO migrations (name) VALUES (?1)", [name])?;
+            newly_applied.push(name.to_string());
         }
     }
 
-    Ok(())
+    Ok(newly_applied)
 }
 
 /// Database migrations
@@ -87,4 +216,62 @@ const MIGRATIONS: &[(&str, &str)] = &[
     ("006_add_hotkey_settings", include_str!("migrations/006_add_hotkey_settings.sql")),
     ("007_add_manual_split_hotkey", include_str!("migrations/007_add_manual_split_hotkey.sql")),
     ("008_add_class_to_gold_splits", include_str!("migrations/008_add_class_to_gold_splits.sql")),
+    ("009_add_breakpoint_keys", include_str!("migrations/009_add_breakpoint_keys.sql")),
+    ("010_add_run_review", include_str!("migrations/010_add_run_review.sql")),
+    ("011_add_split_notes", include_str!("migrations/011_add_split_notes.sql")),
+    ("012_add_split_layout", include_str!("migrations/012_add_split_layout.sql")),
+    ("013_add_warmup_runs", include_str!("migrations/013_add_warmup_runs.sql")),
+    ("014_add_startup_settings", include_str!("migrations/014_add_startup_settings.sql")),
+    ("015_add_game_version", include_str!("migrations/015_add_game_version.sql")),
+    ("016_add_status_server", include_str!("migrations/016_add_status_server.sql")),
+    ("017_add_split_announcements", include_str!("migrations/017_add_split_announcements.sql")),
+    ("018_add_final_snapshots", include_str!("migrations/018_add_final_snapshots.sql")),
+    ("019_add_segment_town_time", include_str!("migrations/019_add_segment_town_time.sql")),
+    ("020_add_quest_progress", include_str!("migrations/020_add_quest_progress.sql")),
+    ("021_add_gold_protection", include_str!("migrations/021_add_gold_protection.sql")),
+    ("022_add_timer_start_offset", include_str!("migrations/022_add_timer_start_offset.sql")),
+    ("023_add_api_recording", include_str!("migrations/023_add_api_recording.sql")),
+    ("024_add_split_experience", include_str!("migrations/024_add_split_experience.sql")),
+    ("025_add_category_defaults", include_str!("migrations/025_add_category_defaults.sql")),
+    ("026_add_locale", include_str!("migrations/026_add_locale.sql")),
+    ("027_add_run_readiness", include_str!("migrations/027_add_run_readiness.sql")),
+    ("028_add_oauth_tokens", include_str!("migrations/028_add_oauth_tokens.sql")),
+    ("029_add_run_events", include_str!("migrations/029_add_run_events.sql")),
+    ("030_add_map_sessions", include_str!("migrations/030_add_map_sessions.sql")),
+    ("031_add_run_category_source", include_str!("migrations/031_add_run_category_source.sql")),
+    ("032_add_run_abandonment", include_str!("migrations/032_add_run_abandonment.sql")),
+    ("033_add_zone_times", include_str!("migrations/033_add_zone_times.sql")),
+    ("034_add_deaths", include_str!("migrations/034_add_deaths.sql")),
+    ("035_add_auto_start", include_str!("migrations/035_add_auto_start.sql")),
+    ("036_add_auto_export", include_str!("migrations/036_add_auto_export.sql")),
+    ("037_add_last_app_version", include_str!("migrations/037_add_last_app_version.sql")),
+    ("038_add_ladder_observations", include_str!("migrations/038_add_ladder_observations.sql")),
+    ("039_add_obs_server", include_str!("migrations/039_add_obs_server.sql")),
+    ("040_add_skipped_splits", include_str!("migrations/040_add_skipped_splits.sql")),
+    ("041_add_overlay_profiles", include_str!("migrations/041_add_overlay_profiles.sql")),
+    ("042_add_split_level", include_str!("migrations/042_add_split_level.sql")),
+    ("043_add_game", include_str!("migrations/043_add_game.sql")),
+    ("044_add_pb_league", include_str!("migrations/044_add_pb_league.sql")),
+    ("045_add_run_reset_split", include_str!("migrations/045_add_run_reset_split.sql")),
+    ("046_add_pauses", include_str!("migrations/046_add_pauses.sql")),
+    ("047_add_snapshot_experience", include_str!("migrations/047_add_snapshot_experience.sql")),
+    ("048_add_guides", include_str!("migrations/048_add_guides.sql")),
+    ("049_add_webhook", include_str!("migrations/049_add_webhook.sql")),
+    ("050_add_update_channel", include_str!("migrations/050_add_update_channel.sql")),
+    ("051_add_overlay_layout", include_str!("migrations/051_add_overlay_layout.sql")),
+    ("052_add_load_time", include_str!("migrations/052_add_load_time.sql")),
+    ("053_add_breakpoint_presets", include_str!("migrations/053_add_breakpoint_presets.sql")),
+    ("054_add_run_ruleset", include_str!("migrations/054_add_run_ruleset.sql")),
+    ("055_add_stats_indexes", include_str!("migrations/055_add_stats_indexes.sql")),
+    ("056_add_run_notes_and_tags", include_str!("migrations/056_add_run_notes_and_tags.sql")),
+    ("057_add_snapshot_retention", include_str!("migrations/057_add_snapshot_retention.sql")),
+    ("058_add_twitch_chat", include_str!("migrations/058_add_twitch_chat.sql")),
+    ("059_add_level_snapshots", include_str!("migrations/059_add_level_snapshots.sql")),
+    ("060_add_image_proxy_hosts", include_str!("migrations/060_add_image_proxy_hosts.sql")),
+    ("061_add_minimize_to_tray", include_str!("migrations/061_add_minimize_to_tray.sql")),
+    ("062_add_background_mode", include_str!("migrations/062_add_background_mode.sql")),
+    ("063_key_gold_splits", include_str!("migrations/063_key_gold_splits.sql")),
+    ("064_add_breakpoint_penalty", include_str!("migrations/064_add_breakpoint_penalty.sql")),
+    ("065_merge_webhook_urls", include_str!("migrations/065_merge_webhook_urls.sql")),
+    ("066_add_follow_game_process", include_str!("migrations/066_add_follow_game_process.sql")),
 ];
@@ -0,0 +1,134 @@
+use rusqlite::{params, Connection};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{get_db, PersonalBest, Run};
+
+/// What `repair` changed, one count per kind of inconsistency
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    /// Splits whose run no longer exists (deleted)
+    pub orphaned_splits: usize,
+    /// Snapshots whose split or run no longer exists (deleted)
+    pub orphaned_snapshots: usize,
+    /// Split notes whose split no longer exists (deleted)
+    pub orphaned_split_notes: usize,
+    /// Completed runs without an end time (end time derived, or un-completed if no total)
+    pub runs_missing_end_time: usize,
+    /// Personal bests pointing at deleted runs (replaced by the next best run, or removed)
+    pub personal_bests_fixed: usize,
+    /// Gold splits set by deleted runs (reset to the best remaining segment, or removed)
+    pub gold_splits_fixed: usize,
+}
+
+/// Find and fix common inconsistencies left behind by older versions, crashes,
+/// or manual edits. Runs in a single transaction.
+pub fn repair() -> Result<RepairReport> {
+    let mut conn = get_db()?;
+    let tx = conn.transaction()?;
+    let report = repair_with(&tx)?;
+    tx.commit()?;
+    Ok(report)
+}
+
+fn repair_with(conn: &Connection) -> Result<RepairReport> {
+    // Children first, so orphaned splits take their snapshots and notes with them
+    let orphaned_snapshots = conn.execute(
+        "DELETE FROM snapshots
+         WHERE run_id NOT IN (SELECT id FROM runs)
+            OR split_id NOT IN (SELECT s.id FROM splits s JOIN runs r ON r.id = s.run_id)",
+        [],
+    )?;
+    let orphaned_split_notes = conn.execute(
+        "DELETE FROM split_notes
+         WHERE split_id NOT IN (SELECT s.id FROM splits s JOIN runs r ON r.id = s.run_id)",
+        [],
+    )?;
+    let orphaned_splits = conn.execute(
+        "DELETE FROM splits WHERE run_id NOT IN (SELECT id FROM runs)",
+        [],
+    )?;
+
+    // Reference runs are entered manually and never have an end time
+    let mut runs_missing_end_time = conn.execute(
+        "UPDATE runs SET ended_at = datetime(started_at, '+' || (total_time_ms / 1000) || ' seconds')
+         WHERE is_completed = 1 AND ended_at IS NULL AND is_reference = 0 AND total_time_ms IS NOT NULL",
+        [],
+    )?;
+    runs_missing_end_time += conn.execute(
+        "UPDATE runs SET is_completed = 0
+         WHERE is_completed = 1 AND ended_at IS NULL AND is_reference = 0",
+        [],
+    )?;
+
+    // Replace PBs whose run is gone with the next best completed run
+    let personal_bests_fixed = conn.execute(
+        "DELETE FROM personal_bests WHERE run_id NOT IN (SELECT id FROM runs)",
+        [],
+    )?;
+    if personal_bests_fixed > 0 {
+        fill_personal_bests(conn)?;
+    }
+
+    // Golds set by deleted runs: reset to the best segment that still exists
+    let best_remaining = "SELECT MIN(s.segment_time_ms) FROM splits s JOIN runs r ON r.id = s.run_id
+         WHERE r.is_reference = 0 AND r.category = gold_splits.category AND r.class = gold_splits.class
+           AND (s.breakpoint_key = gold_splits.breakpoint_key
+                OR (gold_splits.breakpoint_key IS NULL AND s.breakpoint_name = gold_splits.breakpoint_name))";
+    let mut gold_splits_fixed = conn.execute(
+        &format!("DELETE FROM gold_splits WHERE ({best_remaining}) IS NULL"),
+        [],
+    )?;
+    gold_splits_fixed += conn.execute(
+        &format!(
+            "UPDATE gold_splits SET best_segment_ms = ({best_remaining})
+             WHERE best_segment_ms < ({best_remaining})"
+        ),
+        [],
+    )?;
+
+    Ok(RepairReport {
+        orphaned_splits,
+        orphaned_snapshots,
+        orphaned_split_notes,
+        runs_missing_end_time,
+        personal_bests_fixed,
+        gold_splits_fixed,
+    })
+}
+
+/// Rebuild every PB (per category, class, league, and all-time or major patch)
+/// from the completed runs, returning how many there are now. Runs in a
+/// single transaction.
+pub fn recompute_personal_bests() -> Result<usize> {
+    let mut conn = get_db()?;
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM personal_bests", [])?;
+    fill_personal_bests(&tx)?;
+    let count: i64 = tx.query_row("SELECT COUNT(*) FROM personal_bests", [], |row| row.get(0))?;
+    tx.commit()?;
+    Ok(count as usize)
+}
+
+/// Add the fastest completed run as the PB of every key that has none
+fn fill_personal_bests(conn: &Connection) -> Result<()> {
+    // Fastest first, so OR IGNORE keeps the best run for each (category, class, league, patch)
+    let mut stmt = conn.prepare(
+        "SELECT * FROM runs
+         WHERE is_completed = 1 AND is_reference = 0 AND total_time_ms IS NOT NULL
+         ORDER BY total_time_ms ASC",
+    )?;
+    let runs: Vec<Run> = stmt.query_map([], Run::from_row)?.filter_map(|r| r.ok()).collect();
+    for run in runs {
+        let patch = run.game_version.as_deref().and_then(PersonalBest::major_patch);
+        for patch in std::iter::once(String::new()).chain(patch) {
+            conn.execute(
+                "INSERT OR IGNORE INTO personal_bests (category, class, ascendancy, league, patch, run_id, total_time_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![run.category, run.class, run.ascendancy, run.league, patch, run.id, run.total_time_ms],
+            )?;
+        }
+    }
+    Ok(())
+}
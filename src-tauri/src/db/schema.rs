@@ -1,6 +1,7 @@
-use rusqlite::{params, Row};
+use rusqlite::{params, OptionalExtension, Row};
 use serde::{Deserialize, Serialize};
 
+use super::compress::{pack, unpack};
 use super::get_db;
 use anyhow::Result;
 
@@ -18,6 +19,8 @@ pub struct Run {
     pub ascendancy: Option<String>,
     pub league: String,
     pub category: String,
+    /// `selected`, `detected` from the run's first log events, or `manual`
+    pub category_source: String,
     pub started_at: String,
     pub ended_at: Option<String>,
     pub total_time_ms: Option<i64>,
@@ -29,10 +32,35 @@ pub struct Run {
     // Reference run support
     pub is_reference: bool,
     pub source_name: Option<String>,
+    // Review metadata
+    pub video_url: Option<String>,
+    pub vod_offset_ms: Option<i64>,
+    pub review_notes: Option<String>,
+    // First run of a session, excluded from stats on request
+    pub is_warmup: bool,
+    // Game version detected from Client.txt when the run was created
+    pub game_version: Option<String>,
+    // Countdown the timer started with; split times are measured from the end of it
+    pub start_offset_ms: i64,
+    // Why the run was reset unfinished (an `AbandonReason`), and when on the run clock
+    pub abandon_reason: Option<String>,
+    pub abandoned_at_ms: Option<i64>,
+    // Last split reached before the reset; `None` when reset before the first split
+    pub reset_split: Option<String>,
+    pub reset_split_key: Option<String>,
+    // Loading screen time so far (`load_time.rs`), and the total time without it
+    pub load_time_ms: i64,
+    pub load_removed_time_ms: Option<i64>,
+    // `Ruleset` from the league name; `None` until the league is known
+    pub ruleset: Option<String>,
+    // The user's own note on the run ("new build test")
+    pub notes: Option<String>,
 }
 
 impl Run {
     pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let total_time_ms: Option<i64> = row.get("total_time_ms")?;
+        let load_time_ms: i64 = row.get("load_time_ms")?;
         Ok(Run {
             id: row.get("id")?,
             character_name: row.get("character_name")?,
@@ -41,23 +69,38 @@ impl Run {
             ascendancy: row.get("ascendancy")?,
             league: row.get("league")?,
             category: row.get("category")?,
+            category_source: row.get("category_source")?,
             started_at: row.get("started_at")?,
             ended_at: row.get("ended_at")?,
-            total_time_ms: row.get("total_time_ms")?,
+            total_time_ms,
             is_completed: row.get("is_completed")?,
             is_personal_best: row.get("is_personal_best")?,
             breakpoint_preset: row.get("breakpoint_preset")?,
             enabled_breakpoints: row.get("enabled_breakpoints")?,
             is_reference: row.get("is_reference")?,
             source_name: row.get("source_name")?,
+            video_url: row.get("video_url")?,
+            vod_offset_ms: row.get("vod_offset_ms")?,
+            review_notes: row.get("review_notes")?,
+            is_warmup: row.get("is_warmup")?,
+            game_version: row.get("game_version")?,
+            start_offset_ms: row.get("start_offset_ms")?,
+            abandon_reason: row.get("abandon_reason")?,
+            abandoned_at_ms: row.get("abandoned_at_ms")?,
+            reset_split: row.get("reset_split")?,
+            reset_split_key: row.get("reset_split_key")?,
+            load_time_ms,
+            load_removed_time_ms: total_time_ms.map(|total| (total - load_time_ms).max(0)),
+            ruleset: row.get("ruleset")?,
+            notes: row.get("notes")?,
         })
     }
 
     pub fn insert(run: &NewRun) -> Result<i64> {
         let conn = get_db()?;
         conn.execute(
-            "INSERT INTO runs (character_name, account_name, class, ascendancy, league, category, started_at, breakpoint_preset, enabled_breakpoints)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO runs (character_name, account_name, class, ascendancy, league, category, started_at, breakpoint_preset, enabled_breakpoints, game_version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 run.character_name,
                 run.account_name,
@@ -68,6 +111,7 @@ impl Run {
                 run.started_at,
                 run.breakpoint_preset,
                 run.enabled_breakpoints,
+                run.game_version,
             ],
         )?;
         Ok(conn.last_insert_rowid())
@@ -82,15 +126,157 @@ impl Run {
         Ok(())
     }
 
-    pub fn update_character(id: i64, character_name: &str, class: &str) -> Result<()> {
+    /// Mark an unfinished run as reset at its last split, and why if known;
+    /// finished runs are left alone
+    pub fn abandon(id: i64, reason: Option<&str>, abandoned_at_ms: i64) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE runs SET abandon_reason = ?1, abandoned_at_ms = ?2, ended_at = COALESCE(ended_at, datetime('now')),
+                 reset_split = (SELECT breakpoint_name FROM splits WHERE run_id = ?3 ORDER BY split_time_ms DESC LIMIT 1),
+                 reset_split_key = (SELECT breakpoint_key FROM splits WHERE run_id = ?3 ORDER BY split_time_ms DESC LIMIT 1)
+             WHERE id = ?3 AND is_completed = 0",
+            params![reason, abandoned_at_ms, id],
+        )?;
+        Ok(updated)
+    }
+
+    /// Restore the end time of a run recorded elsewhere (`complete` stamps now)
+    pub fn set_ended_at(id: i64, ended_at: &str) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("UPDATE runs SET ended_at = ?1 WHERE id = ?2", params![ended_at, id])?;
+        Ok(())
+    }
+
+    /// Rename the run's character; `class` is left alone when `None`
+    pub fn update_character(id: i64, character_name: &str, class: Option<&str>) -> Result<()> {
         let conn = get_db()?;
         conn.execute(
-            "UPDATE runs SET character_name = ?1, class = ?2 WHERE id = ?3",
+            "UPDATE runs SET character_name = ?1, class = COALESCE(?2, class) WHERE id = ?3",
             params![character_name, class, id],
         )?;
         Ok(())
     }
 
+    /// Set the class, and the ascendancy unless `None` (an unascended level-up
+    /// doesn't clear one the API reported)
+    pub fn set_class(id: i64, class: &str, ascendancy: Option<&str>) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "UPDATE runs SET class = ?1, ascendancy = COALESCE(?2, ascendancy) WHERE id = ?3",
+            params![class, ascendancy, id],
+        )?;
+        Ok(())
+    }
+
+    /// Relabel the run; `source` is `detected` or `manual`
+    pub fn set_category(id: i64, category: &str, source: &str) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE runs SET category = ?1, category_source = ?2 WHERE id = ?3",
+            params![category, source, id],
+        )?;
+        Ok(updated)
+    }
+
+    pub fn set_ruleset(id: i64, ruleset: &str) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute("UPDATE runs SET ruleset = ?1 WHERE id = ?2", params![ruleset, id])?;
+        Ok(updated)
+    }
+
+    /// Add a loading screen to the run's load time
+    pub fn add_load_time(id: i64, load_ms: i64) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE runs SET load_time_ms = load_time_ms + ?1 WHERE id = ?2",
+            params![load_ms, id],
+        )?;
+        Ok(updated)
+    }
+
+    pub fn set_notes(id: i64, notes: Option<&str>) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute("UPDATE runs SET notes = ?1 WHERE id = ?2", params![notes, id])?;
+        Ok(updated)
+    }
+
+    pub fn update_review(id: i64, review: &RunReview) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE runs SET video_url = ?1, vod_offset_ms = ?2, review_notes = ?3 WHERE id = ?4",
+            params![review.video_url, review.vod_offset_ms, review.review_notes, id],
+        )?;
+        Ok(updated)
+    }
+
+    pub fn set_start_offset(id: i64, start_offset_ms: i64) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE runs SET start_offset_ms = ?1 WHERE id = ?2",
+            params![start_offset_ms, id],
+        )?;
+        Ok(updated)
+    }
+
+    pub fn set_warmup(id: i64, is_warmup: bool) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE runs SET is_warmup = ?1 WHERE id = ?2",
+            params![is_warmup, id],
+        )?;
+        Ok(updated)
+    }
+
+    /// Game version of the most recently started run that has one
+    pub fn latest_game_version() -> Result<Option<String>> {
+        let conn = get_db()?;
+        let version = conn
+            .query_row(
+                "SELECT game_version FROM runs WHERE game_version IS NOT NULL
+                 ORDER BY started_at DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .ok();
+        Ok(version)
+    }
+
+    /// Minutes between this run's start and the latest earlier non-reference run,
+    /// or `None` if it is the first run recorded
+    pub fn minutes_since_previous(id: i64) -> Result<Option<f64>> {
+        let conn = get_db()?;
+        let minutes = conn.query_row(
+            "SELECT (julianday(r.started_at) - MAX(julianday(p.started_at))) * 1440
+             FROM runs r JOIN runs p ON p.id != r.id AND p.is_reference = 0
+               AND julianday(p.started_at) <= julianday(r.started_at)
+             WHERE r.id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        Ok(minutes)
+    }
+
+    /// The most recently started run of your own (not a reference run)
+    pub fn latest() -> Result<Option<Run>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM runs WHERE is_reference = 0 ORDER BY started_at DESC LIMIT 1")?;
+        let run = stmt.query_row([], Run::from_row).ok();
+        Ok(run)
+    }
+
+    /// The most recently started run of a category on a breakpoint preset that
+    /// recorded which breakpoints were enabled
+    pub fn latest_with_preset(category: &str, preset: &str) -> Result<Option<Run>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM runs
+             WHERE category = ?1 AND breakpoint_preset = ?2 AND enabled_breakpoints IS NOT NULL
+             ORDER BY started_at DESC LIMIT 1",
+        )?;
+        let run = stmt.query_row(params![category, preset], Run::from_row).ok();
+        Ok(run)
+    }
+
     pub fn get_all() -> Result<Vec<Run>> {
         let conn = get_db()?;
         let mut stmt = conn.prepare("SELECT * FROM runs ORDER BY started_at DESC")?;
@@ -101,6 +287,17 @@ impl Run {
         Ok(runs)
     }
 
+    /// The `limit` most recently started runs, reference runs left out
+    pub fn get_recent(limit: usize) -> Result<Vec<Run>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM runs WHERE is_reference = 0 ORDER BY started_at DESC LIMIT ?1")?;
+        let runs = stmt
+            .query_map([limit as i64], Run::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(runs)
+    }
+
     pub fn get_by_id(id: i64) -> Result<Option<Run>> {
         let conn = get_db()?;
         let mut stmt = conn.prepare("SELECT * FROM runs WHERE id = ?1")?;
@@ -112,8 +309,18 @@ impl Run {
         let conn = get_db()?;
         // Delete associated snapshots first
         conn.execute("DELETE FROM snapshots WHERE run_id = ?1", params![id])?;
-        // Delete associated splits
+        // Delete associated split notes, tags and splits
+        conn.execute(
+            "DELETE FROM split_notes WHERE split_id IN (SELECT id FROM splits WHERE run_id = ?1)",
+            params![id],
+        )?;
+        conn.execute("DELETE FROM run_tags WHERE run_id = ?1", params![id])?;
         conn.execute("DELETE FROM splits WHERE run_id = ?1", params![id])?;
+        conn.execute("DELETE FROM run_events WHERE run_id = ?1", params![id])?;
+        conn.execute("DELETE FROM zone_times WHERE run_id = ?1", params![id])?;
+        conn.execute("DELETE FROM deaths WHERE run_id = ?1", params![id])?;
+        conn.execute("DELETE FROM skipped_splits WHERE run_id = ?1", params![id])?;
+        conn.execute("DELETE FROM pauses WHERE run_id = ?1", params![id])?;
         // Delete the run
         conn.execute("DELETE FROM runs WHERE id = ?1", params![id])?;
         Ok(())
@@ -152,49 +359,8 @@ impl Run {
     pub fn get_filtered(filters: &RunFilters) -> Result<Vec<Run>> {
         let conn = get_db()?;
 
-        let mut sql = String::from("SELECT * FROM runs WHERE 1=1");
-        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-        if let Some(ref class) = filters.class {
-            sql.push_str(" AND class = ?");
-            params_vec.push(Box::new(class.clone()));
-        }
-
-        if let Some(ref ascendancy) = filters.ascendancy {
-            sql.push_str(" AND ascendancy = ?");
-            params_vec.push(Box::new(ascendancy.clone()));
-        }
-
-        if let Some(ref category) = filters.category {
-            sql.push_str(" AND category = ?");
-            params_vec.push(Box::new(category.clone()));
-        }
-
-        if let Some(ref league) = filters.league {
-            sql.push_str(" AND league = ?");
-            params_vec.push(Box::new(league.clone()));
-        }
-
-        if let Some(ref preset) = filters.breakpoint_preset {
-            sql.push_str(" AND breakpoint_preset = ?");
-            params_vec.push(Box::new(preset.clone()));
-        }
-
-        if let Some(completed) = filters.is_completed {
-            sql.push_str(" AND is_completed = ?");
-            params_vec.push(Box::new(completed as i32));
-        }
-
-        if let Some(reference) = filters.include_reference {
-            if !reference {
-                sql.push_str(" AND is_reference = 0");
-            }
-        } else {
-            // By default, exclude reference runs
-            sql.push_str(" AND is_reference = 0");
-        }
-
-        sql.push_str(" ORDER BY started_at DESC");
+        let (clause, params_vec) = filters.where_clause();
+        let sql = format!("SELECT * FROM runs WHERE {} ORDER BY started_at DESC", clause);
 
         let mut stmt = conn.prepare(&sql)?;
         let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
@@ -205,33 +371,67 @@ impl Run {
         Ok(runs)
     }
 
-    /// Get statistics for runs matching the given filters
-    pub fn get_stats(filters: &RunFilters) -> Result<RunStats> {
-        let runs = Run::get_filtered(filters)?;
+    /// One page of the runs matching the filters, newest first, with the
+    /// number of matching runs across all pages
+    pub fn get_paged(filters: &RunFilters, offset: i64, limit: i64) -> Result<RunPage> {
+        let conn = get_db()?;
 
-        let total_runs = runs.len() as i64;
-        let completed_runs: Vec<&Run> = runs.iter().filter(|r| r.is_completed).collect();
-        let completed_count = completed_runs.len() as i64;
+        let (clause, mut params_vec) = filters.where_clause();
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+        let total = conn.query_row(
+            &format!("SELECT COUNT(*) FROM runs WHERE {}", clause),
+            params_refs.as_slice(),
+            |row| row.get(0),
+        )?;
 
-        let completed_times: Vec<i64> = completed_runs
-            .iter()
-            .filter_map(|r| r.total_time_ms)
+        params_vec.push(Box::new(limit.max(0)));
+        params_vec.push(Box::new(offset.max(0)));
+        let sql = format!("SELECT * FROM runs WHERE {} ORDER BY started_at DESC LIMIT ? OFFSET ?", clause);
+        let mut stmt = conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+        let runs = stmt
+            .query_map(params_refs.as_slice(), Run::from_row)?
+            .filter_map(|r| r.ok())
             .collect();
+        Ok(RunPage { runs, total })
+    }
 
-        let average_time_ms = if !completed_times.is_empty() {
-            Some(completed_times.iter().sum::<i64>() / completed_times.len() as i64)
-        } else {
-            None
-        };
-
-        let best_time_ms = completed_times.iter().min().copied();
+    /// Get statistics for runs matching the given filters
+    pub fn get_stats(filters: &RunFilters) -> Result<RunStats> {
+        let conn = get_db()?;
 
-        Ok(RunStats {
-            total_runs,
-            completed_runs: completed_count,
-            average_time_ms,
-            best_time_ms,
-        })
+        // One pass in SQL, rather than loading every run and counting its deaths one by one
+        let (clause, params_vec) = filters.where_clause();
+        let sql = format!(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(is_completed), 0),
+                    SUM(CASE WHEN is_completed = 1 THEN total_time_ms END)
+                      / COUNT(CASE WHEN is_completed = 1 THEN total_time_ms END),
+                    MIN(CASE WHEN is_completed = 1 THEN total_time_ms END),
+                    COALESCE(SUM(d.deaths), 0),
+                    COALESCE(SUM(d.deaths IS NULL), 0),
+                    SUM(CASE WHEN is_completed = 1 THEN total_time_ms - load_time_ms END)
+                      / COUNT(CASE WHEN is_completed = 1 THEN total_time_ms END),
+                    MIN(CASE WHEN is_completed = 1 THEN total_time_ms - load_time_ms END)
+             FROM runs
+             LEFT JOIN (SELECT run_id, COUNT(*) AS deaths FROM deaths GROUP BY run_id) d ON d.run_id = runs.id
+             WHERE {}",
+            clause
+        );
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+        let stats = conn.query_row(&sql, params_refs.as_slice(), |row| {
+            Ok(RunStats {
+                total_runs: row.get(0)?,
+                completed_runs: row.get(1)?,
+                average_time_ms: row.get(2)?,
+                best_time_ms: row.get(3)?,
+                total_deaths: row.get(4)?,
+                deathless_runs: row.get(5)?,
+                average_load_removed_ms: row.get(6)?,
+                best_load_removed_ms: row.get(7)?,
+            })
+        })?;
+        Ok(stats)
     }
 
     /// Insert a reference run (manually entered external times)
@@ -257,6 +457,26 @@ impl Run {
     }
 }
 
+/// VOD link and review notes attached to a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReview {
+    pub video_url: Option<String>,
+    /// Position in the VOD where the run timer started
+    pub vod_offset_ms: Option<i64>,
+    pub review_notes: Option<String>,
+}
+
+impl From<&Run> for RunReview {
+    fn from(run: &Run) -> Self {
+        RunReview {
+            video_url: run.video_url.clone(),
+            vod_offset_ms: run.vod_offset_ms,
+            review_notes: run.review_notes.clone(),
+        }
+    }
+}
+
 /// Filters for querying runs
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -268,6 +488,100 @@ pub struct RunFilters {
     pub breakpoint_preset: Option<String>,
     pub is_completed: Option<bool>,
     pub include_reference: Option<bool>,
+    pub exclude_warmups: Option<bool>,
+    /// `SC`, `HC`, `SSF` or `HCSSF`
+    pub ruleset: Option<String>,
+    pub game_version: Option<String>,
+    /// Runs tagged with this (on the run or one of its splits)
+    pub tag: Option<String>,
+    /// Text in the run's note or one of its split notes
+    pub note: Option<String>,
+}
+
+impl RunFilters {
+    /// SQL condition on `runs` columns for these filters, with its parameters
+    fn where_clause(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut sql = String::from("1=1");
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(ref class) = self.class {
+            sql.push_str(" AND class = ?");
+            params_vec.push(Box::new(class.clone()));
+        }
+
+        if let Some(ref ascendancy) = self.ascendancy {
+            sql.push_str(" AND ascendancy = ?");
+            params_vec.push(Box::new(ascendancy.clone()));
+        }
+
+        if let Some(ref category) = self.category {
+            sql.push_str(" AND category = ?");
+            params_vec.push(Box::new(category.clone()));
+        }
+
+        if let Some(ref league) = self.league {
+            sql.push_str(" AND league = ?");
+            params_vec.push(Box::new(league.clone()));
+        }
+
+        if let Some(ref ruleset) = self.ruleset {
+            sql.push_str(" AND ruleset = ?");
+            params_vec.push(Box::new(ruleset.clone()));
+        }
+
+        if let Some(ref game_version) = self.game_version {
+            sql.push_str(" AND game_version = ?");
+            params_vec.push(Box::new(game_version.clone()));
+        }
+
+        if let Some(ref tag) = self.tag {
+            sql.push_str(" AND id IN (SELECT run_id FROM run_tags WHERE tag = ?)");
+            params_vec.push(Box::new(RunTag::normalize(tag)));
+        }
+
+        if let Some(ref note) = self.note {
+            sql.push_str(
+                " AND (notes LIKE ? OR id IN (SELECT s.run_id FROM split_notes n JOIN splits s ON s.id = n.split_id
+                                             WHERE n.note LIKE ?))",
+            );
+            let pattern = format!("%{}%", note.trim());
+            params_vec.push(Box::new(pattern.clone()));
+            params_vec.push(Box::new(pattern));
+        }
+
+        if let Some(ref preset) = self.breakpoint_preset {
+            sql.push_str(" AND breakpoint_preset = ?");
+            params_vec.push(Box::new(preset.clone()));
+        }
+
+        if let Some(completed) = self.is_completed {
+            sql.push_str(" AND is_completed = ?");
+            params_vec.push(Box::new(completed as i32));
+        }
+
+        if let Some(reference) = self.include_reference {
+            if !reference {
+                sql.push_str(" AND is_reference = 0");
+            }
+        } else {
+            // By default, exclude reference runs
+            sql.push_str(" AND is_reference = 0");
+        }
+
+        if self.exclude_warmups == Some(true) {
+            sql.push_str(" AND is_warmup = 0");
+        }
+
+        (sql, params_vec)
+    }
+}
+
+/// One page of runs and how many runs there are across all pages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunPage {
+    pub runs: Vec<Run>,
+    pub total: i64,
 }
 
 /// Statistics for a set of runs
@@ -278,6 +592,11 @@ pub struct RunStats {
     pub completed_runs: i64,
     pub average_time_ms: Option<i64>,
     pub best_time_ms: Option<i64>,
+    pub total_deaths: i64,
+    pub deathless_runs: i64,
+    /// Completed runs' times without loading screens
+    pub average_load_removed_ms: Option<i64>,
+    pub best_load_removed_ms: Option<i64>,
 }
 
 /// Statistics for a specific breakpoint across multiple runs
@@ -285,10 +604,36 @@ pub struct RunStats {
 #[serde(rename_all = "camelCase")]
 pub struct SplitStat {
     pub breakpoint_name: String,
+    pub breakpoint_key: Option<String>,
     pub average_time_ms: i64,
     pub best_time_ms: i64,
+    pub median_time_ms: i64,
+    pub p90_time_ms: i64,
     pub average_town_time_ms: i64,
     pub run_count: i64,
+    /// Segment averages per layout variant, so seed luck can be told apart from execution
+    pub layouts: Vec<LayoutStat>,
+    /// Experience gained during the segment, over runs where it is known
+    pub average_segment_experience: Option<i64>,
+    pub experience_per_minute: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutStat {
+    pub layout_variant: String,
+    pub average_segment_ms: i64,
+    pub run_count: i64,
+}
+
+/// A run's time at a breakpoint, with the average over it and the runs just before it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitTrendPoint {
+    pub run_id: i64,
+    pub started_at: String,
+    pub split_time_ms: i64,
+    pub rolling_average_ms: i64,
 }
 
 /// Data for creating a reference run
@@ -303,7 +648,9 @@ pub struct ReferenceRunData {
     pub league: Option<String>,
     pub breakpoint_preset: Option<String>,
     pub enabled_breakpoints: Option<String>,
+    #[serde(default)]
     pub total_time_ms: i64,
+    #[serde(default)]
     pub splits: Vec<ReferenceSplitData>,
 }
 
@@ -313,6 +660,8 @@ pub struct ReferenceRunData {
 pub struct ReferenceSplitData {
     pub breakpoint_name: String,
     pub breakpoint_type: String,
+    #[serde(default)]
+    pub breakpoint_key: Option<String>,
     pub split_time_ms: i64,
 }
 
@@ -331,6 +680,9 @@ pub struct NewRun {
     pub breakpoint_preset: Option<String>,
     #[serde(default)]
     pub enabled_breakpoints: Option<String>,
+    // Filled from the log watcher's detected version when not given
+    #[serde(default)]
+    pub game_version: Option<String>,
 }
 
 // ============================================================================
@@ -344,12 +696,26 @@ pub struct Split {
     pub run_id: i64,
     pub breakpoint_type: String,
     pub breakpoint_name: String,
+    /// Canonical location key (e.g. `zone:a2:the_southern_forest`), shared across presets
+    pub breakpoint_key: Option<String>,
     pub split_time_ms: i64,
     pub delta_ms: Option<i64>,
     pub segment_time_ms: i64,
     // Town/hideout time tracking (cumulative at this split)
     pub town_time_ms: i64,
     pub hideout_time_ms: i64,
+    // Town/hideout time spent during this segment only
+    pub segment_town_time_ms: i64,
+    pub segment_hideout_time_ms: i64,
+    // Zone layout RNG: manual tag, or "fast"/"typical"/"slow" when inferred
+    pub layout_variant: Option<String>,
+    pub layout_inferred: bool,
+    // Character experience from the split's snapshot
+    pub experience: Option<i64>,
+    // Character level from the run's last level-up, or the split's snapshot
+    pub character_level: Option<i32>,
+    // Loading screen time (cumulative at this split)
+    pub load_time_ms: i64,
 }
 
 impl Split {
@@ -359,33 +725,72 @@ impl Split {
             run_id: row.get("run_id")?,
             breakpoint_type: row.get("breakpoint_type")?,
             breakpoint_name: row.get("breakpoint_name")?,
+            breakpoint_key: row.get("breakpoint_key")?,
             split_time_ms: row.get("split_time_ms")?,
             delta_ms: row.get("delta_ms")?,
             segment_time_ms: row.get("segment_time_ms")?,
             town_time_ms: row.get("town_time_ms")?,
             hideout_time_ms: row.get("hideout_time_ms")?,
+            segment_town_time_ms: row.get("segment_town_time_ms")?,
+            segment_hideout_time_ms: row.get("segment_hideout_time_ms")?,
+            layout_variant: row.get("layout_variant")?,
+            layout_inferred: row.get("layout_inferred")?,
+            experience: row.get("experience")?,
+            character_level: row.get("character_level")?,
+            load_time_ms: row.get("load_time_ms")?,
         })
     }
 
+    /// Split time without loading screens
+    pub fn load_removed_time_ms(&self) -> i64 {
+        (self.split_time_ms - self.load_time_ms).max(0)
+    }
+
+    /// Insert a split. Per-segment town/hideout time is derived from the previous
+    /// split's cumulative values in the same run.
     pub fn insert(split: &NewSplit) -> Result<i64> {
         let conn = get_db()?;
+        let (prev_town, prev_hideout): (i64, i64) = conn
+            .query_row(
+                "SELECT town_time_ms, hideout_time_ms FROM splits
+                 WHERE run_id = ?1 AND split_time_ms <= ?2
+                 ORDER BY split_time_ms DESC, id DESC LIMIT 1",
+                params![split.run_id, split.split_time_ms],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap_or((0, 0));
+
         conn.execute(
-            "INSERT INTO splits (run_id, breakpoint_type, breakpoint_name, split_time_ms, delta_ms, segment_time_ms, town_time_ms, hideout_time_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO splits (run_id, breakpoint_type, breakpoint_name, breakpoint_key, split_time_ms, delta_ms, segment_time_ms, town_time_ms, hideout_time_ms,
+                                 segment_town_time_ms, segment_hideout_time_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 split.run_id,
                 split.breakpoint_type,
                 split.breakpoint_name,
+                split.breakpoint_key,
                 split.split_time_ms,
                 split.delta_ms,
                 split.segment_time_ms,
                 split.town_time_ms,
                 split.hideout_time_ms,
+                (split.town_time_ms - prev_town).max(0),
+                (split.hideout_time_ms - prev_hideout).max(0),
             ],
         )?;
         Ok(conn.last_insert_rowid())
     }
 
+    /// Act of the split's location, from its canonical key (`zone:a2:...`, `kitava:a5`)
+    pub fn act(&self) -> Option<i32> {
+        Split::act_of_key(self.breakpoint_key.as_deref()?)
+    }
+
+    /// Act named in a canonical breakpoint key
+    pub fn act_of_key(key: &str) -> Option<i32> {
+        key.split(':').find_map(|part| part.strip_prefix('a')?.parse().ok())
+    }
+
     pub fn get_by_run(run_id: i64) -> Result<Vec<Split>> {
         let conn = get_db()?;
         let mut stmt = conn.prepare("SELECT * FROM splits WHERE run_id = ?1 ORDER BY split_time_ms")?;
@@ -396,70 +801,402 @@ impl Split {
         Ok(splits)
     }
 
-    /// Get split statistics for runs matching the given filters
-    pub fn get_stats(filters: &RunFilters) -> Result<Vec<SplitStat>> {
-        let runs = Run::get_filtered(filters)?;
-        if runs.is_empty() {
-            return Ok(Vec::new());
-        }
+    /// Most recently recorded split of a run
+    pub fn get_last(run_id: i64) -> Result<Option<Split>> {
+        let conn = get_db()?;
+        let split = conn
+            .query_row(
+                "SELECT * FROM splits WHERE run_id = ?1 ORDER BY split_time_ms DESC, id DESC LIMIT 1",
+                [run_id],
+                Split::from_row,
+            )
+            .ok();
+        Ok(split)
+    }
 
-        // Collect all splits for matching runs
-        let mut splits_by_breakpoint: std::collections::HashMap<String, Vec<Split>> =
-            std::collections::HashMap::new();
+    pub fn delete(id: i64) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("DELETE FROM snapshots WHERE split_id = ?1", params![id])?;
+        conn.execute("DELETE FROM split_notes WHERE split_id = ?1", params![id])?;
+        conn.execute("DELETE FROM run_tags WHERE split_id = ?1", params![id])?;
+        conn.execute("DELETE FROM splits WHERE id = ?1", params![id])?;
+        Ok(())
+    }
 
-        for run in &runs {
-            if let Ok(splits) = Split::get_by_run(run.id) {
-                for split in splits {
-                    splits_by_breakpoint
-                        .entry(split.breakpoint_name.clone())
-                        .or_default()
-                        .push(split);
-                }
-            }
-        }
+    pub fn set_experience(id: i64, experience: i64) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE splits SET experience = ?1 WHERE id = ?2",
+            params![experience, id],
+        )?;
+        Ok(updated)
+    }
 
-        // Calculate stats for each breakpoint
-        let mut stats: Vec<SplitStat> = splits_by_breakpoint
-            .into_iter()
-            .map(|(name, splits)| {
-                let count = splits.len() as i64;
-                let total_time: i64 = splits.iter().map(|s| s.split_time_ms).sum();
-                let total_town: i64 = splits.iter().map(|s| s.town_time_ms).sum();
-                let best_time = splits.iter().map(|s| s.split_time_ms).min().unwrap_or(0);
-
-                SplitStat {
-                    breakpoint_name: name,
-                    average_time_ms: total_time / count,
-                    best_time_ms: best_time,
-                    average_town_time_ms: total_town / count,
-                    run_count: count,
-                }
-            })
-            .collect();
+    pub fn set_load_time(id: i64, load_time_ms: i64) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE splits SET load_time_ms = ?1 WHERE id = ?2",
+            params![load_time_ms, id],
+        )?;
+        Ok(updated)
+    }
 
-        // Sort by average time
-        stats.sort_by(|a, b| a.average_time_ms.cmp(&b.average_time_ms));
+    pub fn set_character_level(id: i64, character_level: i32) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE splits SET character_level = ?1 WHERE id = ?2",
+            params![character_level, id],
+        )?;
+        Ok(updated)
+    }
 
-        Ok(stats)
+    pub fn set_layout(id: i64, layout_variant: Option<&str>, inferred: bool) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE splits SET layout_variant = ?1, layout_inferred = ?2 WHERE id = ?3",
+            params![layout_variant, inferred, id],
+        )?;
+        Ok(updated)
     }
-}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct NewSplit {
-    pub run_id: i64,
-    pub breakpoint_type: String,
-    pub breakpoint_name: String,
-    pub split_time_ms: i64,
-    pub delta_ms: Option<i64>,
-    pub segment_time_ms: i64,
-    // Town/hideout time tracking (cumulative at this split)
+    /// Segment times previously recorded at the same location for a category/class,
+    /// matched by canonical key when known
+    pub fn segment_history(
+        category: &str,
+        class: &str,
+        breakpoint_name: &str,
+        breakpoint_key: Option<&str>,
+        exclude_run_id: i64,
+    ) -> Result<Vec<i64>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT s.segment_time_ms FROM splits s JOIN runs r ON r.id = s.run_id
+             WHERE r.category = ?1 AND r.class = ?2 AND r.id != ?5 AND r.is_reference = 0
+               AND (s.breakpoint_key = ?4 OR (?4 IS NULL AND s.breakpoint_name = ?3))",
+        )?;
+        let segments = stmt
+            .query_map(
+                params![category, class, breakpoint_name, breakpoint_key, exclude_run_id],
+                |row| row.get(0),
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(segments)
+    }
+
+    /// Get split statistics for runs matching the given filters
+    pub fn get_stats(filters: &RunFilters) -> Result<Vec<SplitStat>> {
+        let conn = get_db()?;
+
+        // Splits of matching runs, grouped by canonical key when known so the same
+        // location is merged across differently named presets, with the experience
+        // gained in each split's segment. That's known only when the split and the
+        // one before it both have experience; the first split counts from zero,
+        // since runs start on a new character.
+        let (clause, params_vec) = filters.where_clause();
+        let matching = format!(
+            "WITH s AS (
+                SELECT id, run_id, breakpoint_name, breakpoint_key, split_time_ms, town_time_ms,
+                       segment_time_ms, experience, layout_variant,
+                       COALESCE(breakpoint_key, breakpoint_name) AS grp,
+                       experience - CASE WHEN ROW_NUMBER() OVER w = 1 THEN 0
+                                         ELSE LAG(experience) OVER w END AS gained
+                FROM splits
+                WHERE run_id IN (SELECT id FROM runs WHERE {})
+                WINDOW w AS (PARTITION BY run_id ORDER BY split_time_ms, id)
+             )",
+            clause
+        );
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let mut layouts: std::collections::HashMap<String, Vec<LayoutStat>> =
+            std::collections::HashMap::new();
+        let mut stmt = conn.prepare(&format!(
+            "{}
+             SELECT grp, layout_variant, SUM(segment_time_ms) / COUNT(*), COUNT(*)
+             FROM s WHERE layout_variant IS NOT NULL
+             GROUP BY grp, layout_variant ORDER BY layout_variant",
+            matching
+        ))?;
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                LayoutStat {
+                    layout_variant: row.get(1)?,
+                    average_segment_ms: row.get(2)?,
+                    run_count: row.get(3)?,
+                },
+            ))
+        })?;
+        for (group, layout) in rows.filter_map(|r| r.ok()) {
+            layouts.entry(group).or_default().push(layout);
+        }
+
+        // Median is the lower one for an even count; p90 is by nearest rank.
+        // Name and key come from the latest run to reach the location.
+        let mut stmt = conn.prepare(&format!(
+            "{},
+             ranked AS (
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY grp ORDER BY split_time_ms) AS pos,
+                       COUNT(*) OVER (PARTITION BY grp) AS n,
+                       ROW_NUMBER() OVER (PARTITION BY grp ORDER BY run_id DESC, id DESC) AS recent
+                FROM s
+             )
+             SELECT grp,
+                    MAX(CASE WHEN recent = 1 THEN breakpoint_name END),
+                    MAX(CASE WHEN recent = 1 THEN breakpoint_key END),
+                    SUM(split_time_ms) / COUNT(*),
+                    MIN(split_time_ms),
+                    MIN(CASE WHEN pos * 2 >= n THEN split_time_ms END),
+                    MIN(CASE WHEN pos * 10 >= n * 9 THEN split_time_ms END),
+                    SUM(town_time_ms) / COUNT(*),
+                    COUNT(*),
+                    SUM(CASE WHEN gained >= 0 THEN gained END)
+                      / COUNT(CASE WHEN gained >= 0 THEN gained END),
+                    SUM(CASE WHEN gained >= 0 THEN gained END) * 60000
+                      / NULLIF(SUM(CASE WHEN gained >= 0 THEN segment_time_ms END), 0)
+             FROM ranked
+             GROUP BY grp
+             ORDER BY 4, grp",
+            matching
+        ))?;
+        let stats = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                let group: String = row.get(0)?;
+                Ok(SplitStat {
+                    breakpoint_name: row.get(1)?,
+                    breakpoint_key: row.get(2)?,
+                    average_time_ms: row.get(3)?,
+                    best_time_ms: row.get(4)?,
+                    median_time_ms: row.get(5)?,
+                    p90_time_ms: row.get(6)?,
+                    average_town_time_ms: row.get(7)?,
+                    run_count: row.get(8)?,
+                    layouts: layouts.remove(&group).unwrap_or_default(),
+                    average_segment_experience: row.get(9)?,
+                    experience_per_minute: row.get(10)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(stats)
+    }
+
+    /// Split times at a breakpoint for the latest `limit` runs matching the filters
+    /// that reached it, oldest first, each with a rolling average over `window` runs.
+    /// Splits sharing the breakpoint's canonical key count under any name.
+    pub fn get_trends(
+        filters: &RunFilters,
+        breakpoint_name: &str,
+        limit: i64,
+        window: i64,
+    ) -> Result<Vec<SplitTrendPoint>> {
+        let conn = get_db()?;
+
+        // The average covers earlier runs beyond the limit, so the first points have a full window
+        let (clause, clause_params) = filters.where_clause();
+        let sql = format!(
+            "WITH t AS (
+                SELECT runs.id, runs.started_at, MIN(s.split_time_ms) AS split_time_ms
+                FROM splits s JOIN runs ON runs.id = s.run_id
+                WHERE (s.breakpoint_name = ?
+                       OR s.breakpoint_key IN (SELECT breakpoint_key FROM splits WHERE breakpoint_name = ?))
+                  AND s.run_id IN (SELECT id FROM runs WHERE {})
+                GROUP BY runs.id
+             ),
+             rolling AS (
+                SELECT *, CAST(AVG(split_time_ms) OVER (
+                    ORDER BY started_at, id ROWS BETWEEN {} PRECEDING AND CURRENT ROW
+                ) AS INTEGER) AS rolling_average_ms
+                FROM t
+             )
+             SELECT * FROM (SELECT * FROM rolling ORDER BY started_at DESC, id DESC LIMIT ?)
+             ORDER BY started_at, id",
+            clause,
+            (window - 1).max(0)
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> =
+            vec![Box::new(breakpoint_name.to_string()), Box::new(breakpoint_name.to_string())];
+        params_vec.extend(clause_params);
+        params_vec.push(Box::new(limit));
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let mut stmt = conn.prepare(&sql)?;
+        let points = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok(SplitTrendPoint {
+                    run_id: row.get(0)?,
+                    started_at: row.get(1)?,
+                    split_time_ms: row.get(2)?,
+                    rolling_average_ms: row.get(3)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(points)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSplit {
+    pub run_id: i64,
+    pub breakpoint_type: String,
+    pub breakpoint_name: String,
+    #[serde(default)]
+    pub breakpoint_key: Option<String>,
+    pub split_time_ms: i64,
+    pub delta_ms: Option<i64>,
+    pub segment_time_ms: i64,
+    // Town/hideout time tracking (cumulative at this split)
     #[serde(default)]
     pub town_time_ms: i64,
     #[serde(default)]
     pub hideout_time_ms: i64,
 }
 
+// ============================================================================
+// Split Note
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitNote {
+    pub id: i64,
+    pub split_id: i64,
+    pub note: String,
+    /// Loss source tag, e.g. "death", "bad_layout", "misclicked_portal"
+    pub mistake_type: Option<String>,
+    pub created_at: String,
+}
+
+impl SplitNote {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(SplitNote {
+            id: row.get("id")?,
+            split_id: row.get("split_id")?,
+            note: row.get("note")?,
+            mistake_type: row.get("mistake_type")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+
+    pub fn insert(note: &NewSplitNote) -> Result<i64> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO split_notes (split_id, note, mistake_type) VALUES (?1, ?2, ?3)",
+            params![note.split_id, note.note, note.mistake_type],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get_by_run(run_id: i64) -> Result<Vec<SplitNote>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT n.* FROM split_notes n JOIN splits s ON s.id = n.split_id
+             WHERE s.run_id = ?1 ORDER BY s.split_time_ms, n.id",
+        )?;
+        let notes = stmt
+            .query_map([run_id], SplitNote::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(notes)
+    }
+
+    pub fn delete(id: i64) -> Result<usize> {
+        let conn = get_db()?;
+        Ok(conn.execute("DELETE FROM split_notes WHERE id = ?1", params![id])?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSplitNote {
+    pub split_id: i64,
+    #[serde(default)]
+    pub note: String,
+    pub mistake_type: Option<String>,
+}
+
+// ============================================================================
+// Run Tag
+// ============================================================================
+
+/// A tag on a run, or on one of its splits when `split_id` is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTag {
+    pub id: i64,
+    pub run_id: i64,
+    pub split_id: Option<i64>,
+    pub tag: String,
+    pub created_at: String,
+}
+
+impl RunTag {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(RunTag {
+            id: row.get("id")?,
+            run_id: row.get("run_id")?,
+            split_id: row.get("split_id")?,
+            tag: row.get("tag")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+
+    /// Tags are matched case-insensitively, with whitespace collapsed
+    /// ("Lag  Spike" -> "lag spike")
+    pub fn normalize(tag: &str) -> String {
+        tag.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+    }
+
+    /// Tag a run or one of its splits; tagging it again returns the existing tag
+    pub fn add(run_id: i64, split_id: Option<i64>, tag: &str) -> Result<RunTag> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT OR IGNORE INTO run_tags (run_id, split_id, tag) VALUES (?1, ?2, ?3)",
+            params![run_id, split_id, tag],
+        )?;
+        let tag = conn.query_row(
+            "SELECT * FROM run_tags WHERE run_id = ?1 AND COALESCE(split_id, 0) = COALESCE(?2, 0) AND tag = ?3",
+            params![run_id, split_id, tag],
+            RunTag::from_row,
+        )?;
+        Ok(tag)
+    }
+
+    /// A run's tags, the whole run's first, then its splits' in split order
+    pub fn get_by_run(run_id: i64) -> Result<Vec<RunTag>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT t.* FROM run_tags t LEFT JOIN splits s ON s.id = t.split_id
+             WHERE t.run_id = ?1 ORDER BY t.split_id IS NOT NULL, s.split_time_ms, t.tag",
+        )?;
+        let tags = stmt
+            .query_map([run_id], RunTag::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tags)
+    }
+
+    /// Every tag in use, alphabetically
+    pub fn names() -> Result<Vec<String>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT DISTINCT tag FROM run_tags ORDER BY tag")?;
+        let names = stmt
+            .query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(names)
+    }
+
+    pub fn delete(id: i64) -> Result<usize> {
+        let conn = get_db()?;
+        Ok(conn.execute("DELETE FROM run_tags WHERE id = ?1", params![id])?)
+    }
+}
+
 // ============================================================================
 // Snapshot
 // ============================================================================
@@ -469,15 +1206,38 @@ pub struct NewSplit {
 pub struct Snapshot {
     pub id: i64,
     pub run_id: i64,
-    pub split_id: i64,
+    /// None for snapshots taken at a level milestone rather than a split
+    pub split_id: Option<i64>,
     pub timestamp: String,
     pub elapsed_time_ms: i64,
     pub character_level: i32,
+    /// Character experience at capture, when the API reported it
+    pub experience: Option<i64>,
+    /// The four JSON columns are stored gzipped once they're long enough
+    /// (`compress::pack`); reads and writes here see plain JSON
     pub items_json: String,
     pub skills_json: String,
     pub passive_tree_json: String,
     pub stats_json: String,
     pub pob_code: Option<String>,
+    /// Captured automatically on `complete_run` as the run's final build
+    pub is_final: bool,
+}
+
+/// A snapshot without its items, skills, tree and stats JSON, for listing a
+/// run's snapshots without loading every build; `Snapshot::get_by_id` loads one in full
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotSummary {
+    pub id: i64,
+    pub run_id: i64,
+    pub split_id: Option<i64>,
+    pub timestamp: String,
+    pub elapsed_time_ms: i64,
+    pub character_level: i32,
+    pub experience: Option<i64>,
+    pub has_pob_code: bool,
+    pub is_final: bool,
 }
 
 impl Snapshot {
@@ -489,29 +1249,32 @@ impl Snapshot {
             timestamp: row.get("timestamp")?,
             elapsed_time_ms: row.get("elapsed_time_ms")?,
             character_level: row.get("character_level")?,
-            items_json: row.get("items_json")?,
-            skills_json: row.get("skills_json")?,
-            passive_tree_json: row.get("passive_tree_json")?,
-            stats_json: row.get("stats_json")?,
+            experience: row.get("experience")?,
+            items_json: unpack(row.get("items_json")?)?,
+            skills_json: unpack(row.get("skills_json")?)?,
+            passive_tree_json: unpack(row.get("passive_tree_json")?)?,
+            stats_json: unpack(row.get("stats_json")?)?,
             pob_code: row.get("pob_code")?,
+            is_final: row.get("is_final")?,
         })
     }
 
     pub fn insert(snapshot: &NewSnapshot) -> Result<i64> {
         let conn = get_db()?;
         conn.execute(
-            "INSERT INTO snapshots (run_id, split_id, timestamp, elapsed_time_ms, character_level, items_json, skills_json, passive_tree_json, stats_json, pob_code)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO snapshots (run_id, split_id, timestamp, elapsed_time_ms, character_level, experience, items_json, skills_json, passive_tree_json, stats_json, pob_code)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 snapshot.run_id,
                 snapshot.split_id,
                 snapshot.timestamp,
                 snapshot.elapsed_time_ms,
                 snapshot.character_level,
-                snapshot.items_json,
-                snapshot.skills_json,
-                snapshot.passive_tree_json,
-                snapshot.stats_json,
+                snapshot.experience,
+                pack(&snapshot.items_json),
+                pack(&snapshot.skills_json),
+                pack(&snapshot.passive_tree_json),
+                pack(&snapshot.stats_json),
                 snapshot.pob_code,
             ],
         )?;
@@ -528,6 +1291,33 @@ impl Snapshot {
         Ok(snapshots)
     }
 
+    /// The run's snapshots without their JSON, in run-clock order
+    pub fn get_summaries(run_id: i64) -> Result<Vec<SnapshotSummary>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, run_id, split_id, timestamp, elapsed_time_ms, character_level, experience,
+                    pob_code IS NOT NULL, is_final
+             FROM snapshots WHERE run_id = ?1 ORDER BY elapsed_time_ms",
+        )?;
+        let summaries = stmt
+            .query_map([run_id], |row| {
+                Ok(SnapshotSummary {
+                    id: row.get(0)?,
+                    run_id: row.get(1)?,
+                    split_id: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    elapsed_time_ms: row.get(4)?,
+                    character_level: row.get(5)?,
+                    experience: row.get(6)?,
+                    has_pob_code: row.get(7)?,
+                    is_final: row.get(8)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(summaries)
+    }
+
     pub fn get_by_id(id: i64) -> Result<Option<Snapshot>> {
         let conn = get_db()?;
         let mut stmt = conn.prepare("SELECT * FROM snapshots WHERE id = ?1")?;
@@ -535,6 +1325,36 @@ impl Snapshot {
         Ok(snapshot)
     }
 
+    /// The run's final build snapshot, if one has been captured
+    pub fn get_final(run_id: i64) -> Result<Option<Snapshot>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM snapshots WHERE run_id = ?1 AND is_final = 1 LIMIT 1")?;
+        let snapshot = stmt.query_row([run_id], Snapshot::from_row).ok();
+        Ok(snapshot)
+    }
+
+    pub fn set_final(id: i64) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("UPDATE snapshots SET is_final = 1 WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// Replace the skills and stats derived from the snapshot's raw JSON
+    pub fn set_analysis(id: i64, skills_json: &str, stats_json: &str) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "UPDATE snapshots SET skills_json = ?1, stats_json = ?2 WHERE id = ?3",
+            params![pack(skills_json), pack(stats_json), id],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_pob_code(id: i64, pob_code: &str) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("UPDATE snapshots SET pob_code = ?1 WHERE id = ?2", params![pob_code, id])?;
+        Ok(())
+    }
+
     pub fn get_by_split(split_id: i64) -> Result<Option<Snapshot>> {
         let conn = get_db()?;
         let mut stmt = conn.prepare("SELECT * FROM snapshots WHERE split_id = ?1")?;
@@ -546,10 +1366,11 @@ impl Snapshot {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewSnapshot {
     pub run_id: i64,
-    pub split_id: i64,
+    pub split_id: Option<i64>,
     pub timestamp: String,
     pub elapsed_time_ms: i64,
     pub character_level: i32,
+    pub experience: Option<i64>,
     pub items_json: String,
     pub skills_json: String,
     pub passive_tree_json: String,
@@ -567,6 +1388,12 @@ pub struct PersonalBest {
     pub id: i64,
     pub category: String,
     pub class: String,
+    /// Ascendancy of the PB run, when it was known
+    pub ascendancy: Option<String>,
+    /// League the PB was set in; each league keeps its own
+    pub league: String,
+    /// Major patch (e.g. "3.25") this PB is for, or empty for the all-time PB
+    pub patch: String,
     pub run_id: i64,
     pub total_time_ms: i64,
 }
@@ -575,120 +1402,1774 @@ impl PersonalBest {
     pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
         Ok(PersonalBest {
             id: row.get("id")?,
-            category: row.get("category")?,
-            class: row.get("class")?,
-            run_id: row.get("run_id")?,
-            total_time_ms: row.get("total_time_ms")?,
+            category: row.get("category")?,
+            class: row.get("class")?,
+            ascendancy: row.get("ascendancy")?,
+            league: row.get("league")?,
+            patch: row.get("patch")?,
+            run_id: row.get("run_id")?,
+            total_time_ms: row.get("total_time_ms")?,
+        })
+    }
+
+    /// Record a completed run against its category, class and league's PB for
+    /// `patch` ("" for all-time), returning whether it set a new PB
+    pub fn get_or_create(run: &Run, patch: &str, total_time_ms: i64) -> Result<bool> {
+        let conn = get_db()?;
+
+        // Check if there's an existing PB
+        let existing: Option<i64> = conn
+            .query_row(
+                "SELECT total_time_ms FROM personal_bests
+                 WHERE category = ?1 AND class = ?2 AND league = ?3 AND patch = ?4",
+                params![run.category, run.class, run.league, patch],
+                |row| row.get(0),
+            )
+            .ok();
+
+        match existing {
+            Some(existing_time) if total_time_ms < existing_time => {
+                // New PB!
+                conn.execute(
+                    "UPDATE personal_bests SET run_id = ?1, total_time_ms = ?2, ascendancy = ?3
+                     WHERE category = ?4 AND class = ?5 AND league = ?6 AND patch = ?7",
+                    params![run.id, total_time_ms, run.ascendancy, run.category, run.class, run.league, patch],
+                )?;
+                Ok(true)
+            }
+            None => {
+                // First run in this category
+                conn.execute(
+                    "INSERT INTO personal_bests (category, class, ascendancy, league, patch, run_id, total_time_ms)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![run.category, run.class, run.ascendancy, run.league, patch, run.id, total_time_ms],
+                )?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// The PB for a category, class and league on `patch` ("" for all-time)
+    pub fn get(category: &str, class: &str, league: &str, patch: &str) -> Result<Option<PersonalBest>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM personal_bests WHERE category = ?1 AND class = ?2 AND league = ?3 AND patch = ?4",
+        )?;
+        let pb = stmt.query_row(params![category, class, league, patch], PersonalBest::from_row).ok();
+        Ok(pb)
+    }
+
+    /// Every PB row across all patches
+    #[cfg(test)]
+    pub fn get_all() -> Result<Vec<PersonalBest>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM personal_bests")?;
+        let pbs = stmt
+            .query_map([], PersonalBest::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(pbs)
+    }
+
+    pub fn get_for_patch(patch: &str) -> Result<Vec<PersonalBest>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM personal_bests WHERE patch = ?1")?;
+        let pbs = stmt
+            .query_map(params![patch], PersonalBest::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(pbs)
+    }
+
+    /// Major patch of a game version: "3.25.1.3" -> "3.25"
+    pub fn major_patch(game_version: &str) -> Option<String> {
+        let mut parts = game_version.split('.');
+        let major = parts.next().filter(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))?;
+        let minor = parts.next().filter(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))?;
+        Some(format!("{}.{}", major, minor))
+    }
+}
+
+// ============================================================================
+// Gold Split
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoldSplit {
+    pub id: i64,
+    pub category: String,
+    pub class: String,
+    pub breakpoint_name: String,
+    pub breakpoint_key: Option<String>,
+    pub best_segment_ms: i64,
+}
+
+impl GoldSplit {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(GoldSplit {
+            id: row.get("id")?,
+            category: row.get("category")?,
+            class: row.get("class")?,
+            breakpoint_name: row.get("breakpoint_name")?,
+            breakpoint_key: row.get("breakpoint_key")?,
+            best_segment_ms: row.get("best_segment_ms")?,
+        })
+    }
+
+    /// Current best segment for a location, matched the same way as `update_if_better`
+    pub fn get_best(
+        category: &str,
+        class: &str,
+        breakpoint_name: &str,
+        breakpoint_key: Option<&str>,
+    ) -> Result<Option<i64>> {
+        let conn = get_db()?;
+        Ok(conn
+            .query_row(
+                "SELECT best_segment_ms FROM gold_splits
+                 WHERE category = ?1 AND class = ?2
+                   AND (breakpoint_key = ?4 OR (breakpoint_key IS NULL AND breakpoint_name = ?3))
+                 ORDER BY breakpoint_key IS NULL LIMIT 1",
+                params![category, class, breakpoint_name, breakpoint_key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Record a segment time, keeping only the best per category/class/location.
+    /// Matches an existing gold by canonical key; the name is only compared with
+    /// golds recorded without a key, which then take this key. Returns what
+    /// changed so the gold can be reverted, or `None` if it wasn't better.
+    pub fn update_if_better(
+        category: &str,
+        class: &str,
+        breakpoint_name: &str,
+        breakpoint_key: Option<&str>,
+        segment_ms: i64,
+    ) -> Result<Option<GoldChange>> {
+        let conn = get_db()?;
+
+        let existing: Option<(i64, i64)> = conn
+            .query_row(
+                "SELECT id, best_segment_ms FROM gold_splits
+                 WHERE category = ?1 AND class = ?2
+                   AND (breakpoint_key = ?4 OR (breakpoint_key IS NULL AND breakpoint_name = ?3))
+                 ORDER BY breakpoint_key IS NULL LIMIT 1",
+                params![category, class, breakpoint_name, breakpoint_key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        match existing {
+            Some((id, existing_time)) if segment_ms < existing_time => {
+                conn.execute(
+                    "UPDATE gold_splits SET best_segment_ms = ?1, breakpoint_key = COALESCE(breakpoint_key, ?2) WHERE id = ?3",
+                    params![segment_ms, breakpoint_key, id],
+                )?;
+                Ok(Some(GoldChange { gold_split_id: id, previous_best_ms: Some(existing_time), segment_ms }))
+            }
+            None => {
+                conn.execute(
+                    "INSERT INTO gold_splits (category, class, breakpoint_name, breakpoint_key, best_segment_ms) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![category, class, breakpoint_name, breakpoint_key, segment_ms],
+                )?;
+                Ok(Some(GoldChange { gold_split_id: conn.last_insert_rowid(), previous_best_ms: None, segment_ms }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Whether any of the run's segments is the gold for its location
+    pub fn held_by_run(run_id: i64) -> Result<bool> {
+        let conn = get_db()?;
+        let held = conn.query_row(
+            "SELECT EXISTS (
+                SELECT 1 FROM splits s JOIN runs r ON r.id = s.run_id
+                JOIN gold_splits g ON g.category = r.category AND g.class = r.class
+                  AND (g.breakpoint_key = s.breakpoint_key OR (g.breakpoint_key IS NULL AND g.breakpoint_name = s.breakpoint_name))
+                WHERE s.run_id = ?1 AND g.best_segment_ms = s.segment_time_ms
+             )",
+            [run_id],
+            |row| row.get(0),
+        )?;
+        Ok(held)
+    }
+
+    /// Rebuild a category's golds from the segments recorded by its non-reference
+    /// runs, with the same rules as recording a split: positive segments that
+    /// don't span a skip. Golds no recorded segment reaches (e.g. imported from
+    /// LiveSplit) are removed. Returns how many golds changed.
+    pub fn recompute(category: &str) -> Result<usize> {
+        let mut conn = get_db()?;
+        let tx = conn.transaction()?;
+
+        // Best segment per class and location
+        let mut stmt = tx.prepare(
+            "SELECT r.class, s.breakpoint_name, s.breakpoint_key, MIN(s.segment_time_ms)
+             FROM splits s JOIN runs r ON r.id = s.run_id
+             WHERE r.category = ?1 AND r.is_reference = 0 AND s.segment_time_ms > 0
+               AND NOT EXISTS (
+                 SELECT 1 FROM skipped_splits k WHERE k.run_id = s.run_id
+                   AND k.elapsed_ms BETWEEN s.split_time_ms - s.segment_time_ms AND s.split_time_ms
+               )
+             GROUP BY r.class, s.breakpoint_name, s.breakpoint_key",
+        )?;
+        let segments: Vec<(String, String, Option<String>, i64)> = stmt
+            .query_map([category], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        drop(stmt);
+        let mut stmt = tx.prepare("SELECT * FROM gold_splits WHERE category = ?1")?;
+        let golds: Vec<GoldSplit> = stmt
+            .query_map([category], GoldSplit::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        drop(stmt);
+
+        // Matched the way `update_if_better` matches: by key, by name only for
+        // golds without one
+        let matches = |gold: &GoldSplit, class: &str, name: &str, key: Option<&str>| {
+            gold.class == class
+                && match gold.breakpoint_key.as_deref() {
+                    Some(gold_key) => key == Some(gold_key),
+                    None => gold.breakpoint_name == name,
+                }
+        };
+
+        let mut changed = 0;
+        for gold in &golds {
+            let best = segments
+                .iter()
+                .filter(|(class, name, key, _)| matches(gold, class, name, key.as_deref()))
+                .map(|(_, _, _, best)| *best)
+                .min();
+            changed += match best {
+                Some(best) if best != gold.best_segment_ms => tx.execute(
+                    "UPDATE gold_splits SET best_segment_ms = ?1 WHERE id = ?2",
+                    params![best, gold.id],
+                )?,
+                Some(_) => 0,
+                None => tx.execute("DELETE FROM gold_splits WHERE id = ?1", [gold.id])?,
+            };
+        }
+
+        // Locations with a recorded segment but no gold yet
+        let mut added: Vec<(String, String, Option<String>, i64)> = Vec::new();
+        for (class, name, key, best) in segments.iter() {
+            if golds.iter().any(|gold| matches(gold, class, name, key.as_deref())) {
+                continue;
+            }
+            let found = added.iter_mut().find(|(c, n, k, _)| {
+                c == class
+                    && match k {
+                        Some(_) => k == key,
+                        None => n == name,
+                    }
+            });
+            match found {
+                Some(entry) => {
+                    entry.2 = entry.2.take().or_else(|| key.clone());
+                    entry.3 = entry.3.min(*best);
+                }
+                None => added.push((class.clone(), name.clone(), key.clone(), *best)),
+            }
+        }
+        for (class, name, key, best) in &added {
+            changed += tx.execute(
+                "INSERT OR IGNORE INTO gold_splits (category, class, breakpoint_name, breakpoint_key, best_segment_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![category, class, name, key, best],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    pub fn get_all() -> Result<Vec<GoldSplit>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM gold_splits")?;
+        let golds = stmt
+            .query_map([], GoldSplit::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(golds)
+    }
+}
+
+// ============================================================================
+// Time Loss Report
+// ============================================================================
+
+/// Time lost to a single mistake type across the filtered runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MistakeLoss {
+    pub mistake_type: String,
+    pub loss_ms: i64,
+    pub occurrences: i64,
+}
+
+/// Time lost against gold splits, attributed to the mistake types tagged on each split
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeLossReport {
+    pub total_loss_ms: i64,
+    /// Loss on splits without a mistake tag
+    pub untagged_loss_ms: i64,
+    pub by_mistake_type: Vec<MistakeLoss>,
+}
+
+impl TimeLossReport {
+    /// Loss per split is its segment time minus the gold for that location. A split
+    /// tagged with several mistake types shares its loss evenly between them.
+    pub fn build(filters: &RunFilters) -> Result<TimeLossReport> {
+        use std::collections::{BTreeSet, HashMap};
+
+        let golds: HashMap<(String, String, String), i64> = GoldSplit::get_all()?
+            .into_iter()
+            .map(|g| {
+                let location = g.breakpoint_key.unwrap_or(g.breakpoint_name);
+                ((g.category, g.class, location), g.best_segment_ms)
+            })
+            .collect();
+
+        let mut report = TimeLossReport::default();
+        let mut by_type: HashMap<String, MistakeLoss> = HashMap::new();
+
+        for run in Run::get_filtered(filters)? {
+            let splits = Split::get_by_run(run.id)?;
+            let mut tags: HashMap<i64, BTreeSet<String>> = HashMap::new();
+            for note in SplitNote::get_by_run(run.id)? {
+                if let Some(mistake) = note.mistake_type {
+                    tags.entry(note.split_id).or_default().insert(mistake);
+                }
+            }
+
+            for split in splits {
+                let location = split.breakpoint_key.clone().unwrap_or_else(|| split.breakpoint_name.clone());
+                let Some(gold) = golds.get(&(run.category.clone(), run.class.clone(), location)) else {
+                    continue;
+                };
+                let loss = (split.segment_time_ms - gold).max(0);
+                report.total_loss_ms += loss;
+
+                match tags.get(&split.id) {
+                    Some(types) if !types.is_empty() => {
+                        let share = loss / types.len() as i64;
+                        for mistake in types {
+                            let entry = by_type.entry(mistake.clone()).or_insert_with(|| MistakeLoss {
+                                mistake_type: mistake.clone(),
+                                loss_ms: 0,
+                                occurrences: 0,
+                            });
+                            entry.loss_ms += share;
+                            entry.occurrences += 1;
+                        }
+                    }
+                    _ => report.untagged_loss_ms += loss,
+                }
+            }
+        }
+
+        report.by_mistake_type = by_type.into_values().collect();
+        report
+            .by_mistake_type
+            .sort_by(|a, b| b.loss_ms.cmp(&a.loss_ms).then_with(|| a.mistake_type.cmp(&b.mistake_type)));
+        Ok(report)
+    }
+}
+
+/// Average town/hideout time spent per act across the filtered runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActTownTime {
+    pub act: i32,
+    pub average_town_time_ms: i64,
+    pub average_hideout_time_ms: i64,
+    pub run_count: i64,
+}
+
+impl ActTownTime {
+    /// Sum per-segment town/hideout time by act for each run, then average over the
+    /// runs that reached that act. Splits without an act in their key (custom or
+    /// level splits) count towards the act of the split before them.
+    pub fn build(filters: &RunFilters) -> Result<Vec<ActTownTime>> {
+        use std::collections::BTreeMap;
+
+        // act -> (town, hideout, runs)
+        let mut totals: BTreeMap<i32, (i64, i64, i64)> = BTreeMap::new();
+
+        for run in Run::get_filtered(filters)? {
+            let mut per_act: BTreeMap<i32, (i64, i64)> = BTreeMap::new();
+            let mut act = None;
+            for split in Split::get_by_run(run.id)? {
+                act = split.act().or(act);
+                let Some(act) = act else { continue };
+                let entry = per_act.entry(act).or_default();
+                entry.0 += split.segment_town_time_ms;
+                entry.1 += split.segment_hideout_time_ms;
+            }
+            for (act, (town, hideout)) in per_act {
+                let entry = totals.entry(act).or_default();
+                entry.0 += town;
+                entry.1 += hideout;
+                entry.2 += 1;
+            }
+        }
+
+        Ok(totals
+            .into_iter()
+            .map(|(act, (town, hideout, runs))| ActTownTime {
+                act,
+                average_town_time_ms: town / runs,
+                average_hideout_time_ms: hideout / runs,
+                run_count: runs,
+            })
+            .collect())
+    }
+}
+
+/// A gold split row written by `GoldSplit::update_if_better`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoldChange {
+    pub gold_split_id: i64,
+    /// Best segment before the change, or `None` if the row was created
+    pub previous_best_ms: Option<i64>,
+    pub segment_ms: i64,
+}
+
+// ============================================================================
+// Provisional Golds
+// ============================================================================
+
+/// Gold changes remembered per split so a mis-split undone (or a run reset)
+/// shortly after can put the previous best segment back
+pub struct ProvisionalGold;
+
+impl ProvisionalGold {
+    pub fn record(run_id: i64, split_id: i64, change: &GoldChange, created_at_ms: i64) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO provisional_golds (run_id, split_id, gold_split_id, previous_best_ms, segment_ms, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![run_id, split_id, change.gold_split_id, change.previous_best_ms, change.segment_ms, created_at_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Revert gold changes made by a run (or one of its splits) at or after
+    /// `since_ms`, newest first, and forget the run's older ones. A gold that has
+    /// since been beaten again is left alone. Returns how many golds were reverted.
+    pub fn revert(run_id: i64, split_id: Option<i64>, since_ms: i64) -> Result<usize> {
+        let mut conn = get_db()?;
+        // A failure partway must not leave some golds reverted with their
+        // provisional rows still there to revert again
+        let tx = conn.transaction()?;
+        let changes: Vec<(i64, Option<i64>, i64)> = tx
+            .prepare(
+                "SELECT gold_split_id, previous_best_ms, segment_ms FROM provisional_golds
+                 WHERE run_id = ?1 AND (?2 IS NULL OR split_id = ?2) AND created_at >= ?3
+                 ORDER BY created_at DESC, id DESC",
+            )?
+            .query_map(params![run_id, split_id, since_ms], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut reverted = 0;
+        for (gold_split_id, previous_best_ms, segment_ms) in changes {
+            reverted += match previous_best_ms {
+                Some(previous) => tx.execute(
+                    "UPDATE gold_splits SET best_segment_ms = ?1 WHERE id = ?2 AND best_segment_ms = ?3",
+                    params![previous, gold_split_id, segment_ms],
+                )?,
+                None => tx.execute(
+                    "DELETE FROM gold_splits WHERE id = ?1 AND best_segment_ms = ?2",
+                    params![gold_split_id, segment_ms],
+                )?,
+            };
+        }
+
+        tx.execute(
+            "DELETE FROM provisional_golds WHERE run_id = ?1 AND (?2 IS NULL OR split_id = ?2)",
+            params![run_id, split_id],
+        )?;
+        tx.commit()?;
+        Ok(reverted)
+    }
+}
+
+// ============================================================================
+// Skipped Splits
+// ============================================================================
+
+/// A breakpoint passed over during a run. It counts as done for finding the
+/// next split, but has no time of its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedSplit {
+    pub id: i64,
+    pub run_id: i64,
+    pub breakpoint_name: String,
+    /// Run clock when skipped
+    pub elapsed_ms: i64,
+}
+
+impl SkippedSplit {
+    pub fn insert(run_id: i64, breakpoint_name: &str, elapsed_ms: i64) -> Result<SkippedSplit> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO skipped_splits (run_id, breakpoint_name, elapsed_ms) VALUES (?1, ?2, ?3)",
+            params![run_id, breakpoint_name, elapsed_ms],
+        )?;
+        Ok(SkippedSplit {
+            id: conn.last_insert_rowid(),
+            run_id,
+            breakpoint_name: breakpoint_name.to_string(),
+            elapsed_ms,
+        })
+    }
+
+    /// A run's skipped breakpoints, in the order they were skipped
+    pub fn get_by_run(run_id: i64) -> Result<Vec<SkippedSplit>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, run_id, breakpoint_name, elapsed_ms FROM skipped_splits
+             WHERE run_id = ?1 ORDER BY elapsed_ms, id",
+        )?;
+        let skipped = stmt
+            .query_map([run_id], |row| {
+                Ok(SkippedSplit {
+                    id: row.get(0)?,
+                    run_id: row.get(1)?,
+                    breakpoint_name: row.get(2)?,
+                    elapsed_ms: row.get(3)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(skipped)
+    }
+
+    pub fn delete(id: i64) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("DELETE FROM skipped_splits WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Pauses
+// ============================================================================
+
+/// A stretch of a run with its timer paused. The run clock stands still
+/// meanwhile, so the pause only adds wall-clock time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pause {
+    pub id: i64,
+    pub run_id: i64,
+    /// Run clock when paused
+    pub elapsed_ms: i64,
+    /// `manual`, `afk` or `disconnect`
+    pub reason: String,
+    pub paused_at: String,
+    /// `None` while paused
+    pub resumed_at: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+impl Pause {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Pause {
+            id: row.get("id")?,
+            run_id: row.get("run_id")?,
+            elapsed_ms: row.get("elapsed_ms")?,
+            reason: row.get("reason")?,
+            paused_at: row.get("paused_at")?,
+            resumed_at: row.get("resumed_at")?,
+            duration_ms: row.get("duration_ms")?,
+        })
+    }
+
+    /// Open a pause for a run, unless one is open already
+    pub fn start(run_id: i64, elapsed_ms: i64, reason: &str) -> Result<usize> {
+        let conn = get_db()?;
+        let inserted = conn.execute(
+            "INSERT INTO pauses (run_id, elapsed_ms, reason, paused_at)
+             SELECT ?1, ?2, ?3, strftime('%Y-%m-%d %H:%M:%f', 'now')
+             WHERE NOT EXISTS (SELECT 1 FROM pauses WHERE run_id = ?1 AND resumed_at IS NULL)",
+            params![run_id, elapsed_ms, reason],
+        )?;
+        Ok(inserted)
+    }
+
+    /// The run's pause still open, if any
+    pub fn open(run_id: i64) -> Result<Option<Pause>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM pauses WHERE run_id = ?1 AND resumed_at IS NULL")?;
+        let pause = stmt.query_row([run_id], Pause::from_row).ok();
+        Ok(pause)
+    }
+
+    /// Close the run's open pause, recording how long it lasted
+    pub fn end(run_id: i64) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE pauses SET resumed_at = strftime('%Y-%m-%d %H:%M:%f', 'now'),
+                 duration_ms = CAST(ROUND((julianday('now') - julianday(paused_at)) * 86400000) AS INTEGER)
+             WHERE run_id = ?1 AND resumed_at IS NULL",
+            params![run_id],
+        )?;
+        Ok(updated)
+    }
+
+    /// A run's pauses in run-clock order
+    pub fn get_by_run(run_id: i64) -> Result<Vec<Pause>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM pauses WHERE run_id = ?1 ORDER BY elapsed_ms, id")?;
+        let pauses = stmt
+            .query_map([run_id], Pause::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(pauses)
+    }
+}
+
+// ============================================================================
+// Quest Progress
+// ============================================================================
+
+/// Campaign act reached and objectives completed during a run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuestProgress {
+    pub act: i32,
+    pub completed: Vec<String>,
+}
+
+impl QuestProgress {
+    /// Progress for a run, or `None` if the run doesn't exist
+    pub fn load(run_id: i64) -> Result<Option<QuestProgress>> {
+        let conn = get_db()?;
+        let Ok(act) = conn.query_row("SELECT quest_act FROM runs WHERE id = ?1", [run_id], |row| row.get(0)) else {
+            return Ok(None);
+        };
+        let mut stmt = conn.prepare("SELECT objective FROM quest_progress WHERE run_id = ?1 ORDER BY completed_at, rowid")?;
+        let completed = stmt
+            .query_map([run_id], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(Some(QuestProgress { act, completed }))
+    }
+
+    pub fn set_act(run_id: i64, act: i32) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("UPDATE runs SET quest_act = ?1 WHERE id = ?2", params![act, run_id])?;
+        Ok(())
+    }
+
+    pub fn complete(run_id: i64, objective: &str) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT OR IGNORE INTO quest_progress (run_id, objective) VALUES (?1, ?2)",
+            params![run_id, objective],
+        )?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Leveling Guide
+// ============================================================================
+
+/// The imported leveling guide; `steps_json` holds its `guide::GuideStep`s
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelingGuide {
+    pub name: String,
+    pub steps_json: String,
+    pub imported_at: String,
+}
+
+impl LevelingGuide {
+    pub fn load() -> Result<Option<LevelingGuide>> {
+        let conn = get_db()?;
+        let guide = conn
+            .query_row("SELECT name, steps_json, imported_at FROM guides WHERE id = 1", [], |row| {
+                Ok(LevelingGuide { name: row.get(0)?, steps_json: row.get(1)?, imported_at: row.get(2)? })
+            })
+            .ok();
+        Ok(guide)
+    }
+
+    /// Replace the imported guide
+    pub fn save(name: &str, steps_json: &str) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO guides (id, name, steps_json, imported_at) VALUES (1, ?1, ?2, datetime('now'))",
+            params![name, steps_json],
+        )?;
+        Ok(())
+    }
+
+    pub fn clear() -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("DELETE FROM guides", [])?;
+        Ok(())
+    }
+
+    /// Guide step a run is on, or `None` if the run doesn't exist
+    pub fn step_of(run_id: i64) -> Result<Option<usize>> {
+        let conn = get_db()?;
+        let step: Option<i64> = conn
+            .query_row("SELECT guide_step FROM runs WHERE id = ?1", [run_id], |row| row.get(0))
+            .ok();
+        Ok(step.map(|step| step.max(0) as usize))
+    }
+
+    pub fn set_step(run_id: i64, step: usize) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("UPDATE runs SET guide_step = ?1 WHERE id = ?2", params![step as i64, run_id])?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Run Events
+// ============================================================================
+
+/// A log event or timer pause kept for the run timeline. `kind` is
+/// `zone_enter` (detail: zone), `level_up` (detail: level), `death`, `pause`
+/// or `resume`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunEvent {
+    pub id: i64,
+    pub run_id: i64,
+    pub kind: String,
+    /// Run clock when it happened
+    pub elapsed_ms: i64,
+    pub detail: Option<String>,
+}
+
+impl RunEvent {
+    pub fn insert(run_id: i64, kind: &str, elapsed_ms: i64, detail: Option<&str>) -> Result<i64> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO run_events (run_id, kind, elapsed_ms, detail) VALUES (?1, ?2, ?3, ?4)",
+            params![run_id, kind, elapsed_ms, detail],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Events of a run in the order they were recorded
+    pub fn get_by_run(run_id: i64) -> Result<Vec<RunEvent>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, run_id, kind, elapsed_ms, detail FROM run_events WHERE run_id = ?1 ORDER BY id",
+        )?;
+        let events = stmt
+            .query_map([run_id], |row| {
+                Ok(RunEvent {
+                    id: row.get(0)?,
+                    run_id: row.get(1)?,
+                    kind: row.get(2)?,
+                    elapsed_ms: row.get(3)?,
+                    detail: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(events)
+    }
+}
+
+// ============================================================================
+// Deaths
+// ============================================================================
+
+/// A death of a run's character
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Death {
+    pub id: i64,
+    pub run_id: i64,
+    /// Client.txt timestamp of the death line
+    pub timestamp: String,
+    /// Zone last entered, if the run had entered one
+    pub zone_name: Option<String>,
+    /// Run clock when it happened
+    pub elapsed_ms: i64,
+    /// Level from the character's last level-up, if any
+    pub character_level: Option<i32>,
+}
+
+impl Death {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Death {
+            id: row.get("id")?,
+            run_id: row.get("run_id")?,
+            timestamp: row.get("timestamp")?,
+            zone_name: row.get("zone_name")?,
+            elapsed_ms: row.get("elapsed_ms")?,
+            character_level: row.get("character_level")?,
+        })
+    }
+
+    pub fn insert(
+        run_id: i64,
+        timestamp: &str,
+        zone_name: Option<&str>,
+        elapsed_ms: i64,
+        character_level: Option<i32>,
+    ) -> Result<i64> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO deaths (run_id, timestamp, zone_name, elapsed_ms, character_level) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![run_id, timestamp, zone_name, elapsed_ms, character_level],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Deaths of a run in run-clock order
+    pub fn get_by_run(run_id: i64) -> Result<Vec<Death>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM deaths WHERE run_id = ?1 ORDER BY elapsed_ms, id")?;
+        let deaths = stmt
+            .query_map([run_id], Death::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(deaths)
+    }
+
+    pub fn count_by_run(run_id: i64) -> Result<i64> {
+        let conn = get_db()?;
+        let count = conn.query_row("SELECT COUNT(*) FROM deaths WHERE run_id = ?1", [run_id], |row| row.get(0))?;
+        Ok(count)
+    }
+}
+
+// ============================================================================
+// Zone Times
+// ============================================================================
+
+/// One visit to a zone during a run, at run-clock time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneTime {
+    pub id: i64,
+    pub run_id: i64,
+    pub zone_name: String,
+    pub entered_at_ms: i64,
+    /// None for the visit still in progress
+    pub duration_ms: Option<i64>,
+    pub is_town: bool,
+    pub is_hideout: bool,
+}
+
+impl ZoneTime {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(ZoneTime {
+            id: row.get("id")?,
+            run_id: row.get("run_id")?,
+            zone_name: row.get("zone_name")?,
+            entered_at_ms: row.get("entered_at_ms")?,
+            duration_ms: row.get("duration_ms")?,
+            is_town: row.get("is_town")?,
+            is_hideout: row.get("is_hideout")?,
+        })
+    }
+
+    /// Close the run's visit in progress at `entered_at_ms` and start one in `zone_name`
+    pub fn enter(run_id: i64, zone_name: &str, entered_at_ms: i64, is_town: bool, is_hideout: bool) -> Result<i64> {
+        ZoneTime::close(run_id, entered_at_ms)?;
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO zone_times (run_id, zone_name, entered_at_ms, is_town, is_hideout) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![run_id, zone_name, entered_at_ms, is_town, is_hideout],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// End the run's visit in progress, if any, at `left_at_ms`
+    pub fn close(run_id: i64, left_at_ms: i64) -> Result<usize> {
+        let conn = get_db()?;
+        let closed = conn.execute(
+            "UPDATE zone_times SET duration_ms = MAX(?1 - entered_at_ms, 0) WHERE run_id = ?2 AND duration_ms IS NULL",
+            params![left_at_ms, run_id],
+        )?;
+        Ok(closed)
+    }
+
+    /// Visits of a run in the order they were made
+    pub fn get_by_run(run_id: i64) -> Result<Vec<ZoneTime>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM zone_times WHERE run_id = ?1 ORDER BY entered_at_ms, id")?;
+        let visits = stmt
+            .query_map([run_id], ZoneTime::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(visits)
+    }
+}
+
+/// Time spent in one zone across the runs matching a filter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneTimeStat {
+    pub zone_name: String,
+    pub is_town: bool,
+    pub is_hideout: bool,
+    /// Runs that entered the zone
+    pub run_count: i64,
+    pub visits: i64,
+    /// Per run that entered it, all visits together
+    pub average_time_ms: i64,
+    pub best_time_ms: i64,
+}
+
+impl ZoneTimeStat {
+    /// Total each run's finished visits per zone, then average over the runs
+    /// that entered the zone. Slowest zones first.
+    pub fn build(filters: &RunFilters) -> Result<Vec<ZoneTimeStat>> {
+        // zone -> (is_town, is_hideout, visits, per-run totals)
+        let mut zones: Vec<(String, bool, bool, i64, Vec<i64>)> = Vec::new();
+
+        for run in Run::get_filtered(filters)? {
+            let mut per_zone: Vec<(String, bool, bool, i64, i64)> = Vec::new();
+            for visit in ZoneTime::get_by_run(run.id)? {
+                let Some(duration) = visit.duration_ms else { continue };
+                match per_zone.iter_mut().find(|zone| zone.0 == visit.zone_name) {
+                    Some(zone) => {
+                        zone.3 += 1;
+                        zone.4 += duration;
+                    }
+                    None => per_zone.push((visit.zone_name, visit.is_town, visit.is_hideout, 1, duration)),
+                }
+            }
+            for (zone_name, is_town, is_hideout, visits, time_ms) in per_zone {
+                match zones.iter_mut().find(|zone| zone.0 == zone_name) {
+                    Some(zone) => {
+                        zone.3 += visits;
+                        zone.4.push(time_ms);
+                    }
+                    None => zones.push((zone_name, is_town, is_hideout, visits, vec![time_ms])),
+                }
+            }
+        }
+
+        let mut stats: Vec<ZoneTimeStat> = zones
+            .into_iter()
+            .map(|(zone_name, is_town, is_hideout, visits, times)| ZoneTimeStat {
+                zone_name,
+                is_town,
+                is_hideout,
+                run_count: times.len() as i64,
+                visits,
+                average_time_ms: times.iter().sum::<i64>() / times.len() as i64,
+                best_time_ms: times.iter().copied().min().unwrap_or(0),
+            })
+            .collect();
+        stats.sort_by_key(|stat| std::cmp::Reverse(stat.average_time_ms));
+        Ok(stats)
+    }
+}
+
+/// A zone's share of the time and deaths across the runs matching a filter,
+/// for a "where do I lose time" heatmap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneHeatmapEntry {
+    pub zone_name: String,
+    pub is_town: bool,
+    pub is_hideout: bool,
+    /// Runs that entered the zone or died in it
+    pub run_count: i64,
+    pub visits: i64,
+    /// All finished visits of all runs together
+    pub total_time_ms: i64,
+    /// Per run that entered the zone
+    pub average_time_ms: i64,
+    pub deaths: i64,
+}
+
+impl ZoneHeatmapEntry {
+    /// Total finished visits and deaths per zone over the filtered runs. Zones
+    /// that cost the most time overall come first.
+    pub fn build(filters: &RunFilters) -> Result<Vec<ZoneHeatmapEntry>> {
+        let mut zones: Vec<ZoneHeatmapEntry> = Vec::new();
+        fn zone<'a>(zones: &'a mut Vec<ZoneHeatmapEntry>, zone_name: &str) -> &'a mut ZoneHeatmapEntry {
+            match zones.iter().position(|zone| zone.zone_name == zone_name) {
+                Some(index) => &mut zones[index],
+                None => {
+                    zones.push(ZoneHeatmapEntry {
+                        zone_name: zone_name.to_string(),
+                        is_town: false,
+                        is_hideout: false,
+                        run_count: 0,
+                        visits: 0,
+                        total_time_ms: 0,
+                        average_time_ms: 0,
+                        deaths: 0,
+                    });
+                    zones.last_mut().unwrap()
+                }
+            }
+        }
+
+        for run in Run::get_filtered(filters)? {
+            let mut seen: Vec<String> = Vec::new();
+            for visit in ZoneTime::get_by_run(run.id)? {
+                let Some(duration) = visit.duration_ms else { continue };
+                let entry = zone(&mut zones, &visit.zone_name);
+                entry.is_town = visit.is_town;
+                entry.is_hideout = visit.is_hideout;
+                entry.visits += 1;
+                entry.total_time_ms += duration;
+                if !seen.contains(&visit.zone_name) {
+                    seen.push(visit.zone_name);
+                }
+            }
+            for death in Death::get_by_run(run.id)? {
+                let Some(zone_name) = death.zone_name else { continue };
+                zone(&mut zones, &zone_name).deaths += 1;
+                if !seen.contains(&zone_name) {
+                    seen.push(zone_name);
+                }
+            }
+            for zone_name in seen {
+                zone(&mut zones, &zone_name).run_count += 1;
+            }
+        }
+
+        for entry in &mut zones {
+            entry.average_time_ms = entry.total_time_ms / entry.run_count.max(1);
+        }
+        zones.sort_by_key(|entry| std::cmp::Reverse(entry.total_time_ms));
+        Ok(zones)
+    }
+}
+
+// ============================================================================
+// Map Sessions
+// ============================================================================
+
+/// An endgame mapping session. Times are wall clock, split by where the
+/// character was; everything but `map_time_ms` is time between maps.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapSession {
+    pub id: i64,
+    pub started_at: String,
+    /// None while the session is running
+    pub ended_at: Option<String>,
+    pub maps_run: i64,
+    pub map_time_ms: i64,
+    pub hideout_time_ms: i64,
+    pub town_time_ms: i64,
+    /// Areas that are neither maps, hideouts nor towns (e.g. the labyrinth)
+    pub other_time_ms: i64,
+}
+
+impl MapSession {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(MapSession {
+            id: row.get("id")?,
+            started_at: row.get("started_at")?,
+            ended_at: row.get("ended_at")?,
+            maps_run: row.get("maps_run")?,
+            map_time_ms: row.get("map_time_ms")?,
+            hideout_time_ms: row.get("hideout_time_ms")?,
+            town_time_ms: row.get("town_time_ms")?,
+            other_time_ms: row.get("other_time_ms")?,
+        })
+    }
+
+    /// Start a session now, returning its id
+    pub fn start() -> Result<i64> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO map_sessions (started_at) VALUES (?1)",
+            [chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Store the running totals of session `self.id`
+    pub fn save_totals(&self) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "UPDATE map_sessions SET maps_run = ?1, map_time_ms = ?2, hideout_time_ms = ?3,
+                town_time_ms = ?4, other_time_ms = ?5 WHERE id = ?6",
+            params![
+                self.maps_run,
+                self.map_time_ms,
+                self.hideout_time_ms,
+                self.town_time_ms,
+                self.other_time_ms,
+                self.id
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn finish(id: i64) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "UPDATE map_sessions SET ended_at = ?1 WHERE id = ?2 AND ended_at IS NULL",
+            params![chrono::Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_by_id(id: i64) -> Result<Option<MapSession>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM map_sessions WHERE id = ?1")?;
+        let session = stmt.query_row([id], MapSession::from_row).ok();
+        Ok(session)
+    }
+
+    /// Sessions, newest first
+    pub fn get_all() -> Result<Vec<MapSession>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM map_sessions ORDER BY id DESC")?;
+        let sessions = stmt
+            .query_map([], MapSession::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(sessions)
+    }
+
+    pub fn delete(id: i64) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute("DELETE FROM map_sessions WHERE id = ?1", [id])?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Ladder Observations
+// ============================================================================
+
+/// A ladder character's level and experience at one `fetch_ladder`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LadderObservation {
+    pub id: i64,
+    pub league: String,
+    pub character_name: String,
+    pub account_name: String,
+    pub class: String,
+    pub level: i32,
+    pub experience: i64,
+    pub rank: i64,
+    /// When the ladder was fetched (RFC 3339)
+    pub observed_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewLadderObservation {
+    pub league: String,
+    pub character_name: String,
+    pub account_name: String,
+    pub class: String,
+    pub level: i32,
+    pub experience: i64,
+    pub rank: i64,
+    pub observed_at: String,
+}
+
+impl LadderObservation {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(LadderObservation {
+            id: row.get("id")?,
+            league: row.get("league")?,
+            character_name: row.get("character_name")?,
+            account_name: row.get("account_name")?,
+            class: row.get("class")?,
+            level: row.get("level")?,
+            experience: row.get("experience")?,
+            rank: row.get("rank")?,
+            observed_at: row.get("observed_at")?,
+        })
+    }
+
+    /// Record an observation unless the character's last one in the league
+    /// has the same level and experience. Returns whether it was recorded.
+    pub fn record(observation: &NewLadderObservation) -> Result<bool> {
+        let conn = get_db()?;
+        let last: Option<(i32, i64)> = conn
+            .query_row(
+                "SELECT level, experience FROM ladder_observations
+                 WHERE league = ?1 AND character_name = ?2 ORDER BY id DESC LIMIT 1",
+                params![observation.league, observation.character_name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        if last == Some((observation.level, observation.experience)) {
+            return Ok(false);
+        }
+        conn.execute(
+            "INSERT INTO ladder_observations
+                (league, character_name, account_name, class, level, experience, rank, observed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                observation.league,
+                observation.character_name,
+                observation.account_name,
+                observation.class,
+                observation.level,
+                observation.experience,
+                observation.rank,
+                observation.observed_at,
+            ],
+        )?;
+        Ok(true)
+    }
+
+    /// Observations of a league's characters, oldest first
+    pub fn get_by_league(league: &str) -> Result<Vec<LadderObservation>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM ladder_observations WHERE league = ?1 ORDER BY id")?;
+        let observations = stmt
+            .query_map([league], LadderObservation::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(observations)
+    }
+
+    /// Observations of one character, oldest first
+    pub fn get_by_character(league: &str, character_name: &str) -> Result<Vec<LadderObservation>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM ladder_observations WHERE league = ?1 AND character_name = ?2 ORDER BY id",
+        )?;
+        let observations = stmt
+            .query_map(params![league, character_name], LadderObservation::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(observations)
+    }
+}
+
+// ============================================================================
+// Category Defaults
+// ============================================================================
+
+/// Overlay display options saved with category defaults
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayLayout {
+    pub scale: String,
+    pub font_size: String,
+    pub show_timer: bool,
+    pub show_zone: bool,
+    pub show_last_split: bool,
+    pub show_breakpoints: bool,
+    pub breakpoint_count: i32,
+}
+
+/// Setup applied automatically when a run of `category` starts.
+/// `None` fields leave the current setting alone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryDefaults {
+    pub category: String,
+    pub breakpoint_preset: Option<String>,
+    /// JSON array of enabled breakpoint names, as stored on runs
+    pub enabled_breakpoints: Option<String>,
+    /// JSON array of breakpoint names that capture a snapshot
+    pub snapshot_breakpoints: Option<String>,
+    /// Comparison mode: current-patch PBs (`true`) or all-time PBs (`false`)
+    pub separate_pbs_by_patch: Option<bool>,
+    pub overlay_layout: Option<OverlayLayout>,
+}
+
+impl CategoryDefaults {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let overlay_layout: Option<String> = row.get("overlay_layout")?;
+        Ok(CategoryDefaults {
+            category: row.get("category")?,
+            breakpoint_preset: row.get("breakpoint_preset")?,
+            enabled_breakpoints: row.get("enabled_breakpoints")?,
+            snapshot_breakpoints: row.get("snapshot_breakpoints")?,
+            separate_pbs_by_patch: row.get("separate_pbs_by_patch")?,
+            overlay_layout: overlay_layout.and_then(|json| serde_json::from_str(&json).ok()),
+        })
+    }
+
+    pub fn get(category: &str) -> Result<Option<CategoryDefaults>> {
+        let conn = get_db()?;
+        let defaults = conn
+            .query_row(
+                "SELECT * FROM category_defaults WHERE category = ?1",
+                [category],
+                CategoryDefaults::from_row,
+            )
+            .ok();
+        Ok(defaults)
+    }
+
+    pub fn get_all() -> Result<Vec<CategoryDefaults>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM category_defaults ORDER BY category")?;
+        let defaults = stmt
+            .query_map([], CategoryDefaults::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(defaults)
+    }
+
+    /// Insert or replace the defaults for `defaults.category`
+    pub fn save(defaults: &CategoryDefaults) -> Result<()> {
+        let conn = get_db()?;
+        let overlay_layout = defaults
+            .overlay_layout
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO category_defaults
+                (category, breakpoint_preset, enabled_breakpoints, snapshot_breakpoints, separate_pbs_by_patch, overlay_layout)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                defaults.category,
+                defaults.breakpoint_preset,
+                defaults.enabled_breakpoints,
+                defaults.snapshot_breakpoints,
+                defaults.separate_pbs_by_patch,
+                overlay_layout,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete(category: &str) -> Result<usize> {
+        let conn = get_db()?;
+        let deleted = conn.execute("DELETE FROM category_defaults WHERE category = ?1", [category])?;
+        Ok(deleted)
+    }
+
+    /// Apply the comparison mode and overlay layout to `settings`
+    pub fn apply_to(&self, settings: &mut Settings) {
+        if let Some(separate) = self.separate_pbs_by_patch {
+            settings.separate_pbs_by_patch = separate;
+        }
+        if let Some(layout) = &self.overlay_layout {
+            settings.overlay_scale = layout.scale.clone();
+            settings.overlay_font_size = layout.font_size.clone();
+            settings.overlay_show_timer = layout.show_timer;
+            settings.overlay_show_zone = layout.show_zone;
+            settings.overlay_show_last_split = layout.show_last_split;
+            settings.overlay_show_breakpoints = layout.show_breakpoints;
+            settings.overlay_breakpoint_count = layout.breakpoint_count;
+        }
+    }
+}
+
+// ============================================================================
+// Breakpoint Presets
+// ============================================================================
+
+/// A split layout built in the app. Its breakpoints are in `breakpoints`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakpointPreset {
+    pub id: i64,
+    pub name: String,
+    /// `poe1` or `poe2`
+    pub game: String,
+    pub created_at: String,
+    pub breakpoint_count: i64,
+}
+
+impl BreakpointPreset {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(BreakpointPreset {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            game: row.get("game")?,
+            created_at: row.get("created_at")?,
+            breakpoint_count: row.get("breakpoint_count")?,
+        })
+    }
+
+    const SELECT: &'static str = "SELECT p.*, (SELECT COUNT(*) FROM breakpoints b WHERE b.preset_id = p.id) AS breakpoint_count
+         FROM breakpoint_presets p";
+
+    /// Create an empty preset. Fails if the name is taken.
+    pub fn create(name: &str, game: &str) -> Result<BreakpointPreset> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO breakpoint_presets (name, game) VALUES (?1, ?2)",
+            params![name, game],
+        )?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        BreakpointPreset::get(id)?.ok_or_else(|| anyhow::anyhow!("Preset {} not found after insert", id))
+    }
+
+    pub fn get(id: i64) -> Result<Option<BreakpointPreset>> {
+        let conn = get_db()?;
+        let preset = conn
+            .query_row(&format!("{} WHERE p.id = ?1", Self::SELECT), [id], BreakpointPreset::from_row)
+            .ok();
+        Ok(preset)
+    }
+
+    pub fn get_by_name(name: &str) -> Result<Option<BreakpointPreset>> {
+        let conn = get_db()?;
+        let preset = conn
+            .query_row(&format!("{} WHERE p.name = ?1", Self::SELECT), [name], BreakpointPreset::from_row)
+            .ok();
+        Ok(preset)
+    }
+
+    pub fn get_all() -> Result<Vec<BreakpointPreset>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare(&format!("{} ORDER BY p.name COLLATE NOCASE", Self::SELECT))?;
+        let presets = stmt
+            .query_map([], BreakpointPreset::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(presets)
+    }
+
+    /// Returns the number of presets renamed
+    pub fn rename(id: i64, name: &str) -> Result<usize> {
+        let conn = get_db()?;
+        Ok(conn.execute("UPDATE breakpoint_presets SET name = ?2 WHERE id = ?1", params![id, name])?)
+    }
+
+    /// Delete a preset and its breakpoints. Returns the number of presets deleted.
+    pub fn delete(id: i64) -> Result<usize> {
+        let conn = get_db()?;
+        Ok(conn.execute("DELETE FROM breakpoint_presets WHERE id = ?1", [id])?)
+    }
+}
+
+/// A breakpoint of a `BreakpointPreset`, in split order by `position`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetBreakpoint {
+    pub id: i64,
+    pub preset_id: i64,
+    pub position: i64,
+    #[serde(flatten)]
+    pub breakpoint: NewPresetBreakpoint,
+}
+
+/// A breakpoint as `add_breakpoint` / `update_breakpoint` take it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPresetBreakpoint {
+    pub name: String,
+    /// Split type the UI groups by: `zone`, `level`, `boss`, `act`, `lab` or `custom`
+    pub breakpoint_type: String,
+    /// `zone`, `level`, `kitava` or `quest`
+    pub trigger_type: String,
+    pub zone_name: Option<String>,
+    pub act: Option<i32>,
+    pub level: Option<u32>,
+    /// `quest_state::OBJECTIVES` id, for quest triggers
+    pub quest: Option<String>,
+    /// Resistance penalty a kitava trigger splits on: 30 (Act 5) or 60 (Act 10)
+    #[serde(default)]
+    pub penalty: Option<i32>,
+    /// Only split on this entry into the zone (1 = first entry only)
+    #[serde(default)]
+    pub entry: Option<u32>,
+    /// Only split once this objective is complete
+    #[serde(default)]
+    pub after_quest: Option<String>,
+    #[serde(default)]
+    pub capture_snapshot: bool,
+}
+
+impl PresetBreakpoint {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(PresetBreakpoint {
+            id: row.get("id")?,
+            preset_id: row.get("preset_id")?,
+            position: row.get("position")?,
+            breakpoint: NewPresetBreakpoint {
+                name: row.get("name")?,
+                breakpoint_type: row.get("breakpoint_type")?,
+                trigger_type: row.get("trigger_type")?,
+                zone_name: row.get("zone_name")?,
+                act: row.get("act")?,
+                level: row.get("level")?,
+                quest: row.get("quest")?,
+                penalty: row.get("penalty")?,
+                entry: row.get("entry")?,
+                after_quest: row.get("after_quest")?,
+                capture_snapshot: row.get("capture_snapshot")?,
+            },
+        })
+    }
+
+    pub fn get_by_preset(preset_id: i64) -> Result<Vec<PresetBreakpoint>> {
+        let conn = get_db()?;
+        let mut stmt = conn.prepare("SELECT * FROM breakpoints WHERE preset_id = ?1 ORDER BY position, id")?;
+        let breakpoints = stmt
+            .query_map([preset_id], PresetBreakpoint::from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(breakpoints)
+    }
+
+    /// Add a breakpoint at the end of a preset
+    pub fn insert(preset_id: i64, bp: &NewPresetBreakpoint) -> Result<i64> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO breakpoints (preset_id, position, name, breakpoint_type, trigger_type, zone_name, act,
+                 level, quest, entry, after_quest, capture_snapshot, penalty)
+             VALUES (?1, (SELECT COALESCE(MAX(position) + 1, 0) FROM breakpoints WHERE preset_id = ?1),
+                 ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                preset_id,
+                bp.name,
+                bp.breakpoint_type,
+                bp.trigger_type,
+                bp.zone_name,
+                bp.act,
+                bp.level,
+                bp.quest,
+                bp.entry,
+                bp.after_quest,
+                bp.capture_snapshot,
+                bp.penalty,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Returns the number of breakpoints updated
+    pub fn update(id: i64, bp: &NewPresetBreakpoint) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE breakpoints SET name = ?2, breakpoint_type = ?3, trigger_type = ?4, zone_name = ?5, act = ?6,
+                 level = ?7, quest = ?8, entry = ?9, after_quest = ?10, capture_snapshot = ?11, penalty = ?12
+             WHERE id = ?1",
+            params![
+                id,
+                bp.name,
+                bp.breakpoint_type,
+                bp.trigger_type,
+                bp.zone_name,
+                bp.act,
+                bp.level,
+                bp.quest,
+                bp.entry,
+                bp.after_quest,
+                bp.capture_snapshot,
+                bp.penalty,
+            ],
+        )?;
+        Ok(updated)
+    }
+
+    /// Returns the number of breakpoints deleted
+    pub fn delete(id: i64) -> Result<usize> {
+        let conn = get_db()?;
+        Ok(conn.execute("DELETE FROM breakpoints WHERE id = ?1", [id])?)
+    }
+
+    /// Put a preset's breakpoints in the order of `ids`, which must be all of them
+    pub fn reorder(preset_id: i64, ids: &[i64]) -> Result<()> {
+        let mut conn = get_db()?;
+        let tx = conn.transaction()?;
+        for (position, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE breakpoints SET position = ?3 WHERE id = ?1 AND preset_id = ?2",
+                params![id, preset_id, position as i64],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Overlay Profiles
+// ============================================================================
+
+/// An overlay window besides the main one, with its own position, size and
+/// opacity. Its window label is `overlay-<id>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayProfile {
+    pub id: String,
+    pub name: String,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    /// `None` follows the overlay opacity setting
+    pub opacity: Option<f64>,
+}
+
+impl OverlayProfile {
+    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(OverlayProfile {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            x: row.get("x")?,
+            y: row.get("y")?,
+            width: row.get("width")?,
+            height: row.get("height")?,
+            opacity: row.get("opacity")?,
         })
     }
 
-    pub fn get_or_create(category: &str, class: &str, run_id: i64, total_time_ms: i64) -> Result<bool> {
+    /// Create a profile with an id made from `name`, numbered if it's taken
+    pub fn create(name: &str) -> Result<OverlayProfile> {
+        let mut slug = String::new();
+        for c in name.trim().chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug = match slug.trim_end_matches('-') {
+            "" => "overlay".to_string(),
+            slug => slug.to_string(),
+        };
+
         let conn = get_db()?;
+        let mut id = slug.clone();
+        let mut n = 1;
+        while conn
+            .query_row("SELECT 1 FROM overlay_profiles WHERE id = ?1", [&id], |_| Ok(()))
+            .is_ok()
+        {
+            n += 1;
+            id = format!("{}-{}", slug, n);
+        }
+        conn.execute(
+            "INSERT INTO overlay_profiles (id, name) VALUES (?1, ?2)",
+            params![id, name.trim()],
+        )?;
+        Ok(OverlayProfile {
+            id,
+            name: name.trim().to_string(),
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+            opacity: None,
+        })
+    }
 
-        // Check if there's an existing PB
-        let existing: Option<i64> = conn
-            .query_row(
-                "SELECT total_time_ms FROM personal_bests WHERE category = ?1 AND class = ?2",
-                params![category, class],
-                |row| row.get(0),
-            )
+    pub fn get(id: &str) -> Result<Option<OverlayProfile>> {
+        let conn = get_db()?;
+        let profile = conn
+            .query_row("SELECT * FROM overlay_profiles WHERE id = ?1", [id], OverlayProfile::from_row)
             .ok();
-
-        match existing {
-            Some(existing_time) if total_time_ms < existing_time => {
-                // New PB!
-                conn.execute(
-                    "UPDATE personal_bests SET run_id = ?1, total_time_ms = ?2 WHERE category = ?3 AND class = ?4",
-                    params![run_id, total_time_ms, category, class],
-                )?;
-                Ok(true)
-            }
-            None => {
-                // First run in this category
-                conn.execute(
-                    "INSERT INTO personal_bests (category, class, run_id, total_time_ms) VALUES (?1, ?2, ?3, ?4)",
-                    params![category, class, run_id, total_time_ms],
-                )?;
-                Ok(true)
-            }
-            _ => Ok(false),
-        }
+        Ok(profile)
     }
 
-    pub fn get_all() -> Result<Vec<PersonalBest>> {
+    pub fn get_all() -> Result<Vec<OverlayProfile>> {
         let conn = get_db()?;
-        let mut stmt = conn.prepare("SELECT * FROM personal_bests")?;
-        let pbs = stmt
-            .query_map([], PersonalBest::from_row)?
+        let mut stmt = conn.prepare("SELECT * FROM overlay_profiles ORDER BY rowid")?;
+        let profiles = stmt
+            .query_map([], OverlayProfile::from_row)?
             .filter_map(|r| r.ok())
             .collect();
-        Ok(pbs)
+        Ok(profiles)
+    }
+
+    /// Save the name and opacity; the window saves its own position and size.
+    /// Returns the number of profiles updated.
+    pub fn save(profile: &OverlayProfile) -> Result<usize> {
+        let conn = get_db()?;
+        let updated = conn.execute(
+            "UPDATE overlay_profiles SET name = ?2, opacity = ?3 WHERE id = ?1",
+            params![profile.id, profile.name, profile.opacity],
+        )?;
+        Ok(updated)
+    }
+
+    pub fn save_position(id: &str, x: i32, y: i32) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "UPDATE overlay_profiles SET x = ?2, y = ?3 WHERE id = ?1",
+            params![id, x, y],
+        )?;
+        Ok(())
+    }
+
+    pub fn save_size(id: &str, width: f64, height: f64) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "UPDATE overlay_profiles SET width = ?2, height = ?3 WHERE id = ?1",
+            params![id, width, height],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete(id: &str) -> Result<usize> {
+        let conn = get_db()?;
+        let deleted = conn.execute("DELETE FROM overlay_profiles WHERE id = ?1", [id])?;
+        Ok(deleted)
     }
 }
 
 // ============================================================================
-// Gold Split
+// Overlay Layout
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GoldSplit {
-    pub id: i64,
-    pub category: String,
-    pub class: String,
-    pub breakpoint_name: String,
-    pub best_segment_ms: i64,
+/// Which parts of the overlay are shown, stored as JSON in
+/// `settings.overlay_layout`. Every overlay window follows it, within the
+/// sections the `overlay_show_*` settings turn on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OverlayComponents {
+    pub timer: bool,
+    /// The last split's name and time
+    pub current_split: bool,
+    /// The last split's delta against the PB
+    pub delta: bool,
+    /// Upcoming breakpoints
+    pub next_split: bool,
+    pub death_count: bool,
+    pub level_pace: bool,
 }
 
-impl GoldSplit {
-    pub fn from_row(row: &Row) -> rusqlite::Result<Self> {
-        Ok(GoldSplit {
-            id: row.get("id")?,
-            category: row.get("category")?,
-            class: row.get("class")?,
-            breakpoint_name: row.get("breakpoint_name")?,
-            best_segment_ms: row.get("best_segment_ms")?,
-        })
+impl Default for OverlayComponents {
+    fn default() -> Self {
+        OverlayComponents {
+            timer: true,
+            current_split: true,
+            delta: true,
+            next_split: true,
+            death_count: true,
+            level_pace: true,
+        }
     }
+}
 
-    pub fn update_if_better(category: &str, class: &str, breakpoint_name: &str, segment_ms: i64) -> Result<bool> {
+impl OverlayComponents {
+    /// The saved layout; everything is shown until one is saved
+    pub fn load() -> Result<OverlayComponents> {
         let conn = get_db()?;
-
-        let existing: Option<i64> = conn
-            .query_row(
-                "SELECT best_segment_ms FROM gold_splits WHERE category = ?1 AND class = ?2 AND breakpoint_name = ?3",
-                params![category, class, breakpoint_name],
-                |row| row.get(0),
-            )
-            .ok();
-
-        match existing {
-            Some(existing_time) if segment_ms < existing_time => {
-                conn.execute(
-                    "UPDATE gold_splits SET best_segment_ms = ?1 WHERE category = ?2 AND class = ?3 AND breakpoint_name = ?4",
-                    params![segment_ms, category, class, breakpoint_name],
-                )?;
-                Ok(true)
-            }
-            None => {
-                conn.execute(
-                    "INSERT INTO gold_splits (category, class, breakpoint_name, best_segment_ms) VALUES (?1, ?2, ?3, ?4)",
-                    params![category, class, breakpoint_name, segment_ms],
-                )?;
-                Ok(true)
-            }
-            _ => Ok(false),
-        }
+        let json: Option<String> = conn
+            .query_row("SELECT overlay_layout FROM settings WHERE id = 1", [], |row| row.get(0))
+            .unwrap_or(None);
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default())
     }
 
-    pub fn get_all() -> Result<Vec<GoldSplit>> {
+    pub fn save(layout: &OverlayComponents) -> Result<()> {
         let conn = get_db()?;
-        let mut stmt = conn.prepare("SELECT * FROM gold_splits")?;
-        let golds = stmt
-            .query_map([], GoldSplit::from_row)?
-            .filter_map(|r| r.ok())
-            .collect();
-        Ok(golds)
+        conn.execute(
+            "INSERT INTO settings (id, overlay_layout) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET overlay_layout = excluded.overlay_layout",
+            params![serde_json::to_string(layout)?],
+        )?;
+        Ok(())
     }
 }
 
@@ -724,6 +3205,193 @@ pub struct Settings {
     pub hotkey_toggle_overlay: String,
     pub hotkey_toggle_overlay_lock: String,
     pub hotkey_manual_split: String,
+    #[serde(default = "default_hotkey_undo_split")]
+    pub hotkey_undo_split: String,
+    #[serde(default = "default_hotkey_skip_split")]
+    pub hotkey_skip_split: String,
+    // Warm-up detection: flag the first run after a gap of `session_gap_minutes`
+    #[serde(default = "default_true")]
+    pub warmup_detection_enabled: bool,
+    #[serde(default = "default_session_gap_minutes")]
+    pub session_gap_minutes: i32,
+    // Startup: OS autostart registration, and starting hidden in the tray
+    #[serde(default)]
+    pub launch_on_boot: bool,
+    #[serde(default)]
+    pub start_minimized: bool,
+    // Compare against PBs from the current major patch only
+    #[serde(default)]
+    pub separate_pbs_by_patch: bool,
+    // Split announcements: tone and/or speech, only for golds or deltas past a threshold
+    #[serde(default)]
+    pub announce_sound: bool,
+    #[serde(default)]
+    pub announce_speech: bool,
+    #[serde(default)]
+    pub announce_golds_only: bool,
+    #[serde(default)]
+    pub announce_min_behind_ms: i64,
+    #[serde(default)]
+    pub announce_min_ahead_ms: i64,
+    // Golds set this many seconds before an undo/reset are reverted with it
+    #[serde(default = "default_gold_protection_secs")]
+    pub gold_protection_secs: i64,
+    // Countdown before the run timer reaches zero (the timer starts at minus this)
+    #[serde(default)]
+    pub timer_start_offset_ms: i64,
+    // Keep raw API responses of the last `api_recording_limit` snapshot attempts
+    #[serde(default)]
+    pub record_api_responses: bool,
+    #[serde(default = "default_api_recording_limit")]
+    pub api_recording_limit: i64,
+    // Number, time and date conventions for table exports (see `table_export::LOCALES`)
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    // Pre-run checklist (`readiness.rs`): "off", "warn" or "block", and which checks it runs
+    #[serde(default = "default_readiness_mode")]
+    pub readiness_mode: String,
+    #[serde(default = "default_true")]
+    pub readiness_log_watcher: bool,
+    #[serde(default = "default_true")]
+    pub readiness_account: bool,
+    #[serde(default)]
+    pub readiness_overlay: bool,
+    #[serde(default = "default_true")]
+    pub readiness_preset: bool,
+    #[serde(default = "default_true")]
+    pub readiness_profile_public: bool,
+    // Start a run when a new character enters `auto_start_zone` (`auto_start.rs`)
+    #[serde(default)]
+    pub auto_start_runs: bool,
+    #[serde(default = "default_auto_start_zone")]
+    pub auto_start_zone: String,
+    // Exports written to `auto_export_dir` (comma-separated `json`, `csv`, `lss`)
+    // when a run completes (`auto_export.rs`)
+    #[serde(default)]
+    pub auto_export_dir: String,
+    #[serde(default = "default_auto_export_formats")]
+    pub auto_export_formats: String,
+    #[serde(default)]
+    pub auto_export_pbs_only: bool,
+    // Which game's client is followed: "poe1" or "poe2" (`game::Game`)
+    #[serde(default = "default_game")]
+    pub game: String,
+    // Pause the run on AFK and disconnect lines (`pause.rs`)
+    #[serde(default)]
+    pub auto_pause: bool,
+    // Notifications POSTed on run completion, PBs and gold splits (`webhook.rs`)
+    #[serde(default)]
+    pub webhook_url: String,
+    #[serde(default)]
+    pub webhook_secret: String,
+    // Release channel the updater checks: "stable" or "beta" (`updater.rs`)
+    #[serde(default = "default_update_channel")]
+    pub update_channel: String,
+    // Snapshots `compact_database` keeps: "all", "final" or "recent" runs (`compact.rs`)
+    #[serde(default = "default_snapshot_retention")]
+    pub snapshot_retention: String,
+    #[serde(default = "default_snapshot_retention_runs")]
+    pub snapshot_retention_runs: i64,
+    // Twitch chat messages on PBs, gold splits and completed runs (`twitch.rs`);
+    // an empty message template posts nothing for that event
+    #[serde(default)]
+    pub twitch_token: String,
+    #[serde(default)]
+    pub twitch_username: String,
+    #[serde(default)]
+    pub twitch_channel: String,
+    #[serde(default = "default_twitch_pb_message")]
+    pub twitch_pb_message: String,
+    #[serde(default = "default_twitch_gold_message")]
+    pub twitch_gold_message: String,
+    #[serde(default)]
+    pub twitch_run_message: String,
+    // Capture a snapshot every this many levels of the run's character,
+    // besides the split snapshots; 0 turns it off
+    #[serde(default)]
+    pub snapshot_level_interval: i32,
+    // Hosts `proxy_image` may fetch from, comma-separated
+    #[serde(default = "default_image_proxy_hosts")]
+    pub image_proxy_hosts: String,
+    // Minimizing the main window hides it in the tray instead of the taskbar
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+    // Closing the main window hides it instead of exiting; the log watcher,
+    // breakpoint engine and overlays keep the run going
+    #[serde(default)]
+    pub background_mode: bool,
+    // Start and stop the log watcher and show and hide the overlays with the
+    // game client (`game_process.rs`)
+    #[serde(default)]
+    pub follow_game_process: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_hotkey_undo_split() -> String {
+    "Ctrl+Shift+Z".to_string()
+}
+
+fn default_hotkey_skip_split() -> String {
+    "Ctrl+Shift+K".to_string()
+}
+
+fn default_session_gap_minutes() -> i32 {
+    120
+}
+
+fn default_gold_protection_secs() -> i64 {
+    10
+}
+
+fn default_api_recording_limit() -> i64 {
+    10
+}
+
+fn default_locale() -> String {
+    "en-US".to_string()
+}
+
+fn default_readiness_mode() -> String {
+    "warn".to_string()
+}
+
+fn default_auto_start_zone() -> String {
+    "The Twilight Strand".to_string()
+}
+
+fn default_auto_export_formats() -> String {
+    "json".to_string()
+}
+
+fn default_game() -> String {
+    "poe1".to_string()
+}
+
+fn default_update_channel() -> String {
+    "stable".to_string()
+}
+
+fn default_snapshot_retention() -> String {
+    "all".to_string()
+}
+
+fn default_snapshot_retention_runs() -> i64 {
+    20
+}
+
+fn default_twitch_pb_message() -> String {
+    "New {category} PB: {time}!".to_string()
+}
+
+fn default_twitch_gold_message() -> String {
+    "New {split} gold: {splitTime}!".to_string()
+}
+
+fn default_image_proxy_hosts() -> String {
+    "web.poecdn.com, www.pathofexile.com, pathofexile2.com, cdn.poe2db.tw".to_string()
 }
 
 impl Default for Settings {
@@ -753,6 +3421,52 @@ impl Default for Settings {
             hotkey_toggle_overlay: "Ctrl+O".to_string(),
             hotkey_toggle_overlay_lock: "Ctrl+Shift+O".to_string(),
             hotkey_manual_split: "Ctrl+Shift+S".to_string(),
+            hotkey_undo_split: default_hotkey_undo_split(),
+            hotkey_skip_split: default_hotkey_skip_split(),
+            warmup_detection_enabled: true,
+            session_gap_minutes: default_session_gap_minutes(),
+            launch_on_boot: false,
+            start_minimized: false,
+            separate_pbs_by_patch: false,
+            announce_sound: false,
+            announce_speech: false,
+            announce_golds_only: false,
+            announce_min_behind_ms: 0,
+            announce_min_ahead_ms: 0,
+            gold_protection_secs: default_gold_protection_secs(),
+            timer_start_offset_ms: 0,
+            record_api_responses: false,
+            api_recording_limit: default_api_recording_limit(),
+            locale: default_locale(),
+            readiness_mode: default_readiness_mode(),
+            readiness_log_watcher: true,
+            readiness_account: true,
+            readiness_overlay: false,
+            readiness_preset: true,
+            readiness_profile_public: true,
+            auto_start_runs: false,
+            auto_start_zone: default_auto_start_zone(),
+            auto_export_dir: String::new(),
+            auto_export_formats: default_auto_export_formats(),
+            auto_export_pbs_only: false,
+            game: default_game(),
+            auto_pause: false,
+            webhook_url: String::new(),
+            webhook_secret: String::new(),
+            update_channel: default_update_channel(),
+            snapshot_retention: default_snapshot_retention(),
+            snapshot_retention_runs: default_snapshot_retention_runs(),
+            twitch_token: String::new(),
+            twitch_username: String::new(),
+            twitch_channel: String::new(),
+            twitch_pb_message: default_twitch_pb_message(),
+            twitch_gold_message: default_twitch_gold_message(),
+            twitch_run_message: String::new(),
+            snapshot_level_interval: 0,
+            image_proxy_hosts: default_image_proxy_hosts(),
+            minimize_to_tray: false,
+            background_mode: false,
+            follow_game_process: false,
         }
     }
 }
@@ -766,7 +3480,17 @@ impl Settings {
                     overlay_show_breakpoints, overlay_breakpoint_count, overlay_bg_opacity, overlay_accent_color,
                     overlay_always_on_top, overlay_locked,
                     hotkey_toggle_timer, hotkey_reset_timer, hotkey_manual_snapshot, hotkey_toggle_overlay, hotkey_toggle_overlay_lock,
-                    hotkey_manual_split
+                    hotkey_manual_split, warmup_detection_enabled, session_gap_minutes,
+                    launch_on_boot, start_minimized, separate_pbs_by_patch,
+                    announce_sound, announce_speech, announce_golds_only, announce_min_behind_ms, announce_min_ahead_ms,
+                    gold_protection_secs, timer_start_offset_ms, record_api_responses, api_recording_limit, locale,
+                    readiness_mode, readiness_log_watcher, readiness_account, readiness_overlay, readiness_preset,
+                    readiness_profile_public, auto_start_runs, auto_start_zone,
+                    auto_export_dir, auto_export_formats, auto_export_pbs_only,
+                    hotkey_undo_split, hotkey_skip_split, game, auto_pause, webhook_url, webhook_secret, update_channel,
+                    snapshot_retention, snapshot_retention_runs, twitch_token, twitch_username, twitch_channel,
+                    twitch_pb_message, twitch_gold_message, twitch_run_message, snapshot_level_interval,
+                    image_proxy_hosts, minimize_to_tray, background_mode, follow_game_process
              FROM settings WHERE id = 1",
             [],
             |row| {
@@ -795,6 +3519,52 @@ impl Settings {
                     hotkey_toggle_overlay: row.get(21)?,
                     hotkey_toggle_overlay_lock: row.get(22)?,
                     hotkey_manual_split: row.get(23)?,
+                    warmup_detection_enabled: row.get(24)?,
+                    session_gap_minutes: row.get(25)?,
+                    launch_on_boot: row.get(26)?,
+                    start_minimized: row.get(27)?,
+                    separate_pbs_by_patch: row.get(28)?,
+                    announce_sound: row.get(29)?,
+                    announce_speech: row.get(30)?,
+                    announce_golds_only: row.get(31)?,
+                    announce_min_behind_ms: row.get(32)?,
+                    announce_min_ahead_ms: row.get(33)?,
+                    gold_protection_secs: row.get(34)?,
+                    timer_start_offset_ms: row.get(35)?,
+                    record_api_responses: row.get(36)?,
+                    api_recording_limit: row.get(37)?,
+                    locale: row.get(38)?,
+                    readiness_mode: row.get(39)?,
+                    readiness_log_watcher: row.get(40)?,
+                    readiness_account: row.get(41)?,
+                    readiness_overlay: row.get(42)?,
+                    readiness_preset: row.get(43)?,
+                    readiness_profile_public: row.get(44)?,
+                    auto_start_runs: row.get(45)?,
+                    auto_start_zone: row.get(46)?,
+                    auto_export_dir: row.get(47)?,
+                    auto_export_formats: row.get(48)?,
+                    auto_export_pbs_only: row.get(49)?,
+                    hotkey_undo_split: row.get(50)?,
+                    hotkey_skip_split: row.get(51)?,
+                    game: row.get(52)?,
+                    auto_pause: row.get(53)?,
+                    webhook_url: row.get(54)?,
+                    webhook_secret: row.get(55)?,
+                    update_channel: row.get(56)?,
+                    snapshot_retention: row.get(57)?,
+                    snapshot_retention_runs: row.get(58)?,
+                    twitch_token: row.get(59)?,
+                    twitch_username: row.get(60)?,
+                    twitch_channel: row.get(61)?,
+                    twitch_pb_message: row.get(62)?,
+                    twitch_gold_message: row.get(63)?,
+                    twitch_run_message: row.get(64)?,
+                    snapshot_level_interval: row.get(65)?,
+                    image_proxy_hosts: row.get(66)?,
+                    minimize_to_tray: row.get(67)?,
+                    background_mode: row.get(68)?,
+                    follow_game_process: row.get(69)?,
                 })
             },
         );
@@ -813,8 +3583,22 @@ impl Settings {
                                    overlay_show_breakpoints, overlay_breakpoint_count, overlay_bg_opacity, overlay_accent_color,
                                    overlay_always_on_top, overlay_locked,
                                    hotkey_toggle_timer, hotkey_reset_timer, hotkey_manual_snapshot, hotkey_toggle_overlay, hotkey_toggle_overlay_lock,
-                                   hotkey_manual_split)
-             VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)
+                                   hotkey_manual_split, warmup_detection_enabled, session_gap_minutes,
+                                   launch_on_boot, start_minimized, separate_pbs_by_patch,
+                                   announce_sound, announce_speech, announce_golds_only, announce_min_behind_ms, announce_min_ahead_ms,
+                                   gold_protection_secs, timer_start_offset_ms, record_api_responses, api_recording_limit, locale,
+                                   readiness_mode, readiness_log_watcher, readiness_account, readiness_overlay, readiness_preset,
+                                   readiness_profile_public, auto_start_runs, auto_start_zone,
+                                   auto_export_dir, auto_export_formats, auto_export_pbs_only,
+                                   hotkey_undo_split, hotkey_skip_split, game, auto_pause, webhook_url, webhook_secret, update_channel,
+                                   snapshot_retention, snapshot_retention_runs, twitch_token, twitch_username, twitch_channel,
+                                   twitch_pb_message, twitch_gold_message, twitch_run_message, snapshot_level_interval,
+                                   image_proxy_hosts, minimize_to_tray, background_mode, follow_game_process)
+             VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29,
+                     ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37, ?38, ?39,
+                     ?40, ?41, ?42, ?43, ?44, ?45, ?46, ?47, ?48, ?49,
+                     ?50, ?51, ?52, ?53, ?54, ?55, ?56, ?57, ?58, ?59,
+                     ?60, ?61, ?62, ?63, ?64, ?65, ?66, ?67, ?68, ?69, ?70)
              ON CONFLICT(id) DO UPDATE SET
                 poe_log_path = excluded.poe_log_path,
                 account_name = excluded.account_name,
@@ -839,7 +3623,53 @@ impl Settings {
                 hotkey_manual_snapshot = excluded.hotkey_manual_snapshot,
                 hotkey_toggle_overlay = excluded.hotkey_toggle_overlay,
                 hotkey_toggle_overlay_lock = excluded.hotkey_toggle_overlay_lock,
-                hotkey_manual_split = excluded.hotkey_manual_split",
+                hotkey_manual_split = excluded.hotkey_manual_split,
+                warmup_detection_enabled = excluded.warmup_detection_enabled,
+                session_gap_minutes = excluded.session_gap_minutes,
+                launch_on_boot = excluded.launch_on_boot,
+                start_minimized = excluded.start_minimized,
+                separate_pbs_by_patch = excluded.separate_pbs_by_patch,
+                announce_sound = excluded.announce_sound,
+                announce_speech = excluded.announce_speech,
+                announce_golds_only = excluded.announce_golds_only,
+                announce_min_behind_ms = excluded.announce_min_behind_ms,
+                announce_min_ahead_ms = excluded.announce_min_ahead_ms,
+                gold_protection_secs = excluded.gold_protection_secs,
+                timer_start_offset_ms = excluded.timer_start_offset_ms,
+                record_api_responses = excluded.record_api_responses,
+                api_recording_limit = excluded.api_recording_limit,
+                locale = excluded.locale,
+                readiness_mode = excluded.readiness_mode,
+                readiness_log_watcher = excluded.readiness_log_watcher,
+                readiness_account = excluded.readiness_account,
+                readiness_overlay = excluded.readiness_overlay,
+                readiness_preset = excluded.readiness_preset,
+                readiness_profile_public = excluded.readiness_profile_public,
+                auto_start_runs = excluded.auto_start_runs,
+                auto_start_zone = excluded.auto_start_zone,
+                auto_export_dir = excluded.auto_export_dir,
+                auto_export_formats = excluded.auto_export_formats,
+                auto_export_pbs_only = excluded.auto_export_pbs_only,
+                hotkey_undo_split = excluded.hotkey_undo_split,
+                hotkey_skip_split = excluded.hotkey_skip_split,
+                game = excluded.game,
+                auto_pause = excluded.auto_pause,
+                webhook_url = excluded.webhook_url,
+                webhook_secret = excluded.webhook_secret,
+                update_channel = excluded.update_channel,
+                snapshot_retention = excluded.snapshot_retention,
+                snapshot_retention_runs = excluded.snapshot_retention_runs,
+                twitch_token = excluded.twitch_token,
+                twitch_username = excluded.twitch_username,
+                twitch_channel = excluded.twitch_channel,
+                twitch_pb_message = excluded.twitch_pb_message,
+                twitch_gold_message = excluded.twitch_gold_message,
+                twitch_run_message = excluded.twitch_run_message,
+                snapshot_level_interval = excluded.snapshot_level_interval,
+                image_proxy_hosts = excluded.image_proxy_hosts,
+                minimize_to_tray = excluded.minimize_to_tray,
+                background_mode = excluded.background_mode,
+                follow_game_process = excluded.follow_game_process",
             params![
                 settings.poe_log_path,
                 settings.account_name,
@@ -865,6 +3695,52 @@ impl Settings {
                 settings.hotkey_toggle_overlay,
                 settings.hotkey_toggle_overlay_lock,
                 settings.hotkey_manual_split,
+                settings.warmup_detection_enabled,
+                settings.session_gap_minutes,
+                settings.launch_on_boot,
+                settings.start_minimized,
+                settings.separate_pbs_by_patch,
+                settings.announce_sound,
+                settings.announce_speech,
+                settings.announce_golds_only,
+                settings.announce_min_behind_ms,
+                settings.announce_min_ahead_ms,
+                settings.gold_protection_secs,
+                settings.timer_start_offset_ms,
+                settings.record_api_responses,
+                settings.api_recording_limit,
+                settings.locale,
+                settings.readiness_mode,
+                settings.readiness_log_watcher,
+                settings.readiness_account,
+                settings.readiness_overlay,
+                settings.readiness_preset,
+                settings.readiness_profile_public,
+                settings.auto_start_runs,
+                settings.auto_start_zone,
+                settings.auto_export_dir,
+                settings.auto_export_formats,
+                settings.auto_export_pbs_only,
+                settings.hotkey_undo_split,
+                settings.hotkey_skip_split,
+                settings.game,
+                settings.auto_pause,
+                settings.webhook_url,
+                settings.webhook_secret,
+                settings.update_channel,
+                settings.snapshot_retention,
+                settings.snapshot_retention_runs,
+                settings.twitch_token,
+                settings.twitch_username,
+                settings.twitch_channel,
+                settings.twitch_pb_message,
+                settings.twitch_gold_message,
+                settings.twitch_run_message,
+                settings.snapshot_level_interval,
+                settings.image_proxy_hosts,
+                settings.minimize_to_tray,
+                settings.background_mode,
+                settings.follow_game_process,
             ],
         )?;
         Ok(())
@@ -891,4 +3767,183 @@ impl Settings {
             Err(_) => Ok((None, None)),
         }
     }
+
+    /// Status page config lives outside `Settings` so saving settings from the UI
+    /// never clobbers the pairing token
+    pub fn get_status_server() -> Result<StatusServerConfig> {
+        let conn = get_db()?;
+        let result = conn.query_row(
+            "SELECT status_server_enabled, status_server_port, status_server_token FROM settings WHERE id = 1",
+            [],
+            |row| {
+                Ok(StatusServerConfig {
+                    enabled: row.get(0)?,
+                    port: row.get(1)?,
+                    token: row.get(2)?,
+                })
+            },
+        );
+        match result {
+            Ok(config) => Ok(config),
+            Err(_) => Ok(StatusServerConfig::default()),
+        }
+    }
+
+    pub fn save_status_server(config: &StatusServerConfig) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO settings (id, status_server_enabled, status_server_port, status_server_token)
+             VALUES (1, ?1, ?2, ?3)
+             ON CONFLICT(id) DO UPDATE SET
+                status_server_enabled = excluded.status_server_enabled,
+                status_server_port = excluded.status_server_port,
+                status_server_token = excluded.status_server_token",
+            params![config.enabled, config.port, config.token],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_obs_server() -> Result<ObsServerConfig> {
+        let conn = get_db()?;
+        let result = conn.query_row(
+            "SELECT obs_server_enabled, obs_server_port FROM settings WHERE id = 1",
+            [],
+            |row| {
+                Ok(ObsServerConfig {
+                    enabled: row.get(0)?,
+                    port: row.get(1)?,
+                })
+            },
+        );
+        match result {
+            Ok(config) => Ok(config),
+            Err(_) => Ok(ObsServerConfig::default()),
+        }
+    }
+
+    pub fn save_obs_server(config: &ObsServerConfig) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO settings (id, obs_server_enabled, obs_server_port)
+             VALUES (1, ?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET
+                obs_server_enabled = excluded.obs_server_enabled,
+                obs_server_port = excluded.obs_server_port",
+            params![config.enabled, config.port],
+        )?;
+        Ok(())
+    }
+    /// App version that last ran against this database; empty before the
+    /// version was recorded. Kept out of `Settings` like the status server token.
+    pub fn get_last_app_version() -> Result<String> {
+        let conn = get_db()?;
+        let result = conn.query_row("SELECT last_app_version FROM settings WHERE id = 1", [], |row| row.get(0));
+        Ok(result.unwrap_or_default())
+    }
+
+    pub fn set_last_app_version(version: &str) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO settings (id, last_app_version) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET last_app_version = excluded.last_app_version",
+            params![version],
+        )?;
+        Ok(())
+    }
+
+    /// api.pathofexile.com sign-in, if any
+    pub fn get_oauth_token() -> Result<Option<OAuthToken>> {
+        let conn = get_db()?;
+        let result = conn.query_row(
+            "SELECT oauth_access_token, oauth_refresh_token, oauth_expires_at, oauth_account_name FROM settings WHERE id = 1",
+            [],
+            |row| {
+                Ok(OAuthToken {
+                    access_token: row.get(0)?,
+                    refresh_token: row.get(1)?,
+                    expires_at: row.get(2)?,
+                    account_name: row.get(3)?,
+                })
+            },
+        );
+        match result {
+            Ok(token) if !token.access_token.is_empty() => Ok(Some(token)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn save_oauth_token(token: &OAuthToken) -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "INSERT INTO settings (id, oauth_access_token, oauth_refresh_token, oauth_expires_at, oauth_account_name)
+             VALUES (1, ?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET
+                oauth_access_token = excluded.oauth_access_token,
+                oauth_refresh_token = excluded.oauth_refresh_token,
+                oauth_expires_at = excluded.oauth_expires_at,
+                oauth_account_name = excluded.oauth_account_name",
+            params![token.access_token, token.refresh_token, token.expires_at, token.account_name],
+        )?;
+        Ok(())
+    }
+
+    pub fn clear_oauth_token() -> Result<()> {
+        let conn = get_db()?;
+        conn.execute(
+            "UPDATE settings SET oauth_access_token = '', oauth_refresh_token = NULL, oauth_expires_at = 0,
+                                 oauth_account_name = ''
+             WHERE id = 1",
+            [],
+        )?;
+        Ok(())
+    }
+}
+
+/// api.pathofexile.com OAuth tokens. Stored in `settings` columns but kept
+/// out of `Settings`, like the status server token, so `save_settings` can't
+/// clobber them and they never reach the frontend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuthToken {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Unix seconds
+    pub expires_at: i64,
+    /// Account the token was issued for (`Name#1234`)
+    pub account_name: String,
+}
+
+/// Phone status page server settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusServerConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+impl Default for StatusServerConfig {
+    fn default() -> Self {
+        StatusServerConfig {
+            enabled: false,
+            port: 8733,
+            token: String::new(),
+        }
+    }
+}
+
+/// OBS browser source server settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsServerConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ObsServerConfig {
+    fn default() -> Self {
+        ObsServerConfig {
+            enabled: false,
+            port: 8734,
+        }
+    }
 }
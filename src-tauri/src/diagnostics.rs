@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::api_client::RecordedResponse;
+use crate::db::{Settings, Snapshot};
+
+/// Snapshot attempts kept while `record_api_responses` is on, newest last
+static RECORDINGS: Mutex<VecDeque<SnapshotRecording>> = Mutex::new(VecDeque::new());
+
+/// Raw API responses and the parsed result of one snapshot attempt
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRecording {
+    pub recorded_at: String,
+    pub run_id: i64,
+    pub split_id: Option<i64>,
+    pub account_name: String,
+    pub character_name: String,
+    pub responses: Vec<RecordedResponse>,
+    /// What was stored for the snapshot, when the attempt succeeded
+    pub parsed: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+/// Keep a snapshot attempt, dropping the oldest beyond the last `limit`
+pub fn record(recording: SnapshotRecording, limit: i64) {
+    if let Ok(mut recordings) = RECORDINGS.lock() {
+        push_bounded(&mut recordings, recording, limit.max(1) as usize);
+    }
+}
+
+/// Recorded attempts, oldest first
+pub fn recordings() -> Vec<SnapshotRecording> {
+    RECORDINGS.lock().map(|r| r.iter().cloned().collect()).unwrap_or_default()
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, item: T, limit: usize) {
+    queue.push_back(item);
+    while queue.len() > limit {
+        queue.pop_front();
+    }
+}
+
+/// The parsed side of a stored snapshot, with its JSON columns expanded
+pub fn parsed_snapshot(snapshot: &Snapshot) -> serde_json::Value {
+    serde_json::json!({
+        "characterLevel": snapshot.character_level,
+        "items": serde_json::from_str::<serde_json::Value>(&snapshot.items_json).unwrap_or_default(),
+        "passiveTree": serde_json::from_str::<serde_json::Value>(&snapshot.passive_tree_json).unwrap_or_default(),
+    })
+}
+
+/// Diagnostics bundle written by `export_diagnostics`
+pub fn bundle(
+    settings: &Settings,
+    game_version: Option<String>,
+    recordings: &[SnapshotRecording],
+) -> serde_json::Value {
+    serde_json::json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "exportedAt": chrono::Utc::now().to_rfc3339(),
+        "os": std::env::consts::OS,
+        "gameVersion": game_version,
+        "settings": settings,
+        "snapshotAttempts": recordings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording(split_id: i64) -> SnapshotRecording {
+        SnapshotRecording {
+            recorded_at: "2024-01-15T12:00:00Z".to_string(),
+            run_id: 1,
+            split_id: Some(split_id),
+            account_name: "TestAccount".to_string(),
+            character_name: "TestNecro".to_string(),
+            responses: vec![RecordedResponse {
+                url: "https://www.pathofexile.com/character-window/get-items".to_string(),
+                status: Some(200),
+                body: "{}".to_string(),
+            }],
+            parsed: None,
+            error: Some("Failed to parse items".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_push_bounded_keeps_newest() {
+        let mut queue = VecDeque::new();
+        for i in 0..5 {
+            push_bounded(&mut queue, i, 3);
+        }
+        assert_eq!(queue, [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_bundle_includes_attempts() {
+        let bundle = bundle(&Settings::default(), Some("3.25.1".to_string()), &[recording(7)]);
+        assert_eq!(bundle["gameVersion"], "3.25.1");
+        let attempt = &bundle["snapshotAttempts"][0];
+        assert_eq!(attempt["splitId"], 7);
+        assert_eq!(attempt["responses"][0]["status"], 200);
+        assert_eq!(attempt["error"], "Failed to parse items");
+    }
+}
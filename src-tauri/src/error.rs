@@ -0,0 +1,181 @@
+use crate::api_client::ApiError;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+/// Error type returned by every Tauri command.
+///
+/// Serialized to the frontend as `{ code, message }` so the UI can branch on
+/// `code` (e.g. show privacy guidance for `profile_private`) and display
+/// `message` as-is.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("Profile is private. Please set your POE profile to public in account settings.")]
+    ProfilePrivate,
+    #[error("Rate limited. Please try again later.")]
+    RateLimited,
+    #[error("Path of Exile sign-in expired. Sign in again in Settings.")]
+    Unauthorized,
+    #[error("Database is busy. Please try again.")]
+    DatabaseLocked,
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("{0}")]
+    ExternalService(String),
+    #[error("{0} timed out")]
+    Timeout(String),
+    #[error("File error: {0}")]
+    Io(String),
+    #[error("Window error: {0}")]
+    Window(String),
+    /// The OS or another app refused a valid global shortcut
+    #[error("{0}")]
+    ShortcutUnavailable(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    /// Stable machine-readable code for the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::ProfilePrivate => "profile_private",
+            AppError::RateLimited => "rate_limited",
+            AppError::Unauthorized => "unauthorized",
+            AppError::DatabaseLocked => "database_locked",
+            AppError::Database(_) => "database",
+            AppError::NotFound(_) => "not_found",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::Network(_) => "network",
+            AppError::ExternalService(_) => "external_service",
+            AppError::Timeout(_) => "timeout",
+            AppError::Io(_) => "io",
+            AppError::Window(_) => "window",
+            AppError::ShortcutUnavailable(_) => "shortcut_unavailable",
+            AppError::Internal(_) => "internal",
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<ApiError> for AppError {
+    fn from(e: ApiError) -> Self {
+        match e {
+            ApiError::PrivateProfile => AppError::ProfilePrivate,
+            ApiError::RateLimited => AppError::RateLimited,
+            ApiError::AccountNotFound => AppError::NotFound("POE account".to_string()),
+            ApiError::Unauthorized => AppError::Unauthorized,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            rusqlite::Error::SqliteFailure(ref err, _)
+                if matches!(
+                    err.code,
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+                ) =>
+            {
+                AppError::DatabaseLocked
+            }
+            _ => AppError::Database(e.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for AppError {
+    fn from(e: reqwest::Error) -> Self {
+        AppError::Network(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for AppError {
+    fn from(e: tauri::Error) -> Self {
+        AppError::Window(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::InvalidInput(format!("Invalid JSON: {}", e))
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        AppError::Internal("Internal state lock was poisoned".to_string())
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    /// Recover the typed cause from the `anyhow::Result`s returned by the db and API layers
+    fn from(e: anyhow::Error) -> Self {
+        let e = match e.downcast::<ApiError>() {
+            Ok(api) => return api.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<rusqlite::Error>() {
+            Ok(db) => return db.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<reqwest::Error>() {
+            Ok(net) => return net.into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<std::io::Error>() {
+            Ok(io) => return io.into(),
+            Err(e) => e,
+        };
+        AppError::Internal(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let json = serde_json::to_value(AppError::RateLimited).unwrap();
+        assert_eq!(json["code"], "rate_limited");
+        assert_eq!(json["message"], "Rate limited. Please try again later.");
+    }
+
+    #[test]
+    fn test_anyhow_api_error_keeps_code() {
+        let err: anyhow::Error = ApiError::PrivateProfile.into();
+        assert_eq!(AppError::from(err).code(), "profile_private");
+    }
+
+    #[test]
+    fn test_anyhow_busy_database_is_locked() {
+        let busy = rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+            None,
+        );
+        assert_eq!(AppError::from(anyhow::Error::from(busy)).code(), "database_locked");
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::Settings;
+use crate::log_watcher::ACT_TOWNS;
+
+/// Which game's client the app follows, from the `game` setting ("poe1" or "poe2")
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Game {
+    #[default]
+    Poe1,
+    Poe2,
+}
+
+/// Town of each Path of Exile 2 act, in order
+pub(crate) const POE2_ACT_TOWNS: &[&str] = &[
+    "Clearfell Encampment",
+    "The Ardura Caravan",
+    "Ziggurat Encampment",
+    "Kingsmarch",
+];
+
+impl Game {
+    /// The game a stored `game` setting names; anything unknown is PoE 1
+    pub fn from_setting(value: &str) -> Game {
+        match value {
+            "poe2" => Game::Poe2,
+            _ => Game::Poe1,
+        }
+    }
+
+    /// The game the saved settings follow
+    pub fn current() -> Game {
+        Settings::load().map(|settings| Game::from_setting(&settings.game)).unwrap_or_default()
+    }
+
+    /// Town of each act, in order; the first visit to the next act's town completes the current act
+    pub fn act_towns(self) -> &'static [&'static str] {
+        match self {
+            Game::Poe1 => ACT_TOWNS,
+            Game::Poe2 => POE2_ACT_TOWNS,
+        }
+    }
+
+    /// First zone of a new character
+    pub fn campaign_start(self) -> &'static str {
+        match self {
+            Game::Poe1 => "The Twilight Strand",
+            Game::Poe2 => "The Riverbank",
+        }
+    }
+
+    /// Realm the character API serves the game's characters from; PoE 1 uses
+    /// the endpoints' default
+    pub fn realm(self) -> Option<&'static str> {
+        match self {
+            Game::Poe1 => None,
+            Game::Poe2 => Some("poe2"),
+        }
+    }
+}
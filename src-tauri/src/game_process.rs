@@ -0,0 +1,147 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How often the process list is checked for the game client
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether game process detection works on this platform. It reads `/proc`,
+/// where a client running under Wine or Proton shows up with its Windows path.
+pub const SUPPORTED: bool = cfg!(target_os = "linux");
+
+/// A running game client
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameProcess {
+    pub pid: u32,
+    /// The client's `logs/Client.txt`, found from its working directory: the
+    /// install folder, inside whichever Wine prefix it runs in
+    pub log_path: Option<PathBuf>,
+}
+
+/// Called with the client when it starts and with None when it exits, and
+/// once with what the first check finds
+pub type Listener = Arc<dyn Fn(Option<&GameProcess>) + Send + Sync>;
+
+static FOLLOWER: Mutex<Option<(Arc<AtomicBool>, JoinHandle<()>)>> = Mutex::new(None);
+
+/// Whether the follower's last check found the client; false before its first
+static GAME_FOUND: AtomicBool = AtomicBool::new(false);
+
+/// Whether a `/proc/<pid>/cmdline` (NUL-separated arguments) is the game
+/// client: `PathOfExile*.exe`, by Windows or Unix path. Only the program
+/// counts, not launchers or shells naming it as an argument.
+pub fn is_game_command(cmdline: &[u8]) -> bool {
+    let program = cmdline.split(|b| *b == 0).next().unwrap_or_default();
+    let program = String::from_utf8_lossy(program);
+    let name = program.rsplit(['/', '\\']).next().unwrap_or_default().to_ascii_lowercase();
+    name.starts_with("pathofexile") && name.ends_with(".exe")
+}
+
+/// The first game client among the processes under `proc_dir`
+pub fn find_game(proc_dir: &Path) -> Option<GameProcess> {
+    std::fs::read_dir(proc_dir).ok()?.flatten().find_map(|entry| {
+        let pid = entry.file_name().to_str()?.parse().ok()?;
+        // Processes can exit mid-scan, and other users' can't be read
+        let cmdline = std::fs::read(entry.path().join("cmdline")).ok()?;
+        if !is_game_command(&cmdline) {
+            return None;
+        }
+        let log_path = std::fs::read_link(entry.path().join("cwd"))
+            .ok()
+            .map(|dir| dir.join("logs").join("Client.txt"))
+            .filter(|path| path.is_file());
+        Some(GameProcess { pid, log_path })
+    })
+}
+
+/// Check for the game client every few seconds, telling `listener` when it
+/// starts or exits. Already following, or unsupported here, does nothing.
+pub fn follow(listener: Listener) {
+    let Ok(mut follower) = FOLLOWER.lock() else {
+        return;
+    };
+    if !SUPPORTED || follower.is_some() {
+        return;
+    }
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let stopped = stop_flag.clone();
+    let thread = thread::spawn(move || {
+        let mut running = None;
+        while !stopped.load(Ordering::Relaxed) {
+            let game = find_game(Path::new("/proc"));
+            if running != Some(game.is_some()) {
+                running = Some(game.is_some());
+                GAME_FOUND.store(game.is_some(), Ordering::Relaxed);
+                listener(game.as_ref());
+            }
+            // Sleep in steps, so `unfollow` doesn't wait out a whole interval
+            let mut slept = Duration::ZERO;
+            while slept < POLL_INTERVAL && !stopped.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(250));
+                slept += Duration::from_millis(250);
+            }
+        }
+    });
+    *follower = Some((stop_flag, thread));
+}
+
+/// Stop checking for the game client
+pub fn unfollow() {
+    let Some((stop_flag, thread)) = FOLLOWER.lock().ok().and_then(|mut f| f.take()) else {
+        return;
+    };
+    stop_flag.store(true, Ordering::Relaxed);
+    let _ = thread.join();
+    GAME_FOUND.store(false, Ordering::Relaxed);
+}
+
+/// Whether the log watcher should wait for the game: it's being followed and
+/// isn't running, or hasn't been checked for yet
+pub fn waiting_for_game() -> bool {
+    let following = FOLLOWER.lock().map(|follower| follower.is_some()).unwrap_or(false);
+    following && !GAME_FOUND.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_commands() {
+        assert!(is_game_command(b"C:\\Program Files (x86)\\Grinding Gear Games\\Path of Exile\\PathOfExile_x64.exe\0"));
+        assert!(is_game_command(b"Z:\\home\\me\\.steam\\steam\\steamapps\\common\\Path of Exile\\PathOfExileSteam.exe\0-gc\0"));
+        assert!(is_game_command(b"/home/me/Games/path-of-exile/drive_c/PathOfExile.EXE"));
+        assert!(!is_game_command(b"/usr/bin/grep\0PathOfExile.exe\0"));
+        assert!(!is_game_command(b"C:\\windows\\system32\\explorer.exe\0"));
+        assert!(!is_game_command(b""));
+    }
+
+    #[test]
+    fn test_find_game_in_proc() {
+        let dir = std::env::temp_dir().join(format!("poe-watcher-game-process-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let process = |pid: &str, cmdline: &[u8]| {
+            std::fs::create_dir_all(dir.join(pid)).unwrap();
+            std::fs::write(dir.join(pid).join("cmdline"), cmdline).unwrap();
+        };
+        process("1", b"/sbin/init\0");
+        process("self", b"C:\\Games\\PathOfExile.exe\0");
+        assert_eq!(find_game(&dir), None, "only numbered entries are processes");
+
+        process("4242", b"C:\\Games\\Path of Exile\\PathOfExile_x64Steam.exe\0");
+        assert_eq!(find_game(&dir), Some(GameProcess { pid: 4242, log_path: None }));
+
+        // The working directory is the install folder, which has the log
+        #[cfg(unix)]
+        {
+            let install = dir.join("install");
+            std::fs::create_dir_all(install.join("logs")).unwrap();
+            std::fs::write(install.join("logs/Client.txt"), "").unwrap();
+            std::os::unix::fs::symlink(&install, dir.join("4242/cwd")).unwrap();
+            assert_eq!(find_game(&dir).unwrap().log_path, Some(install.join("logs").join("Client.txt")));
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
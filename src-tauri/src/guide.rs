@@ -0,0 +1,377 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::db::{LevelingGuide, Run};
+use crate::error::{AppError, AppResult};
+use crate::log_watcher::LogEvent;
+
+/// What finishes a guide step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "value")]
+pub enum StepTrigger {
+    /// Entering a zone, by the name the log gives it
+    Zone(String),
+    /// Generating an area, by its id (`1_1_2`), as Exile Leveling routes name them
+    Area(String),
+    /// Reaching a character level
+    Level(u32),
+}
+
+/// One instruction of a leveling guide
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuideStep {
+    /// Heading the step is under, usually its act
+    pub section: String,
+    pub text: String,
+    /// `None` for steps that only pass once a later step's trigger is seen
+    pub trigger: Option<StepTrigger>,
+}
+
+/// An imported leveling guide
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Guide {
+    pub name: String,
+    pub steps: Vec<GuideStep>,
+}
+
+/// Where a run is in the guide, as returned by `get_current_guide_step` and
+/// the `guide-step-changed` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuideProgress {
+    pub run_id: i64,
+    pub guide_name: String,
+    /// Index of the active step; the steps before it are done
+    pub step_index: usize,
+    pub total_steps: usize,
+    /// `None` once the guide is finished
+    pub step: Option<GuideStep>,
+    pub next: Option<GuideStep>,
+}
+
+impl GuideProgress {
+    pub fn new(run_id: i64, guide: &Guide, step_index: usize) -> GuideProgress {
+        GuideProgress {
+            run_id,
+            guide_name: guide.name.clone(),
+            step_index,
+            total_steps: guide.steps.len(),
+            step: guide.steps.get(step_index).cloned(),
+            next: guide.steps.get(step_index + 1).cloned(),
+        }
+    }
+}
+
+/// Steps past the active one a zone or area can finish, so walking back
+/// through a zone the guide visits again later doesn't skip ahead to it
+const LOOKAHEAD: usize = 8;
+
+/// Parse a guide: an Exile Leveling route (its sections, or an object with
+/// `sections`), or `{ "name", "steps": [{ "text", "section", "zone" | "areaId" | "level" }] }`.
+/// `fallback_name` names guides that don't name themselves.
+pub fn parse(contents: &str, fallback_name: &str) -> AppResult<Guide> {
+    let value: Value = serde_json::from_str(contents)
+        .map_err(|e| AppError::InvalidInput(format!("Not a leveling guide: {}", e)))?;
+    let name = value
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(fallback_name)
+        .to_string();
+
+    let steps: Vec<GuideStep> = if let Some(sections) = value.as_array().or_else(|| value.get("sections").and_then(Value::as_array)) {
+        sections.iter().flat_map(exile_leveling_section).collect()
+    } else if let Some(steps) = value.get("steps").and_then(Value::as_array) {
+        steps.iter().filter_map(simple_step).collect()
+    } else {
+        return Err(AppError::InvalidInput("Not a leveling guide: no sections or steps".to_string()));
+    };
+    if steps.is_empty() {
+        return Err(AppError::InvalidInput("The guide has no steps".to_string()));
+    }
+    Ok(Guide { name, steps })
+}
+
+/// A step of the plain format
+fn simple_step(step: &Value) -> Option<GuideStep> {
+    let text = step.get("text")?.as_str()?.trim().to_string();
+    let trigger = if let Some(zone) = step.get("zone").and_then(Value::as_str) {
+        Some(StepTrigger::Zone(zone.to_string()))
+    } else if let Some(area_id) = step.get("areaId").and_then(Value::as_str) {
+        Some(StepTrigger::Area(area_id.to_string()))
+    } else {
+        step.get("level").and_then(Value::as_u64).map(|level| StepTrigger::Level(level as u32))
+    };
+    Some(GuideStep {
+        section: step.get("section").and_then(Value::as_str).unwrap_or_default().to_string(),
+        text,
+        trigger,
+    })
+}
+
+/// Steps of an Exile Leveling section: fragment steps, finished on reaching
+/// the last area they send the character to, and gem steps
+fn exile_leveling_section(section: &Value) -> Vec<GuideStep> {
+    let name = section.get("name").and_then(Value::as_str).unwrap_or_default();
+    let Some(steps) = section.get("steps").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    steps
+        .iter()
+        .filter_map(|step| {
+            let (text, trigger) = match step.get("type").and_then(Value::as_str) {
+                Some("gem_step") => {
+                    let gem = step.pointer("/requiredGem/id").and_then(Value::as_str)?;
+                    let verb = if step.get("rewardType").and_then(Value::as_str) == Some("vendor") { "Buy" } else { "Take" };
+                    (format!("{} {}", verb, gem_name(gem)), None)
+                }
+                _ => {
+                    let parts = step.get("parts")?.as_array()?;
+                    let text: String = parts.iter().map(fragment_text).collect();
+                    let trigger = parts.iter().rev().find_map(fragment_destination).map(StepTrigger::Area);
+                    (text.split_whitespace().collect::<Vec<_>>().join(" "), trigger)
+                }
+            };
+            (!text.is_empty()).then(|| GuideStep { section: name.to_string(), text, trigger })
+        })
+        .collect()
+}
+
+/// Text of a step part: plain text, or the value, item or area a fragment names
+fn fragment_text(part: &Value) -> String {
+    if let Some(text) = part.as_str() {
+        return text.to_string();
+    }
+    let field = |key: &str| part.get(key).and_then(Value::as_str).map(str::to_string);
+    match part.get("type").and_then(Value::as_str).unwrap_or_default() {
+        "logout" => "Logout".to_string(),
+        "waypoint" | "waypoint_get" => "Waypoint".to_string(),
+        "trial" => "Trial of Ascendancy".to_string(),
+        "dir" => String::new(),
+        kind => field("value")
+            .or_else(|| field("item"))
+            .or_else(|| field("areaId"))
+            .or_else(|| field("dstAreaId"))
+            .unwrap_or_else(|| kind.replace('_', " ")),
+    }
+}
+
+/// Area a fragment sends the character to
+fn fragment_destination(part: &Value) -> Option<String> {
+    let key = match part.get("type")?.as_str()? {
+        "enter" | "logout" => "areaId",
+        "waypoint_use" | "portal_use" => "dstAreaId",
+        _ => return None,
+    };
+    part.get(key)?.as_str().map(str::to_string)
+}
+
+/// "Metadata/Items/Gems/SupportGemAddedFireDamage" → "Added Fire Damage"
+fn gem_name(id: &str) -> String {
+    let base = id.rsplit('/').next().unwrap_or(id);
+    let base = base.trim_start_matches("SkillGem").trim_start_matches("SupportGem");
+    let mut name = String::new();
+    for c in base.chars() {
+        if c.is_uppercase() && !name.is_empty() {
+            name.push(' ');
+        }
+        name.push(c);
+    }
+    name
+}
+
+/// Step a run is on after `event`, or `None` if the event doesn't move it.
+/// A zone or area finishes the first step up to `LOOKAHEAD` ahead that goes
+/// there (and the untriggered steps before it); a level-up finishes level
+/// steps from the active one on.
+pub fn advance(steps: &[GuideStep], step: usize, event: &LogEvent) -> Option<usize> {
+    let matches = |trigger: StepTrigger| {
+        steps
+            .iter()
+            .enumerate()
+            .skip(step)
+            .take(LOOKAHEAD + 1)
+            .find(|(_, s)| s.trigger.as_ref() == Some(&trigger))
+            .map(|(i, _)| i + 1)
+    };
+    match event {
+        LogEvent::ZoneEnter { zone_name, .. } => matches(StepTrigger::Zone(zone_name.clone())),
+        LogEvent::AreaGenerated { area_id, .. } => matches(StepTrigger::Area(area_id.clone())),
+        LogEvent::LevelUp { level, .. } => {
+            let passed = steps[step.min(steps.len())..]
+                .iter()
+                .take_while(|s| matches!(s.trigger, Some(StepTrigger::Level(needed)) if needed <= *level))
+                .count();
+            (passed > 0).then_some(step + passed)
+        }
+        _ => None,
+    }
+}
+
+/// The stored guide, if one was imported
+pub fn load() -> AppResult<Option<Guide>> {
+    let Some(stored) = LevelingGuide::load()? else {
+        return Ok(None);
+    };
+    let steps = serde_json::from_str(&stored.steps_json)
+        .map_err(|e| AppError::Internal(format!("Stored guide: {}", e)))?;
+    Ok(Some(Guide { name: stored.name, steps }))
+}
+
+/// Run being guided, with the guide and the step it's on
+static ACTIVE: Mutex<Option<(i64, Guide, usize)>> = Mutex::new(None);
+
+fn active() -> std::sync::MutexGuard<'static, Option<(i64, Guide, usize)>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Follow a newly created run through the guide, if one is imported
+pub fn start_run(run_id: i64) {
+    let guide = match load() {
+        Ok(guide) => guide,
+        Err(e) => {
+            eprintln!("[guide] Failed to load the guide: {}", e);
+            None
+        }
+    };
+    *active() = guide.map(|guide| (run_id, guide, 0));
+}
+
+/// Pick up a newly imported or cleared guide for the run being guided
+pub fn reload() -> AppResult<()> {
+    let mut active = active();
+    let Some(run_id) = active.as_ref().map(|(run_id, _, _)| *run_id) else {
+        return Ok(());
+    };
+    *active = load()?.map(|guide| (run_id, guide, 0));
+    LevelingGuide::set_step(run_id, 0)?;
+    Ok(())
+}
+
+/// Move the run being guided to `step` (the runner skipping or going back)
+pub fn set_step(run_id: i64, step: usize) -> AppResult<GuideProgress> {
+    let guide = load()?.ok_or_else(|| AppError::NotFound("Leveling guide".to_string()))?;
+    let step = step.min(guide.steps.len());
+    LevelingGuide::set_step(run_id, step)?;
+    if let Some((active_id, _, active_step)) = active().as_mut() {
+        if *active_id == run_id {
+            *active_step = step;
+        }
+    }
+    Ok(GuideProgress::new(run_id, &guide, step))
+}
+
+/// Where `run_id` is in the guide
+pub fn progress(run_id: i64) -> AppResult<GuideProgress> {
+    let step = LevelingGuide::step_of(run_id)?.ok_or_else(|| AppError::NotFound(format!("Run {}", run_id)))?;
+    let guide = load()?.ok_or_else(|| AppError::NotFound("Leveling guide".to_string()))?;
+    Ok(GuideProgress::new(run_id, &guide, step.min(guide.steps.len())))
+}
+
+/// Advance the run being guided on a log event, emitting `guide-step-changed`.
+/// Level-ups of other characters (party members) don't count.
+pub fn handle_event(app_handle: &AppHandle, event: &LogEvent) {
+    let mut active = active();
+    let Some((run_id, guide, step)) = active.as_mut() else {
+        return;
+    };
+    let Some(next) = advance(&guide.steps, *step, event) else {
+        return;
+    };
+    if let LogEvent::LevelUp { character_name, .. } = event {
+        match Run::get_by_id(*run_id) {
+            Ok(Some(run)) if run.character_name == "Unknown" || &run.character_name == character_name => {}
+            _ => return,
+        }
+    }
+    *step = next;
+    if let Err(e) = LevelingGuide::set_step(*run_id, next) {
+        eprintln!("[guide] Failed to save step for run {}: {}", run_id, e);
+    }
+    let _ = app_handle.emit("guide-step-changed", GuideProgress::new(*run_id, guide, next));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(name: &str) -> LogEvent {
+        LogEvent::ZoneEnter { timestamp: String::new(), zone_name: name.to_string() }
+    }
+
+    fn level_up(level: u32) -> LogEvent {
+        LogEvent::LevelUp {
+            timestamp: String::new(),
+            character_name: "TestChar".to_string(),
+            character_class: "Witch".to_string(),
+            level,
+        }
+    }
+
+    #[test]
+    fn test_parse_exile_leveling_route() {
+        let route = serde_json::json!([{
+            "name": "Act 1",
+            "steps": [
+                { "type": "fragment_step", "parts": ["Kill ", { "type": "kill", "value": "Hillock" }] },
+                { "type": "fragment_step", "parts": [{ "type": "enter", "areaId": "1_1_town" }] },
+                { "type": "gem_step", "requiredGem": { "id": "Metadata/Items/Gems/SupportGemAddedFireDamage" }, "rewardType": "vendor" },
+                { "type": "fragment_step", "parts": [{ "type": "waypoint_use", "dstAreaId": "1_1_2", "srcAreaId": "1_1_town" }, " and explore"] }
+            ]
+        }]);
+        let guide = parse(&route.to_string(), "my-route").unwrap();
+        assert_eq!(guide.name, "my-route");
+
+        let steps: Vec<_> = guide.steps.iter().map(|s| (s.section.as_str(), s.text.as_str(), s.trigger.clone())).collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("Act 1", "Kill Hillock", None),
+                ("Act 1", "1_1_town", Some(StepTrigger::Area("1_1_town".to_string()))),
+                ("Act 1", "Buy Added Fire Damage", None),
+                ("Act 1", "1_1_2 and explore", Some(StepTrigger::Area("1_1_2".to_string()))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_plain_guide() {
+        let guide = parse(
+            r#"{"name": "Fast A1", "steps": [{"text": "Go to the Coast", "zone": "The Coast"}, {"text": "Hit 12", "level": 12}, {"note": "no text"}]}"#,
+            "fallback",
+        )
+        .unwrap();
+        assert_eq!(guide.name, "Fast A1");
+        assert_eq!(guide.steps.len(), 2);
+        assert_eq!(guide.steps[1].trigger, Some(StepTrigger::Level(12)));
+        assert!(parse(r#"{"steps": []}"#, "empty").is_err());
+        assert!(parse("[not json", "broken").is_err());
+    }
+
+    #[test]
+    fn test_advance_on_zones_and_levels() {
+        let step = |text: &str, trigger: Option<StepTrigger>| GuideStep { section: String::new(), text: text.to_string(), trigger };
+        let steps = vec![
+            step("Kill Hillock", None),
+            step("Town", Some(StepTrigger::Zone("Lioneye's Watch".to_string()))),
+            step("Level 2", Some(StepTrigger::Level(2))),
+            step("Level 4", Some(StepTrigger::Level(4))),
+            step("Coast", Some(StepTrigger::Zone("The Coast".to_string()))),
+            step("Back to town", Some(StepTrigger::Zone("Lioneye's Watch".to_string()))),
+        ];
+
+        // Entering town finishes the untriggered step before it
+        assert_eq!(advance(&steps, 0, &zone("Lioneye's Watch")), Some(2));
+        assert_eq!(advance(&steps, 2, &zone("The Mud Flats")), None);
+        assert_eq!(advance(&steps, 2, &level_up(3)), Some(3));
+        assert_eq!(advance(&steps, 2, &level_up(5)), Some(4));
+        // The next town visit, not the one already done
+        assert_eq!(advance(&steps, 4, &zone("Lioneye's Watch")), Some(6));
+        // Finished
+        assert_eq!(advance(&steps, 6, &level_up(10)), None);
+    }
+}
@@ -0,0 +1,246 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use crate::error::{AppError, AppResult};
+
+/// How long a cached image is served before it is fetched again
+const IMAGE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Cache size past which the least recently written images are evicted
+const MAX_CACHE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Largest image `proxy_image` passes on, in bytes
+pub const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Where cached images live: `image_cache` under the app data directory
+static CACHE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// What's in the cache, and how often it answered since the app started
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageCacheStats {
+    pub entries: u64,
+    pub size_bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Hosts in the comma-separated `image_proxy_hosts` setting, lowercased.
+/// None when an entry isn't a bare host name (no scheme, port or path) or
+/// the list is empty.
+pub fn parse_hosts(list: &str) -> Option<Vec<String>> {
+    let hosts: Vec<String> = list
+        .split(',')
+        .map(|host| host.trim().to_ascii_lowercase())
+        .filter(|host| !host.is_empty())
+        .collect();
+    let is_host = |host: &str| {
+        host.contains('.')
+            && host.split('.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+    };
+    (!hosts.is_empty() && hosts.iter().all(|host| is_host(host))).then_some(hosts)
+}
+
+/// Whether `proxy_image` may fetch `url`: http(s) on one of `hosts`, matched exactly
+pub fn is_allowed(url: &reqwest::Url, hosts: &[String]) -> bool {
+    matches!(url.scheme(), "https" | "http")
+        && url.host_str().is_some_and(|host| hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(host)))
+}
+
+/// Use `dir` for the cache, creating it if needed
+pub fn init(dir: PathBuf) -> AppResult<()> {
+    fs::create_dir_all(&dir)?;
+    *CACHE_DIR.lock().map_err(|e| AppError::Internal(e.to_string()))? = Some(dir);
+    Ok(())
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    CACHE_DIR.lock().ok()?.clone()
+}
+
+/// Cache file for a URL: the hex SHA-256 of the URL, so any URL is a safe file name
+fn entry_path(dir: &Path, url: &str) -> PathBuf {
+    let hash: String = Sha256::digest(url.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+    dir.join(hash)
+}
+
+/// The cached data URL for `url`, unless it is missing or older than the TTL
+fn lookup_in(dir: &Path, url: &str, now: SystemTime) -> Option<String> {
+    let path = entry_path(dir, url);
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    if now.duration_since(modified).unwrap_or_default() > IMAGE_TTL {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+/// Cache files with their size and last write, oldest first
+fn entries(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+        })
+        .collect();
+    entries.sort_by_key(|(_, _, modified)| *modified);
+    entries
+}
+
+/// Write `data_url` for `url`, then evict the oldest images until the cache
+/// fits in `max_bytes`
+fn store_in(dir: &Path, url: &str, data_url: &str, max_bytes: u64) -> AppResult<()> {
+    fs::write(entry_path(dir, url), data_url)?;
+
+    let entries = entries(dir);
+    let mut size: u64 = entries.iter().map(|(_, len, _)| len).sum();
+    for (path, len, _) in entries {
+        if size <= max_bytes {
+            break;
+        }
+        fs::remove_file(path)?;
+        size -= len;
+    }
+    Ok(())
+}
+
+/// The cached data URL for `url`, counting the hit or miss
+pub fn lookup(url: &str) -> Option<String> {
+    let cached = cache_dir().and_then(|dir| lookup_in(&dir, url, SystemTime::now()));
+    let counter = if cached.is_some() { &HITS } else { &MISSES };
+    counter.fetch_add(1, Ordering::Relaxed);
+    cached
+}
+
+/// Cache a fetched image. Failing to cache only costs a re-download, so
+/// errors are logged rather than returned.
+pub fn store(url: &str, data_url: &str) {
+    let Some(dir) = cache_dir() else {
+        return;
+    };
+    if let Err(e) = store_in(&dir, url, data_url, MAX_CACHE_BYTES) {
+        eprintln!("[image_cache] Failed to cache {}: {}", url, e);
+    }
+}
+
+fn stats_in(dir: &Path) -> ImageCacheStats {
+    let entries = entries(dir);
+    ImageCacheStats {
+        entries: entries.len() as u64,
+        size_bytes: entries.iter().map(|(_, len, _)| len).sum(),
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+    }
+}
+
+pub fn stats() -> ImageCacheStats {
+    match cache_dir() {
+        Some(dir) => stats_in(&dir),
+        None => ImageCacheStats {
+            entries: 0,
+            size_bytes: 0,
+            hits: HITS.load(Ordering::Relaxed),
+            misses: MISSES.load(Ordering::Relaxed),
+        },
+    }
+}
+
+fn clear_in(dir: &Path) -> AppResult<()> {
+    for (path, _, _) in entries(dir) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Delete every cached image and reset the hit counts, returning the stats
+/// from before clearing
+pub fn clear() -> AppResult<ImageCacheStats> {
+    let before = stats();
+    if let Some(dir) = cache_dir() {
+        clear_in(&dir)?;
+    }
+    HITS.store(0, Ordering::Relaxed);
+    MISSES.store(0, Ordering::Relaxed);
+    Ok(before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("poe-watcher-image-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_host_allowlist() {
+        let hosts = parse_hosts(" web.poecdn.com, CDN.poe2db.tw ,").unwrap();
+        assert_eq!(hosts, ["web.poecdn.com", "cdn.poe2db.tw"]);
+        for bad in ["", " , ", "https://web.poecdn.com", "web.poecdn.com/image", "web.poecdn.com:443", "localhost", "-a.com"] {
+            assert_eq!(parse_hosts(bad), None, "{}", bad);
+        }
+
+        let url = |s: &str| reqwest::Url::parse(s).unwrap();
+        assert!(is_allowed(&url("https://web.poecdn.com/image/Art/icon.png"), &hosts));
+        assert!(is_allowed(&url("https://cdn.poe2db.tw/image/Art/2DItems/x.webp"), &hosts));
+        // Look-alike hosts and other schemes don't get through
+        assert!(!is_allowed(&url("https://web.poecdn.com.evil.example/icon.png"), &hosts));
+        assert!(!is_allowed(&url("https://evil.example/?web.poecdn.com"), &hosts));
+        assert!(!is_allowed(&url("ftp://web.poecdn.com/icon.png"), &hosts));
+    }
+
+    #[test]
+    fn test_lookup_respects_ttl() {
+        let dir = temp_dir("ttl");
+        let url = "https://web.poecdn.com/image/icon.png?v=1";
+        assert_eq!(lookup_in(&dir, url, SystemTime::now()), None);
+
+        store_in(&dir, url, "data:image/png;base64,AAAA", MAX_CACHE_BYTES).unwrap();
+        assert_eq!(lookup_in(&dir, url, SystemTime::now()).as_deref(), Some("data:image/png;base64,AAAA"));
+        // Another URL, even one differing only in its query, is its own entry
+        assert_eq!(lookup_in(&dir, "https://web.poecdn.com/image/icon.png?v=2", SystemTime::now()), None);
+
+        let expired = SystemTime::now() + IMAGE_TTL + Duration::from_secs(60);
+        assert_eq!(lookup_in(&dir, url, expired), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_store_evicts_oldest_and_clear() {
+        let dir = temp_dir("evict");
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        store_in(&dir, "a", &"a".repeat(100), 1000).unwrap();
+        fs::File::options().write(true).open(entry_path(&dir, "a")).unwrap().set_modified(old).unwrap();
+        store_in(&dir, "b", &"b".repeat(100), 1000).unwrap();
+        assert_eq!(stats_in(&dir).entries, 2);
+
+        // Over the limit: the oldest write goes first
+        store_in(&dir, "c", &"c".repeat(850), 1000).unwrap();
+        let now = SystemTime::now();
+        assert_eq!(lookup_in(&dir, "a", now), None);
+        assert!(lookup_in(&dir, "b", now).is_some() && lookup_in(&dir, "c", now).is_some());
+        assert_eq!(stats_in(&dir).size_bytes, 950);
+
+        clear_in(&dir).unwrap();
+        assert_eq!(stats_in(&dir).entries, 0);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
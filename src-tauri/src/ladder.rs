@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::api_client::LadderEntry;
+use crate::db::{LadderObservation, NewLadderObservation, ReferenceRunData, ReferenceSplitData};
+use crate::error::{AppError, AppResult};
+
+/// Levels a ladder reference run has splits for, named like the "Level N"
+/// breakpoints so they line up in the comparison view
+pub const LEVEL_MILESTONES: [i32; 10] = [10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+
+/// A ladder character as `fetch_ladder` returns it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LadderRacer {
+    pub rank: u32,
+    pub character_name: String,
+    pub account_name: String,
+    pub class: String,
+    pub level: u32,
+    pub experience: u64,
+    pub dead: bool,
+    /// Level milestones timed so far: reached between two fetches
+    pub milestones: usize,
+}
+
+/// Record the characters of a fetched ladder whose level or experience moved
+/// since their last observation. Returns how many were recorded.
+pub fn record(league: &str, entries: &[LadderEntry], observed_at: &str) -> AppResult<usize> {
+    let mut recorded = 0;
+    for entry in entries {
+        let observation = NewLadderObservation {
+            league: league.to_string(),
+            character_name: entry.character.name.clone(),
+            account_name: entry.account.as_ref().map(|a| a.name.clone()).unwrap_or_default(),
+            class: entry.character.class.clone(),
+            level: entry.character.level as i32,
+            experience: entry.character.experience as i64,
+            rank: entry.rank as i64,
+            observed_at: observed_at.to_string(),
+        };
+        if LadderObservation::record(&observation)? {
+            recorded += 1;
+        }
+    }
+    Ok(recorded)
+}
+
+/// The fetched ladder with how many milestones each character has timed
+pub fn racers(league: &str, entries: &[LadderEntry]) -> AppResult<Vec<LadderRacer>> {
+    let observations = LadderObservation::get_by_league(league)?;
+    Ok(entries
+        .iter()
+        .map(|entry| {
+            let seen: Vec<LadderObservation> = observations
+                .iter()
+                .filter(|o| o.character_name == entry.character.name)
+                .cloned()
+                .collect();
+            LadderRacer {
+                rank: entry.rank,
+                character_name: entry.character.name.clone(),
+                account_name: entry.account.as_ref().map(|a| a.name.clone()).unwrap_or_default(),
+                class: entry.character.class.clone(),
+                level: entry.character.level,
+                experience: entry.character.experience,
+                dead: entry.dead,
+                milestones: milestone_times(&seen).len(),
+            }
+        })
+        .collect())
+}
+
+/// When each milestone was first seen reached. A milestone the character was
+/// already past at its first observation can't be timed and is left out; the
+/// rest are late by up to the time between fetches.
+fn milestone_times(observations: &[LadderObservation]) -> Vec<(i32, DateTime<Utc>)> {
+    let Some(first) = observations.first() else {
+        return Vec::new();
+    };
+    LEVEL_MILESTONES
+        .iter()
+        .filter(|milestone| first.level < **milestone)
+        .filter_map(|milestone| {
+            let reached = observations.iter().find(|o| o.level >= *milestone)?;
+            let at = DateTime::parse_from_rfc3339(&reached.observed_at).ok()?;
+            Some((*milestone, at.with_timezone(&Utc)))
+        })
+        .collect()
+}
+
+/// Level milestone splits of a character's observations, timed from the
+/// league start
+pub fn milestone_splits(observations: &[LadderObservation], league_start: &str) -> AppResult<Vec<ReferenceSplitData>> {
+    let start = DateTime::parse_from_rfc3339(league_start)
+        .map_err(|e| AppError::InvalidInput(format!("League start {}: {}", league_start, e)))?;
+    Ok(milestone_times(observations)
+        .into_iter()
+        .map(|(level, at)| ReferenceSplitData {
+            breakpoint_name: format!("Level {}", level),
+            breakpoint_type: "level".to_string(),
+            breakpoint_key: Some(format!("level:{}", level)),
+            split_time_ms: (at - start.with_timezone(&Utc)).num_milliseconds(),
+        })
+        .filter(|split| split.split_time_ms > 0)
+        .collect())
+}
+
+/// `data` with a ladder character's timed milestones as its splits
+pub fn reference_run(
+    character_name: &str,
+    league: &str,
+    league_start: &str,
+    mut data: ReferenceRunData,
+) -> AppResult<ReferenceRunData> {
+    let observations = LadderObservation::get_by_character(league, character_name)?;
+    data.splits = milestone_splits(&observations, league_start)?;
+    if data.splits.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "No level milestones timed for {} yet. Keep fetching the ladder while they level.",
+            character_name
+        )));
+    }
+    data.total_time_ms = data.splits.last().map(|s| s.split_time_ms).unwrap_or(0);
+    data.character_name = Some(character_name.to_string());
+    data.league = Some(league.to_string());
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seen(level: i32, observed_at: &str) -> LadderObservation {
+        LadderObservation {
+            id: 0,
+            league: "Race".to_string(),
+            character_name: "Leader".to_string(),
+            account_name: "Fast#0001".to_string(),
+            class: "Witch".to_string(),
+            level,
+            experience: 0,
+            rank: 1,
+            observed_at: observed_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_milestones_timed_from_league_start() {
+        let observations = vec![
+            seen(12, "2026-01-01T00:10:00Z"),
+            seen(19, "2026-01-01T00:20:00Z"),
+            seen(31, "2026-01-01T00:40:00Z"),
+            seen(38, "2026-01-01T00:50:00+00:00"),
+        ];
+        let splits = milestone_splits(&observations, "2026-01-01T00:00:00Z").unwrap();
+        // Level 10 was passed before the first fetch
+        let timed: Vec<(&str, i64)> = splits.iter().map(|s| (s.breakpoint_name.as_str(), s.split_time_ms)).collect();
+        assert_eq!(timed, vec![("Level 20", 2_400_000), ("Level 30", 2_400_000)]);
+        assert_eq!(splits[0].breakpoint_key.as_deref(), Some("level:20"));
+
+        assert!(milestone_splits(&[], "2026-01-01T00:00:00Z").unwrap().is_empty());
+        assert!(milestone_splits(&observations, "not a date").is_err());
+    }
+}
@@ -0,0 +1,283 @@
+use serde::Serialize;
+
+use crate::db::{RunEvent, SnapshotSummary, Split};
+
+/// A run's level at one point of its run clock
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelSample {
+    pub elapsed_ms: i64,
+    pub level: i32,
+}
+
+/// A run's character level and experience at one snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperiencePoint {
+    pub elapsed_ms: i64,
+    pub level: i32,
+    pub experience: i64,
+}
+
+/// Level on entering a zone
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneLevel {
+    pub zone_name: String,
+    pub elapsed_ms: i64,
+    pub level: Option<i32>,
+}
+
+/// Level at one breakpoint, for the run and its PB
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitLevel {
+    pub breakpoint_name: String,
+    /// `None` for a PB breakpoint the run hasn't reached yet
+    pub split_time_ms: Option<i64>,
+    pub level: Option<i32>,
+    pub pb_level: Option<i32>,
+    /// Breakpoints still ahead: the PB's level there, shifted by how many
+    /// levels the run is ahead of or behind the PB now
+    pub expected_level: Option<i32>,
+}
+
+/// How a run is levelling compared with its PB
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelPace {
+    pub run_id: i64,
+    /// Run clock the levels are compared at
+    pub elapsed_ms: i64,
+    pub level: Option<i32>,
+    pub pb_run_id: Option<i64>,
+    /// The PB's level at the same run clock
+    pub pb_level: Option<i32>,
+    /// The PB's levels over time, so the overlay can keep comparing as the
+    /// clock runs without asking again
+    pub pb_levels: Vec<LevelSample>,
+    /// Experience per hour up to the last split with experience; runs start
+    /// on a new character, so from zero
+    pub xp_per_hour: Option<f64>,
+    /// The run's splits, then the PB's breakpoints it hasn't reached
+    pub splits: Vec<SplitLevel>,
+    /// Zones in the order entered
+    pub zones: Vec<ZoneLevel>,
+}
+
+/// Levels known for a run in run-clock order: its level-ups, and the levels
+/// its splits' snapshots saw (which catch level-ups missed while the log
+/// wasn't watched)
+pub fn samples(events: &[RunEvent], splits: &[Split]) -> Vec<LevelSample> {
+    let level_ups = events
+        .iter()
+        .filter(|event| event.kind == "level_up")
+        .filter_map(|event| Some(LevelSample { elapsed_ms: event.elapsed_ms, level: event.detail.as_deref()?.parse().ok()? }));
+    let split_levels = splits
+        .iter()
+        .filter_map(|split| Some(LevelSample { elapsed_ms: split.split_time_ms, level: split.character_level? }));
+    let mut samples: Vec<LevelSample> = level_ups.chain(split_levels).collect();
+    samples.sort_by_key(|sample| sample.elapsed_ms);
+    samples
+}
+
+/// Highest level reached by `elapsed_ms`
+pub fn level_at(samples: &[LevelSample], elapsed_ms: i64) -> Option<i32> {
+    samples
+        .iter()
+        .filter(|sample| sample.elapsed_ms <= elapsed_ms)
+        .map(|sample| sample.level)
+        .max()
+}
+
+/// Experience over a run from the snapshots that recorded it, in run-clock
+/// order
+pub fn experience_curve(snapshots: &[SnapshotSummary]) -> Vec<ExperiencePoint> {
+    let mut points: Vec<ExperiencePoint> = snapshots
+        .iter()
+        .filter_map(|snapshot| {
+            Some(ExperiencePoint {
+                elapsed_ms: snapshot.elapsed_time_ms,
+                level: snapshot.character_level,
+                experience: snapshot.experience?,
+            })
+        })
+        .collect();
+    points.sort_by_key(|point| point.elapsed_ms);
+    points
+}
+
+/// Compare a run's levels at `elapsed_ms` and at each breakpoint with its PB's
+pub fn build(
+    run_id: i64,
+    elapsed_ms: i64,
+    events: &[RunEvent],
+    splits: &[Split],
+    pb: Option<(i64, &[RunEvent], &[Split])>,
+) -> LevelPace {
+    let run_levels = samples(events, splits);
+    let pb_levels = pb.map(|(_, events, splits)| samples(events, splits)).unwrap_or_default();
+    let pb_splits = pb.map(|(_, _, splits)| splits).unwrap_or_default();
+
+    let level = level_at(&run_levels, elapsed_ms);
+    let pb_level = level_at(&pb_levels, elapsed_ms);
+    let lead = level.zip(pb_level).map(|(level, pb_level)| level - pb_level);
+    let pb_level_of = |name: &str| {
+        let split = pb_splits.iter().find(|split| split.breakpoint_name == name)?;
+        split.character_level.or_else(|| level_at(&pb_levels, split.split_time_ms))
+    };
+
+    let mut levels: Vec<SplitLevel> = splits
+        .iter()
+        .map(|split| SplitLevel {
+            breakpoint_name: split.breakpoint_name.clone(),
+            split_time_ms: Some(split.split_time_ms),
+            level: split.character_level.or_else(|| level_at(&run_levels, split.split_time_ms)),
+            pb_level: pb_level_of(&split.breakpoint_name),
+            expected_level: None,
+        })
+        .collect();
+    for pb_split in pb_splits {
+        if splits.iter().any(|split| split.breakpoint_name == pb_split.breakpoint_name) {
+            continue;
+        }
+        let pb_level = pb_level_of(&pb_split.breakpoint_name);
+        levels.push(SplitLevel {
+            breakpoint_name: pb_split.breakpoint_name.clone(),
+            split_time_ms: None,
+            level: None,
+            pb_level,
+            expected_level: pb_level.zip(lead).map(|(pb_level, lead)| (pb_level + lead).clamp(1, 100)),
+        });
+    }
+
+    let xp_per_hour = splits
+        .iter()
+        .rev()
+        .find_map(|split| Some((split.experience?, split.split_time_ms)))
+        .filter(|&(_, split_time_ms)| split_time_ms > 0)
+        .map(|(experience, split_time_ms)| experience as f64 / (split_time_ms as f64 / 3_600_000.0));
+
+    let zones = events
+        .iter()
+        .filter(|event| event.kind == "zone_enter")
+        .filter_map(|event| {
+            Some(ZoneLevel {
+                zone_name: event.detail.clone()?,
+                elapsed_ms: event.elapsed_ms,
+                level: level_at(&run_levels, event.elapsed_ms),
+            })
+        })
+        .collect();
+
+    LevelPace {
+        run_id,
+        elapsed_ms,
+        level,
+        pb_run_id: pb.map(|(pb_run_id, _, _)| pb_run_id),
+        pb_level,
+        pb_levels,
+        xp_per_hour,
+        splits: levels,
+        zones,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: &str, elapsed_ms: i64, detail: &str) -> RunEvent {
+        RunEvent {
+            id: 0,
+            run_id: 1,
+            kind: kind.to_string(),
+            elapsed_ms,
+            detail: Some(detail.to_string()),
+        }
+    }
+
+    fn split(name: &str, split_time_ms: i64, character_level: Option<i32>, experience: Option<i64>) -> Split {
+        Split {
+            id: 0,
+            run_id: 1,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: None,
+            split_time_ms,
+            delta_ms: None,
+            segment_time_ms: 0,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+            segment_town_time_ms: 0,
+            segment_hideout_time_ms: 0,
+            layout_variant: None,
+            layout_inferred: false,
+            experience,
+            character_level,
+            load_time_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_level_pace_against_pb() {
+        let events = vec![
+            event("zone_enter", 0, "The Twilight Strand"),
+            event("level_up", 60_000, "2"),
+            event("zone_enter", 90_000, "Lioneye's Watch"),
+            event("level_up", 200_000, "4"),
+        ];
+        // The snapshot at the split saw a level-up the log missed
+        let splits = vec![split("Hillock", 120_000, Some(3), Some(1_500))];
+        let pb_events = vec![event("level_up", 50_000, "2"), event("level_up", 150_000, "3")];
+        let pb_splits = vec![
+            split("Hillock", 100_000, None, None),
+            split("Merveil", 900_000, Some(12), None),
+        ];
+
+        let pace = build(1, 250_000, &events, &splits, Some((7, &pb_events, &pb_splits)));
+        assert_eq!((pace.level, pace.pb_level, pace.pb_run_id), (Some(4), Some(3), Some(7)));
+        assert_eq!(pace.xp_per_hour, Some(45_000.0));
+        assert_eq!(pace.pb_levels.len(), 3);
+
+        let levels: Vec<_> = pace
+            .splits
+            .iter()
+            .map(|s| (s.breakpoint_name.as_str(), s.split_time_ms, s.level, s.pb_level, s.expected_level))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                ("Hillock", Some(120_000), Some(3), Some(2), None),
+                // A level ahead of the PB now, so a level ahead at Merveil
+                ("Merveil", None, None, Some(12), Some(13)),
+            ]
+        );
+
+        let zones: Vec<(&str, Option<i32>)> = pace.zones.iter().map(|z| (z.zone_name.as_str(), z.level)).collect();
+        assert_eq!(zones, vec![("The Twilight Strand", None), ("Lioneye's Watch", Some(2))]);
+
+        let alone = build(1, 250_000, &events, &splits, None);
+        assert_eq!((alone.pb_level, alone.splits[0].pb_level), (None, None));
+    }
+
+    #[test]
+    fn test_experience_curve() {
+        let snapshot = |elapsed_time_ms: i64, character_level: i32, experience: Option<i64>| SnapshotSummary {
+            id: 0,
+            run_id: 1,
+            split_id: Some(0),
+            timestamp: String::new(),
+            elapsed_time_ms,
+            character_level,
+            experience,
+            has_pob_code: false,
+            is_final: false,
+        };
+        let snapshots = vec![snapshot(600_000, 12, Some(45_000)), snapshot(120_000, 3, Some(1_200)), snapshot(300_000, 8, None)];
+
+        let curve: Vec<_> = experience_curve(&snapshots).iter().map(|p| (p.elapsed_ms, p.level, p.experience)).collect();
+        // Snapshots without experience are left out
+        assert_eq!(curve, vec![(120_000, 3, 1_200), (600_000, 12, 45_000)]);
+    }
+}
@@ -1,13 +1,67 @@
+mod abandon;
+mod announcements;
 mod api_client;
+mod auto_export;
+mod auto_start;
+mod backtrack;
+mod breakpoint_engine;
+mod category_detect;
+mod certificate;
+mod changelog;
 mod commands;
+mod comparison;
 mod db;
+mod diagnostics;
+mod error;
+mod game;
+mod game_process;
+mod guide;
+mod image_cache;
+mod ladder;
+mod level_pace;
+mod livesplit;
+mod load_time;
+mod log_backfill;
 mod log_watcher;
+mod map_session;
+mod oauth;
+mod obs_server;
+mod pause;
+mod pob_builder;
+mod poeninja;
+mod privacy;
+mod quest_state;
+mod race_lobby;
+mod rate_limit;
+mod readiness;
+mod reference_import;
+mod ruleset;
+mod run_json;
+mod run_timeline;
+mod share_card;
+mod snapshot_analysis;
+mod snapshot_diff;
+mod snapshot_queue;
+mod splits_image;
+mod stats;
+mod status_server;
+mod table_export;
+mod twitch;
+mod updater;
+mod webhook;
+mod zone_time;
+
+#[cfg(test)]
+mod tests;
 
 use commands::*;
 use std::collections::HashMap;
 use std::sync::Arc;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{Emitter, Manager};
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tauri_plugin_autostart::MacosLauncher;
+use tauri_plugin_global_shortcut::ShortcutState;
 
 /// Shared state mapping shortcut strings to action names.
 /// Accessible from commands via `app.state::<HotkeyMap>()`.
@@ -26,10 +80,19 @@ pub fn run() {
     let app_handle_for_handler = app_handle.clone();
 
     tauri::Builder::default()
+        // Registered first so a second launch exits before it registers
+        // hotkeys or starts a log watcher; its arguments are handed to this
+        // instance, which comes to the front instead
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            focus_main_window(app);
+            let args: Vec<String> = args.into_iter().skip(1).collect();
+            let _ = app.emit_to("main", "second-instance", serde_json::json!({ "args": args, "cwd": cwd }));
+        }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, None))
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
                 .with_handler(move |_app, shortcut_ref, event| {
@@ -59,11 +122,45 @@ pub fn run() {
                 .app_data_dir()
                 .expect("Failed to get app data directory");
 
+            if let Err(e) = image_cache::init(app_data_dir.join("image_cache")) {
+                eprintln!("[image_cache] Failed to create cache directory: {}", e);
+            }
             db::init_db(app_data_dir).expect("Failed to initialize database");
+            if let Err(e) = changelog::record_startup() {
+                eprintln!("[changelog] Failed to record app version: {}", e);
+            }
 
             // Load settings (including hotkeys) and register shortcuts
             let settings = db::Settings::load().unwrap_or_default();
 
+            // Tray icon, so the app can live hidden while the watcher runs
+            setup_tray(app)?;
+
+            // The main window starts hidden (tauri.conf.json); show it unless
+            // the user asked to start minimized to the tray
+            if !settings.start_minimized {
+                focus_main_window(app.handle());
+            }
+
+            // Keep the OS autostart entry in line with the saved setting
+            if let Err(e) = commands::sync_launch_on_boot(app.handle(), settings.launch_on_boot) {
+                eprintln!("[startup] {}", e);
+            }
+
+            // Serve the phone status page if enabled
+            if let Ok(config) = db::Settings::get_status_server() {
+                if let Err(e) = commands::apply_status_server(&config) {
+                    eprintln!("[status] {}", e);
+                }
+            }
+
+            // Serve overlay data to OBS browser sources if enabled
+            if let Ok(config) = db::Settings::get_obs_server() {
+                if let Err(e) = commands::apply_obs_server(&config) {
+                    eprintln!("[obs] {}", e);
+                }
+            }
+
             // Start log watcher if configured
             if !settings.poe_log_path.is_empty() {
                 let path = std::path::PathBuf::from(&settings.poe_log_path);
@@ -76,38 +173,15 @@ pub fn run() {
                 }
             }
 
-            // Register hotkeys from settings (or defaults)
-            let hotkeys_to_register = vec![
-                (settings.hotkey_toggle_timer.clone(), "toggle-timer"),
-                (settings.hotkey_reset_timer.clone(), "reset-timer"),
-                (settings.hotkey_manual_snapshot.clone(), "manual-snapshot"),
-                (settings.hotkey_toggle_overlay.clone(), "toggle-overlay"),
-                (settings.hotkey_toggle_overlay_lock.clone(), "toggle-overlay-lock"),
-                (settings.hotkey_manual_split.clone(), "manual-split"),
-            ];
-
-            {
-                let mut map = hotkey_map.lock().expect("Failed to lock hotkey map");
-
-                // Unregister any leftover shortcuts from a previous instance
-                // (force-killing the app on Windows can leave registrations dangling)
-                let _ = app.global_shortcut().unregister_all();
+            // Follow the game client, which then starts and stops the watcher
+            commands::apply_follow_game(app.handle(), settings.follow_game_process);
 
-                for (shortcut_str, action) in &hotkeys_to_register {
-                    if let Ok(shortcut) = shortcut_str.parse::<Shortcut>() {
-                        match app.global_shortcut().register(shortcut.clone()) {
-                            Ok(_) => {
-                                eprintln!("[hotkeys] Registered global shortcut: {} -> {}", shortcut.to_string(), action);
-                            }
-                            Err(e) => {
-                                eprintln!("[hotkeys] Failed to register global shortcut {}: {}", shortcut_str, e);
-                            }
-                        }
-                        // Use canonical Shortcut::to_string() as key so it matches
-                        // the handler's shortcut_ref.to_string() lookup format.
-                        map.insert(shortcut.to_string(), action.to_string());
-                    }
-                }
+            // Register hotkeys from settings (or defaults). This also clears
+            // leftover shortcuts from a previous instance (force-killing the
+            // app on Windows can leave registrations dangling).
+            let checked = commands::check_hotkeys(&commands::HotkeySettings::from(&settings));
+            if let Err(e) = commands::register_hotkeys(app.handle(), &hotkey_map, checked) {
+                eprintln!("[hotkeys] {}", e);
             }
 
             // Store the hotkey map as managed state so commands can access it
@@ -127,41 +201,178 @@ pub fn run() {
             set_log_poll_fast,
             // Runs
             create_run,
+            get_category_defaults,
+            set_category_defaults,
+            delete_category_defaults,
             update_run_character,
+            set_run_category,
+            set_run_warmup,
+            get_run_review,
+            set_run_review,
             complete_run,
+            abandon_run,
             get_runs,
             get_run,
             delete_run,
             get_runs_filtered,
+            get_runs_paged,
             get_run_stats,
+            get_abandonment_stats,
+            get_reset_stats,
             get_split_stats,
+            get_split_trends,
+            get_time_loss_report,
+            get_town_time_report,
+            get_zone_time_stats,
+            get_zone_heatmap,
+            get_run_timeline,
+            get_level_pace,
+            get_experience_curve,
+            get_backtrack_report,
+            get_zone_times,
+            get_deaths,
+            get_comparison,
+            get_quest_state,
+            get_quest_objectives,
+            import_guide,
+            get_guide,
+            clear_guide,
+            get_current_guide_step,
+            set_guide_step,
             create_reference_run,
+            create_reference_run_from_text,
+            create_reference_run_from_file,
+            fetch_ladder,
+            create_reference_run_from_ladder,
+            fetch_ninja_builds,
+            create_reference_snapshot_from_ninja,
+            import_livesplit,
+            import_run_from_log,
+            parse_reference_splits,
+            parse_reference_file,
             // Splits
             add_split,
             get_splits,
             manual_split,
+            set_split_layout,
+            undo_last_split,
+            skip_split,
+            revert_provisional_golds,
+            start_breakpoint_engine,
+            set_breakpoint_engine_running,
+            pause_run,
+            resume_run,
+            reassign_run_character,
+            get_pauses,
+            stop_breakpoint_engine,
+            // Split notes
+            add_split_note,
+            get_split_notes,
+            delete_split_note,
+            // Run notes and tags
+            set_run_note,
+            add_run_tag,
+            remove_run_tag,
+            get_run_tags,
+            get_run_tag_names,
+            get_runs_by_tag,
+            // Breakpoint presets
+            get_breakpoint_presets,
+            create_breakpoint_preset,
+            rename_breakpoint_preset,
+            delete_breakpoint_preset,
+            get_preset_breakpoints,
+            add_breakpoint,
+            update_breakpoint,
+            remove_breakpoint,
+            reorder_breakpoints,
             // Snapshots
             create_snapshot,
             get_snapshots,
+            get_snapshot_summaries,
             get_snapshot,
+            diff_snapshots,
+            get_gem_timeline,
+            reprocess_snapshots,
+            set_snapshot_pob_code,
             capture_snapshot,
+            get_pending_snapshots,
             // Personal bests
             get_personal_bests,
+            get_personal_best,
+            recompute_personal_bests,
             // Gold splits
             get_gold_splits,
+            recompute_gold_splits,
+            get_sum_of_best,
+            get_best_possible_time,
+            // Map sessions
+            start_map_session,
+            stop_map_session,
+            get_current_map_session,
+            get_map_sessions,
+            delete_map_session,
             // API
             fetch_characters,
+            check_profile_privacy,
+            get_run_readiness,
             fetch_character_data,
             fetch_passive_tree,
+            // Path of Exile sign-in
+            poe_login,
+            get_poe_auth_status,
+            poe_logout,
             // PoB Export
+            generate_pob_code,
             upload_to_pobbin,
             // JSON Export
+            get_run_certificate,
+            verify_run_certificate,
             export_run_json,
+            import_run_json,
+            export_run_table,
+            export_runs_csv,
+            export_splits_csv,
+            export_run_livesplit,
+            // Image Export
+            render_splits_image,
+            generate_share_card,
+            // Maintenance
+            repair_database,
+            compact_database,
+            archive_runs_older_than,
+            get_archived_runs,
+            restore_archived_run,
+            export_diagnostics,
+            get_app_changelog_state,
+            check_for_update,
+            install_update_now,
+            // Status page
+            get_status_server,
+            set_status_server,
+            regenerate_status_token,
+            // OBS browser source server
+            get_obs_server,
+            set_obs_server,
+            // Twitch chat
+            send_twitch_test_message,
+            // Race lobby
+            host_race,
+            join_race,
+            leave_race,
+            get_race_status,
+            // Main window and tray
+            show_main_window,
+            hide_to_tray,
+            quit_app,
             // Image Proxy (CORS bypass)
             proxy_image,
+            get_image_cache_stats,
+            clear_image_cache,
             // Hotkeys
             get_hotkeys,
             update_hotkeys,
+            get_hotkey_registrations,
             // Overlay
             open_overlay,
             close_overlay,
@@ -173,20 +384,79 @@ pub fn run() {
             resize_overlay,
             set_overlay_always_on_top,
             reset_overlay_position,
+            get_overlay_layout,
+            set_overlay_layout,
+            set_overlay_size,
+            get_overlay_profiles,
+            create_overlay_profile,
+            update_overlay_profile,
+            delete_overlay_profile,
         ])
         .on_window_event(|window, event| {
-            // When the main window is closed, close the overlay and exit
-            if let tauri::WindowEvent::CloseRequested { .. } = event {
-                if window.label() == "main" {
-                    // Close the overlay window if it exists
-                    if let Some(overlay) = window.app_handle().get_webview_window("overlay") {
-                        let _ = overlay.close();
+            if window.label() != "main" {
+                return;
+            }
+            let settings = || db::Settings::load().unwrap_or_default();
+            match event {
+                // In background mode closing the main window only hides it:
+                // the log watcher, breakpoint engine and overlays keep the run
+                // going. Otherwise it closes the overlays and exits.
+                tauri::WindowEvent::CloseRequested { api, .. } => {
+                    if settings().background_mode {
+                        api.prevent_close();
+                        let _ = window.hide();
+                    } else {
+                        quit(window.app_handle());
                     }
-                    // Exit the process so it doesn't linger
-                    window.app_handle().exit(0);
                 }
+                // With minimize_to_tray, minimizing hides it in the tray
+                tauri::WindowEvent::Resized(_) if window.is_minimized().unwrap_or(false) && settings().minimize_to_tray => {
+                    let _ = window.hide();
+                }
+                _ => {}
             }
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// Create the tray icon with timer, overlay, Show and Quit entries. Left click
+/// restores the main window. The timer and overlay entries go through the
+/// `global-shortcut` event, so they act like their hotkeys.
+fn setup_tray(app: &tauri::App) -> tauri::Result<()> {
+    let toggle_timer = MenuItem::with_id(app, "toggle-timer", "Start / Pause Timer", true, None::<&str>)?;
+    let toggle_overlay = MenuItem::with_id(app, "toggle-overlay", "Toggle Overlay", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let show = MenuItem::with_id(app, "show", "Show POE Watcher", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&toggle_timer, &toggle_overlay, &separator, &show, &quit])?;
+
+    let mut builder = TrayIconBuilder::new()
+        .tooltip("POE Watcher")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            action @ ("toggle-timer" | "toggle-overlay") => {
+                let _ = app.emit("global-shortcut", action);
+            }
+            "show" => focus_main_window(app),
+            "quit" => quit(app),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                focus_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    Ok(())
+}
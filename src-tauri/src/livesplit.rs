@@ -0,0 +1,217 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use regex::Regex;
+use std::fmt::Write;
+
+use crate::db::{Run, Split};
+use crate::error::{AppError, AppResult};
+use crate::pob_builder::{escape_xml, unescape_xml};
+use crate::reference_import::parse_time;
+
+const GAME_NAME: &str = "Path of Exile";
+/// speedrun.com-style run variable carrying the class, so golds round-trip
+const CLASS_VARIABLE: &str = "Class";
+
+/// A segment of a LiveSplit splits file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LssSegment {
+    pub name: String,
+    /// Cumulative time in the Personal Best comparison
+    pub pb_split_ms: Option<i64>,
+    pub best_segment_ms: Option<i64>,
+}
+
+/// The parts of a `.lss` file that map onto runs, splits and gold splits
+#[derive(Debug, Clone, PartialEq)]
+pub struct LssRun {
+    pub category: Option<String>,
+    pub class: Option<String>,
+    pub segments: Vec<LssSegment>,
+}
+
+/// Render `run` as a LiveSplit splits file. Segments are the run's splits with
+/// the category PB run's split times (matched by key, then name) as the
+/// Personal Best comparison and `golds` (one per split) as Best Segments. The
+/// run itself is the file's only attempt.
+pub fn render(run: &Run, splits: &[Split], pb_splits: &[Split], golds: &[Option<i64>]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Run version=\"1.7.0\">\n");
+    xml.push_str("  <GameIcon />\n");
+    let _ = writeln!(xml, "  <GameName>{}</GameName>", GAME_NAME);
+    let _ = writeln!(xml, "  <CategoryName>{}</CategoryName>", escape_xml(&run.category));
+    xml.push_str("  <Metadata>\n    <Run id=\"\" />\n    <Platform usesEmulator=\"False\">PC</Platform>\n    <Region />\n");
+    let _ = writeln!(
+        xml,
+        "    <Variables>\n      <Variable name=\"{}\">{}</Variable>\n    </Variables>\n  </Metadata>",
+        CLASS_VARIABLE,
+        escape_xml(&run.class)
+    );
+    // A start countdown is a negative offset in LiveSplit
+    let _ = writeln!(xml, "  <Offset>{}</Offset>", format_time(-run.start_offset_ms));
+    xml.push_str("  <AttemptCount>1</AttemptCount>\n  <AttemptHistory>\n");
+    let _ = write!(xml, "    <Attempt id=\"1\" started=\"{}\" isStartedSynced=\"True\"", attempt_date(&run.started_at));
+    if let Some(ended_at) = &run.ended_at {
+        let _ = write!(xml, " ended=\"{}\" isEndedSynced=\"True\"", attempt_date(ended_at));
+    }
+    match run.total_time_ms.filter(|_| run.is_completed) {
+        Some(total) => {
+            let _ = writeln!(xml, ">\n      <RealTime>{}</RealTime>\n    </Attempt>", format_time(total));
+        }
+        None => xml.push_str(" />\n"),
+    }
+    xml.push_str("  </AttemptHistory>\n  <Segments>\n");
+
+    for (split, gold) in splits.iter().zip(golds) {
+        let pb = pb_splits.iter().find(|pb| match (&pb.breakpoint_key, &split.breakpoint_key) {
+            (Some(a), Some(b)) => a == b,
+            _ => pb.breakpoint_name == split.breakpoint_name,
+        });
+        // The attempt is part of the history, so its segment can't beat the best
+        let best = gold.map_or(split.segment_time_ms, |gold| gold.min(split.segment_time_ms));
+
+        let _ = writeln!(xml, "    <Segment>\n      <Name>{}</Name>\n      <Icon />", escape_xml(&split.breakpoint_name));
+        xml.push_str("      <SplitTimes>\n");
+        match pb {
+            Some(pb) => {
+                let _ = writeln!(
+                    xml,
+                    "        <SplitTime name=\"Personal Best\">\n          <RealTime>{}</RealTime>\n        </SplitTime>",
+                    format_time(pb.split_time_ms)
+                );
+            }
+            None => xml.push_str("        <SplitTime name=\"Personal Best\" />\n"),
+        }
+        xml.push_str("      </SplitTimes>\n");
+        let _ = writeln!(xml, "      <BestSegmentTime>\n        <RealTime>{}</RealTime>\n      </BestSegmentTime>", format_time(best));
+        let _ = writeln!(
+            xml,
+            "      <SegmentHistory>\n        <Time id=\"1\">\n          <RealTime>{}</RealTime>\n        </Time>\n      </SegmentHistory>",
+            format_time(split.segment_time_ms)
+        );
+        xml.push_str("    </Segment>\n");
+    }
+
+    xml.push_str("  </Segments>\n  <AutoSplitterSettings />\n</Run>\n");
+    xml
+}
+
+/// Read the category, class variable and segments (Personal Best split times
+/// and Best Segments, real time falling back to game time) of a `.lss` file
+pub fn parse(xml: &str) -> AppResult<LssRun> {
+    lazy_static::lazy_static! {
+        static ref SEGMENT: Regex = Regex::new(r"(?s)<Segment>(.*?)</Segment>").unwrap();
+        static ref NAME: Regex = Regex::new(r"(?s)<Name>(.*?)</Name>").unwrap();
+        static ref PB_TIME: Regex = Regex::new(
+            r#"(?s)<SplitTime name="Personal Best">(.*?)</SplitTime>"#
+        ).unwrap();
+        static ref BEST_SEGMENT: Regex = Regex::new(r"(?s)<BestSegmentTime>(.*?)</BestSegmentTime>").unwrap();
+        static ref REAL_TIME: Regex = Regex::new(r"<RealTime>([^<]+)</RealTime>").unwrap();
+        static ref GAME_TIME: Regex = Regex::new(r"<GameTime>([^<]+)</GameTime>").unwrap();
+        static ref CATEGORY: Regex = Regex::new(r"(?s)<CategoryName>(.*?)</CategoryName>").unwrap();
+        static ref CLASS: Regex = Regex::new(r#"(?s)<Variable name="Class">(.*?)</Variable>"#).unwrap();
+    }
+
+    if !xml.contains("<Segment") {
+        return Err(AppError::InvalidInput("Not a LiveSplit splits file".to_string()));
+    }
+
+    let time = |body: &str| {
+        REAL_TIME
+            .captures(body)
+            .or_else(|| GAME_TIME.captures(body))
+            .and_then(|time| parse_time(&time[1]))
+    };
+    let text = |re: &Regex| {
+        re.captures(xml)
+            .map(|c| unescape_xml(c[1].trim()))
+            .filter(|text| !text.is_empty())
+    };
+
+    let segments = SEGMENT
+        .captures_iter(xml)
+        .filter_map(|segment| {
+            let body = &segment[1];
+            let name = unescape_xml(NAME.captures(body)?[1].trim());
+            Some(LssSegment {
+                name,
+                pb_split_ms: PB_TIME.captures(body).and_then(|pb| time(&pb[1])),
+                best_segment_ms: BEST_SEGMENT.captures(body).and_then(|best| time(&best[1])),
+            })
+        })
+        .filter(|segment| !segment.name.is_empty())
+        .collect();
+
+    Ok(LssRun {
+        category: text(&CATEGORY),
+        class: text(&CLASS),
+        segments,
+    })
+}
+
+/// LiveSplit time span: `[-]hh:mm:ss.fffffff`
+fn format_time(ms: i64) -> String {
+    let sign = if ms < 0 { "-" } else { "" };
+    let ms = ms.abs();
+    format!(
+        "{}{:02}:{:02}:{:02}.{:03}0000",
+        sign,
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Attempt dates are UTC `MM/dd/yyyy HH:mm:ss`
+fn attempt_date(timestamp: &str) -> String {
+    let parsed = DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc).naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S"));
+    match parsed {
+        Ok(t) => t.format("%m/%d/%Y %H:%M:%S").to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(62_340), "00:01:02.3400000");
+        assert_eq!(format_time(3_723_004), "01:02:03.0040000");
+        assert_eq!(format_time(-5_000), "-00:00:05.0000000");
+        assert_eq!(attempt_date("2024-01-15T12:00:00Z"), "01/15/2024 12:00:00");
+        assert_eq!(attempt_date("2024-01-15 12:00:00"), "01/15/2024 12:00:00");
+    }
+
+    #[test]
+    fn test_parse_best_segments_and_class() {
+        let xml = r#"<Run version="1.7.0">
+  <CategoryName>Act 10 &amp; Lab</CategoryName>
+  <Metadata><Variables><Variable name="Class">Witch</Variable></Variables></Metadata>
+  <Segments>
+    <Segment>
+      <Name>The Coast</Name>
+      <SplitTimes><SplitTime name="Personal Best"><RealTime>00:01:02.3400000</RealTime></SplitTime></SplitTimes>
+      <BestSegmentTime><GameTime>00:00:58.0000000</GameTime></BestSegmentTime>
+    </Segment>
+    <Segment>
+      <Name>Never Finished</Name>
+      <SplitTimes><SplitTime name="Personal Best" /></SplitTimes>
+      <BestSegmentTime />
+    </Segment>
+  </Segments>
+</Run>"#;
+        let parsed = parse(xml).unwrap();
+        assert_eq!(parsed.category.as_deref(), Some("Act 10 & Lab"));
+        assert_eq!(parsed.class.as_deref(), Some("Witch"));
+        assert_eq!(
+            parsed.segments,
+            vec![
+                LssSegment { name: "The Coast".to_string(), pb_split_ms: Some(62_340), best_segment_ms: Some(58_000) },
+                LssSegment { name: "Never Finished".to_string(), pb_split_ms: None, best_segment_ms: None },
+            ]
+        );
+        assert!(parse("<Layout></Layout>").is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::breakpoint_engine;
+use crate::db::Run;
+use crate::log_watcher::LogEvent;
+
+/// A gap longer than this wasn't a loading screen (character select after a
+/// disconnect, alt-tab on the loading screen)
+const MAX_LOAD: Duration = Duration::from_secs(60);
+
+/// Times loading screens: from "Connecting to instance server" to the zone
+/// entry that follows it
+#[derive(Debug, Default)]
+pub struct LoadClock {
+    connecting_since: Option<Instant>,
+}
+
+impl LoadClock {
+    /// Feed a log event, returning the length of a loading screen it ended
+    pub fn observe(&mut self, event: &LogEvent, now: Instant) -> Option<i64> {
+        match event {
+            LogEvent::Login { .. } => {
+                self.connecting_since = Some(now);
+                None
+            }
+            LogEvent::ZoneEnter { .. } => {
+                let load = now.saturating_duration_since(self.connecting_since.take()?);
+                (load <= MAX_LOAD).then_some(load.as_millis() as i64)
+            }
+            LogEvent::Disconnect { .. } | LogEvent::LogRotated { .. } => {
+                self.connecting_since = None;
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+static CLOCK: Mutex<Option<LoadClock>> = Mutex::new(None);
+
+/// Add loading screens to the run being timed. Loads while its timer is
+/// paused or stopped aren't on the run clock, so they're left out.
+pub fn handle_event(event: &LogEvent) {
+    let load_ms = {
+        let mut guard = CLOCK.lock().unwrap_or_else(|e| e.into_inner());
+        guard.get_or_insert_with(LoadClock::default).observe(event, Instant::now())
+    };
+    let Some(load_ms) = load_ms else {
+        return;
+    };
+    if let Some((run_id, true, _)) = breakpoint_engine::active_clock() {
+        if let Err(e) = Run::add_load_time(run_id, load_ms) {
+            eprintln!("[load_time] Failed to save load time: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connect() -> LogEvent {
+        LogEvent::Login { timestamp: String::new() }
+    }
+
+    fn enter(zone_name: &str) -> LogEvent {
+        LogEvent::ZoneEnter { timestamp: String::new(), zone_name: zone_name.to_string() }
+    }
+
+    #[test]
+    fn test_loading_screens_timed() {
+        let start = Instant::now();
+        let at = |secs: f64| start + Duration::from_secs_f64(secs);
+        let mut clock = LoadClock::default();
+
+        // A zone entry without a connect before it isn't a load
+        assert_eq!(clock.observe(&enter("The Twilight Strand"), at(0.0)), None);
+
+        assert_eq!(clock.observe(&connect(), at(10.0)), None);
+        assert_eq!(clock.observe(&enter("The Coast"), at(13.5)), Some(3_500));
+        // Each connect ends once
+        assert_eq!(clock.observe(&enter("The Coast"), at(14.0)), None);
+
+        // Disconnects and long gaps aren't loading screens
+        clock.observe(&connect(), at(20.0));
+        clock.observe(&LogEvent::Disconnect { timestamp: String::new() }, at(21.0));
+        assert_eq!(clock.observe(&enter("The Mud Flats"), at(25.0)), None);
+        clock.observe(&connect(), at(30.0));
+        assert_eq!(clock.observe(&enter("The Mud Flats"), at(200.0)), None);
+    }
+}
@@ -0,0 +1,193 @@
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+use crate::breakpoint_engine::{BreakpointEngine, EngineBreakpoint};
+use crate::game::Game;
+use crate::load_time::LoadClock;
+use crate::log_watcher::{LogEvent, LogWatcher};
+use crate::quest_state::{QuestTracker, QuestUpdate};
+use crate::zone_time::ZoneClock;
+
+/// A breakpoint reached while replaying a log, timed from the window's start
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackfilledSplit {
+    pub breakpoint_name: String,
+    pub breakpoint_type: String,
+    pub breakpoint_key: Option<String>,
+    pub split_time_ms: i64,
+    pub town_time_ms: i64,
+    pub hideout_time_ms: i64,
+    pub load_time_ms: i64,
+}
+
+/// What a stretch of Client.txt says about the run played in it
+#[derive(Debug, Default)]
+pub struct Backfill {
+    pub splits: Vec<BackfilledSplit>,
+    /// First character to level up in the window, and its class as logged
+    pub character: Option<(String, String)>,
+    /// Last version line before the window's end
+    pub game_version: Option<String>,
+    /// Time of the last split when every breakpoint was reached, otherwise of
+    /// the last event in the window
+    pub total_time_ms: i64,
+    pub load_time_ms: i64,
+    pub finished: bool,
+}
+
+/// A time as the log writes it (`2024/01/15 12:34:56`) or as a date-time
+/// input gives it (`2024-01-15T12:34`, seconds optional)
+pub fn parse_time(text: &str) -> Option<NaiveDateTime> {
+    ["%Y/%m/%d %H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text.trim(), format).ok())
+}
+
+/// Replay the log from `reader` between `start` and `end` (local times, as
+/// logged) through the breakpoint engine, as if the run had been timed live
+/// from `start`. Town, hideout and loading screen times are measured the same
+/// way the live trackers measure them.
+pub fn backfill(
+    reader: impl BufRead,
+    game: Game,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    breakpoints: Vec<EngineBreakpoint>,
+) -> Result<Backfill> {
+    // The trackers run on Instants, so log times are laid out from an arbitrary one
+    let base = Instant::now();
+    let mut engine = BreakpointEngine::new(0, breakpoints, 0, base);
+    let mut zones = ZoneClock::new(base);
+    zones.start_run(0, base);
+    let mut loads = LoadClock::default();
+    let mut quests = QuestTracker::new(1, Vec::new());
+    let mut completed_quests: Vec<String> = Vec::new();
+    let mut completed: Vec<String> = Vec::new();
+    let mut backfill = Backfill::default();
+
+    LogWatcher::replay(reader, game, |event| {
+        let Some(at) = parse_time(event.timestamp()) else {
+            return true;
+        };
+        if at > end {
+            return false;
+        }
+        if let LogEvent::GameVersion { version, .. } = &event {
+            backfill.game_version = Some(version.clone());
+        }
+        if at < start {
+            return true;
+        }
+        let elapsed_ms = (at - start).num_milliseconds();
+        let now = base + Duration::from_millis(elapsed_ms as u64);
+        backfill.total_time_ms = elapsed_ms;
+
+        match &event {
+            LogEvent::ZoneEnter { zone_name, .. } => zones.enter_zone(zone_name, now),
+            LogEvent::LevelUp { character_name, character_class, .. } if backfill.character.is_none() => {
+                backfill.character = Some((character_name.clone(), character_class.clone()));
+            }
+            _ => {}
+        }
+        if let Some(load_ms) = loads.observe(&event, now) {
+            backfill.load_time_ms += load_ms;
+        }
+        for update in quests.apply(&event) {
+            if let QuestUpdate::Completed(objective) = update {
+                completed_quests.push(objective.to_string());
+            }
+        }
+
+        engine.observe(&event);
+        let Some(breakpoint) = engine.matching(&event, &completed, &completed_quests) else {
+            return true;
+        };
+        let (town_time_ms, hideout_time_ms) = zones.totals(0, now).unwrap_or_default();
+        backfill.splits.push(BackfilledSplit {
+            breakpoint_name: breakpoint.name.clone(),
+            breakpoint_type: breakpoint.breakpoint_type.clone(),
+            breakpoint_key: breakpoint.key.clone(),
+            split_time_ms: elapsed_ms,
+            town_time_ms,
+            hideout_time_ms,
+            load_time_ms: backfill.load_time_ms,
+        });
+        completed.push(breakpoint.name.clone());
+        backfill.finished = engine.is_finished(&completed);
+        !backfill.finished
+    })?;
+
+    Ok(backfill)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breakpoint_engine::BreakpointTrigger;
+
+    fn zone_breakpoint(name: &str) -> EngineBreakpoint {
+        EngineBreakpoint {
+            name: name.to_string(),
+            breakpoint_type: "zone".to_string(),
+            trigger: BreakpointTrigger {
+                kind: "zone".to_string(),
+                zone_name: Some(name.to_string()),
+                act: Some(1),
+                level: None,
+                penalty: None,
+                quest: None,
+                rule: None,
+            },
+            key: None,
+            capture_snapshot: false,
+        }
+    }
+
+    fn line(time: &str, message: &str) -> String {
+        format!("2024/01/15 {} 12345678 abc [INFO Client 1234] : {}\n", time, message)
+    }
+
+    #[test]
+    fn test_backfill_window() {
+        let log = [
+            line("11:00:00", "You have entered The Coast."),
+            line("12:00:00", "You have entered The Twilight Strand."),
+            line("12:00:30", "RaceChar (Witch) is now level 2"),
+            line("12:01:00", "You have entered Lioneye's Watch."),
+            line("12:01:30", "Connecting to instance server at 127.0.0.1:6112"),
+            line("12:01:33", "You have entered The Coast."),
+            line("12:04:00", "You have entered The Mud Flats."),
+            line("12:09:00", "You have entered The Ledge."),
+        ]
+        .concat();
+        let at = |time: &str| parse_time(&format!("2024-01-15T{}", time)).unwrap();
+        let breakpoints = vec![zone_breakpoint("The Coast"), zone_breakpoint("The Mud Flats")];
+
+        let run = backfill(log.as_bytes(), Game::Poe1, at("12:00"), at("13:00"), breakpoints.clone()).unwrap();
+        assert_eq!(run.character, Some(("RaceChar".to_string(), "Witch".to_string())));
+        let times: Vec<(&str, i64)> =
+            run.splits.iter().map(|s| (s.breakpoint_name.as_str(), s.split_time_ms)).collect();
+        // The Coast before the window isn't the run's
+        assert_eq!(times, [("The Coast", 93_000), ("The Mud Flats", 240_000)]);
+        assert_eq!((run.splits[0].town_time_ms, run.splits[0].load_time_ms), (33_000, 3_000));
+        assert!(run.finished);
+        assert_eq!(run.total_time_ms, 240_000);
+
+        // A window that ends early leaves the run unfinished at its last event
+        let partial = backfill(log.as_bytes(), Game::Poe1, at("12:00"), at("12:03"), breakpoints).unwrap();
+        assert_eq!(partial.splits.len(), 1);
+        assert!(!partial.finished);
+        assert_eq!(partial.total_time_ms, 93_000);
+    }
+
+    #[test]
+    fn test_parse_time_formats() {
+        let expected = parse_time("2024/01/15 12:34:00");
+        assert!(expected.is_some());
+        assert_eq!(parse_time("2024-01-15T12:34"), expected);
+        assert_eq!(parse_time(" 2024-01-15 12:34:00 "), expected);
+        assert_eq!(parse_time("yesterday"), None);
+    }
+}
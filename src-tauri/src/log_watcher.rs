@@ -3,9 +3,9 @@ use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::fs::File;
+use std::fs::{File, Metadata};
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -13,6 +13,9 @@ use std::thread;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 
+use crate::game::Game;
+use crate::{auto_start, breakpoint_engine, guide, load_time, map_session, quest_state, zone_time};
+
 /// Events parsed from Client.txt
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "event_type", rename_all = "snake_case")]
@@ -37,46 +40,396 @@ pub enum LogEvent {
     Login {
         timestamp: String,
     },
+    /// AFK mode switched on (`/afk`, or idle long enough) or off
+    Afk {
+        timestamp: String,
+        enabled: bool,
+    },
+    /// Lost the game server, or went back to the login server (logout)
+    Disconnect {
+        timestamp: String,
+    },
+    /// The client loaded an area instance; logged before its zone entry. The
+    /// seed tells instances of the same area apart.
+    AreaGenerated {
+        timestamp: String,
+        area_level: u32,
+        area_id: String,
+        seed: u64,
+    },
     KitavaAffliction {
         timestamp: String,
         penalty: i32,
     },
+    GameVersion {
+        timestamp: String,
+        version: String,
+    },
+    /// Dialogue from a quest NPC (see `QUEST_NPCS`)
+    NpcDialogue {
+        timestamp: String,
+        npc: String,
+        text: String,
+    },
+    /// An act was finished: the next act's town was reached, or Kitava was
+    /// killed for acts 5 and 10 (derived by `CampaignTracker`)
+    ActComplete {
+        timestamp: String,
+        act: i32,
+    },
+    /// Entered the Aspirants' Plaza at the start of a labyrinth
+    LabStart {
+        timestamp: String,
+    },
+    /// Izaro's death line at the end of a labyrinth
+    LabComplete {
+        timestamp: String,
+    },
+    /// Client.txt was truncated or replaced while watched (patch days, log
+    /// rotation); reading restarted at the top of the new contents. The
+    /// timestamp is local time when it was noticed.
+    LogRotated {
+        timestamp: String,
+        reason: RotationReason,
+    },
+}
+
+impl LogEvent {
+    /// When the line was logged (`2024/01/15 12:34:56`, local time)
+    pub fn timestamp(&self) -> &str {
+        match self {
+            LogEvent::ZoneEnter { timestamp, .. }
+            | LogEvent::LevelUp { timestamp, .. }
+            | LogEvent::Death { timestamp, .. }
+            | LogEvent::InstanceDetails { timestamp }
+            | LogEvent::Login { timestamp }
+            | LogEvent::Afk { timestamp, .. }
+            | LogEvent::Disconnect { timestamp }
+            | LogEvent::AreaGenerated { timestamp, .. }
+            | LogEvent::KitavaAffliction { timestamp, .. }
+            | LogEvent::GameVersion { timestamp, .. }
+            | LogEvent::NpcDialogue { timestamp, .. }
+            | LogEvent::ActComplete { timestamp, .. }
+            | LogEvent::LabStart { timestamp }
+            | LogEvent::LabComplete { timestamp }
+            | LogEvent::LogRotated { timestamp, .. } => timestamp,
+        }
+    }
+}
+
+/// How Client.txt changed under the watcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationReason {
+    /// Shorter than the position already read
+    Truncated,
+    /// A different file now sits at the log path
+    Replaced,
+}
+
+/// NPCs whose dialogue is parsed into `NpcDialogue` events (for quest tracking).
+/// Player chat uses the same "Name: text" shape, so only these names are matched.
+const QUEST_NPCS: &[&str] = &["Izaro", "Alira", "Kraityn", "Oak", "Eramir"];
+
+/// Town of each Path of Exile act, in order; the first visit to the next act's
+/// town completes the current act (see `Game::act_towns`)
+pub(crate) const ACT_TOWNS: &[&str] = &[
+    "Lioneye's Watch",
+    "The Forest Encampment",
+    "The Sarn Encampment",
+    "Highgate",
+    "Overseer's Tower",
+    "Lioneye's Watch",
+    "The Bridge Encampment",
+    "The Sarn Encampment",
+    "Highgate",
+    "Oriath Docks",
+];
+
+/// Lines Izaro says when he dies at the end of a labyrinth
+pub(crate) const IZARO_DEATH_LINES: &[&str] = &[
+    "Triumphant at last!",
+    "I die for the Empire!",
+    "Delight in your gilded dungeon, ascendant.",
+    "Your destination is more dangerous than the journey, ascendant.",
+    "You are free!",
+    "The trap of tyranny is inescapable.",
+];
+
+/// First zone of every labyrinth
+const LAB_ENTRANCE: &str = "Aspirants' Plaza";
+
+/// Follows campaign progress across parsed events to derive act and labyrinth
+/// events, which depend on more than the line they come from
+#[derive(Debug, Default)]
+struct CampaignTracker {
+    /// Current act, once a town or the campaign start has placed it
+    act: Option<i32>,
+    game: Game,
+}
+
+impl CampaignTracker {
+    fn observe(&mut self, event: &LogEvent) -> Option<LogEvent> {
+        match event {
+            LogEvent::ZoneEnter { timestamp, zone_name } => {
+                if zone_name == LAB_ENTRANCE {
+                    return Some(LogEvent::LabStart { timestamp: timestamp.clone() });
+                }
+                // PoE 1's campaign start is also act 6's first zone
+                if zone_name == self.game.campaign_start() && self.act != Some(6) {
+                    self.act = Some(1);
+                    return None;
+                }
+                let towns = self.game.act_towns();
+                let next_town = self.act.and_then(|act| towns.get(act as usize).copied());
+                if next_town == Some(zone_name.as_str()) {
+                    return self.complete(timestamp, self.act.unwrap_or(1));
+                }
+                // Towns that appear once in the campaign place an unknown or drifted act
+                let mut acts = towns.iter().enumerate().filter(|(_, town)| **town == zone_name.as_str());
+                if let (Some((index, _)), None) = (acts.next(), acts.next()) {
+                    self.act = Some(index as i32 + 1);
+                }
+                None
+            }
+            LogEvent::KitavaAffliction { timestamp, penalty } => match penalty {
+                30 => self.complete(timestamp, 5),
+                60 => self.complete(timestamp, 10),
+                _ => None,
+            },
+            LogEvent::NpcDialogue { timestamp, npc, text }
+                if npc == "Izaro" && IZARO_DEATH_LINES.contains(&text.as_str()) =>
+            {
+                Some(LogEvent::LabComplete { timestamp: timestamp.clone() })
+            }
+            _ => None,
+        }
+    }
+
+    fn complete(&mut self, timestamp: &str, act: i32) -> Option<LogEvent> {
+        self.act = Some(act + 1);
+        Some(LogEvent::ActComplete { timestamp: timestamp.to_string(), act })
+    }
+}
+
+/// How far back from the end of Client.txt to look for the game version when
+/// the watcher starts after the client (the version is only logged at launch)
+const VERSION_SCAN_BYTES: u64 = 4 * 1024 * 1024;
+
+/// How far the watcher has read, and which file that position belongs to
+#[derive(Debug, Default)]
+struct LogCursor {
+    position: u64,
+    file_id: Option<u64>,
+}
+
+/// Poll interval while a split may be imminent
+const FAST_POLL_MS: u64 = 10;
+/// Poll interval otherwise
+const SLOW_POLL_MS: u64 = 100;
+/// A loading screen that hasn't ended by now was a disconnect or alt-tab
+const LOADING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Why the watcher is polling fast
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PollReason {
+    /// Between "Got Instance Details" and the zone entry that follows
+    Loading,
+    /// The run's next breakpoint fires inside a zone (Kitava's affliction)
+    Breakpoint,
+    /// Turned on with `set_log_poll_fast`
+    Manual,
+}
+
+/// Payload of the `poll-mode-changed` event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PollMode {
+    pub fast: bool,
+    pub interval_ms: u64,
+    pub reason: Option<PollReason>,
+}
+
+impl PollMode {
+    fn new(reason: Option<PollReason>) -> Self {
+        PollMode {
+            fast: reason.is_some(),
+            interval_ms: if reason.is_some() { FAST_POLL_MS } else { SLOW_POLL_MS },
+            reason,
+        }
+    }
+}
+
+impl Default for PollMode {
+    fn default() -> Self {
+        PollMode::new(None)
+    }
+}
+
+/// Picks the poll interval from what the log says the character is doing
+#[derive(Debug, Default)]
+struct PollPlanner {
+    loading_since: Option<Instant>,
+}
+
+impl PollPlanner {
+    fn observe(&mut self, event: &LogEvent, now: Instant) {
+        match event {
+            LogEvent::InstanceDetails { .. } => self.loading_since = Some(now),
+            LogEvent::ZoneEnter { .. } | LogEvent::LogRotated { .. } => self.loading_since = None,
+            _ => {}
+        }
+    }
+
+    fn mode(&self, manual: bool, near_breakpoint: bool, now: Instant) -> PollMode {
+        let loading = self
+            .loading_since
+            .is_some_and(|since| now.saturating_duration_since(since) < LOADING_TIMEOUT);
+        let reason = if manual {
+            Some(PollReason::Manual)
+        } else if loading {
+            Some(PollReason::Loading)
+        } else if near_breakpoint {
+            Some(PollReason::Breakpoint)
+        } else {
+            None
+        };
+        PollMode::new(reason)
+    }
+}
+
+/// The watch loop's poll interval, the manual override from the `LogWatcher`,
+/// and who to tell when the interval changes
+struct Polling<M> {
+    manual: Arc<AtomicBool>,
+    current: PollMode,
+    on_change: M,
+}
+
+impl<M: Fn(PollMode)> Polling<M> {
+    fn set(&mut self, mode: PollMode) {
+        if mode != self.current {
+            self.current = mode;
+            (self.on_change)(mode);
+        }
+    }
+}
+
+/// Identity of the file behind the log path: the inode on Unix, the creation
+/// time elsewhere
+fn file_id(metadata: &Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(metadata.ino())
+    }
+    #[cfg(not(unix))]
+    {
+        metadata
+            .created()
+            .ok()
+            .and_then(|created| created.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|since| since.as_nanos() as u64)
+    }
 }
 
 /// Log watcher state
 pub struct LogWatcher {
     log_path: PathBuf,
-    file_position: Arc<Mutex<u64>>,
+    /// Client whose line formats and towns to expect
+    game: Game,
+    cursor: Arc<Mutex<LogCursor>>,
     watcher: Option<RecommendedWatcher>,
     stop_tx: Option<Sender<()>>,
     fast_polling: Arc<AtomicBool>,
+    game_version: Arc<Mutex<Option<String>>>,
 }
 
 impl LogWatcher {
-    /// Create a new log watcher for the given path
+    /// Create a new log watcher for the given path, matched case-insensitively
+    /// if it doesn't exist as given
     pub fn new(log_path: PathBuf) -> Self {
         LogWatcher {
-            log_path,
-            file_position: Arc::new(Mutex::new(0)),
+            log_path: resolve_path_case(&log_path),
+            game: Game::default(),
+            cursor: Arc::new(Mutex::new(LogCursor::default())),
             watcher: None,
             stop_tx: None,
             fast_polling: Arc::new(AtomicBool::new(false)),
+            game_version: Arc::new(Mutex::new(None)),
         }
     }
 
-    /// Enable or disable fast polling mode (10ms instead of 100ms)
+    /// Read the log as `game`'s client writes it (PoE 1 by default)
+    pub fn with_game(self, game: Game) -> Self {
+        LogWatcher { game, ..self }
+    }
+
+    /// Most recent game version seen in the log, if any
+    pub fn game_version(&self) -> Option<String> {
+        self.game_version.lock().ok().and_then(|v| v.clone())
+    }
+
+    /// Force fast polling on, or hand the interval back to the watcher. It
+    /// polls fast by itself during loading screens and before in-zone splits.
     pub fn set_fast_polling(&self, enabled: bool) {
         self.fast_polling.store(enabled, Ordering::Relaxed);
     }
 
-    /// Start watching the log file
+    /// Start watching the log file, emitting parsed events to the frontend
     pub fn start(&mut self, app_handle: AppHandle) -> Result<()> {
+        let mode_handle = app_handle.clone();
+        self.start_watching(
+            move |event| {
+                if let LogEvent::ZoneEnter { zone_name, .. } = &event {
+                    zone_time::enter_zone(zone_name);
+                }
+                // Before the engine, so a zone split includes the load into it
+                load_time::handle_event(&event);
+                if let LogEvent::LogRotated { .. } = &event {
+                    let _ = app_handle.emit("log-rotated", &event);
+                    return;
+                }
+                quest_state::handle_event(&event);
+                let _ = app_handle.emit("log-event", &event);
+                breakpoint_engine::handle_event(&app_handle, &event);
+                auto_start::handle_event(&app_handle, &event);
+                map_session::handle_event(&app_handle, &event);
+                guide::handle_event(&app_handle, &event);
+            },
+            move |mode| {
+                let _ = mode_handle.emit("poll-mode-changed", mode);
+            },
+        )
+    }
+
+    /// Start watching the log file, passing each new (deduplicated) event to `on_event`
+    pub fn start_with_handler<F>(&mut self, on_event: F) -> Result<()>
+    where
+        F: Fn(LogEvent) + Send + 'static,
+    {
+        self.start_watching(on_event, |_| {})
+    }
+
+    fn start_watching<F, M>(&mut self, on_event: F, on_poll_mode: M) -> Result<()>
+    where
+        F: Fn(LogEvent) + Send + 'static,
+        M: Fn(PollMode) + Send + 'static,
+    {
         let log_path = self.log_path.clone();
-        let file_position = self.file_position.clone();
+        let cursor = self.cursor.clone();
 
         // Initialize position to end of file
         if let Ok(metadata) = std::fs::metadata(&log_path) {
-            *file_position.lock().unwrap() = metadata.len();
+            *cursor.lock().unwrap() = LogCursor {
+                position: metadata.len(),
+                file_id: file_id(&metadata),
+            };
+        }
+
+        // Pick up the version of a client that was already running
+        if let Some(version) = Self::find_last_game_version(&log_path, self.game) {
+            *self.game_version.lock().unwrap() = Some(version);
         }
 
         let (stop_tx, stop_rx) = channel();
@@ -104,9 +457,15 @@ impl LogWatcher {
 
         // Spawn thread to handle file changes
         let log_path_clone = log_path.clone();
-        let fast_polling = self.fast_polling.clone();
+        let polling = Polling {
+            manual: self.fast_polling.clone(),
+            current: PollMode::default(),
+            on_change: on_poll_mode,
+        };
+        let game_version = self.game_version.clone();
+        let game = self.game;
         thread::spawn(move || {
-            Self::watch_loop(log_path_clone, file_position, rx, stop_rx, app_handle, fast_polling);
+            Self::watch_loop(log_path_clone, game, cursor, rx, stop_rx, on_event, polling, game_version);
         });
 
         Ok(())
@@ -121,16 +480,21 @@ impl LogWatcher {
     }
 
     /// Main watch loop - uses active polling for reliable detection
+    #[allow(clippy::too_many_arguments)]
     fn watch_loop(
         log_path: PathBuf,
-        file_position: Arc<Mutex<u64>>,
+        game: Game,
+        cursor: Arc<Mutex<LogCursor>>,
         _rx: Receiver<notify::Event>,
         stop_rx: Receiver<()>,
-        app_handle: AppHandle,
-        fast_polling: Arc<AtomicBool>,
+        on_event: impl Fn(LogEvent),
+        mut polling: Polling<impl Fn(PollMode)>,
+        game_version: Arc<Mutex<Option<String>>>,
     ) {
         // Deduplication: track recent events to prevent duplicates
         let mut recent_events: HashSet<String> = HashSet::new();
+        let mut campaign = CampaignTracker { act: None, game };
+        let mut planner = PollPlanner::default();
         let mut last_cleanup = Instant::now();
 
         loop {
@@ -146,25 +510,43 @@ impl LogWatcher {
             }
 
             // Actively poll the file every 100ms for new content
-            if let Ok(events) = Self::read_new_lines(&log_path, &file_position) {
+            if let Ok(events) = Self::read_new_lines(&log_path, game, &cursor) {
                 for event in events {
-                    // Create a dedup key from event data
-                    let dedup_key = Self::get_event_key(&event);
+                    // Rotations are noticed here rather than read from the log,
+                    // so two in the same second are not duplicates
+                    if !matches!(event, LogEvent::LogRotated { .. }) {
+                        // Create a dedup key from event data
+                        let dedup_key = Self::get_event_key(&event);
 
-                    // Skip if we've seen this exact event recently
-                    if recent_events.contains(&dedup_key) {
-                        continue;
-                    }
+                        // Skip if we've seen this exact event recently
+                        if recent_events.contains(&dedup_key) {
+                            continue;
+                        }
 
-                    recent_events.insert(dedup_key);
-                    // Emit event to frontend
-                    let _ = app_handle.emit("log-event", &event);
+                        recent_events.insert(dedup_key);
+                    }
+                    if let LogEvent::GameVersion { version, .. } = &event {
+                        if let Ok(mut current) = game_version.lock() {
+                            *current = Some(version.clone());
+                        }
+                    }
+                    planner.observe(&event, Instant::now());
+                    let derived = campaign.observe(&event);
+                    on_event(event);
+                    if let Some(derived) = derived {
+                        on_event(derived);
+                    }
                 }
             }
 
-            // Sleep briefly before next poll (10ms in fast mode, 100ms normal)
-            let interval = if fast_polling.load(Ordering::Relaxed) { 10 } else { 100 };
-            thread::sleep(Duration::from_millis(interval));
+            // Sleep briefly before next poll, shorter while a split may be imminent
+            let mode = planner.mode(
+                polling.manual.load(Ordering::Relaxed),
+                breakpoint_engine::near_breakpoint(),
+                Instant::now(),
+            );
+            polling.set(mode);
+            thread::sleep(Duration::from_millis(mode.interval_ms));
         }
     }
 
@@ -186,35 +568,124 @@ impl LogWatcher {
             LogEvent::Login { timestamp } => {
                 format!("login:{}", timestamp)
             }
+            LogEvent::Afk { timestamp, enabled } => {
+                format!("afk:{}:{}", timestamp, enabled)
+            }
+            LogEvent::Disconnect { timestamp } => {
+                format!("disconnect:{}", timestamp)
+            }
+            LogEvent::AreaGenerated { timestamp, seed, .. } => {
+                format!("area:{}:{}", timestamp, seed)
+            }
             LogEvent::KitavaAffliction { timestamp, penalty } => {
                 format!("kitava:{}:{}", timestamp, penalty)
             }
+            LogEvent::GameVersion { timestamp, version } => {
+                format!("version:{}:{}", timestamp, version)
+            }
+            LogEvent::NpcDialogue { timestamp, npc, text } => {
+                format!("npc:{}:{}:{}", timestamp, npc, text)
+            }
+            LogEvent::ActComplete { timestamp, act } => {
+                format!("act:{}:{}", timestamp, act)
+            }
+            LogEvent::LabStart { timestamp } => {
+                format!("lab_start:{}", timestamp)
+            }
+            LogEvent::LabComplete { timestamp } => {
+                format!("lab_complete:{}", timestamp)
+            }
+            LogEvent::LogRotated { timestamp, .. } => {
+                format!("rotated:{}", timestamp)
+            }
         }
     }
 
-    /// Read new lines from the log file
-    fn read_new_lines(log_path: &Path, file_position: &Arc<Mutex<u64>>) -> Result<Vec<LogEvent>> {
+    /// Scan the tail of the log for the last game version line
+    fn find_last_game_version(log_path: &Path, game: Game) -> Option<String> {
+        let mut file = File::open(log_path).ok()?;
+        let len = file.metadata().ok()?.len();
+        file.seek(SeekFrom::Start(len.saturating_sub(VERSION_SCAN_BYTES))).ok()?;
+
+        // The first line may be cut mid-way (or mid-character), so read lossily
+        let mut bytes = Vec::new();
+        std::io::Read::read_to_end(&mut file, &mut bytes).ok()?;
+        String::from_utf8_lossy(&bytes)
+            .lines()
+            .rev()
+            .find_map(|line| match Self::parse_line(line, game) {
+                Some(LogEvent::GameVersion { version, .. }) => Some(version),
+                _ => None,
+            })
+    }
+
+    /// Read new lines from the log file. A file that shrank below the read
+    /// position or was swapped for another is read again from the start,
+    /// preceded by a `LogRotated` event.
+    fn read_new_lines(log_path: &Path, game: Game, cursor: &Arc<Mutex<LogCursor>>) -> Result<Vec<LogEvent>> {
         let mut events = Vec::new();
         let file = File::open(log_path)?;
-        let mut reader = BufReader::new(file);
+        let metadata = file.metadata()?;
+        let mut cursor = cursor.lock().unwrap();
+
+        let id = file_id(&metadata);
+        let reason = if cursor.file_id.is_some() && id.is_some() && cursor.file_id != id {
+            Some(RotationReason::Replaced)
+        } else if metadata.len() < cursor.position {
+            Some(RotationReason::Truncated)
+        } else {
+            None
+        };
+        cursor.file_id = id;
+        if let Some(reason) = reason {
+            cursor.position = 0;
+            events.push(LogEvent::LogRotated {
+                timestamp: chrono::Local::now().format("%Y/%m/%d %H:%M:%S").to_string(),
+                reason,
+            });
+        }
 
-        let mut pos = file_position.lock().unwrap();
-        reader.seek(SeekFrom::Start(*pos))?;
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(cursor.position))?;
 
         let mut line = String::new();
         while reader.read_line(&mut line)? > 0 {
-            if let Some(event) = Self::parse_line(&line) {
+            if let Some(event) = Self::parse_line(&line, game) {
                 events.push(event);
             }
             line.clear();
         }
 
-        *pos = reader.stream_position()?;
+        cursor.position = reader.stream_position()?;
         Ok(events)
     }
 
-    /// Parse a log line into an event
-    fn parse_line(line: &str) -> Option<LogEvent> {
+    /// Parse a log already written, with the act and labyrinth events derived
+    /// from it, for replaying it offline. Lines are read one at a time, so a
+    /// large Client.txt isn't loaded whole; `on_event` returns false to stop.
+    pub fn replay(mut reader: impl BufRead, game: Game, mut on_event: impl FnMut(LogEvent) -> bool) -> Result<()> {
+        let mut campaign = CampaignTracker { act: None, game };
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            // Chat can hold anything; a bad byte shouldn't end the replay
+            if let Some(event) = Self::parse_line(&String::from_utf8_lossy(&line), game) {
+                let derived = campaign.observe(&event);
+                if !on_event(event) {
+                    return Ok(());
+                }
+                if let Some(derived) = derived {
+                    if !on_event(derived) {
+                        return Ok(());
+                    }
+                }
+            }
+            line.clear();
+        }
+        Ok(())
+    }
+
+    /// Parse a log line into an event, as `game`'s client writes it
+    fn parse_line(line: &str, game: Game) -> Option<LogEvent> {
         lazy_static::lazy_static! {
             // Pattern: 2024/01/15 12:34:56 12345678 abc [INFO Client 1234] : You have entered The Coast.
             // Note: POE log format has "] : " before the message
@@ -237,17 +708,64 @@ impl LogWatcher {
                 r"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] :? ?Got Instance Details"
             ).unwrap();
 
+            // Pattern: 2024/01/15 12:34:56 12345678 abc [DEBUG Client 1234] Generating level 83 area "MapWorldsStrand" with seed 2718281828
+            static ref AREA_GENERATED: Regex = Regex::new(
+                r#"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] :? ?Generating level (\d+) area "([^"]+)" with seed (\d+)"#
+            ).unwrap();
+
             // Pattern: Connecting to instance server
             static ref LOGIN: Regex = Regex::new(
                 r"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] :? ?Connecting to instance server"
             ).unwrap();
 
+            // Pattern: 2024/01/15 12:34:56 12345678 abc [INFO Client 1234] : AFK mode is now ON. Autoreply "This player is AFK."
+            static ref AFK: Regex = Regex::new(
+                r"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] :? ?AFK mode is now (ON|OFF)"
+            ).unwrap();
+
+            // Pattern: "Abnormal disconnect: ..." or, on logout, "Async connecting to lon01.login.pathofexile.com:20481"
+            static ref DISCONNECT: Regex = Regex::new(
+                r"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] :? ?(?:Abnormal disconnect|Async connecting to \S*login)"
+            ).unwrap();
+
             // Pattern: Kitava resistance penalty (Act 5: -30%, Act 10: -60%)
             // Act 5: "You have been permanently weakened by Kitava's cruel affliction. You now have -30% to all Resistances."
             // Act 10: "You have been permanently weakened by Kitava's merciless affliction. You now have a total of -60% to all Resistances."
             static ref KITAVA_AFFLICTION: Regex = Regex::new(
                 r"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] :? ?You have been permanently weakened by Kitava's .+ affliction\. You now have (?:a total of )?-(\d+)% to all Resistances\."
             ).unwrap();
+
+            // Pattern: build line logged at client launch, e.g. "Client Version: 3.25.1.3"
+            // (anchored to the start of the message so chat can't match it)
+            static ref GAME_VERSION: Regex = Regex::new(
+                r"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] (?:\[\w+\] )?:? ?(?:Client |Game )?[Vv]ersion:? v?(\d+\.\d+(?:\.\d+)*[a-z]?)\s*$"
+            ).unwrap();
+
+            // Pattern: 2024/01/15 12:34:56 12345678 abc [INFO Client 1234] Izaro: Triumphant at last!
+            static ref NPC_DIALOGUE: Regex = Regex::new(
+                r"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] (\w+): (.+?)\s*$"
+            ).unwrap();
+
+            // Path of Exile 2 doesn't log "You have entered"; the scene change names the zone.
+            // Pattern: 2024/12/06 21:25:09 12345678 abc [INFO Client 1234] [SCENE] Set Source [Clearfell Encampment]
+            // (PoE 1 logs these lines too, next to its own, so they're only read for PoE 2)
+            static ref POE2_ZONE_ENTER: Regex = Regex::new(
+                r"(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}).*\] \[SCENE\] Set Source \[([^\]]+)\]"
+            ).unwrap();
+        }
+
+        // PoE 2 lines first; the rest of its lines read like PoE 1's
+        if game == Game::Poe2 {
+            if let Some(caps) = POE2_ZONE_ENTER.captures(line) {
+                // Loading screens set "(null)" and "(unknown)" sources in between
+                if caps[2].starts_with('(') {
+                    return None;
+                }
+                return Some(LogEvent::ZoneEnter {
+                    timestamp: caps[1].to_string(),
+                    zone_name: caps[2].to_string(),
+                });
+            }
         }
 
         // Try to match zone enter
@@ -283,6 +801,16 @@ impl LogWatcher {
             });
         }
 
+        // Try to match area generation
+        if let Some(caps) = AREA_GENERATED.captures(line) {
+            return Some(LogEvent::AreaGenerated {
+                timestamp: caps[1].to_string(),
+                area_level: caps[2].parse().unwrap_or(1),
+                area_id: caps[3].to_string(),
+                seed: caps[4].parse().unwrap_or(0),
+            });
+        }
+
         // Try to match Kitava affliction
         if let Some(caps) = KITAVA_AFFLICTION.captures(line) {
             return Some(LogEvent::KitavaAffliction {
@@ -291,6 +819,29 @@ impl LogWatcher {
             });
         }
 
+        // Try to match game version
+        if let Some(caps) = GAME_VERSION.captures(line) {
+            return Some(LogEvent::GameVersion {
+                timestamp: caps[1].to_string(),
+                version: caps[2].to_string(),
+            });
+        }
+
+        // Try to match AFK mode changes
+        if let Some(caps) = AFK.captures(line) {
+            return Some(LogEvent::Afk {
+                timestamp: caps[1].to_string(),
+                enabled: &caps[2] == "ON",
+            });
+        }
+
+        // Try to match disconnects and logouts
+        if let Some(caps) = DISCONNECT.captures(line) {
+            return Some(LogEvent::Disconnect {
+                timestamp: caps[1].to_string(),
+            });
+        }
+
         // Try to match login
         if let Some(caps) = LOGIN.captures(line) {
             return Some(LogEvent::Login {
@@ -298,34 +849,224 @@ impl LogWatcher {
             });
         }
 
+        // Try to match quest NPC dialogue
+        if let Some(caps) = NPC_DIALOGUE.captures(line) {
+            if QUEST_NPCS.contains(&&caps[2]) {
+                return Some(LogEvent::NpcDialogue {
+                    timestamp: caps[1].to_string(),
+                    npc: caps[2].to_string(),
+                    text: caps[3].to_string(),
+                });
+            }
+        }
+
         None
     }
 }
 
+/// Game folders, in a Steam library's `steamapps/common` and in the standalone
+/// installs. Path of Exile 2 writes the same Client.txt.
+const GAME_DIRS: [&str; 2] = ["Path of Exile", "Path of Exile 2"];
+
 /// Detect the POE log path automatically
 pub fn detect_log_path() -> Option<PathBuf> {
-    let possible_paths = [
-        // Steam
-        r"C:\Program Files (x86)\Steam\steamapps\common\Path of Exile\logs\Client.txt",
-        // Standalone
-        r"C:\Program Files (x86)\Grinding Gear Games\Path of Exile\logs\Client.txt",
-        // Epic Games
-        r"C:\Program Files\Epic Games\PathOfExile\logs\Client.txt",
-        // Common custom Steam library locations
-        r"D:\Steam\steamapps\common\Path of Exile\logs\Client.txt",
-        r"D:\SteamLibrary\steamapps\common\Path of Exile\logs\Client.txt",
-        r"E:\Steam\steamapps\common\Path of Exile\logs\Client.txt",
-        r"E:\SteamLibrary\steamapps\common\Path of Exile\logs\Client.txt",
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    // Windows: every Steam library listed by the Steam install the registry names
+    #[cfg(windows)]
+    {
+        let steam_roots = windows_steam_roots();
+        candidates.extend(steam_log_paths(&steam_roots, &read_library_folders(&steam_roots)));
+    }
+
+    if cfg!(windows) {
+        let possible_paths = [
+            // Standalone
+            r"C:\Program Files (x86)\Grinding Gear Games\Path of Exile\logs\Client.txt",
+            r"C:\Program Files (x86)\Grinding Gear Games\Path of Exile 2\logs\Client.txt",
+            // Epic Games
+            r"C:\Program Files\Epic Games\PathOfExile\logs\Client.txt",
+            // Common custom Steam library locations, in case Steam's own list can't be read
+            r"D:\Steam\steamapps\common\Path of Exile\logs\Client.txt",
+            r"D:\SteamLibrary\steamapps\common\Path of Exile\logs\Client.txt",
+            r"E:\Steam\steamapps\common\Path of Exile\logs\Client.txt",
+            r"E:\SteamLibrary\steamapps\common\Path of Exile\logs\Client.txt",
+        ];
+        candidates.extend(possible_paths.iter().map(PathBuf::from));
+    } else if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        if cfg!(target_os = "macos") {
+            let library_folders = read_library_folders(&macos_steam_roots(&home));
+            candidates.extend(macos_log_paths(&home, &library_folders));
+        } else {
+            // Linux: Steam under Proton, or a Wine prefix
+            let wine_prefix = std::env::var_os("WINEPREFIX").map(PathBuf::from);
+            let steam_roots = linux_steam_roots(&home);
+            let library_folders = read_library_folders(&steam_roots);
+            let proton_prefixes = proton_prefixes(&steam_libraries(&steam_roots, &library_folders));
+            candidates.extend(wine_log_paths(&home, wine_prefix.as_deref(), &library_folders, &proton_prefixes));
+        }
+    }
+
+    candidates
+        .iter()
+        .map(|path| resolve_path_case(path))
+        .find(|path| path.exists())
+}
+
+/// `path` with each missing component matched case-insensitively against
+/// what's on disk. Wine and Proton prefixes live on case-sensitive file
+/// systems, where the client's folders don't always have the case Windows
+/// paths are written in (`logs/client.txt`, a typed `Logs/Client.txt`).
+/// Unchanged if it exists or a component has no match.
+pub fn resolve_path_case(path: &Path) -> PathBuf {
+    if path.exists() {
+        return path.to_path_buf();
+    }
+
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        let exact = resolved.join(component);
+        if exact.exists() || !matches!(component, Component::Normal(_)) {
+            resolved = exact;
+            continue;
+        }
+        let dir = if resolved.as_os_str().is_empty() { Path::new(".") } else { resolved.as_path() };
+        let name = component.as_os_str().to_string_lossy();
+        let matched = std::fs::read_dir(dir).ok().and_then(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name())
+                .find(|entry| entry.to_string_lossy().eq_ignore_ascii_case(&name))
+        });
+        match matched {
+            Some(entry) => resolved.push(entry),
+            None => return path.to_path_buf(),
+        }
+    }
+    resolved
+}
+
+/// Steam installs on Windows: where the registry says Steam is, then the default
+#[cfg(windows)]
+fn windows_steam_roots() -> Vec<PathBuf> {
+    use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+    use winreg::RegKey;
+
+    let keys = [
+        (HKEY_CURRENT_USER, r"Software\Valve\Steam", "SteamPath"),
+        (HKEY_LOCAL_MACHINE, r"SOFTWARE\WOW6432Node\Valve\Steam", "InstallPath"),
     ];
+    let mut roots: Vec<PathBuf> = keys
+        .iter()
+        .filter_map(|(hive, key, value)| {
+            let path: String = RegKey::predef(*hive).open_subkey(key).ok()?.get_value(value).ok()?;
+            Some(PathBuf::from(path))
+        })
+        .collect();
+    roots.push(PathBuf::from(r"C:\Program Files (x86)\Steam"));
+    roots.dedup();
+    roots
+}
+
+/// Steam installs on Linux, native and Flatpak
+fn linux_steam_roots(home: &Path) -> Vec<PathBuf> {
+    [
+        ".steam/steam",
+        ".local/share/Steam",
+        ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+    ]
+    .iter()
+    .map(|root| home.join(root))
+    .collect()
+}
+
+/// Steam install on macOS
+fn macos_steam_roots(home: &Path) -> Vec<PathBuf> {
+    vec![home.join("Library/Application Support/Steam")]
+}
+
+/// Content of each Steam install's `libraryfolders.vdf`
+fn read_library_folders(steam_roots: &[PathBuf]) -> String {
+    steam_roots
+        .iter()
+        .filter_map(|root| std::fs::read_to_string(root.join("steamapps").join("libraryfolders.vdf")).ok())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    for path_str in &possible_paths {
-        let path = PathBuf::from(path_str);
-        if path.exists() {
-            return Some(path);
+/// The Steam installs, then the extra libraries `library_folders` (the
+/// content of `libraryfolders.vdf`) lists
+fn steam_libraries(steam_roots: &[PathBuf], library_folders: &str) -> Vec<PathBuf> {
+    let mut libraries = steam_roots.to_vec();
+    // Extra libraries are listed as `"path"		"/mnt/games/SteamLibrary"`, with
+    // Windows backslashes escaped: `"D:\\SteamLibrary"`
+    for line in library_folders.lines() {
+        let mut fields = line.split('"').filter(|field| !field.trim().is_empty());
+        if fields.next() == Some("path") {
+            if let Some(path) = fields.next() {
+                let library = PathBuf::from(path.replace("\\\\", "\\"));
+                if !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+            }
         }
     }
+    libraries
+}
+
+/// Client.txt in every game folder of every Steam library
+fn steam_log_paths(steam_roots: &[PathBuf], library_folders: &str) -> Vec<PathBuf> {
+    steam_libraries(steam_roots, library_folders)
+        .iter()
+        .flat_map(|library| {
+            GAME_DIRS
+                .iter()
+                .map(move |game| library.join("steamapps").join("common").join(game).join("logs").join("Client.txt"))
+        })
+        .collect()
+}
 
-    None
+/// Wine prefixes Proton made in the Steam libraries, one per game it ran
+/// (`steamapps/compatdata/<app id>/pfx`), including the standalone client
+/// added to Steam as a non-Steam game
+fn proton_prefixes(libraries: &[PathBuf]) -> Vec<PathBuf> {
+    let mut prefixes: Vec<PathBuf> = libraries
+        .iter()
+        .filter_map(|library| std::fs::read_dir(library.join("steamapps").join("compatdata")).ok())
+        .flat_map(|entries| entries.flatten().map(|entry| entry.path().join("pfx")))
+        .collect();
+    prefixes.sort();
+    prefixes
+}
+
+/// Where Client.txt lives on Linux: in a Steam library when the game runs
+/// through Proton (`library_folders` is the content of Steam's
+/// `libraryfolders.vdf`), or inside a prefix's `drive_c` when the standalone
+/// client runs through Wine, Lutris or Proton (`proton_prefixes`)
+fn wine_log_paths(home: &Path, wine_prefix: Option<&Path>, library_folders: &str, proton_prefixes: &[PathBuf]) -> Vec<PathBuf> {
+    let prefixes = [
+        wine_prefix.map(Path::to_path_buf),
+        Some(home.join(".wine")),
+        // Lutris
+        Some(home.join("Games/path-of-exile")),
+        Some(home.join("Games/path-of-exile-2")),
+    ];
+    let mut paths = steam_log_paths(&linux_steam_roots(home), library_folders);
+    for prefix in prefixes.into_iter().flatten().chain(proton_prefixes.iter().cloned()) {
+        for game in GAME_DIRS {
+            paths.push(prefix.join("drive_c/Program Files (x86)/Grinding Gear Games").join(game).join("logs/Client.txt"));
+        }
+    }
+    paths.dedup();
+    paths
+}
+
+/// Where Client.txt lives on macOS: in a Steam library, or the standalone
+/// client's caches
+fn macos_log_paths(home: &Path, library_folders: &str) -> Vec<PathBuf> {
+    let mut paths = steam_log_paths(&macos_steam_roots(home), library_folders);
+    paths.push(home.join("Library/Caches/com.GGG.PathOfExile/Logs/Client.txt"));
+    paths
 }
 
 #[cfg(test)]
@@ -336,7 +1077,7 @@ mod tests {
     fn test_parse_zone_enter() {
         // Test with colon format (actual POE format)
         let line = "2024/01/15 12:34:56 12345678 abc [INFO Client 1234] : You have entered The Coast.";
-        let event = LogWatcher::parse_line(line);
+        let event = LogWatcher::parse_line(line, Game::Poe1);
         assert!(matches!(event, Some(LogEvent::ZoneEnter { zone_name, .. }) if zone_name == "The Coast"));
     }
 
@@ -344,14 +1085,14 @@ mod tests {
     fn test_parse_zone_enter_no_colon() {
         // Test without colon format (backwards compatibility)
         let line = "2024/01/15 12:34:56 12345678 abc [INFO Client 1234] You have entered The Coast.";
-        let event = LogWatcher::parse_line(line);
+        let event = LogWatcher::parse_line(line, Game::Poe1);
         assert!(matches!(event, Some(LogEvent::ZoneEnter { zone_name, .. }) if zone_name == "The Coast"));
     }
 
     #[test]
     fn test_parse_level_up() {
         let line = "2024/01/15 12:34:56 12345678 abc [INFO Client 1234] : TestChar (Witch) is now level 10";
-        let event = LogWatcher::parse_line(line);
+        let event = LogWatcher::parse_line(line, Game::Poe1);
         assert!(matches!(
             event,
             Some(LogEvent::LevelUp { character_name, character_class, level, .. })
@@ -362,21 +1103,229 @@ mod tests {
     #[test]
     fn test_parse_kitava_act5() {
         let line = "2021/04/29 06:47:13 130346843 bad [INFO Client 17428] : You have been permanently weakened by Kitava's cruel affliction. You now have -30% to all Resistances.";
-        let event = LogWatcher::parse_line(line);
+        let event = LogWatcher::parse_line(line, Game::Poe1);
         assert!(matches!(event, Some(LogEvent::KitavaAffliction { penalty, .. }) if penalty == 30));
     }
 
     #[test]
     fn test_parse_kitava_act10() {
         let line = "2021/04/29 22:27:18 186752375 bad [INFO Client 2900] : You have been permanently weakened by Kitava's merciless affliction. You now have a total of -60% to all Resistances.";
-        let event = LogWatcher::parse_line(line);
+        let event = LogWatcher::parse_line(line, Game::Poe1);
         assert!(matches!(event, Some(LogEvent::KitavaAffliction { penalty, .. }) if penalty == 60));
     }
 
+    #[test]
+    fn test_parse_game_version() {
+        let line = "2024/07/26 20:00:01 12345678 abc [INFO Client 1234] Client Version: 3.25.1.3";
+        let event = LogWatcher::parse_line(line, Game::Poe1);
+        assert!(matches!(event, Some(LogEvent::GameVersion { version, .. }) if version == "3.25.1.3"));
+
+        // Chat that mentions a version is not a build line
+        let chat = "2024/07/26 20:00:01 12345678 abc [INFO Client 1234] #Player: version 3.25.1 is out";
+        assert!(LogWatcher::parse_line(chat, Game::Poe1).is_none());
+    }
+
+    #[test]
+    fn test_parse_poe2_lines() {
+        let scene = "2024/12/06 21:25:09 12345678 abc [INFO Client 1234] [SCENE] Set Source [Clearfell Encampment]";
+        let event = LogWatcher::parse_line(scene, Game::Poe2);
+        assert!(matches!(event, Some(LogEvent::ZoneEnter { zone_name, .. }) if zone_name == "Clearfell Encampment"));
+        // PoE 1 logs the same line beside "You have entered"
+        assert!(LogWatcher::parse_line(scene, Game::Poe1).is_none());
+
+        let loading = "2024/12/06 21:25:08 12345678 abc [INFO Client 1234] [SCENE] Set Source [(null)]";
+        assert!(LogWatcher::parse_line(loading, Game::Poe2).is_none());
+
+        // Shared formats still parse
+        let level = "2024/12/06 21:30:00 12345678 abc [INFO Client 1234] : Exile (Monk) is now level 2";
+        let event = LogWatcher::parse_line(level, Game::Poe2);
+        assert!(matches!(event, Some(LogEvent::LevelUp { level: 2, character_class, .. }) if character_class == "Monk"));
+    }
+
+    #[test]
+    fn test_parse_npc_dialogue() {
+        let line = "2024/01/15 12:34:56 12345678 abc [INFO Client 1234] Izaro: Triumphant at last!";
+        let event = LogWatcher::parse_line(line, Game::Poe1);
+        assert!(matches!(event, Some(LogEvent::NpcDialogue { npc, text, .. }) if npc == "Izaro" && text == "Triumphant at last!"));
+
+        // Local chat from a player has the same shape
+        let chat = "2024/01/15 12:34:56 12345678 abc [INFO Client 1234] SomePlayer: Triumphant at last!";
+        assert!(LogWatcher::parse_line(chat, Game::Poe1).is_none());
+    }
+
+    fn zone(zone_name: &str) -> LogEvent {
+        LogEvent::ZoneEnter {
+            timestamp: "2024/01/15 12:34:56".to_string(),
+            zone_name: zone_name.to_string(),
+        }
+    }
+
+    fn completed_act(tracker: &mut CampaignTracker, event: &LogEvent) -> Option<i32> {
+        match tracker.observe(event) {
+            Some(LogEvent::ActComplete { act, .. }) => Some(act),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_act_complete_on_next_town() {
+        let mut tracker = CampaignTracker::default();
+        assert_eq!(completed_act(&mut tracker, &zone("The Twilight Strand")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("Lioneye's Watch")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Southern Forest")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Forest Encampment")), Some(1));
+        // Going back to an earlier town doesn't complete anything
+        assert_eq!(completed_act(&mut tracker, &zone("Lioneye's Watch")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Forest Encampment")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Sarn Encampment")), Some(2));
+
+        // Kitava completes act 5; the act 6 town that follows doesn't again
+        let kitava = LogEvent::KitavaAffliction { timestamp: String::new(), penalty: 30 };
+        let mut tracker = CampaignTracker { act: Some(5), ..Default::default() };
+        assert_eq!(completed_act(&mut tracker, &kitava), Some(5));
+        assert_eq!(completed_act(&mut tracker, &zone("Lioneye's Watch")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Twilight Strand")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Bridge Encampment")), Some(6));
+    }
+
+    #[test]
+    fn test_poe2_act_complete() {
+        let mut tracker = CampaignTracker { act: None, game: Game::Poe2 };
+        assert_eq!(completed_act(&mut tracker, &zone("The Riverbank")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("Clearfell Encampment")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Ardura Caravan")), Some(1));
+        assert_eq!(completed_act(&mut tracker, &zone("Ziggurat Encampment")), Some(2));
+        // PoE 1 towns mean nothing here
+        assert_eq!(completed_act(&mut tracker, &zone("Highgate")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("Kingsmarch")), Some(3));
+    }
+
+    #[test]
+    fn test_act_placed_by_unique_town() {
+        // Started mid-campaign: an ambiguous town can't place the act, a unique one can
+        let mut tracker = CampaignTracker::default();
+        assert_eq!(completed_act(&mut tracker, &zone("Highgate")), None);
+        assert_eq!(tracker.act, None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Bridge Encampment")), None);
+        assert_eq!(completed_act(&mut tracker, &zone("The Sarn Encampment")), Some(7));
+    }
+
+    #[test]
+    fn test_lab_events() {
+        let mut tracker = CampaignTracker::default();
+        assert!(matches!(tracker.observe(&zone("Aspirants' Plaza")), Some(LogEvent::LabStart { .. })));
+
+        let line = "2024/01/15 12:34:56 12345678 abc [INFO Client 1234] Izaro: You are free!";
+        let izaro = LogWatcher::parse_line(line, Game::Poe1).unwrap();
+        assert!(matches!(tracker.observe(&izaro), Some(LogEvent::LabComplete { .. })));
+    }
+
+    #[test]
+    fn test_parse_area_generated() {
+        let line = r#"2024/01/15 12:34:56 12345678 abc [DEBUG Client 1234] Generating level 83 area "MapWorldsStrand" with seed 2718281828"#;
+        match LogWatcher::parse_line(line, Game::Poe1) {
+            Some(LogEvent::AreaGenerated { area_level, area_id, seed, .. }) => {
+                assert_eq!((area_level, area_id.as_str(), seed), (83, "MapWorldsStrand", 2_718_281_828));
+            }
+            other => panic!("expected AreaGenerated, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_death() {
         let line = "2024/01/15 12:34:56 12345678 abc [INFO Client 1234] : TestChar has been slain.";
-        let event = LogWatcher::parse_line(line);
+        let event = LogWatcher::parse_line(line, Game::Poe1);
         assert!(matches!(event, Some(LogEvent::Death { character_name, .. }) if character_name == "TestChar"));
     }
+
+    #[test]
+    fn test_parse_afk_and_disconnect() {
+        let on = "2024/01/15 12:34:56 12345678 abc [INFO Client 1234] : AFK mode is now ON. Autoreply \"This player is AFK.\"";
+        assert!(matches!(LogWatcher::parse_line(on, Game::Poe1), Some(LogEvent::Afk { enabled: true, .. })));
+        let off = "2024/01/15 12:44:56 12345678 abc [INFO Client 1234] : AFK mode is now OFF.";
+        assert!(matches!(LogWatcher::parse_line(off, Game::Poe1), Some(LogEvent::Afk { enabled: false, .. })));
+
+        let dropped = "2024/01/15 12:50:00 12345678 abc [INFO Client 1234] Abnormal disconnect: An unexpected disconnection occurred.";
+        assert!(matches!(LogWatcher::parse_line(dropped, Game::Poe1), Some(LogEvent::Disconnect { .. })));
+        let logout = "2024/01/15 12:51:00 12345678 abc [INFO Client 1234] Async connecting to lon01.login.pathofexile.com:20481";
+        assert!(matches!(LogWatcher::parse_line(logout, Game::Poe1), Some(LogEvent::Disconnect { .. })));
+        // Someone typing it in chat isn't a mode change
+        let chat = "2024/01/15 12:52:00 12345678 abc [INFO Client 1234] #Player: AFK mode is now ON";
+        assert!(!matches!(LogWatcher::parse_line(chat, Game::Poe1), Some(LogEvent::Afk { .. })));
+    }
+
+    #[test]
+    fn test_poll_mode_follows_loading_screens() {
+        let now = Instant::now();
+        let mut planner = PollPlanner::default();
+        assert_eq!(planner.mode(false, false, now), PollMode::default());
+        assert_eq!(planner.mode(false, false, now).interval_ms, SLOW_POLL_MS);
+
+        planner.observe(&LogEvent::InstanceDetails { timestamp: String::new() }, now);
+        let loading = planner.mode(false, false, now);
+        assert_eq!((loading.reason, loading.interval_ms), (Some(PollReason::Loading), FAST_POLL_MS));
+        // A loading screen that never ends stops counting
+        assert_eq!(planner.mode(false, false, now + LOADING_TIMEOUT).reason, None);
+
+        planner.observe(&LogEvent::ZoneEnter { timestamp: String::new(), zone_name: "The Coast".into() }, now);
+        assert_eq!(planner.mode(false, false, now).reason, None);
+        assert_eq!(planner.mode(false, true, now).reason, Some(PollReason::Breakpoint));
+        assert_eq!(planner.mode(true, true, now).reason, Some(PollReason::Manual));
+    }
+
+    #[test]
+    fn test_wine_log_paths() {
+        let vdf = "\"libraryfolders\"\n{\n\t\"1\"\n\t{\n\t\t\"path\"\t\t\"/mnt/games/SteamLibrary\"\n\t}\n}";
+        let proton = [PathBuf::from("/mnt/games/SteamLibrary/steamapps/compatdata/3141592653/pfx")];
+        let paths = wine_log_paths(Path::new("/home/exile"), Some(Path::new("/opt/poe-prefix")), vdf, &proton);
+        let has = |path: &str| paths.contains(&PathBuf::from(path));
+
+        assert!(has("/home/exile/.steam/steam/steamapps/common/Path of Exile/logs/Client.txt"));
+        assert!(has("/mnt/games/SteamLibrary/steamapps/common/Path of Exile/logs/Client.txt"));
+        assert!(has("/opt/poe-prefix/drive_c/Program Files (x86)/Grinding Gear Games/Path of Exile/logs/Client.txt"));
+        assert!(has("/home/exile/.wine/drive_c/Program Files (x86)/Grinding Gear Games/Path of Exile/logs/Client.txt"));
+        assert!(has("/home/exile/Games/path-of-exile-2/drive_c/Program Files (x86)/Grinding Gear Games/Path of Exile 2/logs/Client.txt"));
+        assert!(has(
+            "/mnt/games/SteamLibrary/steamapps/compatdata/3141592653/pfx/drive_c/Program Files (x86)/Grinding Gear Games/Path of Exile/logs/Client.txt"
+        ));
+        // The prefix from WINEPREFIX is tried before the default one
+        let position = |path: &str| paths.iter().position(|p| p == Path::new(path)).unwrap();
+        assert!(
+            position("/opt/poe-prefix/drive_c/Program Files (x86)/Grinding Gear Games/Path of Exile/logs/Client.txt")
+                < position("/home/exile/.wine/drive_c/Program Files (x86)/Grinding Gear Games/Path of Exile/logs/Client.txt")
+        );
+    }
+
+    #[test]
+    fn test_macos_log_paths() {
+        let paths = macos_log_paths(Path::new("/Users/exile"), "");
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/Users/exile/Library/Application Support/Steam/steamapps/common/Path of Exile/logs/Client.txt"),
+                PathBuf::from("/Users/exile/Library/Application Support/Steam/steamapps/common/Path of Exile 2/logs/Client.txt"),
+                PathBuf::from("/Users/exile/Library/Caches/com.GGG.PathOfExile/Logs/Client.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_steam_log_paths() {
+        // Windows libraries have their backslashes escaped
+        let vdf = "\"libraryfolders\"\n{\n\t\"0\"\n\t{\n\t\t\"path\"\t\t\"C:\\\\Program Files (x86)\\\\Steam\"\n\t}\n\t\"1\"\n\t{\n\t\t\"path\"\t\t\"D:\\\\Games\\\\SteamLibrary\"\n\t\t\"label\"\t\t\"\"\n\t}\n}";
+        let paths = steam_log_paths(&[PathBuf::from(r"C:\Program Files (x86)\Steam")], vdf);
+        let library = |root: &str, game: &str| {
+            PathBuf::from(root).join("steamapps").join("common").join(game).join("logs").join("Client.txt")
+        };
+
+        assert_eq!(
+            paths,
+            vec![
+                library(r"C:\Program Files (x86)\Steam", "Path of Exile"),
+                library(r"C:\Program Files (x86)\Steam", "Path of Exile 2"),
+                library(r"D:\Games\SteamLibrary", "Path of Exile"),
+                library(r"D:\Games\SteamLibrary", "Path of Exile 2"),
+            ]
+        );
+    }
 }
@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+use crate::db::MapSession;
+use crate::error::AppResult;
+use crate::log_watcher::LogEvent;
+use crate::zone_time::{classify_zone, ZoneKind};
+
+/// Where the character is, for map session time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Place {
+    Map,
+    Hideout,
+    Town,
+    Other,
+}
+
+/// Map areas' ids start with "Map" (e.g. `MapWorldsStrand`); zone names don't
+/// say whether an area is a map
+fn is_map_area(area_id: &str) -> bool {
+    area_id.starts_with("Map")
+}
+
+/// Splits a map session's wall-clock time between maps and the places
+/// visited between them, driven by log events
+#[derive(Debug)]
+pub struct MapSessionClock {
+    session: MapSession,
+    /// Unknown until the first zone entry of the session
+    current: Option<Place>,
+    entered_at: Instant,
+    /// Seed of the map area generated for the zone entry that follows
+    loading_map: Option<u64>,
+    /// Map instances entered; a portal back into one isn't a new map
+    map_seeds: HashSet<u64>,
+}
+
+impl MapSessionClock {
+    pub fn new(session: MapSession, now: Instant) -> Self {
+        MapSessionClock {
+            session,
+            current: None,
+            entered_at: now,
+            loading_map: None,
+            map_seeds: HashSet::new(),
+        }
+    }
+
+    /// Feed a log event; true when it moved the character somewhere else
+    pub fn observe(&mut self, event: &LogEvent, now: Instant) -> bool {
+        match event {
+            LogEvent::AreaGenerated { area_id, seed, .. } => {
+                self.loading_map = is_map_area(area_id).then_some(*seed);
+                false
+            }
+            LogEvent::ZoneEnter { zone_name, .. } => {
+                self.close_stint(now);
+                self.current = Some(match self.loading_map.take() {
+                    Some(seed) => {
+                        self.map_seeds.insert(seed);
+                        self.session.maps_run = self.map_seeds.len() as i64;
+                        Place::Map
+                    }
+                    None => match classify_zone(zone_name) {
+                        ZoneKind::Hideout => Place::Hideout,
+                        ZoneKind::Town => Place::Town,
+                        ZoneKind::Field => Place::Other,
+                    },
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The session's totals at `now`, including the current stint
+    pub fn totals(&self, now: Instant) -> MapSession {
+        let mut session = self.session.clone();
+        add_stint(&mut session, self.current, now.saturating_duration_since(self.entered_at).as_millis() as i64);
+        session
+    }
+
+    fn close_stint(&mut self, now: Instant) {
+        let stint = now.saturating_duration_since(self.entered_at).as_millis() as i64;
+        add_stint(&mut self.session, self.current, stint);
+        self.entered_at = now;
+    }
+}
+
+fn add_stint(session: &mut MapSession, place: Option<Place>, stint_ms: i64) {
+    match place {
+        Some(Place::Map) => session.map_time_ms += stint_ms,
+        Some(Place::Hideout) => session.hideout_time_ms += stint_ms,
+        Some(Place::Town) => session.town_time_ms += stint_ms,
+        Some(Place::Other) => session.other_time_ms += stint_ms,
+        None => {}
+    }
+}
+
+static ACTIVE: Mutex<Option<MapSessionClock>> = Mutex::new(None);
+
+fn active() -> std::sync::MutexGuard<'static, Option<MapSessionClock>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start a map session, ending the one running
+pub fn start() -> AppResult<MapSession> {
+    stop()?;
+    let id = MapSession::start()?;
+    let session = MapSession::get_by_id(id)?.unwrap_or(MapSession { id, ..Default::default() });
+    *active() = Some(MapSessionClock::new(session.clone(), Instant::now()));
+    Ok(session)
+}
+
+/// End the running map session, returning it as stored
+pub fn stop() -> AppResult<Option<MapSession>> {
+    let Some(clock) = active().take() else {
+        return Ok(None);
+    };
+    let totals = clock.totals(Instant::now());
+    totals.save_totals()?;
+    MapSession::finish(totals.id)?;
+    Ok(MapSession::get_by_id(totals.id)?)
+}
+
+/// The running session's totals so far
+pub fn current() -> Option<MapSession> {
+    active().as_ref().map(|clock| clock.totals(Instant::now()))
+}
+
+/// Feed a log event from the watcher. Zone entries store the totals and
+/// emit `map-session-updated`.
+pub fn handle_event(app_handle: &AppHandle, event: &LogEvent) {
+    let totals = {
+        let mut guard = active();
+        let Some(clock) = guard.as_mut() else {
+            return;
+        };
+        let now = Instant::now();
+        if !clock.observe(event, now) {
+            return;
+        }
+        clock.totals(now)
+    };
+    if let Err(e) = totals.save_totals() {
+        eprintln!("[map_session] Failed to save session totals: {}", e);
+    }
+    let _ = app_handle.emit("map-session-updated", &totals);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn area(area_id: &str, seed: u64) -> LogEvent {
+        LogEvent::AreaGenerated {
+            timestamp: String::new(),
+            area_level: 83,
+            area_id: area_id.to_string(),
+            seed,
+        }
+    }
+
+    fn zone(name: &str) -> LogEvent {
+        LogEvent::ZoneEnter { timestamp: String::new(), zone_name: name.to_string() }
+    }
+
+    #[test]
+    fn test_clock_splits_maps_from_time_between() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut clock = MapSessionClock::new(MapSession::default(), start);
+
+        let timeline = [
+            (0, area("HideoutCoastal", 1), zone("Coastal Hideout")),
+            (60, area("MapWorldsStrand", 42), zone("Strand")),
+            // Portal out to restock and back into the same map
+            (200, area("HideoutCoastal", 1), zone("Coastal Hideout")),
+            (230, area("MapWorldsStrand", 42), zone("Strand")),
+            (300, area("2_6_town", 7), zone("Oriath Docks")),
+            (340, area("MapWorldsStrand", 43), zone("Strand")),
+        ];
+        for (secs, generated, entered) in &timeline {
+            assert!(!clock.observe(generated, at(*secs)));
+            assert!(clock.observe(entered, at(*secs)));
+        }
+
+        let totals = clock.totals(at(400));
+        assert_eq!(totals.maps_run, 2);
+        assert_eq!(totals.map_time_ms, (140 + 70 + 60) * 1000);
+        assert_eq!(totals.hideout_time_ms, (60 + 30) * 1000);
+        assert_eq!(totals.town_time_ms, 40 * 1000);
+        assert_eq!(totals.other_time_ms, 0);
+    }
+}
@@ -0,0 +1,312 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::api_client::{self, ApiError};
+use crate::db::OAuthToken;
+use crate::table_export::escape_html;
+
+/// Public client registered with GGG for the authorization code + PKCE flow
+pub const CLIENT_ID: &str = "poewatcher";
+/// Character list, items and passives, including private profiles
+pub const SCOPES: &str = "account:profile account:characters";
+
+const AUTHORIZE_URL: &str = "https://www.pathofexile.com/oauth/authorize";
+const TOKEN_URL: &str = "https://www.pathofexile.com/oauth/token";
+
+/// Path the browser is sent back to on the loopback listener
+pub const REDIRECT_PATH: &str = "/oauth/callback";
+/// How long the user has to finish signing in in the browser
+pub const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+/// Tokens are refreshed this close to expiry so requests never race it
+const REFRESH_MARGIN_SECS: i64 = 300;
+
+/// PKCE verifier and its S256 challenge, plus the `state` that ties the
+/// browser redirect to this sign-in
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+    pub state: String,
+}
+
+impl Pkce {
+    pub fn new() -> Result<Self> {
+        let verifier = random_urlsafe(32)?;
+        Ok(Pkce {
+            challenge: challenge_for(&verifier),
+            verifier,
+            state: random_urlsafe(16)?,
+        })
+    }
+}
+
+fn random_urlsafe(len: usize) -> Result<String> {
+    let mut bytes = vec![0u8; len];
+    getrandom::getrandom(&mut bytes).map_err(|e| anyhow!("No OS randomness for sign-in: {}", e))?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn challenge_for(verifier: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Browser URL that starts the sign-in
+pub fn authorize_url(redirect_uri: &str, pkce: &Pkce) -> String {
+    format!(
+        "{}?client_id={}&response_type=code&scope={}&state={}&redirect_uri={}&code_challenge={}&code_challenge_method=S256",
+        AUTHORIZE_URL,
+        CLIENT_ID,
+        urlencoding::encode(SCOPES),
+        pkce.state,
+        urlencoding::encode(redirect_uri),
+        pkce.challenge
+    )
+}
+
+/// Path and query of a request line's target
+fn request_target(request_line: &str) -> (&str, &str) {
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    target.split_once('?').unwrap_or((target, ""))
+}
+
+/// Authorization code from the redirect's request line
+/// (`GET /oauth/callback?code=...&state=... HTTP/1.1`)
+pub fn parse_callback(request_line: &str, expected_state: &str) -> Result<String> {
+    let (path, query) = request_target(request_line);
+    if path != REDIRECT_PATH {
+        return Err(anyhow!("Unexpected sign-in redirect to {}", path));
+    }
+
+    let param = |name: &str| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+            .and_then(|v| urlencoding::decode(v).ok())
+            .map(|v| v.into_owned())
+    };
+    if param("state").as_deref() != Some(expected_state) {
+        return Err(anyhow!("Sign-in state mismatch; start the sign-in again"));
+    }
+    if let Some(error) = param("error") {
+        let description = param("error_description").unwrap_or(error);
+        return Err(anyhow!("Sign-in refused: {}", description));
+    }
+    param("code").ok_or_else(|| anyhow!("Sign-in redirect had no authorization code"))
+}
+
+/// Wait for the browser redirect on `listener` and return its authorization
+/// code. Blocks for up to `timeout`.
+pub fn wait_for_code(listener: TcpListener, state: &str, timeout: Duration) -> Result<String> {
+    listener.set_nonblocking(true)?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => match handle_redirect(stream, state) {
+                // Browsers also ask for /favicon.ico; keep waiting for the redirect
+                Ok(None) => continue,
+                Ok(Some(code)) => return code,
+                Err(e) => eprintln!("[oauth] Redirect failed: {}", e),
+            },
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => eprintln!("[oauth] Accept failed: {}", e),
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow!("Sign-in timed out; start it again from Settings"));
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Answer one request on the loopback listener: `None` if it wasn't the
+/// redirect, otherwise the code (or why there isn't one)
+fn handle_redirect(mut stream: TcpStream, state: &str) -> std::io::Result<Option<Result<String>>> {
+    // Accepted sockets can inherit non-blocking mode from the listener
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    if request_target(&request_line).0 != REDIRECT_PATH {
+        write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+        return Ok(None);
+    }
+
+    let code = parse_callback(request_line.trim_end(), state);
+    let body = result_page(&code);
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(Some(code))
+}
+
+/// Page shown in the browser after the redirect. Errors can quote the
+/// redirect's `error_description`, so they are escaped.
+fn result_page(code: &Result<String>) -> String {
+    let message = match code {
+        Ok(_) => "Signed in to Path of Exile. You can close this tab and return to POE Watcher.".to_string(),
+        Err(e) => escape_html(&format!("Sign-in failed: {}", e)),
+    };
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>POE Watcher</title></head>\
+         <body style=\"background:#0c0a08;color:#e8e0d0;font-family:system-ui,sans-serif;padding:32px\">{}</body></html>",
+        message
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: i64,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    username: String,
+}
+
+impl TokenResponse {
+    fn into_token(self, now: i64, previous: Option<&OAuthToken>) -> OAuthToken {
+        OAuthToken {
+            access_token: self.access_token,
+            // Refreshing may not rotate the refresh token; keep the old one then
+            refresh_token: self.refresh_token.or_else(|| previous.and_then(|t| t.refresh_token.clone())),
+            expires_at: now + self.expires_in,
+            account_name: match previous {
+                Some(t) if self.username.is_empty() => t.account_name.clone(),
+                _ => self.username,
+            },
+        }
+    }
+}
+
+async fn request_token(client: &Client, form: &[(&str, &str)], previous: Option<&OAuthToken>) -> Result<OAuthToken> {
+    let response = client.post(TOKEN_URL).form(form).send().await?;
+    let status = response.status();
+    let text = response.text().await?;
+    if (status == 400 || status == 401) && text.contains("invalid_grant") {
+        // The code or refresh token is used up, expired or revoked
+        return Err(ApiError::Unauthorized.into());
+    }
+    if !status.is_success() {
+        return Err(anyhow!("Token request failed ({}): {}", status, api_client::excerpt(&text)));
+    }
+    let response: TokenResponse = serde_json::from_str(&text)?;
+    Ok(response.into_token(chrono::Utc::now().timestamp(), previous))
+}
+
+/// Trade the redirect's authorization code for tokens
+pub async fn exchange_code(client: &Client, code: &str, verifier: &str, redirect_uri: &str) -> Result<OAuthToken> {
+    let form = [
+        ("client_id", CLIENT_ID),
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", redirect_uri),
+        ("scope", SCOPES),
+        ("code_verifier", verifier),
+    ];
+    request_token(client, &form, None).await
+}
+
+/// New access token from the refresh token
+pub async fn refresh(client: &Client, token: &OAuthToken) -> Result<OAuthToken> {
+    let refresh_token = token.refresh_token.as_deref().ok_or(ApiError::Unauthorized)?;
+    let form = [
+        ("client_id", CLIENT_ID),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+    ];
+    request_token(client, &form, Some(token)).await
+}
+
+pub fn needs_refresh(token: &OAuthToken, now: i64) -> bool {
+    token.expires_at - REFRESH_MARGIN_SECS <= now
+}
+
+/// Whether a token signed in as `token_account` can read `account_name`'s
+/// characters. The `#1234` discriminator is optional in either name.
+pub fn same_account(token_account: &str, account_name: &str) -> bool {
+    let token_account = token_account.trim();
+    let account_name = account_name.trim();
+    if token_account.eq_ignore_ascii_case(account_name) {
+        return true;
+    }
+    let (token_base, token_tag) = token_account.split_once('#').unwrap_or((token_account, ""));
+    let (base, tag) = account_name.split_once('#').unwrap_or((account_name, ""));
+    token_base.eq_ignore_ascii_case(base) && (token_tag.is_empty() || tag.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkce_challenge() {
+        // RFC 7636 appendix B
+        assert_eq!(
+            challenge_for("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let pkce = Pkce::new().unwrap();
+        assert_eq!(pkce.verifier.len(), 43);
+        assert_ne!(pkce.verifier, Pkce::new().unwrap().verifier);
+        let url = authorize_url("http://127.0.0.1:4321/oauth/callback", &pkce);
+        assert!(url.contains("redirect_uri=http%3A%2F%2F127.0.0.1%3A4321%2Foauth%2Fcallback"));
+        assert!(url.contains(&format!("code_challenge={}&code_challenge_method=S256", pkce.challenge)));
+    }
+
+    #[test]
+    fn test_parse_callback() {
+        let line = |query: &str| format!("GET /oauth/callback?{} HTTP/1.1", query);
+        assert_eq!(parse_callback(&line("code=abc%2B1&state=xyz"), "xyz").unwrap(), "abc+1");
+        assert!(parse_callback(&line("code=abc&state=other"), "xyz").is_err());
+        let refused = parse_callback(&line("error=access_denied&error_description=User%20denied&state=xyz"), "xyz");
+        assert_eq!(refused.unwrap_err().to_string(), "Sign-in refused: User denied");
+        assert!(parse_callback("GET /favicon.ico HTTP/1.1", "xyz").is_err());
+        // Only the exact path is the redirect
+        assert_eq!(request_target("GET /favicon.ico?r=/oauth/callback HTTP/1.1").0, "/favicon.ico");
+        assert!(parse_callback("GET /oauth/callback/x?code=abc&state=xyz HTTP/1.1", "xyz").is_err());
+
+        let injected = parse_callback(&line("error=x&error_description=%3Cscript%3Ealert(1)%3C%2Fscript%3E&state=xyz"), "xyz");
+        let page = result_page(&injected);
+        assert!(page.contains("&lt;script&gt;alert(1)&lt;/script&gt;") && !page.contains("<script>"));
+    }
+
+    #[test]
+    fn test_refresh_keeps_account_and_refresh_token() {
+        let previous = OAuthToken {
+            access_token: "old".to_string(),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: 1_000,
+            account_name: "Beerdz#1234".to_string(),
+        };
+        let response: TokenResponse =
+            serde_json::from_str(r#"{"access_token": "new", "expires_in": 36000, "token_type": "bearer"}"#).unwrap();
+        let token = response.into_token(2_000, Some(&previous));
+        assert_eq!(token.access_token, "new");
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+        assert_eq!(token.account_name, "Beerdz#1234");
+        assert_eq!(token.expires_at, 38_000);
+
+        assert!(!needs_refresh(&token, 2_000));
+        assert!(needs_refresh(&token, 37_800));
+    }
+
+    #[test]
+    fn test_same_account() {
+        assert!(same_account("Beerdz#1234", "beerdz#1234"));
+        assert!(same_account("Beerdz#1234", "Beerdz"));
+        assert!(same_account("Beerdz", "Beerdz#1234"));
+        assert!(!same_account("Beerdz#1234", "Beerdz#9999"));
+        assert!(!same_account("Beerdz#1234", "Someone"));
+    }
+}
@@ -0,0 +1,366 @@
+use base64::Engine;
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::io::{BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::status_server::{read_head, Response};
+
+/// GUID every WebSocket server appends to the client key (RFC 6455)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Idle WebSocket connections get a ping this often, so closed browser
+/// sources are noticed and their threads end
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Latest overlay state, serialized, for new connections and `state.json`
+static LATEST: Mutex<Option<String>> = Mutex::new(None);
+
+/// Open WebSocket connections, each fed every published state
+static SUBSCRIBERS: Mutex<Vec<Sender<String>>> = Mutex::new(Vec::new());
+
+/// Stop flag and accept thread of the running server, if any
+static SERVER: Mutex<Option<(Arc<AtomicBool>, JoinHandle<()>)>> = Mutex::new(None);
+
+/// What a request asked for
+#[derive(Debug)]
+pub enum Route {
+    Http(Response),
+    /// Upgrade to a WebSocket, answering with this `Sec-WebSocket-Accept`
+    WebSocket(String),
+}
+
+/// Send an `overlay-state-update` payload to every connected browser source
+pub fn publish(state: &Value) {
+    let json = state.to_string();
+    if let Ok(mut latest) = LATEST.lock() {
+        *latest = Some(json.clone());
+    }
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.retain(|subscriber| subscriber.send(json.clone()).is_ok());
+    }
+}
+
+fn latest() -> Option<String> {
+    LATEST.lock().ok().and_then(|latest| latest.clone())
+}
+
+/// `Sec-WebSocket-Accept` for a client's `Sec-WebSocket-Key`
+pub fn accept_key(key: &str) -> String {
+    let mut sha = Sha1::new();
+    sha.update(key.trim().as_bytes());
+    sha.update(WEBSOCKET_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(sha.finalize())
+}
+
+/// An unmasked server-to-client frame with the given opcode (0x1 text, 0x9 ping)
+pub fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Whether an `Origin` header names a page served from this machine
+fn is_local_origin(origin: &str) -> bool {
+    reqwest::Url::parse(origin).is_ok_and(|url| {
+        matches!(url.scheme(), "http" | "https")
+            && matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"))
+    })
+}
+
+/// Whether a `Host` header names this machine, with or without a port. Other
+/// names are refused so a rebound DNS name can't reach the server.
+fn is_local_host(host: &str) -> bool {
+    let name = host.rsplit_once(':').map_or(host, |(name, port)| {
+        if port.chars().all(|c| c.is_ascii_digit()) { name } else { host }
+    });
+    matches!(name.to_ascii_lowercase().as_str(), "localhost" | "127.0.0.1")
+}
+
+/// Route a request line by its headers (lowercased names). No token: the
+/// server only listens on localhost, requests must name it in `Host`, and
+/// WebSocket upgrades from pages on other sites are refused.
+pub fn route(request_line: &str, headers: &HashMap<String, String>) -> Route {
+    if !headers.get("host").is_some_and(|host| is_local_host(host)) {
+        return Route::Http(Response::text("403 Forbidden", "Only localhost can connect"));
+    }
+    let websocket_key = headers.get("sec-websocket-key").map(String::as_str);
+    let origin = headers.get("origin");
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Route::Http(Response::text("400 Bad Request", "Bad request"));
+    };
+    if method != "GET" {
+        return Route::Http(Response::text("405 Method Not Allowed", "Read-only"));
+    }
+
+    let path = target.split_once('?').map_or(target, |(path, _)| path);
+    match (path, websocket_key) {
+        ("/ws", Some(_)) if origin.is_some_and(|origin| !is_local_origin(origin)) => {
+            Route::Http(Response::text("403 Forbidden", "Only local pages can connect"))
+        }
+        ("/ws", Some(key)) => Route::WebSocket(accept_key(key)),
+        ("/ws", None) => Route::Http(Response::text("426 Upgrade Required", "Connect with a WebSocket")),
+        ("/", _) => Route::Http(Response {
+            status: "200 OK",
+            content_type: "text/html; charset=utf-8",
+            body: OVERLAY_PAGE.to_string(),
+        }),
+        ("/state.json", _) => Route::Http(Response {
+            status: "200 OK",
+            content_type: "application/json",
+            body: latest().unwrap_or_else(|| "null".to_string()),
+        }),
+        _ => Route::Http(Response::text("404 Not Found", "Not found")),
+    }
+}
+
+/// Start serving on localhost, replacing any running server. Returns the bound port.
+pub fn start(port: u16) -> std::io::Result<u16> {
+    stop();
+
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    listener.set_nonblocking(true)?;
+    let bound_port = listener.local_addr()?.port();
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let running = stop_flag.clone();
+    let handle = thread::spawn(move || {
+        while !running.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    // WebSocket connections stay open, so each gets its own thread
+                    let stop_flag = running.clone();
+                    thread::spawn(move || {
+                        if let Err(e) = handle_connection(stream, &stop_flag) {
+                            eprintln!("[obs] Connection failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => {
+                    eprintln!("[obs] Accept failed: {}", e);
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+    });
+
+    if let Ok(mut server) = SERVER.lock() {
+        *server = Some((stop_flag, handle));
+    }
+    Ok(bound_port)
+}
+
+/// Stop the running server, if any, waiting for its accept thread so the port
+/// is free again once this returns. Open WebSocket connections close within a second.
+pub fn stop() {
+    if let Some((stop_flag, handle)) = SERVER.lock().ok().and_then(|mut s| s.take()) {
+        stop_flag.store(true, Ordering::Relaxed);
+        let _ = handle.join();
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, stop_flag: &AtomicBool) -> std::io::Result<()> {
+    // Accepted sockets can inherit non-blocking mode from the listener
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+    let (request_line, headers) = read_head(&mut BufReader::new(&stream))?;
+
+    // No CORS header: only the overlay page, served from here, reads the state
+    match route(&request_line, &headers) {
+        Route::Http(response) => {
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+                response.status,
+                response.content_type,
+                response.body.len(),
+                response.body
+            )?;
+            stream.flush()
+        }
+        Route::WebSocket(accept) => {
+            write!(
+                stream,
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                accept
+            )?;
+            let (sender, receiver) = mpsc::channel();
+            if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+                subscribers.push(sender);
+            }
+            push_states(stream, receiver, stop_flag)
+        }
+    }
+}
+
+/// Write the latest state, then every published one, until the browser
+/// source goes away or the server stops. Nothing the client sends is read.
+fn push_states(mut stream: TcpStream, receiver: Receiver<String>, stop_flag: &AtomicBool) -> std::io::Result<()> {
+    if let Some(state) = latest() {
+        stream.write_all(&frame(0x1, state.as_bytes()))?;
+    }
+    let mut idle = Duration::ZERO;
+    while !stop_flag.load(Ordering::Relaxed) {
+        match receiver.recv_timeout(Duration::from_secs(1)) {
+            Ok(state) => {
+                stream.write_all(&frame(0x1, state.as_bytes()))?;
+                idle = Duration::ZERO;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                idle += Duration::from_secs(1);
+                if idle >= PING_INTERVAL {
+                    stream.write_all(&frame(0x9, &[]))?;
+                    idle = Duration::ZERO;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    // Close frame, status 1001 (going away)
+    let _ = stream.write_all(&frame(0x8, &1001u16.to_be_bytes()));
+    Ok(())
+}
+
+/// Browser source page: the timer, zone, and last split, pushed over the
+/// WebSocket on a transparent background
+const OVERLAY_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>POE Watcher Overlay</title>
+<style>
+  html, body { margin: 0; background: transparent; color: #e8e0d0; font-family: system-ui, sans-serif; }
+  body { padding: 12px; text-shadow: 0 0 4px #000, 0 0 2px #000; }
+  .timer { font-size: 64px; font-weight: 600; font-variant-numeric: tabular-nums; }
+  .zone { font-size: 20px; color: #c8bfaf; }
+  .split { font-size: 24px; margin-top: 4px; }
+  .ahead_gaining { color: #22c55e; } .ahead_losing { color: #86efac; }
+  .behind_gaining { color: #fca5a5; } .behind_losing { color: #ef4444; } .gold { color: #fbbf24; }
+  .next { font-size: 16px; color: #a89f8f; }
+</style>
+</head>
+<body>
+<div class="timer" id="timer">0:00</div>
+<div class="zone" id="zone"></div>
+<div class="split" id="split"></div>
+<div class="next" id="next"></div>
+<script>
+  const fmt = (ms, signed) => {
+    const sign = signed ? (ms < 0 ? '-' : '+') : (ms < 0 ? '-' : '');
+    ms = Math.abs(ms);
+    const s = Math.floor(ms / 1000), h = Math.floor(s / 3600), m = Math.floor(s / 60) % 60;
+    const pad = (n) => String(n).padStart(2, '0');
+    return sign + (h ? h + ':' + pad(m) : m) + ':' + pad(s % 60);
+  };
+  const text = (id, value) => { document.getElementById(id).textContent = value; };
+  let state = null, receivedAt = 0;
+  function render() {
+    if (!state) return;
+    text('zone', state.currentZone || '');
+    const split = document.getElementById('split');
+    const last = state.lastSplit;
+    split.className = 'split' + (last && last.color ? ' ' + last.color : '');
+    split.textContent = last ? last.name + (last.deltaMs != null ? '  ' + fmt(last.deltaMs, true) : '') : '';
+    const next = state.upcomingBreakpoints && state.upcomingBreakpoints[0];
+    text('next', next ? 'Next: ' + next.name : '');
+  }
+  function tick() {
+    if (!state) return;
+    const elapsed = state.elapsedMs + (state.isRunning ? Date.now() - receivedAt : 0);
+    text('timer', fmt(elapsed));
+  }
+  function connect() {
+    const socket = new WebSocket('ws://' + location.host + '/ws');
+    socket.onmessage = (event) => {
+      const data = JSON.parse(event.data);
+      if (data) { state = data; receivedAt = Date.now(); render(); }
+    };
+    socket.onclose = () => setTimeout(connect, 2000);
+  }
+  connect();
+  setInterval(tick, 100);
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_handshake_and_frames() {
+        // Example from RFC 6455 section 1.3
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+        assert_eq!(frame(0x1, b"hi"), vec![0x81, 2, b'h', b'i']);
+        let medium = frame(0x1, &[b'x'; 300]);
+        assert_eq!(&medium[..4], &[0x81, 126, 1, 44]);
+        assert_eq!(medium.len(), 304);
+        let large = frame(0x1, &vec![b'x'; 70_000]);
+        assert_eq!(&large[..2], &[0x81, 127]);
+        assert_eq!(large.len(), 70_010);
+    }
+
+    #[test]
+    fn test_routes() {
+        let headers = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+        };
+        let local = headers(&[("host", "127.0.0.1:8736")]);
+        let upgrade = |origin: Option<&str>| {
+            let mut upgrade = headers(&[("host", "localhost:8736"), ("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")]);
+            upgrade.extend(origin.map(|origin| ("origin".to_string(), origin.to_string())));
+            route("GET /ws HTTP/1.1", &upgrade)
+        };
+        assert!(matches!(upgrade(None), Route::WebSocket(_)));
+        let status = |line: &str| match route(line, &local) {
+            Route::Http(response) => response.status,
+            Route::WebSocket(_) => "101",
+        };
+        assert_eq!(status("GET / HTTP/1.1"), "200 OK");
+        assert_eq!(status("GET /state.json HTTP/1.1"), "200 OK");
+        assert_eq!(status("GET /ws HTTP/1.1"), "426 Upgrade Required");
+        assert_eq!(status("GET /nope HTTP/1.1"), "404 Not Found");
+        assert_eq!(status("POST / HTTP/1.1"), "405 Method Not Allowed");
+
+        // Browser sources load the page from this server; other sites can't subscribe
+        for origin in ["http://127.0.0.1:8736", "http://localhost:8736", "http://[::1]:8736"] {
+            assert!(matches!(upgrade(Some(origin)), Route::WebSocket(_)), "{}", origin);
+        }
+        for origin in ["https://evil.example", "http://localhost.evil.example", "null"] {
+            assert!(matches!(upgrade(Some(origin)), Route::Http(r) if r.status == "403 Forbidden"), "{}", origin);
+        }
+
+        // A page on a rebound DNS name reaches 127.0.0.1 with its own name as the Host
+        for host in ["localhost", "LOCALHOST:8736", "127.0.0.1"] {
+            assert!(matches!(route("GET /state.json HTTP/1.1", &headers(&[("host", host)])), Route::Http(r) if r.status == "200 OK"), "{}", host);
+        }
+        for host in [None, Some("evil.example:8736"), Some("localhost.evil.example"), Some("127.0.0.1.nip.io")] {
+            let request = headers(&host.map(|host| vec![("host", host)]).unwrap_or_default());
+            assert!(matches!(route("GET /state.json HTTP/1.1", &request), Route::Http(r) if r.status == "403 Forbidden"), "{:?}", host);
+        }
+    }
+}
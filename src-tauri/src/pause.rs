@@ -0,0 +1,118 @@
+use serde::Serialize;
+
+use crate::breakpoint_engine;
+use crate::db::{Pause, Settings};
+use crate::error::{AppError, AppResult};
+use crate::log_watcher::LogEvent;
+
+/// Why a run's timer was paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseReason {
+    /// The timer controls or `pause_run`
+    Manual,
+    /// AFK mode came on
+    Afk,
+    /// The client lost the server or logged out
+    Disconnect,
+    /// Another character leveled up and `reassign_run_character` was told to pause
+    CharacterMismatch,
+}
+
+impl PauseReason {
+    /// The value stored in `pauses.reason`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PauseReason::Manual => "manual",
+            PauseReason::Afk => "afk",
+            PauseReason::Disconnect => "disconnect",
+            PauseReason::CharacterMismatch => "character_mismatch",
+        }
+    }
+}
+
+/// Payload of `run-paused` and `run-resumed`: the frontend moves its timer to
+/// the backend's run clock
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PauseChanged {
+    pub run_id: i64,
+    pub paused: bool,
+    pub elapsed_ms: i64,
+    /// Why it paused; `None` on resume
+    pub reason: Option<PauseReason>,
+}
+
+impl PauseChanged {
+    pub fn event_name(&self) -> &'static str {
+        if self.paused { "run-paused" } else { "run-resumed" }
+    }
+}
+
+/// The run clock of `run_id` and whether it's running, if it's the run being timed
+fn clock_of(run_id: i64) -> AppResult<(bool, i64)> {
+    match breakpoint_engine::active_clock() {
+        Some((active, running, elapsed_ms)) if active == run_id => Ok((running, elapsed_ms)),
+        _ => Err(AppError::InvalidInput(format!("Run {} isn't being timed", run_id))),
+    }
+}
+
+/// The run being timed and its clock as of now, in the `run-paused` /
+/// `run-resumed` shape (without a reason), for a frontend catching up
+pub fn current() -> Option<PauseChanged> {
+    let (run_id, running, elapsed_ms) = breakpoint_engine::active_clock()?;
+    Some(PauseChanged { run_id, paused: !running, elapsed_ms, reason: None })
+}
+
+/// Pause the timer of the run being timed. `None` if it was paused already.
+pub fn pause(run_id: i64, reason: PauseReason) -> AppResult<Option<PauseChanged>> {
+    let (running, elapsed_ms) = clock_of(run_id)?;
+    if !running {
+        return Ok(None);
+    }
+    breakpoint_engine::set_running_for(false, elapsed_ms, reason)?;
+    Ok(Some(PauseChanged { run_id, paused: true, elapsed_ms, reason: Some(reason) }))
+}
+
+/// Resume the timer of the run being timed. `None` if it was running.
+pub fn resume(run_id: i64) -> AppResult<Option<PauseChanged>> {
+    let (running, elapsed_ms) = clock_of(run_id)?;
+    if running {
+        return Ok(None);
+    }
+    breakpoint_engine::set_running_for(true, elapsed_ms, PauseReason::Manual)?;
+    Ok(Some(PauseChanged { run_id, paused: false, elapsed_ms, reason: None }))
+}
+
+/// With `auto_pause` on, pause the running timer on AFK and disconnect lines.
+/// A pause they made ends when AFK mode goes off, or for a disconnect when the
+/// character enters a zone again; pauses from the timer are left alone.
+pub fn auto_pause_for(event: &LogEvent) -> AppResult<Option<PauseChanged>> {
+    let reason = match event {
+        LogEvent::Afk { enabled: true, .. } => Some(PauseReason::Afk),
+        LogEvent::Disconnect { .. } => Some(PauseReason::Disconnect),
+        LogEvent::Afk { enabled: false, .. } | LogEvent::ZoneEnter { .. } => None,
+        _ => return Ok(None),
+    };
+    let Some((run_id, running, _)) = breakpoint_engine::active_clock() else {
+        return Ok(None);
+    };
+    if !Settings::load()?.auto_pause {
+        return Ok(None);
+    }
+
+    match reason {
+        Some(reason) if running => pause(run_id, reason),
+        Some(_) => Ok(None),
+        None if running => Ok(None),
+        None => {
+            let ends = match event {
+                LogEvent::Afk { .. } => PauseReason::Afk,
+                _ => PauseReason::Disconnect,
+            };
+            match Pause::open(run_id)? {
+                Some(open) if open.reason == ends.as_str() => resume(run_id),
+                _ => Ok(None),
+            }
+        }
+    }
+}
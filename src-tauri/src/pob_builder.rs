@@ -0,0 +1,565 @@
+use base64::Engine;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use crate::api_client::{PassiveSkills, PoeItem};
+use crate::db::{Run, Snapshot};
+use crate::error::{AppError, AppResult};
+
+/// Inventory IDs of equipped items and the PoB slots they go in
+pub(crate) const SLOT_NAMES: &[(&str, &str)] = &[
+    ("Weapon", "Weapon 1"),
+    ("Weapon2", "Weapon 1 Swap"),
+    ("Offhand", "Weapon 2"),
+    ("Offhand2", "Weapon 2 Swap"),
+    ("Helm", "Helmet"),
+    ("BodyArmour", "Body Armour"),
+    ("Gloves", "Gloves"),
+    ("Boots", "Boots"),
+    ("Belt", "Belt"),
+    ("Amulet", "Amulet"),
+    ("Ring", "Ring 1"),
+    ("Ring2", "Ring 2"),
+    ("Flask", "Flask 1"),
+    ("Flask2", "Flask 2"),
+    ("Flask3", "Flask 3"),
+    ("Flask4", "Flask 4"),
+    ("Flask5", "Flask 5"),
+];
+
+/// Slots listed in every ItemSet; pobb.in rejects sets that leave any out
+const ITEM_SET_SLOTS: &[&str] = &[
+    "Weapon 1", "Weapon 2", "Helmet", "Body Armour", "Gloves", "Boots",
+    "Belt", "Amulet", "Ring 1", "Ring 2", "Ring 3",
+    "Flask 1", "Flask 2", "Flask 3", "Flask 4", "Flask 5",
+    "Weapon 1 Swap", "Weapon 2 Swap",
+    "Graft 1", "Graft 2",
+];
+
+/// Gear with abyssal sockets, each listed with sockets 1-6 after `ITEM_SET_SLOTS`
+const ABYSSAL_SLOTS: &[&str] = &[
+    "Weapon 1", "Weapon 2", "Weapon 1 Swap", "Weapon 2 Swap", "Helmet", "Body Armour", "Gloves", "Boots", "Belt",
+];
+
+/// Auras and reservations, which only win the main socket group on a tie
+const AURA_GEMS: &[&str] = &[
+    "Haste", "Hatred", "Wrath", "Anger", "Grace", "Determination",
+    "Clarity", "Precision", "Vitality", "Discipline", "Malevolence",
+    "Zealotry", "Pride", "Petrified Blood", "Tempest Shield", "Arctic Armour",
+    "Blood and Sand", "Flesh and Stone",
+    "Defiance Banner", "Dread Banner", "War Banner",
+    "Herald of Ash", "Herald of Ice", "Herald of Thunder", "Herald of Purity",
+    "Herald of Agony",
+    "Purity of Fire", "Purity of Ice", "Purity of Lightning", "Purity of Elements",
+];
+
+/// Gems whose PoB skill/gem IDs aren't the PascalCase of their name:
+/// (name, skillId, gemId)
+const GEM_IDS: &[(&str, &str, &str)] = &[
+    // Marks / Curses (internal names differ from display names)
+    ("Assassin's Mark", "AssassinsMark", "Metadata/Items/Gems/SkillGemCriticalWeakness"),
+    ("Sniper's Mark", "SnipersMarkRegular", "Metadata/Items/Gems/SkillGemProjectileWeakness"),
+    ("Poacher's Mark", "PoachersMark", "Metadata/Items/Gems/SkillGemPoachersMark"),
+    ("Warlord's Mark", "WarlordsMark", "Metadata/Items/Gems/SkillGemWarlordsMark"),
+
+    // Auras / Heralds
+    ("Herald of Ice", "HeraldOfIce", "Metadata/Items/Gems/SkillGemHeraldOfIce"),
+    ("Herald of Ash", "HeraldOfAsh", "Metadata/Items/Gems/SkillGemHeraldOfAsh"),
+    ("Herald of Thunder", "HeraldOfThunder", "Metadata/Items/Gems/SkillGemHeraldOfThunder"),
+    ("Herald of Purity", "HeraldOfPurity", "Metadata/Items/Gems/SkillGemHeraldOfPurity"),
+    ("Herald of Agony", "HeraldOfAgony", "Metadata/Items/Gems/SkillGemHeraldOfAgony"),
+    ("Purity of Fire", "PurityOfFire", "Metadata/Items/Gems/SkillGemFireResistAura"),
+    ("Purity of Ice", "PurityOfIce", "Metadata/Items/Gems/SkillGemColdResistAura"),
+    ("Purity of Lightning", "PurityOfLightning", "Metadata/Items/Gems/SkillGemLightningResistAura"),
+    ("Purity of Elements", "PurityOfElements", "Metadata/Items/Gems/SkillGemPurityOfElements"),
+    ("Precision", "Precision", "Metadata/Items/Gems/SkillGemPrecision"),
+    ("Wrath", "Wrath", "Metadata/Items/Gems/SkillGemWrath"),
+
+    // Buffs / Guard / Utility
+    ("Blood Rage", "BloodRage", "Metadata/Items/Gems/SkillGemNewBloodRage"),
+    ("Blood and Sand", "BloodSandStance", "Metadata/Items/Gems/SkillGemBloodSandArmour"),
+    ("Flesh and Stone", "FleshAndStone", "Metadata/Items/Gems/SkillGemBloodSandArmour2"),
+    ("Steelskin", "QuickGuard", "Metadata/Items/Gems/SkillGemSteelskin"),
+    ("Immortal Call", "ImmortalCall", "Metadata/Items/Gems/SkillGemImmortalCall"),
+    ("Molten Shell", "MoltenShell", "Metadata/Items/Gems/SkillGemMoltenShell"),
+
+    // Movement
+    ("Leap Slam", "LeapSlam", "Metadata/Items/Gems/SkillGemLeapSlam"),
+    ("Frostblink", "Frostblink", "Metadata/Items/Gems/SkillGemFrostblink"),
+    ("Flame Dash", "FlameDash", "Metadata/Items/Gems/SkillGemFlameDash"),
+    ("Shield Charge", "ShieldCharge", "Metadata/Items/Gems/SkillGemShieldCharge"),
+    ("Whirling Blades", "WhirlingBlades", "Metadata/Items/Gems/SkillGemWhirlingBlades"),
+
+    // Attack skills
+    ("Rain of Arrows", "RainOfArrows", "Metadata/Items/Gems/SkillGemRainOfArrows"),
+    ("Vaal Rain of Arrows", "RainOfArrows", "Metadata/Items/Gems/SkillGemVaalRainOfArrows"),
+    ("Artillery Ballista", "ArtilleryBallista", "Metadata/Items/Gems/SkillGemArtilleryBallista"),
+
+    // Support gems (internal names differ from display names)
+    ("Lifetap Support", "SupportLifetap", "Metadata/Items/Gems/SupportGemLifetap"),
+    ("Mark On Hit Support", "SupportMarkOnHit", "Metadata/Items/Gems/SupportGemMarkOnHit"),
+    ("Faster Attacks Support", "SupportFasterAttacks", "Metadata/Items/Gems/SupportGemFasterAttack"),
+    ("Momentum Support", "SupportMomentum", "Metadata/Items/Gems/SupportGemOnslaught"),
+    ("Automation Support", "Automation", "Metadata/Items/Gems/SkillGemAutomation"),
+    ("Empower Support", "SupportEmpower", "Metadata/Items/Gems/SupportGemAdditionalLevel"),
+    ("Enhance Support", "SupportEnhance", "Metadata/Items/Gems/SupportGemAdditionalQuality"),
+    ("Enlighten Support", "SupportEnlighten", "Metadata/Items/Gems/SupportGemReducedManaCost"),
+    ("Trinity Support", "SupportTrinity", "Metadata/Items/Gems/SupportGemTrinity"),
+    ("Added Cold Damage Support", "SupportAddedColdDamage", "Metadata/Items/Gems/SupportGemAddedColdDamage"),
+    ("Added Fire Damage Support", "SupportAddedFireDamage", "Metadata/Items/Gems/SupportGemAddedFireDamage"),
+    ("Added Lightning Damage Support", "SupportAddedLightningDamage", "Metadata/Items/Gems/SupportGemAddedLightningDamage"),
+    ("Elemental Damage with Attacks Support", "SupportWeaponElementalDamage", "Metadata/Items/Gems/SupportGemWeaponElementalDamage"),
+    ("Multistrike Support", "SupportMultistrike", "Metadata/Items/Gems/SupportGemMultistrike"),
+    ("Volatility Support", "SupportVolatility", "Metadata/Items/Gems/SupportGemVolatility"),
+    ("Mirage Archer Support", "SupportMirageArcher", "Metadata/Items/Gems/SupportGemMirageArcher"),
+    ("Cast when Damage Taken Support", "SupportCastOnDamageTaken", "Metadata/Items/Gems/SupportGemCastOnDamageTaken"),
+    ("Cast on Critical Strike Support", "SupportCastOnCrit", "Metadata/Items/Gems/SupportGemCastOnCrit"),
+    ("Increased Critical Damage Support", "SupportIncreasedCriticalDamage", "Metadata/Items/Gems/SupportGemIncreasedCriticalDamage"),
+    ("Increased Critical Strikes Support", "SupportIncreasedCriticalStrikes", "Metadata/Items/Gems/SupportGemIncreasedCriticalStrikes"),
+
+    // Transfigured gems
+    ("Smite of Divine Judgement", "Smite", "Metadata/Items/Gems/SkillGemSmite"),];
+
+const RARITIES: &[&str] = &["NORMAL", "MAGIC", "RARE", "UNIQUE", "GEM", "CURRENCY", "DIVINATION", "QUEST", "PROPHECY", "FOIL"];
+
+const CLASSES: &[&str] = &["Scion", "Marauder", "Ranger", "Witch", "Duelist", "Templar", "Shadow"];
+
+/// Ascendancies by base class, in PoB's order (Warden replaced Raider as Ranger's first)
+const ASCENDANCIES: &[(&str, &[&str])] = &[
+    ("Scion", &["Ascendant"]),
+    ("Marauder", &["Juggernaut", "Berserker", "Chieftain"]),
+    ("Ranger", &["Warden", "Deadeye", "Pathfinder"]),
+    ("Witch", &["Necromancer", "Elementalist", "Occultist"]),
+    ("Duelist", &["Slayer", "Gladiator", "Champion"]),
+    ("Templar", &["Inquisitor", "Hierophant", "Guardian"]),
+    ("Shadow", &["Assassin", "Saboteur", "Trickster"]),
+];
+
+/// Base class and ascendancy a class name from the game stands for. The log
+/// reports ascended characters by their ascendancy, so `name` may be one.
+pub(crate) fn parse_class(name: &str) -> Option<(&'static str, Option<&'static str>)> {
+    if let Some(base) = CLASSES.iter().find(|c| **c == name) {
+        return Some((base, None));
+    }
+    let name = if name == "Raider" { "Warden" } else { name };
+    ASCENDANCIES
+        .iter()
+        .find_map(|(base, names)| names.iter().find(|a| **a == name).map(|a| (*base, Some(*a))))
+}
+
+/// Whether a character of `class` is one a filter for `wanted` lists: a base
+/// class matches its ascendancies, anything else only itself
+pub(crate) fn class_matches(wanted: &str, class: &str) -> bool {
+    match parse_class(wanted) {
+        Some((base, None)) => parse_class(class).is_some_and(|(b, _)| b == base),
+        _ => class.eq_ignore_ascii_case(wanted),
+    }
+}
+
+/// Base class and ascendancy for the build. The log reports ascended characters
+/// by their ascendancy, so `class` may be one.
+fn class_and_ascendancy(class: &str, ascendancy: Option<&str>) -> (&'static str, Option<&'static str>) {
+    let base_of = |name: &str| parse_class(name).and_then(|(base, ascendancy)| Some((base, ascendancy?)));
+    if let Some(base) = CLASSES.iter().find(|c| **c == class) {
+        let ascendancy = ascendancy.and_then(base_of).filter(|(b, _)| b == base).map(|(_, a)| a);
+        return (base, ascendancy);
+    }
+    match base_of(class).or_else(|| ascendancy.and_then(base_of)) {
+        Some((base, ascendancy)) => (base, Some(ascendancy)),
+        None => ("Scion", None),
+    }
+}
+
+fn class_id(class: &str) -> usize {
+    CLASSES.iter().position(|c| *c == class).unwrap_or(0)
+}
+
+/// 1-based position of the ascendancy within its class, 0 for none
+fn ascendancy_id(class: &str, ascendancy: Option<&str>) -> usize {
+    ASCENDANCIES
+        .iter()
+        .find(|(base, _)| *base == class)
+        .and_then(|(_, names)| names.iter().position(|a| Some(*a) == ascendancy))
+        .map_or(0, |i| i + 1)
+}
+
+/// PoB skill and gem IDs for a gem name
+fn gem_ids(name: &str) -> (String, String) {
+    if let Some((_, skill_id, gem_id)) = GEM_IDS.iter().find(|(gem, _, _)| *gem == name) {
+        return (skill_id.to_string(), gem_id.to_string());
+    }
+    let pascal: String = name
+        .trim_end_matches(" Support")
+        .replace('\'', "")
+        .split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect();
+    if name.contains("Support") {
+        (format!("Support{}", pascal), format!("Metadata/Items/Gems/SupportGem{}", pascal))
+    } else {
+        (pascal.clone(), format!("Metadata/Items/Gems/SkillGem{}", pascal))
+    }
+}
+
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Reverse of `escape_xml`, for text read from `.lss` and PoB files
+pub(crate) fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// First value of a property as text (values are `[value, display mode]` pairs)
+fn property_text(item: &PoeItem, name: &str) -> Option<String> {
+    let property = item.properties.iter().find(|p| p.name == name)?;
+    match property.values.first()?.get(0)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// PoB slot of an equipped item, e.g. "Ring 2" or "Flask 3"; None for
+/// inventory and stash items
+pub(crate) fn slot_name(item: &PoeItem) -> Option<String> {
+    // The API reports every flask as "Flask"; its x position is the slot
+    match (item.inventory_id.as_str(), item.x) {
+        ("Flask", Some(x)) => Some(format!("Flask {}", x + 1)),
+        (id, _) => SLOT_NAMES.iter().find(|(inventory, _)| *inventory == id).map(|(_, slot)| slot.to_string()),
+    }
+}
+
+pub(crate) fn property_number(item: &PoeItem, name: &str) -> Option<u32> {
+    let digits: String = property_text(item, name)?.chars().filter(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// Sockets as PoB writes them: linked colours joined by `-`, groups by spaces
+fn format_sockets(item: &PoeItem) -> String {
+    let mut groups: BTreeMap<u32, Vec<&str>> = BTreeMap::new();
+    for socket in &item.sockets {
+        let colour = match socket.attr.as_str() {
+            "S" => "R",
+            "D" => "G",
+            "I" => "B",
+            "A" => "A",
+            _ => "W",
+        };
+        groups.entry(socket.group).or_default().push(colour);
+    }
+    groups.values().map(|colours| colours.join("-")).collect::<Vec<_>>().join(" ")
+}
+
+/// Item text in PoB's import format
+fn item_text(item: &PoeItem) -> String {
+    let mut lines = vec![format!("Rarity: {}", RARITIES[(item.frame_type as usize).min(RARITIES.len() - 1)])];
+
+    let name = strip_tags(&item.name);
+    if !name.is_empty() {
+        lines.push(name);
+    }
+    if !item.type_line.is_empty() {
+        lines.push(item.type_line.clone());
+    }
+
+    // Base defences come before the unique ID for pobb.in
+    for property in &item.properties {
+        let is_defence = ["Armour", "Evasion", "Evasion Rating", "Energy Shield", "Ward"]
+            .iter()
+            .any(|stat| property.name.contains(stat));
+        if let Some(value) = property_text(item, &property.name).filter(|_| is_defence) {
+            let stat = if property.name == "Evasion Rating" { "Evasion" } else { property.name.as_str() };
+            lines.push(format!("{}: {}", stat, value));
+            lines.push(format!("{}BasePercentile: 0.5", stat));
+        }
+    }
+
+    if !item.id.is_empty() {
+        lines.push(format!("Unique ID: {}", item.id));
+    }
+    if item.item_level > 0 {
+        lines.push(format!("Item Level: {}", item.item_level));
+    }
+    lines.push("Quality: 0".to_string());
+
+    let sockets = format_sockets(item);
+    if !sockets.is_empty() {
+        lines.push(format!("Sockets: {}", sockets));
+    }
+
+    // The API doesn't report requirements here; estimate from item level
+    let level_req = if item.item_level > 0 { (item.item_level as i64 - 10).clamp(1, 70) } else { 1 };
+    lines.push(format!("LevelReq: {}", level_req));
+
+    lines.push(format!("Implicits: {}", item.implicit_mods.len()));
+    lines.extend(item.implicit_mods.iter().cloned());
+    lines.extend(item.explicit_mods.iter().cloned());
+    lines.join("\n")
+}
+
+/// Drop the `<<set:MS>>`-style markup the API puts in item names
+fn strip_tags(name: &str) -> String {
+    let mut out = String::new();
+    let mut rest = name;
+    while let Some(start) = rest.find("<<") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start..].find(">>") {
+            Some(end) => &rest[start + end + 2..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+/// SkillSet XML from socketed gems, and the 1-based main socket group: the
+/// group with the most supports, preferring non-aura groups on a tie
+fn skills_xml(items: &[&PoeItem]) -> (String, usize) {
+    let mut groups = Vec::new();
+    let mut main_group = 1;
+    let mut best_score = -1;
+
+    for item in items.iter().filter(|item| !item.socketed_items.is_empty()) {
+        let mut support_count = 0;
+        let mut active = Vec::new();
+        let gems: Vec<String> = item
+            .socketed_items
+            .iter()
+            .map(|gem| {
+                let level = property_number(gem, "Level").filter(|l| *l > 0).unwrap_or(20);
+                let quality = property_number(gem, "Quality").unwrap_or(0);
+                let name = if gem.type_line.is_empty() { "Unknown Gem" } else { gem.type_line.as_str() };
+                if name.contains("Support") {
+                    support_count += 1;
+                } else {
+                    active.push(name);
+                }
+                let (skill_id, gem_id) = gem_ids(name);
+                format!(
+                    "\t\t\t\t<Gem qualityId=\"Default\" enabled=\"true\" skillId=\"{}\" quality=\"{}\" gemId=\"{}\" nameSpec=\"{}\" level=\"{}\" enableGlobal1=\"true\"/>",
+                    skill_id, quality, gem_id, escape_xml(name), level
+                )
+            })
+            .collect();
+
+        let is_aura_only = !active.is_empty() && active.iter().all(|name| AURA_GEMS.contains(name));
+        let score = support_count * 10 + if is_aura_only { 0 } else { 1 };
+        if score > best_score {
+            best_score = score;
+            main_group = groups.len() + 1;
+        }
+
+        let slot = SLOT_NAMES
+            .iter()
+            .filter(|(id, _)| !id.starts_with("Flask"))
+            .find(|(id, _)| *id == item.inventory_id)
+            .map_or(item.inventory_id.as_str(), |(_, slot)| slot);
+        groups.push(format!(
+            "\t\t\t<Skill mainActiveSkill=\"1\" enabled=\"true\" slot=\"{}\">\n{}\n\t\t\t</Skill>",
+            escape_xml(slot),
+            gems.join("\n")
+        ));
+    }
+
+    if groups.is_empty() {
+        return ("\t\t<SkillSet id=\"1\"/>".to_string(), 1);
+    }
+    (format!("\t\t<SkillSet id=\"1\">\n{}\n\t\t</SkillSet>", groups.join("\n")), main_group)
+}
+
+/// Path of Building XML for a character's equipped items and passive tree
+pub fn build_xml(items: &[PoeItem], passives: &PassiveSkills, level: i32, class: &str, ascendancy: Option<&str>) -> String {
+    let equipped: Vec<&PoeItem> = items
+        .iter()
+        .filter(|item| !item.inventory_id.is_empty() && !item.inventory_id.starts_with("Stash"))
+        .collect();
+
+    let mut items_xml = Vec::new();
+    let mut slot_items: BTreeMap<String, usize> = BTreeMap::new();
+    for (index, item) in equipped.iter().enumerate() {
+        let item_id = index + 1;
+        // pobb.in shows mod ranges from these
+        let mod_ranges: String = (1..=item.implicit_mods.len() + item.explicit_mods.len())
+            .map(|i| format!("\n\t\t\t<ModRange range=\"0.5\" id=\"{}\"/>", i))
+            .collect();
+        items_xml.push(format!(
+            "\t\t<Item id=\"{}\">\n{}{}\n\t\t</Item>",
+            item_id,
+            escape_xml(&item_text(item)),
+            mod_ranges
+        ));
+
+        if let Some(slot) = slot_name(item) {
+            slot_items.insert(slot, item_id);
+        }
+    }
+
+    let abyssal = ABYSSAL_SLOTS
+        .iter()
+        .flat_map(|slot| (1..=6).map(move |n| format!("{} Abyssal Socket {}", slot, n)));
+    let slots_xml: Vec<String> = ITEM_SET_SLOTS
+        .iter()
+        .map(|slot| slot.to_string())
+        .chain(abyssal)
+        .map(|slot| {
+            let item_id = slot_items.get(&slot).copied().unwrap_or(0);
+            format!("\t\t\t<Slot itemId=\"{}\" name=\"{}\" itemPbURL=\"\"/>", item_id, slot)
+        })
+        .collect();
+
+    let (skills, main_socket_group) = skills_xml(&equipped);
+    let (class, ascendancy) = class_and_ascendancy(class, ascendancy);
+    let nodes = passives.hashes.iter().map(|h| h.to_string()).collect::<Vec<_>>().join(",");
+    let nodes_attr = if nodes.is_empty() { String::new() } else { format!(" nodes=\"{}\"", nodes) };
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<PathOfBuilding>
+	<Build mainSocketGroup="{main_socket_group}" className="{class}" ascendClassName="{ascend_name}" pantheonMajorGod="None" pantheonMinorGod="None" characterLevelAutoMode="false" level="{level}" viewMode="ITEMS" targetVersion="3_0" bandit="None">
+		<PlayerStat stat="Life" value="1000"/>
+	</Build>
+	<Import>
+	</Import>
+	<Calcs>
+	</Calcs>
+	<Items showStatDifferences="true" activeItemSet="1" useSecondWeaponSet="false">
+{items}
+		<ItemSet id="1" useSecondWeaponSet="false">
+{slots}
+		</ItemSet>
+		<TradeSearchWeights/>
+	</Items>
+	<Skills defaultGemLevel="normalMaximum" defaultGemQuality="0" sortGemsByDPS="true" activeSkillSet="1">
+{skills}
+	</Skills>
+	<Tree activeSpec="1">
+		<Spec title="Default" classId="{class_id}" ascendClassId="{ascend_id}" treeVersion="3_27"{nodes_attr}>
+			<URL>https://www.pathofexile.com/passive-skill-tree/3.27.0/AAAA</URL>
+			<Sockets></Sockets>
+		</Spec>
+	</Tree>
+	<Notes>Exported from POE Watcher speedrun tracker</Notes>
+</PathOfBuilding>"#,
+        ascend_name = ascendancy.unwrap_or("None"),
+        items = items_xml.join("\n"),
+        slots = slots_xml.join("\n"),
+        class_id = class_id(class),
+        ascend_id = ascendancy_id(class, ascendancy),
+    )
+}
+
+/// PoB import code: zlib-compressed XML in URL-safe base64
+pub fn encode(xml: &str) -> AppResult<String> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(xml.as_bytes())?;
+    Ok(base64::engine::general_purpose::URL_SAFE.encode(encoder.finish()?))
+}
+
+/// PoB code for a snapshot, using its run for the class and ascendancy
+pub fn snapshot_code(snapshot: &Snapshot, run: &Run) -> AppResult<String> {
+    let items: Vec<PoeItem> = serde_json::from_str(&snapshot.items_json)
+        .map_err(|e| AppError::InvalidInput(format!("Snapshot {} items: {}", snapshot.id, e)))?;
+    let passives: PassiveSkills = serde_json::from_str(&snapshot.passive_tree_json)
+        .map_err(|e| AppError::InvalidInput(format!("Snapshot {} passives: {}", snapshot.id, e)))?;
+    let xml = build_xml(&items, &passives, snapshot.character_level, &run.class, run.ascendancy.as_deref());
+    encode(&xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::ZlibDecoder;
+    use std::io::Read;
+
+    fn decode(code: &str) -> String {
+        let bytes = base64::engine::general_purpose::URL_SAFE.decode(code).unwrap();
+        let mut xml = String::new();
+        ZlibDecoder::new(&bytes[..]).read_to_string(&mut xml).unwrap();
+        xml
+    }
+
+    fn item(json: serde_json::Value) -> PoeItem {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_class_and_ascendancy() {
+        assert_eq!(class_and_ascendancy("Witch", Some("Necromancer")), ("Witch", Some("Necromancer")));
+        assert_eq!(class_and_ascendancy("Necromancer", None), ("Witch", Some("Necromancer")));
+        assert_eq!(class_and_ascendancy("Unknown", Some("Raider")), ("Ranger", Some("Warden")));
+        assert_eq!(class_and_ascendancy("Unknown", None), ("Scion", None));
+        assert_eq!(parse_class("Templar"), Some(("Templar", None)));
+        assert_eq!(parse_class("Raider"), Some(("Ranger", Some("Warden"))));
+        assert_eq!(parse_class("Unknown"), None);
+        assert_eq!(ascendancy_id("Witch", Some("Occultist")), 3);
+        assert_eq!(class_id("Shadow"), 6);
+    }
+
+    #[test]
+    fn test_xml_escape_round_trips() {
+        let text = r#"Lioneye's Watch & "Act 1" <start>"#;
+        assert_eq!(escape_xml(text), "Lioneye&apos;s Watch &amp; &quot;Act 1&quot; &lt;start&gt;");
+        assert_eq!(unescape_xml(&escape_xml(text)), text);
+    }
+
+    #[test]
+    fn test_gem_ids() {
+        assert_eq!(gem_ids("Sniper's Mark").0, "SnipersMarkRegular");
+        assert_eq!(
+            gem_ids("Added Chaos Damage Support"),
+            ("SupportAddedChaosDamage".to_string(), "Metadata/Items/Gems/SupportGemAddedChaosDamage".to_string())
+        );
+        assert_eq!(gem_ids("Bane").1, "Metadata/Items/Gems/SkillGemBane");
+    }
+
+    #[test]
+    fn test_code_round_trips_to_pob_xml() {
+        let items = vec![
+            item(serde_json::json!({
+                "id": "abc",
+                "name": "<<set:MS>><<set:M>><<set:S>>Doom Song",
+                "typeLine": "Plank Kite Shield",
+                "inventoryId": "Offhand",
+                "frameType": 2,
+                "ilvl": 30,
+                "implicitMods": ["+4% to all Elemental Resistances"],
+                "explicitMods": ["+25 to maximum Life", "Adds 1 to 3 Fire & Cold Damage"],
+                "properties": [{ "name": "Evasion Rating", "values": [["64", 1]] }],
+                "sockets": [{ "group": 0, "attr": "S" }, { "group": 0, "attr": "I" }, { "group": 1, "attr": "D" }],
+                "socketedItems": [
+                    { "typeLine": "Raise Zombie", "properties": [{ "name": "Level", "values": [["12", 0]] }] },
+                    { "typeLine": "Minion Damage Support", "properties": [{ "name": "Quality", "values": [["+5%", 1]] }] }
+                ]
+            })),
+            item(serde_json::json!({ "typeLine": "Small Life Flask", "inventoryId": "Flask", "x": 2, "frameType": 0 })),
+            item(serde_json::json!({ "typeLine": "Scroll of Wisdom", "inventoryId": "MainInventory" })),
+        ];
+        let passives: PassiveSkills = serde_json::from_str(r#"{"hashes": [1, 22, 333]}"#).unwrap();
+
+        let xml = decode(&encode(&build_xml(&items, &passives, 28, "Necromancer", None)).unwrap());
+        assert!(xml.contains(r#"className="Witch" ascendClassName="Necromancer""#));
+        assert!(xml.contains(r#"level="28""#));
+        assert!(xml.contains("Rarity: RARE\nDoom Song\nPlank Kite Shield\nEvasion: 64\nEvasionBasePercentile: 0.5\nUnique ID: abc"));
+        assert!(xml.contains("Sockets: R-B G\nLevelReq: 20\nImplicits: 1"));
+        assert!(xml.contains("Adds 1 to 3 Fire &amp; Cold Damage"), "item text is escaped");
+        assert!(xml.contains(r#"<Slot itemId="1" name="Weapon 2" itemPbURL=""/>"#));
+        assert!(xml.contains(r#"<Slot itemId="2" name="Flask 3" itemPbURL=""/>"#));
+        assert!(xml.contains(r#"<Item id="3">"#), "inventory items are kept like the frontend export");
+        assert!(xml.contains(r#"skillId="RaiseZombie" quality="0" gemId="Metadata/Items/Gems/SkillGemRaiseZombie" nameSpec="Raise Zombie" level="12""#));
+        assert!(xml.contains(r#"skillId="SupportMinionDamage" quality="5""#));
+        assert!(xml.contains(r#"classId="3" ascendClassId="1" treeVersion="3_27" nodes="1,22,333""#));
+    }
+}
@@ -0,0 +1,324 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::api_client::{self, PassiveSkills, PoeItem, USER_AGENT};
+use crate::db::{ReferenceRunData, ReferenceSplitData};
+use crate::pob_builder;
+
+const NINJA_BASE: &str = "https://poe.ninja";
+
+/// Category of reference runs made from poe.ninja characters. They carry a
+/// build, not split times, so they're kept out of the speedrun categories'
+/// comparisons.
+pub const NINJA_CATEGORY: &str = "poe.ninja";
+
+/// Most characters `fetch_builds` returns
+const BUILD_LIMIT: usize = 50;
+
+/// A character on poe.ninja's build overview, as `fetch_builds` returns it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NinjaBuild {
+    /// Position on the overview, which lists characters by experience
+    pub rank: usize,
+    pub character_name: String,
+    pub account_name: String,
+    pub class: String,
+    pub level: u32,
+}
+
+/// `GET /api/data/getindexstate`: the build snapshots poe.ninja has
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexState {
+    #[serde(default)]
+    snapshot_versions: Vec<SnapshotVersion>,
+}
+
+/// A league's current build snapshot. Overviews and characters are read
+/// through its `version`, and `url` names the league in their queries.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotVersion {
+    #[serde(default)]
+    url: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    version: String,
+}
+
+/// `getbuildoverview`: one column per field, a row per character. `classes`
+/// indexes into `class_names`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildOverview {
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    accounts: Vec<String>,
+    #[serde(default)]
+    levels: Vec<u32>,
+    #[serde(default)]
+    classes: Vec<usize>,
+    #[serde(default)]
+    class_names: Vec<String>,
+}
+
+/// `getcharacter`: a character's gear and tree as poe.ninja last saw them.
+/// Items are the character-window API's, wrapped with their slot.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NinjaCharacter {
+    #[serde(default)]
+    pub account: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub league: String,
+    #[serde(default)]
+    pub level: u32,
+    /// Ascendancy once ascended, otherwise the base class
+    #[serde(default)]
+    pub class: String,
+    #[serde(default)]
+    items: Vec<NinjaItem>,
+    #[serde(default)]
+    flasks: Vec<NinjaItem>,
+    #[serde(default)]
+    passive_selection: Vec<u32>,
+    #[serde(default)]
+    path_of_building_export: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NinjaItem {
+    item_data: PoeItem,
+}
+
+impl NinjaCharacter {
+    /// Equipped items and flasks, as a snapshot's `items_json` holds them
+    pub fn items(&self) -> Vec<PoeItem> {
+        self.items.iter().chain(&self.flasks).map(|item| item.item_data.clone()).collect()
+    }
+
+    /// Allocated passives, as a snapshot's `passive_tree_json` holds them.
+    /// poe.ninja doesn't list cluster jewel nodes or mastery choices.
+    pub fn passives(&self) -> PassiveSkills {
+        PassiveSkills {
+            hashes: self.passive_selection.clone(),
+            hashes_ex: Vec::new(),
+            mastery_effects: Default::default(),
+        }
+    }
+
+    /// The Path of Building code poe.ninja exports for the character, if any
+    pub fn pob_code(&self) -> Option<String> {
+        let code = self.path_of_building_export.trim();
+        (!code.is_empty()).then(|| code.to_string())
+    }
+
+    /// A reference run holding the character's build: one "Level N" split,
+    /// untimed, for its snapshot to hang off
+    pub fn reference_run(&self) -> ReferenceRunData {
+        let (class, ascendancy) = match pob_builder::parse_class(&self.class) {
+            Some((base, ascendancy)) => (base.to_string(), ascendancy.map(|a| a.to_string())),
+            None => (self.class.clone(), None),
+        };
+        ReferenceRunData {
+            source_name: format!("poe.ninja {}", self.name),
+            character_name: Some(self.name.clone()),
+            class,
+            ascendancy,
+            category: NINJA_CATEGORY.to_string(),
+            league: (!self.league.is_empty()).then(|| self.league.clone()),
+            breakpoint_preset: None,
+            enabled_breakpoints: None,
+            total_time_ms: 0,
+            splits: vec![ReferenceSplitData {
+                breakpoint_name: format!("Level {}", self.level),
+                breakpoint_type: "level".to_string(),
+                breakpoint_key: Some(format!("level:{}", self.level)),
+                split_time_ms: 0,
+            }],
+        }
+    }
+}
+
+/// The overview's characters, optionally only those of `class` (a base class
+/// also matches its ascendancies), at most `BUILD_LIMIT`
+fn builds(overview: BuildOverview, class: Option<&str>) -> Vec<NinjaBuild> {
+    overview
+        .names
+        .into_iter()
+        .enumerate()
+        .map(|(i, character_name)| NinjaBuild {
+            rank: i + 1,
+            character_name,
+            account_name: overview.accounts.get(i).cloned().unwrap_or_default(),
+            class: overview
+                .classes
+                .get(i)
+                .and_then(|c| overview.class_names.get(*c))
+                .cloned()
+                .unwrap_or_default(),
+            level: overview.levels.get(i).copied().unwrap_or(0),
+        })
+        .filter(|build| class.is_none_or(|class| pob_builder::class_matches(class, &build.class)))
+        .take(BUILD_LIMIT)
+        .collect()
+}
+
+/// Client for poe.ninja's build pages' data. Unofficial and unversioned, so
+/// every field is optional and a league it has no builds for is an error.
+pub struct PoeNinjaClient {
+    client: Client,
+}
+
+impl PoeNinjaClient {
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+        PoeNinjaClient { client }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self.client.get(url).send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            anyhow::bail!("poe.ninja returned {}", status);
+        }
+        serde_json::from_str(&text).map_err(|e| {
+            anyhow::anyhow!("Failed to parse poe.ninja response: {} - Response: {}", e, api_client::excerpt(&text))
+        })
+    }
+
+    /// The league's current experience-ladder build snapshot
+    async fn snapshot(&self, league: &str) -> Result<SnapshotVersion> {
+        let index: IndexState = self.get(&format!("{}/api/data/getindexstate", NINJA_BASE)).await?;
+        index
+            .snapshot_versions
+            .into_iter()
+            .find(|s| s.kind == "exp" && (s.name.eq_ignore_ascii_case(league) || s.url.eq_ignore_ascii_case(league)))
+            .ok_or_else(|| anyhow::anyhow!("poe.ninja has no builds for league {}", league))
+    }
+
+    /// Top characters of a league's build overview, optionally of one class
+    pub async fn fetch_builds(&self, league: &str, class: Option<&str>) -> Result<Vec<NinjaBuild>> {
+        let snapshot = self.snapshot(league).await?;
+        let url = format!(
+            "{}/api/data/{}/getbuildoverview?overview={}&type=exp&language=en",
+            NINJA_BASE,
+            snapshot.version,
+            urlencoding::encode(&snapshot.url)
+        );
+        Ok(builds(self.get(&url).await?, class))
+    }
+
+    /// A character's gear and passives as poe.ninja last saw them
+    pub async fn fetch_character(&self, league: &str, account_name: &str, character_name: &str) -> Result<NinjaCharacter> {
+        let snapshot = self.snapshot(league).await?;
+        let url = format!(
+            "{}/api/data/{}/getcharacter?account={}&name={}&overview={}&type=exp&language=en",
+            NINJA_BASE,
+            snapshot.version,
+            urlencoding::encode(account_name),
+            urlencoding::encode(character_name),
+            urlencoding::encode(&snapshot.url)
+        );
+        let mut character: NinjaCharacter = self.get(&url).await?;
+        if character.league.is_empty() {
+            character.league = snapshot.name;
+        }
+        Ok(character)
+    }
+}
+
+impl Default for PoeNinjaClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builds_from_overview_columns() {
+        let overview: BuildOverview = serde_json::from_str(
+            r#"{
+                "names": ["Zoomer", "Tanky", "Minions"],
+                "accounts": ["Fast#0001", "Slow#0002", "Bones#0003"],
+                "levels": [100, 98, 97],
+                "classes": [1, 0, 2],
+                "classNames": ["Juggernaut", "Deadeye", "Necromancer"],
+                "life": [5000, 9000, 4000]
+            }"#,
+        )
+        .unwrap();
+        let all = builds(overview, None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(
+            all[0],
+            NinjaBuild {
+                rank: 1,
+                character_name: "Zoomer".to_string(),
+                account_name: "Fast#0001".to_string(),
+                class: "Deadeye".to_string(),
+                level: 100,
+            }
+        );
+
+        // A base class matches its ascendancies; ranks stay the overview's
+        let overview: BuildOverview = serde_json::from_str(
+            r#"{"names": ["Zoomer", "Minions"], "classes": [0, 1], "classNames": ["Deadeye", "Necromancer"]}"#,
+        )
+        .unwrap();
+        let witches = builds(overview, Some("Witch"));
+        assert_eq!(witches.len(), 1);
+        assert_eq!((witches[0].rank, witches[0].level), (2, 0));
+        assert!(builds(BuildOverview::default(), None).is_empty());
+    }
+
+    #[test]
+    fn test_character_as_reference_snapshot() {
+        let character: NinjaCharacter = serde_json::from_str(
+            r#"{
+                "account": "Bones#0003",
+                "name": "Minions",
+                "level": 92,
+                "class": "Necromancer",
+                "items": [{"itemSlot": 1, "itemData": {"name": "", "typeLine": "Driftwood Wand", "inventoryId": "Weapon"}}],
+                "flasks": [{"itemData": {"typeLine": "Divine Life Flask", "inventoryId": "Flask", "x": 0}}],
+                "jewels": [{"itemData": {"typeLine": "Cobalt Jewel", "inventoryId": "PassiveJewels"}}],
+                "passiveSelection": [4, 8, 15],
+                "pathOfBuildingExport": " eNrtfQ "
+            }"#,
+        )
+        .unwrap();
+
+        // Tree jewels aren't gear
+        let items = character.items();
+        let slots: Vec<&str> = items.iter().map(|i| i.inventory_id.as_str()).collect();
+        assert_eq!(slots, ["Weapon", "Flask"]);
+        assert_eq!(character.passives().hashes, [4, 8, 15]);
+        assert_eq!(character.pob_code().as_deref(), Some("eNrtfQ"));
+
+        let run = character.reference_run();
+        assert_eq!((run.class.as_str(), run.ascendancy.as_deref()), ("Witch", Some("Necromancer")));
+        assert_eq!(run.category, NINJA_CATEGORY);
+        assert_eq!(run.league, None);
+        assert_eq!(run.splits[0].breakpoint_key.as_deref(), Some("level:92"));
+    }
+}
@@ -0,0 +1,79 @@
+use serde::Serialize;
+
+use crate::api_client::{ApiError, PoeApi};
+use crate::error::{AppError, AppResult};
+
+/// Text the profile page shows in place of a private profile
+const PRIVATE_PROFILE_MARKER: &str = "profile is private";
+
+/// What the public endpoints let the app see of an account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyStatus {
+    /// Characters, items and passives are readable
+    Public,
+    /// The whole profile is private
+    Private,
+    /// The profile is public but its characters tab is hidden
+    CharactersHidden,
+}
+
+/// Result of `check_profile_privacy`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePrivacy {
+    pub account_name: String,
+    pub status: PrivacyStatus,
+    /// Characters listed, when the characters tab is readable
+    pub character_count: Option<usize>,
+    /// Steps to make snapshots work, empty when nothing needs changing
+    pub hints: Vec<String>,
+}
+
+/// Probe the character list and, if it is refused, the profile page to tell a
+/// private profile from a hidden characters tab
+pub async fn check(api: &impl PoeApi, account_name: &str) -> AppResult<ProfilePrivacy> {
+    let (status, character_count) = match api.get_characters(account_name).await {
+        Ok(characters) => (PrivacyStatus::Public, Some(characters.len())),
+        Err(e) if is_private(&e) => match api.get_profile_page(account_name).await {
+            Ok(page) if !page.to_lowercase().contains(PRIVATE_PROFILE_MARKER) => {
+                (PrivacyStatus::CharactersHidden, None)
+            }
+            Ok(_) => (PrivacyStatus::Private, None),
+            Err(e) if is_private(&e) => (PrivacyStatus::Private, None),
+            Err(e) => return Err(AppError::from(e)),
+        },
+        Err(e) => return Err(AppError::from(e)),
+    };
+
+    Ok(ProfilePrivacy {
+        account_name: account_name.to_string(),
+        status,
+        character_count,
+        hints: hints(status, character_count),
+    })
+}
+
+fn is_private(e: &anyhow::Error) -> bool {
+    matches!(e.downcast_ref::<ApiError>(), Some(ApiError::PrivateProfile))
+}
+
+fn hints(status: PrivacyStatus, character_count: Option<usize>) -> Vec<String> {
+    let privacy_settings = "On pathofexile.com, open My Account > Privacy Settings";
+    let hints = match status {
+        PrivacyStatus::Public if character_count == Some(0) => vec![
+            "No characters were found. Check the account name, including the #1234 suffix.",
+        ],
+        PrivacyStatus::Public => vec![],
+        PrivacyStatus::Private => vec![
+            privacy_settings,
+            "Untick \"Private Profile\" so the public API can read your account",
+            "Leave \"Hide Characters tab\" unticked as well, or snapshots will still fail",
+        ],
+        PrivacyStatus::CharactersHidden => vec![
+            privacy_settings,
+            "Untick \"Hide Characters tab\" so snapshots can read your gear and passives",
+        ],
+    };
+    hints.into_iter().map(String::from).collect()
+}
@@ -0,0 +1,246 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+use crate::db::QuestProgress;
+use crate::log_watcher::{LogEvent, ACT_TOWNS, IZARO_DEATH_LINES};
+
+/// What completing an objective is worth
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectiveKind {
+    SkillPoint,
+    Bandits,
+    Labyrinth,
+}
+
+/// How an objective is detected from the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trigger {
+    /// Entering the quest's area during its act
+    Zone(&'static str),
+    /// Reaching the next act's town
+    LeaveAct,
+    /// Izaro's death line at the end of a labyrinth
+    IzaroDefeated,
+}
+
+/// A mandatory campaign objective
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Objective {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Act the objective should be done in (before moving on to the next act)
+    pub act: i32,
+    pub kind: ObjectiveKind,
+    trigger: Trigger,
+}
+
+const fn objective(id: &'static str, name: &'static str, act: i32, kind: ObjectiveKind, trigger: Trigger) -> Objective {
+    Objective { id, name, act, kind, trigger }
+}
+
+/// Required skill-point quests, bandits, and the normal labyrinth
+pub const OBJECTIVES: &[Objective] = &[
+    objective("dweller", "The Dweller of the Deep", 1, ObjectiveKind::SkillPoint, Trigger::Zone("The Flooded Depths")),
+    objective("marooned_mariner", "The Marooned Mariner", 1, ObjectiveKind::SkillPoint, Trigger::Zone("The Ship Graveyard Cave")),
+    objective("way_forward", "The Way Forward", 2, ObjectiveKind::SkillPoint, Trigger::Zone("The Western Forest")),
+    objective("bandits", "Deal with the Bandits", 2, ObjectiveKind::Bandits, Trigger::LeaveAct),
+    objective("victarios_secrets", "Victario's Secrets", 3, ObjectiveKind::SkillPoint, Trigger::Zone("The Sewers")),
+    objective("pietys_pets", "Piety's Pets", 3, ObjectiveKind::SkillPoint, Trigger::Zone("The Lunaris Temple Level 2")),
+    objective("normal_lab", "The Labyrinth", 3, ObjectiveKind::Labyrinth, Trigger::IzaroDefeated),
+    objective("indomitable_spirit", "An Indomitable Spirit", 4, ObjectiveKind::SkillPoint, Trigger::Zone("The Mines Level 2")),
+    objective("service_to_science", "In Service to Science", 5, ObjectiveKind::SkillPoint, Trigger::Zone("The Control Blocks")),
+    objective("kitavas_torments", "Kitava's Torments", 5, ObjectiveKind::SkillPoint, Trigger::Zone("The Reliquary")),
+    objective("father_of_war", "The Father of War", 6, ObjectiveKind::SkillPoint, Trigger::Zone("The Karui Fortress")),
+    objective("cloven_one", "The Cloven One", 6, ObjectiveKind::SkillPoint, Trigger::Zone("The Prisoner's Gate")),
+    objective("kisharas_star", "Kishara's Star", 7, ObjectiveKind::SkillPoint, Trigger::Zone("The Causeway")),
+    objective("gemling_legion", "The Gemling Legion", 8, ObjectiveKind::SkillPoint, Trigger::Zone("The Grain Gate")),
+    objective("reflection_of_terror", "Reflection of Terror", 8, ObjectiveKind::SkillPoint, Trigger::Zone("The High Gardens")),
+    objective("queen_of_the_sands", "Queen of the Sands", 9, ObjectiveKind::SkillPoint, Trigger::Zone("The Oasis")),
+    objective("ruler_of_highgate", "The Ruler of Highgate", 9, ObjectiveKind::SkillPoint, Trigger::Zone("The Quarry")),
+    objective("vilentas_vengeance", "Vilenta's Vengeance", 10, ObjectiveKind::SkillPoint, Trigger::Zone("The Control Blocks")),
+];
+
+/// Progress change produced by a log event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuestUpdate {
+    ActReached(i32),
+    Completed(&'static str),
+}
+
+/// Follows act progression and objective completion for one run
+#[derive(Debug)]
+pub struct QuestTracker {
+    act: i32,
+    completed: Vec<&'static str>,
+}
+
+impl QuestTracker {
+    pub fn new(act: i32, completed: Vec<&'static str>) -> Self {
+        QuestTracker { act: act.max(1), completed }
+    }
+
+    pub fn apply(&mut self, event: &LogEvent) -> Vec<QuestUpdate> {
+        let mut updates = Vec::new();
+        match event {
+            LogEvent::ZoneEnter { zone_name, .. } => {
+                let next_town = ACT_TOWNS.get(self.act as usize).copied();
+                if next_town == Some(zone_name.as_str()) {
+                    let act = self.act;
+                    self.complete_where(&mut updates, |o| o.act == act && o.trigger == Trigger::LeaveAct);
+                    self.act += 1;
+                    updates.push(QuestUpdate::ActReached(self.act));
+                }
+                let act = self.act;
+                self.complete_where(&mut updates, |o| {
+                    o.act == act && matches!(o.trigger, Trigger::Zone(zone) if zone == zone_name)
+                });
+            }
+            LogEvent::NpcDialogue { npc, text, .. }
+                if npc == "Izaro" && IZARO_DEATH_LINES.contains(&text.as_str()) =>
+            {
+                self.complete_where(&mut updates, |o| o.trigger == Trigger::IzaroDefeated);
+            }
+            _ => {}
+        }
+        updates
+    }
+
+    fn complete_where(&mut self, updates: &mut Vec<QuestUpdate>, matches: impl Fn(&Objective) -> bool) {
+        for objective in OBJECTIVES.iter().filter(|o| matches(o)) {
+            if !self.completed.contains(&objective.id) {
+                self.completed.push(objective.id);
+                updates.push(QuestUpdate::Completed(objective.id));
+                // Only the first pending labyrinth counts for each Izaro kill
+                if objective.trigger == Trigger::IzaroDefeated {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// One objective and whether the run has completed it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectiveStatus {
+    pub id: String,
+    pub name: String,
+    pub act: i32,
+    pub kind: ObjectiveKind,
+    pub completed: bool,
+}
+
+/// Quest progress of a run, as returned by `get_quest_state`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestState {
+    pub run_id: i64,
+    pub current_act: i32,
+    pub objectives: Vec<ObjectiveStatus>,
+    /// Objectives of the current or earlier acts that are still missing
+    pub missing: Vec<String>,
+}
+
+impl ObjectiveStatus {
+    /// Every objective, marked completed if its id is in `completed`
+    pub fn list(completed: &[String]) -> Vec<ObjectiveStatus> {
+        OBJECTIVES
+            .iter()
+            .map(|o| ObjectiveStatus {
+                id: o.id.to_string(),
+                name: o.name.to_string(),
+                act: o.act,
+                kind: o.kind,
+                completed: completed.iter().any(|c| c == o.id),
+            })
+            .collect()
+    }
+}
+
+impl QuestState {
+    pub fn build(run_id: i64, current_act: i32, completed: &[String]) -> QuestState {
+        let objectives = ObjectiveStatus::list(completed);
+        let missing = objectives
+            .iter()
+            .filter(|o| !o.completed && o.act <= current_act)
+            .map(|o| o.id.clone())
+            .collect();
+        QuestState { run_id, current_act, objectives, missing }
+    }
+}
+
+/// Run whose quest progress is being tracked, with its tracker
+static ACTIVE: Mutex<Option<(i64, QuestTracker)>> = Mutex::new(None);
+
+/// Start tracking quest progress for a newly created run
+pub fn start_run(run_id: i64) {
+    let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    *active = Some((run_id, QuestTracker::new(1, Vec::new())));
+}
+
+/// Feed a log event to the active run's tracker and persist any progress
+pub fn handle_event(event: &LogEvent) {
+    let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+    let Some((run_id, tracker)) = active.as_mut() else {
+        return;
+    };
+    for update in tracker.apply(event) {
+        let result = match update {
+            QuestUpdate::ActReached(act) => QuestProgress::set_act(*run_id, act),
+            QuestUpdate::Completed(objective) => QuestProgress::complete(*run_id, objective),
+        };
+        if let Err(e) = result {
+            eprintln!("[quests] Failed to save progress for run {}: {}", run_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(name: &str) -> LogEvent {
+        LogEvent::ZoneEnter { timestamp: String::new(), zone_name: name.to_string() }
+    }
+
+    fn izaro(text: &str) -> LogEvent {
+        LogEvent::NpcDialogue { timestamp: String::new(), npc: "Izaro".to_string(), text: text.to_string() }
+    }
+
+    #[test]
+    fn test_objectives_complete_in_their_act() {
+        let mut tracker = QuestTracker::new(1, Vec::new());
+        assert_eq!(tracker.apply(&zone("The Flooded Depths")), vec![QuestUpdate::Completed("dweller")]);
+        assert!(tracker.apply(&zone("The Flooded Depths")).is_empty(), "already completed");
+
+        // Act 5's Control Blocks don't count for Act 10's quest
+        let mut tracker = QuestTracker::new(5, Vec::new());
+        assert_eq!(tracker.apply(&zone("The Control Blocks")), vec![QuestUpdate::Completed("service_to_science")]);
+    }
+
+    #[test]
+    fn test_act_progression_and_bandits() {
+        let mut tracker = QuestTracker::new(1, Vec::new());
+        assert!(tracker.apply(&zone("Lioneye's Watch")).is_empty(), "already in act 1");
+        assert_eq!(tracker.apply(&zone("The Forest Encampment")), vec![QuestUpdate::ActReached(2)]);
+        assert_eq!(
+            tracker.apply(&zone("The Sarn Encampment")),
+            vec![QuestUpdate::Completed("bandits"), QuestUpdate::ActReached(3)]
+        );
+    }
+
+    #[test]
+    fn test_izaro_death_completes_lab() {
+        let mut tracker = QuestTracker::new(3, Vec::new());
+        assert!(tracker.apply(&izaro("Justice will be served!")).is_empty());
+        assert_eq!(tracker.apply(&izaro("Triumphant at last!")), vec![QuestUpdate::Completed("normal_lab")]);
+    }
+
+    #[test]
+    fn test_state_lists_missing_objectives() {
+        let state = QuestState::build(1, 2, &["dweller".to_string()]);
+        assert_eq!(state.missing, vec!["marooned_mariner", "way_forward", "bandits"]);
+        assert!(state.objectives.iter().find(|o| o.id == "dweller").unwrap().completed);
+    }
+}
@@ -0,0 +1,793 @@
+use anyhow::Result;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, BufReader, ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::db::{NewRun, NewSplit, ReferenceRunData, Run, Split};
+use crate::obs_server::{accept_key, frame};
+use crate::status_server;
+
+/// Idle connections get a ping this often, so a racer who dropped is noticed
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Largest frame read from a peer. Events are a few hundred bytes.
+const MAX_FRAME_LEN: u64 = 64 * 1024;
+
+/// Most remote racers a lobby tracks at once, and guests a host lets in. Each
+/// racer's runs are stored as reference runs, so this bounds what a lobby can
+/// write while they're connected.
+const MAX_RACERS: usize = 16;
+
+/// Handshake header a guest sends its racer name in. The host binds the
+/// connection to it and drops events that claim another racer.
+const NAME_HEADER: &str = "x-race-name";
+
+/// Something a racer did, sent to everyone else in the lobby
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum RaceEvent {
+    /// A racer started a run, replacing the one the lobby had for them.
+    /// `started_at` tells a replayed start from a new one.
+    RunStarted {
+        racer: String,
+        category: String,
+        class: String,
+        started_at: String,
+    },
+    Split {
+        racer: String,
+        breakpoint_type: String,
+        breakpoint_name: String,
+        breakpoint_key: Option<String>,
+        split_time_ms: i64,
+    },
+    RunFinished { racer: String, total_time_ms: i64 },
+    /// A guest's connection to the host closed. Their run stays stored but
+    /// no longer counts towards `MAX_RACERS`.
+    RacerLeft { racer: String },
+}
+
+impl RaceEvent {
+    pub fn racer(&self) -> &str {
+        match self {
+            RaceEvent::RunStarted { racer, .. }
+            | RaceEvent::Split { racer, .. }
+            | RaceEvent::RunFinished { racer, .. }
+            | RaceEvent::RacerLeft { racer } => racer,
+        }
+    }
+
+    fn with_racer(mut self, name: &str) -> RaceEvent {
+        match &mut self {
+            RaceEvent::RunStarted { racer, .. }
+            | RaceEvent::Split { racer, .. }
+            | RaceEvent::RunFinished { racer, .. }
+            | RaceEvent::RacerLeft { racer } => *racer = name.to_string(),
+        }
+        self
+    }
+}
+
+/// Whether this instance runs the lobby or joined someone else's
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RaceRole {
+    Host,
+    Guest,
+}
+
+/// A remote racer and the reference run their splits are stored in, which
+/// `ComparisonKind::Reference` compares against like any other
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Racer {
+    pub name: String,
+    pub run_id: i64,
+    pub category: String,
+    pub class: String,
+    pub started_at: String,
+    pub last_split: Option<String>,
+    pub split_time_ms: Option<i64>,
+    pub finished: bool,
+}
+
+/// The lobby as `get_race_status` reports it
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RaceStatus {
+    /// None outside a lobby
+    pub role: Option<RaceRole>,
+    pub name: String,
+    /// The `ws://` address guests join (the host's, when a guest)
+    pub address: String,
+    /// Open connections: guests while hosting, 1 while joined
+    pub peers: usize,
+    pub racers: Vec<Racer>,
+}
+
+/// Called with each new remote event, after it was stored
+pub type Listener = Arc<dyn Fn(&RaceEvent) + Send + Sync>;
+
+struct Lobby {
+    role: RaceRole,
+    name: String,
+    address: String,
+    stop_flag: Arc<AtomicBool>,
+    /// A host's accept loop, joined on leave so the port is free again
+    accept_thread: Option<JoinHandle<()>>,
+}
+
+/// An open connection: its writer's queue, the socket to shut down on leave,
+/// and the racer a host bound it to
+struct Peer {
+    id: u64,
+    sender: Sender<String>,
+    stream: TcpStream,
+    racer: Option<String>,
+}
+
+/// The lobby this instance is in, if any
+static LOBBY: Mutex<Option<Lobby>> = Mutex::new(None);
+
+static PEERS: Mutex<Vec<Peer>> = Mutex::new(Vec::new());
+
+static NEXT_PEER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Events of the local run, kept in or out of a lobby so a lobby joined
+/// mid-run still gets its start. The racer is filled in when they're sent.
+static LOCAL_RUN: Mutex<Vec<RaceEvent>> = Mutex::new(Vec::new());
+
+/// Events of each guest's current run, which a host replays to guests that
+/// connect mid-race along with its own
+static HISTORY: Mutex<Vec<RaceEvent>> = Mutex::new(Vec::new());
+
+/// Remote racers by name
+static RACERS: Mutex<Option<HashMap<String, Racer>>> = Mutex::new(None);
+
+/// Host a lobby on every interface, leaving any lobby first. Its address ends
+/// in a random room token guests must join with. Returns the bound port.
+pub fn host(port: u16, name: &str, listener: Listener) -> io::Result<u16> {
+    leave();
+
+    let tcp = TcpListener::bind(("0.0.0.0", port))?;
+    tcp.set_nonblocking(true)?;
+    let bound_port = tcp.local_addr()?.port();
+    let room_path = Arc::new(format!("/{}", status_server::generate_token()?));
+
+    let host = status_server::local_ip().map_or_else(|| "localhost".to_string(), |ip| ip.to_string());
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let running = stop_flag.clone();
+    let accept_path = room_path.clone();
+    let accept_thread = thread::spawn(move || {
+        while !running.load(Ordering::Relaxed) {
+            match tcp.accept() {
+                Ok((stream, _)) => {
+                    let listener = listener.clone();
+                    let room_path = accept_path.clone();
+                    thread::spawn(move || {
+                        if let Err(e) = accept_guest(stream, &room_path, listener) {
+                            eprintln!("[race] Guest connection failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => {
+                    eprintln!("[race] Accept failed: {}", e);
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+    });
+    set_lobby(Lobby {
+        role: RaceRole::Host,
+        name: name.to_string(),
+        address: format!("ws://{}:{}{}", host, bound_port, room_path),
+        stop_flag,
+        accept_thread: Some(accept_thread),
+    });
+
+    Ok(bound_port)
+}
+
+/// Join the lobby at a `ws://host:port/` address, leaving any lobby first.
+/// A relay is just a lobby host nobody races on.
+pub fn join(url: &str, name: &str, listener: Listener) -> io::Result<()> {
+    leave();
+
+    if name.chars().any(char::is_control) {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "Racer names can't contain control characters"));
+    }
+    let (authority, path) = parse_url(url)
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "Lobby address must look like ws://host:port/"))?;
+    let addr = authority
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, format!("Could not resolve {}", authority)))?;
+    let stream = TcpStream::connect_timeout(&addr, Duration::from_secs(5))?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut nonce = [0u8; 16];
+    getrandom::getrandom(&mut nonce).map_err(|e| io::Error::other(e.to_string()))?;
+    let key = base64::engine::general_purpose::STANDARD.encode(nonce);
+    write!(
+        &stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n{}: {}\r\n\r\n",
+        path, authority, key, NAME_HEADER, name
+    )?;
+
+    // Frames can follow the handshake straight away, so the reader that
+    // buffered the headers reads them too
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let (status_line, headers) = status_server::read_head(&mut reader)?;
+    let refused = |reason: &str| io::Error::new(ErrorKind::ConnectionRefused, reason.to_string());
+    match status_line.split_whitespace().nth(1) {
+        Some("101") if headers.get("sec-websocket-accept") == Some(&accept_key(&key)) => {}
+        Some("403") => return Err(refused("The lobby address is missing its room token, or the token is wrong")),
+        Some("409") => return Err(refused("Another racer in the lobby already uses that name")),
+        Some("503") => return Err(refused("The lobby is full")),
+        _ => return Err(refused(&format!("{} is not a race lobby", url.trim()))),
+    }
+    stream.set_read_timeout(None)?;
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    set_lobby(Lobby {
+        role: RaceRole::Guest,
+        name: name.to_string(),
+        address: url.trim().to_string(),
+        stop_flag: stop_flag.clone(),
+        accept_thread: None,
+    });
+    let (peer, receiver) = Peer::open(&stream).inspect_err(|_| leave())?;
+    let id = peer.id;
+    if let Ok(mut peers) = PEERS.lock() {
+        peers.push(peer);
+    }
+    thread::spawn(move || {
+        serve_peer(reader, stream, RaceRole::Guest, None, id, receiver, listener);
+        // Losing the host ends the lobby, unless it was left already
+        if !stop_flag.load(Ordering::Relaxed) {
+            leave();
+        }
+    });
+    Ok(())
+}
+
+/// Close the lobby or leave it. Remote racers' runs stay as reference runs.
+pub fn leave() {
+    if let Some(lobby) = LOBBY.lock().ok().and_then(|mut l| l.take()) {
+        lobby.stop_flag.store(true, Ordering::Relaxed);
+        if let Some(accept_thread) = lobby.accept_thread {
+            let _ = accept_thread.join();
+        }
+    }
+    // Shutting the sockets down ends their reader threads, which drop the writers' queues
+    if let Ok(mut peers) = PEERS.lock() {
+        for peer in peers.drain(..) {
+            let _ = peer.stream.shutdown(Shutdown::Both);
+        }
+    }
+    if let Ok(mut history) = HISTORY.lock() {
+        history.clear();
+    }
+    if let Ok(mut racers) = RACERS.lock() {
+        *racers = None;
+    }
+}
+
+pub fn status() -> RaceStatus {
+    let Some((role, name, address)) = LOBBY
+        .lock()
+        .ok()
+        .and_then(|lobby| lobby.as_ref().map(|l| (l.role, l.name.clone(), l.address.clone())))
+    else {
+        return RaceStatus::default();
+    };
+    let mut racers: Vec<Racer> = RACERS
+        .lock()
+        .ok()
+        .and_then(|racers| racers.as_ref().map(|r| r.values().cloned().collect()))
+        .unwrap_or_default();
+    racers.sort_by(|a, b| a.name.cmp(&b.name));
+    RaceStatus {
+        role: Some(role),
+        name,
+        address,
+        peers: PEERS.lock().map(|peers| peers.len()).unwrap_or(0),
+        racers,
+    }
+}
+
+/// Tell the lobby the local racer started a run; nothing outside a lobby
+pub fn run_started(run: &NewRun) {
+    send(|racer| RaceEvent::RunStarted {
+        racer,
+        category: run.category.clone(),
+        class: run.class.clone(),
+        started_at: run.started_at.clone(),
+    });
+}
+
+pub fn split(split: &NewSplit) {
+    send(|racer| RaceEvent::Split {
+        racer,
+        breakpoint_type: split.breakpoint_type.clone(),
+        breakpoint_name: split.breakpoint_name.clone(),
+        breakpoint_key: split.breakpoint_key.clone(),
+        split_time_ms: split.split_time_ms,
+    });
+}
+
+pub fn run_finished(total_time_ms: i64) {
+    send(|racer| RaceEvent::RunFinished { racer, total_time_ms });
+}
+
+fn send(event: impl FnOnce(String) -> RaceEvent) {
+    let event = event(String::new());
+    if let Ok(mut local) = LOCAL_RUN.lock() {
+        if matches!(event, RaceEvent::RunStarted { .. }) {
+            local.clear();
+        }
+        local.push(event.clone());
+    }
+    if let Some(name) = local_name() {
+        relay(&event.with_racer(&name), None);
+    }
+}
+
+fn local_name() -> Option<String> {
+    LOBBY.lock().ok().and_then(|lobby| lobby.as_ref().map(|l| l.name.clone()))
+}
+
+fn set_lobby(lobby: Lobby) {
+    if let Ok(mut current) = LOBBY.lock() {
+        *current = Some(lobby);
+    }
+    if let Ok(mut racers) = RACERS.lock() {
+        *racers = Some(HashMap::new());
+    }
+}
+
+/// Add a guest's event to the history, dropping their previous run's on a
+/// start and all of theirs when they leave
+fn remember(event: &RaceEvent) {
+    if let Ok(mut history) = HISTORY.lock() {
+        if matches!(event, RaceEvent::RunStarted { .. } | RaceEvent::RacerLeft { .. }) {
+            history.retain(|e| e.racer() != event.racer());
+        }
+        if !matches!(event, RaceEvent::RacerLeft { .. }) {
+            history.push(event.clone());
+        }
+    }
+}
+
+/// Queue an event for every peer but the one it came from
+fn relay(event: &RaceEvent, from: Option<u64>) {
+    let Ok(json) = serde_json::to_string(event) else {
+        return;
+    };
+    if let Ok(mut peers) = PEERS.lock() {
+        peers.retain(|peer| Some(peer.id) == from || peer.sender.send(json.clone()).is_ok());
+    }
+}
+
+/// Store a remote racer's event: a start opens a reference run for them and
+/// splits are added to it as they come in, until they leave. Returns whether
+/// the event was new; replays of ones already stored, the local racer's own,
+/// and starts past `MAX_RACERS` racers are skipped.
+pub(crate) fn apply(event: &RaceEvent) -> Result<bool> {
+    if local_name().as_deref() == Some(event.racer()) {
+        return Ok(false);
+    }
+    // Read the racer under the lock but write to the database without it, so
+    // one slow insert doesn't hold up every other connection. A racer's events
+    // all come in on one connection, so they're never applied concurrently.
+    let poisoned = |_| anyhow::anyhow!("Race lobby state poisoned");
+    let (known, count) = {
+        let racers = RACERS.lock().map_err(poisoned)?;
+        let racers = racers.as_ref();
+        (racers.and_then(|r| r.get(event.racer()).cloned()), racers.map_or(0, HashMap::len))
+    };
+
+    let updated = match event {
+        RaceEvent::RunStarted { racer, category, class, started_at } => {
+            if known.as_ref().is_some_and(|r| &r.started_at == started_at) || (known.is_none() && count >= MAX_RACERS) {
+                return Ok(false);
+            }
+            let run_id = Run::insert_reference(&ReferenceRunData {
+                source_name: format!("Race: {}", racer),
+                character_name: Some(racer.clone()),
+                class: class.clone(),
+                ascendancy: None,
+                category: category.clone(),
+                league: None,
+                breakpoint_preset: None,
+                enabled_breakpoints: None,
+                total_time_ms: 0,
+                splits: Vec::new(),
+            })?;
+            Racer {
+                name: racer.clone(),
+                run_id,
+                category: category.clone(),
+                class: class.clone(),
+                started_at: started_at.clone(),
+                last_split: None,
+                split_time_ms: None,
+                finished: false,
+            }
+        }
+        RaceEvent::Split { breakpoint_type, breakpoint_name, breakpoint_key, split_time_ms, .. } => {
+            // A racer whose start we never saw has no run to add to
+            let Some(mut racer) = known else {
+                return Ok(false);
+            };
+            if racer.finished || racer.split_time_ms.is_some_and(|last| *split_time_ms <= last) {
+                return Ok(false);
+            }
+            Split::insert(&NewSplit {
+                run_id: racer.run_id,
+                breakpoint_type: breakpoint_type.clone(),
+                breakpoint_name: breakpoint_name.clone(),
+                breakpoint_key: breakpoint_key.clone(),
+                split_time_ms: *split_time_ms,
+                delta_ms: None,
+                segment_time_ms: split_time_ms - racer.split_time_ms.unwrap_or(0),
+                town_time_ms: 0,
+                hideout_time_ms: 0,
+            })?;
+            racer.last_split = Some(breakpoint_name.clone());
+            racer.split_time_ms = Some(*split_time_ms);
+            racer
+        }
+        RaceEvent::RunFinished { total_time_ms, .. } => {
+            let Some(mut racer) = known else {
+                return Ok(false);
+            };
+            if racer.finished {
+                return Ok(false);
+            }
+            Run::complete(racer.run_id, *total_time_ms)?;
+            racer.finished = true;
+            racer
+        }
+        RaceEvent::RacerLeft { racer } => {
+            let left = RACERS.lock().map_err(poisoned)?.as_mut().and_then(|racers| racers.remove(racer));
+            return Ok(left.is_some());
+        }
+    };
+    RACERS
+        .lock()
+        .map_err(poisoned)?
+        .get_or_insert_with(HashMap::new)
+        .insert(updated.name.clone(), updated);
+    Ok(true)
+}
+
+/// The racer a guest's handshake binds the connection to, or the status line
+/// and body a host turns it down with. An accepted guest's `peer` takes its
+/// slot under the same lock the name and the cap were checked under, so two
+/// handshakes can't both get the last slot or the same name.
+fn check_guest(
+    request_line: &str,
+    room_path: &str,
+    headers: &HashMap<String, String>,
+    mut peer: Peer,
+) -> Result<String, (&'static str, &'static str)> {
+    if request_line.split_whitespace().nth(1) != Some(room_path) {
+        return Err(("403 Forbidden", "Wrong or missing room token"));
+    }
+    if !headers.contains_key("sec-websocket-key") {
+        return Err(("426 Upgrade Required", "Join with POE Watcher's race lobby"));
+    }
+    let name = headers.get(NAME_HEADER).map(|n| n.trim()).unwrap_or_default();
+    if name.is_empty() {
+        return Err(("400 Bad Request", "Missing racer name"));
+    }
+    let mut peers = PEERS.lock().map_err(|_| ("500 Internal Server Error", "Race lobby state poisoned"))?;
+    if local_name().as_deref() == Some(name) || peers.iter().any(|peer| peer.racer.as_deref() == Some(name)) {
+        return Err(("409 Conflict", "Racer name already in the lobby"));
+    }
+    if peers.len() >= MAX_RACERS {
+        return Err(("503 Service Unavailable", "Lobby is full"));
+    }
+    peer.racer = Some(name.to_string());
+    peers.push(peer);
+    Ok(name.to_string())
+}
+
+fn accept_guest(stream: TcpStream, room_path: &str, listener: Listener) -> io::Result<()> {
+    // Accepted sockets can inherit non-blocking mode from the listener
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let (request_line, headers) = status_server::read_head(&mut reader)?;
+    let (peer, receiver) = Peer::open(&stream)?;
+    let id = peer.id;
+    let racer = match check_guest(&request_line, room_path, &headers, peer) {
+        Ok(racer) => racer,
+        Err((status, body)) => {
+            write!(
+                &stream,
+                "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )?;
+            return Ok(());
+        }
+    };
+    let upgraded = write!(
+        &stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(&headers["sec-websocket-key"])
+    )
+    .and_then(|_| stream.set_read_timeout(None));
+    if let Err(e) = upgraded {
+        close_peer(id);
+        return Err(e);
+    }
+    serve_peer(reader, stream, RaceRole::Host, Some(racer), id, receiver, listener);
+    Ok(())
+}
+
+impl Peer {
+    /// A connection not yet in `PEERS`, with the local run and the history
+    /// queued for it to replay, and the receiving end of its queue
+    fn open(stream: &TcpStream) -> io::Result<(Peer, Receiver<String>)> {
+        let (sender, receiver) = mpsc::channel();
+        let name = local_name().unwrap_or_default();
+        let local = LOCAL_RUN.lock().map(|l| l.clone()).unwrap_or_default();
+        let history = HISTORY.lock().map(|h| h.clone()).unwrap_or_default();
+        for event in local.into_iter().map(|e| e.with_racer(&name)).chain(history) {
+            if let Ok(json) = serde_json::to_string(&event) {
+                let _ = sender.send(json);
+            }
+        }
+        let peer = Peer {
+            id: NEXT_PEER_ID.fetch_add(1, Ordering::Relaxed),
+            sender,
+            stream: stream.try_clone()?,
+            racer: None,
+        };
+        Ok((peer, receiver))
+    }
+}
+
+/// Drop a connection from `PEERS` and shut its socket down. False if it was
+/// gone already, e.g. dropped by `leave`.
+fn close_peer(id: u64) -> bool {
+    let Ok(mut peers) = PEERS.lock() else {
+        return false;
+    };
+    let Some(i) = peers.iter().position(|peer| peer.id == id) else {
+        return false;
+    };
+    let peer = peers.remove(i);
+    let _ = peer.stream.shutdown(Shutdown::Both);
+    true
+}
+
+/// Exchange events with one peer, already in `PEERS` as `id`, until either
+/// side closes: write queued events from a second thread while this one
+/// reads. A host relays what each guest sends to the others, taking only
+/// events for the `racer` the connection was bound to, and tells them when
+/// that racer leaves.
+fn serve_peer(
+    mut reader: impl Read,
+    stream: TcpStream,
+    role: RaceRole,
+    racer: Option<String>,
+    id: u64,
+    receiver: Receiver<String>,
+    listener: Listener,
+) {
+    let Ok(writer) = stream.try_clone() else {
+        close_peer(id);
+        return;
+    };
+    // Client-to-server frames must be masked (RFC 6455 section 5.3)
+    let masked = role == RaceRole::Guest;
+    thread::spawn(move || {
+        let _ = write_events(writer, receiver, masked);
+    });
+
+    while let Ok((opcode, payload)) = read_frame(&mut reader) {
+        match opcode {
+            0x1 => {
+                let Ok(event) = serde_json::from_slice::<RaceEvent>(&payload) else {
+                    continue;
+                };
+                // Only the host says who left
+                if racer.as_deref().is_some_and(|racer| racer != event.racer())
+                    || (role == RaceRole::Host && matches!(event, RaceEvent::RacerLeft { .. }))
+                {
+                    continue;
+                }
+                receive(&event, role, id, &listener);
+            }
+            0x8 => break,
+            // Pings only keep the connection checked; nothing to answer
+            _ => {}
+        }
+    }
+
+    // After `leave` there's no lobby left to tell
+    if close_peer(id) {
+        if let Some(racer) = racer {
+            receive(&RaceEvent::RacerLeft { racer }, role, id, &listener);
+        }
+    }
+}
+
+/// Store an event from peer `id` and pass it on: a host relays it to the
+/// other peers and replays it to later ones
+fn receive(event: &RaceEvent, role: RaceRole, id: u64, listener: &Listener) {
+    match apply(event) {
+        Ok(true) => {
+            if role == RaceRole::Host {
+                remember(event);
+                relay(event, Some(id));
+            }
+            listener(event);
+        }
+        Ok(false) => {}
+        Err(e) => eprintln!("[race] Failed to store {}'s event: {}", event.racer(), e),
+    }
+}
+
+/// Write each queued event, pinging while idle, until the peer is dropped
+fn write_events(mut stream: TcpStream, receiver: Receiver<String>, masked: bool) -> io::Result<()> {
+    let encode = |opcode: u8, payload: &[u8]| -> io::Result<Vec<u8>> {
+        if !masked {
+            return Ok(frame(opcode, payload));
+        }
+        let mut mask = [0u8; 4];
+        getrandom::getrandom(&mut mask).map_err(|e| io::Error::other(e.to_string()))?;
+        Ok(masked_frame(opcode, payload, mask))
+    };
+    let mut idle = Duration::ZERO;
+    loop {
+        match receiver.recv_timeout(Duration::from_secs(1)) {
+            Ok(json) => {
+                stream.write_all(&encode(0x1, json.as_bytes())?)?;
+                idle = Duration::ZERO;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                idle += Duration::from_secs(1);
+                if idle >= PING_INTERVAL {
+                    stream.write_all(&encode(0x9, &[])?)?;
+                    idle = Duration::ZERO;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    let _ = stream.write_all(&encode(0x8, &1001u16.to_be_bytes())?);
+    Ok(())
+}
+
+/// A client-to-server frame: `frame` with its payload masked
+fn masked_frame(opcode: u8, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let masked: Vec<u8> = payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]).collect();
+    let mut frame = frame(opcode, &masked);
+    let header_len = frame.len() - masked.len();
+    frame[1] |= 0x80;
+    frame.splice(header_len..header_len, mask);
+    frame
+}
+
+/// Read one frame, unmasking it if masked. Events fit in a frame, so
+/// fragmented messages aren't reassembled.
+fn read_frame(reader: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut head = [0u8; 2];
+    reader.read_exact(&mut head)?;
+    let len = match head[1] & 0x7f {
+        126 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as u64
+        }
+        127 => {
+            let mut len = [0u8; 8];
+            reader.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => len as u64,
+    };
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(ErrorKind::InvalidData, "Frame too large"));
+    }
+    let mask = if head[1] & 0x80 != 0 {
+        let mut mask = [0u8; 4];
+        reader.read_exact(&mut mask)?;
+        Some(mask)
+    } else {
+        None
+    };
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    if let Some(mask) = mask {
+        for (i, b) in payload.iter_mut().enumerate() {
+            *b ^= mask[i % 4];
+        }
+    }
+    Ok((head[0] & 0x0f, payload))
+}
+
+/// `ws://host:port/path` as the authority to connect to (port 80 if none)
+/// and the request path
+fn parse_url(url: &str) -> Option<(String, String)> {
+    let rest = url.trim().strip_prefix("ws://")?;
+    let (authority, path) = match rest.split_once('/') {
+        Some((authority, path)) => (authority, format!("/{}", path)),
+        None => (rest, "/".to_string()),
+    };
+    if authority.is_empty() {
+        return None;
+    }
+    let authority = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    Some((authority, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_round_trip() {
+        let read = |bytes: Vec<u8>| read_frame(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(read(frame(0x1, b"hi")), (0x1, b"hi".to_vec()));
+        // Masked example from RFC 6455 section 5.7
+        let masked = masked_frame(0x1, b"Hello", [0x37, 0xfa, 0x21, 0x3d]);
+        assert_eq!(masked, [0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58]);
+        assert_eq!(read(masked), (0x1, b"Hello".to_vec()));
+
+        let medium = vec![b'x'; 300];
+        assert_eq!(read(masked_frame(0x1, &medium, [1, 2, 3, 4])), (0x1, medium));
+        assert_eq!(read(frame(0x9, &[])), (0x9, Vec::new()));
+
+        let too_large = frame(0x1, &vec![b'x'; 70_000]);
+        assert!(read_frame(&mut too_large.as_slice()).is_err());
+        assert!(read_frame(&mut [0x81u8, 5, b'h'].as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_event_json_and_urls() {
+        let event = RaceEvent::Split {
+            racer: "Alice".to_string(),
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: "The Coast".to_string(),
+            breakpoint_key: None,
+            split_time_ms: 60_000,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains(r#""type":"split""#) && json.contains(r#""splitTimeMs":60000"#), "{}", json);
+        assert_eq!(serde_json::from_str::<RaceEvent>(&json).unwrap(), event);
+
+        assert_eq!(
+            parse_url("ws://192.168.1.5:9001/"),
+            Some(("192.168.1.5:9001".to_string(), "/".to_string()))
+        );
+        assert_eq!(
+            parse_url(" ws://relay.example.com/rooms/abc "),
+            Some(("relay.example.com:80".to_string(), "/rooms/abc".to_string()))
+        );
+        assert_eq!(parse_url("http://example.com/"), None);
+        assert_eq!(parse_url("ws:///"), None);
+    }
+}
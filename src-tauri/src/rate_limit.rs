@@ -0,0 +1,331 @@
+use reqwest::header::HeaderMap;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Longest period, restriction or `Retry-After` taken from a header. The
+/// values come from the server, so they're clamped before any `Instant` math.
+const MAX_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most hits taken from a limit or state header; each counted hit is kept
+const MAX_HITS: u64 = 1000;
+
+/// Token bucket for endpoints whose policy hasn't been seen yet
+struct TokenBucket {
+    tokens: f64,
+    max_tokens: f64,
+    refill_rate: f64, // tokens per second
+    last_update: Instant,
+}
+
+impl TokenBucket {
+    fn new(max_tokens: f64, refill_rate: f64, now: Instant) -> Self {
+        TokenBucket {
+            tokens: max_tokens,
+            max_tokens,
+            refill_rate,
+            last_update: now,
+        }
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.refill_rate))
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_update).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.max_tokens);
+        self.last_update = now;
+    }
+}
+
+/// One rule window from an `X-Rate-Limit-{rule}` header: at most `max_hits`
+/// requests per `period`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Window {
+    max_hits: usize,
+    period: Duration,
+}
+
+/// Limits GGG published for a policy, with the requests sent under it
+#[derive(Debug, Default)]
+struct Policy {
+    windows: Vec<Window>,
+    /// Send times, oldest first, kept for the longest window
+    sent: VecDeque<Instant>,
+    /// Set by a restriction in the state headers or a 429's `Retry-After`
+    restricted_until: Option<Instant>,
+}
+
+impl Policy {
+    fn wait_time(&self, now: Instant) -> Duration {
+        let restricted = self
+            .restricted_until
+            .map_or(Duration::ZERO, |until| until.saturating_duration_since(now));
+        self.windows
+            .iter()
+            .map(|window| {
+                let in_window: Vec<&Instant> = self
+                    .sent
+                    .iter()
+                    .filter(|sent| now.saturating_duration_since(**sent) < window.period)
+                    .collect();
+                if in_window.len() < window.max_hits {
+                    return Duration::ZERO;
+                }
+                // Wait for enough of the window's requests to age out
+                let freeing = in_window[in_window.len() - window.max_hits];
+                (*freeing + window.period).saturating_duration_since(now)
+            })
+            .fold(restricted, Duration::max)
+    }
+
+    fn prune(&mut self, now: Instant) {
+        let longest = self.windows.iter().map(|w| w.period).max().unwrap_or(Duration::ZERO);
+        while self.sent.front().is_some_and(|sent| now.saturating_duration_since(*sent) >= longest) {
+            self.sent.pop_front();
+        }
+    }
+}
+
+/// Client-side view of the POE API rate limits. Requests wait on the policy
+/// their endpoint answered with last time (`X-Rate-Limit-Policy`), tracking
+/// every rule window GGG publishes; endpoints not seen yet share a default
+/// token bucket.
+pub struct RateLimits {
+    default: TokenBucket,
+    policies: HashMap<String, Policy>,
+    /// Endpoint (URL without its query) to policy name
+    endpoints: HashMap<String, String>,
+}
+
+impl RateLimits {
+    pub fn new(now: Instant) -> Self {
+        RateLimits {
+            // 5 requests per second with burst of 10
+            default: TokenBucket::new(10.0, 5.0, now),
+            policies: HashMap::new(),
+            endpoints: HashMap::new(),
+        }
+    }
+
+    /// Reserve a request to `url`, or say how long to wait before trying again
+    pub fn try_acquire(&mut self, url: &str, now: Instant) -> Result<(), Duration> {
+        let Some(policy) = self.policy_mut(url) else {
+            return self.default.try_acquire(now);
+        };
+        let wait = policy.wait_time(now);
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        policy.sent.push_back(now);
+        policy.prune(now);
+        Ok(())
+    }
+
+    /// Adopt the limits and state in a response's rate limit headers
+    pub fn update(&mut self, url: &str, headers: &HeaderMap, now: Instant) {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let Some(name) = header("x-rate-limit-policy") else {
+            return;
+        };
+        self.endpoints.insert(endpoint(url).to_string(), name.clone());
+        let policy = self.policies.entry(name).or_default();
+
+        let rules = header("x-rate-limit-rules").unwrap_or_default();
+        let mut windows = Vec::new();
+        for rule in rules.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            let limits = parse_triples(&header(&format!("x-rate-limit-{}", rule)).unwrap_or_default());
+            let states = parse_triples(&header(&format!("x-rate-limit-{}-state", rule)).unwrap_or_default());
+            // A window allowing no hits at all can't be waited out
+            for &(max_hits, period, _) in limits.iter().filter(|(max_hits, _, _)| *max_hits > 0) {
+                windows.push(Window {
+                    max_hits: max_hits.min(MAX_HITS) as usize,
+                    period: seconds(period),
+                });
+            }
+            for &(hits, period, restricted) in &states {
+                // Requests from before this session (or another tool) count too
+                let period = seconds(period);
+                let seen = policy.sent.iter().filter(|sent| now.saturating_duration_since(**sent) < period).count();
+                for _ in seen..hits.min(MAX_HITS) as usize {
+                    policy.sent.push_back(now);
+                }
+                if restricted > 0 {
+                    restrict(policy, seconds(restricted), now);
+                }
+            }
+        }
+        if !windows.is_empty() {
+            policy.windows = windows;
+        }
+        policy.prune(now);
+    }
+
+    /// Hold requests to `url` for `retry_after` (a 429's `Retry-After`)
+    pub fn restrict(&mut self, url: &str, retry_after: Duration, now: Instant) {
+        match self.policy_mut(url) {
+            Some(policy) => restrict(policy, retry_after, now),
+            None => {
+                // Unknown policy: drain the default bucket for the duration
+                self.default.tokens = -(retry_after.min(MAX_PERIOD).as_secs_f64() * self.default.refill_rate);
+                self.default.last_update = now;
+            }
+        }
+    }
+
+    fn policy_mut(&mut self, url: &str) -> Option<&mut Policy> {
+        let name = self.endpoints.get(endpoint(url))?;
+        self.policies.get_mut(name)
+    }
+}
+
+fn restrict(policy: &mut Policy, duration: Duration, now: Instant) {
+    let Some(until) = now.checked_add(duration.min(MAX_PERIOD)) else {
+        return;
+    };
+    if policy.restricted_until.is_none_or(|current| current < until) {
+        policy.restricted_until = Some(until);
+    }
+}
+
+/// A header's seconds, clamped to `MAX_PERIOD`
+fn seconds(secs: u64) -> Duration {
+    Duration::from_secs(secs).min(MAX_PERIOD)
+}
+
+/// `Retry-After` in seconds, as the POE API sends it
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get("retry-after")?.to_str().ok()?;
+    value.trim().parse().ok().map(seconds)
+}
+
+/// Rate limits apply per endpoint, whatever the query
+fn endpoint(url: &str) -> &str {
+    url.split('?').next().unwrap_or(url)
+}
+
+/// `a:b:c,a:b:c` header values (limits are hits:period:penalty, states are
+/// hits:period:active restriction, all in seconds)
+fn parse_triples(value: &str) -> Vec<(u64, u64, u64)> {
+    value
+        .split(',')
+        .filter_map(|triple| {
+            let mut parts = triple.trim().split(':').map(|p| p.parse::<u64>().ok());
+            Some((parts.next()??, parts.next()??, parts.next()??))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    const URL: &str = "https://www.pathofexile.com/character-window/get-items?accountName=a&character=b";
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        assert_eq!(parse_triples("45:60:60,240:240:900"), vec![(45, 60, 60), (240, 240, 900)]);
+        assert_eq!(parse_triples("garbage,1:2"), vec![]);
+        assert_eq!(retry_after(&headers(&[("retry-after", "30")])), Some(Duration::from_secs(30)));
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+        assert_eq!(endpoint(URL), "https://www.pathofexile.com/character-window/get-items");
+    }
+
+    #[test]
+    fn test_policy_windows_and_state() {
+        let now = Instant::now();
+        let mut limits = RateLimits::new(now);
+        limits.update(
+            URL,
+            &headers(&[
+                ("x-rate-limit-policy", "character-window-request-limit"),
+                ("x-rate-limit-rules", "Account"),
+                ("x-rate-limit-account", "3:10:60,5:60:300"),
+                ("x-rate-limit-account-state", "1:10:0,1:60:0"),
+            ]),
+            now,
+        );
+
+        // The server already counted one request, so two more fit in the 10s window
+        assert!(limits.try_acquire(URL, now).is_ok());
+        assert!(limits.try_acquire(URL, now).is_ok());
+        assert_eq!(limits.try_acquire(URL, now), Err(Duration::from_secs(10)));
+
+        // The 60s window (5 hits) binds once the 10s window frees up
+        let later = now + Duration::from_secs(10);
+        assert!(limits.try_acquire(URL, later).is_ok());
+        assert!(limits.try_acquire(URL, later).is_ok());
+        assert_eq!(limits.try_acquire(URL, later), Err(Duration::from_secs(50)));
+
+        // Another query on the same endpoint shares the policy
+        let other = "https://www.pathofexile.com/character-window/get-items?accountName=c&character=d";
+        assert!(limits.try_acquire(other, later).is_err());
+    }
+
+    #[test]
+    fn test_restrictions() {
+        let now = Instant::now();
+        let mut limits = RateLimits::new(now);
+
+        // Before any policy is known, a 429 drains the default bucket
+        limits.restrict(URL, Duration::from_secs(4), now);
+        let wait = limits.try_acquire(URL, now).unwrap_err();
+        assert!(wait > Duration::from_secs(4) && wait <= Duration::from_secs(5), "{:?}", wait);
+
+        // An active restriction in the state headers holds the policy
+        limits.update(
+            URL,
+            &headers(&[
+                ("x-rate-limit-policy", "character-window-request-limit"),
+                ("x-rate-limit-rules", "Ip"),
+                ("x-rate-limit-ip", "45:60:60"),
+                ("x-rate-limit-ip-state", "46:60:60"),
+            ]),
+            now,
+        );
+        assert_eq!(limits.try_acquire(URL, now), Err(Duration::from_secs(60)));
+        limits.restrict(URL, Duration::from_secs(120), now);
+        assert_eq!(limits.try_acquire(URL, now), Err(Duration::from_secs(120)));
+        assert!(limits.try_acquire(URL, now + Duration::from_secs(120)).is_ok());
+    }
+
+    #[test]
+    fn test_hostile_headers_are_clamped() {
+        let now = Instant::now();
+        let mut limits = RateLimits::new(now);
+        assert_eq!(retry_after(&headers(&[("retry-after", "18446744073709551615")])), Some(MAX_PERIOD));
+
+        // Huge hits, periods and restrictions, and a window allowing nothing
+        limits.update(
+            URL,
+            &headers(&[
+                ("x-rate-limit-policy", "hostile"),
+                ("x-rate-limit-rules", "Ip"),
+                ("x-rate-limit-ip", "0:10:0,18446744073709551615:18446744073709551615:0"),
+                ("x-rate-limit-ip-state", "18446744073709551615:18446744073709551615:18446744073709551615"),
+            ]),
+            now,
+        );
+        assert_eq!(limits.try_acquire(URL, now), Err(MAX_PERIOD));
+        limits.restrict(URL, Duration::MAX, now);
+        assert_eq!(limits.try_acquire(URL, now), Err(MAX_PERIOD));
+        let policy = limits.policy_mut(URL).unwrap();
+        assert_eq!(policy.windows, [Window { max_hits: MAX_HITS as usize, period: MAX_PERIOD }]);
+        assert_eq!(policy.sent.len(), MAX_HITS as usize);
+    }
+}
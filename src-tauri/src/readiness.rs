@@ -0,0 +1,281 @@
+use serde::Serialize;
+
+use crate::db::{PersonalBest, Run, Settings};
+use crate::error::{AppError, AppResult};
+use crate::privacy::{PrivacyStatus, ProfilePrivacy};
+
+/// What failing checks do to a run start, from the `readiness_mode` setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessMode {
+    Off,
+    /// Start anyway and show what failed
+    Warn,
+    /// Refuse to start until every check passes
+    Block,
+}
+
+impl ReadinessMode {
+    pub fn from_setting(mode: &str) -> Self {
+        match mode {
+            "off" => ReadinessMode::Off,
+            "block" => ReadinessMode::Block,
+            _ => ReadinessMode::Warn,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckId {
+    LogWatcher,
+    AccountVerified,
+    OverlayOpen,
+    Preset,
+    ProfilePublic,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessCheck {
+    pub id: CheckId,
+    pub label: String,
+    pub passed: bool,
+    /// Why the check failed, or a note on how it passed
+    pub detail: Option<String>,
+}
+
+/// Result of `get_run_readiness`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReadiness {
+    pub mode: ReadinessMode,
+    /// Enabled checks only
+    pub checks: Vec<ReadinessCheck>,
+    pub ready: bool,
+    /// Block mode with a failed check: the run must not start
+    pub blocked: bool,
+    /// Privacy probe made for the account checks, if any
+    pub profile_privacy: Option<ProfilePrivacy>,
+}
+
+/// Which checks the checklist runs, from settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checklist {
+    pub mode: ReadinessMode,
+    pub log_watcher: bool,
+    pub account: bool,
+    pub overlay: bool,
+    pub preset: bool,
+    pub profile_public: bool,
+}
+
+impl Checklist {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Checklist {
+            mode: ReadinessMode::from_setting(&settings.readiness_mode),
+            log_watcher: settings.readiness_log_watcher,
+            account: settings.readiness_account,
+            overlay: settings.readiness_overlay,
+            preset: settings.readiness_preset,
+            profile_public: settings.readiness_profile_public,
+        }
+    }
+
+    /// Whether evaluating needs a privacy probe of the account
+    pub fn needs_privacy(&self) -> bool {
+        self.mode != ReadinessMode::Off && (self.account || self.profile_public)
+    }
+}
+
+/// App state the checks look at, gathered by `get_run_readiness`
+#[derive(Debug)]
+pub struct ReadinessState {
+    pub log_watcher_running: bool,
+    pub overlay_open: bool,
+    pub account_name: String,
+    /// Privacy probe of the account; `None` when no account is set or it wasn't needed
+    pub privacy: Option<AppResult<ProfilePrivacy>>,
+    pub selected_preset: Option<String>,
+    /// Preset of the category's all-time PB run
+    pub pb_preset: Option<String>,
+}
+
+pub fn evaluate(checklist: &Checklist, state: ReadinessState) -> RunReadiness {
+    let mut checks = Vec::new();
+    if checklist.mode == ReadinessMode::Off {
+        return RunReadiness { mode: checklist.mode, checks, ready: true, blocked: false, profile_privacy: None };
+    }
+
+    if checklist.log_watcher {
+        checks.push(check(
+            CheckId::LogWatcher,
+            "Log watcher running",
+            state.log_watcher_running,
+            (!state.log_watcher_running).then(|| "Set the Client.txt path in Settings".to_string()),
+        ));
+    }
+
+    let account_name = state.account_name.trim();
+    let (privacy, privacy_error) = match state.privacy {
+        Some(Ok(privacy)) => (Some(privacy), None),
+        Some(Err(AppError::NotFound(_))) => (None, Some(format!("No account named {}", account_name))),
+        Some(Err(e)) => (None, Some(format!("Couldn't check the account: {}", e))),
+        None => (None, None),
+    };
+
+    if checklist.account {
+        let (passed, detail) = match &privacy {
+            _ if account_name.is_empty() => (false, Some("Set your account name in Settings".to_string())),
+            Some(p) if p.character_count == Some(0) => (false, Some("No characters on this account".to_string())),
+            Some(_) => (true, None),
+            None => (false, privacy_error.clone()),
+        };
+        checks.push(check(CheckId::AccountVerified, "Account verified", passed, detail));
+    }
+
+    if checklist.overlay {
+        checks.push(check(
+            CheckId::OverlayOpen,
+            "Overlay open",
+            state.overlay_open,
+            (!state.overlay_open).then(|| "Open the overlay from the title bar or its hotkey".to_string()),
+        ));
+    }
+
+    if checklist.preset {
+        let (passed, detail) = match (&state.pb_preset, &state.selected_preset) {
+            (None, _) => (true, Some("No personal best for this category yet".to_string())),
+            (Some(pb), Some(selected)) if pb == selected => (true, None),
+            (Some(pb), selected) => (
+                false,
+                Some(format!(
+                    "Your PB used the {} splits but {} is selected, so deltas won't line up",
+                    pb,
+                    selected.as_deref().unwrap_or("no preset")
+                )),
+            ),
+        };
+        checks.push(check(CheckId::Preset, "Same splits as PB", passed, detail));
+    }
+
+    if checklist.profile_public {
+        let (passed, detail) = match &privacy {
+            Some(p) if p.status == PrivacyStatus::Public => (true, None),
+            Some(p) if p.status == PrivacyStatus::CharactersHidden => {
+                (false, Some("Characters tab is hidden - snapshots will fail".to_string()))
+            }
+            Some(_) => (false, Some("Profile is private - snapshots will fail".to_string())),
+            None if account_name.is_empty() => (false, Some("No account to check".to_string())),
+            None => (false, privacy_error),
+        };
+        checks.push(check(CheckId::ProfilePublic, "Profile public", passed, detail));
+    }
+
+    let ready = checks.iter().all(|c| c.passed);
+    RunReadiness {
+        mode: checklist.mode,
+        checks,
+        ready,
+        blocked: !ready && checklist.mode == ReadinessMode::Block,
+        profile_privacy: privacy,
+    }
+}
+
+fn check(id: CheckId, label: &str, passed: bool, detail: Option<String>) -> ReadinessCheck {
+    ReadinessCheck { id, label: label.to_string(), passed, detail }
+}
+
+/// Breakpoint preset of the fastest all-time PB in the category, any class
+pub fn pb_preset(category: &str) -> AppResult<Option<String>> {
+    let pb = PersonalBest::get_for_patch("")?
+        .into_iter()
+        .filter(|pb| pb.category == category)
+        .min_by_key(|pb| pb.total_time_ms);
+    match pb {
+        Some(pb) => Ok(Run::get_by_id(pb.run_id)?.and_then(|run| run.breakpoint_preset)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checklist(mode: ReadinessMode) -> Checklist {
+        Checklist { mode, log_watcher: true, account: true, overlay: true, preset: true, profile_public: true }
+    }
+
+    fn state() -> ReadinessState {
+        ReadinessState {
+            log_watcher_running: true,
+            overlay_open: true,
+            account_name: "TestAccount".to_string(),
+            privacy: Some(Ok(ProfilePrivacy {
+                account_name: "TestAccount".to_string(),
+                status: PrivacyStatus::Public,
+                character_count: Some(2),
+                hints: vec![],
+            })),
+            selected_preset: Some("speedrun".to_string()),
+            pb_preset: Some("speedrun".to_string()),
+        }
+    }
+
+    fn failed(readiness: &RunReadiness) -> Vec<CheckId> {
+        readiness.checks.iter().filter(|c| !c.passed).map(|c| c.id).collect()
+    }
+
+    #[test]
+    fn test_all_checks_pass() {
+        let readiness = evaluate(&checklist(ReadinessMode::Block), state());
+        assert_eq!(readiness.checks.len(), 5);
+        assert!(readiness.ready);
+        assert!(!readiness.blocked);
+        assert!(readiness.profile_privacy.is_some());
+    }
+
+    #[test]
+    fn test_failures_block_only_in_block_mode() {
+        let mut broken = state();
+        broken.log_watcher_running = false;
+        broken.selected_preset = Some("minimal".to_string());
+
+        let warn = evaluate(&checklist(ReadinessMode::Warn), broken);
+        assert_eq!(failed(&warn), vec![CheckId::LogWatcher, CheckId::Preset]);
+        assert!(!warn.ready);
+        assert!(!warn.blocked);
+
+        let block = evaluate(&checklist(ReadinessMode::Block), ReadinessState { overlay_open: false, ..state() });
+        assert_eq!(failed(&block), vec![CheckId::OverlayOpen]);
+        assert!(block.blocked);
+
+        let off = evaluate(&checklist(ReadinessMode::Off), ReadinessState { overlay_open: false, ..state() });
+        assert!(off.checks.is_empty());
+        assert!(off.ready && !off.blocked);
+    }
+
+    #[test]
+    fn test_account_checks() {
+        let private = ReadinessState {
+            privacy: Some(Ok(ProfilePrivacy {
+                account_name: "TestAccount".to_string(),
+                status: PrivacyStatus::Private,
+                character_count: None,
+                hints: vec![],
+            })),
+            ..state()
+        };
+        // A private account still exists, it just can't be snapshotted
+        assert_eq!(failed(&evaluate(&checklist(ReadinessMode::Warn), private)), vec![CheckId::ProfilePublic]);
+
+        let missing = ReadinessState { privacy: Some(Err(AppError::NotFound("POE account".into()))), ..state() };
+        let readiness = evaluate(&checklist(ReadinessMode::Warn), missing);
+        assert_eq!(failed(&readiness), vec![CheckId::AccountVerified, CheckId::ProfilePublic]);
+        assert_eq!(readiness.checks[1].detail.as_deref(), Some("No account named TestAccount"));
+
+        let no_pb = ReadinessState { account_name: " ".to_string(), privacy: None, pb_preset: None, ..state() };
+        let readiness = evaluate(&Checklist { profile_public: false, ..checklist(ReadinessMode::Warn) }, no_pb);
+        assert_eq!(failed(&readiness), vec![CheckId::AccountVerified]);
+    }
+}
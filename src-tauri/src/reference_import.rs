@@ -0,0 +1,559 @@
+use chrono::{DateTime, NaiveDateTime};
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+
+use crate::db::ReferenceSplitData;
+use crate::error::{AppError, AppResult};
+use crate::livesplit;
+use crate::pob_builder::unescape_xml;
+
+/// Breakpoint type given to splits parsed from text, since pastes only carry names
+const PASTED_BREAKPOINT_TYPE: &str = "custom";
+
+/// Zone entered between two snapshots
+const ZONE_BREAKPOINT_TYPE: &str = "zone";
+
+/// Level reached between two snapshots, keyed `level:<n>` like ladder milestones
+const LEVEL_BREAKPOINT_TYPE: &str = "level";
+
+/// Parse a pasted split table into reference splits.
+///
+/// Accepted line formats (one split per line, blank lines and `#` comments ignored):
+/// - Tab separated: `The Coast\t1:02.34`
+/// - Comma separated: `The Coast, 1:02.34`
+/// - LiveSplit text exports / aligned columns: `The Coast    -0:03.10    1:02.34`
+///   (the last time on the line is used, so delta columns are skipped)
+///
+/// Lines without a time (headers, `-` for skipped splits) are ignored. Times are
+/// treated as cumulative split times unless they ever decrease, in which case the
+/// table is read as segment times and accumulated.
+pub fn parse_split_text(text: &str) -> AppResult<Vec<ReferenceSplitData>> {
+    lazy_static::lazy_static! {
+        // 1:02:03.45, 02:03.45, 2:03, 63.5 (optionally signed, for delta columns)
+        static ref TIME: Regex = Regex::new(
+            r"^[+-]?(?:\d+:)?(?:\d{1,2}:)?\d+(?:[.,]\d+)?$"
+        ).unwrap();
+    }
+
+    let mut rows: Vec<(String, i64)> = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, time) = if let Some((name, rest)) = line.split_once('\t') {
+            (name.trim(), last_time_token(rest, &TIME))
+        } else if let Some((name, time)) = line
+            .rsplit_once(',')
+            .filter(|(_, t)| TIME.is_match(t.trim()))
+        {
+            (name.trim(), Some(time.trim()))
+        } else {
+            split_columns(line, &TIME)
+        };
+
+        let Some(time_ms) = time.and_then(parse_time) else {
+            continue;
+        };
+        if name.is_empty() {
+            continue;
+        }
+        rows.push((name.to_string(), time_ms));
+    }
+
+    if rows.is_empty() {
+        return Err(AppError::InvalidInput(
+            "No split times found in pasted text".to_string(),
+        ));
+    }
+
+    // Segment tables go down and up; cumulative tables never decrease
+    let is_segment_table = rows.windows(2).any(|w| w[1].1 < w[0].1);
+
+    let mut elapsed = 0i64;
+    Ok(rows
+        .into_iter()
+        .map(|(name, time_ms)| {
+            let split_time_ms = if is_segment_table {
+                elapsed += time_ms;
+                elapsed
+            } else {
+                time_ms
+            };
+            split(name, split_time_ms)
+        })
+        .collect())
+}
+
+/// Parse an exported split file from another tool, picking the format by extension:
+/// - `.lss`: LiveSplit splits (Personal Best times)
+/// - `.json`: Exilence Next snapshot exports, or timestamped zone/split logs from
+///   leveling trackers
+/// - `.xml`: Path of Building builds with timed leveling trees
+/// - anything else: the pasted-table formats of [`parse_split_text`]
+pub fn parse_import_file(path: &Path, contents: &str) -> AppResult<Vec<ReferenceSplitData>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    match extension.as_deref() {
+        Some("lss") => parse_livesplit_lss(contents),
+        Some("json") if is_exilence_export(contents) => parse_exilence_snapshots(contents),
+        Some("json") => parse_timestamped_json(contents),
+        Some("xml") => parse_pob_build(contents),
+        _ => parse_split_text(contents),
+    }
+}
+
+/// Parse a LiveSplit `.lss` file, using each segment's Personal Best split time
+/// (real time, falling back to game time). Segments without a PB time are skipped.
+pub fn parse_livesplit_lss(xml: &str) -> AppResult<Vec<ReferenceSplitData>> {
+    let splits: Vec<ReferenceSplitData> = livesplit::parse(xml)?
+        .segments
+        .into_iter()
+        .filter_map(|segment| Some(split(segment.name, segment.pb_split_ms?)))
+        .collect();
+
+    if splits.is_empty() {
+        return Err(AppError::InvalidInput(
+            "No Personal Best split times found in LiveSplit file".to_string(),
+        ));
+    }
+    Ok(splits)
+}
+
+/// Parse a JSON export of timestamped zone entries or splits, as written by
+/// leveling trackers. Accepts an array of entries, or an object holding one under
+/// `splits`, `segments`, `events`, or `zones`. Each entry needs a name (`name`,
+/// `zone`, `zoneName`, or `split`) and one of:
+/// - `splitTimeMs`/`timeMs`: elapsed milliseconds
+/// - `time`/`splitTime`: elapsed time as `h:mm:ss` text or seconds
+/// - `timestamp`: wall-clock time (RFC 3339, `YYYY/MM/DD HH:MM:SS`, or epoch
+///   milliseconds), taken relative to the first entry
+pub fn parse_timestamped_json(json: &str) -> AppResult<Vec<ReferenceSplitData>> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+    let entries = match &value {
+        Value::Array(entries) => entries,
+        Value::Object(object) => ["splits", "segments", "events", "zones"]
+            .iter()
+            .find_map(|key| object.get(*key).and_then(Value::as_array))
+            .ok_or_else(|| AppError::InvalidInput("No list of splits found in JSON".to_string()))?,
+        _ => return Err(AppError::InvalidInput("Expected a JSON array of splits".to_string())),
+    };
+
+    let mut start_ms: Option<i64> = None;
+    let mut splits = Vec::new();
+    for entry in entries {
+        let Some(name) = ["name", "zone", "zoneName", "split"]
+            .iter()
+            .find_map(|key| entry.get(*key).and_then(Value::as_str))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        else {
+            continue;
+        };
+
+        let elapsed_ms = if let Some(ms) = ["splitTimeMs", "timeMs"]
+            .iter()
+            .find_map(|key| entry.get(*key).and_then(Value::as_f64))
+        {
+            Some(ms.round() as i64)
+        } else if let Some(time) = ["time", "splitTime"].iter().find_map(|key| entry.get(*key)) {
+            match time {
+                Value::String(text) => parse_time(text),
+                Value::Number(secs) => secs.as_f64().map(|s| (s * 1000.0).round() as i64),
+                _ => None,
+            }
+        } else {
+            entry
+                .get("timestamp")
+                .and_then(timestamp_ms)
+                .map(|at_ms| at_ms - *start_ms.get_or_insert(at_ms))
+        };
+
+        if let Some(ms) = elapsed_ms.filter(|ms| *ms >= 0) {
+            splits.push(split(name.to_string(), ms));
+        }
+    }
+
+    if splits.is_empty() {
+        return Err(AppError::InvalidInput(
+            "No timed splits found in JSON".to_string(),
+        ));
+    }
+    Ok(splits)
+}
+
+/// Whether a JSON file is an Exilence Next export: an object with a `snapshots`
+/// list whose entries are stamped with `created`
+fn is_exilence_export(json: &str) -> bool {
+    serde_json::from_str::<Value>(json)
+        .ok()
+        .and_then(|value| value.get("snapshots")?.get(0)?.get("created").cloned())
+        .is_some()
+}
+
+/// Parse an Exilence Next profile or session export. Its `snapshots` are taken in
+/// `created` order and timed from the first; each one whose character level or
+/// zone differs from the snapshot before becomes a split:
+/// - a zone split named after the new `area` (or `zone`/`areaName`)
+/// - a `Level <n>` split when the level (`level`, or `character.level`) went up
+///
+/// Snapshots without either are only net worth and are skipped.
+pub fn parse_exilence_snapshots(json: &str) -> AppResult<Vec<ReferenceSplitData>> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid JSON: {}", e)))?;
+    let snapshots = value
+        .get("snapshots")
+        .and_then(Value::as_array)
+        .ok_or_else(|| AppError::InvalidInput("No snapshots found in Exilence Next export".to_string()))?;
+
+    let mut stamped: Vec<(i64, &Value)> = snapshots
+        .iter()
+        .filter_map(|snapshot| Some((timestamp_ms(snapshot.get("created")?)?, snapshot)))
+        .collect();
+    stamped.sort_by_key(|(at_ms, _)| *at_ms);
+    let Some(start_ms) = stamped.first().map(|(at_ms, _)| *at_ms) else {
+        return Err(AppError::InvalidInput("No timestamped snapshots in Exilence Next export".to_string()));
+    };
+
+    let mut zone: Option<&str> = None;
+    let mut level: Option<i64> = None;
+    let mut splits = Vec::new();
+    for (at_ms, snapshot) in stamped {
+        let elapsed_ms = at_ms - start_ms;
+        let snapshot_zone = ["area", "zone", "areaName"]
+            .iter()
+            .find_map(|key| snapshot.get(*key).and_then(Value::as_str))
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+        let snapshot_level = snapshot
+            .get("level")
+            .or_else(|| snapshot.get("character")?.get("level"))
+            .and_then(Value::as_i64);
+
+        if let Some(name) = snapshot_zone.filter(|name| zone != Some(*name)) {
+            if zone.is_some() {
+                splits.push(ReferenceSplitData {
+                    breakpoint_type: ZONE_BREAKPOINT_TYPE.to_string(),
+                    ..split(name.to_string(), elapsed_ms)
+                });
+            }
+            zone = Some(name);
+        }
+        if let Some(reached) = snapshot_level.filter(|reached| level.is_some_and(|level| *reached > level)) {
+            splits.push(level_split(reached, elapsed_ms));
+        }
+        level = snapshot_level.or(level);
+    }
+
+    if splits.is_empty() {
+        return Err(AppError::InvalidInput(
+            "No level or zone changes found in Exilence Next snapshots".to_string(),
+        ));
+    }
+    Ok(splits)
+}
+
+/// Parse a Path of Building build XML. PoB keeps no clock of its own, so this
+/// reads the leveling trees: each `<Spec>` whose title names a time, like
+/// `Level 12 - 0:15:30` or `The Coast 2:10`, becomes a split. A title that
+/// otherwise only names a level (`Level 12`, `lvl 12`) gives a `Level <n>`
+/// split; any other text is used as the split name.
+pub fn parse_pob_build(xml: &str) -> AppResult<Vec<ReferenceSplitData>> {
+    lazy_static::lazy_static! {
+        static ref SPEC_TITLE: Regex = Regex::new(r#"<Spec\b[^>]*?\btitle="([^"]*)""#).unwrap();
+        static ref TITLE_TIME: Regex = Regex::new(r"(?:^|\s|\()(\d+:\d{2}(?::\d{2})?(?:\.\d+)?)\)?\s*$").unwrap();
+        static ref TITLE_LEVEL: Regex = Regex::new(r"(?i)^(?:level|lvl)\.?\s*(\d+)$").unwrap();
+    }
+
+    if !xml.contains("<PathOfBuilding") {
+        return Err(AppError::InvalidInput("Not a Path of Building build".to_string()));
+    }
+
+    let splits: Vec<ReferenceSplitData> = SPEC_TITLE
+        .captures_iter(xml)
+        .filter_map(|spec| {
+            let title = unescape_xml(spec[1].trim());
+            let time = TITLE_TIME.captures(&title)?;
+            let split_time_ms = parse_time(&time[1])?;
+            let name = title[..time.get(0)?.start()].trim().trim_end_matches(['-', '@', '(']).trim();
+            if let Some(level) = TITLE_LEVEL.captures(name).and_then(|level| level[1].parse().ok()) {
+                return Some(level_split(level, split_time_ms));
+            }
+            (!name.is_empty()).then(|| split(name.to_string(), split_time_ms))
+        })
+        .collect();
+
+    if splits.is_empty() {
+        return Err(AppError::InvalidInput(
+            "No timed leveling trees found in Path of Building build. Title them with a time, like \"Level 12 - 0:15:30\".".to_string(),
+        ));
+    }
+    Ok(splits)
+}
+
+fn level_split(level: i64, split_time_ms: i64) -> ReferenceSplitData {
+    ReferenceSplitData {
+        breakpoint_name: format!("Level {}", level),
+        breakpoint_type: LEVEL_BREAKPOINT_TYPE.to_string(),
+        breakpoint_key: Some(format!("level:{}", level)),
+        split_time_ms,
+    }
+}
+
+/// Wall-clock milliseconds of a JSON timestamp
+fn timestamp_ms(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(ms) => ms.as_i64(),
+        Value::String(text) => DateTime::parse_from_rfc3339(text)
+            .map(|t| t.timestamp_millis())
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(text, "%Y/%m/%d %H:%M:%S")
+                    .ok()
+                    .map(|t| t.and_utc().timestamp_millis())
+            }),
+        _ => None,
+    }
+}
+
+fn split(name: String, split_time_ms: i64) -> ReferenceSplitData {
+    ReferenceSplitData {
+        breakpoint_name: name,
+        breakpoint_type: PASTED_BREAKPOINT_TYPE.to_string(),
+        breakpoint_key: None,
+        split_time_ms,
+    }
+}
+
+/// Find the last unsigned time among whitespace separated tokens
+fn last_time_token<'a>(text: &'a str, time: &Regex) -> Option<&'a str> {
+    text.split_whitespace()
+        .rev()
+        .find(|t| time.is_match(t) && !t.starts_with(['+', '-']))
+}
+
+/// Split an aligned-column line into the name and the last unsigned time. Trailing
+/// time and `-` columns are peeled off from the right; bare integers count as
+/// part of the name so names like `Act 1` stay intact.
+fn split_columns<'a>(line: &'a str, time: &Regex) -> (&'a str, Option<&'a str>) {
+    let mut name = line;
+    while let Some((rest, token)) = name.trim_end().rsplit_once(char::is_whitespace) {
+        let is_column = token == "-" || (time.is_match(token) && token.contains([':', '.']));
+        if !is_column {
+            break;
+        }
+        name = rest;
+    }
+    (name.trim(), last_time_token(&line[name.len()..], time))
+}
+
+/// Parse `h:mm:ss.ff`, `mm:ss.ff`, or `ss.ff` into milliseconds
+pub fn parse_time(text: &str) -> Option<i64> {
+    let text = text.trim().replace(',', ".");
+    let mut parts: Vec<&str> = text.split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+
+    let seconds: f64 = parts.pop()?.parse().ok()?;
+    let mut total_ms = (seconds * 1000.0).round() as i64;
+    let mut multiplier = 60_000i64;
+    while let Some(part) = parts.pop() {
+        let value: i64 = part.parse().ok()?;
+        total_ms += value * multiplier;
+        multiplier *= 60;
+    }
+
+    if total_ms < 0 {
+        return None;
+    }
+    Some(total_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn times(splits: &[ReferenceSplitData]) -> Vec<i64> {
+        splits.iter().map(|s| s.split_time_ms).collect()
+    }
+
+    #[test]
+    fn test_parse_time_formats() {
+        assert_eq!(parse_time("1:02:03.45"), Some(3_723_450));
+        assert_eq!(parse_time("02:03.5"), Some(123_500));
+        assert_eq!(parse_time("2:03"), Some(123_000));
+        assert_eq!(parse_time("45.25"), Some(45_250));
+        assert_eq!(parse_time("1:02,5"), Some(62_500));
+        assert_eq!(parse_time("abc"), None);
+    }
+
+    #[test]
+    fn test_parse_tab_separated() {
+        let text = "The Coast\t1:02.34\nThe Mud Flats\t3:10.00\n";
+        let splits = parse_split_text(text).unwrap();
+        assert_eq!(splits[0].breakpoint_name, "The Coast");
+        assert_eq!(splits[1].breakpoint_name, "The Mud Flats");
+        assert_eq!(times(&splits), vec![62_340, 190_000]);
+    }
+
+    #[test]
+    fn test_parse_comma_separated() {
+        let text = "# WR by someone\nLioneye's Watch, 0:45\nMerveil, 9:58.2\n\n";
+        let splits = parse_split_text(text).unwrap();
+        assert_eq!(splits.len(), 2);
+        assert_eq!(splits[0].breakpoint_name, "Lioneye's Watch");
+        assert_eq!(times(&splits), vec![45_000, 598_200]);
+    }
+
+    #[test]
+    fn test_parse_livesplit_columns() {
+        let text = "\
+Split            Delta       Time
+The Coast        -0:03.10    1:02.34
+The Tidal Island +0:01.00    2:30.00
+Skipped Split    -           -
+Act 1            -1:00.00    1:05:00.00";
+        let splits = parse_split_text(text).unwrap();
+        let names: Vec<&str> = splits.iter().map(|s| s.breakpoint_name.as_str()).collect();
+        assert_eq!(names, vec!["The Coast", "The Tidal Island", "Act 1"]);
+        assert_eq!(times(&splits), vec![62_340, 150_000, 3_900_000]);
+    }
+
+    #[test]
+    fn test_segment_times_are_accumulated() {
+        let text = "A\t1:00\nB\t0:30\nC\t2:00";
+        let splits = parse_split_text(text).unwrap();
+        assert_eq!(times(&splits), vec![60_000, 90_000, 210_000]);
+    }
+
+    #[test]
+    fn test_no_times_is_error() {
+        assert!(parse_split_text("Split\tTime\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_livesplit_lss() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Run version="1.7.0">
+  <Segments>
+    <Segment>
+      <Name>The Coast</Name>
+      <SplitTimes>
+        <SplitTime name="Personal Best">
+          <RealTime>00:01:02.3400000</RealTime>
+        </SplitTime>
+      </SplitTimes>
+    </Segment>
+    <Segment>
+      <Name>Never Finished</Name>
+      <SplitTimes>
+        <SplitTime name="Personal Best" />
+      </SplitTimes>
+    </Segment>
+    <Segment>
+      <Name>Merveil &amp; Co</Name>
+      <SplitTimes>
+        <SplitTime name="Personal Best">
+          <GameTime>00:09:58.2000000</GameTime>
+        </SplitTime>
+      </SplitTimes>
+    </Segment>
+  </Segments>
+</Run>"#;
+        let splits = parse_livesplit_lss(xml).unwrap();
+        let names: Vec<&str> = splits.iter().map(|s| s.breakpoint_name.as_str()).collect();
+        assert_eq!(names, vec!["The Coast", "Merveil & Co"]);
+        assert_eq!(times(&splits), vec![62_340, 598_200]);
+    }
+
+    #[test]
+    fn test_parse_timestamped_json() {
+        let json = r#"[
+            {"zone": "Lioneye's Watch", "timestamp": "2024-08-01T12:00:00Z"},
+            {"zone": "The Coast", "timestamp": "2024-08-01T12:01:05Z"},
+            {"zone": "The Mud Flats", "timestamp": "2024-08-01T12:03:00+00:00"}
+        ]"#;
+        let splits = parse_timestamped_json(json).unwrap();
+        assert_eq!(splits[1].breakpoint_name, "The Coast");
+        assert_eq!(times(&splits), vec![0, 65_000, 180_000]);
+
+        let json = r#"{"splits": [
+            {"name": "The Coast", "splitTimeMs": 62340},
+            {"name": "Merveil", "time": "9:58.2"},
+            {"name": "No Time"},
+            {"name": "Act 2", "time": 900}
+        ]}"#;
+        assert_eq!(times(&parse_timestamped_json(json).unwrap()), vec![62_340, 598_200, 900_000]);
+
+        let json = r#"[{"zone": "The Coast", "timestamp": "2024/08/01 12:00:00"},
+                       {"zone": "The Ledge", "timestamp": "2024/08/01 12:00:30"}]"#;
+        assert_eq!(times(&parse_timestamped_json(json).unwrap()), vec![0, 30_000]);
+        assert!(parse_timestamped_json("{}").is_err());
+    }
+
+    #[test]
+    fn test_parse_exilence_snapshots() {
+        let json = r#"{"profile": {"name": "Race"}, "snapshots": [
+            {"created": "2024-08-01T12:05:00Z", "character": {"level": 4}, "area": "The Coast"},
+            {"created": "2024-08-01T12:00:00Z", "character": {"level": 1}, "area": "The Twilight Strand"},
+            {"created": "2024-08-01T12:06:00Z", "character": {"level": 4}, "area": "The Coast"},
+            {"created": "2024-08-01T12:09:30Z", "level": 7, "area": "The Mud Flats"},
+            {"created": "2024-08-01T12:10:00Z", "netWorth": 12.5}
+        ]}"#;
+        assert!(is_exilence_export(json));
+        let splits = parse_exilence_snapshots(json).unwrap();
+        let names: Vec<&str> = splits.iter().map(|s| s.breakpoint_name.as_str()).collect();
+        assert_eq!(names, vec!["The Coast", "Level 4", "The Mud Flats", "Level 7"]);
+        assert_eq!(times(&splits), vec![300_000, 300_000, 570_000, 570_000]);
+        assert_eq!(splits[0].breakpoint_type, "zone");
+        assert_eq!(splits[1].breakpoint_key.as_deref(), Some("level:4"));
+
+        // Net worth alone has nothing to split on
+        let worth_only = r#"{"snapshots": [{"created": "2024-08-01T12:00:00Z", "netWorth": 1}]}"#;
+        assert!(parse_exilence_snapshots(worth_only).is_err());
+        assert!(!is_exilence_export(r#"{"splits": []}"#));
+    }
+
+    #[test]
+    fn test_parse_pob_build() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<PathOfBuilding>
+	<Build level="70" className="Witch" ascendClassName="Necromancer" targetVersion="3_0">
+	</Build>
+	<Tree activeSpec="4">
+		<Spec title="Level 12 - 0:15:30" classId="3" treeVersion="3_27" nodes="1,2"/>
+		<Spec title="Act 2 &amp; Library (lvl 24)" classId="3" treeVersion="3_27" nodes="1,2,3"/>
+		<Spec treeVersion="3_27" title="Merveil 9:58.2" classId="3"/>
+		<Spec title="lvl 30 (1:02:00)" classId="3" treeVersion="3_27"/>
+	</Tree>
+</PathOfBuilding>"#;
+        let splits = parse_pob_build(xml).unwrap();
+        let names: Vec<&str> = splits.iter().map(|s| s.breakpoint_name.as_str()).collect();
+        assert_eq!(names, vec!["Level 12", "Merveil", "Level 30"]);
+        assert_eq!(times(&splits), vec![930_000, 598_200, 3_720_000]);
+        assert_eq!(splits[0].breakpoint_type, "level");
+        assert_eq!(splits[1].breakpoint_type, "custom");
+
+        assert!(parse_pob_build(r#"<PathOfBuilding><Tree><Spec title="Default"/></Tree></PathOfBuilding>"#).is_err());
+        assert!(parse_pob_build("<Run></Run>").is_err());
+    }
+
+    #[test]
+    fn test_parse_import_file_dispatch() {
+        let lss = "<Segment><Name>A</Name><SplitTime name=\"Personal Best\"><RealTime>00:00:10</RealTime></SplitTime></Segment>";
+        assert_eq!(times(&parse_import_file(Path::new("run.LSS"), lss).unwrap()), vec![10_000]);
+        let csv = "A, 0:10\nB, 0:20";
+        assert_eq!(times(&parse_import_file(Path::new("wr.csv"), csv).unwrap()), vec![10_000, 20_000]);
+        let exilence = r#"{"snapshots": [{"created": 0, "level": 1}, {"created": 5000, "level": 2}]}"#;
+        assert_eq!(times(&parse_import_file(Path::new("profile.json"), exilence).unwrap()), vec![5_000]);
+        let pob = r#"<PathOfBuilding><Spec title="Level 2 - 0:05"/></PathOfBuilding>"#;
+        assert_eq!(times(&parse_import_file(Path::new("build.xml"), pob).unwrap()), vec![5_000]);
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Character ruleset a run was played under, read from its league name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ruleset {
+    #[serde(rename = "SC")]
+    Softcore,
+    #[serde(rename = "HC")]
+    Hardcore,
+    #[serde(rename = "SSF")]
+    Ssf,
+    #[serde(rename = "HCSSF")]
+    HardcoreSsf,
+}
+
+impl Ruleset {
+    /// Ruleset of a league as the character API names it ("Settlers",
+    /// "Hardcore Settlers", "SSF Settlers HC", "Solo Self-Found"); `None` when
+    /// the league isn't known yet
+    pub fn from_league(league: &str) -> Option<Ruleset> {
+        let league = league.trim().to_lowercase();
+        if league.is_empty() {
+            return None;
+        }
+        let words: Vec<&str> = league.split(|c: char| !c.is_alphanumeric() && c != '-').collect();
+        let hardcore = words.iter().any(|w| *w == "hardcore" || *w == "hc");
+        let ssf = words.contains(&"ssf") || league.contains("solo self-found");
+        Some(match (hardcore, ssf) {
+            (false, false) => Ruleset::Softcore,
+            (true, false) => Ruleset::Hardcore,
+            (false, true) => Ruleset::Ssf,
+            (true, true) => Ruleset::HardcoreSsf,
+        })
+    }
+
+    /// The stored and filtered name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Ruleset::Softcore => "SC",
+            Ruleset::Hardcore => "HC",
+            Ruleset::Ssf => "SSF",
+            Ruleset::HardcoreSsf => "HCSSF",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ruleset_from_league() {
+        assert_eq!(Ruleset::from_league("Standard"), Some(Ruleset::Softcore));
+        assert_eq!(Ruleset::from_league("Settlers"), Some(Ruleset::Softcore));
+        assert_eq!(Ruleset::from_league("Hardcore"), Some(Ruleset::Hardcore));
+        assert_eq!(Ruleset::from_league("Hardcore Settlers"), Some(Ruleset::Hardcore));
+        assert_eq!(Ruleset::from_league("SSF Settlers"), Some(Ruleset::Ssf));
+        assert_eq!(Ruleset::from_league("Solo Self-Found"), Some(Ruleset::Ssf));
+        assert_eq!(Ruleset::from_league("SSF Settlers HC"), Some(Ruleset::HardcoreSsf));
+        assert_eq!(Ruleset::from_league("Hardcore SSF"), Some(Ruleset::HardcoreSsf));
+        // Words, not substrings: "Harvest" isn't HC
+        assert_eq!(Ruleset::from_league("Harvest"), Some(Ruleset::Softcore));
+        assert_eq!(Ruleset::from_league(""), None);
+        assert_eq!(Ruleset::from_league("SSF Settlers HC").unwrap().as_str(), "HCSSF");
+    }
+}
@@ -0,0 +1,201 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::certificate::RunCertificate;
+use crate::error::{AppError, AppResult};
+
+/// Format version written by `export_run_json`
+pub const VERSION: &str = "0.3.1";
+
+/// Export format minors `import_run_json` reads. 0.2 files predate the
+/// account name, breakpoint keys, and snapshot skills/stats.
+const READABLE: [&str; 2] = ["0.2", "0.3"];
+
+/// A run file written by `export_run_json`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunExport {
+    pub version: String,
+    pub run: ExportedRun,
+    #[serde(default)]
+    pub splits: Vec<ExportedSplit>,
+    #[serde(default)]
+    pub snapshots: Vec<ExportedSnapshot>,
+    /// Completed runs exported from 0.3.1 on
+    #[serde(default)]
+    pub certificate: Option<RunCertificate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedRun {
+    pub character: String,
+    /// Missing from 0.2 exports
+    #[serde(default)]
+    pub account_name: Option<String>,
+    pub class: String,
+    pub ascendancy: Option<String>,
+    pub league: String,
+    pub category: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub total_time_ms: Option<i64>,
+    /// Missing before load removal
+    #[serde(default)]
+    pub load_time_ms: i64,
+    pub is_completed: bool,
+    pub breakpoint_preset: Option<String>,
+    #[serde(default)]
+    pub game_version: Option<String>,
+    pub video_url: Option<String>,
+    pub vod_offset_ms: Option<i64>,
+    pub review_notes: Option<String>,
+    /// Missing before run notes and tags
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedSplit {
+    pub breakpoint_name: String,
+    pub breakpoint_type: String,
+    #[serde(default)]
+    pub breakpoint_key: Option<String>,
+    pub split_time_ms: i64,
+    pub segment_time_ms: i64,
+    pub delta_ms: Option<i64>,
+    #[serde(default)]
+    pub town_time_ms: i64,
+    #[serde(default)]
+    pub hideout_time_ms: i64,
+    #[serde(default)]
+    pub load_time_ms: i64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: Vec<ExportedSplitNote>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedSplitNote {
+    #[serde(default)]
+    pub note: String,
+    pub mistake_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedSnapshot {
+    pub split_name: String,
+    pub elapsed_time_ms: i64,
+    pub character_level: i32,
+    #[serde(default)]
+    pub experience: Option<i64>,
+    #[serde(default)]
+    pub items: Value,
+    #[serde(default)]
+    pub skills: Value,
+    #[serde(default)]
+    pub passive_tree: Value,
+    #[serde(default)]
+    pub stats: Value,
+    pub pob_code: Option<String>,
+    #[serde(default)]
+    pub is_final: bool,
+}
+
+/// Parse an exported run, rejecting versions this build can't read
+pub fn parse(contents: &str) -> AppResult<RunExport> {
+    let value: Value = serde_json::from_str(contents)
+        .map_err(|e| AppError::InvalidInput(format!("Not a run export: {}", e)))?;
+    let version = value.get("version").and_then(Value::as_str).unwrap_or_default();
+    let minor = version.splitn(3, '.').take(2).collect::<Vec<_>>().join(".");
+    if !READABLE.contains(&minor.as_str()) {
+        return Err(AppError::InvalidInput(format!("Unsupported run export version: {:?}", version)));
+    }
+    serde_json::from_value(value).map_err(|e| AppError::InvalidInput(format!("Invalid run export: {}", e)))
+}
+
+/// Whether the run was recorded by `account_name`. Exports that don't name
+/// an account (0.2) count as someone else's.
+pub fn is_own(run: &ExportedRun, account_name: &str) -> bool {
+    let account_name = account_name.trim();
+    !account_name.is_empty()
+        && run
+            .account_name
+            .as_deref()
+            .is_some_and(|exported| exported.trim().eq_ignore_ascii_case(account_name))
+}
+
+/// A snapshot's JSON field as stored, with `fallback` for fields the export lacks
+pub fn json_field(value: &Value, fallback: &str) -> String {
+    if value.is_null() {
+        fallback.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(version: &str, account: &str) -> String {
+        serde_json::json!({
+            "version": version,
+            "exportedAt": "2026-01-01T00:00:00Z",
+            "run": {
+                "character": "Runner",
+                "accountName": account,
+                "class": "Witch",
+                "ascendancy": null,
+                "league": "Standard",
+                "category": "Act 10 Any%",
+                "startedAt": "2026-01-01T00:00:00Z",
+                "endedAt": null,
+                "totalTimeMs": 3_600_000,
+                "isCompleted": true,
+                "isPersonalBest": false,
+                "breakpointPreset": null,
+                "videoUrl": null,
+                "vodOffsetMs": null,
+                "reviewNotes": null,
+            },
+            "splits": [],
+            "snapshots": [{
+                "splitName": "Unknown",
+                "elapsedTimeMs": 0,
+                "characterLevel": 1,
+                "items": [],
+                "passiveTree": {},
+                "pobCode": null,
+                "isFinal": false,
+            }],
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_checks_version_and_account() {
+        let old = parse(&export("0.2.0", "Me")).unwrap();
+        assert_eq!(old.run.character, "Runner");
+        assert_eq!(json_field(&old.snapshots[0].skills, "[]"), "[]");
+        assert_eq!(json_field(&old.snapshots[0].items, "[]"), "[]");
+        assert!(parse(&export(VERSION, "Me")).is_ok());
+
+        assert!(matches!(parse(&export("1.0.0", "Me")), Err(AppError::InvalidInput(_))));
+        assert!(matches!(parse(&export("0.1.0", "Me")), Err(AppError::InvalidInput(_))));
+        assert!(matches!(parse("[]"), Err(AppError::InvalidInput(_))));
+
+        let run = parse(&export(VERSION, "Me")).unwrap().run;
+        assert!(is_own(&run, "me"));
+        assert!(!is_own(&run, "Someone"));
+        assert!(!is_own(&run, ""));
+        let anonymous = ExportedRun { account_name: None, ..run };
+        assert!(!is_own(&anonymous, "Me"));
+    }
+}
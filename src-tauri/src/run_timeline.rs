@@ -0,0 +1,102 @@
+use serde::Serialize;
+
+use crate::db::{RunEvent, SnapshotSummary, Split};
+
+/// One entry of a run's timeline, at run-clock time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEntry {
+    /// `zone_enter`, `level_up`, `death`, `pause`, `resume`, `split` or `snapshot`
+    pub kind: String,
+    pub elapsed_ms: i64,
+    /// Zone, level, breakpoint or character, depending on the kind
+    pub label: String,
+    pub split_id: Option<i64>,
+    pub snapshot_id: Option<i64>,
+}
+
+/// Merge a run's recorded events, splits and snapshots in run-clock order.
+/// At equal times the event comes first (the zone entry before the split it
+/// triggered, the split before its snapshot).
+pub fn build(events: &[RunEvent], splits: &[Split], snapshots: &[SnapshotSummary]) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = events
+        .iter()
+        .map(|event| {
+            let detail = event.detail.clone().unwrap_or_default();
+            TimelineEntry {
+                label: match event.kind.as_str() {
+                    "level_up" => format!("Level {}", detail),
+                    "pause" => "Timer paused".to_string(),
+                    "resume" => "Timer resumed".to_string(),
+                    _ => detail,
+                },
+                kind: event.kind.clone(),
+                elapsed_ms: event.elapsed_ms,
+                split_id: None,
+                snapshot_id: None,
+            }
+        })
+        .collect();
+
+    entries.extend(splits.iter().map(|split| TimelineEntry {
+        kind: "split".to_string(),
+        elapsed_ms: split.split_time_ms,
+        label: split.breakpoint_name.clone(),
+        split_id: Some(split.id),
+        snapshot_id: None,
+    }));
+
+    entries.extend(snapshots.iter().map(|snapshot| {
+        let split_name = splits
+            .iter()
+            .find(|split| Some(split.id) == snapshot.split_id)
+            .map(|split| split.breakpoint_name.clone());
+        TimelineEntry {
+            kind: "snapshot".to_string(),
+            elapsed_ms: snapshot.elapsed_time_ms,
+            label: match split_name {
+                Some(name) => format!("{} (level {})", name, snapshot.character_level),
+                None if snapshot.is_final => format!("Final build (level {})", snapshot.character_level),
+                None => format!("Level {}", snapshot.character_level),
+            },
+            split_id: snapshot.split_id,
+            snapshot_id: Some(snapshot.id),
+        }
+    }));
+
+    // Stable, so ties keep the events-splits-snapshots order
+    entries.sort_by_key(|entry| entry.elapsed_ms);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: &str, elapsed_ms: i64, detail: Option<&str>) -> RunEvent {
+        RunEvent {
+            id: 0,
+            run_id: 1,
+            kind: kind.to_string(),
+            elapsed_ms,
+            detail: detail.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_events_in_clock_order() {
+        let events = vec![
+            event("zone_enter", 0, Some("The Twilight Strand")),
+            event("level_up", 30_000, Some("2")),
+            event("pause", 45_000, None),
+            event("resume", 45_000, None),
+            event("zone_enter", 20_000, Some("The Coast")),
+        ];
+        let timeline = build(&events, &[], &[]);
+        let labels: Vec<&str> = timeline.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec!["The Twilight Strand", "The Coast", "Level 2", "Timer paused", "Timer resumed"]
+        );
+    }
+}
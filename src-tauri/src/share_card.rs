@@ -0,0 +1,188 @@
+use std::convert::Infallible;
+
+use embedded_graphics::mono_font::iso_8859_1::FONT_10X20;
+use embedded_graphics::mono_font::MonoTextStyle;
+use embedded_graphics::pixelcolor::Rgb888;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{Circle, PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, RoundedRectangle};
+use embedded_graphics::text::{Baseline, Text};
+
+use crate::splits_image::{encode, format_time, Canvas, BACKGROUND, GOLD, MUTED, TEXT};
+
+/// Link-preview size used by Discord, X and most other sites
+const WIDTH: u32 = 1200;
+const HEIGHT: u32 = 630;
+const BORDER: u32 = 6;
+const EMBLEM_CENTER: Point = Point::new(230, 315);
+const EMBLEM_DIAMETER: u32 = 280;
+/// Left edge of the text block right of the class emblem
+const TEXT_LEFT: i32 = 440;
+const GLYPH: Size = Size::new(10, 20);
+/// Characters of the category that fit at 3x between the emblem and the border
+const CATEGORY_CHARS: usize = 24;
+
+/// What goes on a run's share card
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareCard {
+    pub category: String,
+    pub class: String,
+    pub ascendancy: Option<String>,
+    pub character_name: String,
+    pub total_time_ms: i64,
+    /// Already formatted, e.g. "Jan 15, 2024"
+    pub date: String,
+    pub is_personal_best: bool,
+}
+
+/// Emblem color for a base class (ascendancies are drawn with their base class)
+fn class_color(class: &str) -> Rgb888 {
+    match class {
+        "Marauder" => Rgb888::new(0xb0, 0x3a, 0x2e),
+        "Ranger" => Rgb888::new(0x4c, 0x8c, 0x3c),
+        "Witch" => Rgb888::new(0x6a, 0x4c, 0xa8),
+        "Duelist" => Rgb888::new(0xc0, 0x7a, 0x2c),
+        "Templar" => Rgb888::new(0x3c, 0x6e, 0xb4),
+        "Shadow" => Rgb888::new(0x2e, 0x8c, 0x8c),
+        "Scion" => Rgb888::new(0xb4, 0x9a, 0x5a),
+        _ => Rgb888::new(0x5a, 0x58, 0x55),
+    }
+}
+
+/// Draws each pixel as a `scale`-sized block, so the built-in bitmap font can
+/// be used for large text
+struct Scaled<'a> {
+    canvas: &'a mut Canvas,
+    origin: Point,
+    scale: u32,
+}
+
+impl OriginDimensions for Scaled<'_> {
+    fn size(&self) -> Size {
+        let size = self.canvas.size();
+        Size::new(size.width / self.scale, size.height / self.scale)
+    }
+}
+
+impl DrawTarget for Scaled<'_> {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Infallible>
+    where
+        I: IntoIterator<Item = Pixel<Rgb888>>,
+    {
+        for Pixel(point, color) in pixels {
+            let top_left = self.origin + point * self.scale as i32;
+            self.canvas.fill_solid(&Rectangle::new(top_left, Size::new(self.scale, self.scale)), color)?;
+        }
+        Ok(())
+    }
+}
+
+/// Draw `s` at `scale` times the font size with its top-left corner at `at`
+fn text(canvas: &mut Canvas, s: &str, at: Point, scale: u32, color: Rgb888) {
+    let mut target = Scaled { canvas, origin: at, scale };
+    let style = MonoTextStyle::new(&FONT_10X20, color);
+    let _ = Text::with_baseline(s, Point::zero(), style, Baseline::Top).draw(&mut target);
+}
+
+/// Size of `s` drawn at `scale`
+fn text_size(s: &str, scale: u32) -> Size {
+    Size::new(GLYPH.width * s.chars().count() as u32, GLYPH.height) * scale
+}
+
+/// Render the card to PNG bytes: a class emblem on the left; category, final
+/// time, class and character, and date on the right; a PB badge when it was one
+pub fn render_png(card: &ShareCard) -> Result<Vec<u8>, png::EncodingError> {
+    let mut canvas = Canvas::new(WIDTH, HEIGHT, BACKGROUND);
+
+    let _ = Rectangle::new(Point::zero(), Size::new(WIDTH, HEIGHT))
+        .into_styled(PrimitiveStyle::with_stroke(GOLD, BORDER))
+        .draw(&mut canvas);
+
+    // Class emblem: a ring in the class color with the class initial
+    let emblem = PrimitiveStyleBuilder::new()
+        .fill_color(class_color(&card.class))
+        .stroke_color(GOLD)
+        .stroke_width(BORDER)
+        .build();
+    let _ = Circle::with_center(EMBLEM_CENTER, EMBLEM_DIAMETER).into_styled(emblem).draw(&mut canvas);
+    let initial: String = card.class.chars().next().unwrap_or('?').to_uppercase().collect();
+    let initial_size = text_size(&initial, 8);
+    let initial_at = EMBLEM_CENTER - Point::new(initial_size.width as i32 / 2, initial_size.height as i32 / 2);
+    text(&mut canvas, &initial, initial_at, 8, TEXT);
+
+    let category: String = if card.category.chars().count() > CATEGORY_CHARS {
+        card.category.chars().take(CATEGORY_CHARS - 1).chain(['.']).collect()
+    } else {
+        card.category.clone()
+    };
+    text(&mut canvas, &category, Point::new(TEXT_LEFT, 120), 3, GOLD);
+    text(&mut canvas, &format_time(card.total_time_ms), Point::new(TEXT_LEFT, 200), 6, TEXT);
+
+    let class = match &card.ascendancy {
+        Some(ascendancy) => format!("{} ({})", ascendancy, card.class),
+        None => card.class.clone(),
+    };
+    text(&mut canvas, &class, Point::new(TEXT_LEFT, 360), 2, TEXT);
+    text(&mut canvas, &card.character_name, Point::new(TEXT_LEFT, 410), 2, MUTED);
+    text(&mut canvas, &card.date, Point::new(TEXT_LEFT, 460), 2, MUTED);
+
+    if card.is_personal_best {
+        let label = "PERSONAL BEST";
+        let label_size = text_size(label, 2);
+        let badge = Rectangle::new(
+            Point::new(WIDTH as i32 - 60 - label_size.width as i32 - 40, 40),
+            label_size + Size::new(40, 24),
+        );
+        let _ = RoundedRectangle::with_equal_corners(badge, Size::new(12, 12))
+            .into_styled(PrimitiveStyle::with_fill(GOLD))
+            .draw(&mut canvas);
+        text(&mut canvas, label, badge.top_left + Point::new(20, 12), 2, BACKGROUND);
+    }
+
+    text(&mut canvas, "PoE Watcher", Point::new(TEXT_LEFT, 560), 1, MUTED);
+
+    encode(&canvas)
+}
+
+/// "Jan 15, 2024" from an RFC 3339 timestamp, or the timestamp as-is if it doesn't parse
+pub fn format_date(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|at| at.format("%b %-d, %Y").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(is_personal_best: bool) -> ShareCard {
+        ShareCard {
+            category: "Act 10 Any%".to_string(),
+            class: "Witch".to_string(),
+            ascendancy: Some("Necromancer".to_string()),
+            character_name: "TestNecro".to_string(),
+            total_time_ms: 9_876_540,
+            date: format_date("2024-01-15T12:00:00Z"),
+            is_personal_best,
+        }
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date("2024-01-05T23:59:00+00:00"), "Jan 5, 2024");
+        assert_eq!(format_date("yesterday"), "yesterday");
+    }
+
+    #[test]
+    fn test_render_png_social_size() {
+        let png = render_png(&card(false)).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert_eq!((width, height), (WIDTH, HEIGHT));
+
+        assert_ne!(render_png(&card(true)).unwrap(), png, "PB badge should be drawn");
+    }
+}
@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::api_client::{PassiveSkills, PoeItem};
+use crate::db::{Snapshot, Split};
+use crate::error::{AppError, AppResult};
+use crate::pob_builder::property_number;
+
+/// A gem socketed in an equipped item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Gem {
+    pub name: String,
+    pub level: u32,
+    pub quality: u32,
+    pub is_support: bool,
+    /// Socket group of the item the gem is in; gems sharing one are linked.
+    /// `None` for snapshots stored before sockets were recorded
+    #[serde(default)]
+    pub link_group: Option<u32>,
+}
+
+/// The gems socketed in one item, in socket order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillGroup {
+    pub inventory_id: String,
+    pub gems: Vec<Gem>,
+}
+
+/// Build numbers derived from a snapshot, stored as `stats_json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildStats {
+    /// Allocated passives, including ascendancy nodes
+    pub passive_points: usize,
+    /// Passives allocated in cluster jewels
+    pub cluster_passives: usize,
+    pub masteries: usize,
+    pub equipped_items: usize,
+    pub unique_items: usize,
+    pub socketed_gems: usize,
+    pub support_gems: usize,
+    /// Most linked sockets on one equipped item
+    pub max_links: usize,
+}
+
+/// Skills and stats derived from a snapshot's raw items and passives
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub skills: Vec<SkillGroup>,
+    pub stats: BuildStats,
+}
+
+impl Analysis {
+    pub fn skills_json(&self) -> String {
+        serde_json::to_string(&self.skills).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    pub fn stats_json(&self) -> String {
+        serde_json::to_string(&self.stats).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// Derive skills and stats from the character's items and passive tree as the
+/// API returned them
+pub fn analyze(items: &[PoeItem], passives: &PassiveSkills) -> Analysis {
+    let equipped: Vec<&PoeItem> = items
+        .iter()
+        .filter(|item| !item.inventory_id.is_empty() && !item.inventory_id.starts_with("Stash"))
+        .collect();
+
+    let skills: Vec<SkillGroup> = equipped
+        .iter()
+        .filter(|item| !item.socketed_items.is_empty())
+        .map(|item| SkillGroup { inventory_id: item.inventory_id.clone(), gems: socketed_gems(item) })
+        .filter(|group| !group.gems.is_empty())
+        .collect();
+
+    let max_links = equipped.iter().map(|item| max_links(item)).max().unwrap_or(0);
+    let gems = skills.iter().flat_map(|group| &group.gems);
+
+    let stats = BuildStats {
+        passive_points: passives.hashes.len(),
+        cluster_passives: passives.hashes_ex.len(),
+        masteries: passives.mastery_effects.len(),
+        equipped_items: equipped.len(),
+        unique_items: equipped.iter().filter(|item| item.frame_type == 3).count(),
+        socketed_gems: gems.clone().count(),
+        support_gems: gems.filter(|gem| gem.is_support).count(),
+        max_links,
+    };
+    Analysis { skills, stats }
+}
+
+/// Gems socketed in an item, in socket order
+pub(crate) fn socketed_gems(item: &PoeItem) -> Vec<Gem> {
+    item.socketed_items
+        .iter()
+        // Abyss jewels share the socket list with gems
+        .filter(|gem| !gem.type_line.is_empty() && !gem.type_line.ends_with("Jewel"))
+        .map(|gem| Gem {
+            name: gem.type_line.clone(),
+            level: property_number(gem, "Level").unwrap_or(1),
+            quality: property_number(gem, "Quality").unwrap_or(0),
+            is_support: gem.type_line.contains("Support"),
+            link_group: gem
+                .socket
+                .and_then(|socket| item.sockets.get(socket as usize))
+                .map(|socket| socket.group),
+        })
+        .collect()
+}
+
+/// Size of an item's largest linked socket group
+pub(crate) fn max_links(item: &PoeItem) -> usize {
+    let mut links: HashMap<u32, usize> = HashMap::new();
+    for socket in &item.sockets {
+        *links.entry(socket.group).or_default() += 1;
+    }
+    links.into_values().max().unwrap_or(0)
+}
+
+/// A stored snapshot's raw `items_json` and `passive_tree_json`
+pub(crate) fn parse_snapshot(snapshot: &Snapshot) -> AppResult<(Vec<PoeItem>, PassiveSkills)> {
+    let items: Vec<PoeItem> = serde_json::from_str(&snapshot.items_json)
+        .map_err(|e| AppError::InvalidInput(format!("Snapshot {} items: {}", snapshot.id, e)))?;
+    let passives: PassiveSkills = serde_json::from_str(&snapshot.passive_tree_json)
+        .map_err(|e| AppError::InvalidInput(format!("Snapshot {} passives: {}", snapshot.id, e)))?;
+    Ok((items, passives))
+}
+
+/// Analysis of a stored snapshot, from its raw `items_json` and `passive_tree_json`
+pub fn analyze_snapshot(snapshot: &Snapshot) -> AppResult<Analysis> {
+    let (items, passives) = parse_snapshot(snapshot)?;
+    Ok(analyze(&items, &passives))
+}
+
+/// A gem as one of a run's snapshots had it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GemAtSplit {
+    /// `None` for a level milestone snapshot
+    pub split_id: Option<i64>,
+    /// `None` when the snapshot's split was undone, or it had none
+    pub breakpoint_name: Option<String>,
+    pub elapsed_ms: i64,
+    pub level: u32,
+    pub quality: u32,
+    pub inventory_id: String,
+    pub link_group: Option<u32>,
+}
+
+/// When a gem was first equipped during a run, and its level at each split after
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GemHistory {
+    pub name: String,
+    pub is_support: bool,
+    /// Run clock of the first snapshot the gem was socketed in
+    pub first_equipped_ms: i64,
+    pub first_breakpoint: Option<String>,
+    /// Snapshots the gem was socketed in, in run-clock order
+    pub splits: Vec<GemAtSplit>,
+}
+
+/// Every gem a run's snapshots saw socketed, in the order they were first
+/// equipped, from their stored skills. A gem socketed twice counts at its
+/// highest level.
+pub fn gem_timeline(snapshots: &[Snapshot], splits: &[Split]) -> Vec<GemHistory> {
+    let mut snapshots: Vec<&Snapshot> = snapshots.iter().collect();
+    snapshots.sort_by_key(|snapshot| snapshot.elapsed_time_ms);
+
+    let mut timeline: Vec<GemHistory> = Vec::new();
+    for snapshot in snapshots {
+        let Ok(skills) = serde_json::from_str::<Vec<SkillGroup>>(&snapshot.skills_json) else {
+            continue;
+        };
+        let breakpoint_name = splits
+            .iter()
+            .find(|split| Some(split.id) == snapshot.split_id)
+            .map(|split| split.breakpoint_name.clone());
+
+        // Name, support, and the highest copy of each gem in this snapshot
+        let mut seen: Vec<(String, bool, GemAtSplit)> = Vec::new();
+        for group in &skills {
+            for gem in &group.gems {
+                let at = GemAtSplit {
+                    split_id: snapshot.split_id,
+                    breakpoint_name: breakpoint_name.clone(),
+                    elapsed_ms: snapshot.elapsed_time_ms,
+                    level: gem.level,
+                    quality: gem.quality,
+                    inventory_id: group.inventory_id.clone(),
+                    link_group: gem.link_group,
+                };
+                match seen.iter_mut().find(|(name, _, _)| name == &gem.name) {
+                    Some(copy) if copy.2.level < gem.level => copy.2 = at,
+                    Some(_) => {}
+                    None => seen.push((gem.name.clone(), gem.is_support, at)),
+                }
+            }
+        }
+
+        for (name, is_support, at) in seen {
+            match timeline.iter_mut().find(|history| history.name == name) {
+                Some(history) => history.splits.push(at),
+                None => timeline.push(GemHistory {
+                    is_support,
+                    first_equipped_ms: at.elapsed_ms,
+                    first_breakpoint: at.breakpoint_name.clone(),
+                    name,
+                    splits: vec![at],
+                }),
+            }
+        }
+    }
+    timeline
+}
+
+/// Result of `reprocess_snapshots`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprocessReport {
+    pub runs: usize,
+    pub snapshots: usize,
+    /// Snapshots whose skills or stats changed
+    pub updated: usize,
+    /// Snapshots whose stored JSON couldn't be parsed; left as they were
+    pub failed: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_gems_links_and_passives() {
+        let items: Vec<PoeItem> = serde_json::from_value(serde_json::json!([
+            {
+                "inventoryId": "BodyArmour",
+                "typeLine": "Simple Robe",
+                "frameType": 3,
+                "sockets": [{"group": 0, "attr": "I"}, {"group": 0, "attr": "I"}, {"group": 0, "attr": "D"}, {"group": 1, "attr": "S"}],
+                "socketedItems": [
+                    {"typeLine": "Fireball", "socket": 0, "properties": [{"name": "Level", "values": [["12", 0]]}]},
+                    {"typeLine": "Added Fire Damage Support", "socket": 2, "properties": [{"name": "Quality", "values": [["+7%", 1]]}]},
+                    {"typeLine": "Murderous Eye Jewel", "socket": 3}
+                ]
+            },
+            {"inventoryId": "Ring", "typeLine": "Iron Ring"},
+            {"inventoryId": "Stash1", "typeLine": "Chaos Orb"}
+        ]))
+        .unwrap();
+        let passives: PassiveSkills =
+            serde_json::from_value(serde_json::json!({"hashes": [1, 2, 3], "hashes_ex": [9], "mastery_effects": {}})).unwrap();
+
+        let analysis = analyze(&items, &passives);
+        assert_eq!(analysis.skills.len(), 1);
+        assert_eq!(analysis.skills[0].inventory_id, "BodyArmour");
+        assert_eq!(
+            analysis.skills[0].gems,
+            vec![
+                Gem { name: "Fireball".to_string(), level: 12, quality: 0, is_support: false, link_group: Some(0) },
+                Gem { name: "Added Fire Damage Support".to_string(), level: 1, quality: 7, is_support: true, link_group: Some(0) },
+            ]
+        );
+        assert_eq!(
+            analysis.stats,
+            BuildStats {
+                passive_points: 3,
+                cluster_passives: 1,
+                masteries: 0,
+                equipped_items: 2,
+                unique_items: 1,
+                socketed_gems: 2,
+                support_gems: 1,
+                max_links: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_gem_timeline() {
+        let gem = |name: &str, level: u32, link_group: Option<u32>| Gem {
+            name: name.to_string(),
+            level,
+            quality: 0,
+            is_support: name.ends_with("Support"),
+            link_group,
+        };
+        let snapshot = |split_id: i64, elapsed_time_ms: i64, skills: Vec<SkillGroup>| Snapshot {
+            id: split_id,
+            run_id: 1,
+            split_id: Some(split_id),
+            timestamp: String::new(),
+            elapsed_time_ms,
+            character_level: 1,
+            experience: None,
+            items_json: "[]".to_string(),
+            skills_json: serde_json::to_string(&skills).unwrap(),
+            passive_tree_json: "{}".to_string(),
+            stats_json: "{}".to_string(),
+            pob_code: None,
+            is_final: false,
+        };
+        let group = |inventory_id: &str, gems: Vec<Gem>| SkillGroup { inventory_id: inventory_id.to_string(), gems };
+        let split = |id: i64, name: &str| Split {
+            id,
+            run_id: 1,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: None,
+            split_time_ms: 0,
+            delta_ms: None,
+            segment_time_ms: 0,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+            segment_town_time_ms: 0,
+            segment_hideout_time_ms: 0,
+            layout_variant: None,
+            layout_inferred: false,
+            experience: None,
+            character_level: None,
+            load_time_ms: 0,
+        };
+
+        let snapshots = vec![
+            snapshot(2, 600_000, vec![
+                group("Weapon", vec![gem("Fireball", 8, Some(0)), gem("Onslaught Support", 1, Some(0))]),
+                group("Helm", vec![gem("Fireball", 3, None)]),
+            ]),
+            snapshot(1, 120_000, vec![group("Weapon", vec![gem("Fireball", 2, None)])]),
+        ];
+        let timeline = gem_timeline(&snapshots, &[split(1, "The Coast")]);
+
+        let gems: Vec<_> = timeline.iter().map(|g| (g.name.as_str(), g.is_support, g.first_equipped_ms)).collect();
+        assert_eq!(gems, vec![("Fireball", false, 120_000), ("Onslaught Support", true, 600_000)]);
+        assert_eq!(timeline[0].first_breakpoint.as_deref(), Some("The Coast"));
+        // The highest copy counts; the second snapshot's split was undone
+        let fireball: Vec<_> = timeline[0].splits.iter().map(|s| (s.level, s.inventory_id.as_str(), s.breakpoint_name.as_deref())).collect();
+        assert_eq!(fireball, vec![(2, "Weapon", Some("The Coast")), (8, "Weapon", None)]);
+    }
+}
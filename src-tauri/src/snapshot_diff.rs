@@ -0,0 +1,315 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use crate::api_client::{PassiveSkills, PoeItem};
+use crate::db::Snapshot;
+use crate::error::AppResult;
+use crate::pob_builder::{slot_name, SLOT_NAMES};
+use crate::snapshot_analysis::{max_links, parse_snapshot, socketed_gems};
+
+/// How an equipped item changed between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemChangeKind {
+    /// The slot was empty before
+    Added,
+    /// The slot is empty after
+    Removed,
+    /// A different item, or the same item with different mods or sockets
+    Changed,
+}
+
+/// How a socketed gem changed between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GemChangeKind {
+    Added,
+    Removed,
+    /// Same gem, different level
+    Leveled,
+}
+
+/// The parts of an item the progression view shows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemSummary {
+    pub name: String,
+    pub type_line: String,
+    /// 0 normal, 1 magic, 2 rare, 3 unique
+    pub frame_type: u32,
+    pub item_level: u32,
+    pub sockets: usize,
+    pub max_links: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemChange {
+    /// PoB slot name, e.g. "Body Armour" or "Flask 2"
+    pub slot: String,
+    pub change: ItemChangeKind,
+    pub before: Option<ItemSummary>,
+    pub after: Option<ItemSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GemChange {
+    pub name: String,
+    pub change: GemChangeKind,
+    /// Slot the gem is socketed in after, or was in before when removed
+    pub slot: String,
+    pub level_before: Option<u32>,
+    pub level_after: Option<u32>,
+}
+
+/// Result of `diff_snapshots`: what changed from snapshot A to snapshot B
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    pub snapshot_a: i64,
+    pub snapshot_b: i64,
+    pub level_before: i32,
+    pub level_after: i32,
+    pub levels_gained: i32,
+    /// Run-clock time between the two snapshots
+    pub elapsed_time_ms: i64,
+    /// In slot order
+    pub items: Vec<ItemChange>,
+    pub gems: Vec<GemChange>,
+    /// Node hashes, cluster jewel nodes included, sorted
+    pub passives_allocated: Vec<u32>,
+    pub passives_refunded: Vec<u32>,
+}
+
+/// A gem and the slot of the item it's socketed in
+struct SlottedGem {
+    slot: String,
+    name: String,
+    level: u32,
+}
+
+/// Diff two stored snapshots, usually of the same run with A the earlier one
+pub fn diff_snapshots(a: &Snapshot, b: &Snapshot) -> AppResult<SnapshotDiff> {
+    let (items_a, passives_a) = parse_snapshot(a)?;
+    let (items_b, passives_b) = parse_snapshot(b)?;
+    Ok(SnapshotDiff {
+        snapshot_a: a.id,
+        snapshot_b: b.id,
+        level_before: a.character_level,
+        level_after: b.character_level,
+        levels_gained: b.character_level - a.character_level,
+        elapsed_time_ms: b.elapsed_time_ms - a.elapsed_time_ms,
+        items: diff_items(&items_a, &items_b),
+        gems: diff_gems(&items_a, &items_b),
+        passives_allocated: allocated(&passives_b).difference(&allocated(&passives_a)).copied().collect(),
+        passives_refunded: allocated(&passives_a).difference(&allocated(&passives_b)).copied().collect(),
+    })
+}
+
+/// Equipped items by slot; inventory and stash items have none
+fn slotted(items: &[PoeItem]) -> Vec<(String, &PoeItem)> {
+    let mut slotted: Vec<(String, &PoeItem)> =
+        items.iter().filter_map(|item| slot_name(item).map(|slot| (slot, item))).collect();
+    slotted.sort_by_key(|(slot, _)| SLOT_NAMES.iter().position(|(_, name)| name == slot));
+    slotted
+}
+
+fn summary(item: &PoeItem) -> ItemSummary {
+    ItemSummary {
+        name: item.name.clone(),
+        type_line: item.type_line.clone(),
+        frame_type: item.frame_type,
+        item_level: item.item_level,
+        sockets: item.sockets.len(),
+        max_links: max_links(item),
+    }
+}
+
+/// Whether two items in a slot are the same item as it was. Gems are diffed
+/// separately, so swapping them doesn't change the item.
+fn same_item(a: &PoeItem, b: &PoeItem) -> bool {
+    (a.id.is_empty() || b.id.is_empty() || a.id == b.id)
+        && a.name == b.name
+        && a.type_line == b.type_line
+        && a.implicit_mods == b.implicit_mods
+        && a.explicit_mods == b.explicit_mods
+        && a.sockets.len() == b.sockets.len()
+        && max_links(a) == max_links(b)
+}
+
+fn find<'a>(slotted: &[(String, &'a PoeItem)], slot: &str) -> Option<&'a PoeItem> {
+    slotted.iter().find(|(name, _)| name == slot).map(|(_, item)| *item)
+}
+
+fn diff_items(items_a: &[PoeItem], items_b: &[PoeItem]) -> Vec<ItemChange> {
+    let before = slotted(items_a);
+    let after = slotted(items_b);
+    let mut changes = Vec::new();
+    for (_, slot) in SLOT_NAMES {
+        let slot = slot.to_string();
+        let change = match (find(&before, &slot), find(&after, &slot)) {
+            (None, Some(_)) => ItemChangeKind::Added,
+            (Some(_), None) => ItemChangeKind::Removed,
+            (Some(a), Some(b)) if !same_item(a, b) => ItemChangeKind::Changed,
+            _ => continue,
+        };
+        changes.push(ItemChange {
+            change,
+            before: find(&before, &slot).map(summary),
+            after: find(&after, &slot).map(summary),
+            slot,
+        });
+    }
+    changes
+}
+
+fn gems(items: &[PoeItem]) -> Vec<SlottedGem> {
+    slotted(items)
+        .into_iter()
+        .flat_map(|(slot, item)| {
+            socketed_gems(item)
+                .into_iter()
+                .map(move |gem| SlottedGem { slot: slot.clone(), name: gem.name, level: gem.level })
+        })
+        .collect()
+}
+
+/// Pair each gem after with one of the same name before, preferring the same
+/// slot so a gem moved to a new item reads as leveled rather than removed
+/// and added. Unpaired gems were added or removed.
+fn diff_gems(items_a: &[PoeItem], items_b: &[PoeItem]) -> Vec<GemChange> {
+    let mut before: Vec<Option<SlottedGem>> = gems(items_a).into_iter().map(Some).collect();
+    let after = gems(items_b);
+
+    let mut pairs: Vec<Option<SlottedGem>> = after.iter().map(|_| None).collect();
+    for same_slot in [true, false] {
+        for (gem, pair) in after.iter().zip(pairs.iter_mut()).filter(|(_, pair)| pair.is_none()) {
+            let found = before.iter().position(|candidate| {
+                candidate
+                    .as_ref()
+                    .is_some_and(|c| c.name == gem.name && (!same_slot || c.slot == gem.slot))
+            });
+            if let Some(index) = found {
+                *pair = before[index].take();
+            }
+        }
+    }
+
+    let mut changes: Vec<GemChange> = after
+        .into_iter()
+        .zip(pairs)
+        .filter_map(|(gem, pair)| {
+            let change = match &pair {
+                None => GemChangeKind::Added,
+                Some(old) if old.level != gem.level => GemChangeKind::Leveled,
+                Some(_) => return None,
+            };
+            Some(GemChange {
+                name: gem.name,
+                change,
+                slot: gem.slot,
+                level_before: pair.map(|old| old.level),
+                level_after: Some(gem.level),
+            })
+        })
+        .collect();
+    changes.extend(before.into_iter().flatten().map(|gem| GemChange {
+        name: gem.name,
+        change: GemChangeKind::Removed,
+        slot: gem.slot,
+        level_before: Some(gem.level),
+        level_after: None,
+    }));
+    changes
+}
+
+fn allocated(passives: &PassiveSkills) -> BTreeSet<u32> {
+    passives.hashes.iter().chain(&passives.hashes_ex).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gem(name: &str, level: u32) -> serde_json::Value {
+        serde_json::json!({"typeLine": name, "properties": [{"name": "Level", "values": [[level.to_string(), 0]]}]})
+    }
+
+    fn snapshot(id: i64, level: i32, items: serde_json::Value, hashes: &[u32]) -> Snapshot {
+        Snapshot {
+            id,
+            run_id: 1,
+            split_id: Some(id),
+            timestamp: String::new(),
+            elapsed_time_ms: id * 60_000,
+            character_level: level,
+            experience: None,
+            items_json: items.to_string(),
+            skills_json: "[]".to_string(),
+            passive_tree_json: serde_json::json!({"hashes": hashes}).to_string(),
+            stats_json: "{}".to_string(),
+            pob_code: None,
+            is_final: false,
+        }
+    }
+
+    #[test]
+    fn test_diff_items_gems_and_passives() {
+        let before = snapshot(
+            1,
+            12,
+            serde_json::json!([
+                {"id": "a", "inventoryId": "BodyArmour", "typeLine": "Simple Robe", "socketedItems": [gem("Fireball", 5), gem("Arcane Surge Support", 1)]},
+                {"id": "b", "inventoryId": "Ring", "typeLine": "Iron Ring"},
+                {"id": "c", "inventoryId": "Flask", "x": 0, "typeLine": "Small Life Flask"},
+                {"id": "d", "inventoryId": "MainInventory", "typeLine": "Scroll of Wisdom"}
+            ]),
+            &[1, 2, 3],
+        );
+        let after = snapshot(
+            2,
+            20,
+            serde_json::json!([
+                {"id": "e", "inventoryId": "BodyArmour", "name": "Doom Shroud", "typeLine": "Silken Vest", "socketedItems": [gem("Fireball", 9), gem("Added Fire Damage Support", 1)]},
+                {"id": "c", "inventoryId": "Flask", "x": 0, "typeLine": "Small Life Flask"},
+                {"id": "f", "inventoryId": "Flask", "x": 1, "typeLine": "Small Mana Flask"}
+            ]),
+            &[2, 3, 4, 5],
+        );
+
+        let diff = diff_snapshots(&before, &after).unwrap();
+        assert_eq!((diff.level_before, diff.level_after, diff.levels_gained), (12, 20, 8));
+        assert_eq!(diff.elapsed_time_ms, 60_000);
+
+        let items: Vec<(&str, ItemChangeKind)> = diff.items.iter().map(|c| (c.slot.as_str(), c.change)).collect();
+        assert_eq!(
+            items,
+            vec![
+                ("Body Armour", ItemChangeKind::Changed),
+                ("Ring 1", ItemChangeKind::Removed),
+                ("Flask 2", ItemChangeKind::Added),
+            ]
+        );
+        assert_eq!(diff.items[0].after.as_ref().map(|i| i.name.as_str()), Some("Doom Shroud"));
+        assert_eq!(diff.items[1].after, None);
+
+        // Fireball moved to the new armour and leveled; the supports were swapped
+        let gems: Vec<(&str, GemChangeKind, Option<u32>, Option<u32>)> =
+            diff.gems.iter().map(|g| (g.name.as_str(), g.change, g.level_before, g.level_after)).collect();
+        assert_eq!(
+            gems,
+            vec![
+                ("Fireball", GemChangeKind::Leveled, Some(5), Some(9)),
+                ("Added Fire Damage Support", GemChangeKind::Added, None, Some(1)),
+                ("Arcane Surge Support", GemChangeKind::Removed, Some(1), None),
+            ]
+        );
+
+        assert_eq!(diff.passives_allocated, vec![4, 5]);
+        assert_eq!(diff.passives_refunded, vec![1]);
+
+        let unchanged = diff_snapshots(&after, &after).unwrap();
+        assert!(unchanged.items.is_empty() && unchanged.gems.is_empty() && unchanged.passives_allocated.is_empty());
+    }
+}
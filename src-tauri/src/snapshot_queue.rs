@@ -0,0 +1,216 @@
+use serde::Serialize;
+
+/// A snapshot capture waiting for the API
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotRequest {
+    pub run_id: i64,
+    /// None for a capture at a level milestone
+    pub split_id: Option<i64>,
+    pub elapsed_time_ms: i64,
+    pub account_name: String,
+    pub character_name: String,
+    pub is_final: bool,
+}
+
+/// Payload of the `snapshot-queue` event
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QueueState {
+    /// Split whose snapshot is being fetched
+    pub capturing: Option<i64>,
+    /// Splits waiting, in the order they will be captured. Level milestone
+    /// captures have no split and aren't listed.
+    pub queued: Vec<i64>,
+}
+
+/// Whether a pending capture is being fetched or waiting its turn
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingState {
+    Capturing,
+    Queued,
+}
+
+/// A capture listed by `get_pending_snapshots`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingSnapshot {
+    pub run_id: i64,
+    pub split_id: Option<i64>,
+    pub character_name: String,
+    pub is_final: bool,
+    pub state: PendingState,
+    /// When the capture started, for the one in flight
+    pub started_at: Option<String>,
+}
+
+/// Snapshot captures run one at a time, newest split first, so the current
+/// build arrives first when quick splits back up behind the rate limiter.
+#[derive(Debug, Default)]
+pub struct SnapshotQueue {
+    pending: Vec<SnapshotRequest>,
+    /// The capture in flight and when it started
+    capturing: Option<(SnapshotRequest, String)>,
+}
+
+impl SnapshotQueue {
+    pub const fn new() -> Self {
+        SnapshotQueue {
+            pending: Vec::new(),
+            capturing: None,
+        }
+    }
+
+    /// Queue a capture and return the waiting captures it supersedes: earlier
+    /// non-final splits and level milestones of the same character, which would
+    /// only fetch the same (current) build once their turn came.
+    pub fn push(&mut self, request: SnapshotRequest) -> Vec<SnapshotRequest> {
+        let is_retry = |queued: &SnapshotRequest| queued.split_id.is_some() && queued.split_id == request.split_id;
+        let (superseded, kept) = std::mem::take(&mut self.pending).into_iter().partition(|queued| {
+            !queued.is_final
+                && !is_retry(queued)
+                && queued.run_id == request.run_id
+                && queued.account_name == request.account_name
+                && queued.character_name == request.character_name
+        });
+        self.pending = kept;
+        self.pending.retain(|queued| !is_retry(queued));
+        self.pending.push(request);
+        superseded
+    }
+
+    /// Start the newest waiting capture, unless one is already in flight
+    pub fn start_next(&mut self) -> Option<SnapshotRequest> {
+        if self.capturing.is_some() {
+            return None;
+        }
+        let request = self.pending.pop()?;
+        self.capturing = Some((request.clone(), chrono::Utc::now().to_rfc3339()));
+        Some(request)
+    }
+
+    /// Mark the in-flight capture as done
+    pub fn finish(&mut self) {
+        self.capturing = None;
+    }
+
+    /// Drop the waiting captures of a deleted run and return them
+    pub fn cancel_run(&mut self, run_id: i64) -> Vec<SnapshotRequest> {
+        let (cancelled, kept) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|queued| queued.run_id == run_id);
+        self.pending = kept;
+        cancelled
+    }
+
+    /// Run of the capture in flight
+    pub fn capturing_run(&self) -> Option<i64> {
+        self.capturing.as_ref().map(|(request, _)| request.run_id)
+    }
+
+    pub fn state(&self) -> QueueState {
+        QueueState {
+            capturing: self.capturing.as_ref().and_then(|(request, _)| request.split_id),
+            queued: self.pending.iter().rev().filter_map(|r| r.split_id).collect(),
+        }
+    }
+
+    /// The capture in flight, then the waiting ones in the order they will run
+    pub fn pending(&self) -> Vec<PendingSnapshot> {
+        let entry = |request: &SnapshotRequest, state, started_at: Option<&String>| PendingSnapshot {
+            run_id: request.run_id,
+            split_id: request.split_id,
+            character_name: request.character_name.clone(),
+            is_final: request.is_final,
+            state,
+            started_at: started_at.cloned(),
+        };
+        self.capturing
+            .iter()
+            .map(|(request, started_at)| entry(request, PendingState::Capturing, Some(started_at)))
+            .chain(self.pending.iter().rev().map(|request| entry(request, PendingState::Queued, None)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(split_id: i64, character_name: &str, is_final: bool) -> SnapshotRequest {
+        SnapshotRequest {
+            run_id: 1,
+            split_id: Some(split_id),
+            elapsed_time_ms: split_id * 1000,
+            account_name: "TestAccount".to_string(),
+            character_name: character_name.to_string(),
+            is_final,
+        }
+    }
+
+    #[test]
+    fn test_newest_split_captured_first() {
+        let mut queue = SnapshotQueue::new();
+        queue.push(request(1, "TestNecro", false));
+        assert_eq!(queue.start_next().and_then(|r| r.split_id), Some(1));
+
+        // Splits while the first capture is in flight wait behind it
+        assert!(queue.push(request(2, "Other", false)).is_empty());
+        assert!(queue.push(request(3, "TestNecro", false)).is_empty());
+        assert_eq!(queue.start_next(), None);
+        assert_eq!(queue.state(), QueueState { capturing: Some(1), queued: vec![3, 2] });
+
+        queue.finish();
+        assert_eq!(queue.start_next().and_then(|r| r.split_id), Some(3));
+        queue.finish();
+        assert_eq!(queue.start_next().and_then(|r| r.split_id), Some(2));
+    }
+
+    #[test]
+    fn test_older_waiting_captures_coalesced() {
+        let mut queue = SnapshotQueue::new();
+        queue.push(request(1, "TestNecro", false));
+        queue.start_next();
+
+        let split_ids = |requests: Vec<SnapshotRequest>| requests.iter().filter_map(|r| r.split_id).collect::<Vec<_>>();
+        assert!(queue.push(request(2, "TestNecro", false)).is_empty());
+        assert_eq!(split_ids(queue.push(request(3, "TestNecro", false))), vec![2]);
+        assert_eq!(split_ids(queue.push(request(4, "TestNecro", true))), vec![3]);
+        assert_eq!(queue.state(), QueueState { capturing: Some(1), queued: vec![4] });
+
+        // The final capture is never superseded, and a retry replaces its own waiting capture
+        assert!(queue.push(request(2, "TestNecro", false)).is_empty());
+        assert!(queue.push(request(2, "TestNecro", false)).is_empty());
+        assert_eq!(queue.state().queued, vec![2, 4]);
+
+        // Level milestone captures have no split to retry; a newer one supersedes them
+        let level = SnapshotRequest { split_id: None, ..request(5, "TestNecro", false) };
+        assert_eq!(split_ids(queue.push(level.clone())), vec![2]);
+        assert_eq!(queue.state().queued, vec![4]);
+        assert_eq!(queue.push(level.clone()), vec![level.clone()]);
+        assert_eq!(queue.push(request(6, "TestNecro", false)), vec![level]);
+        assert_eq!(queue.state().queued, vec![6, 4]);
+    }
+
+    #[test]
+    fn test_cancel_run_drops_its_waiting_captures() {
+        let mut queue = SnapshotQueue::new();
+        queue.push(request(1, "TestNecro", false));
+        queue.start_next();
+        queue.push(request(2, "TestNecro", true));
+        queue.push(SnapshotRequest { run_id: 2, ..request(3, "Other", false) });
+
+        let pending = queue.pending();
+        assert_eq!(pending.iter().map(|p| (p.split_id, p.state)).collect::<Vec<_>>(), vec![
+            (Some(1), PendingState::Capturing),
+            (Some(3), PendingState::Queued),
+            (Some(2), PendingState::Queued),
+        ]);
+        assert!(pending[0].started_at.is_some());
+        assert_eq!(pending[1].started_at, None);
+
+        let cancelled = queue.cancel_run(1);
+        assert_eq!(cancelled.iter().map(|r| r.split_id).collect::<Vec<_>>(), vec![Some(2)]);
+        assert_eq!(queue.capturing_run(), Some(1));
+        assert_eq!(queue.state(), QueueState { capturing: Some(1), queued: vec![3] });
+    }
+}
@@ -0,0 +1,256 @@
+use std::convert::Infallible;
+
+use embedded_graphics::mono_font::iso_8859_1::{FONT_10X20, FONT_9X15};
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::pixelcolor::Rgb888;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{PrimitiveStyle, Rectangle};
+use embedded_graphics::text::{Alignment, Baseline, Text, TextStyleBuilder};
+
+use crate::db::Split;
+
+const WIDTH: u32 = 560;
+const PADDING: i32 = 16;
+const HEADER_HEIGHT: i32 = 64;
+const ROW_HEIGHT: i32 = 24;
+/// Right edges of the delta and time columns
+const DELTA_RIGHT: i32 = WIDTH as i32 - PADDING - 110;
+const TIME_RIGHT: i32 = WIDTH as i32 - PADDING;
+/// Characters of the split name that fit left of the delta column
+const NAME_CHARS: usize = 30;
+
+// App theme colors
+pub(crate) const BACKGROUND: Rgb888 = Rgb888::new(0x12, 0x12, 0x14);
+const ROW_ALT: Rgb888 = Rgb888::new(0x1c, 0x1c, 0x20);
+pub(crate) const TEXT: Rgb888 = Rgb888::new(0xe8, 0xe6, 0xe3);
+pub(crate) const MUTED: Rgb888 = Rgb888::new(0x8a, 0x88, 0x85);
+pub(crate) const GOLD: Rgb888 = Rgb888::new(0xc8, 0xaa, 0x6e);
+const AHEAD: Rgb888 = Rgb888::new(0x4c, 0xaf, 0x50);
+const BEHIND: Rgb888 = Rgb888::new(0xe5, 0x39, 0x35);
+
+/// One line of the rendered splits table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageRow {
+    pub name: String,
+    pub split_time_ms: i64,
+    /// Difference from the comparison run at the same split
+    pub delta_ms: Option<i64>,
+    /// Segment matched or beat the best segment
+    pub is_gold: bool,
+}
+
+/// Pair a run's splits with the comparison run's splits (matched by canonical key,
+/// falling back to the name) and the best segment at each location
+pub fn build_rows(
+    splits: &[Split],
+    comparison: &[Split],
+    best_segment: impl Fn(&Split) -> Option<i64>,
+) -> Vec<ImageRow> {
+    splits
+        .iter()
+        .map(|split| {
+            let compared = comparison.iter().find(|c| match (&c.breakpoint_key, &split.breakpoint_key) {
+                (Some(a), Some(b)) => a == b,
+                _ => c.breakpoint_name == split.breakpoint_name,
+            });
+            ImageRow {
+                name: split.breakpoint_name.clone(),
+                split_time_ms: split.split_time_ms,
+                delta_ms: split
+                    .delta_ms
+                    .or_else(|| compared.map(|c| split.split_time_ms - c.split_time_ms)),
+                is_gold: best_segment(split).is_some_and(|best| split.segment_time_ms <= best),
+            }
+        })
+        .collect()
+}
+
+/// RGB pixel buffer that embedded-graphics draws into
+pub(crate) struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    pub(crate) fn new(width: u32, height: u32, background: Rgb888) -> Self {
+        let pixels = [background.r(), background.g(), background.b()]
+            .repeat((width * height) as usize);
+        Canvas { width, height, pixels }
+    }
+}
+
+impl OriginDimensions for Canvas {
+    fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+impl DrawTarget for Canvas {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Infallible>
+    where
+        I: IntoIterator<Item = Pixel<Rgb888>>,
+    {
+        for Pixel(point, color) in pixels {
+            if point.x < 0 || point.y < 0 || point.x as u32 >= self.width || point.y as u32 >= self.height {
+                continue;
+            }
+            let i = (point.y as usize * self.width as usize + point.x as usize) * 3;
+            self.pixels[i..i + 3].copy_from_slice(&[color.r(), color.g(), color.b()]);
+        }
+        Ok(())
+    }
+}
+
+/// Render the splits table to PNG bytes: a title and subtitle, then one row per
+/// split with its delta (colored ahead/behind, gold for best segments) and time
+pub fn render_png(title: &str, subtitle: &str, rows: &[ImageRow]) -> Result<Vec<u8>, png::EncodingError> {
+    let height = (HEADER_HEIGHT + ROW_HEIGHT * (rows.len() as i32 + 1) + PADDING) as u32;
+    let mut canvas = Canvas::new(WIDTH, height, BACKGROUND);
+
+    text(&mut canvas, title, Point::new(PADDING, PADDING), &FONT_10X20, GOLD, Alignment::Left);
+    text(&mut canvas, subtitle, Point::new(PADDING, PADDING + 24), &FONT_9X15, MUTED, Alignment::Left);
+
+    let header_y = HEADER_HEIGHT;
+    text(&mut canvas, "Split", Point::new(PADDING, header_y), &FONT_9X15, MUTED, Alignment::Left);
+    text(&mut canvas, "Delta", Point::new(DELTA_RIGHT, header_y), &FONT_9X15, MUTED, Alignment::Right);
+    text(&mut canvas, "Time", Point::new(TIME_RIGHT, header_y), &FONT_9X15, MUTED, Alignment::Right);
+
+    for (i, row) in rows.iter().enumerate() {
+        let y = header_y + ROW_HEIGHT * (i as i32 + 1);
+        if i % 2 == 0 {
+            let _ = Rectangle::new(Point::new(0, y - 4), Size::new(WIDTH, ROW_HEIGHT as u32))
+                .into_styled(PrimitiveStyle::with_fill(ROW_ALT))
+                .draw(&mut canvas);
+        }
+
+        let name: String = if row.name.chars().count() > NAME_CHARS {
+            row.name.chars().take(NAME_CHARS - 1).chain(['.']).collect()
+        } else {
+            row.name.clone()
+        };
+        text(&mut canvas, &name, Point::new(PADDING, y), &FONT_9X15, TEXT, Alignment::Left);
+
+        if let Some(delta) = row.delta_ms {
+            let color = match delta {
+                _ if row.is_gold => GOLD,
+                d if d < 0 => AHEAD,
+                d if d > 0 => BEHIND,
+                _ => MUTED,
+            };
+            text(&mut canvas, &format_delta(delta), Point::new(DELTA_RIGHT, y), &FONT_9X15, color, Alignment::Right);
+        } else if row.is_gold {
+            text(&mut canvas, "gold", Point::new(DELTA_RIGHT, y), &FONT_9X15, GOLD, Alignment::Right);
+        }
+        text(&mut canvas, &format_time(row.split_time_ms), Point::new(TIME_RIGHT, y), &FONT_9X15, TEXT, Alignment::Right);
+    }
+
+    encode(&canvas)
+}
+
+fn text(canvas: &mut Canvas, s: &str, at: Point, font: &MonoFont, color: Rgb888, alignment: Alignment) {
+    let style = TextStyleBuilder::new().alignment(alignment).baseline(Baseline::Top).build();
+    let _ = Text::with_text_style(s, at, MonoTextStyle::new(font, color), style).draw(canvas);
+}
+
+pub(crate) fn encode(canvas: &Canvas) -> Result<Vec<u8>, png::EncodingError> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, canvas.width, canvas.height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&canvas.pixels)?;
+    writer.finish()?;
+    Ok(bytes)
+}
+
+/// `1:02:03.45` / `2:03.45`
+pub fn format_time(ms: i64) -> String {
+    let ms = ms.max(0);
+    let (hours, minutes, seconds, centis) = (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000 / 10);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}.{:02}", hours, minutes, seconds, centis)
+    } else {
+        format!("{}:{:02}.{:02}", minutes, seconds, centis)
+    }
+}
+
+/// `+1:02.3` / `-4.5`
+pub fn format_delta(ms: i64) -> String {
+    let sign = if ms < 0 { '-' } else { '+' };
+    let abs = ms.abs();
+    let (minutes, seconds, tenths) = (abs / 60_000, abs / 1000 % 60, abs % 1000 / 100);
+    if minutes > 0 {
+        format!("{}{}:{:02}.{}", sign, minutes, seconds, tenths)
+    } else {
+        format!("{}{}.{}", sign, seconds, tenths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(name: &str, key: Option<&str>, split_time_ms: i64, segment_time_ms: i64) -> Split {
+        Split {
+            id: 0,
+            run_id: 0,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: key.map(str::to_string),
+            split_time_ms,
+            delta_ms: None,
+            segment_time_ms,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+            segment_town_time_ms: 0,
+            segment_hideout_time_ms: 0,
+            layout_variant: None,
+            layout_inferred: false,
+            experience: None,
+            character_level: None,
+            load_time_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_format_times() {
+        assert_eq!(format_time(62_345), "1:02.34");
+        assert_eq!(format_time(3_723_450), "1:02:03.45");
+        assert_eq!(format_delta(-4_560), "-4.5");
+        assert_eq!(format_delta(61_200), "+1:01.2");
+        assert_eq!(format_delta(0), "+0.0");
+    }
+
+    #[test]
+    fn test_build_rows_matches_comparison() {
+        let splits = [
+            split("The Coast", Some("zone:a1:the_coast"), 60_000, 60_000),
+            split("Mud Flats", None, 150_000, 90_000),
+            split("The Ledge", None, 200_000, 50_000),
+        ];
+        let pb = [
+            split("Coast (renamed)", Some("zone:a1:the_coast"), 65_000, 65_000),
+            split("Mud Flats", None, 140_000, 75_000),
+        ];
+        let rows = build_rows(&splits, &pb, |s| (s.breakpoint_name == "The Coast").then_some(60_000));
+        let deltas: Vec<Option<i64>> = rows.iter().map(|r| r.delta_ms).collect();
+        assert_eq!(deltas, vec![Some(-5_000), Some(10_000), None]);
+        assert!(rows[0].is_gold && !rows[1].is_gold);
+    }
+
+    #[test]
+    fn test_render_png_size_grows_with_rows() {
+        let row = ImageRow { name: "The Coast".to_string(), split_time_ms: 60_000, delta_ms: Some(-1_000), is_gold: true };
+        let png = render_png("Witch - Act 1", "TestChar", std::slice::from_ref(&row)).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        // IHDR width/height follow the signature and chunk header
+        let dims = |png: &[u8]| (u32::from_be_bytes(png[16..20].try_into().unwrap()), u32::from_be_bytes(png[20..24].try_into().unwrap()));
+        let bigger = render_png("Witch - Act 1", "TestChar", &[row.clone(), row]).unwrap();
+        assert_eq!(dims(&png).0, WIDTH);
+        assert_eq!(dims(&bigger).1 - dims(&png).1, ROW_HEIGHT as u32);
+    }
+}
@@ -0,0 +1,179 @@
+use serde::Serialize;
+
+use crate::breakpoint_engine;
+use crate::db::{GoldSplit, Run, SkippedSplit, Split};
+use crate::error::{AppError, AppResult};
+
+/// The golds of a set of breakpoints added up
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SumOfBest {
+    /// `None` until every breakpoint has a gold
+    pub sum_of_best_ms: Option<i64>,
+    pub breakpoints: usize,
+    /// Breakpoints without a gold yet, in split order
+    pub missing: Vec<String>,
+}
+
+/// The fastest a run can still finish: its time so far plus the golds of
+/// the breakpoints it has left
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BestPossibleTime {
+    pub run_id: i64,
+    /// Run clock now while the run is live, else where it ended
+    pub elapsed_ms: i64,
+    /// Split time of the run's last split
+    pub completed_ms: i64,
+    /// Enabled breakpoints not yet split or skipped
+    pub remaining: usize,
+    /// `None` when a remaining breakpoint has no gold, or the run didn't
+    /// record which breakpoints were enabled
+    pub best_possible_ms: Option<i64>,
+}
+
+/// Names in a run's `enabled_breakpoints` (a JSON array)
+fn breakpoint_names(enabled_breakpoints: Option<&str>) -> Option<Vec<String>> {
+    enabled_breakpoints.and_then(|json| serde_json::from_str(json).ok())
+}
+
+/// Best segment for a breakpoint among `golds`, by name: enabled breakpoints
+/// are stored without their keys
+fn gold_for(name: &str, golds: &[GoldSplit]) -> Option<i64> {
+    golds
+        .iter()
+        .filter(|gold| gold.breakpoint_name == name)
+        .map(|gold| gold.best_segment_ms)
+        .min()
+}
+
+/// Add up the golds of `breakpoints`
+pub fn sum_of_best(breakpoints: &[String], golds: &[GoldSplit]) -> SumOfBest {
+    let mut sum_of_best_ms = Some(0);
+    let mut missing = Vec::new();
+    for name in breakpoints {
+        match gold_for(name, golds) {
+            Some(gold) => sum_of_best_ms = sum_of_best_ms.map(|sum| sum + gold),
+            None => {
+                sum_of_best_ms = None;
+                missing.push(name.clone());
+            }
+        }
+    }
+    SumOfBest { sum_of_best_ms, breakpoints: breakpoints.len(), missing }
+}
+
+/// LiveSplit's best possible time: the last split's time, then the golds of
+/// the remaining breakpoints. A segment already running longer than its
+/// gold counts at its current length.
+pub fn best_possible(completed_ms: i64, elapsed_ms: i64, remaining: &[String], golds: &[GoldSplit]) -> Option<i64> {
+    let Some((next, rest)) = remaining.split_first() else {
+        return Some(completed_ms);
+    };
+    let next_gold = gold_for(next, golds)?;
+    let rest = sum_of_best(rest, golds).sum_of_best_ms?;
+    Some((completed_ms + next_gold).max(elapsed_ms) + rest)
+}
+
+/// Golds of a category, of one class or (without) the best of any class
+fn category_golds(category: &str, class: Option<&str>) -> AppResult<Vec<GoldSplit>> {
+    Ok(GoldSplit::get_all()?
+        .into_iter()
+        .filter(|gold| gold.category == category && class.is_none_or(|class| gold.class == class))
+        .collect())
+}
+
+/// Sum of best for the breakpoints a preset enables, as the latest run of
+/// the category on that preset had them
+pub fn get_sum_of_best(category: &str, preset: &str, class: Option<&str>) -> AppResult<SumOfBest> {
+    let breakpoints = Run::latest_with_preset(category, preset)?
+        .and_then(|run| breakpoint_names(run.enabled_breakpoints.as_deref()))
+        .ok_or_else(|| AppError::NotFound(format!("A {} run on the {} preset", category, preset)))?;
+    Ok(sum_of_best(&breakpoints, &category_golds(category, class)?))
+}
+
+/// Best possible time of a run against its category and class golds
+pub fn best_possible_time(run_id: i64) -> AppResult<BestPossibleTime> {
+    let Some(run) = Run::get_by_id(run_id)? else {
+        return Err(AppError::NotFound(format!("Run {}", run_id)));
+    };
+    let splits = Split::get_by_run(run_id)?;
+    let skipped = SkippedSplit::get_by_run(run_id)?;
+    let completed_ms = splits.last().map_or(0, |split| split.split_time_ms);
+    let elapsed_ms = breakpoint_engine::elapsed_for(run_id)
+        .or(run.total_time_ms)
+        .unwrap_or(completed_ms);
+
+    let remaining = breakpoint_names(run.enabled_breakpoints.as_deref()).map(|names| {
+        names
+            .into_iter()
+            .filter(|name| {
+                !splits.iter().any(|split| &split.breakpoint_name == name)
+                    && !skipped.iter().any(|skip| &skip.breakpoint_name == name)
+            })
+            .collect::<Vec<_>>()
+    });
+    let best_possible_ms = match (&remaining, run.is_completed) {
+        (_, true) => Some(run.total_time_ms.unwrap_or(completed_ms)),
+        (Some(remaining), false) => {
+            best_possible(completed_ms, elapsed_ms, remaining, &category_golds(&run.category, Some(&run.class))?)
+        }
+        (None, false) => None,
+    };
+
+    Ok(BestPossibleTime {
+        run_id,
+        elapsed_ms,
+        completed_ms,
+        remaining: remaining.map_or(0, |remaining| remaining.len()),
+        best_possible_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gold(class: &str, name: &str, best_segment_ms: i64) -> GoldSplit {
+        GoldSplit {
+            id: 0,
+            category: "Any%".to_string(),
+            class: class.to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: None,
+            best_segment_ms,
+        }
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_sum_of_best() {
+        let golds = vec![gold("Witch", "Hillock", 60_000), gold("Ranger", "Hillock", 50_000), gold("Witch", "Brutus", 300_000)];
+
+        let sum = sum_of_best(&names(&["Hillock", "Brutus"]), &golds);
+        assert_eq!((sum.sum_of_best_ms, sum.breakpoints), (Some(350_000), 2));
+        assert!(sum.missing.is_empty());
+
+        let partial = sum_of_best(&names(&["Hillock", "Merveil"]), &golds);
+        assert_eq!(partial.sum_of_best_ms, None);
+        assert_eq!(partial.missing, names(&["Merveil"]));
+    }
+
+    #[test]
+    fn test_best_possible_time() {
+        let golds = vec![gold("Witch", "Brutus", 300_000), gold("Witch", "Merveil", 200_000)];
+        let remaining = names(&["Brutus", "Merveil"]);
+
+        // Still inside the next segment's gold
+        assert_eq!(best_possible(100_000, 150_000, &remaining, &golds), Some(600_000));
+        // Already slower than the gold: the segment counts at its length so far
+        assert_eq!(best_possible(100_000, 500_000, &remaining, &golds), Some(700_000));
+        // Finished: the last split's time
+        assert_eq!(best_possible(900_000, 900_000, &[], &golds), Some(900_000));
+        // A breakpoint without a gold
+        assert_eq!(best_possible(0, 0, &names(&["Brutus", "Dominus"]), &golds), None);
+    }
+}
@@ -0,0 +1,427 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::db::Run;
+
+/// Number of recent runs listed on the status page
+const RECENT_RUN_COUNT: usize = 5;
+
+/// Most bytes read for a request or response line and its headers
+const MAX_HEAD_LEN: u64 = 8 * 1024;
+
+/// Latest timer state pushed by the frontend (via `sync_overlay_state`) and when it arrived
+static LIVE_STATE: Mutex<Option<(Value, Instant)>> = Mutex::new(None);
+
+/// Stop flag and accept thread of the running server, if any
+static SERVER: Mutex<Option<(Arc<AtomicBool>, JoinHandle<()>)>> = Mutex::new(None);
+
+/// Read-only view of the current run for the phone status page
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusSnapshot {
+    pub is_running: bool,
+    pub elapsed_ms: i64,
+    pub current_zone: Option<String>,
+    pub last_split: Option<SplitStatus>,
+    pub next_split: Option<String>,
+    pub recent_runs: Vec<RecentRun>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitStatus {
+    pub name: String,
+    pub delta_ms: Option<i64>,
+    pub is_best_segment: bool,
+    /// `SplitColor` the backend gave the split, e.g. `ahead_losing`
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentRun {
+    pub category: String,
+    pub class: String,
+    pub started_at: String,
+    pub total_time_ms: Option<i64>,
+    pub is_completed: bool,
+}
+
+/// Remember the latest timer state so the status page can serve it
+pub fn update_live_state(state: Value) {
+    if let Ok(mut live) = LIVE_STATE.lock() {
+        *live = Some((state, Instant::now()));
+    }
+}
+
+/// Build the status page snapshot from the cached overlay state. A running
+/// timer is advanced by how long ago the state was pushed.
+pub fn build_status(state: Option<&Value>, age: Duration, runs: &[Run]) -> StatusSnapshot {
+    let recent_runs = runs
+        .iter()
+        .filter(|r| !r.is_reference)
+        .take(RECENT_RUN_COUNT)
+        .map(|r| RecentRun {
+            category: r.category.clone(),
+            class: r.class.clone(),
+            started_at: r.started_at.clone(),
+            total_time_ms: r.total_time_ms,
+            is_completed: r.is_completed,
+        })
+        .collect();
+
+    let Some(state) = state else {
+        return StatusSnapshot {
+            recent_runs,
+            ..Default::default()
+        };
+    };
+
+    let is_running = state["isRunning"].as_bool().unwrap_or(false);
+    let mut elapsed_ms = state["elapsedMs"].as_i64().unwrap_or(0);
+    if is_running {
+        elapsed_ms += age.as_millis() as i64;
+    }
+
+    let last_split = state["lastSplit"]["name"].as_str().map(|name| SplitStatus {
+        name: name.to_string(),
+        delta_ms: state["lastSplit"]["deltaMs"].as_i64(),
+        is_best_segment: state["lastSplit"]["isBestSegment"].as_bool().unwrap_or(false),
+        color: state["lastSplit"]["color"].as_str().map(str::to_string),
+    });
+
+    StatusSnapshot {
+        is_running,
+        elapsed_ms,
+        current_zone: state["currentZone"].as_str().map(str::to_string),
+        last_split,
+        next_split: state["upcomingBreakpoints"][0]["name"].as_str().map(str::to_string),
+        recent_runs,
+    }
+}
+
+/// A response to write back over the socket
+#[derive(Debug)]
+pub struct Response {
+    pub status: &'static str,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    pub(crate) fn text(status: &'static str, body: &str) -> Self {
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.to_string(),
+        }
+    }
+}
+
+/// Compare tokens in constant time, so response timing doesn't leak how much
+/// of a guess was right
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Route a request line (`GET /status.json?token=... HTTP/1.1`). Every route
+/// requires the pairing token.
+pub fn respond(request_line: &str, token: &str) -> Response {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Response::text("400 Bad Request", "Bad request");
+    };
+    if method != "GET" {
+        return Response::text("405 Method Not Allowed", "Read-only");
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let given = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .and_then(|t| urlencoding::decode(t).ok());
+    if token.is_empty() || !given.is_some_and(|given| tokens_match(&given, token)) {
+        return Response::text("401 Unauthorized", "Invalid or missing pairing token");
+    }
+
+    match path {
+        "/" => Response {
+            status: "200 OK",
+            content_type: "text/html; charset=utf-8",
+            body: STATUS_PAGE.to_string(),
+        },
+        "/status.json" => {
+            let runs = Run::get_recent(RECENT_RUN_COUNT).unwrap_or_default();
+            let live = LIVE_STATE.lock().ok().and_then(|s| s.clone());
+            let status = match &live {
+                Some((state, at)) => build_status(Some(state), at.elapsed(), &runs),
+                None => build_status(None, Duration::ZERO, &runs),
+            };
+            Response {
+                status: "200 OK",
+                content_type: "application/json",
+                body: serde_json::to_string(&status).unwrap_or_default(),
+            }
+        }
+        _ => Response::text("404 Not Found", "Not found"),
+    }
+}
+
+/// Start serving on all interfaces (so a phone on the LAN can connect),
+/// replacing any running server. Returns the bound port.
+pub fn start(port: u16, token: String) -> std::io::Result<u16> {
+    stop();
+
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    listener.set_nonblocking(true)?;
+    let bound_port = listener.local_addr()?.port();
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let running = stop_flag.clone();
+    let handle = thread::spawn(move || {
+        while !running.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    // Each request gets its own thread, so a slow client
+                    // doesn't hold up the others
+                    let token = token.clone();
+                    thread::spawn(move || {
+                        if let Err(e) = handle_connection(stream, &token) {
+                            eprintln!("[status] Request failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => {
+                    eprintln!("[status] Accept failed: {}", e);
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+    });
+
+    if let Ok(mut server) = SERVER.lock() {
+        *server = Some((stop_flag, handle));
+    }
+    Ok(bound_port)
+}
+
+/// Stop the running server, if any, waiting for its accept thread to exit so
+/// the port is free again once this returns
+pub fn stop() {
+    if let Some((stop_flag, handle)) = SERVER.lock().ok().and_then(|mut s| s.take()) {
+        stop_flag.store(true, Ordering::Relaxed);
+        let _ = handle.join();
+    }
+}
+
+/// Read a request or status line and the headers after it, by lowercased
+/// name, failing past `MAX_HEAD_LEN` bytes so a peer can't grow them forever
+pub(crate) fn read_head(reader: &mut impl BufRead) -> io::Result<(String, HashMap<String, String>)> {
+    let mut limited = reader.take(MAX_HEAD_LEN);
+    let mut first_line = String::new();
+    limited.read_line(&mut first_line)?;
+    let mut headers = HashMap::new();
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if limited.read_line(&mut header)? == 0 {
+            if limited.limit() == 0 {
+                return Err(io::Error::new(ErrorKind::InvalidData, "Headers too large"));
+            }
+            break;
+        }
+        if header.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    Ok((first_line.trim_end().to_string(), headers))
+}
+
+fn handle_connection(mut stream: TcpStream, token: &str) -> std::io::Result<()> {
+    // Accepted sockets can inherit non-blocking mode from the listener
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+    // Nothing in the headers is needed, but they're read to the end
+    let (request_line, _) = read_head(&mut BufReader::new(&stream))?;
+
+    let response = respond(&request_line, token);
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.content_type,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+/// Random 128-bit hex token from the OS RNG
+pub fn generate_token() -> io::Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// LAN address of this machine (no packets are sent; connecting a UDP socket
+/// just selects the outbound interface)
+pub fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// Phone-friendly page that polls `status.json` with the token from its own URL
+const STATUS_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>POE Watcher</title>
+<style>
+  body { margin: 0; padding: 16px; background: #0c0a08; color: #e8e0d0; font-family: system-ui, sans-serif; }
+  .timer { font-size: 56px; font-variant-numeric: tabular-nums; text-align: center; margin: 8px 0; }
+  .zone { text-align: center; color: #a89f8f; }
+  .split { text-align: center; font-size: 22px; margin: 16px 0; }
+  .ahead_gaining { color: #22c55e; } .ahead_losing { color: #86efac; }
+  .behind_gaining { color: #fca5a5; } .behind_losing { color: #ef4444; } .gold { color: #fbbf24; }
+  .muted { color: #a89f8f; font-size: 14px; }
+  table { width: 100%; border-collapse: collapse; margin-top: 16px; font-size: 14px; }
+  td { padding: 6px 0; border-top: 1px solid #2a241c; }
+  td:last-child { text-align: right; font-variant-numeric: tabular-nums; }
+</style>
+</head>
+<body>
+<div class="timer" id="timer">0:00:00</div>
+<div class="zone" id="zone"></div>
+<div class="split" id="split"></div>
+<div class="muted" id="next"></div>
+<table id="runs"></table>
+<script>
+  const token = new URLSearchParams(location.search).get('token') || '';
+  const fmt = (ms, signed) => {
+    const sign = signed ? (ms < 0 ? '-' : '+') : '';
+    ms = Math.abs(ms);
+    const s = Math.floor(ms / 1000), h = Math.floor(s / 3600), m = Math.floor(s / 60) % 60;
+    const pad = (n) => String(n).padStart(2, '0');
+    return sign + (h ? h + ':' + pad(m) : m) + ':' + pad(s % 60);
+  };
+  const text = (id, value) => { document.getElementById(id).textContent = value; };
+  let status = null, fetchedAt = 0;
+  async function poll() {
+    try {
+      const res = await fetch('status.json?token=' + encodeURIComponent(token), { cache: 'no-store' });
+      if (res.ok) { status = await res.json(); fetchedAt = Date.now(); render(); }
+      else text('zone', res.status === 401 ? 'Invalid pairing token' : 'Error ' + res.status);
+    } catch { text('zone', 'Disconnected'); }
+  }
+  function render() {
+    if (!status) return;
+    text('zone', status.currentZone || '');
+    const split = document.getElementById('split');
+    const last = status.lastSplit;
+    split.className = 'split' + (last && last.color ? ' ' + last.color : '');
+    split.textContent = last ? last.name + (last.deltaMs != null ? '  ' + fmt(last.deltaMs, true) : '') : '';
+    text('next', status.nextSplit ? 'Next: ' + status.nextSplit : '');
+    const runs = document.getElementById('runs');
+    runs.replaceChildren(...status.recentRuns.map((r) => {
+      const row = document.createElement('tr');
+      const name = document.createElement('td'), time = document.createElement('td');
+      name.textContent = r.category + ' - ' + r.class;
+      time.textContent = r.isCompleted && r.totalTimeMs != null ? fmt(r.totalTimeMs) : 'DNF';
+      row.append(name, time);
+      return row;
+    }));
+  }
+  function tick() {
+    if (!status) return;
+    const elapsed = status.elapsedMs + (status.isRunning ? Date.now() - fetchedAt : 0);
+    text('timer', (elapsed < 0 ? '-' : '') + fmt(elapsed));
+  }
+  poll();
+  setInterval(poll, 2000);
+  setInterval(tick, 100);
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_requires_pairing_token() {
+        assert_eq!(respond("GET /?token=secret HTTP/1.1", "secret").status, "200 OK");
+        assert_eq!(respond("GET /?token=wrong HTTP/1.1", "secret").status, "401 Unauthorized");
+        assert_eq!(respond("GET / HTTP/1.1", "secret").status, "401 Unauthorized");
+        // An unset token never matches, even an empty one
+        assert_eq!(respond("GET /?token= HTTP/1.1", "").status, "401 Unauthorized");
+        assert_eq!(respond("POST /?token=secret HTTP/1.1", "secret").status, "405 Method Not Allowed");
+        assert_eq!(respond("GET /nope?token=secret HTTP/1.1", "secret").status, "404 Not Found");
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret") && !tokens_match("secret1", "secret"));
+    }
+
+    #[test]
+    fn test_read_head_caps_headers() {
+        let request = "GET /?token=x HTTP/1.1\r\nHost: phone\r\nX-Thing:  a:b \r\n\r\nbody";
+        let (line, headers) = read_head(&mut request.as_bytes()).unwrap();
+        assert_eq!(line, "GET /?token=x HTTP/1.1");
+        assert_eq!((headers["host"].as_str(), headers["x-thing"].as_str()), ("phone", "a:b"));
+
+        // A peer trickling headers forever is cut off
+        let endless = format!("GET / HTTP/1.1\r\n{}", "X-Pad: 0123456789\r\n".repeat(1000));
+        assert_eq!(read_head(&mut endless.as_bytes()).unwrap_err().kind(), ErrorKind::InvalidData);
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(10_000));
+        assert!(read_head(&mut long_line.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_build_status_advances_running_timer() {
+        let state = json!({
+            "isRunning": true,
+            "elapsedMs": 60_000,
+            "currentZone": "The Coast",
+            "lastSplit": { "name": "The Twilight Strand", "deltaMs": -1500, "isBestSegment": false, "color": "ahead_gaining" },
+            "upcomingBreakpoints": [{ "name": "The Mud Flats" }],
+        });
+        let status = build_status(Some(&state), Duration::from_millis(2_500), &[]);
+
+        assert!(status.is_running);
+        assert_eq!(status.elapsed_ms, 62_500);
+        assert_eq!(status.current_zone.as_deref(), Some("The Coast"));
+        let last_split = status.last_split.unwrap();
+        assert_eq!(last_split.delta_ms, Some(-1500));
+        assert_eq!(last_split.color.as_deref(), Some("ahead_gaining"));
+        assert_eq!(status.next_split.as_deref(), Some("The Mud Flats"));
+    }
+
+    #[test]
+    fn test_build_status_without_state() {
+        let status = build_status(None, Duration::from_secs(10), &[]);
+        assert_eq!(status, StatusSnapshot::default());
+    }
+
+    #[test]
+    fn test_generated_tokens_differ() {
+        let token = generate_token().unwrap();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, generate_token().unwrap());
+    }
+}
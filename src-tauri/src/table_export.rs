@@ -0,0 +1,398 @@
+use crate::db::{Run, Split};
+use crate::splits_image::{format_delta, format_time};
+
+/// Number, time and date conventions for a `locale` setting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportLocale {
+    pub decimal: char,
+    pub hour12: bool,
+    /// chrono format for dates
+    pub date_format: &'static str,
+}
+
+const fn locale(decimal: char, hour12: bool, date_format: &'static str) -> ExportLocale {
+    ExportLocale { decimal, hour12, date_format }
+}
+
+/// Locales offered in settings; anything else falls back to `en-US`
+pub const LOCALES: &[(&str, ExportLocale)] = &[
+    ("en-US", locale('.', true, "%m/%d/%Y")),
+    ("en-GB", locale('.', false, "%d/%m/%Y")),
+    ("de-DE", locale(',', false, "%d.%m.%Y")),
+    ("fr-FR", locale(',', false, "%d/%m/%Y")),
+    ("es-ES", locale(',', false, "%d/%m/%Y")),
+    ("pt-BR", locale(',', false, "%d/%m/%Y")),
+    ("ru-RU", locale(',', false, "%d.%m.%Y")),
+    ("ISO", locale('.', false, "%Y-%m-%d")),
+];
+
+impl ExportLocale {
+    pub fn for_tag(tag: &str) -> ExportLocale {
+        LOCALES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(tag))
+            .map(|(_, locale)| *locale)
+            .unwrap_or(LOCALES[0].1)
+    }
+
+    /// Spreadsheets in decimal-comma locales expect `;` between CSV fields
+    pub fn field_separator(&self) -> char {
+        if self.decimal == ',' { ';' } else { ',' }
+    }
+
+    /// Seconds with millisecond precision, e.g. `83.500` / `83,500`
+    pub fn seconds(&self, ms: i64) -> String {
+        let sign = if ms < 0 { "-" } else { "" };
+        format!("{}{}{}{:03}", sign, ms.abs() / 1000, self.decimal, ms.abs() % 1000)
+    }
+
+    /// `1:23:45.67`, with the locale's decimal separator
+    pub fn time(&self, ms: i64) -> String {
+        format_time(ms).replace('.', &self.decimal.to_string())
+    }
+
+    /// `+1:02.3`, with the locale's decimal separator
+    pub fn delta(&self, ms: i64) -> String {
+        format_delta(ms).replace('.', &self.decimal.to_string())
+    }
+
+    /// Local date and time from an RFC 3339 timestamp, or the timestamp as-is if it doesn't parse
+    pub fn datetime(&self, timestamp: &str) -> String {
+        let time_format = if self.hour12 { "%-I:%M %p" } else { "%H:%M" };
+        chrono::DateTime::parse_from_rfc3339(timestamp)
+            .map(|at| {
+                at.with_timezone(&chrono::Local)
+                    .format(&format!("{} {}", self.date_format, time_format))
+                    .to_string()
+            })
+            .unwrap_or_else(|_| timestamp.to_string())
+    }
+}
+
+/// File formats for `export_run_table`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Markdown,
+    Html,
+}
+
+impl TableFormat {
+    /// Format for a file extension (`csv`, `md`/`markdown`, `html`/`htm`)
+    pub fn from_extension(extension: &str) -> Option<TableFormat> {
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Some(TableFormat::Csv),
+            "md" | "markdown" => Some(TableFormat::Markdown),
+            "html" | "htm" => Some(TableFormat::Html),
+            _ => None,
+        }
+    }
+}
+
+const HEADERS: [&str; 6] = ["Split", "Time", "Segment", "Delta", "Town", "Hideout"];
+
+/// Render a run's splits table
+pub fn render(format: TableFormat, run: &Run, splits: &[Split], locale: &ExportLocale) -> String {
+    match format {
+        TableFormat::Csv => csv(splits, locale),
+        TableFormat::Markdown => markdown(run, splits, locale),
+        TableFormat::Html => html(run, splits, locale),
+    }
+}
+
+/// A CSV field, quoted when it holds the separator, a quote or a line break
+fn csv_field(value: &str, separator: &str) -> String {
+    if value.contains(separator) || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// CSV lines from rows of raw values, header first
+fn csv_lines(header: &[&str], rows: impl IntoIterator<Item = Vec<String>>, locale: &ExportLocale) -> String {
+    let separator = locale.field_separator().to_string();
+    let mut out = String::new();
+    let header = header.iter().map(|h| h.to_string());
+    for row in std::iter::once(header.collect::<Vec<_>>()).chain(rows) {
+        let fields: Vec<String> = row.iter().map(|value| csv_field(value, &separator)).collect();
+        out.push_str(&fields.join(&separator));
+        out.push('\n');
+    }
+    out
+}
+
+/// Times in seconds so spreadsheets import them as numbers
+fn csv(splits: &[Split], locale: &ExportLocale) -> String {
+    let mut header = vec![HEADERS[0].to_string()];
+    header.extend(HEADERS[1..].iter().map(|h| format!("{} (s)", h)));
+    let header: Vec<&str> = header.iter().map(String::as_str).collect();
+    let rows = splits.iter().map(|split| {
+        vec![
+            split.breakpoint_name.clone(),
+            locale.seconds(split.split_time_ms),
+            locale.seconds(split.segment_time_ms),
+            split.delta_ms.map(|d| locale.seconds(d)).unwrap_or_default(),
+            locale.seconds(split.town_time_ms),
+            locale.seconds(split.hideout_time_ms),
+        ]
+    });
+    csv_lines(&header, rows, locale)
+}
+
+const RUN_HEADERS: [&str; 17] = [
+    "Run ID",
+    "Started",
+    "Ended",
+    "Character",
+    "Class",
+    "Ascendancy",
+    "League",
+    "Ruleset",
+    "Category",
+    "Preset",
+    "Game Version",
+    "Completed",
+    "Personal Best",
+    "Warm-up",
+    "Total (s)",
+    "Abandon Reason",
+    "Abandoned At (s)",
+];
+
+/// One row per run for spreadsheet analysis. Timestamps stay RFC 3339 and
+/// times are in seconds, so spreadsheets read them as dates and numbers.
+pub fn runs_csv(runs: &[Run], locale: &ExportLocale) -> String {
+    let rows = runs.iter().map(|run| {
+        vec![
+            run.id.to_string(),
+            run.started_at.clone(),
+            run.ended_at.clone().unwrap_or_default(),
+            run.character_name.clone(),
+            run.class.clone(),
+            run.ascendancy.clone().unwrap_or_default(),
+            run.league.clone(),
+            run.ruleset.clone().unwrap_or_default(),
+            run.category.clone(),
+            run.breakpoint_preset.clone().unwrap_or_default(),
+            run.game_version.clone().unwrap_or_default(),
+            run.is_completed.to_string(),
+            run.is_personal_best.to_string(),
+            run.is_warmup.to_string(),
+            run.total_time_ms.map(|ms| locale.seconds(ms)).unwrap_or_default(),
+            run.abandon_reason.clone().unwrap_or_default(),
+            run.abandoned_at_ms.map(|ms| locale.seconds(ms)).unwrap_or_default(),
+        ]
+    });
+    csv_lines(&RUN_HEADERS, rows, locale)
+}
+
+const SPLIT_HEADERS: [&str; 13] = [
+    "Run ID",
+    "Category",
+    "Class",
+    "Split #",
+    "Split",
+    "Type",
+    "Key",
+    "Time (s)",
+    "Segment (s)",
+    "Delta (s)",
+    "Town (s)",
+    "Hideout (s)",
+    "Layout",
+];
+
+/// One row per split of each run, in split order, for spreadsheet analysis
+pub fn splits_csv(runs: &[(Run, Vec<Split>)], locale: &ExportLocale) -> String {
+    let rows = runs.iter().flat_map(|(run, splits)| {
+        splits.iter().enumerate().map(move |(index, split)| {
+            vec![
+                run.id.to_string(),
+                run.category.clone(),
+                run.class.clone(),
+                (index + 1).to_string(),
+                split.breakpoint_name.clone(),
+                split.breakpoint_type.clone(),
+                split.breakpoint_key.clone().unwrap_or_default(),
+                locale.seconds(split.split_time_ms),
+                locale.seconds(split.segment_time_ms),
+                split.delta_ms.map(|d| locale.seconds(d)).unwrap_or_default(),
+                locale.seconds(split.town_time_ms),
+                locale.seconds(split.hideout_time_ms),
+                split.layout_variant.clone().unwrap_or_default(),
+            ]
+        })
+    });
+    csv_lines(&SPLIT_HEADERS, rows, locale)
+}
+
+/// Display cells of a split, shared by the Markdown and HTML tables
+fn display_row(split: &Split, locale: &ExportLocale) -> [String; 6] {
+    [
+        split.breakpoint_name.clone(),
+        locale.time(split.split_time_ms),
+        locale.time(split.segment_time_ms),
+        split.delta_ms.map(|d| locale.delta(d)).unwrap_or_default(),
+        locale.time(split.town_time_ms),
+        locale.time(split.hideout_time_ms),
+    ]
+}
+
+fn title(run: &Run, locale: &ExportLocale) -> String {
+    format!("{} - {} ({}) - {}", run.character_name, run.category, run.class, locale.datetime(&run.started_at))
+}
+
+fn markdown(run: &Run, splits: &[Split], locale: &ExportLocale) -> String {
+    let mut out = format!("## {}\n\n", title(run, locale));
+    out.push_str(&format!("| {} |\n", HEADERS.join(" | ")));
+    out.push_str("| --- | ---: | ---: | ---: | ---: | ---: |\n");
+    for split in splits {
+        let cells = display_row(split, locale).map(|cell| cell.replace('|', "\\|"));
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    if let Some(total) = run.total_time_ms {
+        out.push_str(&format!("\n**Total:** {}\n", locale.time(total)));
+    }
+    out
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html(run: &Run, splits: &[Split], locale: &ExportLocale) -> String {
+    let cells = |tag: &str, values: &[String]| -> String {
+        values.iter().map(|v| format!("<{0}>{1}</{0}>", tag, escape_html(v))).collect()
+    };
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h2>{0}</h2>\n<table>\n",
+        escape_html(&title(run, locale))
+    );
+    out.push_str(&format!("<tr>{}</tr>\n", cells("th", &HEADERS.map(String::from))));
+    for split in splits {
+        out.push_str(&format!("<tr>{}</tr>\n", cells("td", &display_row(split, locale))));
+    }
+    out.push_str("</table>\n");
+    if let Some(total) = run.total_time_ms {
+        out.push_str(&format!("<p><strong>Total:</strong> {}</p>\n", locale.time(total)));
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(name: &str, split_time_ms: i64, delta_ms: Option<i64>) -> Split {
+        Split {
+            id: 0,
+            run_id: 0,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: None,
+            split_time_ms,
+            delta_ms,
+            segment_time_ms: split_time_ms,
+            town_time_ms: 1_500,
+            hideout_time_ms: 0,
+            segment_town_time_ms: 0,
+            segment_hideout_time_ms: 0,
+            layout_variant: None,
+            layout_inferred: false,
+            experience: None,
+            character_level: None,
+            load_time_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_locale_numbers_and_dates() {
+        let de = ExportLocale::for_tag("de-de");
+        assert_eq!(de.seconds(83_500), "83,500");
+        assert_eq!(de.seconds(-2_050), "-2,050");
+        assert_eq!(de.time(83_500), "1:23,50");
+        assert_eq!(de.delta(-4_500), "-4,5");
+        assert_eq!(de.field_separator(), ';');
+
+        let us = ExportLocale::for_tag("unknown");
+        assert_eq!(us, ExportLocale::for_tag("en-US"));
+        assert_eq!(us.field_separator(), ',');
+
+        let timestamp = chrono::Local::now().to_rfc3339();
+        let at = chrono::DateTime::parse_from_rfc3339(&timestamp).unwrap();
+        assert_eq!(us.datetime(&timestamp), at.format("%m/%d/%Y %-I:%M %p").to_string());
+        assert_eq!(ExportLocale::for_tag("ISO").datetime(&timestamp), at.format("%Y-%m-%d %H:%M").to_string());
+        assert_eq!(us.datetime("yesterday"), "yesterday");
+    }
+
+    #[test]
+    fn test_csv_uses_locale_separators() {
+        let splits = [split("The Coast, Act 1", 83_500, None), split("Mud Flats", 120_000, Some(-1_250))];
+        let csv = csv(&splits, &ExportLocale::for_tag("fr-FR"));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "Split;Time (s);Segment (s);Delta (s);Town (s);Hideout (s)");
+        assert_eq!(lines[1], "The Coast, Act 1;83,500;83,500;;1,500;0,000");
+        assert_eq!(lines[2], "Mud Flats;120,000;120,000;-1,250;1,500;0,000");
+
+        let csv = super::csv(&splits, &ExportLocale::for_tag("en-US"));
+        assert!(csv.lines().nth(1).unwrap().starts_with("\"The Coast, Act 1\",83.500,"));
+    }
+
+    #[test]
+    fn test_runs_and_splits_csv() {
+        let run: Run = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "characterName": "Strand;Runner",
+            "accountName": "",
+            "class": "Witch",
+            "ascendancy": null,
+            "league": "Standard",
+            "category": "any%",
+            "categorySource": "manual",
+            "startedAt": "2026-01-15T12:00:00Z",
+            "endedAt": null,
+            "totalTimeMs": 3_723_450,
+            "isCompleted": true,
+            "isPersonalBest": false,
+            "breakpointPreset": null,
+            "enabledBreakpoints": null,
+            "isReference": false,
+            "sourceName": null,
+            "videoUrl": null,
+            "vodOffsetMs": null,
+            "reviewNotes": null,
+            "isWarmup": false,
+            "gameVersion": "poe1",
+            "startOffsetMs": 0,
+            "abandonReason": null,
+            "abandonedAtMs": null,
+            "loadTimeMs": 0,
+            "ruleset": "SC"
+        }))
+        .unwrap();
+        let de = ExportLocale::for_tag("de-DE");
+
+        let runs = runs_csv(std::slice::from_ref(&run), &de);
+        let lines: Vec<&str> = runs.lines().collect();
+        assert_eq!(lines[0].split(';').count(), RUN_HEADERS.len());
+        assert_eq!(
+            lines[1],
+            "7;2026-01-15T12:00:00Z;;\"Strand;Runner\";Witch;;Standard;SC;any%;;poe1;true;false;false;3723,450;;"
+        );
+
+        let splits = splits_csv(&[(run, vec![split("The Coast", 83_500, Some(-1_250))])], &de);
+        let lines: Vec<&str> = splits.lines().collect();
+        assert_eq!(lines[0].split(';').count(), SPLIT_HEADERS.len());
+        assert_eq!(lines[1], "7;any%;Witch;1;The Coast;zone;;83,500;83,500;-1,250;1,500;0,000;");
+    }
+
+    #[test]
+    fn test_table_format_from_extension() {
+        assert_eq!(TableFormat::from_extension("CSV"), Some(TableFormat::Csv));
+        assert_eq!(TableFormat::from_extension("md"), Some(TableFormat::Markdown));
+        assert_eq!(TableFormat::from_extension("htm"), Some(TableFormat::Html));
+        assert_eq!(TableFormat::from_extension("json"), None);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::api_client::{ApiError, CharacterItems, PassiveSkills, PoeApi, PoeCharacter};
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Recorded character-window responses for an ascended level 42 Necromancer
+pub const CHARACTERS_JSON: &str = include_str!("fixtures/get-characters.json");
+pub const ITEMS_JSON: &str = include_str!("fixtures/get-items.json");
+pub const PASSIVES_JSON: &str = include_str!("fixtures/get-passive-skills.json");
+
+/// `PoeApi` that serves the recorded fixtures instead of calling pathofexile.com
+pub struct FakePoeApi {
+    private_profile: bool,
+    hidden_characters: bool,
+    calls: AtomicUsize,
+}
+
+impl FakePoeApi {
+    pub fn new() -> Self {
+        FakePoeApi {
+            private_profile: false,
+            hidden_characters: false,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Behave like an account whose profile is private (every endpoint returns 403)
+    pub fn private_profile() -> Self {
+        FakePoeApi {
+            private_profile: true,
+            hidden_characters: false,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Behave like a public profile with the characters tab hidden (character
+    /// endpoints return 403, the profile page is readable)
+    pub fn hidden_characters() -> Self {
+        FakePoeApi {
+            private_profile: false,
+            hidden_characters: true,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of requests made so far
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    fn respond<T: DeserializeOwned>(&self, fixture: &str) -> Result<T> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if self.private_profile || self.hidden_characters {
+            return Err(ApiError::PrivateProfile.into());
+        }
+        Ok(serde_json::from_str(fixture)?)
+    }
+}
+
+impl PoeApi for FakePoeApi {
+    async fn get_characters(&self, _account_name: &str) -> Result<Vec<PoeCharacter>> {
+        self.respond(CHARACTERS_JSON)
+    }
+
+    async fn get_items(&self, _account_name: &str, _character_name: &str) -> Result<CharacterItems> {
+        self.respond(ITEMS_JSON)
+    }
+
+    async fn get_passive_skills(&self, _account_name: &str, _character_name: &str) -> Result<PassiveSkills> {
+        self.respond(PASSIVES_JSON)
+    }
+
+    async fn get_profile_page(&self, account_name: &str) -> Result<String> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if self.private_profile {
+            return Ok(format!("<h1>{}</h1><p>This profile is private.</p>", account_name));
+        }
+        Ok(format!("<h1>{}</h1><p>Joined: Jan 1, 2013</p>", account_name))
+    }
+}
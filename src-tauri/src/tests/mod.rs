@@ -0,0 +1,9 @@
+//! Shared test support and end-to-end tests for the log -> split pipeline
+
+pub mod fake_api;
+pub mod support;
+
+mod pipeline;
+mod runs;
+mod snapshot;
+mod status;
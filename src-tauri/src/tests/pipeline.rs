@@ -0,0 +1,1096 @@
+use super::support::{block_on, level_line, log_line, setup_db, zone_line, TempLog};
+use crate::breakpoint_engine::{self, EngineBreakpoint};
+use crate::commands::{
+    finish_run, get_deaths, import_run_from_log, LogImportPreset, get_run_timeline, get_split_trends, get_zone_heatmap, get_zone_time_stats, get_zone_times, record_split, revert_provisional_golds, skip_split,
+    undo_last_split,
+};
+use crate::db::{Death, GoldSplit, NewRun, NewSplit, Pause, PersonalBest, ProvisionalGold, Run, RunFilters, Settings, SkippedSplit, Split};
+use crate::log_watcher::{resolve_path_case, LogEvent, LogWatcher, RotationReason};
+use crate::pause::{self, PauseReason};
+use crate::quest_state;
+use chrono::NaiveDateTime;
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+const CATEGORY: &str = "Act 1 Any%";
+const BREAKPOINTS: &[&str] = &["The Coast", "The Mud Flats", "The Submerged Passage", "The Ledge"];
+
+/// Append lines to a fresh Client.txt and collect the events the watcher delivers
+fn watch_lines(lines: &[String], expected: usize) -> Vec<LogEvent> {
+    let log = TempLog::new();
+    let (tx, rx) = channel();
+
+    let mut watcher = LogWatcher::new(log.path().to_path_buf());
+    watcher
+        .start_with_handler(move |event| {
+            let _ = tx.send(event);
+        })
+        .expect("Failed to start watcher");
+
+    log.append(lines);
+
+    let mut events = Vec::new();
+    while events.len() < expected {
+        match rx.recv_timeout(Duration::from_secs(5)) {
+            Ok(event) => events.push(event),
+            Err(_) => break,
+        }
+    }
+    watcher.stop();
+    events
+}
+
+fn parse_ts(ts: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(ts, "%Y/%m/%d %H:%M:%S").expect("valid log timestamp")
+}
+
+fn create_run() -> i64 {
+    Run::insert(&NewRun {
+        character_name: "TestChar".to_string(),
+        account_name: "TestAccount".to_string(),
+        class: "Witch".to_string(),
+        ascendancy: None,
+        league: "Standard".to_string(),
+        category: CATEGORY.to_string(),
+        started_at: "2024-01-15T12:00:00Z".to_string(),
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        game_version: None,
+    })
+    .expect("Failed to insert run")
+}
+
+/// Mirror of the frontend split logic: the run starts on the first zone entry and
+/// splits each time the next breakpoint zone is entered. Returns the final time.
+fn record_zone_splits(run_id: i64, events: &[LogEvent]) -> i64 {
+    let mut start = None;
+    let mut next = 0;
+    let mut prev_time = 0;
+
+    for event in events {
+        let LogEvent::ZoneEnter { timestamp, zone_name } = event else {
+            continue;
+        };
+        let at = parse_ts(timestamp);
+        let start = *start.get_or_insert(at);
+
+        if next < BREAKPOINTS.len() && zone_name == BREAKPOINTS[next] {
+            let split_time_ms = (at - start).num_milliseconds();
+            record_split(&NewSplit {
+                run_id,
+                breakpoint_type: "zone".to_string(),
+                breakpoint_name: zone_name.clone(),
+                breakpoint_key: None,
+                split_time_ms,
+                delta_ms: None,
+                segment_time_ms: split_time_ms - prev_time,
+                town_time_ms: 0,
+                hideout_time_ms: 0,
+            })
+            .expect("Failed to record split");
+            prev_time = split_time_ms;
+            next += 1;
+        }
+    }
+
+    prev_time
+}
+
+/// Play one run through the watcher, with each breakpoint `segments[i]` seconds after the previous
+fn play_run(segments: &[i64]) -> (i64, i64) {
+    let mut lines = vec![zone_line(0, "The Twilight Strand")];
+    let mut offset = 0;
+    for (zone, segment) in BREAKPOINTS.iter().zip(segments) {
+        offset += segment;
+        lines.push(zone_line(offset, zone));
+    }
+
+    let events = watch_lines(&lines, lines.len());
+    assert_eq!(events.len(), lines.len(), "watcher should deliver every appended line");
+
+    let run_id = create_run();
+    let total = record_zone_splits(run_id, &events);
+    (run_id, total)
+}
+
+fn golds() -> Vec<(String, i64)> {
+    let mut golds: Vec<(String, i64)> = GoldSplit::get_all()
+        .unwrap()
+        .into_iter()
+        .filter(|g| g.category == CATEGORY && g.class == "Witch")
+        .map(|g| (g.breakpoint_name, g.best_segment_ms))
+        .collect();
+    golds.sort_by_key(|(name, _)| BREAKPOINTS.iter().position(|b| b == name));
+    golds
+}
+
+#[test]
+fn test_watcher_only_emits_appended_lines() {
+    let log = TempLog::new();
+    log.append(&[zone_line(0, "The Twilight Strand")]);
+
+    let (tx, rx) = channel();
+    let mut watcher = LogWatcher::new(log.path().to_path_buf());
+    watcher
+        .start_with_handler(move |event| {
+            let _ = tx.send(event);
+        })
+        .unwrap();
+
+    log.append(&[
+        zone_line(10, "The Coast"),
+        "2024/01/15 12:00:11 12345678 abc [DEBUG Client 1234] Unrelated line".to_string(),
+        level_line(12, "TestChar", "Witch", 2),
+    ]);
+
+    let first = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    let second = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    watcher.stop();
+
+    assert!(matches!(first, LogEvent::ZoneEnter { zone_name, .. } if zone_name == "The Coast"));
+    assert!(matches!(second, LogEvent::LevelUp { level: 2, .. }));
+    assert!(rx.recv_timeout(Duration::from_millis(300)).is_err());
+}
+
+#[test]
+fn test_watcher_matches_log_path_case() {
+    // A Wine prefix on a case-sensitive file system, with the path typed the Windows way
+    let log = TempLog::new();
+    let typed = log.path().with_file_name("CLIENT.TXT");
+    assert_eq!(resolve_path_case(&typed), log.path());
+
+    let (tx, rx) = channel();
+    let mut watcher = LogWatcher::new(typed);
+    watcher
+        .start_with_handler(move |event| {
+            let _ = tx.send(event);
+        })
+        .unwrap();
+
+    log.append(&[zone_line(10, "The Coast")]);
+    let event = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    watcher.stop();
+
+    assert!(matches!(event, LogEvent::ZoneEnter { zone_name, .. } if zone_name == "The Coast"));
+}
+
+#[test]
+fn test_watcher_tracks_game_version() {
+    // Client launched before the watcher: the version comes from the existing log
+    let log = TempLog::new();
+    log.append(&[log_line(0, "Client Version: 3.24.2.1"), zone_line(5, "The Twilight Strand")]);
+
+    let (tx, rx) = channel();
+    let mut watcher = LogWatcher::new(log.path().to_path_buf());
+    watcher
+        .start_with_handler(move |event| {
+            let _ = tx.send(event);
+        })
+        .unwrap();
+    assert_eq!(watcher.game_version().as_deref(), Some("3.24.2.1"));
+
+    // Client restarted on a new patch while watching
+    log.append(&[log_line(60, "Client Version: 3.25.0.4")]);
+    let event = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    watcher.stop();
+
+    assert!(matches!(event, LogEvent::GameVersion { version, .. } if version == "3.25.0.4"));
+    assert_eq!(watcher.game_version().as_deref(), Some("3.25.0.4"));
+}
+
+#[test]
+fn test_watcher_follows_truncated_and_replaced_log() {
+    let log = TempLog::new();
+    log.append(&[zone_line(0, "The Twilight Strand"), zone_line(10, "The Coast")]);
+
+    let (tx, rx) = channel();
+    let mut watcher = LogWatcher::new(log.path().to_path_buf());
+    watcher
+        .start_with_handler(move |event| {
+            let _ = tx.send(event);
+        })
+        .unwrap();
+
+    // Truncated in place: the new first line is read, not skipped
+    std::fs::write(log.path(), format!("{}\n", zone_line(20, "The Mud Flats"))).unwrap();
+    let rotated = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    let zone = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(rotated, LogEvent::LogRotated { reason: RotationReason::Truncated, .. }));
+    assert!(matches!(zone, LogEvent::ZoneEnter { zone_name, .. } if zone_name == "The Mud Flats"));
+
+    // Replaced by a longer file, which a position check alone would miss.
+    // Windows can carry the old creation time over to the new file, so the
+    // identity check is only reliable on Unix.
+    if cfg!(unix) {
+        let replacement = log.path().with_extension("new");
+        let lines: Vec<String> = (0..5).map(|i| zone_line(30 + i, "The Tidal Island")).collect();
+        std::fs::write(&replacement, lines.join("\n") + "\n").unwrap();
+        std::fs::rename(&replacement, log.path()).unwrap();
+        let rotated = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        let zone = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(rotated, LogEvent::LogRotated { reason: RotationReason::Replaced, .. }));
+        assert!(matches!(zone, LogEvent::ZoneEnter { zone_name, .. } if zone_name == "The Tidal Island"));
+    }
+    watcher.stop();
+}
+
+#[test]
+fn test_run_produces_splits_and_golds() {
+    let _db = setup_db();
+
+    let (run_id, total) = play_run(&[60, 90, 120, 45]);
+    assert_eq!(total, 315_000);
+
+    let splits = Split::get_by_run(run_id).unwrap();
+    let names: Vec<&str> = splits.iter().map(|s| s.breakpoint_name.as_str()).collect();
+    assert_eq!(names, BREAKPOINTS);
+    let segments: Vec<i64> = splits.iter().map(|s| s.segment_time_ms).collect();
+    assert_eq!(segments, vec![60_000, 90_000, 120_000, 45_000]);
+    assert_eq!(splits.last().unwrap().split_time_ms, 315_000);
+
+    let expected: Vec<(String, i64)> = BREAKPOINTS
+        .iter()
+        .map(|b| b.to_string())
+        .zip(segments)
+        .collect();
+    assert_eq!(golds(), expected);
+}
+
+#[test]
+fn test_golds_and_pb_only_improve() {
+    let _db = setup_db();
+
+    let (first, first_total) = play_run(&[60, 90, 120, 45]);
+    assert!(finish_run(first, first_total).unwrap(), "first completed run is a PB");
+
+    // Faster overall, but slower on the first segment
+    let (second, second_total) = play_run(&[70, 80, 100, 45]);
+    assert!(finish_run(second, second_total).unwrap());
+
+    // Slower run never replaces the PB
+    let (third, third_total) = play_run(&[65, 95, 130, 50]);
+    assert!(!finish_run(third, third_total).unwrap());
+
+    let golds: Vec<i64> = golds().into_iter().map(|(_, ms)| ms).collect();
+    assert_eq!(golds, vec![60_000, 80_000, 100_000, 45_000]);
+
+    let pbs = PersonalBest::get_all().unwrap();
+    assert_eq!(pbs.len(), 1);
+    assert_eq!(pbs[0].run_id, second);
+    assert_eq!(pbs[0].total_time_ms, 295_000);
+    assert!(Run::get_by_id(third).unwrap().unwrap().is_completed);
+}
+
+#[test]
+fn test_record_split_reports_beaten_golds() {
+    let _db = setup_db();
+
+    // First attempt sets the gold but doesn't beat one; the slower and faster retries follow
+    let results: Vec<bool> = [60_000, 65_000, 55_000]
+        .into_iter()
+        .map(|segment| {
+            record_split(&NewSplit {
+                run_id: create_run(),
+                breakpoint_type: "zone".to_string(),
+                breakpoint_name: "The Coast".to_string(),
+                breakpoint_key: None,
+                split_time_ms: segment,
+                delta_ms: None,
+                segment_time_ms: segment,
+                town_time_ms: 0,
+                hideout_time_ms: 0,
+            })
+            .unwrap()
+            .2
+        })
+        .collect();
+    assert_eq!(results, vec![false, false, true]);
+}
+
+#[test]
+fn test_undo_reverts_provisional_golds() {
+    let _db = setup_db();
+    let split = |run_id: i64, name: &str, segment: i64| {
+        record_split(&NewSplit {
+            run_id,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: None,
+            split_time_ms: segment,
+            delta_ms: None,
+            segment_time_ms: segment,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+        })
+        .unwrap()
+    };
+    let best = |name: &str| GoldSplit::get_best(CATEGORY, "Witch", name, None).unwrap();
+
+    // An old gold, then a mis-split that beats it and a brand new location
+    split(create_run(), "The Coast", 60_000);
+    let run_id = create_run();
+    split(run_id, "The Coast", 5_000);
+    split(run_id, "The Mud Flats", 10_000);
+    assert_eq!(best("The Coast"), Some(5_000));
+
+    // Undo removes the latest split and the gold row it created
+    assert_eq!(block_on(undo_last_split(run_id)).unwrap(), 1);
+    assert_eq!(best("The Mud Flats"), None);
+    assert_eq!(Split::get_by_run(run_id).unwrap().len(), 1);
+
+    // Reset puts back the gold the mis-split beat
+    assert_eq!(block_on(revert_provisional_golds(run_id)).unwrap(), 1);
+    assert_eq!(best("The Coast"), Some(60_000));
+    assert_eq!(block_on(revert_provisional_golds(run_id)).unwrap(), 0, "already reverted");
+
+    // Golds older than the window stay, as do golds beaten again since
+    let run_id = create_run();
+    split(run_id, "The Coast", 50_000);
+    let future_ms = chrono::Utc::now().timestamp_millis() + 60_000;
+    assert_eq!(ProvisionalGold::revert(run_id, None, future_ms).unwrap(), 0);
+    let first = create_run();
+    split(first, "The Ledge", 40_000);
+    split(create_run(), "The Ledge", 30_000);
+    assert_eq!(block_on(revert_provisional_golds(first)).unwrap(), 0);
+    assert_eq!(best("The Ledge"), Some(30_000));
+    assert_eq!(best("The Coast"), Some(50_000));
+
+    assert_eq!(block_on(undo_last_split(create_run())).unwrap_err().code(), "not_found");
+}
+
+#[test]
+fn test_skip_split_moves_on_without_a_gold() {
+    let _db = setup_db();
+    let breakpoints: Vec<EngineBreakpoint> = BREAKPOINTS
+        .iter()
+        .map(|zone| {
+            serde_json::from_value(serde_json::json!({
+                "name": zone,
+                "type": "zone",
+                "trigger": { "type": "zone", "zoneName": zone, "act": 1 },
+                "captureSnapshot": false,
+            }))
+            .unwrap()
+        })
+        .collect();
+    let enter = |zone: &str| {
+        let event = LogEvent::ZoneEnter { timestamp: String::new(), zone_name: zone.to_string() };
+        let pending = breakpoint_engine::split_for(&event).unwrap()?;
+        record_split(&pending.split).expect("Failed to record split");
+        Some(pending.split)
+    };
+
+    let run_id = create_run();
+    breakpoint_engine::start_run(run_id, breakpoints, 1_000, None);
+    assert_eq!(enter("The Coast").map(|s| s.breakpoint_name).as_deref(), Some("The Coast"));
+
+    // The Mud Flats was missed: skipping it lets the next zone split
+    let skipped = block_on(skip_split(run_id, "The Mud Flats".to_string())).unwrap();
+    assert_eq!(skipped.breakpoint_name, "The Mud Flats");
+    assert_eq!(block_on(skip_split(run_id, "The Mud Flats".to_string())).unwrap_err().code(), "invalid_input");
+    assert_eq!(block_on(skip_split(run_id, "The Coast".to_string())).unwrap_err().code(), "invalid_input");
+    assert_eq!(block_on(skip_split(run_id + 1, "The Ledge".to_string())).unwrap_err().code(), "not_found");
+    assert!(enter("The Mud Flats").is_none());
+
+    // Its segment covers both breakpoints, so no gold is set for it
+    breakpoint_engine::set_running(true, 5_000).unwrap();
+    let spanning = enter("The Submerged Passage").expect("next breakpoint splits");
+    let coast_ms = Split::get_by_run(run_id).unwrap()[0].split_time_ms;
+    assert_eq!(spanning.segment_time_ms, spanning.split_time_ms - coast_ms);
+    breakpoint_engine::stop();
+    let best = |name: &str| {
+        let key = format!("zone:a1:{}", name.to_lowercase().replace(' ', "_"));
+        GoldSplit::get_best(CATEGORY, "Witch", name, Some(&key)).unwrap()
+    };
+    assert!(best("The Coast").is_some());
+    assert_eq!(best("The Submerged Passage"), None);
+
+    // Undo takes back the split, then the skip, then the split before it
+    assert_eq!(block_on(undo_last_split(run_id)).unwrap(), 0);
+    assert_eq!(Split::get_by_run(run_id).unwrap().len(), 1);
+    assert_eq!(block_on(undo_last_split(run_id)).unwrap(), 0);
+    assert!(SkippedSplit::get_by_run(run_id).unwrap().is_empty());
+    assert_eq!(Split::get_by_run(run_id).unwrap().len(), 1);
+    block_on(undo_last_split(run_id)).unwrap();
+    assert!(Split::get_by_run(run_id).unwrap().is_empty());
+}
+
+#[test]
+fn test_golds_merge_by_breakpoint_key() {
+    let _db = setup_db();
+    let key = Some("zone:a2:the_southern_forest".to_string());
+
+    // Two presets name the same location differently
+    for (name, segment) in [("The Southern Forest", 600_000), ("Act 2 start", 570_000)] {
+        let run_id = create_run();
+        record_split(&NewSplit {
+            run_id,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: key.clone(),
+            split_time_ms: segment,
+            delta_ms: None,
+            segment_time_ms: segment,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+        })
+        .unwrap();
+    }
+
+    let golds = GoldSplit::get_all().unwrap();
+    assert_eq!(golds.len(), 1);
+    assert_eq!(golds[0].breakpoint_key, key);
+    assert_eq!(golds[0].best_segment_ms, 570_000);
+
+    let stats = Split::get_stats(&Default::default()).unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].run_count, 2);
+
+    // The same name under another key is another location
+    let other = GoldSplit::update_if_better(CATEGORY, "Witch", "The Southern Forest", Some("zone:a7:the_southern_forest"), 900_000)
+        .unwrap()
+        .unwrap();
+    assert_ne!(other.gold_split_id, golds[0].id);
+    assert_eq!(GoldSplit::get_best(CATEGORY, "Witch", "The Southern Forest", key.as_deref()).unwrap(), Some(570_000));
+
+    // A gold recorded without a key is matched by name and takes the key
+    let legacy = GoldSplit::update_if_better(CATEGORY, "Witch", "The Crossroads", None, 300_000).unwrap().unwrap();
+    let keyed = GoldSplit::update_if_better(CATEGORY, "Witch", "The Crossroads", Some("zone:a2:the_crossroads"), 280_000)
+        .unwrap()
+        .unwrap();
+    assert_eq!(keyed.gold_split_id, legacy.gold_split_id);
+    assert_eq!(keyed.previous_best_ms, Some(300_000));
+}
+
+#[test]
+fn test_layout_inferred_from_segment_history() {
+    let _db = setup_db();
+
+    // Not enough history on the first runs
+    for segments in [[60, 90, 120, 45], [62, 88, 118, 46], [58, 92, 121, 44]] {
+        let (run_id, _) = play_run(&segments);
+        assert!(Split::get_by_run(run_id).unwrap().iter().all(|s| s.layout_variant.is_none()));
+    }
+
+    let (run_id, _) = play_run(&[45, 90, 150, 46]);
+    let layouts: Vec<Option<String>> = Split::get_by_run(run_id)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.layout_variant)
+        .collect();
+    let expected = ["fast", "typical", "slow", "typical"].map(|v| Some(v.to_string()));
+    assert_eq!(layouts, expected);
+
+    let stats = Split::get_stats(&Default::default()).unwrap();
+    let passage = stats.iter().find(|s| s.breakpoint_name == "The Submerged Passage").unwrap();
+    assert_eq!(passage.layouts.len(), 1);
+    assert_eq!(passage.layouts[0].layout_variant, "slow");
+    assert_eq!(passage.layouts[0].average_segment_ms, 150_000);
+}
+
+#[test]
+fn test_experience_per_segment_in_stats() {
+    let _db = setup_db();
+
+    // Experience from each split's snapshot; missing snapshots leave gaps
+    for experience in [
+        [Some(600), Some(2_400), Some(6_000), None],
+        [Some(900), None, Some(8_000), Some(9_000)],
+    ] {
+        let (run_id, _) = play_run(&[60, 90, 120, 45]);
+        for (split, experience) in Split::get_by_run(run_id).unwrap().iter().zip(experience) {
+            if let Some(experience) = experience {
+                Split::set_experience(split.id, experience).unwrap();
+            }
+        }
+    }
+
+    let stats = Split::get_stats(&Default::default()).unwrap();
+    let experience = |name: &str| {
+        let stat = stats.iter().find(|s| s.breakpoint_name == name).unwrap();
+        (stat.average_segment_experience, stat.experience_per_minute)
+    };
+    assert_eq!(experience("The Coast"), (Some(750), Some(750)));
+    assert_eq!(experience("The Mud Flats"), (Some(1_800), Some(1_200)));
+    assert_eq!(experience("The Submerged Passage"), (Some(3_600), Some(1_800)));
+    assert_eq!(experience("The Ledge"), (Some(1_000), Some(1_333)));
+}
+
+#[test]
+fn test_split_stats_median_and_p90() {
+    let _db = setup_db();
+
+    for segment in [55, 51, 60, 58, 52, 57, 54, 59, 53, 56] {
+        play_run(&[segment]);
+    }
+
+    let stats = Split::get_stats(&Default::default()).unwrap();
+    assert_eq!(stats.len(), 1);
+    let coast = &stats[0];
+    assert_eq!(coast.breakpoint_name, "The Coast");
+    assert_eq!((coast.run_count, coast.best_time_ms, coast.average_time_ms), (10, 51_000, 55_500));
+    // Lower median of an even count, nearest-rank p90
+    assert_eq!((coast.median_time_ms, coast.p90_time_ms), (55_000, 59_000));
+
+    // Filters apply before the percentiles are taken
+    let filters = RunFilters { category: Some("none".to_string()), ..Default::default() };
+    assert!(Split::get_stats(&filters).unwrap().is_empty());
+}
+
+#[test]
+fn test_split_trends_rolling_average() {
+    let _db = setup_db();
+
+    // A second faster each run
+    for run in 0..35 {
+        play_run(&[100 - run, 60]);
+    }
+
+    let trends = block_on(get_split_trends(RunFilters::default(), "The Coast".to_string())).unwrap();
+    assert_eq!(trends.len(), 30);
+    // The latest 30 runs, oldest first; the first average still covers the 5 runs up to it
+    assert_eq!((trends[0].split_time_ms, trends[0].rolling_average_ms), (95_000, 97_000));
+    assert_eq!((trends[29].split_time_ms, trends[29].rolling_average_ms), (66_000, 68_000));
+    assert!(trends.windows(2).all(|w| w[0].run_id < w[1].run_id));
+
+    let err = block_on(get_split_trends(RunFilters::default(), " ".to_string())).unwrap_err();
+    assert_eq!(err.code(), "invalid_input");
+}
+
+#[test]
+fn test_breakpoint_engine_splits_from_log_events() {
+    let _db = setup_db();
+
+    // Breakpoints as the frontend sends them to `start_breakpoint_engine`
+    let breakpoints: Vec<EngineBreakpoint> = BREAKPOINTS
+        .iter()
+        .map(|zone| {
+            serde_json::from_value(serde_json::json!({
+                "name": zone,
+                "type": "zone",
+                "trigger": { "type": "zone", "zoneName": zone, "act": 1 },
+                "key": format!("zone:1:{}", zone),
+                "captureSnapshot": false,
+            }))
+            .unwrap()
+        })
+        .collect();
+
+    let mut lines = vec![zone_line(0, "The Twilight Strand")];
+    lines.extend(BREAKPOINTS.iter().enumerate().map(|(i, zone)| zone_line(i as i64 + 1, zone)));
+    // Going back to an earlier zone doesn't split it again
+    lines.insert(3, zone_line(2, "The Coast"));
+    let events = watch_lines(&lines, lines.len());
+    assert_eq!(events.len(), lines.len());
+
+    let run_id = create_run();
+    breakpoint_engine::start_run(run_id, breakpoints, 0, None);
+    breakpoint_engine::set_running(false, 0).unwrap();
+    assert!(breakpoint_engine::split_for(&events[1]).unwrap().is_none(), "paused timer doesn't split");
+    breakpoint_engine::set_running(true, 1_000).unwrap();
+
+    let mut last = Vec::new();
+    for event in &events {
+        if let Some(pending) = breakpoint_engine::split_for(event).unwrap() {
+            assert_eq!(pending.character_name.as_deref(), Some("TestChar"));
+            assert!(pending.split.split_time_ms >= 1_000);
+            record_split(&pending.split).expect("Failed to record split");
+            last.push(pending.is_last);
+        }
+    }
+    breakpoint_engine::stop();
+
+    let splits = Split::get_by_run(run_id).unwrap();
+    let names: Vec<&str> = splits.iter().map(|s| s.breakpoint_name.as_str()).collect();
+    assert_eq!(names, BREAKPOINTS);
+    assert_eq!(splits[0].breakpoint_key.as_deref(), Some("zone:1:The Coast"));
+    assert_eq!(last, vec![false, false, false, true]);
+    assert!(breakpoint_engine::split_for(&events[1]).unwrap().is_none(), "stopped engine doesn't split");
+}
+
+#[test]
+fn test_breakpoint_engine_trigger_rules() {
+    let _db = setup_db();
+
+    let breakpoint = |name: &str, zone: &str, rule: serde_json::Value| -> EngineBreakpoint {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "type": "zone",
+            "trigger": { "type": "zone", "zoneName": zone, "act": 1, "rule": rule },
+            "captureSnapshot": false,
+        }))
+        .unwrap()
+    };
+    let breakpoints = vec![
+        breakpoint("Back in Town", "Lioneye's Watch", serde_json::json!({ "afterQuest": "dweller" })),
+        breakpoint("The Tidal Island", "The Tidal Island", serde_json::json!({ "entry": 1 })),
+    ];
+
+    let run_id = create_run();
+    quest_state::start_run(run_id);
+    breakpoint_engine::start_run(run_id, breakpoints, 0, None);
+
+    // Events in the order the log watcher's handler sees them
+    let split = |zone: &str| {
+        let event = LogEvent::ZoneEnter { timestamp: String::new(), zone_name: zone.to_string() };
+        quest_state::handle_event(&event);
+        let pending = breakpoint_engine::split_for(&event).unwrap()?;
+        record_split(&pending.split).expect("Failed to record split");
+        Some(pending.split.breakpoint_name)
+    };
+    assert_eq!(split("Lioneye's Watch"), None, "dweller not done yet");
+    assert_eq!(split("The Tidal Island"), None, "town is still the next zone split");
+    assert_eq!(split("The Flooded Depths"), None);
+    assert_eq!(split("Lioneye's Watch").as_deref(), Some("Back in Town"));
+    // Only the first entry counts, and that one was before the town split
+    assert_eq!(split("The Tidal Island"), None);
+    breakpoint_engine::stop();
+}
+
+#[test]
+fn test_pauses_recorded_and_auto_paused() {
+    let _db = setup_db();
+
+    let run_id = create_run();
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+    let afk = |enabled: bool| LogEvent::Afk { timestamp: String::new(), enabled };
+    let disconnect = LogEvent::Disconnect { timestamp: String::new() };
+    let zone = LogEvent::ZoneEnter { timestamp: String::new(), zone_name: "The Coast".to_string() };
+
+    assert!(pause::pause(run_id + 1, PauseReason::Manual).is_err(), "not the run being timed");
+    let paused = pause::pause(run_id, PauseReason::Manual).unwrap().unwrap();
+    assert_eq!((paused.paused, paused.reason), (true, Some(PauseReason::Manual)));
+    let current = pause::current().unwrap();
+    assert_eq!((current.run_id, current.paused, current.elapsed_ms), (run_id, true, paused.elapsed_ms));
+    // A main window shown again catches up on the clock, splits and skips
+    Split::insert(&NewSplit {
+        run_id,
+        breakpoint_type: "zone".to_string(),
+        breakpoint_name: "The Coast".to_string(),
+        breakpoint_key: None,
+        split_time_ms: 60_000,
+        delta_ms: None,
+        segment_time_ms: 60_000,
+        town_time_ms: 0,
+        hideout_time_ms: 0,
+    })
+    .unwrap();
+    SkippedSplit::insert(run_id, "The Mud Flats", 90_000).unwrap();
+    let resync = breakpoint_engine::resync().unwrap().unwrap();
+    assert_eq!(resync.clock, current);
+    assert_eq!(resync.splits.iter().map(|s| s.breakpoint_name.as_str()).collect::<Vec<_>>(), ["The Coast"]);
+    assert_eq!(resync.skipped_splits[0].breakpoint_name, "The Mud Flats");
+    assert!(pause::pause(run_id, PauseReason::Manual).unwrap().is_none(), "already paused");
+    assert!(!pause::resume(run_id).unwrap().unwrap().paused);
+
+    // Off by default
+    assert!(pause::auto_pause_for(&afk(true)).unwrap().is_none());
+    Settings::save(&Settings { auto_pause: true, ..Settings::default() }).unwrap();
+
+    assert_eq!(pause::auto_pause_for(&afk(true)).unwrap().unwrap().reason, Some(PauseReason::Afk));
+    assert!(pause::auto_pause_for(&zone).unwrap().is_none(), "zones only end disconnect pauses");
+    assert!(pause::auto_pause_for(&afk(false)).unwrap().is_some());
+
+    assert_eq!(pause::auto_pause_for(&disconnect).unwrap().unwrap().reason, Some(PauseReason::Disconnect));
+    assert!(pause::auto_pause_for(&zone).unwrap().is_some());
+
+    // A pause from the timer isn't ended by the log
+    pause::pause(run_id, PauseReason::Manual).unwrap();
+    assert!(pause::auto_pause_for(&afk(false)).unwrap().is_none());
+    breakpoint_engine::stop();
+    assert!(pause::current().is_none(), "no run being timed");
+    assert!(breakpoint_engine::resync().unwrap().is_none());
+    Settings::save(&Settings::default()).unwrap();
+
+    let pauses = Pause::get_by_run(run_id).unwrap();
+    let reasons: Vec<&str> = pauses.iter().map(|p| p.reason.as_str()).collect();
+    assert_eq!(reasons, ["manual", "afk", "disconnect", "manual"]);
+    assert!(pauses.iter().all(|p| p.resumed_at.is_some() && p.duration_ms.is_some()), "stop closes the last pause");
+    assert!(Pause::open(run_id).unwrap().is_none());
+}
+
+#[test]
+fn test_level_up_corrects_run_class() {
+    let _db = setup_db();
+
+    let run_id = Run::insert(&NewRun {
+        character_name: "Unknown".to_string(),
+        account_name: "TestAccount".to_string(),
+        class: "Unknown".to_string(),
+        ascendancy: None,
+        league: "Standard".to_string(),
+        category: CATEGORY.to_string(),
+        started_at: "2024-01-15T12:00:00Z".to_string(),
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        game_version: None,
+    })
+    .unwrap();
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+
+    let level_up = |character: &str, class: &str, level: u32| LogEvent::LevelUp {
+        timestamp: String::new(),
+        character_name: character.to_string(),
+        character_class: class.to_string(),
+        level,
+    };
+    let updated = breakpoint_engine::class_for(&level_up("TestChar", "Duelist", 2)).unwrap().unwrap();
+    assert_eq!((updated.class.as_str(), updated.ascendancy), ("Duelist", None));
+    assert!(breakpoint_engine::class_for(&level_up("TestChar", "Duelist", 3)).unwrap().is_none(), "unchanged");
+
+    // Once the frontend adopts the character, party members no longer count
+    Run::update_character(run_id, "TestChar", None).unwrap();
+    assert!(breakpoint_engine::class_for(&level_up("PartyMember", "Witch", 40)).unwrap().is_none());
+
+    // Ascended characters level up under their ascendancy
+    let updated = breakpoint_engine::class_for(&level_up("TestChar", "Slayer", 34)).unwrap().unwrap();
+    assert_eq!(updated.ascendancy.as_deref(), Some("Slayer"));
+    breakpoint_engine::stop();
+
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+    assert_eq!((run.character_name.as_str(), run.class.as_str()), ("TestChar", "Duelist"));
+    assert_eq!(run.ascendancy.as_deref(), Some("Slayer"));
+}
+
+#[test]
+fn test_import_run_from_log() {
+    let _db = setup_db();
+    let log = TempLog::new();
+    log.append(&[
+        zone_line(-600, "The Coast"),
+        zone_line(0, "The Twilight Strand"),
+        level_line(20, "RaceChar", "Witch", 2),
+        zone_line(60, "The Coast"),
+        zone_line(200, "The Mud Flats"),
+        zone_line(400, "The Submerged Passage"),
+    ]);
+    let breakpoint = |zone: &str| -> EngineBreakpoint {
+        serde_json::from_value(serde_json::json!({
+            "name": zone,
+            "type": "zone",
+            "trigger": { "type": "zone", "zoneName": zone, "act": 1 },
+            "captureSnapshot": false,
+        }))
+        .unwrap()
+    };
+    let preset = |names: &[&str]| LogImportPreset {
+        name: "custom".to_string(),
+        category: CATEGORY.to_string(),
+        breakpoints: names.iter().map(|name| breakpoint(name)).collect(),
+        preset_id: None,
+    };
+    let path = log.path().to_string_lossy().into_owned();
+    let import = |start: &str, end: &str, names: &[&str]| {
+        block_on(import_run_from_log(path.clone(), start.to_string(), end.to_string(), preset(names)))
+    };
+
+    let run_id = import("2024-01-15T12:00", "2024-01-15T13:00", &["The Coast", "The Mud Flats"]).unwrap();
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+    assert_eq!((run.character_name.as_str(), run.class.as_str()), ("RaceChar", "Witch"));
+    assert!(run.is_completed);
+    assert_eq!(run.total_time_ms, Some(200_000));
+    let splits: Vec<(String, i64)> =
+        Split::get_by_run(run_id).unwrap().into_iter().map(|s| (s.breakpoint_name, s.segment_time_ms)).collect();
+    assert_eq!(splits, [("The Coast".to_string(), 60_000), ("The Mud Flats".to_string(), 140_000)]);
+    assert_eq!(GoldSplit::get_best(CATEGORY, "Witch", "The Mud Flats", Some("zone:a1:the_mud_flats")).unwrap(), Some(140_000));
+
+    // Short of the last breakpoint, the run is kept as reset there
+    let run_id = import("2024-01-15 12:00:00", "2024-01-15 12:05:00", &["The Coast", "The Ledge"]).unwrap();
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+    assert!(!run.is_completed);
+    assert_eq!(run.reset_split.as_deref(), Some("The Coast"));
+
+    let err = import("2024-01-15T12:00", "2024-01-15T11:00", &["The Coast"]).unwrap_err();
+    assert_eq!(err.code(), "invalid_input");
+    let err = import("2024-01-15T12:00", "2024-01-15T13:00", &["The Ledge"]).unwrap_err();
+    assert_eq!(err.code(), "invalid_input", "nothing reached");
+}
+
+#[test]
+fn test_other_character_level_up_reported_once() {
+    let _db = setup_db();
+
+    let run_id = Run::insert(&NewRun {
+        character_name: "TestChar".to_string(),
+        account_name: "TestAccount".to_string(),
+        class: "Witch".to_string(),
+        ascendancy: None,
+        league: "Standard".to_string(),
+        category: CATEGORY.to_string(),
+        started_at: "2024-01-15T12:00:00Z".to_string(),
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        game_version: None,
+    })
+    .unwrap();
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+
+    let level_up = |character: &str, class: &str, level: u32| LogEvent::LevelUp {
+        timestamp: String::new(),
+        character_name: character.to_string(),
+        character_class: class.to_string(),
+        level,
+    };
+    assert!(breakpoint_engine::mismatch_for(&level_up("TestChar", "Witch", 5)).unwrap().is_none());
+    let mismatch = breakpoint_engine::mismatch_for(&level_up("AltChar", "Slayer", 12)).unwrap().unwrap();
+    assert_eq!((mismatch.run_character.as_str(), mismatch.character_name.as_str()), ("TestChar", "AltChar"));
+    assert!(breakpoint_engine::mismatch_for(&level_up("AltChar", "Slayer", 13)).unwrap().is_none(), "reported once");
+
+    assert!(breakpoint_engine::take_mismatch(run_id + 1).is_none(), "not the run being timed");
+    assert_eq!(breakpoint_engine::take_mismatch(run_id).unwrap().level, 12);
+    assert!(breakpoint_engine::take_mismatch(run_id).is_none());
+
+    // Only the run's character reaches level breakpoints
+    let level_12: EngineBreakpoint = serde_json::from_value(serde_json::json!({
+        "name": "Level 12",
+        "type": "level",
+        "trigger": { "type": "level", "level": 12 },
+        "captureSnapshot": false,
+    }))
+    .unwrap();
+    breakpoint_engine::start_run(run_id, vec![level_12], 0, None);
+    assert!(breakpoint_engine::split_for(&level_up("AltChar", "Slayer", 12)).unwrap().is_none());
+    let split = breakpoint_engine::split_for(&level_up("TestChar", "Witch", 12)).unwrap().unwrap();
+    assert_eq!(split.split.breakpoint_name, "Level 12");
+    breakpoint_engine::stop();
+}
+
+#[test]
+fn test_early_events_set_run_category() {
+    let _db = setup_db();
+    let new_run = || NewRun {
+        character_name: "TestChar".to_string(),
+        account_name: "TestAccount".to_string(),
+        class: "Witch".to_string(),
+        ascendancy: None,
+        league: "Standard".to_string(),
+        category: CATEGORY.to_string(),
+        started_at: "2024-01-15T12:00:00Z".to_string(),
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        game_version: None,
+    };
+    let map = LogEvent::AreaGenerated {
+        timestamp: String::new(),
+        area_level: 83,
+        area_id: "MapWorldsStrand".to_string(),
+        seed: 1,
+    };
+    let lab = LogEvent::LabStart { timestamp: String::new() };
+
+    // A campaign category left selected while mapping
+    let run_id = Run::insert(&new_run()).unwrap();
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+    let updated = breakpoint_engine::category_for(&map).unwrap().unwrap();
+    assert_eq!(updated.category, "Mapping");
+    // Only the first telling event counts
+    assert!(breakpoint_engine::category_for(&lab).unwrap().is_none());
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+    assert_eq!((run.category.as_str(), run.category_source.as_str()), ("Mapping", "detected"));
+
+    // A category set by hand is kept
+    let run_id = Run::insert(&new_run()).unwrap();
+    Run::set_category(run_id, "Lab farming", "manual").unwrap();
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+    assert!(breakpoint_engine::category_for(&lab).unwrap().is_none());
+    breakpoint_engine::stop();
+    assert_eq!(Run::get_by_id(run_id).unwrap().unwrap().category, "Lab farming");
+}
+
+#[test]
+fn test_run_timeline_merges_events_and_splits() {
+    let _db = setup_db();
+
+    let run_id = create_run();
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+
+    let zone = LogEvent::ZoneEnter { timestamp: String::new(), zone_name: "The Coast".to_string() };
+    breakpoint_engine::record_event(&zone).unwrap();
+    record_split(&NewSplit {
+        run_id,
+        breakpoint_type: "zone".to_string(),
+        breakpoint_name: "The Coast".to_string(),
+        breakpoint_key: None,
+        split_time_ms: 5_000,
+        delta_ms: None,
+        segment_time_ms: 5_000,
+        town_time_ms: 0,
+        hideout_time_ms: 0,
+    })
+    .unwrap();
+    let level_up = |character: &str| LogEvent::LevelUp {
+        timestamp: String::new(),
+        character_name: character.to_string(),
+        character_class: "Witch".to_string(),
+        level: 2,
+    };
+    breakpoint_engine::record_event(&level_up("PartyMember")).unwrap();
+    breakpoint_engine::record_event(&level_up("TestChar")).unwrap();
+    breakpoint_engine::set_running(false, 60_000).unwrap();
+    breakpoint_engine::set_running(false, 60_000).unwrap();
+    breakpoint_engine::set_running(true, 60_000).unwrap();
+    breakpoint_engine::stop();
+
+    let timeline = block_on(get_run_timeline(run_id)).unwrap();
+    let kinds: Vec<&str> = timeline.iter().map(|e| e.kind.as_str()).collect();
+    assert_eq!(kinds, vec!["zone_enter", "level_up", "split", "pause", "resume"]);
+    assert_eq!(timeline[0].label, "The Coast");
+    assert_eq!(timeline[1].label, "Level 2");
+    assert!(timeline[2].split_id.is_some());
+    assert_eq!(timeline[3].elapsed_ms, 60_000);
+    assert_eq!(block_on(get_run_timeline(run_id + 1)).unwrap_err().code(), "not_found");
+}
+
+#[test]
+fn test_zone_times_from_zone_entries() {
+    let _db = setup_db();
+
+    let run_id = create_run();
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+    let entries = [(0, "The Twilight Strand"), (40_000, "Lioneye's Watch"), (50_000, "The Coast")];
+    for (elapsed_ms, zone_name) in entries {
+        breakpoint_engine::set_running(false, elapsed_ms).unwrap();
+        let zone = LogEvent::ZoneEnter { timestamp: String::new(), zone_name: zone_name.to_string() };
+        breakpoint_engine::record_event(&zone).unwrap();
+    }
+    record_split(&NewSplit {
+        run_id,
+        breakpoint_type: "zone".to_string(),
+        breakpoint_name: "Lioneye's Watch".to_string(),
+        breakpoint_key: None,
+        split_time_ms: 45_000,
+        delta_ms: None,
+        segment_time_ms: 45_000,
+        town_time_ms: 5_000,
+        hideout_time_ms: 0,
+    })
+    .unwrap();
+
+    // The visit in progress has no duration until the run ends
+    let live = block_on(get_zone_times(run_id)).unwrap();
+    assert_eq!(live.visits.last().unwrap().duration_ms, None);
+    breakpoint_engine::stop();
+    finish_run(run_id, 70_000).unwrap();
+
+    let report = block_on(get_zone_times(run_id)).unwrap();
+    let visits: Vec<(&str, Option<i64>, bool)> = report
+        .visits
+        .iter()
+        .map(|v| (v.zone_name.as_str(), v.duration_ms, v.is_town))
+        .collect();
+    assert_eq!(
+        visits,
+        vec![
+            ("The Twilight Strand", Some(40_000), false),
+            ("Lioneye's Watch", Some(10_000), true),
+            ("The Coast", Some(20_000), false),
+        ]
+    );
+    assert_eq!(report.segments.len(), 2);
+    assert_eq!(report.segments[0].zones[1].time_ms, 5_000);
+    assert_eq!(report.segments[1].breakpoint_name, None);
+    assert_eq!(block_on(get_zone_times(run_id + 1)).unwrap_err().code(), "not_found");
+
+    let stats = block_on(get_zone_time_stats(RunFilters::default())).unwrap();
+    let zones: Vec<(&str, i64)> = stats.iter().map(|s| (s.zone_name.as_str(), s.average_time_ms)).collect();
+    assert_eq!(zones, vec![("The Twilight Strand", 40_000), ("The Coast", 20_000), ("Lioneye's Watch", 10_000)]);
+
+    // Deaths count towards their zone, even one the run never finished a visit to
+    Death::insert(run_id, "2024/01/15 12:01:00", Some("The Coast"), 60_000, Some(3)).unwrap();
+    Death::insert(run_id, "2024/01/15 12:01:05", Some("The Coast"), 65_000, Some(3)).unwrap();
+    Death::insert(run_id, "2024/01/15 12:01:08", Some("The Tidal Island"), 68_000, Some(3)).unwrap();
+    Death::insert(run_id, "2024/01/15 12:01:09", None, 69_000, Some(3)).unwrap();
+    let heatmap = block_on(get_zone_heatmap(RunFilters::default())).unwrap();
+    let cells: Vec<(&str, i64, i64, i64, i64)> = heatmap
+        .iter()
+        .map(|z| (z.zone_name.as_str(), z.run_count, z.total_time_ms, z.average_time_ms, z.deaths))
+        .collect();
+    assert_eq!(
+        cells,
+        vec![
+            ("The Twilight Strand", 1, 40_000, 40_000, 0),
+            ("The Coast", 1, 20_000, 20_000, 2),
+            ("Lioneye's Watch", 1, 10_000, 10_000, 0),
+            ("The Tidal Island", 1, 0, 0, 1),
+        ]
+    );
+    assert!(heatmap[2].is_town);
+}
+
+#[test]
+fn test_deaths_recorded_for_run_character() {
+    let _db = setup_db();
+
+    let run_id = create_run();
+    let other_run = create_run();
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+    breakpoint_engine::set_running(false, 30_000).unwrap();
+    let events = [
+        LogEvent::ZoneEnter { timestamp: String::new(), zone_name: "The Coast".to_string() },
+        LogEvent::LevelUp {
+            timestamp: String::new(),
+            character_name: "TestChar".to_string(),
+            character_class: "Witch".to_string(),
+            level: 5,
+        },
+    ];
+    for event in &events {
+        breakpoint_engine::record_event(event).unwrap();
+    }
+    let death = |character: &str| LogEvent::Death {
+        timestamp: "2024/01/15 12:00:30".to_string(),
+        character_name: character.to_string(),
+    };
+
+    assert!(breakpoint_engine::death_for(&death("PartyMember")).unwrap().is_none());
+    assert!(breakpoint_engine::death_for(&events[0]).unwrap().is_none());
+    let recorded = breakpoint_engine::death_for(&death("TestChar")).unwrap().unwrap();
+    assert_eq!(recorded.zone_name.as_deref(), Some("The Coast"));
+    assert_eq!((recorded.character_level, recorded.elapsed_ms, recorded.deaths), (Some(5), 30_000, 1));
+    breakpoint_engine::stop();
+    assert!(breakpoint_engine::death_for(&death("TestChar")).unwrap().is_none());
+
+    let deaths = block_on(get_deaths(run_id)).unwrap();
+    assert_eq!(deaths.len(), 1);
+    assert_eq!(deaths[0].timestamp, "2024/01/15 12:00:30");
+    assert!(block_on(get_deaths(other_run)).unwrap().is_empty());
+    assert_eq!(block_on(get_deaths(other_run + 1)).unwrap_err().code(), "not_found");
+
+    let stats = Run::get_stats(&RunFilters::default()).unwrap();
+    assert_eq!((stats.total_deaths, stats.deathless_runs), (1, 1));
+}
+
+#[test]
+fn test_level_milestones_queue_snapshots() {
+    let _db = setup_db();
+    let run_id = create_run();
+    let level_up = |character: &str, level: u32| LogEvent::LevelUp {
+        timestamp: String::new(),
+        character_name: character.to_string(),
+        character_class: "Witch".to_string(),
+        level,
+    };
+    breakpoint_engine::start_run(run_id, Vec::new(), 0, None);
+    breakpoint_engine::set_running(true, 45_000).unwrap();
+
+    // Off by default
+    assert!(breakpoint_engine::level_snapshot_for(&level_up("TestChar", 10)).unwrap().is_none());
+
+    let settings = Settings { account_name: "TestAccount".to_string(), snapshot_level_interval: 5, ..Settings::default() };
+    Settings::save(&settings).unwrap();
+    assert!(breakpoint_engine::level_snapshot_for(&level_up("TestChar", 9)).unwrap().is_none());
+    assert!(breakpoint_engine::level_snapshot_for(&level_up("PartyMember", 10)).unwrap().is_none());
+    let (request, level) = breakpoint_engine::level_snapshot_for(&level_up("TestChar", 10)).unwrap().unwrap();
+    assert_eq!(level, 10);
+    assert_eq!((request.run_id, request.split_id, request.is_final), (run_id, None, false));
+    assert_eq!((request.account_name.as_str(), request.character_name.as_str()), ("TestAccount", "TestChar"));
+    assert!(request.elapsed_time_ms >= 45_000);
+
+    // Not while paused, nor without an account to fetch the character from
+    breakpoint_engine::set_running(false, 50_000).unwrap();
+    assert!(breakpoint_engine::level_snapshot_for(&level_up("TestChar", 15)).unwrap().is_none());
+    breakpoint_engine::set_running(true, 50_000).unwrap();
+    Settings::save(&Settings { account_name: String::new(), ..settings }).unwrap();
+    assert!(breakpoint_engine::level_snapshot_for(&level_up("TestChar", 15)).unwrap().is_none());
+    breakpoint_engine::stop();
+}
@@ -0,0 +1,1570 @@
+use super::support::{block_on, setup_db, TempLog};
+use crate::abandon::AbandonReason;
+use crate::certificate::CertificateStatus;
+use crate::auto_export::{self, ExportFormat};
+use crate::{auto_start, breakpoint_engine};
+use crate::commands::{
+    abandon_run, add_split_note, compare_split, create_reference_run, create_run as create_run_cmd, get_comparison, delete_category_defaults, delete_split_note,
+    export_run_json, export_run_livesplit, get_run_certificate, verify_run_certificate, export_runs_csv, export_splits_csv, finish_run, import_run_json, get_backtrack_report, get_map_sessions, start_map_session, stop_map_session, import_livesplit, set_category_defaults,
+    get_abandonment_stats, get_reset_stats, get_personal_bests, get_quest_state, get_run_review, get_split_notes, get_time_loss_report,
+    get_poe_auth_status, get_town_time_report, poe_logout, record_split, repair_database, set_run_review,
+    create_overlay_profile, get_overlay_profiles, overlay_state_for, update_overlay_profile, get_level_pace,
+    get_overlay_layout, validate_overlay_layout,
+    add_breakpoint, create_breakpoint_preset, delete_breakpoint_preset, get_breakpoint_presets, get_preset_breakpoints,
+    rename_breakpoint_preset, reorder_breakpoints, update_breakpoint,
+    archive_runs_older_than, get_archived_runs, get_run_stats, get_runs_paged, restore_archived_run, get_personal_best,
+    recompute_personal_bests, recompute_gold_splits, remove_run,
+    add_run_tag, get_run_tag_names, get_run_tags, get_runs_by_tag, remove_run_tag, set_run_note,
+};
+use crate::db::{
+    get_db, ActTownTime, ArchiveReport, CategoryDefaults, NewPresetBreakpoint, GoldSplit, LadderObservation, NewRun, NewSplit, NewSplitNote, OAuthToken, PersonalBest,
+    NewSnapshot, RepairReport, OverlayComponents, OverlayLayout, OverlayProfile, ReferenceRunData, ReferenceSplitData, Run, RunEvent, RunFilters, RunReview, Settings, Snapshot, Split,
+};
+use crate::comparison::{ComparisonKind, SplitColor};
+use crate::api_client::LadderEntry;
+use crate::ladder;
+use crate::livesplit;
+use crate::log_watcher::LogEvent;
+use crate::quest_state;
+use crate::race_lobby::{self, RaceEvent};
+use crate::run_json;
+use crate::webhook::{self, WebhookPayload};
+use crate::readiness::{self, Checklist, ReadinessMode};
+
+fn new_run(started_at: &str) -> NewRun {
+    NewRun {
+        character_name: "TestChar".to_string(),
+        account_name: "TestAccount".to_string(),
+        class: "Witch".to_string(),
+        ascendancy: None,
+        league: "Standard".to_string(),
+        category: "Act 10 Any%".to_string(),
+        started_at: started_at.to_string(),
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        game_version: None,
+    }
+}
+
+fn create_run() -> i64 {
+    Run::insert(&new_run("2024-01-15T12:00:00Z")).unwrap()
+}
+
+#[test]
+fn test_run_review_round_trip() {
+    let _db = setup_db();
+    let run_id = create_run();
+
+    block_on(set_run_review(
+        run_id,
+        RunReview {
+            video_url: Some(" https://www.twitch.tv/videos/123 ".to_string()),
+            vod_offset_ms: Some(95_000),
+            review_notes: Some("   ".to_string()),
+        },
+    ))
+    .unwrap();
+
+    let review = block_on(get_run_review(run_id)).unwrap();
+    assert_eq!(review.video_url.as_deref(), Some("https://www.twitch.tv/videos/123"));
+    assert_eq!(review.vod_offset_ms, Some(95_000));
+    assert_eq!(review.review_notes, None);
+}
+
+#[test]
+fn test_run_review_rejects_bad_input() {
+    let _db = setup_db();
+    let run_id = create_run();
+
+    let bad_url = RunReview {
+        video_url: Some("twitch.tv/videos/123".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(block_on(set_run_review(run_id, bad_url)).unwrap_err().code(), "invalid_input");
+
+    let negative = RunReview {
+        vod_offset_ms: Some(-1),
+        ..Default::default()
+    };
+    assert_eq!(block_on(set_run_review(run_id, negative)).unwrap_err().code(), "invalid_input");
+
+    let missing = block_on(set_run_review(run_id + 1, RunReview::default())).unwrap_err();
+    assert_eq!(missing.code(), "not_found");
+}
+
+/// Record a run with one split per `(name, segment_ms)` and return the split ids
+fn record_segments(run_id: i64, segments: &[(&str, i64)]) -> Vec<i64> {
+    let mut elapsed = 0;
+    segments
+        .iter()
+        .map(|(name, segment)| {
+            elapsed += segment;
+            record_split(&NewSplit {
+                run_id,
+                breakpoint_type: "zone".to_string(),
+                breakpoint_name: name.to_string(),
+                breakpoint_key: None,
+                split_time_ms: elapsed,
+                delta_ms: None,
+                segment_time_ms: *segment,
+                town_time_ms: 0,
+                hideout_time_ms: 0,
+            })
+            .unwrap()
+            .0
+        })
+        .collect()
+}
+
+fn tag(split_id: i64, mistake_type: &str) -> i64 {
+    block_on(add_split_note(NewSplitNote {
+        split_id,
+        note: String::new(),
+        mistake_type: Some(mistake_type.to_string()),
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_time_loss_by_mistake_type() {
+    let _db = setup_db();
+    let segments = ["The Coast", "The Mud Flats", "The Ledge"];
+
+    let fast = create_run();
+    record_segments(fast, &segments.map(|s| (s, 60_000)));
+
+    let slow = create_run();
+    let ids = record_segments(slow, &[(segments[0], 90_000), (segments[1], 80_000), (segments[2], 65_000)]);
+    tag(ids[0], "Death");
+    tag(ids[1], "bad layout");
+    tag(ids[1], "death");
+
+    let notes = block_on(get_split_notes(slow)).unwrap();
+    assert_eq!(notes.len(), 3);
+    assert_eq!(notes[1].mistake_type.as_deref(), Some("bad_layout"));
+
+    let report = block_on(get_time_loss_report(Default::default())).unwrap();
+    assert_eq!(report.total_loss_ms, 55_000);
+    assert_eq!(report.untagged_loss_ms, 5_000);
+    let losses: Vec<(&str, i64, i64)> = report
+        .by_mistake_type
+        .iter()
+        .map(|m| (m.mistake_type.as_str(), m.loss_ms, m.occurrences))
+        .collect();
+    assert_eq!(losses, vec![("death", 40_000, 2), ("bad_layout", 10_000, 1)]);
+
+    // Removing a tag moves its share back out of the breakdown
+    block_on(delete_split_note(notes[0].id)).unwrap();
+    let report = block_on(get_time_loss_report(Default::default())).unwrap();
+    assert_eq!(report.by_mistake_type[0].loss_ms, 10_000);
+}
+
+#[test]
+fn test_split_note_requires_content() {
+    let _db = setup_db();
+    let run_id = create_run();
+    let ids = record_segments(run_id, &[("The Coast", 60_000)]);
+
+    let empty = NewSplitNote {
+        split_id: ids[0],
+        note: "  ".to_string(),
+        mistake_type: Some(" ".to_string()),
+    };
+    assert_eq!(block_on(add_split_note(empty)).unwrap_err().code(), "invalid_input");
+    assert_eq!(block_on(delete_split_note(999)).unwrap_err().code(), "not_found");
+}
+
+#[test]
+fn test_first_run_of_session_is_warmup() {
+    let _db = setup_db();
+
+    let starts = [
+        "2024-01-15T12:00:00Z",
+        "2024-01-15T12:45:00Z",
+        "2024-01-15T16:00:00Z",
+        "2024-01-15T16:30:00.500Z",
+    ];
+    let ids: Vec<i64> = starts
+        .iter()
+        .map(|s| block_on(create_run_cmd(new_run(s))).unwrap())
+        .collect();
+
+    let warmups: Vec<bool> = ids
+        .iter()
+        .map(|id| Run::get_by_id(*id).unwrap().unwrap().is_warmup)
+        .collect();
+    assert_eq!(warmups, vec![true, false, true, false]);
+
+    let filters = RunFilters {
+        exclude_warmups: Some(true),
+        ..Default::default()
+    };
+    let kept: Vec<i64> = Run::get_filtered(&filters).unwrap().iter().map(|r| r.id).collect();
+    assert_eq!(kept, vec![ids[3], ids[1]]);
+}
+
+#[test]
+fn test_warmup_detection_can_be_disabled() {
+    let _db = setup_db();
+    Settings::save(&Settings {
+        warmup_detection_enabled: false,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let run_id = block_on(create_run_cmd(new_run("2024-01-15T12:00:00Z"))).unwrap();
+    assert!(!Run::get_by_id(run_id).unwrap().unwrap().is_warmup);
+}
+
+#[test]
+fn test_runs_filtered_by_ruleset_and_game_version() {
+    let _db = setup_db();
+    let mut runs = Vec::new();
+    for (league, game_version) in [("Standard", "3.25"), ("SSF Settlers HC", "3.25"), ("Hardcore Settlers", "3.26"), ("", "3.26")] {
+        let run_id = block_on(create_run_cmd(NewRun {
+            league: league.to_string(),
+            game_version: Some(game_version.to_string()),
+            ..new_run("2024-01-15T12:00:00Z")
+        }))
+        .unwrap();
+        finish_run(run_id, 3_600_000).unwrap();
+        runs.push(run_id);
+    }
+    let rulesets: Vec<Option<String>> = runs.iter().map(|id| Run::get_by_id(*id).unwrap().unwrap().ruleset).collect();
+    assert_eq!(rulesets, vec![Some("SC".to_string()), Some("HCSSF".to_string()), Some("HC".to_string()), None]);
+
+    let hardcore = RunFilters { ruleset: Some("HC".to_string()), ..RunFilters::default() };
+    let page = block_on(get_runs_paged(0, 10, hardcore.clone())).unwrap();
+    assert_eq!(page.runs.iter().map(|r| r.id).collect::<Vec<_>>(), vec![runs[2]]);
+    assert_eq!(block_on(get_run_stats(hardcore)).unwrap().completed_runs, 1);
+
+    let patch = RunFilters { game_version: Some("3.25".to_string()), ..RunFilters::default() };
+    assert_eq!(block_on(get_run_stats(patch)).unwrap().total_runs, 2);
+}
+
+#[test]
+fn test_abandonment_reasons_tallied() {
+    let _db = setup_db();
+    let abandoned = [
+        (AbandonReason::Death, 600_000),
+        (AbandonReason::BadLayout, 120_000),
+        (AbandonReason::Death, 1_200_000),
+    ];
+    for (index, (reason, at_ms)) in abandoned.iter().enumerate() {
+        let run_id = Run::insert(&new_run(&format!("2024-01-15T1{}:00:00Z", index))).unwrap();
+        block_on(abandon_run(run_id, Some(*reason), *at_ms)).unwrap();
+    }
+    let finished = Run::insert(&new_run("2024-01-15T14:00:00Z")).unwrap();
+    finish_run(finished, 3_600_000).unwrap();
+    Run::insert(&new_run("2024-01-15T15:00:00Z")).unwrap();
+
+    let err = block_on(abandon_run(finished, Some(AbandonReason::Irl), 0)).unwrap_err();
+    assert_eq!(err.code(), "invalid_input");
+    assert_eq!(block_on(abandon_run(999, Some(AbandonReason::Irl), 0)).unwrap_err().code(), "not_found");
+
+    let stats = block_on(get_abandonment_stats(RunFilters::default())).unwrap();
+    assert_eq!((stats.total_runs, stats.completed_runs), (5, 1));
+    assert_eq!((stats.abandoned_runs, stats.unexplained_runs), (3, 1));
+    let reasons: Vec<(AbandonReason, i64, Option<i64>)> = stats
+        .by_reason
+        .iter()
+        .map(|r| (r.reason, r.count, r.average_abandoned_at_ms))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![(AbandonReason::Death, 2, Some(900_000)), (AbandonReason::BadLayout, 1, Some(120_000))]
+    );
+    assert!((stats.by_reason[0].share - 2.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_resets_counted_per_split_and_act() {
+    let _db = setup_db();
+    let lioneye = ("Lioneye's Watch", "zone:a1:lioneyes_watch");
+    // Two resets after The Southern Forest (one preset names it differently),
+    // one after Lioneye's Watch, one before the first split
+    let resets: [&[(&str, &str)]; 4] = [
+        &[lioneye, ("Southern Forest", "zone:a2:the_southern_forest")],
+        &[lioneye, ("The Southern Forest", "zone:a2:the_southern_forest")],
+        &[lioneye],
+        &[],
+    ];
+    let mut run_ids = Vec::new();
+    for (index, splits) in resets.iter().enumerate() {
+        let run_id = Run::insert(&new_run(&format!("2024-01-15T1{}:00:00Z", index))).unwrap();
+        for (position, (name, key)) in splits.iter().enumerate() {
+            record_split(&NewSplit {
+                run_id,
+                breakpoint_type: "zone".to_string(),
+                breakpoint_name: name.to_string(),
+                breakpoint_key: Some(key.to_string()),
+                split_time_ms: (position as i64 + 1) * 60_000,
+                delta_ms: None,
+                segment_time_ms: 60_000,
+                town_time_ms: 0,
+                hideout_time_ms: 0,
+            })
+            .unwrap();
+        }
+        let reason = (index == 0).then_some(AbandonReason::Death);
+        block_on(abandon_run(run_id, reason, 300_000)).unwrap();
+        run_ids.push(run_id);
+    }
+    let finished = Run::insert(&new_run("2024-01-15T15:00:00Z")).unwrap();
+    finish_run(finished, 3_600_000).unwrap();
+
+    let run = Run::get_by_id(run_ids[0]).unwrap().unwrap();
+    assert_eq!(run.reset_split.as_deref(), Some("Southern Forest"));
+    assert_eq!(run.reset_split_key.as_deref(), Some("zone:a2:the_southern_forest"));
+    // Reset without picking a reason
+    let run = Run::get_by_id(run_ids[2]).unwrap().unwrap();
+    assert_eq!((run.abandon_reason, run.reset_split.as_deref()), (None, Some("Lioneye's Watch")));
+
+    let stats = block_on(get_reset_stats(RunFilters::default())).unwrap();
+    assert_eq!((stats.attempts, stats.resets), (5, 4));
+    let by_split: Vec<(Option<&str>, Option<i32>, i64)> = stats
+        .by_split
+        .iter()
+        .map(|s| (s.breakpoint_name.as_deref(), s.act, s.count))
+        .collect();
+    assert_eq!(
+        by_split,
+        vec![(Some("The Southern Forest"), Some(2), 2), (None, Some(1), 1), (Some("Lioneye's Watch"), Some(1), 1)]
+    );
+    let by_act: Vec<(i32, i64)> = stats.by_act.iter().map(|a| (a.act, a.count)).collect();
+    assert_eq!(by_act, vec![(1, 2), (2, 2)]);
+    assert!((stats.by_act[0].share - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_category_defaults_applied_on_run_start() {
+    let _db = setup_db();
+    let layout = OverlayLayout {
+        scale: "large".to_string(),
+        font_size: "small".to_string(),
+        show_timer: true,
+        show_zone: false,
+        show_last_split: true,
+        show_breakpoints: true,
+        breakpoint_count: 5,
+    };
+    block_on(set_category_defaults(CategoryDefaults {
+        category: "Act 10 Any%".to_string(),
+        breakpoint_preset: Some("any-percent".to_string()),
+        enabled_breakpoints: Some(r#"["Kill Brutus","Kill Kitava"]"#.to_string()),
+        snapshot_breakpoints: None,
+        separate_pbs_by_patch: Some(true),
+        overlay_layout: Some(layout.clone()),
+    }))
+    .unwrap();
+
+    let mut other = new_run("2024-01-15T12:00:00Z");
+    other.category = "Act 5".to_string();
+    let other_id = block_on(create_run_cmd(other)).unwrap();
+    assert_eq!(Run::get_by_id(other_id).unwrap().unwrap().breakpoint_preset, None);
+    assert!(!Settings::load().unwrap().separate_pbs_by_patch);
+
+    let run_id = block_on(create_run_cmd(new_run("2024-01-15T13:00:00Z"))).unwrap();
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+    assert_eq!(run.breakpoint_preset.as_deref(), Some("any-percent"));
+    assert_eq!(run.enabled_breakpoints.as_deref(), Some(r#"["Kill Brutus","Kill Kitava"]"#));
+    let settings = Settings::load().unwrap();
+    assert!(settings.separate_pbs_by_patch);
+    assert_eq!(settings.overlay_scale, "large");
+    assert_eq!(settings.overlay_breakpoint_count, 5);
+    assert_eq!(CategoryDefaults::get("Act 10 Any%").unwrap().unwrap().overlay_layout, Some(layout));
+
+    block_on(delete_category_defaults("Act 10 Any%".to_string())).unwrap();
+    let missing = block_on(delete_category_defaults("Act 10 Any%".to_string())).unwrap_err();
+    assert_eq!(missing.code(), "not_found");
+}
+
+#[test]
+fn test_overlay_profiles() {
+    let _db = setup_db();
+    let compact = block_on(create_overlay_profile("Compact Timer".to_string())).unwrap();
+    assert_eq!(compact.id, "compact-timer");
+    let second = block_on(create_overlay_profile(" Compact timer! ".to_string())).unwrap();
+    assert_eq!((second.id.as_str(), second.name.as_str()), ("compact-timer-2", "Compact timer!"));
+    assert_eq!(block_on(create_overlay_profile("2nd monitor: splits".to_string())).unwrap().id, "2nd-monitor-splits");
+    assert_eq!(block_on(create_overlay_profile("!!".to_string())).unwrap().id, "overlay");
+    assert_eq!(block_on(create_overlay_profile("  ".to_string())).unwrap_err().code(), "invalid_input");
+
+    OverlayProfile::save_position("compact-timer", 2000, 40).unwrap();
+    OverlayProfile::save_size("compact-timer", 200.0, 90.0).unwrap();
+    let profile = OverlayProfile { opacity: Some(0.5), ..compact };
+    block_on(update_overlay_profile(profile.clone())).unwrap();
+    let saved = OverlayProfile::get("compact-timer").unwrap().unwrap();
+    assert_eq!((saved.x, saved.y, saved.width, saved.height, saved.opacity), (Some(2000), Some(40), Some(200.0), Some(90.0), Some(0.5)));
+
+    let too_faint = OverlayProfile { opacity: Some(0.0), ..profile.clone() };
+    assert_eq!(block_on(update_overlay_profile(too_faint)).unwrap_err().code(), "invalid_input");
+    let missing = OverlayProfile { id: "gone".to_string(), ..profile };
+    assert_eq!(block_on(update_overlay_profile(missing)).unwrap_err().code(), "not_found");
+
+    // Each window gets the shared state with its own opacity
+    let state = serde_json::json!({ "elapsedMs": 1000, "opacity": 0.8 });
+    assert_eq!(overlay_state_for(&state, Some(&saved))["opacity"], 0.5);
+    assert_eq!(overlay_state_for(&state, Some(&second))["opacity"], 0.8);
+    assert_eq!(overlay_state_for(&state, None), state);
+
+    assert_eq!(OverlayProfile::delete("compact-timer-2").unwrap(), 1);
+    let ids: Vec<String> = block_on(get_overlay_profiles()).unwrap().into_iter().map(|p| p.id).collect();
+    assert_eq!(ids, vec!["compact-timer", "2nd-monitor-splits", "overlay"]);
+}
+
+#[test]
+fn test_overlay_layout() {
+    let _db = setup_db();
+    assert_eq!(block_on(get_overlay_layout()).unwrap(), OverlayComponents::default());
+
+    let compact = OverlayComponents { next_split: false, level_pace: false, ..OverlayComponents::default() };
+    validate_overlay_layout(&compact).unwrap();
+    OverlayComponents::save(&compact).unwrap();
+    Settings::save(&Settings::default()).unwrap();
+    assert_eq!(block_on(get_overlay_layout()).unwrap(), compact);
+
+    let nothing = OverlayComponents {
+        timer: false,
+        current_split: false,
+        delta: false,
+        next_split: false,
+        death_count: false,
+        level_pace: false,
+    };
+    assert_eq!(validate_overlay_layout(&nothing).unwrap_err().code(), "invalid_input");
+    let orphan_delta = OverlayComponents { current_split: false, ..OverlayComponents::default() };
+    assert_eq!(validate_overlay_layout(&orphan_delta).unwrap_err().code(), "invalid_input");
+
+    // Layouts are typed: unknown components are refused
+    assert!(serde_json::from_str::<OverlayComponents>(r#"{"timer":true,"currentSplit":true,"delta":true,"nextSplit":true,"deathCount":true,"levelPace":true,"clock":true}"#).is_err());
+}
+
+#[test]
+fn test_breakpoint_presets() {
+    let _db = setup_db();
+    let preset = block_on(create_breakpoint_preset("Act 1 quests".to_string(), None)).unwrap();
+    assert_eq!((preset.game.as_str(), preset.breakpoint_count), ("poe1", 0));
+    let taken = block_on(create_breakpoint_preset(" Act 1 quests ".to_string(), None)).unwrap_err();
+    assert_eq!(taken.code(), "invalid_input");
+
+    let zone = |name: &str, zone: &str| NewPresetBreakpoint {
+        name: name.to_string(),
+        breakpoint_type: "zone".to_string(),
+        trigger_type: "zone".to_string(),
+        zone_name: Some(zone.to_string()),
+        act: Some(1),
+        level: None,
+        quest: None,
+        penalty: None,
+        entry: None,
+        after_quest: None,
+        capture_snapshot: false,
+    };
+    let add = |bp: NewPresetBreakpoint| block_on(add_breakpoint(preset.id, bp));
+    let coast = add(zone("Coast", "The Coast")).unwrap();
+    let dweller = add(NewPresetBreakpoint {
+        trigger_type: "quest".to_string(),
+        quest: Some("dweller".to_string()),
+        // Fields the trigger doesn't use are dropped
+        zone_name: Some("The Flooded Depths".to_string()),
+        ..zone("Dweller", "")
+    })
+    .unwrap();
+    let level = add(NewPresetBreakpoint { trigger_type: "level".to_string(), level: Some(12), ..zone("Level 12", "") })
+        .unwrap();
+    let kitava = add(NewPresetBreakpoint {
+        breakpoint_type: "boss".to_string(),
+        trigger_type: "kitava".to_string(),
+        penalty: Some(60),
+        ..zone("Kitava (Act 10)", "")
+    })
+    .unwrap();
+    assert_eq!((coast.position, dweller.position, level.position, kitava.position), (0, 1, 2, 3));
+    assert_eq!(dweller.breakpoint.zone_name, None);
+    assert_eq!(kitava.breakpoint.act, Some(10));
+
+    // Triggers that can't fire, and names the engine couldn't tell apart
+    for bad in [
+        zone("No zone", " "),
+        NewPresetBreakpoint { trigger_type: "level".to_string(), ..zone("No level", "") },
+        NewPresetBreakpoint { trigger_type: "quest".to_string(), quest: Some("nope".to_string()), ..zone("Bad quest", "") },
+        NewPresetBreakpoint { trigger_type: "kitava".to_string(), ..zone("Kitava", "The Coast") },
+        NewPresetBreakpoint { trigger_type: "kitava".to_string(), penalty: Some(45), ..zone("Kitava 45", "") },
+        NewPresetBreakpoint { entry: Some(0), ..zone("Entry 0", "The Coast") },
+        zone("coast", "The Mud Flats"),
+    ] {
+        assert_eq!(add(bad).unwrap_err().code(), "invalid_input");
+    }
+    block_on(update_breakpoint(preset.id, coast.id, zone("The Coast", "The Coast"))).unwrap();
+
+    block_on(reorder_breakpoints(preset.id, vec![level.id, coast.id, dweller.id, kitava.id])).unwrap();
+    let wrong = block_on(reorder_breakpoints(preset.id, vec![level.id, coast.id])).unwrap_err();
+    assert_eq!(wrong.code(), "invalid_input");
+    let breakpoints = block_on(get_preset_breakpoints(preset.id)).unwrap();
+    let names: Vec<_> = breakpoints.iter().map(|bp| bp.breakpoint.name.as_str()).collect();
+    assert_eq!(names, vec!["Level 12", "The Coast", "Dweller", "Kitava (Act 10)"]);
+
+    // What the engine runs for the preset
+    let engine: Vec<breakpoint_engine::EngineBreakpoint> = breakpoints.iter().map(Into::into).collect();
+    let keys: Vec<_> = engine.iter().map(|bp| bp.key.as_deref()).collect();
+    assert_eq!(keys, vec![Some("level:12"), Some("zone:a1:the_coast"), Some("quest:dweller"), Some("kitava:a10")]);
+    assert_eq!(engine[3].trigger.penalty, Some(60));
+
+    block_on(rename_breakpoint_preset(preset.id, "Dweller%".to_string())).unwrap();
+    let presets = block_on(get_breakpoint_presets()).unwrap();
+    assert_eq!((presets[0].name.as_str(), presets[0].breakpoint_count), ("Dweller%", 4));
+    block_on(delete_breakpoint_preset(preset.id)).unwrap();
+    assert_eq!(block_on(get_preset_breakpoints(preset.id)).unwrap_err().code(), "not_found");
+    let orphans: i64 = get_db().unwrap().query_row("SELECT COUNT(*) FROM breakpoints", [], |row| row.get(0)).unwrap();
+    assert_eq!(orphans, 0);
+}
+
+#[test]
+fn test_start_offset_recorded_and_countdown_splits_clamped() {
+    let _db = setup_db();
+    Settings::save(&Settings {
+        timer_start_offset_ms: 10_000,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let run_id = block_on(create_run_cmd(new_run("2024-01-15T12:00:00Z"))).unwrap();
+    assert_eq!(Run::get_by_id(run_id).unwrap().unwrap().start_offset_ms, 10_000);
+
+    // A split while the timer still shows -3s is stored at zero and sets no gold
+    let (split_id, _, is_gold) = record_split(&NewSplit {
+        run_id,
+        breakpoint_type: "zone".to_string(),
+        breakpoint_name: "The Twilight Strand".to_string(),
+        breakpoint_key: None,
+        split_time_ms: -3_000,
+        delta_ms: None,
+        segment_time_ms: -3_000,
+        town_time_ms: 0,
+        hideout_time_ms: 0,
+    })
+    .unwrap();
+    assert!(!is_gold);
+    let split = Split::get_by_run(run_id).unwrap().into_iter().find(|s| s.id == split_id).unwrap();
+    assert_eq!((split.split_time_ms, split.segment_time_ms), (0, 0));
+    assert_eq!(GoldSplit::get_best("Act 10 Any%", "Witch", "The Twilight Strand", None).unwrap(), None);
+}
+
+#[test]
+fn test_split_level_and_pace_against_pb() {
+    let _db = setup_db();
+    let pb = create_run();
+    RunEvent::insert(pb, "level_up", 30_000, Some("2")).unwrap();
+    RunEvent::insert(pb, "level_up", 100_000, Some("4")).unwrap();
+    record_segments(pb, &[("The Coast", 60_000), ("The Mud Flats", 90_000)]);
+    finish_run(pb, 150_000).unwrap();
+
+    let run_id = create_run();
+    RunEvent::insert(run_id, "level_up", 20_000, Some("2")).unwrap();
+    RunEvent::insert(run_id, "level_up", 50_000, Some("3")).unwrap();
+    let splits = record_segments(run_id, &[("The Coast", 55_000)]);
+    assert_eq!(Split::get_last(run_id).unwrap().unwrap().character_level, Some(3));
+    // A snapshot's level replaces the log's
+    Split::set_character_level(splits[0], 4).unwrap();
+    RunEvent::insert(run_id, "level_up", 120_000, Some("5")).unwrap();
+
+    // Not live, so compared at its last event
+    let pace = block_on(get_level_pace(run_id)).unwrap();
+    assert_eq!((pace.elapsed_ms, pace.level, pace.pb_run_id, pace.pb_level), (120_000, Some(5), Some(pb), Some(4)));
+    let levels: Vec<_> = pace
+        .splits
+        .iter()
+        .map(|s| (s.breakpoint_name.as_str(), s.level, s.pb_level, s.expected_level))
+        .collect();
+    assert_eq!(levels, vec![("The Coast", Some(4), Some(2), None), ("The Mud Flats", None, Some(4), Some(5))]);
+
+    assert_eq!(block_on(get_level_pace(run_id + 1)).unwrap_err().code(), "not_found");
+}
+
+#[test]
+fn test_repair_database_fixes_dangling_rows() {
+    let _db = setup_db();
+
+    // Fast run holds the PB and the golds, slow run is what should remain
+    let fast = create_run();
+    let fast_splits = record_segments(fast, &[("The Coast", 60_000), ("The Mud Flats", 90_000)]);
+    finish_run(fast, 150_000).unwrap();
+    tag(fast_splits[0], "death");
+    let slow = create_run();
+    record_segments(slow, &[("The Coast", 70_000)]);
+    finish_run(slow, 200_000).unwrap();
+
+    let stuck = create_run();
+    {
+        let conn = get_db().unwrap();
+        conn.execute("UPDATE runs SET is_completed = 1 WHERE id = ?1", [stuck]).unwrap();
+
+        // Simulate a delete from before cascades existed
+        conn.execute("PRAGMA foreign_keys = OFF", []).unwrap();
+        conn.execute("DELETE FROM runs WHERE id = ?1", [fast]).unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+    }
+
+    let report = block_on(repair_database()).unwrap();
+    assert_eq!(
+        report,
+        RepairReport {
+            orphaned_splits: 2,
+            orphaned_snapshots: 0,
+            orphaned_split_notes: 1,
+            runs_missing_end_time: 1,
+            personal_bests_fixed: 1,
+            gold_splits_fixed: 2,
+        }
+    );
+
+    let pbs = PersonalBest::get_all().unwrap();
+    assert_eq!(pbs.len(), 1);
+    assert_eq!(pbs[0].run_id, slow);
+
+    let golds = GoldSplit::get_all().unwrap();
+    assert_eq!(golds.len(), 1);
+    assert_eq!(golds[0].breakpoint_name, "The Coast");
+    assert_eq!(golds[0].best_segment_ms, 70_000);
+
+    assert!(!Run::get_by_id(stuck).unwrap().unwrap().is_completed);
+
+    // A second pass has nothing left to fix
+    assert_eq!(block_on(repair_database()).unwrap(), RepairReport::default());
+}
+
+#[test]
+fn test_archive_and_restore_old_runs() {
+    let _db = setup_db();
+
+    // The PB stays however old it is
+    let pb = Run::insert(&new_run("2024-01-01T12:00:00Z")).unwrap();
+    record_segments(pb, &[("The Coast", 60_000)]);
+    finish_run(pb, 60_000).unwrap();
+    let old = Run::insert(&new_run("2024-01-02T12:00:00Z")).unwrap();
+    let old_splits = record_segments(old, &[("The Coast", 70_000), ("The Mud Flats", 50_000)]);
+    tag(old_splits[0], "death");
+    Snapshot::insert(&NewSnapshot {
+        run_id: old,
+        split_id: Some(old_splits[1]),
+        timestamp: "2024-01-02T12:02:00Z".to_string(),
+        elapsed_time_ms: 120_000,
+        character_level: 5,
+        experience: None,
+        items_json: r#"[{"name":"Wand"}]"#.to_string(),
+        skills_json: "[]".to_string(),
+        passive_tree_json: "{}".to_string(),
+        stats_json: "{}".to_string(),
+        pob_code: Some("pob".to_string()),
+    })
+    .unwrap();
+    let recent = Run::insert(&new_run("2024-06-01T12:00:00Z")).unwrap();
+
+    assert_eq!(block_on(archive_runs_older_than("March 2024".to_string())).unwrap_err().code(), "invalid_input");
+    let report = block_on(archive_runs_older_than("2024-03-01".to_string())).unwrap();
+    assert_eq!(report, ArchiveReport { runs_archived: 1, snapshots_archived: 1 });
+    assert!(Run::get_by_id(old).unwrap().is_none());
+    assert!(Split::get_by_run(old).unwrap().is_empty());
+
+    let page = block_on(get_runs_paged(0, 1, RunFilters::default())).unwrap();
+    assert_eq!((page.total, page.runs[0].id), (2, recent));
+    let page = block_on(get_runs_paged(1, 1, RunFilters::default())).unwrap();
+    assert_eq!(page.runs.iter().map(|r| r.id).collect::<Vec<_>>(), vec![pb]);
+    assert_eq!(block_on(get_runs_paged(0, 0, RunFilters::default())).unwrap_err().code(), "invalid_input");
+
+    let archived = block_on(get_archived_runs(0, 10)).unwrap();
+    assert_eq!((archived.total, archived.runs[0].id), (1, old));
+    assert_eq!(archived.runs[0].started_at, "2024-01-02T12:00:00Z");
+
+    // Restored with its ids, so the split note and snapshot still line up
+    block_on(restore_archived_run(old)).unwrap();
+    assert_eq!(block_on(restore_archived_run(old)).unwrap_err().code(), "not_found");
+    assert_eq!(block_on(get_archived_runs(0, 10)).unwrap().total, 0);
+    assert_eq!(Split::get_by_run(old).unwrap().iter().map(|s| s.id).collect::<Vec<_>>(), old_splits);
+    assert_eq!(block_on(get_split_notes(old)).unwrap().len(), 1);
+    let summaries = Snapshot::get_summaries(old).unwrap();
+    assert_eq!((summaries.len(), summaries[0].split_id, summaries[0].has_pob_code), (1, Some(old_splits[1]), true));
+    assert_eq!(Snapshot::get_by_id(summaries[0].id).unwrap().unwrap().items_json, r#"[{"name":"Wand"}]"#);
+}
+
+#[test]
+fn test_deleting_a_run_recomputes_its_golds() {
+    let _db = setup_db();
+    let fast = create_run();
+    record_segments(fast, &[("The Coast", 60_000), ("The Mud Flats", 40_000)]);
+    let slow = create_run();
+    record_segments(slow, &[("The Coast", 70_000), ("The Mud Flats", 50_000)]);
+    let gold = |name: &str| GoldSplit::get_best("Act 10 Any%", "Witch", name, None).unwrap();
+
+    // Deleting a run without golds leaves them alone
+    let other = create_run();
+    record_segments(other, &[("The Coast", 90_000)]);
+    remove_run(other).unwrap();
+    assert_eq!(gold("The Coast"), Some(60_000));
+
+    remove_run(fast).unwrap();
+    assert_eq!((gold("The Coast"), gold("The Mud Flats")), (Some(70_000), Some(50_000)));
+
+    // A gold lost from the table comes back; nothing else changes
+    get_db().unwrap().execute("DELETE FROM gold_splits WHERE breakpoint_name = 'The Coast'", []).unwrap();
+    assert_eq!(block_on(recompute_gold_splits("Act 10 Any%".to_string())).unwrap(), 1);
+    assert_eq!(gold("The Coast"), Some(70_000));
+    assert_eq!(block_on(recompute_gold_splits("Act 10 Any%".to_string())).unwrap(), 0);
+
+    // With the last run gone the category has no golds
+    remove_run(slow).unwrap();
+    assert!(GoldSplit::get_all().unwrap().is_empty());
+}
+
+#[test]
+fn test_pbs_separated_by_major_patch() {
+    let _db = setup_db();
+    let finish_on = |started_at: &str, version: &str, total_ms: i64| {
+        let run_id = Run::insert(&NewRun {
+            game_version: Some(version.to_string()),
+            ..new_run(started_at)
+        })
+        .unwrap();
+        (run_id, finish_run(run_id, total_ms).unwrap())
+    };
+
+    let (old_patch_pb, _) = finish_on("2024-01-15T12:00:00Z", "3.24.2", 100_000);
+    Settings::save(&Settings {
+        separate_pbs_by_patch: true,
+        ..Settings::default()
+    })
+    .unwrap();
+
+    // Slower than the old patch's PB, but the first run on the new patch
+    let (new_patch_pb, is_pb) = finish_on("2024-08-01T12:00:00Z", "3.25.0.1", 120_000);
+    assert!(is_pb);
+    assert!(!finish_on("2024-08-02T12:00:00Z", "3.25.1", 130_000).1);
+
+    let current = block_on(get_personal_bests()).unwrap();
+    assert_eq!(current.len(), 1);
+    assert_eq!((current[0].patch.as_str(), current[0].run_id), ("3.25", new_patch_pb));
+
+    // Turning the setting off goes back to the all-time PB
+    Settings::save(&Settings::default()).unwrap();
+    let all_time = block_on(get_personal_bests()).unwrap();
+    assert_eq!(all_time.len(), 1);
+    assert_eq!((all_time[0].patch.as_str(), all_time[0].run_id), ("", old_patch_pb));
+}
+
+#[test]
+fn test_pbs_kept_per_league() {
+    let _db = setup_db();
+    let finish_in = |league: &str, ascendancy: Option<&str>, total_ms: i64| {
+        let run_id = Run::insert(&NewRun {
+            league: league.to_string(),
+            ascendancy: ascendancy.map(str::to_string),
+            ..new_run("2024-08-01T12:00:00Z")
+        })
+        .unwrap();
+        (run_id, finish_run(run_id, total_ms).unwrap())
+    };
+
+    let (league_pb, _) = finish_in("Settlers", Some("Elementalist"), 100_000);
+    // Faster, but in Standard, so the league PB stays
+    let (standard_pb, is_pb) = finish_in("Standard", None, 90_000);
+    assert!(is_pb);
+    assert!(!finish_in("Settlers", None, 110_000).1);
+
+    let pb = |league: &str| {
+        block_on(get_personal_best("Act 10 Any%".to_string(), "Witch".to_string(), league.to_string())).unwrap()
+    };
+    let settlers = pb("Settlers").unwrap();
+    assert_eq!((settlers.run_id, settlers.ascendancy.as_deref()), (league_pb, Some("Elementalist")));
+    assert_eq!(pb("Standard").unwrap().run_id, standard_pb);
+    assert!(pb("Hardcore Settlers").is_none());
+
+    // Rebuilt from the runs after the table lost them
+    get_db().unwrap().execute("DELETE FROM personal_bests", []).unwrap();
+    assert_eq!(block_on(recompute_personal_bests()).unwrap(), 2);
+    assert_eq!(pb("Settlers").unwrap().run_id, league_pb);
+    assert_eq!(pb("Standard").unwrap().total_time_ms, 90_000);
+}
+
+#[test]
+fn test_run_readiness_preset_from_pb() {
+    let _db = setup_db();
+    assert_eq!(readiness::pb_preset("Act 10 Any%").unwrap(), None);
+
+    let finish_with = |preset: &str, total_ms: i64| {
+        let run_id = Run::insert(&NewRun {
+            breakpoint_preset: Some(preset.to_string()),
+            ..new_run("2024-01-15T12:00:00Z")
+        })
+        .unwrap();
+        finish_run(run_id, total_ms).unwrap();
+    };
+    finish_with("speedrun", 100_000);
+    finish_with("minimal", 120_000);
+    assert_eq!(readiness::pb_preset("Act 10 Any%").unwrap().as_deref(), Some("speedrun"));
+    assert_eq!(readiness::pb_preset("Act 5").unwrap(), None);
+
+    Settings::save(&Settings {
+        readiness_mode: "block".to_string(),
+        readiness_overlay: true,
+        readiness_profile_public: false,
+        ..Settings::default()
+    })
+    .unwrap();
+    let checklist = Checklist::from_settings(&Settings::load().unwrap());
+    assert_eq!(checklist.mode, ReadinessMode::Block);
+    assert!(checklist.overlay && checklist.account && !checklist.profile_public);
+}
+
+#[test]
+fn test_oauth_token_survives_settings_save() {
+    let _db = setup_db();
+    assert_eq!(Settings::get_oauth_token().unwrap(), None);
+    assert!(!block_on(get_poe_auth_status()).unwrap().connected);
+
+    let token = OAuthToken {
+        access_token: "access".to_string(),
+        refresh_token: Some("refresh".to_string()),
+        expires_at: 1_700_000_000,
+        account_name: "Beerdz#1234".to_string(),
+    };
+    Settings::save_oauth_token(&token).unwrap();
+    Settings::save(&Settings::default()).unwrap();
+    assert_eq!(Settings::get_oauth_token().unwrap(), Some(token));
+
+    let status = block_on(get_poe_auth_status()).unwrap();
+    assert!(status.connected);
+    assert_eq!(status.account_name.as_deref(), Some("Beerdz#1234"));
+
+    assert!(!block_on(poe_logout()).unwrap().connected);
+    assert_eq!(Settings::get_oauth_token().unwrap(), None);
+}
+
+#[test]
+fn test_livesplit_export_and_import() {
+    let _db = setup_db();
+    let pb_run = create_run();
+    record_segments(pb_run, &[("The Coast", 60_000), ("The Mud Flats", 50_000)]);
+    finish_run(pb_run, 110_000).unwrap();
+    let run_id = create_run();
+    record_segments(run_id, &[("The Coast", 55_000), ("The Mud Flats", 70_000)]);
+    finish_run(run_id, 125_000).unwrap();
+
+    let dir = TempLog::new();
+    let path = dir.path().with_file_name("run.lss").to_string_lossy().into_owned();
+    block_on(export_run_livesplit(run_id, path.clone())).unwrap();
+
+    // The PB run is the comparison; golds come from both runs
+    let lss = livesplit::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(lss.category.as_deref(), Some("Act 10 Any%"));
+    assert_eq!(lss.class.as_deref(), Some("Witch"));
+    let times: Vec<_> = lss.segments.iter().map(|s| (s.pb_split_ms, s.best_segment_ms)).collect();
+    assert_eq!(times, vec![(Some(60_000), Some(55_000)), (Some(110_000), Some(50_000))]);
+
+    get_db().unwrap().execute("DELETE FROM gold_splits", []).unwrap();
+    let imported = block_on(import_livesplit(path)).unwrap();
+    assert_eq!(imported.golds_imported, 2);
+    let reference = Run::get_by_id(imported.run_id).unwrap().unwrap();
+    assert!(reference.is_reference);
+    assert_eq!((reference.category.as_str(), reference.class.as_str()), ("Act 10 Any%", "Witch"));
+    assert_eq!(reference.total_time_ms, Some(110_000));
+    let split_times: Vec<i64> = Split::get_by_run(imported.run_id).unwrap().iter().map(|s| s.split_time_ms).collect();
+    assert_eq!(split_times, vec![60_000, 110_000]);
+    assert_eq!(GoldSplit::get_best("Act 10 Any%", "Witch", "The Coast", None).unwrap(), Some(55_000));
+}
+
+#[test]
+fn test_json_export_and_import() {
+    let _db = setup_db();
+    let run_id = create_run();
+    let split_ids = record_segments(run_id, &[("The Coast", 60_000), ("The Mud Flats", 50_000)]);
+    finish_run(run_id, 110_000).unwrap();
+    Snapshot::insert(&NewSnapshot {
+        run_id,
+        split_id: Some(split_ids[1]),
+        timestamp: "2024-01-15T12:02:00Z".to_string(),
+        elapsed_time_ms: 110_000,
+        character_level: 4,
+        experience: None,
+        items_json: r#"[{"name":"Wand"}]"#.to_string(),
+        skills_json: "[]".to_string(),
+        passive_tree_json: r#"{"hashes":[1,2]}"#.to_string(),
+        stats_json: "{}".to_string(),
+        pob_code: Some("pob".to_string()),
+    })
+    .unwrap();
+
+    let dir = TempLog::new();
+    let path = dir.path().with_file_name("run.json").to_string_lossy().into_owned();
+    block_on(export_run_json(run_id, path.clone())).unwrap();
+
+    // Re-importing your own run into the same history is refused
+    let settings = Settings { account_name: "testaccount".to_string(), ..Settings::default() };
+    Settings::save(&settings).unwrap();
+    assert_eq!(block_on(import_run_json(path.clone())).unwrap_err().code(), "invalid_input");
+
+    // Into a fresh history it comes back as a regular run with its PB
+    Run::delete(run_id).unwrap();
+    get_db().unwrap().execute("DELETE FROM gold_splits", []).unwrap();
+    let own = block_on(import_run_json(path.clone())).unwrap();
+    assert!(!own.is_reference);
+    assert_eq!(own.snapshots_imported, 1);
+    assert_eq!(own.certificate, Some(CertificateStatus::Valid));
+    let run = Run::get_by_id(own.run_id).unwrap().unwrap();
+    assert!(run.is_completed);
+    assert!(PersonalBest::get_for_patch("").unwrap().iter().any(|pb| pb.run_id == own.run_id));
+    assert_eq!((run.started_at.as_str(), run.total_time_ms), ("2024-01-15T12:00:00Z", Some(110_000)));
+    assert_eq!(GoldSplit::get_best("Act 10 Any%", "Witch", "The Mud Flats", None).unwrap(), Some(50_000));
+    let splits = Split::get_by_run(own.run_id).unwrap();
+    let snapshots = Snapshot::get_by_run(own.run_id).unwrap();
+    assert_eq!(snapshots[0].split_id, Some(splits[1].id));
+    assert_eq!(snapshots[0].passive_tree_json, r#"{"hashes":[1,2]}"#);
+
+    // Another account's run is a reference
+    Settings::save(&Settings { account_name: "Someone".to_string(), ..Settings::default() }).unwrap();
+    let other = block_on(import_run_json(path.clone())).unwrap();
+    assert!(other.is_reference);
+    let reference = Run::get_by_id(other.run_id).unwrap().unwrap();
+    assert!(reference.is_reference);
+    assert_eq!(reference.source_name.as_deref(), Some("TestAccount (TestChar)"));
+    assert_eq!(Split::get_by_run(other.run_id).unwrap().len(), 2);
+
+    // A split time edited after export no longer matches the certificate
+    let edited = std::fs::read_to_string(&path).unwrap().replace("\"splitTimeMs\": 110000", "\"splitTimeMs\": 100000");
+    std::fs::write(&path, &edited).unwrap();
+    assert_eq!(block_on(import_run_json(path.clone())).unwrap().certificate, Some(CertificateStatus::Tampered));
+
+    // Files from newer exporters are rejected
+    let newer = edited.replace(&format!("\"{}\"", run_json::VERSION), "\"1.0.0\"");
+    std::fs::write(&path, newer).unwrap();
+    assert_eq!(block_on(import_run_json(path)).unwrap_err().code(), "invalid_input");
+}
+
+#[test]
+fn test_run_notes_and_tags() {
+    let _db = setup_db();
+    let tagged = create_run();
+    let split_ids = record_segments(tagged, &[("The Coast", 60_000), ("The Ledge", 90_000)]);
+    let other = create_run();
+    let other_split = record_segments(other, &[("The Coast", 65_000)])[0];
+
+    block_on(set_run_note(tagged, Some("  new build test ".to_string()))).unwrap();
+    assert_eq!(Run::get_by_id(tagged).unwrap().unwrap().notes.as_deref(), Some("new build test"));
+    assert_eq!(block_on(set_run_note(9999, None)).unwrap_err().code(), "not_found");
+
+    // Tags are normalized, and tagging twice keeps one
+    let first = block_on(add_run_tag(tagged, "New  Build".to_string(), None)).unwrap();
+    assert_eq!(first.tag, "new build");
+    assert_eq!(block_on(add_run_tag(tagged, "new build".to_string(), None)).unwrap().id, first.id);
+    block_on(add_run_tag(tagged, "Lag Spike".to_string(), Some(split_ids[1]))).unwrap();
+    assert_eq!(block_on(add_run_tag(tagged, " ".to_string(), None)).unwrap_err().code(), "invalid_input");
+    // A split of another run can't be tagged through this one
+    assert_eq!(
+        block_on(add_run_tag(tagged, "lag spike".to_string(), Some(other_split))).unwrap_err().code(),
+        "not_found"
+    );
+    block_on(add_split_note(NewSplitNote { split_id: other_split, note: "Disconnected in A4".to_string(), mistake_type: None }))
+        .unwrap();
+
+    let tags = block_on(get_run_tags(tagged)).unwrap();
+    let listed: Vec<(Option<i64>, &str)> = tags.iter().map(|t| (t.split_id, t.tag.as_str())).collect();
+    assert_eq!(listed, vec![(None, "new build"), (Some(split_ids[1]), "lag spike")]);
+    assert_eq!(block_on(get_run_tag_names()).unwrap(), vec!["lag spike", "new build"]);
+
+    // Split tags find the run too, and filters match tags and note text
+    let by_tag = |tag: &str| -> Vec<i64> { block_on(get_runs_by_tag(tag.to_string())).unwrap().iter().map(|r| r.id).collect() };
+    assert_eq!(by_tag("LAG SPIKE"), vec![tagged]);
+    let filtered = |filters: RunFilters| -> Vec<i64> { Run::get_filtered(&filters).unwrap().iter().map(|r| r.id).collect() };
+    assert_eq!(filtered(RunFilters { tag: Some("new build".to_string()), ..RunFilters::default() }), vec![tagged]);
+    assert_eq!(filtered(RunFilters { note: Some("BUILD".to_string()), ..RunFilters::default() }), vec![tagged]);
+    assert_eq!(filtered(RunFilters { note: Some("a4".to_string()), ..RunFilters::default() }), vec![other]);
+
+    // Exported with the run, and brought back on import
+    block_on(add_split_note(NewSplitNote { split_id: split_ids[0], note: "Slow start".to_string(), mistake_type: None }))
+        .unwrap();
+    let dir = TempLog::new();
+    let path = dir.path().with_file_name("tagged.json").to_string_lossy().into_owned();
+    block_on(export_run_json(tagged, path.clone())).unwrap();
+    let imported = block_on(import_run_json(path)).unwrap().run_id;
+    assert_eq!(Run::get_by_id(imported).unwrap().unwrap().notes.as_deref(), Some("new build test"));
+    let imported_tags: Vec<String> = block_on(get_run_tags(imported)).unwrap().into_iter().map(|t| t.tag).collect();
+    assert_eq!(imported_tags, vec!["new build", "lag spike"]);
+    assert_eq!(block_on(get_split_notes(imported)).unwrap()[0].note, "Slow start");
+    assert_eq!(by_tag("new build"), vec![imported, tagged]);
+
+    block_on(remove_run_tag(first.id)).unwrap();
+    assert_eq!(block_on(remove_run_tag(first.id)).unwrap_err().code(), "not_found");
+    Run::delete(tagged).unwrap();
+    assert_eq!(by_tag("new build"), vec![imported]);
+}
+
+#[test]
+fn test_run_certificate() {
+    let _db = setup_db();
+    let run_id = create_run();
+    record_segments(run_id, &[("The Coast", 60_000), ("The Mud Flats", 50_000)]);
+    assert_eq!(block_on(get_run_certificate(run_id)).unwrap_err().code(), "invalid_input");
+    assert_eq!(block_on(get_run_certificate(9_999)).unwrap_err().code(), "not_found");
+
+    finish_run(run_id, 110_000).unwrap();
+    let certificate = block_on(get_run_certificate(run_id)).unwrap();
+    assert_eq!(certificate.app_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(certificate.total_time_ms, 110_000);
+    let times: Vec<i64> = certificate.splits.iter().map(|s| s.time_ms).collect();
+    assert_eq!(times, vec![60_000, 110_000]);
+    // The same run always gets the same certificate
+    assert_eq!(block_on(get_run_certificate(run_id)).unwrap(), certificate);
+
+    // Checked on someone else's install from its JSON alone
+    let shared: crate::certificate::RunCertificate =
+        serde_json::from_str(&serde_json::to_string(&certificate).unwrap()).unwrap();
+    assert_eq!(block_on(verify_run_certificate(shared.clone())).unwrap(), CertificateStatus::Valid);
+    let edited = crate::certificate::RunCertificate { category: "Act 5".to_string(), ..shared };
+    assert_eq!(block_on(verify_run_certificate(edited)).unwrap(), CertificateStatus::Tampered);
+}
+
+#[test]
+fn test_ladder_milestones_become_reference_run() {
+    let _db = setup_db();
+    let ladder_at = |leader_level: u32, leader_xp: u64| -> Vec<LadderEntry> {
+        serde_json::from_value(serde_json::json!([
+            {"rank": 1, "character": {"name": "Leader", "level": leader_level, "class": "Necromancer", "experience": leader_xp}, "account": {"name": "Fast#0001"}},
+            {"rank": 2, "dead": true, "character": {"name": "Second", "level": 8, "class": "Slayer", "experience": 5000}}
+        ]))
+        .unwrap()
+    };
+
+    assert_eq!(ladder::record("Race", &ladder_at(9, 4000), "2026-01-01T00:05:00Z").unwrap(), 2);
+    // Unchanged since the last fetch: nothing new to record
+    assert_eq!(ladder::record("Race", &ladder_at(9, 4000), "2026-01-01T00:06:00Z").unwrap(), 0);
+    assert_eq!(ladder::record("Race", &ladder_at(14, 30000), "2026-01-01T00:12:00Z").unwrap(), 1);
+    assert_eq!(ladder::record("Race", &ladder_at(23, 90000), "2026-01-01T00:30:00Z").unwrap(), 1);
+    assert_eq!(LadderObservation::get_by_character("Race", "Leader").unwrap().len(), 3);
+    assert!(LadderObservation::get_by_league("Other").unwrap().is_empty());
+
+    let racers = ladder::racers("Race", &ladder_at(23, 90000)).unwrap();
+    assert_eq!((racers[0].milestones, racers[0].account_name.as_str()), (2, "Fast#0001"));
+    assert_eq!((racers[1].milestones, racers[1].account_name.as_str(), racers[1].dead), (0, "", true));
+
+    let data = ReferenceRunData {
+        source_name: "Race leader".to_string(),
+        character_name: None,
+        class: "Witch".to_string(),
+        ascendancy: Some("Necromancer".to_string()),
+        category: "Act 10 Any%".to_string(),
+        league: None,
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        total_time_ms: 0,
+        splits: Vec::new(),
+    };
+    let reference = ladder::reference_run("Leader", "Race", "2026-01-01T00:00:00Z", data.clone()).unwrap();
+    let splits: Vec<(&str, i64)> = reference.splits.iter().map(|s| (s.breakpoint_name.as_str(), s.split_time_ms)).collect();
+    assert_eq!(splits, vec![("Level 10", 720_000), ("Level 20", 1_800_000)]);
+    assert_eq!(reference.total_time_ms, 1_800_000);
+    assert_eq!((reference.character_name.as_deref(), reference.league.as_deref()), (Some("Leader"), Some("Race")));
+
+    let run_id = block_on(create_reference_run(reference)).unwrap();
+    assert!(Run::get_by_id(run_id).unwrap().unwrap().is_reference);
+    assert_eq!(Split::get_by_run(run_id).unwrap()[1].breakpoint_key.as_deref(), Some("level:20"));
+
+    let untimed = ladder::reference_run("Second", "Race", "2026-01-01T00:00:00Z", data).unwrap_err();
+    assert_eq!(untimed.code(), "invalid_input");
+}
+
+#[test]
+fn test_split_colors_against_pb_and_golds() {
+    let _db = setup_db();
+    let pb_run = create_run();
+    record_segments(pb_run, &[("The Coast", 60_000), ("The Mud Flats", 50_000), ("The Tidal Island", 40_000)]);
+    finish_run(pb_run, 150_000).unwrap();
+
+    let run_id = create_run();
+    let mut elapsed = 0;
+    let mut split = |name: &str, segment_ms: i64| {
+        elapsed += segment_ms;
+        let split = NewSplit {
+            run_id,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: None,
+            split_time_ms: elapsed,
+            delta_ms: None,
+            segment_time_ms: segment_ms,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+        };
+        let comparison = compare_split(&split).unwrap();
+        record_split(&NewSplit { delta_ms: comparison.delta_ms, ..split }).unwrap();
+        comparison
+    };
+
+    let coast = split("The Coast", 62_000);
+    assert_eq!((coast.delta_ms, coast.color), (Some(2_000), SplitColor::BehindLosing));
+    let mud_flats = split("The Mud Flats", 45_000);
+    assert_eq!((mud_flats.delta_ms, mud_flats.color), (Some(-3_000), SplitColor::Gold));
+    // Still ahead, but the segment gave back a second
+    let tidal = split("The Tidal Island", 41_000);
+    assert_eq!((tidal.delta_ms, tidal.segment_delta_ms), (Some(-2_000), Some(1_000)));
+    assert_eq!(tidal.color, SplitColor::AheadLosing);
+    assert!(!tidal.is_gold);
+
+    // The delta is stored with the split
+    let deltas: Vec<_> = Split::get_by_run(run_id).unwrap().iter().map(|s| s.delta_ms).collect();
+    assert_eq!(deltas, vec![Some(2_000), Some(-3_000), Some(-2_000)]);
+}
+
+#[test]
+fn test_comparison_rows_for_each_kind() {
+    let _db = setup_db();
+    let pb_run = create_run();
+    record_segments(pb_run, &[("The Coast", 60_000), ("The Mud Flats", 50_000)]);
+    finish_run(pb_run, 110_000).unwrap();
+    let slower = create_run();
+    record_segments(slower, &[("The Coast", 70_000), ("The Mud Flats", 60_000)]);
+    finish_run(slower, 130_000).unwrap();
+    let reference = block_on(create_reference_run(ReferenceRunData {
+        source_name: "WR".to_string(),
+        character_name: None,
+        class: "Witch".to_string(),
+        ascendancy: None,
+        category: "Act 10 Any%".to_string(),
+        league: None,
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        total_time_ms: 90_000,
+        splits: vec![ReferenceSplitData {
+            breakpoint_name: "The Coast".to_string(),
+            breakpoint_type: "zone".to_string(),
+            breakpoint_key: None,
+            split_time_ms: 50_000,
+        }],
+    }))
+    .unwrap();
+
+    // A run in progress, one split in
+    let run_id = create_run();
+    record_segments(run_id, &[("The Coast", 62_000)]);
+    let compare = |kind| block_on(get_comparison(run_id, kind)).unwrap();
+
+    let pb = compare(ComparisonKind::PersonalBest);
+    assert_eq!(pb.len(), 2);
+    assert_eq!((pb[0].delta_ms, pb[0].color), (Some(2_000), SplitColor::BehindLosing));
+    assert_eq!((pb[1].split_time_ms, pb[1].comparison_split_ms), (None, Some(110_000)));
+
+    let gold = compare(ComparisonKind::Gold);
+    assert_eq!((gold[0].comparison_segment_ms, gold[0].delta_ms), (Some(60_000), Some(2_000)));
+
+    let average = compare(ComparisonKind::Average { count: 5 });
+    assert_eq!(average[0].comparison_split_ms, Some(65_000));
+    assert_eq!((average[0].delta_ms, average[0].color), (Some(-3_000), SplitColor::AheadGaining));
+    assert_eq!(average[1].comparison_split_ms, Some(120_000));
+
+    let wr = compare(ComparisonKind::Reference { run_id: reference });
+    assert_eq!(wr.len(), 1);
+    assert_eq!(wr[0].delta_ms, Some(12_000));
+
+    let missing = block_on(get_comparison(run_id, ComparisonKind::Reference { run_id: 999 })).unwrap_err();
+    assert_eq!(missing.code(), "not_found");
+    let empty = block_on(get_comparison(run_id, ComparisonKind::Average { count: 0 })).unwrap_err();
+    assert_eq!(empty.code(), "invalid_input");
+}
+
+#[test]
+fn test_race_events_stored_as_live_reference_runs() {
+    let _db = setup_db();
+    race_lobby::leave();
+    let split = |name: &str, split_time_ms| RaceEvent::Split {
+        racer: "Alice".to_string(),
+        breakpoint_type: "zone".to_string(),
+        breakpoint_name: name.to_string(),
+        breakpoint_key: None,
+        split_time_ms,
+    };
+
+    // Splits before a start have no run to go to
+    assert!(!race_lobby::apply(&split("The Coast", 55_000)).unwrap());
+    let started = RaceEvent::RunStarted {
+        racer: "Alice".to_string(),
+        category: "Act 10 Any%".to_string(),
+        class: "Ranger".to_string(),
+        started_at: "2024-01-15T12:00:00Z".to_string(),
+    };
+    assert!(race_lobby::apply(&started).unwrap());
+    assert!(race_lobby::apply(&split("The Coast", 55_000)).unwrap());
+    // Replays of a start or split already stored change nothing
+    assert!(!race_lobby::apply(&started).unwrap());
+    assert!(!race_lobby::apply(&split("The Coast", 55_000)).unwrap());
+
+    let alice = Run::get_all().unwrap().into_iter().find(|r| r.is_reference).unwrap();
+    assert_eq!(alice.source_name.as_deref(), Some("Race: Alice"));
+
+    // The local run is compared against Alice's splits as they arrive
+    let run_id = create_run();
+    record_segments(run_id, &[("The Coast", 60_000), ("The Mud Flats", 50_000)]);
+    let compare = || block_on(get_comparison(run_id, ComparisonKind::Reference { run_id: alice.id })).unwrap();
+    let rows = compare();
+    assert_eq!(rows[0].delta_ms, Some(5_000));
+    assert_eq!((rows[1].comparison_split_ms, rows[1].delta_ms), (None, None));
+
+    assert!(race_lobby::apply(&split("The Mud Flats", 100_000)).unwrap());
+    let rows = compare();
+    assert_eq!((rows[1].comparison_segment_ms, rows[1].delta_ms), (Some(45_000), Some(10_000)));
+
+    let finished = RaceEvent::RunFinished { racer: "Alice".to_string(), total_time_ms: 100_000 };
+    assert!(race_lobby::apply(&finished).unwrap());
+    assert!(!race_lobby::apply(&split("The Southern Forest", 120_000)).unwrap());
+    assert_eq!(Run::get_by_id(alice.id).unwrap().unwrap().total_time_ms, Some(100_000));
+
+    // Past the racer cap, new racers' starts aren't stored
+    let start = |racer: String| RaceEvent::RunStarted {
+        racer,
+        category: "Act 10 Any%".to_string(),
+        class: "Witch".to_string(),
+        started_at: "2024-01-15T12:00:00Z".to_string(),
+    };
+    for i in 1..16 {
+        assert!(race_lobby::apply(&start(format!("Racer {}", i))).unwrap());
+    }
+    assert!(!race_lobby::apply(&start("One too many".to_string())).unwrap());
+    // A racer who left makes room for a new one
+    assert!(race_lobby::apply(&RaceEvent::RacerLeft { racer: "Racer 1".to_string() }).unwrap());
+    assert!(!race_lobby::apply(&RaceEvent::RacerLeft { racer: "Racer 1".to_string() }).unwrap());
+    assert!(race_lobby::apply(&start("One too many".to_string())).unwrap());
+    // Racers already in the lobby can still start over
+    assert!(race_lobby::apply(&RaceEvent::RunStarted {
+        racer: "Alice".to_string(),
+        category: "Act 10 Any%".to_string(),
+        class: "Ranger".to_string(),
+        started_at: "2024-01-15T13:00:00Z".to_string(),
+    })
+    .unwrap());
+    race_lobby::leave();
+}
+
+#[test]
+fn test_race_lobby_handshake_binds_racer() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpStream;
+
+    let _db = setup_db();
+    let port = race_lobby::host(0, "Host", std::sync::Arc::new(|_| {})).unwrap();
+    let address = race_lobby::status().address;
+    let room = &address[address.rfind('/').unwrap()..];
+    assert_eq!(room.len(), 33, "{}", address);
+
+    let handshake = |path: &str, name: Option<&str>| {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let name = name.map(|n| format!("X-Race-Name: {}\r\n", n)).unwrap_or_default();
+        write!(stream, "GET {} HTTP/1.1\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n{}\r\n", path, name).unwrap();
+        let mut status = String::new();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        reader.read_line(&mut status).unwrap();
+        while reader.read_line(&mut String::new()).unwrap() > 2 {}
+        (status.split_whitespace().nth(1).unwrap().to_string(), stream)
+    };
+
+    assert_eq!(handshake("/", Some("Bob")).0, "403");
+    assert_eq!(handshake("/0123456789abcdef0123456789abcdef", Some("Bob")).0, "403");
+    assert_eq!(handshake(room, None).0, "400");
+    assert_eq!(handshake(room, Some("Host")).0, "409");
+    let (status, mut bob) = handshake(room, Some("Bob"));
+    assert_eq!(status, "101");
+    // The slot is taken before the handshake is answered
+    assert_eq!(race_lobby::status().peers, 1);
+    assert_eq!(handshake(room, Some("Bob")).0, "409");
+
+    // Headers past the cap are dropped without an answer
+    let mut flood = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let _ = write!(flood, "GET {} HTTP/1.1\r\nX-Padding: {}\r\n\r\n", room, "x".repeat(16 * 1024));
+    let mut answer = String::new();
+    assert!(BufReader::new(flood).read_line(&mut answer).map_or(true, |read| read == 0));
+
+    // Bob's connection can only speak for Bob
+    let started = |racer: &str| {
+        let event = RaceEvent::RunStarted {
+            racer: racer.to_string(),
+            category: "Act 10 Any%".to_string(),
+            class: "Witch".to_string(),
+            started_at: "2024-01-15T12:00:00Z".to_string(),
+        };
+        crate::obs_server::frame(0x1, serde_json::to_string(&event).unwrap().as_bytes())
+    };
+    bob.write_all(&started("Carol")).unwrap();
+    bob.write_all(&started("Bob")).unwrap();
+    for _ in 0..100 {
+        if !race_lobby::status().racers.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    let racers: Vec<String> = race_lobby::status().racers.into_iter().map(|r| r.name).collect();
+    assert_eq!(racers, ["Bob"]);
+
+    // Bob leaving frees his name and his place under the racer cap
+    drop(bob);
+    for _ in 0..100 {
+        if race_lobby::status().racers.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    let status = race_lobby::status();
+    assert_eq!((status.peers, status.racers.len()), (0, 0));
+    assert_eq!(handshake(room, Some("Bob")).0, "101");
+
+    // Closing the lobby frees the port straight away
+    race_lobby::leave();
+    assert_eq!(race_lobby::host(port, "Host", std::sync::Arc::new(|_| {})).unwrap(), port);
+    race_lobby::leave();
+}
+
+/// (key, split time, cumulative town, cumulative hideout)
+type TownSplit = (Option<&'static str>, i64, i64, i64);
+
+#[test]
+fn test_town_time_per_act() {
+    let _db = setup_db();
+
+    let runs: [&[TownSplit]; 2] = [
+        &[
+            (Some("zone:a1:the_coast"), 60_000, 10_000, 0),
+            (None, 120_000, 14_000, 2_000),
+            (Some("zone:a2:the_southern_forest"), 600_000, 40_000, 2_000),
+        ],
+        &[
+            (Some("zone:a1:the_coast"), 70_000, 20_000, 0),
+            (Some("kitava:a2"), 650_000, 30_000, 6_000),
+        ],
+    ];
+    for splits in runs {
+        let run_id = create_run();
+        for (key, split_time_ms, town_time_ms, hideout_time_ms) in splits {
+            Split::insert(&NewSplit {
+                run_id,
+                breakpoint_type: "zone".to_string(),
+                breakpoint_name: "Split".to_string(),
+                breakpoint_key: key.map(str::to_string),
+                split_time_ms: *split_time_ms,
+                delta_ms: None,
+                segment_time_ms: 0,
+                town_time_ms: *town_time_ms,
+                hideout_time_ms: *hideout_time_ms,
+            })
+            .unwrap();
+        }
+    }
+
+    // Cumulative values are kept, per-segment values derived from the previous split
+    let first = Split::get_by_run(1).unwrap();
+    let segments: Vec<(i64, i64)> = first
+        .iter()
+        .map(|s| (s.segment_town_time_ms, s.segment_hideout_time_ms))
+        .collect();
+    assert_eq!(segments, vec![(10_000, 0), (4_000, 2_000), (26_000, 0)]);
+    assert_eq!(first[2].town_time_ms, 40_000);
+
+    let report = block_on(get_town_time_report(RunFilters::default())).unwrap();
+    assert_eq!(
+        report,
+        vec![
+            ActTownTime { act: 1, average_town_time_ms: 17_000, average_hideout_time_ms: 1_000, run_count: 2 },
+            ActTownTime { act: 2, average_town_time_ms: 18_000, average_hideout_time_ms: 3_000, run_count: 2 },
+        ]
+    );
+}
+
+#[test]
+fn test_backtrack_report_from_zone_entries() {
+    let _db = setup_db();
+    let run_id = create_run();
+    for (zone, elapsed_ms) in [
+        ("The Coast", 0),
+        ("The Mud Flats", 40_000),
+        ("The Coast", 70_000),
+        ("The Submerged Passage", 85_000),
+    ] {
+        RunEvent::insert(run_id, "zone_enter", elapsed_ms, Some(zone)).unwrap();
+    }
+    RunEvent::insert(run_id, "level_up", 50_000, Some("5")).unwrap();
+    Run::complete(run_id, 100_000).unwrap();
+
+    let report = block_on(get_backtrack_report(run_id)).unwrap();
+    assert_eq!((report.backtracks, report.backtrack_time_ms), (1, 15_000));
+    let entries: Vec<(&str, u32)> = report.zones.iter().map(|z| (z.zone_name.as_str(), z.entries)).collect();
+    assert_eq!(entries, vec![("The Coast", 2), ("The Mud Flats", 1), ("The Submerged Passage", 1)]);
+
+    assert!(block_on(get_backtrack_report(run_id + 1)).is_err());
+}
+
+#[test]
+fn test_map_sessions_start_and_stop() {
+    let _db = setup_db();
+    let first = block_on(start_map_session()).unwrap();
+    // Starting again ends the running session
+    let second = block_on(start_map_session()).unwrap();
+
+    let sessions = block_on(get_map_sessions()).unwrap();
+    let ids: Vec<(i64, bool)> = sessions.iter().map(|s| (s.id, s.ended_at.is_some())).collect();
+    assert_eq!(ids, vec![(second.id, false), (first.id, true)]);
+
+    let stopped = block_on(stop_map_session()).unwrap().unwrap();
+    assert_eq!(stopped.id, second.id);
+    assert!(stopped.ended_at.is_some());
+    assert_eq!(block_on(stop_map_session()).unwrap(), None);
+}
+
+#[test]
+fn test_quest_state_follows_log_events() {
+    let _db = setup_db();
+    let run_id = block_on(create_run_cmd(new_run("2024-01-15T12:00:00Z"))).unwrap();
+
+    for zone in ["The Twilight Strand", "The Flooded Depths", "The Forest Encampment"] {
+        quest_state::handle_event(&LogEvent::ZoneEnter {
+            timestamp: "2024/01/15 12:10:00".to_string(),
+            zone_name: zone.to_string(),
+        });
+    }
+
+    let state = block_on(get_quest_state(run_id)).unwrap();
+    assert_eq!(state.current_act, 2);
+    assert_eq!(state.missing, vec!["marooned_mariner", "way_forward", "bandits"]);
+
+    assert_eq!(block_on(get_quest_state(run_id + 1)).unwrap_err().code(), "not_found");
+}
+
+#[test]
+fn test_auto_start_on_new_character() {
+    let _db = setup_db();
+    breakpoint_engine::stop();
+    let strand = |seed: u64| {
+        let generated = LogEvent::AreaGenerated {
+            timestamp: "2024/01/15 13:00:00".to_string(),
+            area_level: 1,
+            area_id: "1_1_1".to_string(),
+            seed,
+        };
+        let entered = LogEvent::ZoneEnter {
+            timestamp: "2024/01/15 13:00:01".to_string(),
+            zone_name: "The Twilight Strand".to_string(),
+        };
+        assert!(auto_start::start_for(&generated).unwrap().is_none());
+        auto_start::start_for(&entered).unwrap()
+    };
+
+    // Off by default
+    assert!(strand(7_000_001).is_none());
+
+    Settings::save(&Settings { auto_start_runs: true, ..Settings::default() }).unwrap();
+    Run::insert(&NewRun {
+        league: "Settlers".to_string(),
+        breakpoint_preset: Some("speedrun".to_string()),
+        ..new_run("2024-01-15T12:00:00Z")
+    })
+    .unwrap();
+    let started = strand(7_000_002).unwrap();
+    assert_eq!(started.zone_name, "The Twilight Strand");
+    let run = Run::get_by_id(started.run_id).unwrap().unwrap();
+    assert_eq!(run.character_name, "Unknown");
+    assert_eq!((run.category.as_str(), run.league.as_str()), ("Act 10 Any%", "Settlers"));
+    assert_eq!(run.breakpoint_preset.as_deref(), Some("speedrun"));
+
+    // Not while a run is being timed
+    breakpoint_engine::start_run(started.run_id, Vec::new(), 0, None);
+    assert!(strand(7_000_003).is_none());
+    breakpoint_engine::stop();
+}
+
+#[test]
+fn test_auto_export_writes_selected_formats() {
+    let _db = setup_db();
+    let run_id = create_run();
+    record_segments(run_id, &[("The Coast", 60_000), ("The Mud Flats", 50_000)]);
+    let is_pb = finish_run(run_id, 110_000).unwrap();
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+
+    let dir = TempLog::new();
+    let export_dir = dir.path().with_file_name("exports");
+    let settings = Settings {
+        auto_export_dir: export_dir.to_string_lossy().into_owned(),
+        auto_export_formats: "csv,json".to_string(),
+        auto_export_pbs_only: true,
+        ..Settings::default()
+    };
+    assert!(is_pb && auto_export::wanted(&settings, true));
+    assert!(!auto_export::wanted(&settings, false));
+    assert!(!auto_export::wanted(&Settings::default(), true));
+
+    let files = auto_export::write_exports(&run, &settings).unwrap();
+    let names: Vec<String> = files.iter().map(|f| f.file_name().unwrap().to_string_lossy().into_owned()).collect();
+    assert_eq!(names, vec![
+        auto_export::file_name(&run, ExportFormat::Json),
+        auto_export::file_name(&run, ExportFormat::Csv),
+    ]);
+    let exported = run_json::parse(&std::fs::read_to_string(&files[0]).unwrap()).unwrap();
+    assert_eq!((exported.splits.len(), exported.run.total_time_ms), (2, Some(110_000)));
+    assert!(std::fs::read_to_string(&files[1]).unwrap().contains("The Mud Flats"));
+
+    // The completion webhook lists the files; without any it leaves them out
+    let payload = serde_json::to_value(WebhookPayload::run_completed(&run, true).with_files(&files)).unwrap();
+    assert_eq!(payload["event"], "personal_best");
+    assert_eq!(payload["files"][1], files[1].to_string_lossy().as_ref());
+    let payload = serde_json::to_value(WebhookPayload::run_completed(&run, false)).unwrap();
+    assert!(payload.get("files").is_none());
+
+    assert!(webhook::validate_url(" https://example.com/hook").is_ok());
+    assert!(webhook::validate_url("").is_ok());
+    assert_eq!(webhook::validate_url("example.com/hook").unwrap_err().code(), "invalid_input");
+}
+
+#[test]
+fn test_export_runs_and_splits_csv() {
+    let _db = setup_db();
+    let finished = create_run();
+    record_segments(finished, &[("The Coast", 60_000), ("The Mud Flats", 50_000)]);
+    finish_run(finished, 110_000).unwrap();
+    let unfinished = create_run();
+    record_segments(unfinished, &[("The Coast", 65_000)]);
+
+    let dir = TempLog::new();
+    let runs_path = dir.path().with_file_name("runs.csv").to_string_lossy().into_owned();
+    let filters = RunFilters { is_completed: Some(true), ..RunFilters::default() };
+    assert_eq!(block_on(export_runs_csv(filters, runs_path.clone())).unwrap(), 1);
+    let runs = std::fs::read_to_string(&runs_path).unwrap();
+    let lines: Vec<&str> = runs.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("Run ID,Started,Ended,Character,"));
+    assert!(lines[1].starts_with(&format!("{},", finished)));
+    let fields: Vec<&str> = lines[1].split(',').collect();
+    assert_eq!((fields[11], fields[14]), ("true", "110.000"));
+
+    let splits_path = dir.path().with_file_name("splits.csv").to_string_lossy().into_owned();
+    assert_eq!(block_on(export_splits_csv(vec![finished, unfinished], splits_path.clone())).unwrap(), 3);
+    let splits = std::fs::read_to_string(&splits_path).unwrap();
+    let lines: Vec<&str> = splits.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[2].starts_with(&format!("{},Act 10 Any%,Witch,2,The Mud Flats,", finished)));
+    assert!(lines[3].starts_with(&format!("{},Act 10 Any%,Witch,1,The Coast,", unfinished)));
+
+    let err = block_on(export_splits_csv(vec![finished, 9_999], splits_path)).unwrap_err();
+    assert_eq!(err.code(), "not_found");
+}
+
+#[test]
+fn test_db_connections_are_pooled() {
+    let _db = setup_db();
+    let run_id = create_run();
+
+    // A second connection while one is checked out, seeing the same database
+    let writer = get_db().unwrap();
+    let reader = get_db().unwrap();
+    writer.execute("UPDATE runs SET category = 'Act 5' WHERE id = ?1", [run_id]).unwrap();
+    let category: String = reader.query_row("SELECT category FROM runs WHERE id = ?1", [run_id], |row| row.get(0)).unwrap();
+    assert_eq!(category, "Act 5");
+    let foreign_keys: bool = reader.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+    assert!(foreign_keys);
+    drop((writer, reader));
+
+    // Returned connections are reused
+    assert_eq!(Run::get_by_id(run_id).unwrap().unwrap().category, "Act 5");
+}
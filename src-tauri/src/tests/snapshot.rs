@@ -0,0 +1,451 @@
+use super::fake_api::FakePoeApi;
+use super::support::{block_on, setup_db};
+use base64::Engine;
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+use crate::api_client::{PassiveSkills, PoeApi, PoeItem};
+use crate::commands::{
+    diff_snapshots, final_snapshot_target, get_ascendancy_name, get_base_class_name, insert_ninja_reference,
+    reprocess_snapshots, take_snapshot, FinalSnapshotTarget,
+};
+use crate::db::{
+    compact, get_db, NewRun, NewSnapshot, NewSplit, PersonalBest, Run, RunFilters, Snapshot, SnapshotRetention, Split,
+};
+use crate::pob_builder;
+use crate::poeninja::{NinjaCharacter, NINJA_CATEGORY};
+use crate::privacy::{self, PrivacyStatus};
+use crate::snapshot_analysis::{BuildStats, ReprocessReport, SkillGroup};
+use crate::snapshot_diff::{GemChangeKind, ItemChangeKind};
+
+/// A run the way the timer creates it before any API data has arrived
+fn create_unknown_run() -> (i64, i64) {
+    let run_id = Run::insert(&NewRun {
+        character_name: "TestNecro".to_string(),
+        account_name: "TestAccount".to_string(),
+        class: "Unknown".to_string(),
+        ascendancy: None,
+        league: String::new(),
+        category: "Act 10 Any%".to_string(),
+        started_at: "2024-01-15T12:00:00Z".to_string(),
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        game_version: None,
+    })
+    .unwrap();
+
+    let split_id = Split::insert(&NewSplit {
+        run_id,
+        breakpoint_type: "boss".to_string(),
+        breakpoint_name: "Merveil".to_string(),
+        breakpoint_key: None,
+        split_time_ms: 600_000,
+        delta_ms: None,
+        segment_time_ms: 600_000,
+        town_time_ms: 0,
+        hideout_time_ms: 0,
+    })
+    .unwrap();
+
+    (run_id, split_id)
+}
+
+#[test]
+fn test_fixtures_parse() {
+    let api = FakePoeApi::new();
+    let characters = block_on(api.get_characters("TestAccount")).unwrap();
+    assert_eq!(characters.len(), 2);
+    assert_eq!(characters[0].class, "Necromancer");
+
+    let items = block_on(api.get_items("TestAccount", "TestNecro")).unwrap();
+    let weapon = &items.items[0];
+    assert_eq!(weapon.inventory_id, "Weapon");
+    assert_eq!(weapon.socketed_items.len(), 2);
+    assert_eq!(weapon.socketed_items[0].type_line, "Raise Zombie");
+
+    let passives = block_on(api.get_passive_skills("TestAccount", "TestNecro")).unwrap();
+    assert_eq!(passives.hashes.len(), 18);
+    assert!(passives.mastery_effects.is_empty());
+}
+
+#[test]
+fn test_ascendancy_mapping() {
+    assert_eq!(get_ascendancy_name("Witch", 0), None);
+    assert_eq!(get_ascendancy_name("Witch", 1).as_deref(), Some("Necromancer"));
+    assert_eq!(get_ascendancy_name("Ranger", 1).as_deref(), Some("Warden"));
+    assert_eq!(get_ascendancy_name("Shadow", 3).as_deref(), Some("Trickster"));
+    assert_eq!(get_ascendancy_name("Scion", 1).as_deref(), Some("Ascendant"));
+    assert_eq!(get_ascendancy_name("Scion", 2), None);
+    assert_eq!(get_ascendancy_name("Necromancer", 1), None);
+}
+
+#[test]
+fn test_base_class_from_class_id() {
+    assert_eq!(get_base_class_name(0), Some("Scion"));
+    assert_eq!(get_base_class_name(3), Some("Witch"));
+    assert_eq!(get_base_class_name(6), Some("Shadow"));
+    // Unknown IDs (e.g. a new class) keep the class the API reports
+    assert_eq!(get_base_class_name(7), None);
+}
+
+#[test]
+fn test_take_snapshot_stores_base_class() {
+    let _db = setup_db();
+    let (run_id, split_id) = create_unknown_run();
+    let api = FakePoeApi::new();
+    block_on(take_snapshot(&api, run_id, Some(split_id), 600_000, "TestAccount", "TestNecro")).unwrap();
+
+    // The fixture character is ascended, so the API reports "Necromancer" as its
+    // class; the run gets the base class from the class ID and the ascendancy from both
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+    assert_eq!(run.class, "Witch");
+    assert_eq!(run.ascendancy.as_deref(), Some("Necromancer"));
+}
+
+#[test]
+fn test_take_snapshot_stores_character_data() {
+    let _db = setup_db();
+    let (run_id, split_id) = create_unknown_run();
+    let api = FakePoeApi::new();
+
+    let (snapshot_id, level) =
+        block_on(take_snapshot(&api, run_id, Some(split_id), 600_000, "TestAccount", "TestNecro")).unwrap();
+    assert_eq!(level, 42);
+    assert_eq!(api.calls(), 2);
+
+    let snapshot = Snapshot::get_by_id(snapshot_id).unwrap().unwrap();
+    assert_eq!(snapshot.split_id, Some(split_id));
+    assert_eq!(snapshot.elapsed_time_ms, 600_000);
+    assert_eq!(snapshot.character_level, 42);
+    assert_eq!(snapshot.experience, Some(7_451_580));
+    let split = Split::get_by_run(run_id).unwrap().pop().unwrap();
+    assert_eq!(split.experience, Some(7_451_580));
+
+    let items: Vec<PoeItem> = serde_json::from_str(&snapshot.items_json).unwrap();
+    let slots: Vec<&str> = items.iter().map(|i| i.inventory_id.as_str()).collect();
+    assert_eq!(slots, vec!["Weapon", "Helm"]);
+
+    let passives: PassiveSkills = serde_json::from_str(&snapshot.passive_tree_json).unwrap();
+    assert_eq!(passives.hashes.len(), 18);
+
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+    assert_eq!(run.league, "Settlers");
+}
+
+#[test]
+fn test_take_snapshot_private_profile() {
+    let _db = setup_db();
+    let (run_id, split_id) = create_unknown_run();
+    let api = FakePoeApi::private_profile();
+
+    let err = block_on(take_snapshot(&api, run_id, Some(split_id), 600_000, "TestAccount", "TestNecro"))
+        .unwrap_err();
+    assert_eq!(err.code(), "profile_private");
+    assert_eq!(api.calls(), 1);
+
+    assert!(Snapshot::get_by_run(run_id).unwrap().is_empty());
+    assert_eq!(Run::get_by_id(run_id).unwrap().unwrap().class, "Unknown");
+}
+
+#[test]
+fn test_pob_code_from_stored_snapshot() {
+    let _db = setup_db();
+    let (run_id, split_id) = create_unknown_run();
+    let api = FakePoeApi::new();
+    let (snapshot_id, _) =
+        block_on(take_snapshot(&api, run_id, Some(split_id), 600_000, "TestAccount", "TestNecro")).unwrap();
+
+    let snapshot = Snapshot::get_by_id(snapshot_id).unwrap().unwrap();
+    let run = Run::get_by_id(run_id).unwrap().unwrap();
+    let code = pob_builder::snapshot_code(&snapshot, &run).unwrap();
+    Snapshot::set_pob_code(snapshot_id, &code).unwrap();
+    assert_eq!(Snapshot::get_by_id(snapshot_id).unwrap().unwrap().pob_code.as_deref(), Some(code.as_str()));
+
+    let compressed = base64::engine::general_purpose::URL_SAFE.decode(&code).unwrap();
+    let mut xml = String::new();
+    ZlibDecoder::new(&compressed[..]).read_to_string(&mut xml).unwrap();
+    assert!(xml.contains(r#"className="Witch" ascendClassName="Necromancer""#));
+    assert!(xml.contains(r#"level="42""#));
+    assert!(xml.contains(r#"nameSpec="Raise Zombie""#));
+    assert!(xml.contains(r#"<Slot itemId="2" name="Helmet" itemPbURL=""/>"#));
+}
+
+#[test]
+fn test_reprocess_snapshots_rederives_skills_and_stats() {
+    let _db = setup_db();
+    let (run_id, split_id) = create_unknown_run();
+    let (snapshot_id, _) =
+        block_on(take_snapshot(&FakePoeApi::new(), run_id, Some(split_id), 600_000, "TestAccount", "TestNecro")).unwrap();
+    let fresh = Snapshot::get_by_id(snapshot_id).unwrap().unwrap();
+    let skills: Vec<SkillGroup> = serde_json::from_str(&fresh.skills_json).unwrap();
+    assert_eq!(skills[0].gems[0].name, "Raise Zombie");
+    let stats: BuildStats = serde_json::from_str(&fresh.stats_json).unwrap();
+    assert_eq!(stats.passive_points, 18);
+
+    // Snapshots stored before the analysis existed, and one with unreadable items
+    Snapshot::set_analysis(snapshot_id, "[]", "{}").unwrap();
+    Snapshot::insert(&NewSnapshot {
+        run_id,
+        split_id: Some(split_id),
+        timestamp: "2024-01-15T12:20:00Z".to_string(),
+        elapsed_time_ms: 1_200_000,
+        character_level: 50,
+        experience: None,
+        items_json: "not json".to_string(),
+        skills_json: "[]".to_string(),
+        passive_tree_json: "{}".to_string(),
+        stats_json: "{}".to_string(),
+        pob_code: None,
+    })
+    .unwrap();
+
+    let report = block_on(reprocess_snapshots(RunFilters::default())).unwrap();
+    assert_eq!(report, ReprocessReport { runs: 1, snapshots: 2, updated: 1, failed: 1 });
+    let reprocessed = Snapshot::get_by_id(snapshot_id).unwrap().unwrap();
+    assert_eq!((reprocessed.skills_json, reprocessed.stats_json), (fresh.skills_json, fresh.stats_json));
+
+    // Already current: nothing to update
+    assert_eq!(block_on(reprocess_snapshots(RunFilters::default())).unwrap().updated, 0);
+    let other_class = RunFilters { class: Some("Ranger".to_string()), ..RunFilters::default() };
+    assert_eq!(block_on(reprocess_snapshots(other_class)).unwrap(), ReprocessReport::default());
+}
+
+#[test]
+fn test_diff_snapshots_between_captures() {
+    let _db = setup_db();
+    let (run_id, split_id) = create_unknown_run();
+    let (first_id, _) =
+        block_on(take_snapshot(&FakePoeApi::new(), run_id, Some(split_id), 600_000, "TestAccount", "TestNecro")).unwrap();
+    let first = Snapshot::get_by_id(first_id).unwrap().unwrap();
+
+    // Later: helmet sold, Raise Zombie leveled, one passive more
+    let mut items: Vec<serde_json::Value> = serde_json::from_str(&first.items_json).unwrap();
+    items.retain(|item| item["inventoryId"] != "Helm");
+    for item in items.iter_mut() {
+        for gem in item["socketedItems"].as_array_mut().into_iter().flatten() {
+            if gem["typeLine"] == "Raise Zombie" {
+                gem["properties"] = serde_json::json!([{"name": "Level", "values": [["20", 0]]}]);
+            }
+        }
+    }
+    let mut passives: PassiveSkills = serde_json::from_str(&first.passive_tree_json).unwrap();
+    passives.hashes.push(999_999);
+    let second_id = Snapshot::insert(&NewSnapshot {
+        run_id,
+        split_id: Some(split_id),
+        timestamp: "2024-01-15T12:20:00Z".to_string(),
+        elapsed_time_ms: 1_200_000,
+        character_level: first.character_level + 5,
+        experience: None,
+        items_json: serde_json::to_string(&items).unwrap(),
+        skills_json: "[]".to_string(),
+        passive_tree_json: serde_json::to_string(&passives).unwrap(),
+        stats_json: "{}".to_string(),
+        pob_code: None,
+    })
+    .unwrap();
+
+    let diff = block_on(diff_snapshots(first_id, second_id)).unwrap();
+    assert_eq!((diff.levels_gained, diff.elapsed_time_ms), (5, 600_000));
+    assert_eq!(diff.items.len(), 1);
+    assert_eq!((diff.items[0].slot.as_str(), diff.items[0].change), ("Helmet", ItemChangeKind::Removed));
+    assert_eq!(diff.gems.len(), 1);
+    assert_eq!((diff.gems[0].name.as_str(), diff.gems[0].change), ("Raise Zombie", GemChangeKind::Leveled));
+    assert_eq!(diff.gems[0].level_after, Some(20));
+    assert_eq!(diff.passives_allocated, vec![999_999]);
+    assert!(diff.passives_refunded.is_empty());
+
+    assert_eq!(block_on(diff_snapshots(first_id, second_id + 1)).unwrap_err().code(), "not_found");
+}
+
+#[test]
+fn test_ninja_reference_snapshot() {
+    let _db = setup_db();
+    let (run_id, split_id) = create_unknown_run();
+    let (own_id, _) =
+        block_on(take_snapshot(&FakePoeApi::new(), run_id, Some(split_id), 600_000, "TestAccount", "TestNecro")).unwrap();
+    let own = Snapshot::get_by_id(own_id).unwrap().unwrap();
+
+    // A meta build: the same gear without a helmet, and one passive more
+    let items: Vec<serde_json::Value> = serde_json::from_str(&own.items_json).unwrap();
+    let mut passives: PassiveSkills = serde_json::from_str(&own.passive_tree_json).unwrap();
+    passives.hashes.push(999_999);
+    let character: NinjaCharacter = serde_json::from_value(serde_json::json!({
+        "account": "Meta#0001",
+        "name": "MetaNecro",
+        "league": "Settlers",
+        "level": 92,
+        "class": "Necromancer",
+        "items": items
+            .iter()
+            .filter(|item| item["inventoryId"] != "Helm")
+            .map(|item| serde_json::json!({"itemData": item}))
+            .collect::<Vec<_>>(),
+        "passiveSelection": passives.hashes,
+        "pathOfBuildingExport": "eNrtfQ",
+    }))
+    .unwrap();
+
+    let reference = insert_ninja_reference(&character).unwrap();
+    let run = Run::get_by_id(reference.run_id).unwrap().unwrap();
+    assert!(run.is_reference);
+    assert_eq!((run.category.as_str(), run.class.as_str()), (NINJA_CATEGORY, "Witch"));
+    assert_eq!(run.ascendancy.as_deref(), Some("Necromancer"));
+    let snapshot = Snapshot::get_by_id(reference.snapshot_id).unwrap().unwrap();
+    assert_eq!((snapshot.run_id, snapshot.character_level), (reference.run_id, 92));
+    assert_eq!(snapshot.pob_code.as_deref(), Some("eNrtfQ"));
+    // Analyzed like a captured snapshot
+    assert_ne!(snapshot.skills_json, "[]");
+
+    let diff = block_on(diff_snapshots(own_id, reference.snapshot_id)).unwrap();
+    assert_eq!(diff.items.len(), 1);
+    assert_eq!((diff.items[0].slot.as_str(), diff.items[0].change), ("Helmet", ItemChangeKind::Removed));
+    assert_eq!(diff.passives_allocated, vec![999_999]);
+}
+
+#[test]
+fn test_profile_privacy_status() {
+    let public = block_on(privacy::check(&FakePoeApi::new(), "TestAccount")).unwrap();
+    assert_eq!(public.status, PrivacyStatus::Public);
+    assert!(public.character_count.unwrap() > 0);
+    assert!(public.hints.is_empty());
+
+    let api = FakePoeApi::hidden_characters();
+    let hidden = block_on(privacy::check(&api, "TestAccount")).unwrap();
+    assert_eq!(hidden.status, PrivacyStatus::CharactersHidden);
+    assert_eq!(hidden.character_count, None);
+    assert!(hidden.hints.iter().any(|h| h.contains("Hide Characters tab")));
+    assert_eq!(api.calls(), 2, "profile page probed after the characters tab refused");
+
+    let private = block_on(privacy::check(&FakePoeApi::private_profile(), "TestAccount")).unwrap();
+    assert_eq!(private.status, PrivacyStatus::Private);
+    assert!(private.hints.iter().any(|h| h.contains("Private Profile")));
+}
+
+#[test]
+fn test_level_milestone_snapshot_has_no_split() {
+    let _db = setup_db();
+    let (run_id, _) = create_unknown_run();
+
+    let (snapshot_id, level) =
+        block_on(take_snapshot(&FakePoeApi::new(), run_id, None, 300_000, "TestAccount", "TestNecro")).unwrap();
+    assert_eq!(level, 42);
+    let snapshot = Snapshot::get_by_id(snapshot_id).unwrap().unwrap();
+    assert_eq!((snapshot.split_id, snapshot.elapsed_time_ms), (None, 300_000));
+    assert_eq!(Snapshot::get_summaries(run_id).unwrap()[0].split_id, None);
+
+    // The run's split keeps what it had
+    let split = Split::get_by_run(run_id).unwrap().pop().unwrap();
+    assert_eq!((split.experience, split.character_level), (None, None));
+}
+
+#[test]
+fn test_final_snapshot_once_per_run() {
+    let _db = setup_db();
+    let (run_id, split_id) = create_unknown_run();
+
+    let target = final_snapshot_target(run_id).unwrap().unwrap();
+    assert_eq!(
+        target,
+        FinalSnapshotTarget {
+            split_id,
+            account_name: "TestAccount".to_string(),
+            character_name: "TestNecro".to_string(),
+        }
+    );
+
+    let api = FakePoeApi::new();
+    let (snapshot_id, _) =
+        block_on(take_snapshot(&api, run_id, Some(target.split_id), 600_000, "TestAccount", "TestNecro")).unwrap();
+    Snapshot::set_final(snapshot_id).unwrap();
+    Snapshot::set_pob_code(snapshot_id, "eNrtfQ").unwrap();
+
+    let snapshot = Snapshot::get_final(run_id).unwrap().unwrap();
+    assert_eq!(snapshot.id, snapshot_id);
+    assert_eq!(snapshot.pob_code.as_deref(), Some("eNrtfQ"));
+    assert_eq!(final_snapshot_target(run_id).unwrap(), None);
+}
+
+/// A snapshot with a full inventory's worth of items JSON
+fn insert_large_snapshot(run_id: i64, split_id: i64, elapsed_time_ms: i64) -> i64 {
+    Snapshot::insert(&NewSnapshot {
+        run_id,
+        split_id: Some(split_id),
+        timestamp: "2024-01-15T12:10:00Z".to_string(),
+        elapsed_time_ms,
+        character_level: 12,
+        experience: None,
+        items_json: format!("[{}]", vec![r#"{"inventoryId":"Weapon","typeLine":"Driftwood Wand"}"#; 40].join(",")),
+        skills_json: "[]".to_string(),
+        passive_tree_json: "{}".to_string(),
+        stats_json: "{}".to_string(),
+        pob_code: None,
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_snapshot_json_compressed_and_compacted() {
+    let _db = setup_db();
+    let stored_type = |id: i64, column: &str| -> String {
+        get_db()
+            .unwrap()
+            .query_row(&format!("SELECT typeof({column}) FROM snapshots WHERE id = ?1"), [id], |row| row.get(0))
+            .unwrap()
+    };
+
+    // A finished run holding the PB, a finished run, and the run in progress
+    let (pb_run, pb_split) = create_unknown_run();
+    Run::complete(pb_run, 600_000).unwrap();
+    PersonalBest::get_or_create(&Run::get_by_id(pb_run).unwrap().unwrap(), "", 600_000).unwrap();
+    let (old_run, old_split) = create_unknown_run();
+    Run::complete(old_run, 700_000).unwrap();
+    let (live_run, live_split) = create_unknown_run();
+
+    let first = insert_large_snapshot(old_run, old_split, 100_000);
+    let last = insert_large_snapshot(old_run, old_split, 300_000);
+    let final_build = insert_large_snapshot(old_run, old_split, 200_000);
+    Snapshot::set_final(final_build).unwrap();
+    insert_large_snapshot(pb_run, pb_split, 100_000);
+    insert_large_snapshot(live_run, live_split, 100_000);
+    insert_large_snapshot(live_run, live_split, 200_000);
+
+    // Long JSON is stored compressed and reads back unchanged; short JSON stays text
+    assert_eq!(stored_type(first, "items_json"), "blob");
+    assert_eq!(stored_type(first, "skills_json"), "text");
+    let snapshot = Snapshot::get_by_id(first).unwrap().unwrap();
+    assert!(snapshot.items_json.starts_with(r#"[{"inventoryId":"Weapon""#));
+    assert_eq!(snapshot.skills_json, "[]");
+
+    // A snapshot written before compression
+    let legacy_items = format!("[{}]", vec![r#"{"inventoryId":"Helm"}"#; 40].join(","));
+    let legacy = {
+        let conn = get_db().unwrap();
+        conn.execute(
+            "INSERT INTO snapshots (run_id, split_id, timestamp, elapsed_time_ms, character_level, items_json, skills_json, passive_tree_json, stats_json)
+             VALUES (?1, ?2, '2024-01-15T12:00:00Z', 50000, 5, ?3, '[]', '{}', '{}')",
+            rusqlite::params![pb_run, pb_split, legacy_items],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    };
+
+    let report = compact(SnapshotRetention::FinalOnly).unwrap();
+    // Only the finished run without a PB loses snapshots, down to its final build
+    assert_eq!(report.snapshots_pruned, 2);
+    let kept: Vec<i64> = Snapshot::get_by_run(old_run).unwrap().iter().map(|s| s.id).collect();
+    assert_eq!(kept, [final_build]);
+    assert!(Snapshot::get_by_id(last).unwrap().is_none());
+    assert_eq!(Snapshot::get_by_run(pb_run).unwrap().len(), 2);
+    assert_eq!(Snapshot::get_by_run(live_run).unwrap().len(), 2);
+
+    assert_eq!(report.snapshots_compressed, 1);
+    assert_eq!(stored_type(legacy, "items_json"), "blob");
+    assert_eq!(Snapshot::get_by_id(legacy).unwrap().unwrap().items_json, legacy_items);
+
+    // Keeping only the latest run's snapshots still leaves the PB run's
+    let report = compact(SnapshotRetention::LastRuns(1)).unwrap();
+    assert_eq!(report.snapshots_pruned, 1);
+    assert!(Snapshot::get_by_run(old_run).unwrap().is_empty());
+    assert_eq!(Snapshot::get_by_run(pb_run).unwrap().len(), 2);
+    assert_eq!(compact(SnapshotRetention::All).unwrap().snapshots_pruned, 0);
+}
@@ -0,0 +1,195 @@
+use super::support::{block_on, setup_db};
+use crate::changelog;
+use crate::commands::{
+    check_hotkeys, get_app_changelog_state, get_obs_server, get_status_server, regenerate_status_token, set_obs_server, set_status_server, HotkeySettings,
+};
+use crate::db::{NewRun, Run, Settings};
+use crate::obs_server;
+use crate::status_server;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+fn get(port: u16, target: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).expect("Failed to connect");
+    write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+/// Read one short unmasked server frame: its first byte and text payload
+fn read_frame(socket: &mut TcpStream) -> (u8, String) {
+    let mut header = [0u8; 2];
+    socket.read_exact(&mut header).unwrap();
+    let mut payload = vec![0u8; header[1] as usize];
+    socket.read_exact(&mut payload).unwrap();
+    (header[0], String::from_utf8(payload).unwrap())
+}
+
+#[test]
+fn test_status_page_serves_live_state() {
+    let _db = setup_db();
+    Run::insert(&NewRun {
+        character_name: "TestChar".to_string(),
+        account_name: "TestAccount".to_string(),
+        class: "Witch".to_string(),
+        ascendancy: None,
+        league: "Standard".to_string(),
+        category: "Act 10 Any%".to_string(),
+        started_at: "2024-01-15T12:00:00Z".to_string(),
+        breakpoint_preset: None,
+        enabled_breakpoints: None,
+        game_version: None,
+    })
+    .unwrap();
+    status_server::update_live_state(serde_json::json!({
+        "isRunning": false,
+        "elapsedMs": 90_000,
+        "currentZone": "The Coast",
+        "lastSplit": { "name": "The Coast", "deltaMs": 2000, "isBestSegment": false },
+    }));
+
+    let port = status_server::start(0, "pairing".to_string()).unwrap();
+    // A client that connects and sends nothing doesn't hold up the others
+    let _idle = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let asked_at = std::time::Instant::now();
+    let unauthorized = get(port, "/status.json");
+    assert!(asked_at.elapsed() < std::time::Duration::from_secs(1));
+    let page = get(port, "/?token=pairing");
+    let status = get(port, "/status.json?token=pairing");
+    status_server::stop();
+
+    // Stopping frees the port, so restarting on it (e.g. a new token) works
+    assert_eq!(status_server::start(port, "repaired".to_string()).unwrap(), port);
+    let repaired = get(port, "/?token=repaired");
+    status_server::stop();
+    assert!(repaired.starts_with("HTTP/1.1 200"));
+
+    assert!(unauthorized.starts_with("HTTP/1.1 401"));
+    assert!(page.starts_with("HTTP/1.1 200") && page.contains("<html>"));
+
+    let body = status.split("\r\n\r\n").nth(1).unwrap();
+    let json: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(json["elapsedMs"], 90_000);
+    assert_eq!(json["currentZone"], "The Coast");
+    assert_eq!(json["lastSplit"]["deltaMs"], 2000);
+    assert_eq!(json["recentRuns"][0]["category"], "Act 10 Any%");
+}
+
+#[test]
+fn test_status_server_config_keeps_token() {
+    let _db = setup_db();
+    assert!(!block_on(get_status_server()).unwrap().config.enabled);
+    assert_eq!(block_on(set_status_server(false, 80)).unwrap_err().code(), "invalid_input");
+
+    // Disabled still issues a token, so it is ready when enabled later
+    let first = block_on(set_status_server(false, 8734)).unwrap();
+    assert_eq!(first.config.port, 8734);
+    assert_eq!(first.config.token.len(), 32);
+    assert!(first.urls.is_empty());
+    assert_eq!(block_on(set_status_server(false, 8735)).unwrap().config.token, first.config.token);
+
+    let regenerated = block_on(regenerate_status_token()).unwrap();
+    assert_ne!(regenerated.config.token, first.config.token);
+}
+
+#[test]
+fn test_obs_server_pushes_overlay_state() {
+    let _db = setup_db();
+    assert_eq!(block_on(set_obs_server(false, 80)).unwrap_err().code(), "invalid_input");
+    let info = block_on(set_obs_server(false, 8736)).unwrap();
+    assert!(info.url.is_empty());
+    assert_eq!(block_on(get_obs_server()).unwrap().config.port, 8736);
+
+    obs_server::publish(&serde_json::json!({ "elapsedMs": 1000, "currentZone": "The Coast" }));
+    let port = obs_server::start(0).unwrap();
+    let state = get(port, "/state.json");
+
+    let mut socket = TcpStream::connect(("127.0.0.1", port)).expect("Failed to connect");
+    write!(
+        socket,
+        "GET /ws HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n"
+    )
+    .unwrap();
+
+    // The handshake is followed by the latest state, then each published one
+    let mut handshake = Vec::new();
+    let mut byte = [0u8; 1];
+    while !handshake.ends_with(b"\r\n\r\n") {
+        socket.read_exact(&mut byte).unwrap();
+        handshake.push(byte[0]);
+    }
+    let handshake = String::from_utf8(handshake).unwrap();
+    let (opcode, first) = read_frame(&mut socket);
+    obs_server::publish(&serde_json::json!({ "elapsedMs": 2000, "currentZone": "The Mud Flats" }));
+    let (_, second) = read_frame(&mut socket);
+    obs_server::stop();
+
+    // Stopping frees the port for a restart
+    assert_eq!(obs_server::start(port).unwrap(), port);
+    let restarted = get(port, "/state.json");
+    obs_server::stop();
+    assert!(restarted.starts_with("HTTP/1.1 200"));
+
+    assert!(state.starts_with("HTTP/1.1 200"));
+    let body = state.split("\r\n\r\n").nth(1).unwrap();
+    assert_eq!(serde_json::from_str::<serde_json::Value>(body).unwrap()["currentZone"], "The Coast");
+
+    assert!(handshake.starts_with("HTTP/1.1 101"));
+    assert!(handshake.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+    assert_eq!(opcode, 0x81);
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&first).unwrap()["elapsedMs"], 1000);
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&second).unwrap()["currentZone"], "The Mud Flats");
+}
+
+#[test]
+fn test_app_changelog_state_records_version() {
+    let _db = setup_db();
+    let current = env!("CARGO_PKG_VERSION");
+
+    // A new database has nothing to announce
+    let first = changelog::record_startup().unwrap();
+    assert!(first.is_first_run && !first.is_upgrade);
+    assert_eq!(first.previous_version, None);
+    assert_eq!(Settings::get_last_app_version().unwrap(), current);
+
+    // Updated from an older version, with the migrations of this launch
+    Settings::set_last_app_version("0.0.1").unwrap();
+    let updated = changelog::record_startup().unwrap();
+    assert!(updated.is_upgrade && !updated.is_first_run);
+    assert_eq!(updated.previous_version.as_deref(), Some("0.0.1"));
+    assert!(updated.migrations.iter().any(|m| m.name == "037_add_last_app_version"));
+    assert_eq!(block_on(get_app_changelog_state()).unwrap(), updated);
+    assert_eq!(Settings::get_last_app_version().unwrap(), current);
+}
+
+#[test]
+fn test_hotkeys_checked_before_registering() {
+    let defaults = HotkeySettings::from(&Settings::default());
+    let checked = check_hotkeys(&defaults);
+    assert_eq!(checked.len(), 8);
+    assert!(checked.iter().all(|c| c.registration.error.is_none()));
+
+    // Modifier order doesn't make a different shortcut
+    let hotkeys = HotkeySettings {
+        toggle_timer: "Ctrl+".to_string(),
+        skip_split: "Shift+Ctrl+S".to_string(),
+        ..defaults
+    };
+    let errors: Vec<(String, Option<String>, Option<&str>)> = check_hotkeys(&hotkeys)
+        .into_iter()
+        .map(|c| (c.registration.hotkey, c.registration.error, c.registration.error_code))
+        .filter(|(_, error, _)| error.is_some())
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            ("toggleTimer".to_string(), Some("Invalid shortcut format: Ctrl+".to_string()), Some("invalid_input")),
+            (
+                "skipSplit".to_string(),
+                Some("Shift+Ctrl+S is already bound to manualSplit".to_string()),
+                Some("invalid_input")
+            ),
+        ]
+    );
+}
@@ -0,0 +1,88 @@
+use crate::db;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// The database is a process-wide global, so tests that touch it run one at a time
+static DB_LOCK: Mutex<()> = Mutex::new(());
+
+/// Reset the global database to a fresh in-memory schema and hold it for the test
+pub fn setup_db() -> MutexGuard<'static, ()> {
+    let guard = DB_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    db::init_in_memory().expect("Failed to initialize in-memory database");
+    guard
+}
+
+/// A Client.txt in its own temp directory, removed on drop
+pub struct TempLog {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl TempLog {
+    pub fn new() -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "poe-watcher-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&dir).expect("Failed to create temp dir");
+        let path = dir.join("Client.txt");
+        std::fs::write(&path, "").expect("Failed to create temp log");
+        TempLog { dir, path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append lines the way the game client does (one write per line)
+    pub fn append(&self, lines: &[String]) {
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .expect("Failed to open temp log");
+        for line in lines {
+            writeln!(file, "{}", line).expect("Failed to write temp log");
+        }
+    }
+}
+
+impl Drop for TempLog {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Build a Client.txt line `offset_secs` after a fixed base time
+pub fn log_line(offset_secs: i64, message: &str) -> String {
+    let base = chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+        .and_then(|d| d.and_hms_opt(12, 0, 0))
+        .expect("valid base time");
+    let ts = base + chrono::Duration::seconds(offset_secs);
+    format!(
+        "{} 12345678 abc [INFO Client 1234] : {}",
+        ts.format("%Y/%m/%d %H:%M:%S"),
+        message
+    )
+}
+
+pub fn zone_line(offset_secs: i64, zone: &str) -> String {
+    log_line(offset_secs, &format!("You have entered {}.", zone))
+}
+
+pub fn level_line(offset_secs: i64, character: &str, class: &str, level: u32) -> String {
+    log_line(offset_secs, &format!("{} ({}) is now level {}", character, class, level))
+}
+
+/// Run a future to completion on a single-threaded runtime
+pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("Failed to build test runtime")
+        .block_on(future)
+}
@@ -0,0 +1,292 @@
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::db::{NewSplit, Run, Settings};
+use crate::error::{AppError, AppResult};
+use crate::webhook::{WebhookEvent, WebhookPayload};
+
+/// Twitch chat's IRC server, over TLS so the token isn't sent in the clear
+const TWITCH_HOST: &str = "irc.chat.twitch.tv";
+const TWITCH_PORT: u16 = 6697;
+
+/// Longest chat message Twitch accepts, in characters
+const MAX_MESSAGE_CHARS: usize = 500;
+
+/// Where to post: the chat token, the account it belongs to, and the channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatConfig {
+    pub token: String,
+    pub username: String,
+    pub channel: String,
+}
+
+impl ChatConfig {
+    /// The chat settings, None until both a token and a channel are set. The
+    /// token may keep its `oauth:` prefix and the channel its `#`; without a
+    /// username the token is taken to be the channel owner's.
+    pub fn from_settings(settings: &Settings) -> Option<ChatConfig> {
+        let token = settings.twitch_token.trim();
+        let token = token.strip_prefix("oauth:").unwrap_or(token);
+        let channel = channel_name(&settings.twitch_channel);
+        if token.is_empty() || channel.is_empty() {
+            return None;
+        }
+        let username = channel_name(&settings.twitch_username);
+        Some(ChatConfig {
+            token: token.to_string(),
+            username: if username.is_empty() { channel.clone() } else { username },
+            channel,
+        })
+    }
+}
+
+/// A channel or login name as IRC wants it: lowercase, without the `#`
+pub fn channel_name(name: &str) -> String {
+    let name = name.trim();
+    name.strip_prefix('#').unwrap_or(name).to_ascii_lowercase()
+}
+
+/// Whether a channel or login name is one Twitch could have issued
+pub fn is_valid_name(name: &str) -> bool {
+    let name = channel_name(name);
+    name.is_empty() || name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Fill a message template's placeholders from a notification payload:
+/// `{character}`, `{class}`, `{category}`, `{league}`, `{time}` (the run's
+/// total), and for gold splits `{split}`, `{splitTime}`, `{segment}` and
+/// `{delta}`. Unknown placeholders are left as they are.
+pub fn render(template: &str, payload: &WebhookPayload) -> String {
+    let split = payload.split.as_ref();
+    let time = |ms: Option<i64>| ms.map(chat_time).unwrap_or_default();
+    let delta = split
+        .and_then(|s| s.delta_ms)
+        .map(|ms| format!("{}{}", if ms < 0 { "-" } else { "+" }, chat_time(ms.abs())))
+        .unwrap_or_default();
+    [
+        ("{character}", payload.character_name.clone()),
+        ("{class}", payload.class.clone()),
+        ("{category}", payload.category.clone()),
+        ("{league}", payload.league.clone()),
+        ("{time}", time(payload.total_time_ms)),
+        ("{split}", split.map(|s| s.breakpoint_name.clone()).unwrap_or_default()),
+        ("{splitTime}", time(split.map(|s| s.split_time_ms))),
+        ("{segment}", time(split.map(|s| s.segment_time_ms))),
+        ("{delta}", delta),
+    ]
+    .iter()
+    .fold(template.to_string(), |message, (placeholder, value)| message.replace(placeholder, value))
+}
+
+/// `1:02:03` / `24:31`: whole seconds read better in chat
+fn chat_time(ms: i64) -> String {
+    let seconds = ms.max(0) / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// The template setting for a payload's event; an empty one posts nothing
+fn template(settings: &Settings, payload: &WebhookPayload) -> String {
+    let template = if payload.event == WebhookEvent::PersonalBest.as_str() {
+        &settings.twitch_pb_message
+    } else if payload.event == WebhookEvent::GoldSplit.as_str() {
+        &settings.twitch_gold_message
+    } else {
+        &settings.twitch_run_message
+    };
+    template.trim().to_string()
+}
+
+/// IRC lines that log in, join the channel, post `message` and quit. Line
+/// breaks in the message would start new commands, so they become spaces.
+fn irc_lines(config: &ChatConfig, message: &str) -> (Vec<String>, Vec<String>) {
+    let message: String = message
+        .chars()
+        .map(|c| if c == '\r' || c == '\n' { ' ' } else { c })
+        .take(MAX_MESSAGE_CHARS)
+        .collect();
+    let login = vec![format!("PASS oauth:{}", config.token), format!("NICK {}", config.username)];
+    let post = vec![
+        format!("JOIN #{}", config.channel),
+        format!("PRIVMSG #{} :{}", config.channel, message.trim()),
+        "QUIT".to_string(),
+    ];
+    (login, post)
+}
+
+/// Post one message to the channel's chat, waiting for the login to be accepted
+pub fn post(config: &ChatConfig, message: &str) -> AppResult<()> {
+    let network = |e: std::io::Error| AppError::Network(format!("Twitch chat: {}", e));
+
+    let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let tls = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| AppError::Internal(format!("TLS setup failed: {}", e)))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = ServerName::try_from(TWITCH_HOST).map_err(|e| AppError::Internal(e.to_string()))?;
+    let connection = ClientConnection::new(Arc::new(tls), server_name)
+        .map_err(|e| AppError::Internal(format!("TLS setup failed: {}", e)))?;
+
+    let tcp = TcpStream::connect((TWITCH_HOST, TWITCH_PORT)).map_err(network)?;
+    tcp.set_read_timeout(Some(Duration::from_secs(10))).map_err(network)?;
+    tcp.set_write_timeout(Some(Duration::from_secs(10))).map_err(network)?;
+    let mut stream = BufReader::new(StreamOwned::new(connection, tcp));
+
+    let (login, post) = irc_lines(config, message);
+    let send = |stream: &mut BufReader<StreamOwned<ClientConnection, TcpStream>>, lines: &[String]| {
+        let writer = stream.get_mut();
+        for line in lines {
+            writer.write_all(format!("{}\r\n", line).as_bytes())?;
+        }
+        writer.flush()
+    };
+    send(&mut stream, &login).map_err(network)?;
+
+    // 001 is the welcome; a bad token gets a NOTICE and the connection closed
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if stream.read_line(&mut line).map_err(network)? == 0 {
+            return Err(AppError::Network("Twitch chat closed the connection during login".to_string()));
+        }
+        if line.contains(" 001 ") {
+            break;
+        }
+        if line.contains("NOTICE") && (line.contains("authentication failed") || line.contains("Improperly formatted")) {
+            return Err(AppError::InvalidInput(format!(
+                "Twitch rejected the chat token for {}",
+                config.username
+            )));
+        }
+    }
+    send(&mut stream, &post).map_err(network)?;
+
+    // Twitch closes the connection once QUIT is handled, after the message
+    while stream.read_line(&mut line).unwrap_or(0) > 0 {
+        line.clear();
+    }
+    Ok(())
+}
+
+/// Post a notification's message to Twitch chat, if chat is set up and the
+/// event has a message. Runs in the background; failures are logged.
+fn announce(payload: WebhookPayload) {
+    thread::spawn(move || {
+        let result: AppResult<()> = (|| {
+            let settings = Settings::load()?;
+            let Some(config) = ChatConfig::from_settings(&settings) else {
+                return Ok(());
+            };
+            let template = template(&settings, &payload);
+            if template.is_empty() {
+                return Ok(());
+            }
+            post(&config, &render(&template, &payload))
+        })();
+        if let Err(e) = result {
+            eprintln!("[twitch] Failed to post {} for run {}: {}", payload.event, payload.run_id, e);
+        }
+    });
+}
+
+/// Announce that a run completed, or set a PB
+pub fn run_completed(run_id: i64, is_pb: bool) -> AppResult<()> {
+    if let Some(run) = Run::get_by_id(run_id)? {
+        announce(WebhookPayload::run_completed(&run, is_pb));
+    }
+    Ok(())
+}
+
+/// Announce a split that beat its gold
+pub fn gold_split(run: &Run, split: &NewSplit) {
+    if !run.is_reference {
+        announce(WebhookPayload::gold_split(run, split));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::webhook::WebhookSplit;
+
+    fn payload(event: WebhookEvent, split: Option<WebhookSplit>) -> WebhookPayload {
+        WebhookPayload {
+            event: event.as_str(),
+            content: String::new(),
+            run_id: 1,
+            character_name: "Zoomer".to_string(),
+            class: "Ranger".to_string(),
+            category: "Act 10 Any%".to_string(),
+            league: "Standard".to_string(),
+            total_time_ms: Some(3_723_000),
+            is_personal_best: event == WebhookEvent::PersonalBest,
+            split,
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_templates() {
+        let pb = payload(WebhookEvent::PersonalBest, None);
+        assert_eq!(render("New {category} PB: {time}!", &pb), "New Act 10 Any% PB: 1:02:03!");
+        assert_eq!(render("{character} the {class} {unknown}", &pb), "Zoomer the Ranger {unknown}");
+
+        let gold = payload(
+            WebhookEvent::GoldSplit,
+            Some(WebhookSplit {
+                breakpoint_name: "Act 3".to_string(),
+                split_time_ms: 1_471_000,
+                segment_time_ms: 512_000,
+                delta_ms: Some(-4_000),
+            }),
+        );
+        assert_eq!(
+            render("New {split} gold: {splitTime}! ({segment}, {delta})", &gold),
+            "New Act 3 gold: 24:31! (8:32, -0:04)"
+        );
+        // Split placeholders are empty outside gold splits
+        assert_eq!(render("[{split}{delta}]", &pb), "[]");
+    }
+
+    #[test]
+    fn test_config_and_irc_lines() {
+        let settings = Settings {
+            twitch_token: " oauth:abc123 ".to_string(),
+            twitch_channel: "#StreamerName".to_string(),
+            ..Settings::default()
+        };
+        let config = ChatConfig::from_settings(&settings).unwrap();
+        assert_eq!(
+            config,
+            ChatConfig {
+                token: "abc123".to_string(),
+                username: "streamername".to_string(),
+                channel: "streamername".to_string(),
+            }
+        );
+        assert!(ChatConfig::from_settings(&Settings { twitch_channel: String::new(), ..settings.clone() }).is_none());
+        let bot = Settings { twitch_username: "SplitBot".to_string(), ..settings };
+        assert_eq!(ChatConfig::from_settings(&bot).unwrap().username, "splitbot");
+
+        assert!(is_valid_name("#Some_Channel1") && is_valid_name(""));
+        assert!(!is_valid_name("two words") && !is_valid_name("a:b"));
+
+        // A line break can't smuggle in another command
+        let (login, post) = irc_lines(&config, "PB!\r\nPRIVMSG #other :spam");
+        assert_eq!(login, ["PASS oauth:abc123", "NICK streamername"]);
+        assert_eq!(post[0], "JOIN #streamername");
+        assert_eq!(post[1], "PRIVMSG #streamername :PB!  PRIVMSG #other :spam");
+        let (_, long) = irc_lines(&config, &"x".repeat(600));
+        assert_eq!(long[1].len(), "PRIVMSG #streamername :".len() + MAX_MESSAGE_CHARS);
+    }
+}
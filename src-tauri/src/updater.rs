@@ -0,0 +1,156 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::db::Settings;
+use crate::error::{AppError, AppResult};
+
+const RELEASES: &str = "https://github.com/kburke8/poe-watcher/releases";
+
+/// Release channel updates come from, from the `update_channel` setting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    /// The channel a setting or command argument names
+    pub fn parse(value: &str) -> Option<UpdateChannel> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "stable" => Some(UpdateChannel::Stable),
+            "beta" => Some(UpdateChannel::Beta),
+            _ => None,
+        }
+    }
+
+    /// The channel the saved settings follow; anything unknown is stable
+    pub fn current() -> UpdateChannel {
+        Settings::load()
+            .ok()
+            .and_then(|settings| UpdateChannel::parse(&settings.update_channel))
+            .unwrap_or_default()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+
+    /// Update manifest for the channel. GitHub's latest release skips
+    /// prereleases, so beta builds publish theirs under the rolling `beta` tag.
+    pub fn endpoint(self) -> String {
+        match self {
+            UpdateChannel::Stable => format!("{}/latest/download/latest.json", RELEASES),
+            UpdateChannel::Beta => format!("{}/download/beta/latest.json", RELEASES),
+        }
+    }
+}
+
+/// A newer version `check_for_update` found
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: &'static str,
+    /// Release date, RFC 3339
+    pub date: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Payload of `update-progress` while `install_update_now` downloads
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateProgress {
+    pub downloaded: u64,
+    pub content_length: Option<u64>,
+}
+
+/// The update the last check found, installed by `install_update_now`
+static PENDING: Mutex<Option<Update>> = Mutex::new(None);
+
+fn plugin_error(e: tauri_plugin_updater::Error) -> AppError {
+    AppError::ExternalService(format!("Update failed: {}", e))
+}
+
+/// Check `channel` for a newer version, remembering it for `install`
+pub async fn check(app_handle: &AppHandle, channel: UpdateChannel) -> AppResult<Option<UpdateInfo>> {
+    let endpoint = Url::parse(&channel.endpoint())
+        .map_err(|e| AppError::Internal(format!("Invalid update endpoint: {}", e)))?;
+    let update = app_handle
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(plugin_error)?
+        .check()
+        .await
+        .map_err(plugin_error)?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        channel: channel.as_str(),
+        date: update
+            .date
+            .and_then(|date| chrono::DateTime::from_timestamp(date.unix_timestamp(), 0))
+            .map(|date| date.to_rfc3339()),
+        notes: update.body.clone(),
+    });
+    *PENDING.lock().unwrap() = update;
+    Ok(info)
+}
+
+/// Download and install the update the last check found (checking the saved
+/// channel if there wasn't one), then restart. Returns false when already up
+/// to date.
+pub async fn install(app_handle: &AppHandle) -> AppResult<bool> {
+    let pending = PENDING.lock().unwrap().take();
+    let update = match pending {
+        Some(update) => update,
+        None => {
+            check(app_handle, UpdateChannel::current()).await?;
+            match PENDING.lock().unwrap().take() {
+                Some(update) => update,
+                None => return Ok(false),
+            }
+        }
+    };
+
+    let mut downloaded = 0u64;
+    update
+        .download_and_install(
+            |chunk_length, content_length| {
+                downloaded += chunk_length as u64;
+                let _ = app_handle.emit("update-progress", UpdateProgress { downloaded, content_length });
+            },
+            || {},
+        )
+        .await
+        .map_err(plugin_error)?;
+    app_handle.restart()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channels() {
+        assert_eq!(UpdateChannel::parse(" Beta "), Some(UpdateChannel::Beta));
+        assert_eq!(UpdateChannel::parse("stable"), Some(UpdateChannel::Stable));
+        assert_eq!(UpdateChannel::parse("nightly"), None);
+
+        assert_eq!(
+            UpdateChannel::Stable.endpoint(),
+            "https://github.com/kburke8/poe-watcher/releases/latest/download/latest.json"
+        );
+        assert_eq!(
+            UpdateChannel::Beta.endpoint(),
+            "https://github.com/kburke8/poe-watcher/releases/download/beta/latest.json"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::db::{NewSplit, Run, Settings};
+use crate::error::{AppError, AppResult};
+use crate::splits_image::format_time;
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>` when `webhook_secret` is set
+pub const SIGNATURE_HEADER: &str = "X-PoE-Watcher-Signature";
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    RunCompleted,
+    PersonalBest,
+    GoldSplit,
+}
+
+impl WebhookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookEvent::RunCompleted => "run_completed",
+            WebhookEvent::PersonalBest => "personal_best",
+            WebhookEvent::GoldSplit => "gold_split",
+        }
+    }
+}
+
+/// The split a `gold_split` notification is for
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSplit {
+    pub breakpoint_name: String,
+    pub split_time_ms: i64,
+    pub segment_time_ms: i64,
+    pub delta_ms: Option<i64>,
+}
+
+/// Body POSTed to `webhook_url`. `content` is a one-line summary, so the URL
+/// can be a Discord webhook as is.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub event: &'static str,
+    pub content: String,
+    pub run_id: i64,
+    pub character_name: String,
+    pub class: String,
+    pub category: String,
+    pub league: String,
+    pub total_time_ms: Option<i64>,
+    pub is_personal_best: bool,
+    pub split: Option<WebhookSplit>,
+    /// Files auto-export wrote for the run, on completions
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+}
+
+impl WebhookPayload {
+    /// A completed run; PBs are sent as `personal_best`
+    pub fn run_completed(run: &Run, is_pb: bool) -> Self {
+        let (event, verb) = if is_pb {
+            (WebhookEvent::PersonalBest, "set a new personal best")
+        } else {
+            (WebhookEvent::RunCompleted, "completed a run")
+        };
+        let time = run.total_time_ms.map(|ms| format!(" in {}", format_time(ms))).unwrap_or_default();
+        WebhookPayload {
+            content: format!("{} ({}) {} of {}{}", run.character_name, run.class, verb, run.category, time),
+            ..Self::base(run, event, is_pb)
+        }
+    }
+
+    /// A split that beat its gold
+    pub fn gold_split(run: &Run, split: &NewSplit) -> Self {
+        WebhookPayload {
+            content: format!(
+                "{} ({}) got a gold split at {}: {} segment",
+                run.character_name,
+                run.class,
+                split.breakpoint_name,
+                format_time(split.segment_time_ms)
+            ),
+            split: Some(WebhookSplit {
+                breakpoint_name: split.breakpoint_name.clone(),
+                split_time_ms: split.split_time_ms,
+                segment_time_ms: split.segment_time_ms,
+                delta_ms: split.delta_ms,
+            }),
+            ..Self::base(run, WebhookEvent::GoldSplit, false)
+        }
+    }
+
+    fn base(run: &Run, event: WebhookEvent, is_pb: bool) -> Self {
+        WebhookPayload {
+            event: event.as_str(),
+            content: String::new(),
+            run_id: run.id,
+            character_name: run.character_name.clone(),
+            class: run.class.clone(),
+            category: run.category.clone(),
+            league: run.league.clone(),
+            total_time_ms: run.total_time_ms,
+            is_personal_best: is_pb,
+            split: None,
+            files: Vec::new(),
+        }
+    }
+
+    /// The payload listing the exported `files`
+    pub fn with_files(self, files: &[PathBuf]) -> Self {
+        WebhookPayload {
+            files: files.iter().map(|path| path.to_string_lossy().to_string()).collect(),
+            ..self
+        }
+    }
+}
+
+/// Check a `webhook_url` before it is saved; empty turns notifications off
+pub fn validate_url(url: &str) -> AppResult<()> {
+    let url = url.trim();
+    if !url.is_empty() && !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(AppError::InvalidInput(format!("Webhook URL must start with http(s)://: {}", url)));
+    }
+    Ok(())
+}
+
+/// `sha256=<hex>` HMAC-SHA256 of `body` keyed with `secret` (RFC 2104)
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    const BLOCK: usize = 64;
+    let mut key = [0u8; BLOCK];
+    if secret.len() > BLOCK {
+        key[..32].copy_from_slice(&Sha256::digest(secret.as_bytes()));
+    } else {
+        key[..secret.len()].copy_from_slice(secret.as_bytes());
+    }
+
+    let pad = |byte: u8| key.iter().map(|k| k ^ byte).collect::<Vec<u8>>();
+    let inner = Sha256::new().chain_update(pad(0x36)).chain_update(body).finalize();
+    let outer = Sha256::new().chain_update(pad(0x5c)).chain_update(inner).finalize();
+    format!("sha256={}", outer.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+async fn post(url: &str, secret: &str, payload: &WebhookPayload) -> AppResult<()> {
+    let body = serde_json::to_vec(payload)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize webhook payload: {}", e)))?;
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+    let mut request = client.post(url).header(reqwest::header::CONTENT_TYPE, "application/json");
+    if !secret.is_empty() {
+        request = request.header(SIGNATURE_HEADER, signature(secret, &body));
+    }
+    request
+        .body(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::Network(format!("Webhook {} failed: {}", url, e)))?;
+    Ok(())
+}
+
+/// POST `payload` to `webhook_url`, if one is set. Failures are logged.
+pub async fn send(settings: &Settings, payload: &WebhookPayload) {
+    let url = settings.webhook_url.trim();
+    if url.is_empty() {
+        return;
+    }
+    if let Err(e) = post(url, settings.webhook_secret.trim(), payload).await {
+        eprintln!("[webhook] Failed to send {} for run {}: {}", payload.event, payload.run_id, e);
+    }
+}
+
+/// `send` in the background
+fn notify(payload: WebhookPayload) {
+    tauri::async_runtime::spawn(async move {
+        match Settings::load() {
+            Ok(settings) => send(&settings, &payload).await,
+            Err(e) => eprintln!("[webhook] Failed to send {} for run {}: {}", payload.event, payload.run_id, e),
+        }
+    });
+}
+
+/// Notify that a split beat its gold. Reference runs don't set golds worth announcing.
+pub fn gold_split(run: &Run, split: &NewSplit) {
+    if !run.is_reference {
+        notify(WebhookPayload::gold_split(run, split));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than a block are hashed first
+        assert_eq!(
+            signature(&"k".repeat(100), b"body"),
+            "sha256=a3306f78bc603683d9d9d98b9304be3e3e99497c42e660d7b00f6698b0df4e89"
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::db::{Split, ZoneTime};
+
+/// Act towns (matched as substrings, so "Highgate (A9)"-style names count too)
+const TOWN_ZONES: &[&str] = &[
+    "Lioneye's Watch",
+    "The Forest Encampment",
+    "The Sarn Encampment",
+    "Highgate",
+    "Overseer's Tower",
+    "The Bridge Encampment",
+    "Oriath Docks",
+    // Path of Exile 2
+    "Clearfell Encampment",
+    "The Ardura Caravan",
+    "Ziggurat Encampment",
+    "Kingsmarch",
+];
+
+/// What a zone counts as for town/hideout time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneKind {
+    Town,
+    Hideout,
+    Field,
+}
+
+/// Classify a zone name the same way the frontend's `isTownZone`/`isHideoutZone` do
+pub fn classify_zone(zone_name: &str) -> ZoneKind {
+    let name = zone_name.to_lowercase();
+    if name.contains("hideout") {
+        ZoneKind::Hideout
+    } else if TOWN_ZONES.iter().any(|town| name.contains(&town.to_lowercase())) {
+        ZoneKind::Town
+    } else {
+        ZoneKind::Field
+    }
+}
+
+/// Cumulative town/hideout time for the active run, driven by zone-enter events
+#[derive(Debug)]
+pub struct ZoneClock {
+    run_id: Option<i64>,
+    /// Unknown until the first zone event arrives (e.g. log watcher not running)
+    current: Option<ZoneKind>,
+    entered_at: Instant,
+    town_ms: i64,
+    hideout_ms: i64,
+}
+
+impl ZoneClock {
+    pub fn new(now: Instant) -> Self {
+        ZoneClock {
+            run_id: None,
+            current: None,
+            entered_at: now,
+            town_ms: 0,
+            hideout_ms: 0,
+        }
+    }
+
+    /// Reset the totals for a new run; the zone the player is standing in carries over
+    pub fn start_run(&mut self, run_id: i64, now: Instant) {
+        self.run_id = Some(run_id);
+        self.entered_at = now;
+        self.town_ms = 0;
+        self.hideout_ms = 0;
+    }
+
+    pub fn enter_zone(&mut self, zone_name: &str, now: Instant) {
+        self.close_stint(now);
+        self.current = Some(classify_zone(zone_name));
+    }
+
+    /// Cumulative (town, hideout) time at `now`, including the current stint.
+    /// `None` if the clock isn't tracking `run_id` or hasn't seen a zone yet.
+    pub fn totals(&self, run_id: i64, now: Instant) -> Option<(i64, i64)> {
+        if self.run_id != Some(run_id) {
+            return None;
+        }
+        let stint = now.saturating_duration_since(self.entered_at).as_millis() as i64;
+        Some(match self.current? {
+            ZoneKind::Town => (self.town_ms + stint, self.hideout_ms),
+            ZoneKind::Hideout => (self.town_ms, self.hideout_ms + stint),
+            ZoneKind::Field => (self.town_ms, self.hideout_ms),
+        })
+    }
+
+    fn close_stint(&mut self, now: Instant) {
+        let stint = now.saturating_duration_since(self.entered_at).as_millis() as i64;
+        match self.current {
+            Some(ZoneKind::Town) => self.town_ms += stint,
+            Some(ZoneKind::Hideout) => self.hideout_ms += stint,
+            Some(ZoneKind::Field) | None => {}
+        }
+        self.entered_at = now;
+    }
+}
+
+static CLOCK: Mutex<Option<ZoneClock>> = Mutex::new(None);
+
+fn with_clock<T>(f: impl FnOnce(&mut ZoneClock, Instant) -> T) -> T {
+    let now = Instant::now();
+    let mut guard = CLOCK.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(|| ZoneClock::new(now)), now)
+}
+
+/// Start tracking town/hideout time for a newly created run
+pub fn start_run(run_id: i64) {
+    with_clock(|clock, now| clock.start_run(run_id, now));
+}
+
+/// Feed a zone-enter event from the log watcher
+pub fn enter_zone(zone_name: &str) {
+    with_clock(|clock, now| clock.enter_zone(zone_name, now));
+}
+
+/// Cumulative (town, hideout) time so far for `run_id`, if it is the tracked run
+pub fn totals_for_run(run_id: i64) -> Option<(i64, i64)> {
+    with_clock(|clock, now| clock.totals(run_id, now))
+}
+
+/// Time in one zone within a segment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneSpan {
+    pub zone_name: String,
+    pub kind: ZoneKind,
+    pub time_ms: i64,
+    /// Visits that overlap the segment
+    pub visits: i64,
+}
+
+/// The zones played between one split and the next
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentZones {
+    /// Split ending the segment; `None` for the time after the last split
+    pub breakpoint_name: Option<String>,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// In order of first entry within the segment
+    pub zones: Vec<ZoneSpan>,
+}
+
+/// Result of `get_zone_times`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneTimeReport {
+    pub run_id: i64,
+    pub visits: Vec<ZoneTime>,
+    pub segments: Vec<SegmentZones>,
+}
+
+fn visit_kind(visit: &ZoneTime) -> ZoneKind {
+    if visit.is_town {
+        ZoneKind::Town
+    } else if visit.is_hideout {
+        ZoneKind::Hideout
+    } else {
+        ZoneKind::Field
+    }
+}
+
+/// Cut a run's zone visits at its split times, so a visit that spans a split
+/// counts towards both segments. The visit in progress runs to `end_ms` (the
+/// finished run's total time) and is left out while that's unknown. Time after
+/// the last split gets a segment of its own when any visit reaches into it.
+pub fn by_segment(visits: &[ZoneTime], splits: &[Split], end_ms: Option<i64>) -> Vec<SegmentZones> {
+    let spans: Vec<(&ZoneTime, i64, i64)> = visits
+        .iter()
+        .filter_map(|visit| {
+            let left_at = visit.duration_ms.map(|ms| visit.entered_at_ms + ms).or(end_ms)?;
+            Some((visit, visit.entered_at_ms, left_at))
+        })
+        .collect();
+    if spans.is_empty() {
+        return Vec::new();
+    }
+
+    let mut segments: Vec<SegmentZones> = Vec::new();
+    let mut start_ms = 0;
+    for split in splits {
+        segments.push(SegmentZones {
+            breakpoint_name: Some(split.breakpoint_name.clone()),
+            start_ms,
+            end_ms: split.split_time_ms,
+            zones: Vec::new(),
+        });
+        start_ms = split.split_time_ms;
+    }
+    let last_left = spans.iter().map(|&(_, _, left_at)| left_at).max().unwrap_or(start_ms);
+    if last_left > start_ms {
+        segments.push(SegmentZones { breakpoint_name: None, start_ms, end_ms: last_left, zones: Vec::new() });
+    }
+
+    for segment in &mut segments {
+        for &(visit, entered_at, left_at) in &spans {
+            let overlap = left_at.min(segment.end_ms) - entered_at.max(segment.start_ms);
+            if overlap <= 0 {
+                continue;
+            }
+            match segment.zones.iter_mut().find(|zone| zone.zone_name == visit.zone_name) {
+                Some(zone) => {
+                    zone.time_ms += overlap;
+                    zone.visits += 1;
+                }
+                None => segment.zones.push(ZoneSpan {
+                    zone_name: visit.zone_name.clone(),
+                    kind: visit_kind(visit),
+                    time_ms: overlap,
+                    visits: 1,
+                }),
+            }
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_classify_zone() {
+        assert_eq!(classify_zone("Lioneye's Watch"), ZoneKind::Town);
+        assert_eq!(classify_zone("Highgate"), ZoneKind::Town);
+        assert_eq!(classify_zone("Coastal Hideout"), ZoneKind::Hideout);
+        assert_eq!(classify_zone("The Coast"), ZoneKind::Field);
+    }
+
+    #[test]
+    fn test_clock_accumulates_per_run() {
+        let t0 = Instant::now();
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+
+        let mut clock = ZoneClock::new(t0);
+        clock.start_run(6, at(0));
+        assert_eq!(clock.totals(6, at(5)), None, "no zone seen yet");
+        clock.enter_zone("Lioneye's Watch", at(0));
+        clock.start_run(7, at(10));
+        assert_eq!(clock.totals(7, at(25)), Some((15_000, 0)));
+
+        clock.enter_zone("The Coast", at(30));
+        clock.enter_zone("Coastal Hideout", at(90));
+        clock.enter_zone("The Mud Flats", at(95));
+        clock.enter_zone("Lioneye's Watch", at(200));
+        assert_eq!(clock.totals(7, at(203)), Some((23_000, 5_000)));
+        assert_eq!(clock.totals(8, at(203)), None);
+
+        // A new run starts from zero, still standing in town
+        clock.start_run(8, at(300));
+        assert_eq!(clock.totals(8, at(304)), Some((4_000, 0)));
+    }
+
+    #[test]
+    fn test_visits_cut_at_split_times() {
+        let visit = |zone_name: &str, entered_at_ms: i64, duration_ms: Option<i64>| ZoneTime {
+            id: 0,
+            run_id: 1,
+            zone_name: zone_name.to_string(),
+            entered_at_ms,
+            duration_ms,
+            is_town: zone_name == "Lioneye's Watch",
+            is_hideout: false,
+        };
+        let split = |name: &str, split_time_ms: i64| Split {
+            id: 0,
+            run_id: 1,
+            breakpoint_type: "zone".to_string(),
+            breakpoint_name: name.to_string(),
+            breakpoint_key: None,
+            split_time_ms,
+            delta_ms: None,
+            segment_time_ms: 0,
+            town_time_ms: 0,
+            hideout_time_ms: 0,
+            segment_town_time_ms: 0,
+            segment_hideout_time_ms: 0,
+            layout_variant: None,
+            layout_inferred: false,
+            experience: None,
+            character_level: None,
+            load_time_ms: 0,
+        };
+        let visits = [
+            visit("The Twilight Strand", 0, Some(40_000)),
+            visit("Lioneye's Watch", 40_000, Some(10_000)),
+            visit("The Coast", 50_000, Some(30_000)),
+            visit("Lioneye's Watch", 80_000, Some(5_000)),
+            visit("The Coast", 85_000, None),
+        ];
+        let splits = [split("Lioneye's Watch", 45_000), split("The Coast", 60_000)];
+
+        let segments = by_segment(&visits, &splits, Some(100_000));
+        let zones = |segment: &SegmentZones| -> Vec<(String, i64, i64)> {
+            segment.zones.iter().map(|z| (z.zone_name.clone(), z.time_ms, z.visits)).collect()
+        };
+        let names: Vec<Option<&str>> = segments.iter().map(|s| s.breakpoint_name.as_deref()).collect();
+        assert_eq!(names, vec![Some("Lioneye's Watch"), Some("The Coast"), None]);
+        assert_eq!(
+            zones(&segments[0]),
+            vec![("The Twilight Strand".into(), 40_000, 1), ("Lioneye's Watch".into(), 5_000, 1)]
+        );
+        assert_eq!(
+            zones(&segments[1]),
+            vec![("Lioneye's Watch".into(), 5_000, 1), ("The Coast".into(), 10_000, 1)]
+        );
+        assert_eq!(
+            zones(&segments[2]),
+            vec![("The Coast".into(), 35_000, 2), ("Lioneye's Watch".into(), 5_000, 1)]
+        );
+        assert_eq!(segments[0].zones[1].kind, ZoneKind::Town);
+        assert_eq!(segments[2].end_ms, 100_000);
+
+        // While the run is going, the visit in progress is left out
+        let live = by_segment(&visits, &splits, None);
+        assert_eq!(live[2].end_ms, 85_000);
+        assert!(by_segment(&[], &splits, None).is_empty());
+    }
+}
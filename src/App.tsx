@@ -12,11 +12,13 @@ import { SnapshotView } from "./components/Snapshot/SnapshotView";
 import { ComparisonView } from "./components/Comparison/ComparisonView";
 import { HistoryView } from "./components/History/HistoryView";
 import { SettingsView } from "./components/Settings/SettingsView";
-import { defaultBreakpoints } from "./config/breakpoints";
-import type { Breakpoint, WizardConfig } from "./types";
+import { WhatsNewNotice } from "./components/Shared/WhatsNewNotice";
+import { defaultBreakpoints, poe2Breakpoints } from "./config/breakpoints";
+import type { Breakpoint, BreakpointPreset, PresetBreakpoint, ReadinessMode, WizardConfig } from "./types";
 
 const BREAKPOINTS_STORAGE_KEY = 'poe-watcher-breakpoints';
 const WIZARD_CONFIG_STORAGE_KEY = 'poe-watcher-wizard-config';
+const CUSTOM_PRESET_STORAGE_KEY = 'poe-watcher-breakpoint-preset';
 
 function App() {
   const currentView = useSettingsStore((state) => state.currentView);
@@ -34,8 +36,11 @@ function App() {
 
   // Listen for overlay closed (e.g. via overlay's X button) to sync state
   useEffect(() => {
-    const unlisten = listen('overlay-closed', () => {
-      useSettingsStore.getState().setOverlayOpen(false);
+    const unlisten = listen<string | null>('overlay-closed', (event) => {
+      // Profile overlays carry their id; the main overlay doesn't
+      if (!event.payload) {
+        useSettingsStore.getState().setOverlayOpen(false);
+      }
     });
     return () => { unlisten.then((fn) => fn()); };
   }, []);
@@ -78,7 +83,7 @@ function App() {
               });
 
               // Merge with defaults to add any missing fields
-              const defaultBpMap = new Map(defaultBreakpoints.map(bp => [bp.name, bp]));
+              const defaultBpMap = new Map([...defaultBreakpoints, ...poe2Breakpoints].map(bp => [bp.name, bp]));
               const migrated = deduplicated.map((bp: Breakpoint) => {
                 const defaultBp = defaultBpMap.get(bp.name);
                 return {
@@ -111,6 +116,21 @@ function App() {
           console.error('[App] Failed to load wizard config:', e);
         }
 
+        // Load the custom breakpoint preset runs use, if one is picked and still exists
+        try {
+          const presetId = Number(localStorage.getItem(CUSTOM_PRESET_STORAGE_KEY));
+          if (presetId) {
+            const presets = await invoke<BreakpointPreset[]>('get_breakpoint_presets');
+            const preset = presets.find((p) => p.id === presetId) ?? null;
+            const presetBreakpoints = preset
+              ? await invoke<PresetBreakpoint[]>('get_preset_breakpoints', { presetId })
+              : [];
+            useSettingsStore.getState().setCustomPreset(preset, presetBreakpoints);
+          }
+        } catch (e) {
+          console.error('[App] Failed to load breakpoint preset:', e);
+        }
+
         // Load saved settings from backend
         const settings = await invoke<{
           poe_log_path: string;
@@ -129,6 +149,50 @@ function App() {
           overlay_accent_color: string;
           overlay_always_on_top: boolean;
           overlay_locked: boolean;
+          warmup_detection_enabled: boolean;
+          session_gap_minutes: number;
+          launch_on_boot: boolean;
+          start_minimized: boolean;
+          minimize_to_tray: boolean;
+          background_mode: boolean;
+          follow_game_process: boolean;
+          separate_pbs_by_patch: boolean;
+          announce_sound: boolean;
+          announce_speech: boolean;
+          announce_golds_only: boolean;
+          announce_min_behind_ms: number;
+          announce_min_ahead_ms: number;
+          gold_protection_secs: number;
+          timer_start_offset_ms: number;
+          record_api_responses: boolean;
+          api_recording_limit: number;
+          locale: string;
+          readiness_mode: ReadinessMode;
+          readiness_log_watcher: boolean;
+          readiness_account: boolean;
+          readiness_overlay: boolean;
+          readiness_preset: boolean;
+          readiness_profile_public: boolean;
+          auto_start_runs: boolean;
+          auto_start_zone: string;
+          auto_pause: boolean;
+          auto_export_dir: string;
+          auto_export_formats: string;
+          auto_export_pbs_only: boolean;
+          webhook_url: string;
+          webhook_secret: string;
+          twitch_token: string;
+          twitch_username: string;
+          twitch_channel: string;
+          twitch_pb_message: string;
+          twitch_gold_message: string;
+          twitch_run_message: string;
+          update_channel: string;
+          snapshot_retention: string;
+          snapshot_retention_runs: number;
+          snapshot_level_interval: number;
+          image_proxy_hosts: string;
+          game: string;
         } | null>('get_settings');
 
         if (settings) {
@@ -149,6 +213,53 @@ function App() {
             overlayAccentColor: settings.overlay_accent_color || 'transparent',
             overlayAlwaysOnTop: settings.overlay_always_on_top ?? true,
             overlayLocked: settings.overlay_locked ?? false,
+            warmupDetectionEnabled: settings.warmup_detection_enabled ?? true,
+            sessionGapMinutes: settings.session_gap_minutes ?? 120,
+            launchOnBoot: settings.launch_on_boot ?? false,
+            startMinimized: settings.start_minimized ?? false,
+            minimizeToTray: settings.minimize_to_tray ?? false,
+            backgroundMode: settings.background_mode ?? false,
+            followGameProcess: settings.follow_game_process ?? false,
+            separatePbsByPatch: settings.separate_pbs_by_patch ?? false,
+            announceSound: settings.announce_sound ?? false,
+            announceSpeech: settings.announce_speech ?? false,
+            announceGoldsOnly: settings.announce_golds_only ?? false,
+            announceMinBehindMs: settings.announce_min_behind_ms ?? 0,
+            announceMinAheadMs: settings.announce_min_ahead_ms ?? 0,
+            goldProtectionSecs: settings.gold_protection_secs ?? 10,
+            timerStartOffsetMs: settings.timer_start_offset_ms ?? 0,
+            recordApiResponses: settings.record_api_responses ?? false,
+            apiRecordingLimit: settings.api_recording_limit ?? 10,
+            locale: settings.locale ?? 'en-US',
+            readinessMode: settings.readiness_mode ?? 'warn',
+            readinessLogWatcher: settings.readiness_log_watcher ?? true,
+            readinessAccount: settings.readiness_account ?? true,
+            readinessOverlay: settings.readiness_overlay ?? false,
+            readinessPreset: settings.readiness_preset ?? true,
+            readinessProfilePublic: settings.readiness_profile_public ?? true,
+            autoStartRuns: settings.auto_start_runs ?? false,
+            autoStartZone: settings.auto_start_zone || 'The Twilight Strand',
+            autoPause: settings.auto_pause ?? false,
+            autoExportDir: settings.auto_export_dir ?? '',
+            autoExportFormats: settings.auto_export_formats ?? 'json',
+            autoExportPbsOnly: settings.auto_export_pbs_only ?? false,
+            webhookUrl: settings.webhook_url ?? '',
+            webhookSecret: settings.webhook_secret ?? '',
+            twitchToken: settings.twitch_token ?? '',
+            twitchUsername: settings.twitch_username ?? '',
+            twitchChannel: settings.twitch_channel ?? '',
+            twitchPbMessage: settings.twitch_pb_message ?? 'New {category} PB: {time}!',
+            twitchGoldMessage: settings.twitch_gold_message ?? 'New {split} gold: {splitTime}!',
+            twitchRunMessage: settings.twitch_run_message ?? '',
+            updateChannel: settings.update_channel === 'beta' ? 'beta' : 'stable',
+            snapshotRetention:
+              settings.snapshot_retention === 'final' || settings.snapshot_retention === 'recent'
+                ? settings.snapshot_retention
+                : 'all',
+            snapshotRetentionRuns: settings.snapshot_retention_runs ?? 20,
+            snapshotLevelInterval: settings.snapshot_level_interval ?? 0,
+            imageProxyHosts: settings.image_proxy_hosts ?? 'web.poecdn.com, www.pathofexile.com, pathofexile2.com, cdn.poe2db.tw',
+            game: settings.game === 'poe2' ? 'poe2' : 'poe1',
           });
 
           // Start log watcher if we have a path
@@ -169,6 +280,9 @@ function App() {
 
         // Load PB splits and gold splits for comparison
         await useRunStore.getState().loadPbAndGoldSplits();
+
+        // Flag a private profile on startup (no-op without an account name)
+        useSettingsStore.getState().checkProfilePrivacy();
       } catch (error) {
         console.error('Failed to initialize:', error);
       }
@@ -200,6 +314,7 @@ function App() {
       <main className="flex-1 overflow-auto">
         {renderView()}
       </main>
+      <WhatsNewNotice />
     </div>
   );
 }
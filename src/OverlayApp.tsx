@@ -4,18 +4,24 @@ import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { OverlayTimer } from './components/Overlay/OverlayTimer';
 import { OverlayZone } from './components/Overlay/OverlayZone';
+import { OverlayLevelPace } from './components/Overlay/OverlayLevelPace';
+import { OverlayBestTimes } from './components/Overlay/OverlayBestTimes';
+import { OverlayGuideStep } from './components/Overlay/OverlayGuideStep';
 import { OverlaySplit } from './components/Overlay/OverlaySplit';
 import { OverlayBreakpoints } from './components/Overlay/OverlayBreakpoints';
+import type { OverlayComponents, SplitColor } from './types';
 
 interface OverlayState {
   startTime: number | null;
   elapsedMs: number;
   isRunning: boolean;
   currentZone: string | null;
+  deaths?: number;
   lastSplit: {
     name: string;
     deltaMs: number | null;
     isBestSegment: boolean;
+    color: SplitColor;
     splitTimeMs?: number;
     segmentTimeMs?: number;
     pbSegmentTimeMs?: number | null;
@@ -23,6 +29,12 @@ interface OverlayState {
   } | null;
   upcomingBreakpoints: { name: string; pbTimeMs: number | null; pbSegmentTimeMs: number | null }[];
   opacity: number;
+  level?: number | null;
+  pbLevel?: number | null;
+  sumOfBestMs?: number | null;
+  bestPossibleMs?: number | null;
+  guideStep?: string | null;
+  guideNext?: string | null;
   // Display config
   scale?: 'small' | 'medium' | 'large';
   fontSize?: 'small' | 'medium' | 'large';
@@ -41,6 +53,16 @@ interface OverlayState {
   hotkeyToggleOverlayLock?: string;
 }
 
+// Everything is shown until the saved layout arrives
+const defaultLayout: OverlayComponents = {
+  timer: true,
+  currentSplit: true,
+  delta: true,
+  nextSplit: true,
+  deathCount: true,
+  levelPace: true,
+};
+
 const initialState: OverlayState = {
   startTime: null,
   elapsedMs: 0,
@@ -60,8 +82,14 @@ function debounce<T extends (...args: unknown[]) => unknown>(fn: T, ms: number)
   };
 }
 
+// Extra overlay windows are labelled `overlay-<profile id>`
+const profileId = getCurrentWindow().label.startsWith('overlay-')
+  ? getCurrentWindow().label.slice('overlay-'.length)
+  : null;
+
 export function OverlayApp() {
   const [state, setState] = useState<OverlayState>(initialState);
+  const [layout, setLayout] = useState<OverlayComponents>(defaultLayout);
   const [isLocked, setIsLocked] = useState(false);
   const prevScaleRef = useRef<string | undefined>(undefined);
 
@@ -79,6 +107,18 @@ export function OverlayApp() {
     };
   }, []);
 
+  // Components shown, from the backend; set_overlay_layout pushes changes live
+  useEffect(() => {
+    invoke<OverlayComponents>('get_overlay_layout').then(setLayout).catch(() => {});
+    const unlistenLayout = listen<OverlayComponents>('overlay-layout-changed', (event) => {
+      setLayout(event.payload);
+    });
+
+    return () => {
+      unlistenLayout.then((fn) => fn());
+    };
+  }, []);
+
   // Sync lock state from payload
   useEffect(() => {
     if (state.isLocked !== undefined && state.isLocked !== isLocked) {
@@ -88,8 +128,10 @@ export function OverlayApp() {
   // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [state.isLocked]);
 
-  // Sync scale changes - resize overlay window
+  // Sync scale changes - resize overlay window. Profile windows keep the
+  // size they were dragged to.
   useEffect(() => {
+    if (profileId) return;
     if (state.scale && state.scale !== prevScaleRef.current) {
       prevScaleRef.current = state.scale;
       const sizes = { small: [240, 120], medium: [320, 180], large: [420, 240] };
@@ -136,7 +178,7 @@ export function OverlayApp() {
     const savePositionDebounced = debounce(async () => {
       try {
         const position = await getCurrentWindow().outerPosition();
-        await invoke('set_overlay_position', { x: position.x, y: position.y });
+        await invoke('set_overlay_position', { x: position.x, y: position.y, profileId });
       } catch (error) {
         console.error('Failed to save position:', error);
       }
@@ -151,6 +193,28 @@ export function OverlayApp() {
     };
   }, []);
 
+  // Save size when a profile window is resized
+  useEffect(() => {
+    if (!profileId) return;
+    const saveSizeDebounced = debounce(async () => {
+      try {
+        const window = getCurrentWindow();
+        const size = (await window.innerSize()).toLogical(await window.scaleFactor());
+        await invoke('set_overlay_size', { profileId, width: size.width, height: size.height });
+      } catch (error) {
+        console.error('Failed to save size:', error);
+      }
+    }, 500);
+
+    const unlistenResize = getCurrentWindow().onResized(() => {
+      saveSizeDebounced();
+    });
+
+    return () => {
+      unlistenResize.then((fn) => fn());
+    };
+  }, []);
+
   // Handle dragging
   const handleMouseDown = useCallback((e: React.MouseEvent) => {
     if ((e.target as HTMLElement).closest('button')) return;
@@ -173,7 +237,7 @@ export function OverlayApp() {
   // Close overlay
   const handleClose = useCallback(async () => {
     try {
-      await invoke('close_overlay');
+      await invoke('close_overlay', { profileId });
     } catch (error) {
       console.error('Failed to close overlay:', error);
     }
@@ -184,10 +248,10 @@ export function OverlayApp() {
   const isTransparentAccent = accentColor === 'transparent';
   const bgOpacity = state.bgOpacity ?? 0.9;
   const windowOpacity = state.opacity ?? 0.8;
-  const showTimer = state.showTimer ?? true;
+  const showTimer = (state.showTimer ?? true) && layout.timer;
   const showZone = state.showZone ?? true;
-  const showLastSplit = state.showLastSplit ?? true;
-  const showBreakpoints = state.showBreakpoints ?? true;
+  const showLastSplit = (state.showLastSplit ?? true) && layout.currentSplit;
+  const showBreakpoints = (state.showBreakpoints ?? true) && layout.nextSplit;
   const breakpointCount = state.breakpointCount ?? 3;
   const scale = state.scale || 'medium';
   // Scale drives font size directly - ensures content fits the window
@@ -266,25 +330,56 @@ export function OverlayApp() {
           <OverlayTimer startTime={state.startTime} elapsedMs={state.elapsedMs} isRunning={state.isRunning} fontSize={fontSize} hotkeyToggleTimer={state.hotkeyToggleTimer} />
         )}
 
+        {/* Best possible time and sum of best */}
+        {showTimer && (
+          <OverlayBestTimes
+            bestPossibleMs={state.bestPossibleMs ?? null}
+            sumOfBestMs={state.sumOfBestMs ?? null}
+            fontSize={fontSize}
+          />
+        )}
+
         {/* Current zone */}
         {showZone && (
           <OverlayZone
             zoneName={state.currentZone}
+            deaths={layout.deathCount ? state.deaths ?? 0 : 0}
             fontSize={fontSize}
             isAhead={state.lastSplit?.deltaMs != null ? state.lastSplit.deltaMs < 0 : undefined}
           />
         )}
 
+        {/* Level vs PB */}
+        {showZone && layout.levelPace && (
+          <OverlayLevelPace
+            level={state.level ?? null}
+            pbLevel={state.pbLevel ?? null}
+            elapsedMs={state.elapsedMs}
+            fontSize={fontSize}
+          />
+        )}
+
+        {/* Leveling guide */}
+        {showZone && (
+          <OverlayGuideStep
+            step={state.guideStep ?? null}
+            next={state.guideNext ?? null}
+            fontSize={fontSize}
+          />
+        )}
+
         {/* Last split */}
         {showLastSplit && state.lastSplit && (
           <OverlaySplit
             name={state.lastSplit.name}
             deltaMs={state.lastSplit.deltaMs}
             isBestSegment={state.lastSplit.isBestSegment}
+            color={state.lastSplit.color}
             splitTimeMs={state.lastSplit.splitTimeMs}
             segmentTimeMs={state.lastSplit.segmentTimeMs}
             pbSegmentTimeMs={state.lastSplit.pbSegmentTimeMs}
             goldSegmentTimeMs={state.lastSplit.goldSegmentTimeMs}
+            showDelta={layout.delta}
             fontSize={fontSize}
             scale={scale}
           />
@@ -1,8 +1,11 @@
-import { useState, useMemo } from 'react';
+import { useState, useMemo, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
 import { useSettingsStore } from '../../stores/settingsStore';
 import { CustomSelect } from '../Shared/CustomSelect';
-import type { ReferenceRunData, ReferenceSplitData } from '../../types';
+import type { LadderRacer, ReferenceRunData, ReferenceSplitData } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
+import { getBreakpointKey } from '../../config/breakpoints';
 
 interface AddReferenceRunModalProps {
   isOpen: boolean;
@@ -23,6 +26,9 @@ const classAscendancies: Record<string, string[]> = {
 
 const categories = ['any%', 'all-skills', 'all-waypoints', 'glitchless'];
 
+// Ladder re-fetch interval while a racer is being followed; each fetch can time a level milestone
+const LADDER_REFRESH_MS = 2 * 60 * 1000;
+
 export function AddReferenceRunModal({ isOpen, onClose, onSuccess }: AddReferenceRunModalProps) {
   const { breakpoints, getCurrentPresetName, getEnabledBreakpointNames } = useSettingsStore();
 
@@ -35,6 +41,14 @@ export function AddReferenceRunModal({ isOpen, onClose, onSuccess }: AddReferenc
   const [useCurrentPreset, setUseCurrentPreset] = useState(true);
   const [customPreset, setCustomPreset] = useState('');
   const [splitTimes, setSplitTimes] = useState<Record<string, string>>({});
+  const [inputMode, setInputMode] = useState<'manual' | 'paste' | 'ladder'>('manual');
+  const [ladder, setLadder] = useState<LadderRacer[]>([]);
+  const [ladderCharacter, setLadderCharacter] = useState<string | null>(null);
+  const [isLoadingLadder, setIsLoadingLadder] = useState(false);
+  const [pasteText, setPasteText] = useState('');
+  // Split file exported by another tool; takes precedence over pasted text
+  const [importPath, setImportPath] = useState<string | null>(null);
+  const [pastedSplits, setPastedSplits] = useState<ReferenceSplitData[]>([]);
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
@@ -43,14 +57,83 @@ export function AddReferenceRunModal({ isOpen, onClose, onSuccess }: AddReferenc
     return breakpoints.filter((bp) => bp.isEnabled);
   }, [breakpoints]);
 
+  // Preview the pasted table as the backend will parse it
+  useEffect(() => {
+    if (inputMode === 'paste' && importPath) {
+      invoke<ReferenceSplitData[]>('parse_reference_file', { path: importPath })
+        .then(setPastedSplits)
+        .catch((err) => {
+          setPastedSplits([]);
+          setError(`Failed to read file: ${getErrorMessage(err)}`);
+        });
+      return;
+    }
+    if (inputMode !== 'paste' || !pasteText.trim()) {
+      setPastedSplits([]);
+      return;
+    }
+    invoke<ReferenceSplitData[]>('parse_reference_splits', { text: pasteText })
+      .then(setPastedSplits)
+      .catch(() => setPastedSplits([]));
+  }, [inputMode, pasteText, importPath]);
+
+  const loadLadder = useCallback(async () => {
+    setIsLoadingLadder(true);
+    try {
+      setLadder(await invoke<LadderRacer[]>('fetch_ladder', { league, class: selectedClass || null }));
+    } catch (err) {
+      setError(`Failed to load ladder: ${getErrorMessage(err)}`);
+    } finally {
+      setIsLoadingLadder(false);
+    }
+  }, [league, selectedClass]);
+
+  // Keep fetching while the ladder is shown so milestones get timed
+  useEffect(() => {
+    if (!isOpen || inputMode !== 'ladder' || ladder.length === 0) return;
+    const timer = setInterval(loadLadder, LADDER_REFRESH_MS);
+    return () => clearInterval(timer);
+  }, [isOpen, inputMode, ladder.length, loadLadder]);
+
+  const handleSelectRacer = (racer: LadderRacer) => {
+    setLadderCharacter(racer.characterName);
+    if (!sourceName.trim()) {
+      setSourceName(`Ladder #${racer.rank} ${racer.characterName}`);
+    }
+    const base = classAscendancies[racer.class]
+      ? racer.class
+      : Object.keys(classAscendancies).find((cls) => classAscendancies[cls].includes(racer.class));
+    if (base) {
+      setSelectedClass(base);
+      setAscendancy(base === racer.class ? '' : racer.class);
+    }
+  };
+
+  const handleImportFile = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [
+        { name: 'Split files', extensions: ['lss', 'json', 'xml', 'csv', 'tsv', 'txt'] },
+        { name: 'All files', extensions: ['*'] },
+      ],
+    });
+    if (typeof selected === 'string') {
+      setError(null);
+      setImportPath(selected);
+    }
+  };
+
   // Calculate total time from last split
   const totalTimeMs = useMemo(() => {
+    if (inputMode === 'paste') {
+      return pastedSplits.length > 0 ? pastedSplits[pastedSplits.length - 1].splitTimeMs : 0;
+    }
     const lastSplit = enabledBreakpoints[enabledBreakpoints.length - 1];
     if (lastSplit && splitTimes[lastSplit.name]) {
       return parseTimeInput(splitTimes[lastSplit.name]);
     }
     return 0;
-  }, [enabledBreakpoints, splitTimes]);
+  }, [inputMode, pastedSplits, enabledBreakpoints, splitTimes]);
 
   const handleSplitTimeChange = (breakpointName: string, value: string) => {
     setSplitTimes((prev) => ({
@@ -72,6 +155,45 @@ export function AddReferenceRunModal({ isOpen, onClose, onSuccess }: AddReferenc
       return;
     }
 
+    const metadata = {
+      sourceName: sourceName.trim(),
+      characterName: characterName.trim() || undefined,
+      class: selectedClass,
+      ascendancy: ascendancy || undefined,
+      category,
+      league,
+      breakpointPreset: useCurrentPreset ? getCurrentPresetName() : customPreset || undefined,
+      enabledBreakpoints: useCurrentPreset
+        ? JSON.stringify(getEnabledBreakpointNames())
+        : undefined,
+    };
+
+    if (inputMode === 'ladder') {
+      if (!ladderCharacter) {
+        setError('Pick a character from the ladder');
+        return;
+      }
+      const data: ReferenceRunData = { ...metadata, totalTimeMs: 0, splits: [] };
+      await submit(() =>
+        invoke('create_reference_run_from_ladder', { league, characterName: ladderCharacter, data })
+      );
+      return;
+    }
+
+    if (inputMode === 'paste') {
+      if (pastedSplits.length === 0) {
+        setError(importPath ? 'No split times found in file' : 'No split times found in pasted text');
+        return;
+      }
+      const data: ReferenceRunData = { ...metadata, totalTimeMs: 0, splits: [] };
+      await submit(() =>
+        importPath
+          ? invoke('create_reference_run_from_file', { path: importPath, data })
+          : invoke('create_reference_run_from_text', { text: pasteText, data })
+      );
+      return;
+    }
+
     // Build splits array
     const splits: ReferenceSplitData[] = [];
     for (const bp of enabledBreakpoints) {
@@ -82,6 +204,7 @@ export function AddReferenceRunModal({ isOpen, onClose, onSuccess }: AddReferenc
           splits.push({
             breakpointName: bp.name,
             breakpointType: bp.type,
+            breakpointKey: getBreakpointKey(bp.trigger),
             splitTimeMs: timeMs,
           });
         }
@@ -97,23 +220,18 @@ export function AddReferenceRunModal({ isOpen, onClose, onSuccess }: AddReferenc
     const finalTotalTimeMs = splits[splits.length - 1].splitTimeMs;
 
     const data: ReferenceRunData = {
-      sourceName: sourceName.trim(),
-      characterName: characterName.trim() || undefined,
-      class: selectedClass,
-      ascendancy: ascendancy || undefined,
-      category,
-      league,
-      breakpointPreset: useCurrentPreset ? getCurrentPresetName() : customPreset || undefined,
-      enabledBreakpoints: useCurrentPreset
-        ? JSON.stringify(getEnabledBreakpointNames())
-        : undefined,
+      ...metadata,
       totalTimeMs: finalTotalTimeMs,
       splits,
     };
 
+    await submit(() => invoke('create_reference_run', { data }));
+  };
+
+  const submit = async (create: () => Promise<unknown>) => {
     setIsSubmitting(true);
     try {
-      await invoke('create_reference_run', { data });
+      await create();
       onSuccess();
       onClose();
       // Reset form
@@ -124,8 +242,12 @@ export function AddReferenceRunModal({ isOpen, onClose, onSuccess }: AddReferenc
       setCategory('any%');
       setLeague('Standard');
       setSplitTimes({});
+      setPasteText('');
+      setImportPath(null);
+      setLadder([]);
+      setLadderCharacter(null);
     } catch (err) {
-      setError(`Failed to create reference run: ${err}`);
+      setError(`Failed to create reference run: ${getErrorMessage(err)}`);
     } finally {
       setIsSubmitting(false);
     }
@@ -255,25 +377,135 @@ export function AddReferenceRunModal({ isOpen, onClose, onSuccess }: AddReferenc
 
           {/* Split times */}
           <div>
-            <label className="block text-sm text-[--color-text-muted] mb-2">
-              Split Times (format: MM:SS or HH:MM:SS)
-            </label>
-            <div className="space-y-2 max-h-[300px] overflow-auto">
-              {enabledBreakpoints.map((bp) => (
-                <div key={bp.name} className="flex items-center gap-3">
-                  <span className="text-sm text-[--color-text] w-48 truncate" title={bp.name}>
-                    {bp.name}
+            <div className="flex items-center justify-between mb-2">
+              <label className="text-sm text-[--color-text-muted]">
+                {inputMode === 'manual'
+                  ? 'Split Times (format: MM:SS or HH:MM:SS)'
+                  : inputMode === 'paste'
+                    ? 'Paste Splits (one "name, time" per line, tab separated, or LiveSplit text)'
+                    : 'Ladder (level milestones, timed from the league start)'}
+              </label>
+              <div className="flex gap-3">
+                <button
+                  onClick={() => setInputMode(inputMode === 'manual' ? 'paste' : 'manual')}
+                  className="text-xs text-[--color-poe-gold] hover:text-[--color-poe-gold-light]"
+                >
+                  {inputMode === 'manual' ? 'Paste table instead' : 'Enter times manually'}
+                </button>
+                {inputMode !== 'ladder' && (
+                  <button
+                    onClick={() => setInputMode('ladder')}
+                    className="text-xs text-[--color-poe-gold] hover:text-[--color-poe-gold-light]"
+                  >
+                    Chase a ladder racer
+                  </button>
+                )}
+              </div>
+            </div>
+            {inputMode === 'ladder' ? (
+              <div className="space-y-2">
+                <div className="flex items-center gap-2 text-xs">
+                  <button
+                    onClick={loadLadder}
+                    disabled={isLoadingLadder || !league.trim()}
+                    className="px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] hover:border-[--color-poe-gold] disabled:opacity-50"
+                  >
+                    {isLoadingLadder ? 'Loading...' : ladder.length > 0 ? 'Refresh' : 'Load ladder'}
+                  </button>
+                  <span className="text-[--color-text-muted]">
+                    {league} top 200{selectedClass ? `, ${selectedClass} only` : ''}. Milestones are timed by when a
+                    fetch first sees them reached, so keep this open while they level.
                   </span>
-                  <input
-                    type="text"
-                    value={splitTimes[bp.name] || ''}
-                    onChange={(e) => handleSplitTimeChange(bp.name, e.target.value)}
-                    placeholder="MM:SS"
-                    className="w-24 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm timer-display text-center"
-                  />
                 </div>
-              ))}
-            </div>
+                {ladder.length > 0 && (
+                  <div className="max-h-[240px] overflow-auto space-y-1">
+                    {ladder.map((racer) => (
+                      <button
+                        key={racer.characterName}
+                        onClick={() => handleSelectRacer(racer)}
+                        className={`w-full flex items-center justify-between px-2 py-1 rounded text-sm text-left ${
+                          ladderCharacter === racer.characterName
+                            ? 'bg-[--color-poe-gold]/20 text-[--color-text]'
+                            : 'text-[--color-text] hover:bg-[--color-surface-elevated]'
+                        }`}
+                      >
+                        <span className="truncate">
+                          #{racer.rank} {racer.characterName}
+                          <span className="text-[--color-text-muted]"> {racer.class}{racer.dead ? ' (dead)' : ''}</span>
+                        </span>
+                        <span className="text-[--color-text-muted] shrink-0">
+                          Lv {racer.level} · {racer.milestones} timed
+                        </span>
+                      </button>
+                    ))}
+                  </div>
+                )}
+              </div>
+            ) : inputMode === 'paste' ? (
+              <div className="space-y-2">
+                <div className="flex items-center gap-2 text-xs">
+                  <button
+                    onClick={handleImportFile}
+                    className="px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] hover:border-[--color-poe-gold]"
+                  >
+                    Import file...
+                  </button>
+                  {importPath ? (
+                    <>
+                      <span className="text-[--color-text-muted] truncate" title={importPath}>
+                        {importPath.split(/[\\/]/).pop()}
+                      </span>
+                      <button
+                        onClick={() => setImportPath(null)}
+                        className="text-[--color-text-muted] hover:text-[--color-text]"
+                      >
+                        Clear
+                      </button>
+                    </>
+                  ) : (
+                    <span className="text-[--color-text-muted]">
+                      LiveSplit .lss, Exilence Next or leveling tracker JSON, a Path of Building .xml with timed leveling trees, or a CSV/TSV table
+                    </span>
+                  )}
+                </div>
+                <textarea
+                  value={pasteText}
+                  onChange={(e) => { setPasteText(e.target.value); setImportPath(null); }}
+                  rows={8}
+                  placeholder={'The Coast\t1:02\nThe Mud Flats\t3:10'}
+                  className="w-full px-3 py-2 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text] text-sm font-mono"
+                />
+                {pastedSplits.length > 0 && (
+                  <div className="max-h-[200px] overflow-auto space-y-1">
+                    {pastedSplits.map((split, i) => (
+                      <div key={i} className="flex items-center justify-between text-sm">
+                        <span className="text-[--color-text] truncate">{split.breakpointName}</span>
+                        <span className="timer-display text-[--color-text-muted]">
+                          {formatTime(split.splitTimeMs)}
+                        </span>
+                      </div>
+                    ))}
+                  </div>
+                )}
+              </div>
+            ) : (
+              <div className="space-y-2 max-h-[300px] overflow-auto">
+                {enabledBreakpoints.map((bp) => (
+                  <div key={bp.name} className="flex items-center gap-3">
+                    <span className="text-sm text-[--color-text] w-48 truncate" title={bp.name}>
+                      {bp.name}
+                    </span>
+                    <input
+                      type="text"
+                      value={splitTimes[bp.name] || ''}
+                      onChange={(e) => handleSplitTimeChange(bp.name, e.target.value)}
+                      placeholder="MM:SS"
+                      className="w-24 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm timer-display text-center"
+                    />
+                  </div>
+                ))}
+              </div>
+            )}
           </div>
 
           {/* Total time preview */}
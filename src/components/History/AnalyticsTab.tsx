@@ -1,4 +1,4 @@
-import { useMemo } from 'react';
+import { useMemo, useState } from 'react';
 import { useRunStore } from '../../stores/runStore';
 import {
   LineChart,
@@ -13,9 +13,13 @@ import {
   Legend,
 } from 'recharts';
 import { format } from 'date-fns';
+import { ABANDON_REASON_LABELS } from '../../types';
+import { SplitTrendChart } from './SplitTrendChart';
+import { ZoneHeatmap } from './ZoneHeatmap';
 
 export function AnalyticsTab() {
-  const { filteredRuns, runStats, splitStats } = useRunStore();
+  const { filteredRuns, filters, runStats, abandonmentStats, resetStats, splitStats, zoneTimeStats, zoneHeatmap } = useRunStore();
+  const [trendBreakpoint, setTrendBreakpoint] = useState<string | null>(null);
 
   // Prepare time trend data (runs over time)
   const trendData = useMemo(() => {
@@ -65,6 +69,11 @@ export function AnalyticsTab() {
           <div className="text-2xl font-bold text-[--color-text]">
             {runStats?.totalRuns ?? 0}
           </div>
+          {runStats && runStats.totalRuns > 0 && (
+            <div className="text-xs text-[--color-text-muted] mt-1">
+              {runStats.totalDeaths} death{runStats.totalDeaths === 1 ? '' : 's'}, {runStats.deathlessRuns} deathless
+            </div>
+          )}
         </div>
         <div className="p-4 bg-[--color-surface] rounded-lg">
           <div className="text-xs text-[--color-text-muted] mb-1">Completed</div>
@@ -201,6 +210,146 @@ export function AnalyticsTab() {
         </div>
       </div>
 
+      {/* Abandonment Reasons */}
+      <div className="p-4 bg-[--color-surface] rounded-lg">
+        <h3 className="text-sm font-semibold text-[--color-text] mb-4">Why Runs End Early</h3>
+        {abandonmentStats && abandonmentStats.abandonedRuns > 0 ? (
+          <div className="space-y-2">
+            {abandonmentStats.byReason.map((entry) => (
+              <div key={entry.reason} className="flex items-center gap-3 text-sm">
+                <span className="w-28 text-[--color-text]">{ABANDON_REASON_LABELS[entry.reason]}</span>
+                <div className="flex-1 h-2 bg-[--color-surface-elevated] rounded">
+                  <div
+                    className="h-2 bg-[--color-timer-behind] rounded"
+                    style={{ width: `${Math.round(entry.share * 100)}%` }}
+                  />
+                </div>
+                <span className="w-20 text-right text-[--color-text-muted]">
+                  {entry.count} ({Math.round(entry.share * 100)}%)
+                </span>
+                <span
+                  className="w-16 text-right timer-display text-[--color-text-muted]"
+                  title="Average time into the run when abandoned"
+                >
+                  {entry.averageAbandonedAtMs != null ? formatTime(entry.averageAbandonedAtMs) : '-'}
+                </span>
+              </div>
+            ))}
+            {abandonmentStats.unexplainedRuns > 0 && (
+              <div className="text-xs text-[--color-text-muted]">
+                {abandonmentStats.unexplainedRuns} unfinished run{abandonmentStats.unexplainedRuns === 1 ? '' : 's'} without a reason
+              </div>
+            )}
+          </div>
+        ) : (
+          <div className="py-4 text-center text-[--color-text-muted]">
+            No abandoned runs with a reason for the current filters
+          </div>
+        )}
+      </div>
+
+      {/* Where Runs Are Reset */}
+      <div className="p-4 bg-[--color-surface] rounded-lg">
+        <h3 className="text-sm font-semibold text-[--color-text] mb-4">
+          Where Runs Are Reset
+          {resetStats && resetStats.resets > 0 && (
+            <span className="ml-2 font-normal text-[--color-text-muted]">
+              {resetStats.resets} of {resetStats.attempts} attempts
+            </span>
+          )}
+        </h3>
+        {resetStats && resetStats.resets > 0 ? (
+          <div className="grid grid-cols-2 gap-6">
+            <div className="space-y-2">
+              {resetStats.byAct.map((entry) => (
+                <div key={entry.act} className="flex items-center gap-3 text-sm">
+                  <span className="w-16 text-[--color-text]">Act {entry.act}</span>
+                  <div className="flex-1 h-2 bg-[--color-surface-elevated] rounded">
+                    <div
+                      className="h-2 bg-[--color-timer-behind] rounded"
+                      style={{ width: `${Math.round(entry.share * 100)}%` }}
+                    />
+                  </div>
+                  <span className="w-20 text-right text-[--color-text-muted]">
+                    {entry.count} ({Math.round(entry.share * 100)}%)
+                  </span>
+                </div>
+              ))}
+            </div>
+            <div className="space-y-1">
+              {resetStats.bySplit.slice(0, 8).map((entry) => (
+                <div key={entry.breakpointKey ?? entry.breakpointName ?? ''} className="flex justify-between text-sm">
+                  <span className="text-[--color-text] truncate" title="Last split reached before the reset">
+                    {entry.breakpointName ? `After ${entry.breakpointName}` : 'Before the first split'}
+                  </span>
+                  <span className="text-[--color-text-muted]">{entry.count}</span>
+                </div>
+              ))}
+            </div>
+          </div>
+        ) : (
+          <div className="py-4 text-center text-[--color-text-muted]">
+            No reset runs for the current filters
+          </div>
+        )}
+      </div>
+
+      {/* Zone Heatmap */}
+      <div className="p-4 bg-[--color-surface] rounded-lg">
+        <h3 className="text-sm font-semibold text-[--color-text] mb-4">Where Time Goes</h3>
+        {zoneHeatmap.length > 0 ? (
+          <ZoneHeatmap entries={zoneHeatmap} />
+        ) : (
+          <div className="py-4 text-center text-[--color-text-muted]">
+            No zone times recorded for the current filters
+          </div>
+        )}
+      </div>
+
+      {/* Zone Time Table */}
+      <div className="p-4 bg-[--color-surface] rounded-lg">
+        <h3 className="text-sm font-semibold text-[--color-text] mb-4">Time per Zone</h3>
+        {zoneTimeStats.length > 0 ? (
+          <div className="overflow-auto max-h-[300px]">
+            <table className="w-full">
+              <thead className="sticky top-0 bg-[--color-surface]">
+                <tr className="border-b border-[--color-border] text-[--color-text-muted] text-xs">
+                  <th className="p-2 text-left">Zone</th>
+                  <th className="p-2 text-right" title="Per run that entered the zone, all visits together">Avg Time</th>
+                  <th className="p-2 text-right">Best Time</th>
+                  <th className="p-2 text-right">Visits</th>
+                  <th className="p-2 text-right">Runs</th>
+                </tr>
+              </thead>
+              <tbody>
+                {zoneTimeStats.map((stat) => (
+                  <tr
+                    key={stat.zoneName}
+                    className="border-b border-[--color-border] hover:bg-[--color-surface-elevated]"
+                  >
+                    <td className={`p-2 text-sm ${stat.isTown || stat.isHideout ? 'text-[--color-poe-gold]' : 'text-[--color-text]'}`}>
+                      {stat.zoneName}
+                    </td>
+                    <td className="p-2 text-right timer-display text-[--color-text-muted] text-sm">
+                      {formatTime(stat.averageTimeMs)}
+                    </td>
+                    <td className="p-2 text-right timer-display text-[--color-timer-ahead] text-sm">
+                      {formatTime(stat.bestTimeMs)}
+                    </td>
+                    <td className="p-2 text-right text-[--color-text-muted] text-sm">{stat.visits}</td>
+                    <td className="p-2 text-right text-[--color-text-muted] text-sm">{stat.runCount}</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+        ) : (
+          <div className="py-4 text-center text-[--color-text-muted]">
+            No zone times recorded for the current filters
+          </div>
+        )}
+      </div>
+
       {/* Split Stats Table */}
       <div className="p-4 bg-[--color-surface] rounded-lg">
         <h3 className="text-sm font-semibold text-[--color-text] mb-4">Per-Split Statistics</h3>
@@ -211,8 +360,11 @@ export function AnalyticsTab() {
                 <tr className="border-b border-[--color-border] text-[--color-text-muted] text-xs">
                   <th className="p-2 text-left">Breakpoint</th>
                   <th className="p-2 text-right">Avg Time</th>
+                  <th className="p-2 text-right">Median</th>
+                  <th className="p-2 text-right" title="90% of runs reached the split by this time">P90</th>
                   <th className="p-2 text-right">Best Time</th>
                   <th className="p-2 text-right">Avg Town</th>
+                  <th className="p-2 text-right" title="Experience gained per minute of segment time">XP/min</th>
                   <th className="p-2 text-right">Runs</th>
                 </tr>
               </thead>
@@ -220,18 +372,34 @@ export function AnalyticsTab() {
                 {splitStats.map((stat) => (
                   <tr
                     key={stat.breakpointName}
-                    className="border-b border-[--color-border] hover:bg-[--color-surface-elevated]"
+                    onClick={() => setTrendBreakpoint(stat.breakpointName === trendBreakpoint ? null : stat.breakpointName)}
+                    title="Show this split's trend"
+                    className={`border-b border-[--color-border] hover:bg-[--color-surface-elevated] cursor-pointer ${
+                      stat.breakpointName === trendBreakpoint ? 'bg-[--color-surface-elevated]' : ''
+                    }`}
                   >
                     <td className="p-2 text-[--color-text] text-sm">{stat.breakpointName}</td>
                     <td className="p-2 text-right timer-display text-[--color-text-muted] text-sm">
                       {formatTime(stat.averageTimeMs)}
                     </td>
+                    <td className="p-2 text-right timer-display text-[--color-text-muted] text-sm">
+                      {formatTime(stat.medianTimeMs)}
+                    </td>
+                    <td className="p-2 text-right timer-display text-[--color-text-muted] text-sm">
+                      {formatTime(stat.p90TimeMs)}
+                    </td>
                     <td className="p-2 text-right timer-display text-[--color-timer-ahead] text-sm">
                       {formatTime(stat.bestTimeMs)}
                     </td>
                     <td className="p-2 text-right timer-display text-[--color-text-muted] text-sm">
                       {formatTime(stat.averageTownTimeMs)}
                     </td>
+                    <td
+                      className="p-2 text-right text-[--color-text-muted] text-sm"
+                      title={stat.averageSegmentExperience != null ? `Avg ${stat.averageSegmentExperience.toLocaleString()} XP per segment` : undefined}
+                    >
+                      {stat.experiencePerMinute != null ? stat.experiencePerMinute.toLocaleString() : '-'}
+                    </td>
                     <td className="p-2 text-right text-[--color-text-muted] text-sm">
                       {stat.runCount}
                     </td>
@@ -246,6 +414,8 @@ export function AnalyticsTab() {
           </div>
         )}
       </div>
+
+      {trendBreakpoint && <SplitTrendChart breakpointName={trendBreakpoint} filters={filters} />}
     </div>
   );
 }
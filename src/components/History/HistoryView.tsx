@@ -1,17 +1,25 @@
 import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
 import { useRunStore } from '../../stores/runStore';
+import { exportRunsCsv, exportSplitsCsv } from '../../utils/jsonExport';
 import { RunFilter } from '../Shared/RunFilter';
 import { RunsTab } from './RunsTab';
 import { AnalyticsTab } from './AnalyticsTab';
+import { MapsTab } from './MapsTab';
 import { AddReferenceRunModal } from './AddReferenceRunModal';
-import type { RunFilters } from '../../types';
+import { ImportLogRunModal } from './ImportLogRunModal';
+import type { LiveSplitImport, RunFilters, RunJsonImport } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
 
-type TabType = 'runs' | 'analytics';
+type TabType = 'runs' | 'analytics' | 'maps';
 
 export function HistoryView() {
   const [activeTab, setActiveTab] = useState<TabType>('runs');
   const [showAddReferenceModal, setShowAddReferenceModal] = useState(false);
-  const { filters, setFilters, clearFilters, loadFilteredRuns, loadRunStats, loadSplitStats } =
+  const [showImportLogModal, setShowImportLogModal] = useState(false);
+  const [importResult, setImportResult] = useState<string | null>(null);
+  const { filters, filteredRuns, setFilters, clearFilters, loadFilteredRuns, loadRunStats, loadSplitStats } =
     useRunStore();
 
   // Load data when filters change
@@ -29,6 +37,78 @@ export function HistoryView() {
     clearFilters();
   };
 
+  // PB becomes a reference run; Best Segments are merged into the gold splits
+  const handleImportLiveSplit = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [{ name: 'LiveSplit Splits', extensions: ['lss'] }],
+    });
+    if (!path) return; // user cancelled
+
+    try {
+      const result = await invoke<LiveSplitImport>('import_livesplit', { path });
+      setImportResult(
+        `Imported LiveSplit PB as a reference run${
+          result.goldsImported > 0 ? `, ${result.goldsImported} gold split${result.goldsImported !== 1 ? 's' : ''} updated` : ''
+        }`
+      );
+      loadFilteredRuns();
+      loadRunStats();
+    } catch (error) {
+      setImportResult(`LiveSplit import failed: ${getErrorMessage(error)}`);
+    }
+  };
+
+  // A run exported from this app; someone else's comes in as a reference run
+  const handleImportJson = async () => {
+    const filePath = await open({
+      multiple: false,
+      filters: [{ name: 'JSON', extensions: ['json'] }],
+    });
+    if (!filePath) return; // user cancelled
+
+    try {
+      const result = await invoke<RunJsonImport>('import_run_json', { filePath });
+      const certificate =
+        result.certificate === 'valid'
+          ? ', certificate verified'
+          : result.certificate === 'tampered'
+            ? ', certificate does NOT match the run'
+            : result.certificate === 'unsupported_version'
+              ? ', certificate from a newer version'
+              : '';
+      setImportResult(
+        `Imported ${result.isReference ? 'a reference run' : 'your run'} with ${result.snapshotsImported} snapshot${
+          result.snapshotsImported !== 1 ? 's' : ''
+        }${certificate}`
+      );
+      loadFilteredRuns();
+      loadRunStats();
+      loadSplitStats();
+    } catch (error) {
+      setImportResult(`JSON import failed: ${getErrorMessage(error)}`);
+    }
+  };
+
+  // The runs matching the current filters, and their splits
+  const handleExportRunsCsv = async () => {
+    try {
+      const count = await exportRunsCsv(filters);
+      if (count !== null) setImportResult(`Exported ${count} run${count !== 1 ? 's' : ''} to CSV`);
+    } catch (error) {
+      setImportResult(`CSV export failed: ${getErrorMessage(error)}`);
+    }
+  };
+
+  const handleExportSplitsCsv = async () => {
+    try {
+      const count = await exportSplitsCsv(filteredRuns.map((run) => run.id));
+      if (count !== null) setImportResult(`Exported ${count} split${count !== 1 ? 's' : ''} to CSV`);
+    } catch (error) {
+      setImportResult(`CSV export failed: ${getErrorMessage(error)}`);
+    }
+  };
+
   return (
     <div className="h-full flex flex-col p-6">
       <div className="mb-4">
@@ -72,18 +152,62 @@ export function HistoryView() {
           >
             Analytics
           </button>
+          <button
+            onClick={() => setActiveTab('maps')}
+            className={`px-4 py-2 rounded-lg text-sm font-medium transition-colors ${
+              activeTab === 'maps'
+                ? 'bg-[--color-poe-gold] text-[--color-poe-darker]'
+                : 'bg-[--color-surface] text-[--color-text-muted] hover:text-[--color-text]'
+            }`}
+          >
+            Maps
+          </button>
+        </div>
+        <div className="flex items-center gap-2">
+          {importResult && <span className="text-xs text-[--color-text-muted]">{importResult}</span>}
+          <button
+            onClick={handleImportJson}
+            className="px-4 py-2 bg-[--color-surface] text-[--color-text] rounded-lg text-sm font-medium border border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 transition-colors"
+          >
+            Import JSON
+          </button>
+          <button
+            onClick={handleImportLiveSplit}
+            className="px-4 py-2 bg-[--color-surface] text-[--color-text] rounded-lg text-sm font-medium border border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 transition-colors"
+          >
+            Import LiveSplit
+          </button>
+          <button
+            onClick={() => setShowImportLogModal(true)}
+            className="px-4 py-2 bg-[--color-surface] text-[--color-text] rounded-lg text-sm font-medium border border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 transition-colors"
+          >
+            Import from Log
+          </button>
+          <button
+            onClick={handleExportRunsCsv}
+            className="px-4 py-2 bg-[--color-surface] text-[--color-text] rounded-lg text-sm font-medium border border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 transition-colors"
+          >
+            Export Runs CSV
+          </button>
+          <button
+            onClick={handleExportSplitsCsv}
+            disabled={filteredRuns.length === 0}
+            className="px-4 py-2 bg-[--color-surface] text-[--color-text] rounded-lg text-sm font-medium border border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 transition-colors disabled:opacity-50"
+          >
+            Export Splits CSV
+          </button>
+          <button
+            onClick={() => setShowAddReferenceModal(true)}
+            className="px-4 py-2 bg-[--color-poe-gem] text-white rounded-lg text-sm font-medium hover:bg-teal-600 transition-colors"
+          >
+            + Add Reference Run
+          </button>
         </div>
-        <button
-          onClick={() => setShowAddReferenceModal(true)}
-          className="px-4 py-2 bg-[--color-poe-gem] text-white rounded-lg text-sm font-medium hover:bg-teal-600 transition-colors"
-        >
-          + Add Reference Run
-        </button>
       </div>
 
       {/* Tab content */}
       <div className="flex-1 overflow-hidden">
-        {activeTab === 'runs' ? <RunsTab /> : <AnalyticsTab />}
+        {activeTab === 'runs' ? <RunsTab /> : activeTab === 'analytics' ? <AnalyticsTab /> : <MapsTab />}
       </div>
 
       {/* Add Reference Run Modal */}
@@ -95,6 +219,17 @@ export function HistoryView() {
           loadRunStats();
         }}
       />
+
+      <ImportLogRunModal
+        isOpen={showImportLogModal}
+        onClose={() => setShowImportLogModal(false)}
+        onSuccess={() => {
+          setImportResult('Imported a run from Client.txt');
+          loadFilteredRuns();
+          loadRunStats();
+          loadSplitStats();
+        }}
+      />
     </div>
   );
 }
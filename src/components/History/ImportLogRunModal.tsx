@@ -0,0 +1,132 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useSettingsStore } from '../../stores/settingsStore';
+import { CustomSelect } from '../Shared/CustomSelect';
+import { getErrorMessage } from '../../utils/errors';
+import { getBreakpointKey } from '../../config/breakpoints';
+
+interface ImportLogRunModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+  onSuccess: () => void;
+}
+
+const categories = ['any%', 'all-skills', 'all-waypoints', 'glitchless'];
+
+/** Time a race the app wasn't running for from the Client.txt it left behind */
+export function ImportLogRunModal({ isOpen, onClose, onSuccess }: ImportLogRunModalProps) {
+  const { poeLogPath, breakpoints, customPreset, getCurrentPresetName } = useSettingsStore();
+
+  const [startTime, setStartTime] = useState('');
+  const [endTime, setEndTime] = useState('');
+  const [category, setCategory] = useState('any%');
+  const [isSubmitting, setIsSubmitting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSubmit = async () => {
+    if (!startTime || !endTime) {
+      setError('Start and end times are required');
+      return;
+    }
+    setIsSubmitting(true);
+    setError(null);
+    try {
+      // The current preset's breakpoints, as the live breakpoint engine gets them
+      await invoke<number>('import_run_from_log', {
+        logPath: poeLogPath,
+        startTime,
+        endTime,
+        preset: {
+          name: getCurrentPresetName(),
+          category,
+          breakpoints: breakpoints
+            .filter((bp) => bp.isEnabled)
+            .map((bp) => ({ ...bp, key: getBreakpointKey(bp.trigger) })),
+          presetId: customPreset?.id ?? null,
+        },
+      });
+      onSuccess();
+      onClose();
+    } catch (e) {
+      setError(getErrorMessage(e));
+    } finally {
+      setIsSubmitting(false);
+    }
+  };
+
+  if (!isOpen) return null;
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
+      <div className="bg-[--color-surface] rounded-lg w-full max-w-md flex flex-col">
+        <div className="p-4 border-b border-[--color-border]">
+          <h2 className="text-lg font-semibold text-[--color-text]">Import Run from Log</h2>
+          <p className="text-sm text-[--color-text-muted]">
+            Replay part of Client.txt through the {getCurrentPresetName()} preset's breakpoints. Times are local, as
+            the log writes them.
+          </p>
+        </div>
+
+        <div className="p-4 space-y-4">
+          {error && (
+            <div className="p-3 bg-[--color-timer-behind]/20 text-[--color-timer-behind] rounded-lg text-sm">
+              {error}
+            </div>
+          )}
+
+          {!poeLogPath && (
+            <div className="text-sm text-[--color-text-muted]">Set the Client.txt path in Settings first.</div>
+          )}
+
+          <div>
+            <label className="block text-sm text-[--color-text-muted] mb-1">Run started</label>
+            <input
+              type="datetime-local"
+              step={1}
+              value={startTime}
+              onChange={(e) => setStartTime(e.target.value)}
+              className="w-full px-3 py-2 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text]"
+            />
+          </div>
+
+          <div>
+            <label className="block text-sm text-[--color-text-muted] mb-1">Run ended</label>
+            <input
+              type="datetime-local"
+              step={1}
+              value={endTime}
+              onChange={(e) => setEndTime(e.target.value)}
+              className="w-full px-3 py-2 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text]"
+            />
+          </div>
+
+          <div>
+            <label className="block text-sm text-[--color-text-muted] mb-1">Category</label>
+            <CustomSelect
+              value={category}
+              onChange={setCategory}
+              options={categories.map((cat) => ({ value: cat, label: cat }))}
+            />
+          </div>
+        </div>
+
+        <div className="p-4 border-t border-[--color-border] flex justify-end gap-3">
+          <button
+            onClick={onClose}
+            disabled={isSubmitting}
+            className="px-4 py-2 text-[--color-text-muted] hover:text-[--color-text] disabled:opacity-50"
+          >
+            Cancel
+          </button>
+          <button
+            onClick={handleSubmit}
+            disabled={isSubmitting || !poeLogPath}
+            className="px-4 py-2 bg-[--color-poe-gold] text-[--color-poe-darker] font-semibold rounded-lg hover:bg-[--color-poe-gold-light] disabled:opacity-50"
+          >
+            {isSubmitting ? 'Importing...' : 'Import Run'}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,187 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { format } from 'date-fns';
+import { getErrorMessage } from '../../utils/errors';
+import type { MapSession } from '../../types';
+
+function betweenMs(session: MapSession): number {
+  return session.hideoutTimeMs + session.townTimeMs + session.otherTimeMs;
+}
+
+export function MapsTab() {
+  const [sessions, setSessions] = useState<MapSession[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadSessions = useCallback(async () => {
+    try {
+      setSessions(await invoke<MapSession[]>('get_map_sessions'));
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  const running = sessions.find((session) => session.endedAt === null) ?? null;
+
+  useEffect(() => {
+    loadSessions();
+    const unlisten = listen<MapSession>('map-session-updated', () => loadSessions());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [loadSessions]);
+
+  // The current stint keeps growing between zone changes
+  useEffect(() => {
+    if (!running) return;
+    const interval = setInterval(async () => {
+      const live = await invoke<MapSession | null>('get_current_map_session').catch(() => null);
+      if (live) {
+        setSessions((prev) => prev.map((session) => (session.id === live.id ? live : session)));
+      }
+    }, 1000);
+    return () => clearInterval(interval);
+  }, [running?.id]);
+
+  const handleStart = async () => {
+    try {
+      await invoke('start_map_session');
+      setError(null);
+      loadSessions();
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  };
+
+  const handleStop = async () => {
+    try {
+      await invoke('stop_map_session');
+      loadSessions();
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  };
+
+  const handleDelete = async (session: MapSession) => {
+    try {
+      await invoke('delete_map_session', { sessionId: session.id });
+      loadSessions();
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  };
+
+  return (
+    <div className="h-full flex flex-col bg-[--color-surface] rounded-lg overflow-hidden">
+      <div className="p-4 border-b border-[--color-border] flex items-center justify-between gap-4">
+        <div>
+          <div className="text-[--color-text] font-medium">
+            {running ? `Mapping since ${format(new Date(running.startedAt), 'HH:mm')}` : 'No map session running'}
+          </div>
+          <div className="text-xs text-[--color-text-muted]">
+            Time in maps vs hideout, town and other areas, from Client.txt. The log watcher must be running.
+          </div>
+        </div>
+        <div className="flex items-center gap-2">
+          {error && <span className="text-xs text-[--color-timer-behind]">{error}</span>}
+          {running ? (
+            <button
+              onClick={handleStop}
+              className="px-4 py-2 bg-[--color-timer-behind] text-white rounded-lg text-sm font-medium hover:opacity-90 transition-opacity"
+            >
+              Stop Session
+            </button>
+          ) : (
+            <button
+              onClick={handleStart}
+              className="px-4 py-2 bg-[--color-poe-gold] text-[--color-poe-darker] rounded-lg text-sm font-medium hover:bg-[--color-poe-gold-light] transition-colors"
+            >
+              Start Map Session
+            </button>
+          )}
+        </div>
+      </div>
+
+      <div className="flex-1 overflow-auto">
+        <table className="w-full">
+          <thead className="sticky top-0 bg-[--color-surface]">
+            <tr className="border-b border-[--color-border] text-[--color-text-muted] text-sm">
+              <th className="p-3 text-left">Started</th>
+              <th className="p-3 text-right">Maps</th>
+              <th className="p-3 text-right">In maps</th>
+              <th className="p-3 text-right">Between maps</th>
+              <th className="p-3 text-right">Hideout / Town / Other</th>
+              <th className="p-3 text-right">Per map</th>
+              <th className="p-3 text-right">Efficiency</th>
+              <th className="p-3 text-right">Actions</th>
+            </tr>
+          </thead>
+          <tbody>
+            {sessions.length === 0 ? (
+              <tr>
+                <td colSpan={8} className="p-8 text-center text-[--color-text-muted]">
+                  No map sessions yet
+                </td>
+              </tr>
+            ) : (
+              sessions.map((session) => {
+                const between = betweenMs(session);
+                const total = session.mapTimeMs + between;
+                return (
+                  <tr key={session.id} className="border-b border-[--color-border] hover:bg-[--color-surface-elevated]">
+                    <td className="p-3 text-[--color-text-muted] text-sm">
+                      {format(new Date(session.startedAt), 'MMM d, yyyy HH:mm')}
+                      {session.endedAt === null && (
+                        <span className="ml-2 px-1.5 py-0.5 text-xs bg-[--color-poe-gold]/20 text-[--color-poe-gold] rounded">
+                          LIVE
+                        </span>
+                      )}
+                    </td>
+                    <td className="p-3 text-right text-[--color-text]">{session.mapsRun}</td>
+                    <td className="p-3 text-right timer-display text-[--color-text]">{formatTime(session.mapTimeMs)}</td>
+                    <td className="p-3 text-right timer-display text-[--color-text]">{formatTime(between)}</td>
+                    <td className="p-3 text-right timer-display text-sm text-[--color-text-muted]">
+                      {formatTime(session.hideoutTimeMs)} / {formatTime(session.townTimeMs)} / {formatTime(session.otherTimeMs)}
+                    </td>
+                    <td
+                      className="p-3 text-right timer-display text-sm text-[--color-text-muted]"
+                      title="Average time in a map, and between maps"
+                    >
+                      {session.mapsRun > 0
+                        ? `${formatTime(session.mapTimeMs / session.mapsRun)} / ${formatTime(between / session.mapsRun)}`
+                        : '--'}
+                    </td>
+                    <td className="p-3 text-right text-[--color-text]" title="Share of the session spent in maps">
+                      {total > 0 ? `${Math.round((session.mapTimeMs / total) * 100)}%` : '--'}
+                    </td>
+                    <td className="p-3 text-right">
+                      <button
+                        onClick={() => handleDelete(session)}
+                        className="px-2 py-1 text-xs text-[--color-timer-behind] hover:bg-[--color-timer-behind]/20 rounded"
+                        title="Delete session"
+                      >
+                        Delete
+                      </button>
+                    </td>
+                  </tr>
+                );
+              })
+            )}
+          </tbody>
+        </table>
+      </div>
+    </div>
+  );
+}
+
+function formatTime(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+
+  if (hours > 0) {
+    return `${hours}:${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+  }
+  return `${minutes}:${seconds.toString().padStart(2, '0')}`;
+}
@@ -0,0 +1,176 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { CustomSelect } from '../Shared/CustomSelect';
+import { getErrorMessage } from '../../utils/errors';
+import type { Run, RunTag, Split } from '../../types';
+
+interface RunNotesModalProps {
+  run: Run;
+  onClose: () => void;
+  onSaved: () => void;
+}
+
+/** Annotate a run: a note on the whole run, and tags on it or its splits */
+export function RunNotesModal({ run, onClose, onSaved }: RunNotesModalProps) {
+  const [note, setNote] = useState(run.notes ?? '');
+  const [tags, setTags] = useState<RunTag[]>([]);
+  const [splits, setSplits] = useState<Split[]>([]);
+  const [newTag, setNewTag] = useState('');
+  const [tagSplitId, setTagSplitId] = useState('');
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    Promise.all([
+      invoke<RunTag[]>('get_run_tags', { runId: run.id }),
+      invoke<Split[]>('get_splits', { runId: run.id }),
+    ])
+      .then(([runTags, runSplits]) => {
+        setTags(runTags);
+        setSplits(runSplits);
+      })
+      .catch((e) => setError(getErrorMessage(e)));
+  }, [run.id]);
+
+  const splitName = (splitId: number | null) =>
+    splitId === null ? null : splits.find((s) => s.id === splitId)?.breakpointName ?? 'Split';
+
+  const handleAddTag = async () => {
+    if (!newTag.trim()) return;
+    setError(null);
+    try {
+      const tag = await invoke<RunTag>('add_run_tag', {
+        runId: run.id,
+        tag: newTag,
+        splitId: tagSplitId ? Number(tagSplitId) : null,
+      });
+      setTags((current) => (current.some((t) => t.id === tag.id) ? current : [...current, tag]));
+      setNewTag('');
+    } catch (e) {
+      setError(getErrorMessage(e));
+    }
+  };
+
+  const handleRemoveTag = async (tagId: number) => {
+    setError(null);
+    try {
+      await invoke('remove_run_tag', { tagId });
+      setTags((current) => current.filter((t) => t.id !== tagId));
+    } catch (e) {
+      setError(getErrorMessage(e));
+    }
+  };
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    setError(null);
+    try {
+      await invoke('set_run_note', { runId: run.id, note });
+      onSaved();
+      onClose();
+    } catch (e) {
+      setError(getErrorMessage(e));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
+      <div className="bg-[--color-surface] rounded-lg w-full max-w-md flex flex-col">
+        <div className="p-4 border-b border-[--color-border]">
+          <h2 className="text-lg font-semibold text-[--color-text]">Notes and Tags</h2>
+          <p className="text-sm text-[--color-text-muted]">
+            {run.characterName || run.character || 'Unknown'} - {run.category}
+          </p>
+        </div>
+
+        <div className="p-4 space-y-4">
+          {error && (
+            <div className="p-3 bg-[--color-timer-behind]/20 text-[--color-timer-behind] rounded-lg text-sm">
+              {error}
+            </div>
+          )}
+
+          <div>
+            <label className="block text-sm text-[--color-text-muted] mb-1">Note</label>
+            <textarea
+              value={note}
+              onChange={(e) => setNote(e.target.value)}
+              rows={3}
+              placeholder="New build test"
+              className="w-full px-3 py-2 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text]"
+            />
+          </div>
+
+          <div>
+            <label className="block text-sm text-[--color-text-muted] mb-1">Tags</label>
+            <div className="flex flex-wrap gap-2 mb-2">
+              {tags.length === 0 && <span className="text-sm text-[--color-text-muted]">No tags yet</span>}
+              {tags.map((tag) => (
+                <span
+                  key={tag.id}
+                  className="flex items-center gap-1 px-2 py-0.5 text-xs bg-[--color-surface-elevated] text-[--color-text] rounded"
+                >
+                  {tag.tag}
+                  {tag.splitId !== null && (
+                    <span className="text-[--color-text-muted]">@ {splitName(tag.splitId)}</span>
+                  )}
+                  <button
+                    onClick={() => handleRemoveTag(tag.id)}
+                    className="ml-1 text-[--color-text-muted] hover:text-[--color-timer-behind]"
+                    title="Remove tag"
+                  >
+                    x
+                  </button>
+                </span>
+              ))}
+            </div>
+            <div className="flex gap-2">
+              <input
+                type="text"
+                value={newTag}
+                onChange={(e) => setNewTag(e.target.value)}
+                onKeyDown={(e) => e.key === 'Enter' && handleAddTag()}
+                placeholder="lag spike"
+                className="flex-1 px-3 py-2 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text]"
+              />
+              <CustomSelect
+                value={tagSplitId}
+                onChange={setTagSplitId}
+                className="min-w-[130px]"
+                options={[
+                  { value: '', label: 'Whole run' },
+                  ...splits.map((s) => ({ value: String(s.id), label: s.breakpointName })),
+                ]}
+              />
+              <button
+                onClick={handleAddTag}
+                className="px-3 py-2 bg-[--color-surface-elevated] text-[--color-text] rounded-lg hover:bg-[--color-border]"
+              >
+                Add
+              </button>
+            </div>
+          </div>
+        </div>
+
+        <div className="p-4 border-t border-[--color-border] flex justify-end gap-3">
+          <button
+            onClick={onClose}
+            disabled={isSaving}
+            className="px-4 py-2 text-[--color-text-muted] hover:text-[--color-text] disabled:opacity-50"
+          >
+            Close
+          </button>
+          <button
+            onClick={handleSave}
+            disabled={isSaving}
+            className="px-4 py-2 bg-[--color-poe-gold] text-[--color-poe-darker] font-semibold rounded-lg hover:bg-[--color-poe-gold-light] disabled:opacity-50"
+          >
+            {isSaving ? 'Saving...' : 'Save Note'}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
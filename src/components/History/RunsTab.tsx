@@ -1,22 +1,56 @@
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useRunStore } from '../../stores/runStore';
 import { useSettingsStore } from '../../stores/settingsStore';
-import { exportRunToJson } from '../../utils/jsonExport';
+import {
+  copyRunCertificate,
+  exportRunLiveSplit,
+  exportRunTable,
+  exportRunToJson,
+  exportShareCard,
+  exportSplitsImage,
+} from '../../utils/jsonExport';
 import { format } from 'date-fns';
-import type { Run } from '../../types';
+import type { Run, RunPage } from '../../types';
+import { RunNotesModal } from './RunNotesModal';
 
 type SortField = 'startedAt' | 'totalTimeMs' | 'class' | 'category';
 type SortDirection = 'asc' | 'desc';
 
+// Runs fetched per page of the table
+const PAGE_SIZE = 50;
+
 export function RunsTab() {
-  const { filteredRuns, loadFilteredRuns } = useRunStore();
+  const { filters, filteredRuns, loadFilteredRuns } = useRunStore();
   const { setCurrentView } = useSettingsStore();
   const [sortField, setSortField] = useState<SortField>('startedAt');
   const [sortDirection, setSortDirection] = useState<SortDirection>('desc');
+  const [pageRuns, setPageRuns] = useState<Run[]>([]);
+  const [total, setTotal] = useState(0);
+  const [notesRun, setNotesRun] = useState<Run | null>(null);
+
+  // Back to the first page whenever the filtered runs are reloaded
+  useEffect(() => {
+    invoke<RunPage>('get_runs_paged', { offset: 0, limit: PAGE_SIZE, filters })
+      .then((page) => {
+        setPageRuns(page.runs);
+        setTotal(page.total);
+      })
+      .catch((error) => console.error('[RunsTab] Failed to load runs:', error));
+  }, [filters, filteredRuns]);
+
+  const handleLoadMore = async () => {
+    try {
+      const page = await invoke<RunPage>('get_runs_paged', { offset: pageRuns.length, limit: PAGE_SIZE, filters });
+      setPageRuns((runs) => [...runs, ...page.runs]);
+      setTotal(page.total);
+    } catch (error) {
+      console.error('[RunsTab] Failed to load more runs:', error);
+    }
+  };
 
-  // Sort runs
-  const sortedRuns = [...filteredRuns].sort((a, b) => {
+  // Sort the loaded runs
+  const sortedRuns = [...pageRuns].sort((a, b) => {
     let comparison = 0;
 
     switch (sortField) {
@@ -50,6 +84,8 @@ export function RunsTab() {
     try {
       await invoke('delete_run', { runId: run.id });
       loadFilteredRuns();
+      // Deleting a run that held golds rebuilds them
+      useRunStore.getState().loadPbAndGoldSplits();
     } catch (error) {
       console.error('[RunsTab] Failed to delete run:', error);
     }
@@ -139,6 +175,11 @@ export function RunsTab() {
                     {run.sourceName && (
                       <div className="text-xs text-[--color-text-muted]">{run.sourceName}</div>
                     )}
+                    {run.notes && (
+                      <div className="text-xs text-[--color-text-muted] italic truncate max-w-[220px]" title={run.notes}>
+                        {run.notes}
+                      </div>
+                    )}
                   </td>
                   <td className="p-3 text-[--color-text]">
                     {run.class}
@@ -179,6 +220,13 @@ export function RunsTab() {
                       >
                         View
                       </button>
+                      <button
+                        onClick={() => setNotesRun(run)}
+                        className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text] hover:bg-[--color-surface-elevated] rounded"
+                        title="Edit the run's note and tags"
+                      >
+                        Notes
+                      </button>
                       <button
                         onClick={() => exportRunToJson(run.id, run)}
                         className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text] hover:bg-[--color-surface-elevated] rounded"
@@ -186,6 +234,45 @@ export function RunsTab() {
                       >
                         Export
                       </button>
+                      <button
+                        onClick={() => exportRunTable(run.id, run)}
+                        className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text] hover:bg-[--color-surface-elevated] rounded"
+                        title="Save splits table as CSV, Markdown or HTML"
+                      >
+                        Table
+                      </button>
+                      <button
+                        onClick={() => exportRunLiveSplit(run.id, run)}
+                        className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text] hover:bg-[--color-surface-elevated] rounded"
+                        title="Save as LiveSplit splits (.lss) with your PB and golds"
+                      >
+                        LiveSplit
+                      </button>
+                      <button
+                        onClick={() => exportSplitsImage(run.id, run)}
+                        className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text] hover:bg-[--color-surface-elevated] rounded"
+                        title="Save splits table as a PNG image"
+                      >
+                        Image
+                      </button>
+                      {run.isCompleted && (
+                        <button
+                          onClick={() => exportShareCard(run.id, run)}
+                          className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text] hover:bg-[--color-surface-elevated] rounded"
+                          title="Save a shareable card with the final time"
+                        >
+                          Card
+                        </button>
+                      )}
+                      {run.isCompleted && (
+                        <button
+                          onClick={() => copyRunCertificate(run.id).catch((err) => console.error('[RunsTab] Failed to copy certificate:', err))}
+                          className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text] hover:bg-[--color-surface-elevated] rounded"
+                          title="Copy the run's tamper-evident certificate (splits, times, app version)"
+                        >
+                          Certificate
+                        </button>
+                      )}
                       <button
                         onClick={() => handleDelete(run)}
                         className="px-2 py-1 text-xs text-[--color-timer-behind] hover:bg-[--color-timer-behind]/20 rounded"
@@ -203,9 +290,28 @@ export function RunsTab() {
       </div>
 
       {/* Footer with count */}
-      <div className="p-3 border-t border-[--color-border] text-sm text-[--color-text-muted]">
-        {filteredRuns.length} run{filteredRuns.length !== 1 ? 's' : ''} found
+      <div className="p-3 border-t border-[--color-border] text-sm text-[--color-text-muted] flex items-center justify-between">
+        <span>
+          {total} run{total !== 1 ? 's' : ''} found
+          {pageRuns.length < total && ` (showing ${pageRuns.length})`}
+        </span>
+        {pageRuns.length < total && (
+          <button
+            onClick={handleLoadMore}
+            className="px-3 py-1 text-xs bg-[--color-surface-elevated] hover:bg-[--color-border] rounded transition-colors"
+          >
+            Load more
+          </button>
+        )}
       </div>
+
+      {notesRun && (
+        <RunNotesModal
+          run={notesRun}
+          onClose={() => setNotesRun(null)}
+          onSaved={() => loadFilteredRuns()}
+        />
+      )}
     </div>
   );
 }
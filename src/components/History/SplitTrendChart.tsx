@@ -0,0 +1,88 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { LineChart, Line, XAxis, YAxis, CartesianGrid, Tooltip, ResponsiveContainer, Legend } from 'recharts';
+import { format } from 'date-fns';
+import type { RunFilters, SplitTrendPoint } from '../../types';
+
+interface SplitTrendChartProps {
+  breakpointName: string;
+  filters: RunFilters;
+}
+
+/** A breakpoint's split time over the latest filtered runs, with its rolling average */
+export function SplitTrendChart({ breakpointName, filters }: SplitTrendChartProps) {
+  const [points, setPoints] = useState<SplitTrendPoint[]>([]);
+
+  useEffect(() => {
+    invoke<SplitTrendPoint[]>('get_split_trends', { filters, breakpointName })
+      .then(setPoints)
+      .catch((error) => console.error('[SplitTrendChart] Failed to load split trends:', error));
+  }, [breakpointName, filters]);
+
+  const data = points.map((point) => ({
+    date: format(new Date(point.startedAt), 'MMM d'),
+    time: point.splitTimeMs,
+    average: point.rollingAverageMs,
+  }));
+
+  return (
+    <div className="p-4 bg-[--color-surface] rounded-lg">
+      <h3 className="text-sm font-semibold text-[--color-text] mb-4">{breakpointName} Over Time</h3>
+      {data.length > 0 ? (
+        <ResponsiveContainer width="100%" height={250}>
+          <LineChart data={data}>
+            <CartesianGrid strokeDasharray="3 3" stroke="rgba(255,255,255,0.1)" />
+            <XAxis dataKey="date" stroke="rgba(255,255,255,0.5)" tick={{ fill: 'rgba(255,255,255,0.5)', fontSize: 11 }} />
+            <YAxis
+              stroke="rgba(255,255,255,0.5)"
+              tick={{ fill: 'rgba(255,255,255,0.5)', fontSize: 11 }}
+              tickFormatter={(value) => formatTime(value)}
+            />
+            <Tooltip
+              contentStyle={{
+                backgroundColor: 'var(--color-surface-elevated)',
+                border: '1px solid var(--color-border)',
+                borderRadius: '8px',
+              }}
+              labelStyle={{ color: 'var(--color-text)' }}
+              formatter={(value: number, name: string) => [formatTime(value), name]}
+            />
+            <Legend />
+            <Line
+              type="monotone"
+              dataKey="time"
+              name="Split time"
+              stroke="var(--color-poe-gold)"
+              strokeWidth={1}
+              dot={{ fill: 'var(--color-poe-gold)', strokeWidth: 0, r: 3 }}
+            />
+            <Line
+              type="monotone"
+              dataKey="average"
+              name="Rolling average"
+              stroke="var(--color-timer-ahead)"
+              strokeWidth={2}
+              dot={false}
+            />
+          </LineChart>
+        </ResponsiveContainer>
+      ) : (
+        <div className="h-[250px] flex items-center justify-center text-[--color-text-muted]">
+          No runs reached this split for the current filters
+        </div>
+      )}
+    </div>
+  );
+}
+
+function formatTime(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+
+  if (hours > 0) {
+    return `${hours}:${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+  }
+  return `${minutes}:${seconds.toString().padStart(2, '0')}`;
+}
@@ -0,0 +1,46 @@
+import type { ZoneHeatmapEntry } from '../../types';
+
+interface ZoneHeatmapProps {
+  entries: ZoneHeatmapEntry[];
+}
+
+/** One tile per zone, shaded by its share of the time spent across the filtered runs */
+export function ZoneHeatmap({ entries }: ZoneHeatmapProps) {
+  const maxTimeMs = Math.max(...entries.map((entry) => entry.totalTimeMs), 1);
+
+  return (
+    <div className="grid grid-cols-[repeat(auto-fill,minmax(140px,1fr))] gap-1 max-h-[300px] overflow-auto">
+      {entries.map((entry) => {
+        const heat = entry.totalTimeMs / maxTimeMs;
+        return (
+          <div
+            key={entry.zoneName}
+            title={`${entry.visits} visits over ${entry.runCount} runs`}
+            className="p-2 rounded border border-[--color-border]"
+            style={{ backgroundColor: `rgba(220, 80, 60, ${(0.08 + heat * 0.72).toFixed(2)})` }}
+          >
+            <div
+              className={`text-xs truncate ${entry.isTown || entry.isHideout ? 'text-[--color-poe-gold]' : 'text-[--color-text]'}`}
+            >
+              {entry.zoneName}
+            </div>
+            <div className="text-xs timer-display text-[--color-text]">{formatTime(entry.totalTimeMs)}</div>
+            <div className="text-xs text-[--color-text-muted]">
+              avg {formatTime(entry.averageTimeMs)}
+              {entry.deaths > 0 && ` · ${entry.deaths} ${entry.deaths === 1 ? 'death' : 'deaths'}`}
+            </div>
+          </div>
+        );
+      })}
+    </div>
+  );
+}
+
+function formatTime(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+  const mmss = `${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+  return hours > 0 ? `${hours}:${mmss}` : mmss;
+}
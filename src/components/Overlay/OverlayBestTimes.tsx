@@ -0,0 +1,30 @@
+interface OverlayBestTimesProps {
+  sumOfBestMs: number | null;
+  bestPossibleMs: number | null;
+  fontSize?: 'small' | 'medium' | 'large';
+}
+
+function formatTime(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+  return `${hours}:${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+}
+
+/** "BPT 1:02:13 · SoB 0:58:40" */
+export function OverlayBestTimes({ bestPossibleMs, sumOfBestMs, fontSize = 'medium' }: OverlayBestTimesProps) {
+  if (bestPossibleMs === null && sumOfBestMs === null) {
+    return null;
+  }
+
+  const sizeClass = fontSize === 'small' ? 'text-[10px]' : fontSize === 'large' ? 'text-sm' : 'text-xs';
+
+  return (
+    <div className={`text-center ${sizeClass}`} style={{ color: '#9ca3af' }}>
+      {bestPossibleMs !== null && <span title="Best possible time">BPT {formatTime(bestPossibleMs)}</span>}
+      {bestPossibleMs !== null && sumOfBestMs !== null && <span> · </span>}
+      {sumOfBestMs !== null && <span title="Sum of best segments" style={{ color: '#fbbf24' }}>SoB {formatTime(sumOfBestMs)}</span>}
+    </div>
+  );
+}
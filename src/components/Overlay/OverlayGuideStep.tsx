@@ -0,0 +1,21 @@
+interface OverlayGuideStepProps {
+  step: string | null;
+  next: string | null;
+  fontSize?: 'small' | 'medium' | 'large';
+}
+
+/** The leveling guide step to do now, and the one after it dimmed */
+export function OverlayGuideStep({ step, next, fontSize = 'medium' }: OverlayGuideStepProps) {
+  if (step === null) {
+    return null;
+  }
+
+  const sizeClass = fontSize === 'small' ? 'text-[10px]' : fontSize === 'large' ? 'text-sm' : 'text-xs';
+
+  return (
+    <div className={`mt-1 ${sizeClass}`}>
+      <div style={{ color: '#e5e7eb' }}>{step}</div>
+      {next !== null && <div className="truncate" style={{ color: '#6b7280' }}>{next}</div>}
+    </div>
+  );
+}
@@ -0,0 +1,31 @@
+interface OverlayLevelPaceProps {
+  level: number | null;
+  pbLevel: number | null;
+  elapsedMs: number;
+  fontSize?: 'small' | 'medium' | 'large';
+}
+
+function formatClock(ms: number): string {
+  const totalMinutes = Math.floor(ms / 60000);
+  const hours = Math.floor(totalMinutes / 60);
+  const minutes = totalMinutes % 60;
+  return `${hours}:${minutes.toString().padStart(2, '0')}`;
+}
+
+/** "Lv 38 @ 1:02 vs PB 36" */
+export function OverlayLevelPace({ level, pbLevel, elapsedMs, fontSize = 'medium' }: OverlayLevelPaceProps) {
+  if (level === null) {
+    return null;
+  }
+
+  const sizeClass = fontSize === 'small' ? 'text-[10px]' : fontSize === 'large' ? 'text-sm' : 'text-xs';
+  // Green when ahead of the PB's level, red when behind
+  const color = pbLevel === null || level === pbLevel ? '#9ca3af' : level > pbLevel ? '#22c55e' : '#ef4444';
+
+  return (
+    <div className={`text-center ${sizeClass}`} style={{ color }}>
+      Lv {level} @ {formatClock(elapsedMs)}
+      {pbLevel !== null && <span> vs PB {pbLevel}</span>}
+    </div>
+  );
+}
@@ -1,15 +1,28 @@
+import type { SplitColor } from '../../types';
+
 interface OverlaySplitProps {
   name: string;
   deltaMs: number | null;
   isBestSegment: boolean;
+  color: SplitColor;
   splitTimeMs?: number;
   segmentTimeMs?: number;
   pbSegmentTimeMs?: number | null;
   goldSegmentTimeMs?: number | null;
+  showDelta?: boolean;
   fontSize?: 'small' | 'medium' | 'large';
   scale?: 'small' | 'medium' | 'large';
 }
 
+const DELTA_COLORS: Record<SplitColor, string> = {
+  gold: '#fbbf24',
+  ahead_gaining: '#22c55e',
+  ahead_losing: '#86efac', // still ahead, losing time
+  behind_gaining: '#e8c9a5', // still behind, gaining time
+  behind_losing: '#d4a574', // amber
+  neutral: '#9ca3af',
+};
+
 function formatDelta(ms: number): string {
   const absMs = Math.abs(ms);
   const totalSeconds = Math.floor(absMs / 1000);
@@ -44,13 +57,8 @@ function formatSegmentTime(ms: number): string {
   return `${seconds}s`;
 }
 
-export function OverlaySplit({ name, deltaMs, isBestSegment, splitTimeMs, segmentTimeMs, pbSegmentTimeMs, goldSegmentTimeMs, fontSize = 'medium', scale = 'medium' }: OverlaySplitProps) {
-  let deltaColor = '#9ca3af'; // neutral
-  if (isBestSegment) {
-    deltaColor = '#fbbf24'; // gold
-  } else if (deltaMs !== null) {
-    deltaColor = deltaMs < 0 ? '#22c55e' : '#d4a574'; // ahead / behind (amber)
-  }
+export function OverlaySplit({ name, deltaMs, isBestSegment, color, splitTimeMs, segmentTimeMs, pbSegmentTimeMs, goldSegmentTimeMs, showDelta = true, fontSize = 'medium', scale = 'medium' }: OverlaySplitProps) {
+  const deltaColor = DELTA_COLORS[color];
 
   const sizeClass = fontSize === 'small' ? 'text-xs' : fontSize === 'large' ? 'text-base' : 'text-sm';
   const detailSizeClass = fontSize === 'small' ? 'text-[9px]' : fontSize === 'large' ? 'text-sm' : 'text-xs';
@@ -71,14 +79,16 @@ export function OverlaySplit({ name, deltaMs, isBestSegment, splitTimeMs, segmen
               {formatSplitTime(splitTimeMs)}
             </span>
           )}
-          <span style={{ color: deltaColor }} className="flex items-center gap-1">
-            {isBestSegment && (
-              <svg className="w-3 h-3" fill="currentColor" viewBox="0 0 24 24">
-                <path d="M12 2l3.09 6.26L22 9.27l-5 4.87 1.18 6.88L12 17.77l-6.18 3.25L7 14.14 2 9.27l6.91-1.01L12 2z" />
-              </svg>
-            )}
-            {deltaMs !== null ? formatDelta(deltaMs) : '--'}
-          </span>
+          {showDelta && (
+            <span style={{ color: deltaColor }} className="flex items-center gap-1">
+              {isBestSegment && (
+                <svg className="w-3 h-3" fill="currentColor" viewBox="0 0 24 24">
+                  <path d="M12 2l3.09 6.26L22 9.27l-5 4.87 1.18 6.88L12 17.77l-6.18 3.25L7 14.14 2 9.27l6.91-1.01L12 2z" />
+                </svg>
+              )}
+              {deltaMs !== null ? formatDelta(deltaMs) : '--'}
+            </span>
+          )}
         </span>
       </div>
       {/* Segment comparison line */}
@@ -1,10 +1,11 @@
 interface OverlayZoneProps {
   zoneName: string | null;
+  deaths?: number;
   fontSize?: 'small' | 'medium' | 'large';
   isAhead?: boolean;
 }
 
-export function OverlayZone({ zoneName, fontSize = 'medium', isAhead }: OverlayZoneProps) {
+export function OverlayZone({ zoneName, deaths = 0, fontSize = 'medium', isAhead }: OverlayZoneProps) {
   if (!zoneName) {
     return null;
   }
@@ -17,6 +18,11 @@ export function OverlayZone({ zoneName, fontSize = 'medium', isAhead }: OverlayZ
     <div className="text-center">
       <div className={`${sizeClass} truncate`} style={{ color }} title={zoneName}>
         {zoneName}
+        {deaths > 0 && (
+          <span className="ml-2 text-red-400" title={`${deaths} death${deaths === 1 ? '' : 's'} this run`}>
+            ☠{deaths}
+          </span>
+        )}
       </div>
     </div>
   );
@@ -1,4 +1,7 @@
 export { OverlayTimer } from './OverlayTimer';
 export { OverlayZone } from './OverlayZone';
+export { OverlayLevelPace } from './OverlayLevelPace';
+export { OverlayBestTimes } from './OverlayBestTimes';
+export { OverlayGuideStep } from './OverlayGuideStep';
 export { OverlaySplit } from './OverlaySplit';
 export { OverlayBreakpoints } from './OverlayBreakpoints';
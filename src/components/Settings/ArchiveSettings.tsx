@@ -0,0 +1,120 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useRunStore } from '../../stores/runStore';
+import { getErrorMessage } from '../../utils/errors';
+import type { ArchiveReport, RunPage } from '../../types';
+
+// Archived runs fetched per page
+const PAGE_SIZE = 20;
+
+const BUTTON_CLASS =
+  'px-4 py-2 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 shadow-sm hover:border-[--color-poe-gold]/70 hover:shadow-md active:scale-95 active:shadow-none transition-all font-medium disabled:opacity-50 disabled:cursor-wait';
+
+/** Move old runs into the archive database (archive_runs_older_than), and browse or restore them */
+export function ArchiveSettings() {
+  const [before, setBefore] = useState('');
+  const [archiving, setArchiving] = useState(false);
+  const [result, setResult] = useState<string | null>(null);
+  // Loaded only when asked for, a page at a time
+  const [archived, setArchived] = useState<RunPage | null>(null);
+
+  const loadArchived = async (offset: number) => {
+    try {
+      const page = await invoke<RunPage>('get_archived_runs', { offset, limit: PAGE_SIZE });
+      setArchived((current) =>
+        offset > 0 && current ? { runs: [...current.runs, ...page.runs], total: page.total } : page
+      );
+    } catch (error) {
+      setResult(`Loading archived runs failed: ${getErrorMessage(error)}`);
+    }
+  };
+
+  const handleArchive = async () => {
+    if (!before) return;
+    setArchiving(true);
+    try {
+      const report = await invoke<ArchiveReport>('archive_runs_older_than', { date: before });
+      setResult(
+        `Archived ${report.runsArchived} run${report.runsArchived !== 1 ? 's' : ''} and ` +
+          `${report.snapshotsArchived} snapshot${report.snapshotsArchived !== 1 ? 's' : ''}`
+      );
+      useRunStore.getState().loadFilteredRuns();
+      if (archived) loadArchived(0);
+    } catch (error) {
+      setResult(`Archiving failed: ${getErrorMessage(error)}`);
+    } finally {
+      setArchiving(false);
+    }
+  };
+
+  const handleRestore = async (runId: number) => {
+    try {
+      await invoke('restore_archived_run', { runId });
+      useRunStore.getState().loadFilteredRuns();
+      loadArchived(0);
+    } catch (error) {
+      setResult(`Restoring failed: ${getErrorMessage(error)}`);
+    }
+  };
+
+  return (
+    <div>
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-[--color-text]">Archive Old Runs</div>
+          <div className="text-xs text-[--color-text-muted]">
+            {result ?? 'Move runs started before a date into a separate archive file; PB and reference runs stay'}
+          </div>
+        </div>
+        <div className="flex items-center gap-2">
+          <input
+            type="date"
+            value={before}
+            onChange={(e) => setBefore(e.target.value)}
+            className="px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+          />
+          <button onClick={handleArchive} disabled={archiving || !before} className={BUTTON_CLASS}>
+            {archiving ? 'Archiving...' : 'Archive'}
+          </button>
+        </div>
+      </div>
+      {archived === null ? (
+        <button
+          onClick={() => loadArchived(0)}
+          className="mt-2 text-xs text-[--color-text-muted] hover:text-[--color-poe-gold] transition-colors"
+        >
+          Show archived runs
+        </button>
+      ) : (
+        <div className="mt-2 space-y-1">
+          {archived.runs.length === 0 && <div className="text-xs text-[--color-text-muted]">No archived runs</div>}
+          {archived.runs.map((run) => (
+            <div key={run.id} className="flex items-center justify-between text-sm">
+              <span className="text-[--color-text]">
+                {run.characterName || 'Unknown'}
+                <span className="text-[--color-text-muted]">
+                  {' '}
+                  · {run.ascendancy || run.class} · {run.category} · {run.startedAt.slice(0, 10)}
+                </span>
+              </span>
+              <button
+                onClick={() => handleRestore(run.id)}
+                className="px-2 py-0.5 text-xs text-[--color-text-muted] hover:text-[--color-poe-gold] transition-colors"
+              >
+                Restore
+              </button>
+            </div>
+          ))}
+          {archived.runs.length < archived.total && (
+            <button
+              onClick={() => loadArchived(archived.runs.length)}
+              className="text-xs text-[--color-text-muted] hover:text-[--color-poe-gold] transition-colors"
+            >
+              Load more ({archived.total - archived.runs.length} left)
+            </button>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,351 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type {
+  BreakpointPreset,
+  NewPresetBreakpoint,
+  PresetBreakpoint,
+  PresetTriggerType,
+  QuestObjective,
+} from '../../types';
+import { useSettingsStore } from '../../stores/settingsStore';
+import { CustomSelect } from '../Shared/CustomSelect';
+import { getErrorMessage } from '../../utils/errors';
+
+const TRIGGER_OPTIONS: { value: PresetTriggerType; label: string }[] = [
+  { value: 'zone', label: 'Zone entered' },
+  { value: 'level', label: 'Level reached' },
+  { value: 'kitava', label: 'Kitava defeated' },
+  { value: 'quest', label: 'Quest completed' },
+];
+
+const PENALTY_OPTIONS = [
+  { value: '30', label: 'Act 5 (-30% resistances)' },
+  { value: '60', label: 'Act 10 (-60% resistances)' },
+];
+
+const EMPTY_BREAKPOINT: NewPresetBreakpoint = {
+  name: '',
+  breakpointType: 'zone',
+  triggerType: 'zone',
+  zoneName: null,
+  act: null,
+  level: null,
+  quest: null,
+  captureSnapshot: false,
+};
+
+function describeTrigger(bp: PresetBreakpoint, objectives: QuestObjective[]): string {
+  switch (bp.triggerType) {
+    case 'level':
+      return `Level ${bp.level}`;
+    case 'kitava':
+      return `Kitava (A${bp.act})`;
+    case 'quest':
+      return objectives.find((o) => o.id === bp.quest)?.name ?? bp.quest ?? '';
+    default:
+      return bp.act ? `${bp.zoneName} (A${bp.act})` : bp.zoneName ?? '';
+  }
+}
+
+/** Split layouts of the user's own, stored in the database and run by the backend breakpoint engine */
+export function BreakpointPresetsSettings() {
+  const customPreset = useSettingsStore((s) => s.customPreset);
+  const setCustomPreset = useSettingsStore((s) => s.setCustomPreset);
+  const game = useSettingsStore((s) => s.game);
+  const [presets, setPresets] = useState<BreakpointPreset[]>([]);
+  const [selectedId, setSelectedId] = useState<number | null>(null);
+  const [breakpoints, setBreakpoints] = useState<PresetBreakpoint[]>([]);
+  const [objectives, setObjectives] = useState<QuestObjective[]>([]);
+  const [newName, setNewName] = useState('');
+  const [draft, setDraft] = useState<NewPresetBreakpoint>(EMPTY_BREAKPOINT);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadPresets = useCallback(async () => {
+    const loaded = await invoke<BreakpointPreset[]>('get_breakpoint_presets');
+    setPresets(loaded);
+    return loaded;
+  }, []);
+
+  useEffect(() => {
+    loadPresets().catch((err) => setError(getErrorMessage(err)));
+    invoke<QuestObjective[]>('get_quest_objectives')
+      .then(setObjectives)
+      .catch((err) => setError(getErrorMessage(err)));
+  }, [loadPresets]);
+
+  // Breakpoints of the preset being edited; the one runs use is kept in sync
+  const refresh = useCallback(async (presetId: number) => {
+    const loaded = await invoke<PresetBreakpoint[]>('get_preset_breakpoints', { presetId });
+    setBreakpoints(loaded);
+    const updated = await loadPresets();
+    const active = useSettingsStore.getState().customPreset;
+    if (active?.id === presetId) {
+      setCustomPreset(updated.find((p) => p.id === presetId) ?? null, loaded);
+    }
+  }, [loadPresets, setCustomPreset]);
+
+  const run = useCallback(async (action: () => Promise<void>) => {
+    try {
+      await action();
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  const handleSelect = (presetId: number) => run(async () => {
+    setSelectedId(presetId);
+    await refresh(presetId);
+  });
+
+  const handleCreate = () => run(async () => {
+    const preset = await invoke<BreakpointPreset>('create_breakpoint_preset', { name: newName, game });
+    setNewName('');
+    await loadPresets();
+    setSelectedId(preset.id);
+    setBreakpoints([]);
+  });
+
+  const handleDelete = (presetId: number) => run(async () => {
+    await invoke('delete_breakpoint_preset', { presetId });
+    if (customPreset?.id === presetId) setCustomPreset(null);
+    if (selectedId === presetId) {
+      setSelectedId(null);
+      setBreakpoints([]);
+    }
+    await loadPresets();
+  });
+
+  const handleUse = (preset: BreakpointPreset | null) => run(async () => {
+    const presetBreakpoints = preset
+      ? await invoke<PresetBreakpoint[]>('get_preset_breakpoints', { presetId: preset.id })
+      : [];
+    setCustomPreset(preset, presetBreakpoints);
+  });
+
+  const handleAdd = () => run(async () => {
+    if (selectedId === null) return;
+    const breakpointType =
+      draft.triggerType === 'quest' ? 'custom' : draft.triggerType === 'kitava' ? 'boss' : draft.triggerType;
+    await invoke('add_breakpoint', { presetId: selectedId, breakpoint: { ...draft, breakpointType } });
+    setDraft({ ...EMPTY_BREAKPOINT, triggerType: draft.triggerType, breakpointType });
+    await refresh(selectedId);
+  });
+
+  const handleRemove = (breakpointId: number) => run(async () => {
+    if (selectedId === null) return;
+    await invoke('remove_breakpoint', { breakpointId });
+    await refresh(selectedId);
+  });
+
+  const handleToggleSnapshot = (bp: PresetBreakpoint) => run(async () => {
+    if (selectedId === null) return;
+    await invoke('update_breakpoint', {
+      presetId: bp.presetId,
+      breakpointId: bp.id,
+      breakpoint: { ...bp, captureSnapshot: !bp.captureSnapshot },
+    });
+    await refresh(selectedId);
+  });
+
+  const handleMove = (index: number, direction: -1 | 1) => run(async () => {
+    if (selectedId === null) return;
+    const ids = breakpoints.map((bp) => bp.id);
+    const target = index + direction;
+    if (target < 0 || target >= ids.length) return;
+    [ids[index], ids[target]] = [ids[target], ids[index]];
+    await invoke('reorder_breakpoints', { presetId: selectedId, breakpointIds: ids });
+    await refresh(selectedId);
+  });
+
+  const questOptions = [
+    { value: '', label: 'Pick a quest' },
+    ...objectives.map((o) => ({ value: o.id, label: `${o.name} (A${o.act})` })),
+  ];
+  const selected = presets.find((p) => p.id === selectedId) ?? null;
+
+  return (
+    <div className="space-y-3">
+      <div className="text-xs text-[--color-text-muted]">
+        Build your own split layout from zones, levels and quests. The preset in use replaces the breakpoints above for new runs.
+      </div>
+
+      {presets.map((preset) => {
+        const inUse = customPreset?.id === preset.id;
+        return (
+          <div key={preset.id} className="flex items-center gap-3">
+            <button
+              onClick={() => handleSelect(preset.id)}
+              className={`flex-1 text-left text-sm truncate ${
+                selectedId === preset.id ? 'text-[--color-poe-gold]' : 'text-[--color-text]'
+              }`}
+            >
+              {preset.name}
+              <span className="ml-2 text-xs text-[--color-text-muted]">
+                {preset.breakpointCount} breakpoints{preset.game !== game ? ` · ${preset.game === 'poe2' ? 'PoE 2' : 'PoE 1'}` : ''}
+              </span>
+            </button>
+            <button
+              onClick={() => handleUse(inUse ? null : preset)}
+              className={`px-3 py-1 text-xs rounded-md border-2 transition-all active:scale-95 font-medium ${
+                inUse
+                  ? 'bg-[--color-poe-gold] text-[--color-poe-darker] border-[--color-poe-gold-light]'
+                  : 'bg-[--color-surface] text-[--color-text] border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70'
+              }`}
+            >
+              {inUse ? 'In use' : 'Use'}
+            </button>
+            <button
+              onClick={() => handleDelete(preset.id)}
+              className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-timer-behind] transition-colors"
+              title="Delete preset"
+            >
+              Delete
+            </button>
+          </div>
+        );
+      })}
+
+      <div className="flex items-center gap-2">
+        <input
+          type="text"
+          value={newName}
+          onChange={(e) => setNewName(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter' && newName.trim()) handleCreate();
+          }}
+          placeholder="e.g. Act 1-3 quests"
+          className="flex-1 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+        />
+        <button
+          onClick={handleCreate}
+          disabled={!newName.trim()}
+          className="px-3 py-1 text-xs bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 transition-all active:scale-95 font-medium disabled:opacity-50 disabled:cursor-not-allowed"
+        >
+          New Preset
+        </button>
+      </div>
+
+      {selected && (
+        <div className="border-t border-[--color-border] pt-3 space-y-2">
+          <div className="text-sm font-medium text-[--color-text]">{selected.name}</div>
+          {breakpoints.length === 0 && (
+            <div className="text-xs text-[--color-text-muted]">No breakpoints yet.</div>
+          )}
+          {breakpoints.map((bp, index) => (
+            <div key={bp.id} className="flex items-center gap-2 text-sm">
+              <div className="flex flex-col">
+                <button
+                  onClick={() => handleMove(index, -1)}
+                  disabled={index === 0}
+                  className="text-xs text-[--color-text-muted] hover:text-[--color-text] disabled:opacity-30"
+                  title="Move up"
+                >
+                  ▲
+                </button>
+                <button
+                  onClick={() => handleMove(index, 1)}
+                  disabled={index === breakpoints.length - 1}
+                  className="text-xs text-[--color-text-muted] hover:text-[--color-text] disabled:opacity-30"
+                  title="Move down"
+                >
+                  ▼
+                </button>
+              </div>
+              <span className="flex-1 truncate text-[--color-text]">
+                {bp.name}
+                <span className="ml-2 text-xs text-[--color-text-muted]">{describeTrigger(bp, objectives)}</span>
+              </span>
+              <label className="flex items-center gap-1 text-xs text-[--color-text-muted]">
+                <input type="checkbox" checked={bp.captureSnapshot} onChange={() => handleToggleSnapshot(bp)} />
+                Snapshot
+              </label>
+              <button
+                onClick={() => handleRemove(bp.id)}
+                className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-timer-behind] transition-colors"
+                title="Remove breakpoint"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+
+          <div className="flex flex-wrap items-center gap-2 pt-2">
+            <input
+              type="text"
+              value={draft.name}
+              onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+              placeholder="Split name"
+              className="w-40 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+            />
+            <CustomSelect
+              value={draft.triggerType}
+              onChange={(value) => {
+                const triggerType = value as PresetTriggerType;
+                setDraft({ ...draft, triggerType, penalty: triggerType === 'kitava' ? draft.penalty ?? 30 : null });
+              }}
+              options={TRIGGER_OPTIONS}
+              className="w-40"
+            />
+            {draft.triggerType === 'zone' && (
+              <>
+                <input
+                  type="text"
+                  value={draft.zoneName ?? ''}
+                  onChange={(e) => setDraft({ ...draft, zoneName: e.target.value })}
+                  placeholder="e.g. The Coast"
+                  className="w-44 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                />
+                <input
+                  type="number"
+                  min={1}
+                  max={10}
+                  value={draft.act ?? ''}
+                  onChange={(e) => setDraft({ ...draft, act: e.target.value ? Number(e.target.value) : null })}
+                  placeholder="Act"
+                  className="w-16 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                />
+              </>
+            )}
+            {draft.triggerType === 'level' && (
+              <input
+                type="number"
+                min={2}
+                max={100}
+                value={draft.level ?? ''}
+                onChange={(e) => setDraft({ ...draft, level: e.target.value ? Number(e.target.value) : null })}
+                placeholder="Level"
+                className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+            )}
+            {draft.triggerType === 'kitava' && (
+              <CustomSelect
+                value={String(draft.penalty ?? 30)}
+                onChange={(value) => setDraft({ ...draft, penalty: Number(value) })}
+                options={PENALTY_OPTIONS}
+                className="w-56"
+              />
+            )}
+            {draft.triggerType === 'quest' && (
+              <CustomSelect
+                value={draft.quest ?? ''}
+                onChange={(value) => setDraft({ ...draft, quest: value || null })}
+                options={questOptions}
+                className="w-64"
+              />
+            )}
+            <button
+              onClick={handleAdd}
+              disabled={!draft.name.trim()}
+              className="px-3 py-1 text-xs bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 transition-all active:scale-95 font-medium disabled:opacity-50 disabled:cursor-not-allowed"
+            >
+              Add Breakpoint
+            </button>
+          </div>
+        </div>
+      )}
+
+      {error && <div className="text-xs text-[--color-timer-behind]">{error}</div>}
+    </div>
+  );
+}
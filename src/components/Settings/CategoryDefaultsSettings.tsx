@@ -0,0 +1,89 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useSettingsStore } from '../../stores/settingsStore';
+import { getWizardCategory } from '../../config/wizardRoutes';
+import type { CategoryDefaults } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
+
+/** Save the current setup as the defaults for the wizard's category, and manage saved ones */
+export function CategoryDefaultsSettings() {
+  const wizardConfig = useSettingsStore((s) => s.wizardConfig);
+  const saveCategoryDefaults = useSettingsStore((s) => s.saveCategoryDefaults);
+  const [saved, setSaved] = useState<CategoryDefaults[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  const category = wizardConfig ? getWizardCategory(wizardConfig) : 'any%';
+
+  const refresh = useCallback(async () => {
+    try {
+      setSaved(await invoke<CategoryDefaults[]>('get_category_defaults'));
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  const handleSave = useCallback(async () => {
+    try {
+      await saveCategoryDefaults(category);
+      await refresh();
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, [category, saveCategoryDefaults, refresh]);
+
+  const handleDelete = useCallback(async (name: string) => {
+    try {
+      await invoke('delete_category_defaults', { category: name });
+      await refresh();
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, [refresh]);
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-[--color-text]">Save Defaults for {category}</div>
+          <div className="text-xs text-[--color-text-muted]">
+            Breakpoints, snapshot captures, PB comparison and overlay layout are restored when a {category} run starts
+          </div>
+        </div>
+        <button
+          onClick={handleSave}
+          className="px-4 py-2 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 shadow-sm hover:border-[--color-poe-gold]/70 hover:shadow-md active:scale-95 active:shadow-none transition-all font-medium"
+        >
+          Save
+        </button>
+      </div>
+
+      {saved.length > 0 && (
+        <div className="space-y-1">
+          {saved.map((defaults) => (
+            <div key={defaults.category} className="flex items-center justify-between text-sm">
+              <span className="text-[--color-text]">
+                {defaults.category}
+                {defaults.breakpointPreset && (
+                  <span className="text-xs text-[--color-text-muted] ml-2">{defaults.breakpointPreset}</span>
+                )}
+              </span>
+              <button
+                onClick={() => handleDelete(defaults.category)}
+                className="text-xs text-[--color-text-muted] hover:text-red-400 transition-colors"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {error && <div className="text-xs text-[--color-timer-behind]">{error}</div>}
+    </div>
+  );
+}
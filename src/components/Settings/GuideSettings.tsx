@@ -0,0 +1,75 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import type { Guide } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
+
+/** Import a leveling guide whose steps the overlay follows as you play */
+export function GuideSettings() {
+  const [guide, setGuide] = useState<Guide | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<Guide | null>('get_guide')
+      .then(setGuide)
+      .catch((err) => setError(getErrorMessage(err)));
+  }, []);
+
+  const handleImport = useCallback(async () => {
+    try {
+      const path = await open({
+        multiple: false,
+        filters: [{ name: 'Leveling Guide', extensions: ['json'] }],
+        title: 'Import Leveling Guide',
+      });
+      if (!path) return;
+      setGuide(await invoke<Guide>('import_guide', { path }));
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  const handleClear = useCallback(async () => {
+    try {
+      await invoke('clear_guide');
+      setGuide(null);
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-[--color-text]">Leveling Guide</div>
+          <div className="text-xs text-[--color-text-muted]">
+            {guide
+              ? `${guide.name} · ${guide.steps.length} steps`
+              : 'An Exile Leveling route export; the overlay shows the current step and moves on as you enter zones and level up'}
+          </div>
+        </div>
+        <div className="flex gap-2">
+          <button
+            onClick={handleImport}
+            className="px-3 py-1 bg-[--color-surface] text-[--color-text] rounded border border-[--color-border] hover:border-[--color-poe-gold]/70 text-sm"
+          >
+            {guide ? 'Replace' : 'Import'}
+          </button>
+          {guide && (
+            <button
+              onClick={handleClear}
+              className="px-3 py-1 bg-[--color-surface] text-[--color-text] rounded border border-[--color-border] hover:border-[--color-poe-gold]/70 text-sm"
+            >
+              Remove
+            </button>
+          )}
+        </div>
+      </div>
+
+      {error && <div className="text-xs text-[--color-timer-behind]">{error}</div>}
+    </div>
+  );
+}
@@ -0,0 +1,82 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { ObsServerInfo } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
+
+/** Enable the localhost server OBS browser sources read the overlay from */
+export function ObsServerSettings() {
+  const [info, setInfo] = useState<ObsServerInfo | null>(null);
+  const [port, setPort] = useState(8734);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<ObsServerInfo>('get_obs_server')
+      .then((loaded) => {
+        setInfo(loaded);
+        setPort(loaded.port);
+      })
+      .catch((err) => setError(getErrorMessage(err)));
+  }, []);
+
+  const apply = useCallback(async (enabled: boolean, port: number) => {
+    try {
+      setInfo(await invoke<ObsServerInfo>('set_obs_server', { enabled, port }));
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  const enabled = info?.enabled ?? false;
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-[--color-text]">OBS Browser Source</div>
+          <div className="text-xs text-[--color-text-muted]">
+            Serve the overlay timer and splits on this PC for an OBS browser source
+          </div>
+        </div>
+        <button
+          onClick={() => apply(!enabled, port)}
+          className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+            enabled ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+          }`}
+        >
+          <div
+            className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+              enabled ? 'translate-x-6' : 'translate-x-0.5'
+            }`}
+          />
+        </button>
+      </div>
+
+      <div className="flex items-center gap-2">
+        <span className="text-xs text-[--color-text-muted]">Port</span>
+        <input
+          type="number"
+          min={1024}
+          max={65535}
+          value={port}
+          onChange={(e) => setPort(Number(e.target.value) || 0)}
+          onBlur={() => {
+            if (info && port !== info.port) apply(enabled, port);
+          }}
+          className="w-24 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+        />
+      </div>
+
+      {enabled && info?.url && (
+        <div className="space-y-1">
+          <div className="text-xs text-[--color-text-muted]">
+            Add a browser source with this URL, or read <code>state.json</code> and the <code>/ws</code> WebSocket from your own page:
+          </div>
+          <code className="block text-xs text-[--color-poe-gold] break-all select-all">{info.url}</code>
+        </div>
+      )}
+
+      {error && <div className="text-xs text-[--color-timer-behind]">{error}</div>}
+    </div>
+  );
+}
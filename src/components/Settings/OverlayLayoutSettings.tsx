@@ -0,0 +1,55 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { OverlayComponents } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
+
+const COMPONENTS: { key: keyof OverlayComponents; label: string }[] = [
+  { key: 'timer', label: 'Timer' },
+  { key: 'currentSplit', label: 'Current split' },
+  { key: 'delta', label: 'Delta' },
+  { key: 'nextSplit', label: 'Next splits' },
+  { key: 'deathCount', label: 'Death count' },
+  { key: 'levelPace', label: 'Level pace' },
+];
+
+/** Pick the overlay's components; open overlays update as soon as one is toggled */
+export function OverlayLayoutSettings() {
+  const [layout, setLayout] = useState<OverlayComponents | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<OverlayComponents>('get_overlay_layout')
+      .then(setLayout)
+      .catch((err) => setError(getErrorMessage(err)));
+  }, []);
+
+  const handleToggle = useCallback(async (key: keyof OverlayComponents) => {
+    if (!layout) return;
+    const next = { ...layout, [key]: !layout[key] };
+    try {
+      await invoke('set_overlay_layout', { layout: next });
+      setLayout(next);
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, [layout]);
+
+  if (!layout) {
+    return error ? <div className="text-xs text-[--color-timer-behind]">{error}</div> : null;
+  }
+
+  return (
+    <div className="space-y-2">
+      <div className="grid grid-cols-2 gap-2 text-sm text-[--color-text]">
+        {COMPONENTS.map(({ key, label }) => (
+          <label key={key} className="flex items-center gap-2">
+            <input type="checkbox" checked={layout[key]} onChange={() => handleToggle(key)} />
+            {label}
+          </label>
+        ))}
+      </div>
+      {error && <div className="text-xs text-[--color-timer-behind]">{error}</div>}
+    </div>
+  );
+}
@@ -0,0 +1,162 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { OverlayProfile } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
+
+/** Extra overlay windows, e.g. a compact timer on a second monitor */
+export function OverlayProfilesSettings() {
+  const [profiles, setProfiles] = useState<OverlayProfile[]>([]);
+  const [openIds, setOpenIds] = useState<Set<string>>(new Set());
+  const [newName, setNewName] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<OverlayProfile[]>('get_overlay_profiles')
+      .then(setProfiles)
+      .catch((err) => setError(getErrorMessage(err)));
+  }, []);
+
+  // A profile window closed from its own X button
+  useEffect(() => {
+    const unlisten = listen<string | null>('overlay-closed', (event) => {
+      const id = event.payload;
+      if (id) {
+        setOpenIds((prev) => {
+          const next = new Set(prev);
+          next.delete(id);
+          return next;
+        });
+      }
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
+  const handleCreate = useCallback(async () => {
+    try {
+      const profile = await invoke<OverlayProfile>('create_overlay_profile', { name: newName });
+      setProfiles((prev) => [...prev, profile]);
+      setNewName('');
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, [newName]);
+
+  const handleToggle = useCallback(async (id: string) => {
+    try {
+      const isOpen = await invoke<boolean>('toggle_overlay', { profileId: id });
+      setOpenIds((prev) => {
+        const next = new Set(prev);
+        if (isOpen) next.add(id);
+        else next.delete(id);
+        return next;
+      });
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  const handleOpacity = useCallback(async (profile: OverlayProfile, opacity: number | null) => {
+    const updated = { ...profile, opacity };
+    setProfiles((prev) => prev.map((p) => (p.id === profile.id ? updated : p)));
+    try {
+      await invoke('update_overlay_profile', { profile: updated });
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  const handleDelete = useCallback(async (id: string) => {
+    try {
+      await invoke('delete_overlay_profile', { id });
+      setProfiles((prev) => prev.filter((p) => p.id !== id));
+      setOpenIds((prev) => {
+        const next = new Set(prev);
+        next.delete(id);
+        return next;
+      });
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  return (
+    <div className="space-y-3">
+      <div className="text-xs text-[--color-text-muted]">
+        Extra overlay windows show the same timer and splits, each with its own position, size and opacity. Drag the edges of one to resize it.
+      </div>
+
+      {profiles.map((profile) => {
+        const isOpen = openIds.has(profile.id);
+        return (
+          <div key={profile.id} className="flex items-center gap-3">
+            <span className="flex-1 text-sm text-[--color-text] truncate">{profile.name}</span>
+            <label className="flex items-center gap-2 text-xs text-[--color-text-muted]">
+              <input
+                type="checkbox"
+                checked={profile.opacity !== null}
+                onChange={(e) => handleOpacity(profile, e.target.checked ? 0.8 : null)}
+              />
+              Own opacity
+            </label>
+            {profile.opacity !== null && (
+              <input
+                type="range"
+                min="0.1"
+                max="1"
+                step="0.05"
+                value={profile.opacity}
+                onChange={(e) => handleOpacity(profile, parseFloat(e.target.value))}
+                className="w-24 accent-[--color-poe-gold]"
+                title={`${Math.round(profile.opacity * 100)}%`}
+              />
+            )}
+            <button
+              onClick={() => handleToggle(profile.id)}
+              className={`px-3 py-1 text-xs rounded-md border-2 transition-all active:scale-95 font-medium ${
+                isOpen
+                  ? 'bg-[--color-timer-behind] text-white border-red-400'
+                  : 'bg-[--color-surface] text-[--color-text] border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70'
+              }`}
+            >
+              {isOpen ? 'Close' : 'Open'}
+            </button>
+            <button
+              onClick={() => handleDelete(profile.id)}
+              className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-timer-behind] transition-colors"
+              title="Delete overlay window"
+            >
+              Delete
+            </button>
+          </div>
+        );
+      })}
+
+      <div className="flex items-center gap-2">
+        <input
+          type="text"
+          value={newName}
+          onChange={(e) => setNewName(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === 'Enter' && newName.trim()) handleCreate();
+          }}
+          placeholder="e.g. Compact timer"
+          className="flex-1 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+        />
+        <button
+          onClick={handleCreate}
+          disabled={!newName.trim()}
+          className="px-3 py-1 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 active:scale-95 transition-all font-medium disabled:opacity-50"
+        >
+          Add Overlay Window
+        </button>
+      </div>
+
+      {error && <div className="text-xs text-[--color-timer-behind]">{error}</div>}
+    </div>
+  );
+}
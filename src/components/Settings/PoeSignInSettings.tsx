@@ -0,0 +1,60 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { PoeAuthStatus } from '../../types';
+import { useSettingsStore } from '../../stores/settingsStore';
+import { getErrorMessage } from '../../utils/errors';
+
+/** Sign in with a Path of Exile account so snapshots work with a private profile */
+export function PoeSignInSettings() {
+  const { checkProfilePrivacy } = useSettingsStore();
+  const [status, setStatus] = useState<PoeAuthStatus | null>(null);
+  const [pending, setPending] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<PoeAuthStatus>('get_poe_auth_status')
+      .then(setStatus)
+      .catch((err) => setError(getErrorMessage(err)));
+  }, []);
+
+  const apply = useCallback(
+    async (command: 'poe_login' | 'poe_logout') => {
+      setPending(true);
+      try {
+        setStatus(await invoke<PoeAuthStatus>(command));
+        setError(null);
+        checkProfilePrivacy();
+      } catch (err) {
+        setError(getErrorMessage(err));
+      } finally {
+        setPending(false);
+      }
+    },
+    [checkProfilePrivacy]
+  );
+
+  const connected = status?.connected ?? false;
+
+  return (
+    <div className="mt-3">
+      <div className="flex items-center justify-between gap-4">
+        <div>
+          <div className="text-sm text-[--color-text]">Path of Exile Sign-in</div>
+          <div className="text-xs text-[--color-text-muted]">
+            {connected
+              ? `Signed in as ${status?.accountName}. Characters are read with your sign-in, so a private profile works.`
+              : 'Optional. Sign in to capture snapshots without making your profile public.'}
+          </div>
+        </div>
+        <button
+          onClick={() => apply(connected ? 'poe_logout' : 'poe_login')}
+          disabled={pending}
+          className="px-4 py-2 bg-[--color-surface] text-[--color-text] rounded-lg border border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 active:scale-95 transition-all disabled:opacity-50 whitespace-nowrap"
+        >
+          {pending ? (connected ? 'Signing out...' : 'Waiting for browser...') : connected ? 'Sign Out' : 'Sign In'}
+        </button>
+      </div>
+      {error && <p className="text-xs text-[--color-timer-behind] mt-2">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,69 @@
+import { useSettingsStore } from '../../stores/settingsStore';
+import type { ReadinessMode } from '../../types';
+import { CustomSelect } from '../Shared/CustomSelect';
+
+const MODES = [
+  { value: 'off', label: 'Off' },
+  { value: 'warn', label: 'Warn - start anyway' },
+  { value: 'block', label: 'Block - refuse to start' },
+];
+
+/** Pre-run checklist (get_run_readiness): what failing checks do, and which checks run */
+export function RunChecklistSettings() {
+  const {
+    readinessMode,
+    readinessLogWatcher,
+    readinessAccount,
+    readinessOverlay,
+    readinessPreset,
+    readinessProfilePublic,
+    setReadinessMode,
+    setReadinessLogWatcher,
+    setReadinessAccount,
+    setReadinessOverlay,
+    setReadinessPreset,
+    setReadinessProfilePublic,
+  } = useSettingsStore();
+
+  const checks = [
+    { label: 'Log watcher running', checked: readinessLogWatcher, onChange: setReadinessLogWatcher },
+    { label: 'Account verified', checked: readinessAccount, onChange: setReadinessAccount },
+    { label: 'Profile public (snapshots work)', checked: readinessProfilePublic, onChange: setReadinessProfilePublic },
+    { label: 'Same splits preset as the category PB', checked: readinessPreset, onChange: setReadinessPreset },
+    { label: 'Overlay open', checked: readinessOverlay, onChange: setReadinessOverlay },
+  ];
+
+  return (
+    <div>
+      <div className="flex items-center justify-between gap-4">
+        <div>
+          <div className="text-sm text-[--color-text]">Pre-run Checklist</div>
+          <div className="text-xs text-[--color-text-muted]">
+            Checked when a new run starts, so PB attempts don't lose snapshots halfway through
+          </div>
+        </div>
+        <CustomSelect
+          value={readinessMode}
+          onChange={(mode) => setReadinessMode(mode as ReadinessMode)}
+          options={MODES}
+          className="w-56"
+        />
+      </div>
+      {readinessMode !== 'off' && (
+        <div className="mt-2 grid grid-cols-2 gap-2">
+          {checks.map((check) => (
+            <label key={check.label} className="flex items-center gap-2 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={check.checked}
+                onChange={(e) => check.onChange(e.target.checked)}
+                className="w-4 h-4 rounded"
+              />
+              <span className="text-xs text-[--color-text]">{check.label}</span>
+            </label>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
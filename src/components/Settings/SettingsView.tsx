@@ -1,12 +1,68 @@
-import { useState, useMemo, useCallback } from 'react';
+import { useState, useMemo, useCallback, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { open } from '@tauri-apps/plugin-dialog';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { useSettingsStore } from '../../stores/settingsStore';
+import { useRunStore } from '../../stores/runStore';
 import { useUpdateChecker } from '../../hooks/useUpdateChecker';
 import { BreakpointWizard, RouteCustomizations } from './BreakpointWizard';
+import { BreakpointPresetsSettings } from './BreakpointPresetsSettings';
 import { HotkeyInput } from './HotkeyInput';
-import type { HotkeySettings } from '../../types';
+import { StatusServerSettings } from './StatusServerSettings';
+import { ObsServerSettings } from './ObsServerSettings';
+import { GuideSettings } from './GuideSettings';
+import { OverlayProfilesSettings } from './OverlayProfilesSettings';
+import { OverlayLayoutSettings } from './OverlayLayoutSettings';
+import { CategoryDefaultsSettings } from './CategoryDefaultsSettings';
+import { RunChecklistSettings } from './RunChecklistSettings';
+import { PoeSignInSettings } from './PoeSignInSettings';
+import { TriggerRuleEditor } from './TriggerRuleEditor';
+import { ArchiveSettings } from './ArchiveSettings';
+import type {
+  CompactReport,
+  Game,
+  HotkeyRegistration,
+  HotkeySettings,
+  ImageCacheStats,
+  RepairReport,
+  ReprocessReport,
+  SnapshotRetention,
+  UpdateChannel,
+} from '../../types';
 import { DEFAULT_HOTKEYS } from '../../types';
+import { campaignStartZone } from '../../config/breakpoints';
+import { getErrorMessage } from '../../utils/errors';
+import { CustomSelect } from '../Shared/CustomSelect';
+import { ProfilePrivacyNotice } from '../Shared/ProfilePrivacyNotice';
+
+// Locales understood by the table exporters (table_export.rs)
+const EXPORT_LOCALES = [
+  { value: 'en-US', label: 'English (US) - 1234.5 - 01/15/2024 2:30 PM' },
+  { value: 'en-GB', label: 'English (UK) - 1234.5 - 15/01/2024 14:30' },
+  { value: 'de-DE', label: 'Deutsch - 1234,5 - 15.01.2024 14:30' },
+  { value: 'fr-FR', label: 'Français - 1234,5 - 15/01/2024 14:30' },
+  { value: 'es-ES', label: 'Español - 1234,5 - 15/01/2024 14:30' },
+  { value: 'pt-BR', label: 'Português (BR) - 1234,5 - 15/01/2024 14:30' },
+  { value: 'ru-RU', label: 'Русский - 1234,5 - 15.01.2024 14:30' },
+  { value: 'ISO', label: 'ISO - 1234.5 - 2024-01-15 14:30' },
+];
+
+// Games whose clients can be followed (game.rs)
+const GAMES = [
+  { value: 'poe1', label: 'Path of Exile' },
+  { value: 'poe2', label: 'Path of Exile 2' },
+];
+
+// Release channels the updater can follow (updater.rs)
+const UPDATE_CHANNELS = [
+  { value: 'stable', label: 'Stable' },
+  { value: 'beta', label: 'Beta (prereleases)' },
+];
+
+const SNAPSHOT_RETENTIONS = [
+  { value: 'all', label: 'Keep all' },
+  { value: 'final', label: 'Final build only' },
+  { value: 'recent', label: 'Last runs only' },
+];
 
 const BREAKPOINTS_STORAGE_KEY = 'poe-watcher-breakpoints';
 
@@ -14,11 +70,23 @@ const HOTKEY_ACTIONS: { key: keyof HotkeySettings; label: string }[] = [
   { key: 'toggleTimer', label: 'Start / Pause Timer' },
   { key: 'resetTimer', label: 'Reset Timer' },
   { key: 'manualSplit', label: 'Manual Split' },
+  { key: 'undoSplit', label: 'Undo Split' },
+  { key: 'skipSplit', label: 'Skip Split' },
   { key: 'manualSnapshot', label: 'Manual Snapshot' },
   { key: 'toggleOverlay', label: 'Toggle Overlay' },
   { key: 'toggleOverlayLock', label: 'Toggle Overlay Lock' },
 ];
 
+type HotkeyErrors = Partial<Record<keyof HotkeySettings, string>>;
+
+function registrationErrors(registrations: HotkeyRegistration[]): HotkeyErrors {
+  const errors: HotkeyErrors = {};
+  for (const r of registrations) {
+    if (r.error) errors[r.hotkey] = r.error;
+  }
+  return errors;
+}
+
 export function SettingsView() {
   const {
     poeLogPath,
@@ -30,12 +98,14 @@ export function SettingsView() {
     breakpoints,
     setLogPath,
     setAccountName,
+    checkProfilePrivacy,
     setTestCharacterName,
     setCheckUpdates,
     setOverlayEnabled,
     setOverlayOpacity,
     toggleBreakpoint,
     toggleSnapshotCapture,
+    setBreakpointRule,
     moveBreakpoint,
     setAllBreakpoints,
     setActBreakpoints,
@@ -66,6 +136,100 @@ export function SettingsView() {
     setOverlayAlwaysOnTop,
     setOverlayLocked,
     setOverlayOpen,
+    // Warm-up detection
+    warmupDetectionEnabled,
+    sessionGapMinutes,
+    setWarmupDetectionEnabled,
+    setSessionGapMinutes,
+    // Startup
+    launchOnBoot,
+    startMinimized,
+    setLaunchOnBoot,
+    setStartMinimized,
+    minimizeToTray,
+    setMinimizeToTray,
+    backgroundMode,
+    setBackgroundMode,
+    followGameProcess,
+    setFollowGameProcess,
+    // Personal bests
+    separatePbsByPatch,
+    setSeparatePbsByPatch,
+    goldProtectionSecs,
+    setGoldProtectionSecs,
+    timerStartOffsetMs,
+    setTimerStartOffsetMs,
+    recordApiResponses,
+    setRecordApiResponses,
+    apiRecordingLimit,
+    setApiRecordingLimit,
+    locale,
+    setLocale,
+    // Pre-run checklist
+    readinessMode,
+    readinessLogWatcher,
+    readinessAccount,
+    readinessOverlay,
+    readinessPreset,
+    readinessProfilePublic,
+    // Auto-start
+    autoStartRuns,
+    autoStartZone,
+    setAutoStartRuns,
+    setAutoStartZone,
+    // Auto-pause
+    autoPause,
+    setAutoPause,
+    // Auto-export
+    autoExportDir,
+    autoExportFormats,
+    autoExportPbsOnly,
+    setAutoExportDir,
+    setAutoExportFormats,
+    setAutoExportPbsOnly,
+    // Notification webhook
+    webhookUrl,
+    webhookSecret,
+    setWebhookUrl,
+    setWebhookSecret,
+    // Twitch chat
+    twitchToken,
+    twitchUsername,
+    twitchChannel,
+    twitchPbMessage,
+    twitchGoldMessage,
+    twitchRunMessage,
+    setTwitchToken,
+    setTwitchUsername,
+    setTwitchChannel,
+    setTwitchPbMessage,
+    setTwitchGoldMessage,
+    setTwitchRunMessage,
+    // Updates
+    updateChannel,
+    setUpdateChannel,
+    // Database maintenance
+    snapshotRetention,
+    snapshotRetentionRuns,
+    setSnapshotRetention,
+    setSnapshotRetentionRuns,
+    snapshotLevelInterval,
+    setSnapshotLevelInterval,
+    imageProxyHosts,
+    setImageProxyHosts,
+    game,
+    setGame,
+    // Split announcements
+    announceSound,
+    announceSpeech,
+    announceGoldsOnly,
+    announceMinBehindMs,
+    announceMinAheadMs,
+    setAnnounceSound,
+    setAnnounceSpeech,
+    setAnnounceGoldsOnly,
+    setAnnounceMinBehindMs,
+    setAnnounceMinAheadMs,
     // Hotkeys
     hotkeys,
     setHotkeys,
@@ -75,15 +239,29 @@ export function SettingsView() {
 
   // Filter state for breakpoints
   const [actFilter, setActFilter] = useState<number | 'all' | 'level'>('all');
+  const [editingRule, setEditingRule] = useState<string | null>(null);
   const [saveStatus, setSaveStatus] = useState<'idle' | 'saving' | 'saved' | 'error'>('idle');
 
   // Local hotkey editing state (changes are applied on "Apply" click)
   const [editingHotkeys, setEditingHotkeys] = useState<HotkeySettings>({ ...hotkeys });
-  const [hotkeyErrors, setHotkeyErrors] = useState<Partial<Record<keyof HotkeySettings, string>>>({});
+  const [hotkeyErrors, setHotkeyErrors] = useState<HotkeyErrors>({});
   const [hotkeyApplyStatus, setHotkeyApplyStatus] = useState<'idle' | 'applying' | 'applied' | 'error'>('idle');
 
   // Sync local editing state when store hotkeys change (e.g., after loadHotkeys)
   const [lastSyncedHotkeys, setLastSyncedHotkeys] = useState(hotkeys);
+  const [repairing, setRepairing] = useState(false);
+  const [repairResult, setRepairResult] = useState<string | null>(null);
+  const [compacting, setCompacting] = useState(false);
+  const [compactResult, setCompactResult] = useState<string | null>(null);
+  const [imageCacheStats, setImageCacheStats] = useState<ImageCacheStats | null>(null);
+  const [clearingImageCache, setClearingImageCache] = useState(false);
+  const [twitchTesting, setTwitchTesting] = useState(false);
+  const [twitchTestResult, setTwitchTestResult] = useState<string | null>(null);
+  const [recomputing, setRecomputing] = useState(false);
+  const [recomputeResult, setRecomputeResult] = useState<string | null>(null);
+  const [reprocessing, setReprocessing] = useState(false);
+  const [reprocessResult, setReprocessResult] = useState<string | null>(null);
+  const [diagnosticsResult, setDiagnosticsResult] = useState<string | null>(null);
   if (hotkeys !== lastSyncedHotkeys) {
     setEditingHotkeys({ ...hotkeys });
     setLastSyncedHotkeys(hotkeys);
@@ -94,7 +272,7 @@ export function SettingsView() {
 
     // Check for duplicates
     setHotkeyErrors(() => {
-      const newErrors: Partial<Record<keyof HotkeySettings, string>> = {};
+      const newErrors: HotkeyErrors = {};
       const allKeys = HOTKEY_ACTIONS.map(a => a.key);
       const values = { ...editingHotkeys, [key]: value } as Record<keyof HotkeySettings, string>;
 
@@ -111,6 +289,13 @@ export function SettingsView() {
     setHotkeyApplyStatus('idle');
   }, [editingHotkeys]);
 
+  // Show shortcuts that failed to register at startup
+  useEffect(() => {
+    invoke<HotkeyRegistration[]>('get_hotkey_registrations')
+      .then(registrations => setHotkeyErrors(registrationErrors(registrations)))
+      .catch(error => console.error('Failed to load hotkey registrations:', error));
+  }, []);
+
   const hasHotkeyChanges = useMemo(() => {
     return Object.keys(editingHotkeys).some(
       k => editingHotkeys[k as keyof HotkeySettings] !== hotkeys[k as keyof HotkeySettings]
@@ -123,10 +308,16 @@ export function SettingsView() {
     if (hasHotkeyErrors) return;
     setHotkeyApplyStatus('applying');
     try {
-      await invoke('update_hotkeys', { hotkeys: editingHotkeys });
-      setHotkeys(editingHotkeys);
-      setHotkeyApplyStatus('applied');
-      setTimeout(() => setHotkeyApplyStatus('idle'), 2000);
+      const registrations = await invoke<HotkeyRegistration[]>('update_hotkeys', { hotkeys: editingHotkeys });
+      const errors = registrationErrors(registrations);
+      setHotkeyErrors(errors);
+      // Nothing is registered when the backend rejected the set as a whole
+      if (registrations.some(r => r.registered)) {
+        setHotkeys(editingHotkeys);
+      }
+      const failed = Object.keys(errors).length > 0;
+      setHotkeyApplyStatus(failed ? 'error' : 'applied');
+      setTimeout(() => setHotkeyApplyStatus('idle'), failed ? 3000 : 2000);
     } catch (error) {
       console.error('Failed to apply hotkeys:', error);
       setHotkeyApplyStatus('error');
@@ -137,12 +328,14 @@ export function SettingsView() {
   const handleResetHotkeys = useCallback(async () => {
     setHotkeyApplyStatus('applying');
     try {
-      await invoke('update_hotkeys', { hotkeys: DEFAULT_HOTKEYS });
+      const registrations = await invoke<HotkeyRegistration[]>('update_hotkeys', { hotkeys: DEFAULT_HOTKEYS });
+      const errors = registrationErrors(registrations);
       setHotkeys({ ...DEFAULT_HOTKEYS });
       setEditingHotkeys({ ...DEFAULT_HOTKEYS });
-      setHotkeyErrors({});
-      setHotkeyApplyStatus('applied');
-      setTimeout(() => setHotkeyApplyStatus('idle'), 2000);
+      setHotkeyErrors(errors);
+      const failed = Object.keys(errors).length > 0;
+      setHotkeyApplyStatus(failed ? 'error' : 'applied');
+      setTimeout(() => setHotkeyApplyStatus('idle'), failed ? 3000 : 2000);
     } catch (error) {
       console.error('Failed to reset hotkeys:', error);
       setHotkeyApplyStatus('error');
@@ -169,6 +362,110 @@ export function SettingsView() {
     }
   }, []);
 
+  // Repair database inconsistencies
+  const handleRepairDatabase = useCallback(async () => {
+    setRepairing(true);
+    try {
+      const report = await invoke<RepairReport>('repair_database');
+      setRepairResult(formatRepairReport(report));
+    } catch (error) {
+      setRepairResult(`Repair failed: ${getErrorMessage(error)}`);
+    } finally {
+      setRepairing(false);
+    }
+  }, []);
+
+  // Prune snapshots per the saved retention setting, compress and VACUUM
+  const handleCompactDatabase = useCallback(async () => {
+    setCompacting(true);
+    try {
+      const report = await invoke<CompactReport>('compact_database');
+      setCompactResult(formatCompactReport(report));
+    } catch (error) {
+      setCompactResult(`Compact failed: ${getErrorMessage(error)}`);
+    } finally {
+      setCompacting(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    invoke<ImageCacheStats>('get_image_cache_stats')
+      .then(setImageCacheStats)
+      .catch(error => console.error('Failed to load image cache stats:', error));
+  }, []);
+
+  // Delete cached item icons; they're downloaded again as they're shown
+  const handleClearImageCache = useCallback(async () => {
+    setClearingImageCache(true);
+    try {
+      await invoke<ImageCacheStats>('clear_image_cache');
+      setImageCacheStats(await invoke<ImageCacheStats>('get_image_cache_stats'));
+    } catch (error) {
+      console.error('Failed to clear image cache:', error);
+    } finally {
+      setClearingImageCache(false);
+    }
+  }, []);
+
+  // Post a message to Twitch chat with the saved chat settings
+  const handleTwitchTest = useCallback(async () => {
+    setTwitchTesting(true);
+    try {
+      await invoke('send_twitch_test_message');
+      setTwitchTestResult('Test message sent');
+    } catch (error) {
+      setTwitchTestResult(`Test failed: ${getErrorMessage(error)}`);
+    } finally {
+      setTwitchTesting(false);
+    }
+  }, []);
+
+  // Rebuild PBs per category, class and league from the completed runs
+  const handleRecomputePbs = useCallback(async () => {
+    setRecomputing(true);
+    try {
+      const count = await invoke<number>('recompute_personal_bests');
+      setRecomputeResult(`Rebuilt ${count} personal best${count !== 1 ? 's' : ''}`);
+      useRunStore.getState().loadPbAndGoldSplits();
+    } catch (error) {
+      setRecomputeResult(`Recompute failed: ${getErrorMessage(error)}`);
+    } finally {
+      setRecomputing(false);
+    }
+  }, []);
+
+  // Re-derive gems and build stats of every stored snapshot
+  const handleReprocessSnapshots = useCallback(async () => {
+    setReprocessing(true);
+    try {
+      const report = await invoke<ReprocessReport>('reprocess_snapshots', { filters: {} });
+      setReprocessResult(
+        `Updated ${report.updated} of ${report.snapshots} snapshots` +
+          (report.failed > 0 ? `, ${report.failed} couldn't be read` : '')
+      );
+    } catch (error) {
+      setReprocessResult(`Reprocessing failed: ${getErrorMessage(error)}`);
+    } finally {
+      setReprocessing(false);
+    }
+  }, []);
+
+  // Save recorded API responses and settings for a bug report
+  const handleExportDiagnostics = useCallback(async () => {
+    const path = await save({
+      defaultPath: `poe-watcher-diagnostics_${new Date().toISOString().slice(0, 10)}.json`,
+      filters: [{ name: 'JSON', extensions: ['json'] }],
+    });
+    if (!path) return; // user cancelled
+
+    try {
+      await invoke('export_diagnostics', { path });
+      setDiagnosticsResult('Diagnostics saved');
+    } catch (error) {
+      setDiagnosticsResult(`Export failed: ${getErrorMessage(error)}`);
+    }
+  }, []);
+
   // Breakpoints are loaded and auto-saved in App.tsx
 
   // Get unique acts from breakpoints
@@ -207,6 +504,25 @@ export function SettingsView() {
     }
   };
 
+  const handleBrowseAutoExportDir = async () => {
+    try {
+      const result = await open({ directory: true, multiple: false, title: 'Select Export Folder' });
+      if (result) {
+        setAutoExportDir(result);
+      }
+    } catch (error) {
+      console.error('Failed to browse for export folder:', error);
+    }
+  };
+
+  const autoExportFormatList = autoExportFormats.split(',').filter(Boolean);
+  const toggleAutoExportFormat = (format: string) => {
+    const next = autoExportFormatList.includes(format)
+      ? autoExportFormatList.filter((f) => f !== format)
+      : [...autoExportFormatList, format];
+    setAutoExportFormats(next.join(','));
+  };
+
   const handleDetectLogPath = async () => {
     try {
       const result = await invoke<string | null>('detect_log_path_cmd');
@@ -242,9 +558,56 @@ export function SettingsView() {
           overlay_accent_color: overlayAccentColor,
           overlay_always_on_top: overlayAlwaysOnTop,
           overlay_locked: overlayLocked,
+          warmup_detection_enabled: warmupDetectionEnabled,
+          session_gap_minutes: sessionGapMinutes,
+          launch_on_boot: launchOnBoot,
+          start_minimized: startMinimized,
+          minimize_to_tray: minimizeToTray,
+          background_mode: backgroundMode,
+          follow_game_process: followGameProcess,
+          separate_pbs_by_patch: separatePbsByPatch,
+          announce_sound: announceSound,
+          announce_speech: announceSpeech,
+          announce_golds_only: announceGoldsOnly,
+          announce_min_behind_ms: announceMinBehindMs,
+          announce_min_ahead_ms: announceMinAheadMs,
+          gold_protection_secs: goldProtectionSecs,
+          timer_start_offset_ms: timerStartOffsetMs,
+          record_api_responses: recordApiResponses,
+          api_recording_limit: apiRecordingLimit,
+          locale,
+          readiness_mode: readinessMode,
+          readiness_log_watcher: readinessLogWatcher,
+          readiness_account: readinessAccount,
+          readiness_overlay: readinessOverlay,
+          readiness_preset: readinessPreset,
+          readiness_profile_public: readinessProfilePublic,
+          auto_start_runs: autoStartRuns,
+          auto_start_zone: autoStartZone.trim() || campaignStartZone[game],
+          auto_pause: autoPause,
+          auto_export_dir: autoExportDir.trim(),
+          auto_export_formats: autoExportFormats,
+          auto_export_pbs_only: autoExportPbsOnly,
+          webhook_url: webhookUrl.trim(),
+          webhook_secret: webhookSecret.trim(),
+          twitch_token: twitchToken.trim(),
+          twitch_username: twitchUsername.trim(),
+          twitch_channel: twitchChannel.trim(),
+          twitch_pb_message: twitchPbMessage.trim(),
+          twitch_gold_message: twitchGoldMessage.trim(),
+          twitch_run_message: twitchRunMessage.trim(),
+          update_channel: updateChannel,
+          snapshot_retention: snapshotRetention,
+          snapshot_retention_runs: snapshotRetentionRuns,
+          snapshot_level_interval: snapshotLevelInterval,
+          image_proxy_hosts: imageProxyHosts.trim(),
+          game,
         },
       });
 
+      // PB comparisons depend on separate_pbs_by_patch
+      useRunStore.getState().loadPbAndGoldSplits();
+
       // Save breakpoints to localStorage (not stored in backend)
       // Deduplicate before saving
       try {
@@ -287,6 +650,22 @@ export function SettingsView() {
         <section className="mb-8">
           <h2 className="text-lg font-semibold text-[--color-text] mb-4">Path of Exile</h2>
           <div className="bg-[--color-surface] rounded-lg p-4 space-y-4">
+            {/* Game */}
+            <div className="flex items-center justify-between gap-4">
+              <div>
+                <div className="text-sm text-[--color-text]">Game</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Log format, campaign breakpoints and character API to use. Switching resets the breakpoints.
+                </div>
+              </div>
+              <CustomSelect
+                value={game}
+                onChange={(value) => setGame(value as Game)}
+                options={GAMES}
+                className="w-48"
+              />
+            </div>
+
             {/* Log path */}
             <div>
               <label className="block text-sm text-[--color-text-muted] mb-2">
@@ -323,94 +702,538 @@ export function SettingsView() {
               <label className="block text-sm text-[--color-text-muted] mb-2">
                 POE Account Name
               </label>
-              <input
-                type="text"
-                value={accountName}
-                onChange={(e) => setAccountName(e.target.value)}
-                placeholder="YourAccountName"
-                className="w-full p-3 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text] placeholder-[--color-text-muted]"
-              />
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  value={accountName}
+                  onChange={(e) => setAccountName(e.target.value)}
+                  onBlur={() => checkProfilePrivacy()}
+                  placeholder="YourAccountName"
+                  className="flex-1 p-3 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text] placeholder-[--color-text-muted]"
+                />
+                <button
+                  onClick={() => checkProfilePrivacy()}
+                  disabled={!accountName.trim()}
+                  className="px-4 py-2 bg-[--color-surface] text-[--color-text] rounded-lg border border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 active:scale-95 transition-all disabled:opacity-50"
+                >
+                  Check Privacy
+                </button>
+              </div>
               <p className="text-xs text-[--color-text-muted] mt-2">
-                Required for fetching character data from the POE API. Your profile must be set to public.
+                Required for fetching character data from the POE API. Your profile must be set to public
+                unless you sign in below.
               </p>
+              <ProfilePrivacyNotice showPublic className="mt-2" />
+              <PoeSignInSettings />
             </div>
 
-            {/* Test character name - dev only */}
-            {import.meta.env.DEV && (
+            {/* Warm-up detection */}
+            <div>
+              <div className="flex items-center justify-between">
+                <div>
+                  <div className="text-sm text-[--color-text]">Flag Warm-up Runs</div>
+                  <div className="text-xs text-[--color-text-muted]">
+                    Mark the first run of each session so stats can exclude it
+                  </div>
+                </div>
+                <button
+                  onClick={() => setWarmupDetectionEnabled(!warmupDetectionEnabled)}
+                  className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                    warmupDetectionEnabled ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                  }`}
+                >
+                  <div
+                    className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                      warmupDetectionEnabled ? 'translate-x-6' : 'translate-x-0.5'
+                    }`}
+                  />
+                </button>
+              </div>
+              {warmupDetectionEnabled && (
+                <div className="flex items-center gap-2 mt-2">
+                  <span className="text-xs text-[--color-text-muted]">New session after</span>
+                  <input
+                    type="number"
+                    min={10}
+                    value={sessionGapMinutes}
+                    onChange={(e) => setSessionGapMinutes(Math.max(10, Number(e.target.value) || 0))}
+                    className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                  />
+                  <span className="text-xs text-[--color-text-muted]">minutes without a run</span>
+                </div>
+              )}
+            </div>
+
+            {/* Auto-start */}
+            <div>
+              <div className="flex items-center justify-between">
+                <div>
+                  <div className="text-sm text-[--color-text]">Auto-start Runs</div>
+                  <div className="text-xs text-[--color-text-muted]">
+                    Start a run when a new character enters the starting zone
+                  </div>
+                </div>
+                <button
+                  onClick={() => setAutoStartRuns(!autoStartRuns)}
+                  className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                    autoStartRuns ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                  }`}
+                >
+                  <div
+                    className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                      autoStartRuns ? 'translate-x-6' : 'translate-x-0.5'
+                    }`}
+                  />
+                </button>
+              </div>
+              {autoStartRuns && (
+                <div className="flex items-center gap-2 mt-2">
+                  <span className="text-xs text-[--color-text-muted]">Starting zone</span>
+                  <input
+                    type="text"
+                    value={autoStartZone}
+                    onChange={(e) => setAutoStartZone(e.target.value)}
+                    placeholder={campaignStartZone[game]}
+                    className="flex-1 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                  />
+                </div>
+              )}
+            </div>
+
+            {/* Auto-pause */}
+            <div className="flex items-center justify-between">
               <div>
-                <label className="block text-sm text-[--color-text-muted] mb-2">
-                  Test Character Name
-                </label>
+                <div className="text-sm text-[--color-text]">Auto-pause</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Pause the timer while AFK or disconnected, resuming when you're back
+                </div>
+              </div>
+              <button
+                onClick={() => setAutoPause(!autoPause)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  autoPause ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    autoPause ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+
+            {/* Auto-export */}
+            <div>
+              <div className="text-sm text-[--color-text]">Auto-export Completed Runs</div>
+              <div className="text-xs text-[--color-text-muted]">
+                Write exports to a folder whenever a run completes
+              </div>
+              <div className="flex gap-2 mt-2">
                 <input
                   type="text"
-                  value={testCharacterName}
-                  onChange={(e) => setTestCharacterName(e.target.value)}
-                  placeholder="beerdz_layoutguy"
-                  className="w-full p-3 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text] placeholder-[--color-text-muted]"
+                  value={autoExportDir}
+                  onChange={(e) => setAutoExportDir(e.target.value)}
+                  placeholder="Export folder (empty = off)"
+                  className="flex-1 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
                 />
-                <p className="text-xs text-[--color-text-muted] mt-2">
-                  Fallback character name for snapshots when not detected from game events.
-                </p>
+                <button
+                  onClick={handleBrowseAutoExportDir}
+                  className="px-3 py-1 bg-[--color-surface] text-[--color-text] rounded border border-[--color-border] hover:border-[--color-poe-gold]/70 text-sm"
+                >
+                  Browse
+                </button>
               </div>
-            )}
-          </div>
-        </section>
+              <div className="flex items-center gap-4 mt-2 text-xs text-[--color-text-muted]">
+                {['json', 'csv', 'lss'].map((format) => (
+                  <label key={format} className="flex items-center gap-1">
+                    <input
+                      type="checkbox"
+                      checked={autoExportFormatList.includes(format)}
+                      onChange={() => toggleAutoExportFormat(format)}
+                    />
+                    {format.toUpperCase()}
+                  </label>
+                ))}
+                <label className="flex items-center gap-1 ml-auto">
+                  <input
+                    type="checkbox"
+                    checked={autoExportPbsOnly}
+                    onChange={(e) => setAutoExportPbsOnly(e.target.checked)}
+                  />
+                  Only personal bests
+                </label>
+              </div>
+            </div>
 
-        {/* Breakpoints — Wizard */}
-        <section className="mb-8">
-          <details className="group" open>
-            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
-              <svg
-                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
-                fill="none" stroke="currentColor" viewBox="0 0 24 24"
-              >
-                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
-              </svg>
-              Breakpoints
-            </summary>
-          <p className="text-sm text-[--color-text-muted] mb-4">
-            Configure which zone transitions trigger automatic splits. Use the wizard to generate a breakpoint set based on your run type and routing preferences.
-          </p>
-          <BreakpointWizard />
-          </details>
-        </section>
+            {/* Notification webhook */}
+            <div>
+              <div className="text-sm text-[--color-text]">Notification Webhook</div>
+              <div className="text-xs text-[--color-text-muted]">
+                POST a JSON summary on run completion (with any auto-exported files), new PBs and gold splits (works with Discord webhooks)
+              </div>
+              <input
+                type="text"
+                value={webhookUrl}
+                onChange={(e) => setWebhookUrl(e.target.value)}
+                placeholder="Webhook URL (empty = off)"
+                className="w-full mt-2 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+              <input
+                type="password"
+                value={webhookSecret}
+                onChange={(e) => setWebhookSecret(e.target.value)}
+                placeholder="Signing secret (optional)"
+                className="w-full mt-2 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+            </div>
 
-        {/* Route Customizations */}
-        <section className="mb-8">
-          <details className="group">
-            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
-              <svg
-                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
-                fill="none" stroke="currentColor" viewBox="0 0 24 24"
-              >
-                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
-              </svg>
-              Route Customizations
-              <span className="text-xs font-normal text-[--color-text-muted]">(Optional)</span>
-            </summary>
-            <div className="bg-[--color-surface] rounded-lg p-4">
-              <p className="text-sm text-[--color-text-muted] mb-4">
-                Adjust zone ordering to match your preferred routing. Changes apply immediately to the wizard breakpoints.
-              </p>
-              <RouteCustomizations />
+            {/* Twitch chat */}
+            <div>
+              <div className="text-sm text-[--color-text]">Twitch Chat</div>
+              <div className="text-xs text-[--color-text-muted]">
+                Post to your chat on new PBs, gold splits and finished runs. Templates can use {'{category}'}, {'{time}'},{' '}
+                {'{character}'}, {'{class}'}, {'{league}'}, and for golds {'{split}'}, {'{splitTime}'}, {'{segment}'},{' '}
+                {'{delta}'}; leave one empty to skip that event
+              </div>
+              <input
+                type="text"
+                value={twitchChannel}
+                onChange={(e) => setTwitchChannel(e.target.value)}
+                placeholder="Channel (empty = off)"
+                className="w-full mt-2 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+              <input
+                type="password"
+                value={twitchToken}
+                onChange={(e) => setTwitchToken(e.target.value)}
+                placeholder="Chat OAuth token (oauth:...)"
+                className="w-full mt-2 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+              <input
+                type="text"
+                value={twitchUsername}
+                onChange={(e) => setTwitchUsername(e.target.value)}
+                placeholder="Bot account (empty = the channel's own account)"
+                className="w-full mt-2 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+              <input
+                type="text"
+                value={twitchPbMessage}
+                onChange={(e) => setTwitchPbMessage(e.target.value)}
+                placeholder="PB message"
+                className="w-full mt-2 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+              <input
+                type="text"
+                value={twitchGoldMessage}
+                onChange={(e) => setTwitchGoldMessage(e.target.value)}
+                placeholder="Gold split message"
+                className="w-full mt-2 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+              <input
+                type="text"
+                value={twitchRunMessage}
+                onChange={(e) => setTwitchRunMessage(e.target.value)}
+                placeholder="Completed run message (non-PB)"
+                className="w-full mt-2 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+              <div className="flex items-center justify-between mt-2">
+                <div className="text-xs text-[--color-text-muted]">
+                  {twitchTestResult ?? 'Save first; the test uses the saved settings'}
+                </div>
+                <button
+                  onClick={handleTwitchTest}
+                  disabled={twitchTesting}
+                  className="px-3 py-1 text-sm bg-[--color-surface-elevated] text-[--color-text] rounded border border-[--color-border] hover:border-[--color-poe-gold] disabled:opacity-50"
+                >
+                  {twitchTesting ? 'Sending...' : 'Send Test'}
+                </button>
+              </div>
             </div>
-          </details>
-        </section>
 
-        {/* Advanced: Manual Overrides */}
-        <section className="mb-8">
-          <details className="group">
-            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
-              <svg
-                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
-                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+            {/* Personal bests */}
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Separate PBs by Patch</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Compare against PBs from the current major patch only (e.g. 3.25)
+                </div>
+              </div>
+              <button
+                onClick={() => setSeparatePbsByPatch(!separatePbsByPatch)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  separatePbsByPatch ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
               >
-                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
-              </svg>
-              Advanced: Manual Overrides
-            </summary>
-            <div className="bg-[--color-surface] rounded-lg overflow-hidden">
-              {/* Filter and bulk actions */}
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    separatePbsByPatch ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+
+            {/* Gold protection */}
+            <div>
+              <div className="text-sm text-[--color-text]">Gold Split Protection</div>
+              <div className="text-xs text-[--color-text-muted] mb-2">
+                Golds set shortly before undoing a split or resetting are reverted, so mis-splits don't stick
+              </div>
+              <div className="flex items-center gap-2">
+                <input
+                  type="number"
+                  min={0}
+                  value={goldProtectionSecs}
+                  onChange={(e) => setGoldProtectionSecs(Math.max(0, Number(e.target.value) || 0))}
+                  className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                />
+                <span className="text-xs text-[--color-text-muted]">seconds (0 to disable)</span>
+              </div>
+            </div>
+
+            {/* Level snapshots */}
+            <div>
+              <div className="text-sm text-[--color-text]">Level Snapshots</div>
+              <div className="text-xs text-[--color-text-muted] mb-2">
+                Also capture the build at level milestones, whichever breakpoints are enabled
+              </div>
+              <div className="flex items-center gap-2">
+                <span className="text-xs text-[--color-text-muted]">Every</span>
+                <input
+                  type="number"
+                  min={0}
+                  max={100}
+                  value={snapshotLevelInterval}
+                  onChange={(e) => setSnapshotLevelInterval(Math.min(100, Math.max(0, Number(e.target.value) || 0)))}
+                  className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                />
+                <span className="text-xs text-[--color-text-muted]">levels (0 to disable)</span>
+              </div>
+            </div>
+
+            {/* Timer start offset */}
+            <div>
+              <div className="text-sm text-[--color-text]">Start Countdown</div>
+              <div className="text-xs text-[--color-text-muted] mb-2">
+                Start the timer below zero, e.g. to cover character creation or a category's start rule
+              </div>
+              <div className="flex items-center gap-2">
+                <span className="text-xs text-[--color-text-muted]">-</span>
+                <input
+                  type="number"
+                  min={0}
+                  step={0.1}
+                  value={timerStartOffsetMs / 1000}
+                  onChange={(e) => setTimerStartOffsetMs(Math.round(Math.max(0, Number(e.target.value) || 0) * 1000))}
+                  className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                />
+                <span className="text-xs text-[--color-text-muted]">seconds (0 to start at zero)</span>
+              </div>
+            </div>
+
+            <RunChecklistSettings />
+
+            {/* Startup */}
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Launch on System Startup</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Start POE Watcher when you log in
+                </div>
+              </div>
+              <button
+                onClick={() => setLaunchOnBoot(!launchOnBoot)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  launchOnBoot ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    launchOnBoot ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Start Minimized to Tray</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Open hidden in the tray with the log watcher already running
+                </div>
+              </div>
+              <button
+                onClick={() => setStartMinimized(!startMinimized)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  startMinimized ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    startMinimized ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Minimize to Tray</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Minimizing the window hides it in the tray instead of the taskbar
+                </div>
+              </div>
+              <button
+                onClick={() => setMinimizeToTray(!minimizeToTray)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  minimizeToTray ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    minimizeToTray ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Background Mode</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Closing the window hides it instead of quitting; the timer, splits, hotkeys and overlay keep running. Quit from the tray
+                </div>
+              </div>
+              <button
+                onClick={() => setBackgroundMode(!backgroundMode)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  backgroundMode ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    backgroundMode ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Follow the Game (Linux, experimental)</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Start watching the log and show the overlay when Path of Exile starts under Wine or Proton; stop and hide them when it exits
+                </div>
+              </div>
+              <button
+                onClick={() => setFollowGameProcess(!followGameProcess)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  followGameProcess ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    followGameProcess ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+
+            {/* Test character name - dev only */}
+            {import.meta.env.DEV && (
+              <div>
+                <label className="block text-sm text-[--color-text-muted] mb-2">
+                  Test Character Name
+                </label>
+                <input
+                  type="text"
+                  value={testCharacterName}
+                  onChange={(e) => setTestCharacterName(e.target.value)}
+                  placeholder="beerdz_layoutguy"
+                  className="w-full p-3 bg-[--color-surface-elevated] border border-[--color-border] rounded-lg text-[--color-text] placeholder-[--color-text-muted]"
+                />
+                <p className="text-xs text-[--color-text-muted] mt-2">
+                  Fallback character name for snapshots when not detected from game events.
+                </p>
+              </div>
+            )}
+          </div>
+        </section>
+
+        {/* Breakpoints — Wizard */}
+        <section className="mb-8">
+          <details className="group" open>
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Breakpoints
+            </summary>
+          <p className="text-sm text-[--color-text-muted] mb-4">
+            Configure which zone transitions trigger automatic splits. Use the wizard to generate a breakpoint set based on your run type and routing preferences.
+          </p>
+          <BreakpointWizard />
+          </details>
+        </section>
+
+        {/* Custom Breakpoint Presets */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Custom Presets
+              <span className="text-xs font-normal text-[--color-text-muted]">(Optional)</span>
+            </summary>
+            <div className="bg-[--color-surface] rounded-lg p-4">
+              <BreakpointPresetsSettings />
+            </div>
+          </details>
+        </section>
+
+        {/* Route Customizations */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Route Customizations
+              <span className="text-xs font-normal text-[--color-text-muted]">(Optional)</span>
+            </summary>
+            <div className="bg-[--color-surface] rounded-lg p-4">
+              <p className="text-sm text-[--color-text-muted] mb-4">
+                Adjust zone ordering to match your preferred routing. Changes apply immediately to the wizard breakpoints.
+              </p>
+              <RouteCustomizations />
+            </div>
+          </details>
+        </section>
+
+        {/* Advanced: Manual Overrides */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Advanced: Manual Overrides
+            </summary>
+            <div className="bg-[--color-surface] rounded-lg overflow-hidden">
+              {/* Filter and bulk actions */}
               <div className="p-4 border-b border-[--color-border] space-y-3">
                 <p className="text-sm text-[--color-text-muted]">
                   Fine-tune individual breakpoints. Changes here override the wizard output.
@@ -526,81 +1349,97 @@ export function SettingsView() {
                   </div>
                 ) : (
                   filteredBreakpoints.map((bp, index) => (
-                    <div
-                      key={`${index}-${bp.name}`}
-                      className="flex items-center justify-between p-3 border-b border-[--color-border] last:border-0 hover:bg-[--color-surface-elevated]/50"
-                    >
-                      <div className="flex items-center gap-3 flex-1 min-w-0">
-                        <span className="text-sm flex-shrink-0">{getTypeIcon(bp.type)}</span>
-                        <span className={`truncate ${bp.isEnabled ? 'text-[--color-text]' : 'text-[--color-text-muted]'}`}>{bp.name}</span>
-                        <span className="text-xs text-[--color-text-muted] bg-[--color-surface-elevated] px-2 py-0.5 rounded flex-shrink-0">
-                          {bp.type}
-                        </span>
-                        {bp.trigger.act && (
-                          <span className="text-xs text-[--color-text-muted] flex-shrink-0">
-                            A{bp.trigger.act}
+                    <div key={`${index}-${bp.name}`} className="border-b border-[--color-border] last:border-0">
+                      <div className="flex items-center justify-between p-3 hover:bg-[--color-surface-elevated]/50">
+                        <div className="flex items-center gap-3 flex-1 min-w-0">
+                          <span className="text-sm flex-shrink-0">{getTypeIcon(bp.type)}</span>
+                          <span className={`truncate ${bp.isEnabled ? 'text-[--color-text]' : 'text-[--color-text-muted]'}`}>{bp.name}</span>
+                          <span className="text-xs text-[--color-text-muted] bg-[--color-surface-elevated] px-2 py-0.5 rounded flex-shrink-0">
+                            {bp.type}
                           </span>
-                        )}
-                      </div>
-                      <div className="flex items-center gap-3 flex-shrink-0">
-                        {/* Move buttons */}
-                        <div className="flex gap-1">
+                          {bp.trigger.act && (
+                            <span className="text-xs text-[--color-text-muted] flex-shrink-0">
+                              A{bp.trigger.act}
+                            </span>
+                          )}
+                        </div>
+                        <div className="flex items-center gap-3 flex-shrink-0">
+                          {/* Move buttons */}
+                          <div className="flex gap-1">
+                            <button
+                              onClick={() => moveBreakpoint(bp.name, 'up')}
+                              disabled={index === 0}
+                              className="p-1 text-[--color-text-muted] hover:text-[--color-text] disabled:opacity-30 disabled:cursor-not-allowed active:scale-90 transition-all"
+                              title="Move up"
+                            >
+                              <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M5 15l7-7 7 7" />
+                              </svg>
+                            </button>
+                            <button
+                              onClick={() => moveBreakpoint(bp.name, 'down')}
+                              disabled={index === filteredBreakpoints.length - 1}
+                              className="p-1 text-[--color-text-muted] hover:text-[--color-text] disabled:opacity-30 disabled:cursor-not-allowed active:scale-90 transition-all"
+                              title="Move down"
+                            >
+                              <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M19 9l-7 7-7-7" />
+                              </svg>
+                            </button>
+                          </div>
+                          {/* Trigger rule */}
                           <button
-                            onClick={() => moveBreakpoint(bp.name, 'up')}
-                            disabled={index === 0}
-                            className="p-1 text-[--color-text-muted] hover:text-[--color-text] disabled:opacity-30 disabled:cursor-not-allowed active:scale-90 transition-all"
-                            title="Move up"
+                            onClick={() => setEditingRule(editingRule === bp.name ? null : bp.name)}
+                            className={`p-1.5 rounded transition-all active:scale-90 border ${
+                              bp.trigger.rule
+                                ? 'text-[--color-poe-gold] bg-[--color-poe-gold]/10 border-[--color-poe-gold]/50'
+                                : 'text-zinc-500 hover:text-zinc-300 border-transparent hover:border-zinc-600'
+                            }`}
+                            title={bp.trigger.rule ? 'Trigger rule set - click to edit' : 'Add a trigger rule (Nth entry, after a quest)'}
                           >
                             <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M5 15l7-7 7 7" />
+                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M3 4a1 1 0 011-1h16a1 1 0 011 1v2.586a1 1 0 01-.293.707l-6.414 6.414a1 1 0 00-.293.707V17l-4 4v-6.586a1 1 0 00-.293-.707L3.293 7.293A1 1 0 013 6.586V4z" />
                             </svg>
                           </button>
+                          {/* Snapshot toggle */}
                           <button
-                            onClick={() => moveBreakpoint(bp.name, 'down')}
-                            disabled={index === filteredBreakpoints.length - 1}
-                            className="p-1 text-[--color-text-muted] hover:text-[--color-text] disabled:opacity-30 disabled:cursor-not-allowed active:scale-90 transition-all"
-                            title="Move down"
+                            onClick={() => toggleSnapshotCapture(bp.name)}
+                            disabled={!bp.isEnabled}
+                            className={`p-1.5 rounded transition-all active:scale-90 ${
+                              bp.captureSnapshot && bp.isEnabled
+                                ? 'text-amber-400 bg-amber-400/20 border border-amber-400/50'
+                                : bp.isEnabled
+                                ? 'text-zinc-500 hover:text-zinc-300 border border-transparent hover:border-zinc-600'
+                                : 'text-zinc-600 border border-transparent opacity-30 cursor-not-allowed'
+                            }`}
+                            title={bp.captureSnapshot ? 'Snapshot enabled - click to disable' : 'Click to enable snapshot capture'}
                           >
-                            <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M19 9l-7 7-7-7" />
+                            <svg className="w-4 h-4" fill={bp.captureSnapshot && bp.isEnabled ? 'currentColor' : 'none'} stroke="currentColor" viewBox="0 0 24 24">
+                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M3 9a2 2 0 012-2h.93a2 2 0 001.664-.89l.812-1.22A2 2 0 0110.07 4h3.86a2 2 0 011.664.89l.812 1.22A2 2 0 0018.07 7H19a2 2 0 012 2v9a2 2 0 01-2 2H5a2 2 0 01-2-2V9z" />
+                              <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M15 13a3 3 0 11-6 0 3 3 0 016 0z" />
                             </svg>
                           </button>
-                        </div>
-                        {/* Snapshot toggle */}
-                        <button
-                          onClick={() => toggleSnapshotCapture(bp.name)}
-                          disabled={!bp.isEnabled}
-                          className={`p-1.5 rounded transition-all active:scale-90 ${
-                            bp.captureSnapshot && bp.isEnabled
-                              ? 'text-amber-400 bg-amber-400/20 border border-amber-400/50'
-                              : bp.isEnabled
-                              ? 'text-zinc-500 hover:text-zinc-300 border border-transparent hover:border-zinc-600'
-                              : 'text-zinc-600 border border-transparent opacity-30 cursor-not-allowed'
-                          }`}
-                          title={bp.captureSnapshot ? 'Snapshot enabled - click to disable' : 'Click to enable snapshot capture'}
-                        >
-                          <svg className="w-4 h-4" fill={bp.captureSnapshot && bp.isEnabled ? 'currentColor' : 'none'} stroke="currentColor" viewBox="0 0 24 24">
-                            <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M3 9a2 2 0 012-2h.93a2 2 0 001.664-.89l.812-1.22A2 2 0 0110.07 4h3.86a2 2 0 011.664.89l.812 1.22A2 2 0 0018.07 7H19a2 2 0 012 2v9a2 2 0 01-2 2H5a2 2 0 01-2-2V9z" />
-                            <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M15 13a3 3 0 11-6 0 3 3 0 016 0z" />
-                          </svg>
-                        </button>
-                        {/* Split toggle */}
-                        <button
-                          onClick={() => toggleBreakpoint(bp.name)}
-                          className={`w-10 h-5 rounded-full transition-all duration-150 active:scale-95 border ${
-                            bp.isEnabled
-                              ? 'bg-green-600 border-green-500'
-                              : 'bg-zinc-700 border-zinc-600'
-                          }`}
-                          title={bp.isEnabled ? 'Split enabled' : 'Enable split'}
-                        >
-                          <div
-                            className={`w-4 h-4 rounded-full bg-white shadow transition-transform duration-150 ${
-                              bp.isEnabled ? 'translate-x-5' : 'translate-x-0.5'
+                          {/* Split toggle */}
+                          <button
+                            onClick={() => toggleBreakpoint(bp.name)}
+                            className={`w-10 h-5 rounded-full transition-all duration-150 active:scale-95 border ${
+                              bp.isEnabled
+                                ? 'bg-green-600 border-green-500'
+                                : 'bg-zinc-700 border-zinc-600'
                             }`}
-                          />
-                        </button>
+                            title={bp.isEnabled ? 'Split enabled' : 'Enable split'}
+                          >
+                            <div
+                              className={`w-4 h-4 rounded-full bg-white shadow transition-transform duration-150 ${
+                                bp.isEnabled ? 'translate-x-5' : 'translate-x-0.5'
+                              }`}
+                            />
+                          </button>
+                        </div>
                       </div>
+                      {editingRule === bp.name && (
+                        <TriggerRuleEditor breakpoint={bp} onChange={(rule) => setBreakpointRule(bp.name, rule)} />
+                      )}
                     </div>
                   ))
                 )}
@@ -850,6 +1689,124 @@ export function SettingsView() {
                 </button>
               </div>
             </div>
+
+            {/* Components shown */}
+            <div className="pt-3 border-t border-[--color-border]">
+              <h3 className="text-sm font-semibold text-[--color-text-muted] mb-3 uppercase tracking-wide">Layout</h3>
+              <OverlayLayoutSettings />
+            </div>
+
+            {/* Extra overlay windows */}
+            <div className="pt-3 border-t border-[--color-border]">
+              <h3 className="text-sm font-semibold text-[--color-text-muted] mb-3 uppercase tracking-wide">Overlay Windows</h3>
+              <OverlayProfilesSettings />
+            </div>
+          </div>
+          </details>
+        </section>
+
+        {/* Split Announcements */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Split Announcements
+            </summary>
+          <div className="bg-[--color-surface] rounded-lg p-4 space-y-4">
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Play Sound</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Short tone on announced splits (higher pitch for golds)
+                </div>
+              </div>
+              <button
+                onClick={() => setAnnounceSound(!announceSound)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  announceSound ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    announceSound ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Speak Delta</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Read the split and delta aloud, e.g. "The Coast, 35 seconds behind"
+                </div>
+              </div>
+              <button
+                onClick={() => setAnnounceSpeech(!announceSpeech)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  announceSpeech ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    announceSpeech ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-sm text-[--color-text]">Golds Only</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Only announce splits that beat your best segment
+                </div>
+              </div>
+              <button
+                onClick={() => setAnnounceGoldsOnly(!announceGoldsOnly)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  announceGoldsOnly ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    announceGoldsOnly ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+            {!announceGoldsOnly && (
+            <div className="space-y-2">
+              <div className="flex items-center gap-2">
+                <span className="text-xs text-[--color-text-muted] w-28">Behind by at least</span>
+                <input
+                  type="number"
+                  min={0}
+                  value={Math.round(announceMinBehindMs / 1000)}
+                  onChange={(e) => setAnnounceMinBehindMs(Math.max(0, Number(e.target.value) || 0) * 1000)}
+                  className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                />
+                <span className="text-xs text-[--color-text-muted]">seconds</span>
+              </div>
+              <div className="flex items-center gap-2">
+                <span className="text-xs text-[--color-text-muted] w-28">Ahead by at least</span>
+                <input
+                  type="number"
+                  min={0}
+                  value={Math.round(announceMinAheadMs / 1000)}
+                  onChange={(e) => setAnnounceMinAheadMs(Math.max(0, Number(e.target.value) || 0) * 1000)}
+                  className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                />
+                <span className="text-xs text-[--color-text-muted]">seconds</span>
+              </div>
+              <p className="text-xs text-[--color-text-muted]">
+                Splits closer to your PB than this stay quiet. Golds are always announced.
+              </p>
+            </div>
+            )}
           </div>
           </details>
         </section>
@@ -941,6 +1898,19 @@ export function SettingsView() {
               </button>
             </div>
 
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Update Channel</div>
+                <div className="text-xs text-[--color-text-muted]">Beta installs prerelease builds before they reach stable</div>
+              </div>
+              <CustomSelect
+                value={updateChannel}
+                onChange={(value) => setUpdateChannel(value as UpdateChannel)}
+                options={UPDATE_CHANNELS}
+                className="w-48"
+              />
+            </div>
+
             <div className="flex items-center justify-between pt-2 border-t border-[--color-border]">
               <div>
                 <div className="text-[--color-text]">Check Now</div>
@@ -981,6 +1951,283 @@ export function SettingsView() {
           </details>
         </section>
 
+        {/* Category Defaults */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Category Defaults
+            </summary>
+          <div className="bg-[--color-surface] rounded-lg p-4">
+            <CategoryDefaultsSettings />
+          </div>
+          </details>
+        </section>
+
+        {/* Phone Status Page */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Phone Status Page
+            </summary>
+          <div className="bg-[--color-surface] rounded-lg p-4">
+            <StatusServerSettings />
+          </div>
+          </details>
+        </section>
+
+        {/* OBS Browser Source */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              OBS Browser Source
+            </summary>
+          <div className="bg-[--color-surface] rounded-lg p-4">
+            <ObsServerSettings />
+          </div>
+          </details>
+        </section>
+
+        {/* Leveling Guide */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Leveling Guide
+            </summary>
+          <div className="bg-[--color-surface] rounded-lg p-4">
+            <GuideSettings />
+          </div>
+          </details>
+        </section>
+
+        {/* Data */}
+        <section className="mb-8">
+          <details className="group">
+            <summary className="cursor-pointer text-lg font-semibold text-[--color-text] mb-4 select-none flex items-center gap-2 hover:text-[--color-poe-gold] transition-colors">
+              <svg
+                className="w-4 h-4 text-[--color-text-muted] transition-transform group-open:rotate-90"
+                fill="none" stroke="currentColor" viewBox="0 0 24 24"
+              >
+                <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9 5l7 7-7 7" />
+              </svg>
+              Data
+            </summary>
+          <div className="bg-[--color-surface] rounded-lg p-4 space-y-4">
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Repair Database</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  {repairResult ?? 'Remove orphaned splits and snapshots, and fix stale PBs and golds'}
+                </div>
+              </div>
+              <button
+                onClick={handleRepairDatabase}
+                disabled={repairing}
+                className="px-4 py-2 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 shadow-sm hover:border-[--color-poe-gold]/70 hover:shadow-md active:scale-95 active:shadow-none transition-all font-medium disabled:opacity-50 disabled:cursor-wait"
+              >
+                {repairing ? 'Repairing...' : 'Repair'}
+              </button>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Snapshot Retention</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Snapshots Compact keeps. PB, reference and in-progress runs keep all of theirs.
+                </div>
+              </div>
+              <div className="flex items-center gap-2">
+                {snapshotRetention === 'recent' && (
+                  <input
+                    type="number"
+                    min={1}
+                    value={snapshotRetentionRuns}
+                    onChange={(e) => setSnapshotRetentionRuns(Math.max(1, Number(e.target.value) || 0))}
+                    className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                  />
+                )}
+                <CustomSelect
+                  value={snapshotRetention}
+                  onChange={(value) => setSnapshotRetention(value as SnapshotRetention)}
+                  options={SNAPSHOT_RETENTIONS}
+                  className="w-48"
+                />
+              </div>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Compact Database</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  {compactResult ?? 'Prune snapshots by the saved retention, compress old ones, and shrink the file'}
+                </div>
+              </div>
+              <button
+                onClick={handleCompactDatabase}
+                disabled={compacting}
+                className="px-4 py-2 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 shadow-sm hover:border-[--color-poe-gold]/70 hover:shadow-md active:scale-95 active:shadow-none transition-all font-medium disabled:opacity-50 disabled:cursor-wait"
+              >
+                {compacting ? 'Compacting...' : 'Compact'}
+              </button>
+            </div>
+
+            <div>
+              <div className="text-sm text-[--color-text]">Trusted Image Hosts</div>
+              <div className="text-xs text-[--color-text-muted] mb-2">
+                Hosts item icons and passive tree art may be loaded from, comma-separated
+              </div>
+              <input
+                type="text"
+                value={imageProxyHosts}
+                onChange={(e) => setImageProxyHosts(e.target.value)}
+                placeholder="web.poecdn.com"
+                className="w-full px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+              />
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Image Cache</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  {imageCacheStats ? formatImageCacheStats(imageCacheStats) : 'Item icons kept on disk for a week'}
+                </div>
+              </div>
+              <button
+                onClick={handleClearImageCache}
+                disabled={clearingImageCache || imageCacheStats?.entries === 0}
+                className="px-4 py-2 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 shadow-sm hover:border-[--color-poe-gold]/70 hover:shadow-md active:scale-95 active:shadow-none transition-all font-medium disabled:opacity-50 disabled:cursor-wait"
+              >
+                {clearingImageCache ? 'Clearing...' : 'Clear'}
+              </button>
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Recompute Personal Bests</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  {recomputeResult ?? 'Rebuild the PB of every category, class and league from your completed runs'}
+                </div>
+              </div>
+              <button
+                onClick={handleRecomputePbs}
+                disabled={recomputing}
+                className="px-4 py-2 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 shadow-sm hover:border-[--color-poe-gold]/70 hover:shadow-md active:scale-95 active:shadow-none transition-all font-medium disabled:opacity-50 disabled:cursor-wait"
+              >
+                {recomputing ? 'Recomputing...' : 'Recompute'}
+              </button>
+            </div>
+
+            <ArchiveSettings />
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Reprocess Snapshots</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  {reprocessResult ?? 'Re-derive gems and build stats of old snapshots from their stored items and passives'}
+                </div>
+              </div>
+              <button
+                onClick={handleReprocessSnapshots}
+                disabled={reprocessing}
+                className="px-4 py-2 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 shadow-sm hover:border-[--color-poe-gold]/70 hover:shadow-md active:scale-95 active:shadow-none transition-all font-medium disabled:opacity-50 disabled:cursor-wait"
+              >
+                {reprocessing ? 'Reprocessing...' : 'Reprocess'}
+              </button>
+            </div>
+
+            {/* API response recording */}
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Record API Responses</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Keep the raw POE API responses of recent snapshot attempts for bug reports
+                </div>
+              </div>
+              <button
+                onClick={() => setRecordApiResponses(!recordApiResponses)}
+                className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+                  recordApiResponses ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+                }`}
+              >
+                <div
+                  className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+                    recordApiResponses ? 'translate-x-6' : 'translate-x-0.5'
+                  }`}
+                />
+              </button>
+            </div>
+            {recordApiResponses && (
+              <div className="flex items-center gap-2">
+                <span className="text-xs text-[--color-text-muted]">Keep the last</span>
+                <input
+                  type="number"
+                  min={1}
+                  max={100}
+                  value={apiRecordingLimit}
+                  onChange={(e) => setApiRecordingLimit(Math.min(100, Math.max(1, Number(e.target.value) || 1)))}
+                  className="w-20 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+                />
+                <span className="text-xs text-[--color-text-muted]">snapshot attempts</span>
+              </div>
+            )}
+
+            <div className="flex items-center justify-between gap-4">
+              <div>
+                <div className="text-[--color-text]">Export Format</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  Decimal separator, time and date format for CSV, Markdown and HTML split tables
+                </div>
+              </div>
+              <CustomSelect
+                value={locale}
+                onChange={setLocale}
+                options={EXPORT_LOCALES}
+                className="w-80"
+              />
+            </div>
+
+            <div className="flex items-center justify-between">
+              <div>
+                <div className="text-[--color-text]">Export Diagnostics</div>
+                <div className="text-xs text-[--color-text-muted]">
+                  {diagnosticsResult ?? 'Save settings and recorded API responses to attach to a bug report'}
+                </div>
+              </div>
+              <button
+                onClick={handleExportDiagnostics}
+                className="px-4 py-2 text-sm bg-[--color-surface] text-[--color-text] rounded-md border-2 border-[--color-poe-gold]/40 shadow-sm hover:border-[--color-poe-gold]/70 hover:shadow-md active:scale-95 active:shadow-none transition-all font-medium"
+              >
+                Export
+              </button>
+            </div>
+
+          </div>
+          </details>
+        </section>
+
         {/* Save button */}
         <div className="flex gap-3 items-center">
           <button
@@ -1010,6 +2257,33 @@ export function SettingsView() {
   );
 }
 
+function formatRepairReport(report: RepairReport): string {
+  const fixes = [
+    [report.orphanedSplits, 'orphaned splits'],
+    [report.orphanedSnapshots, 'orphaned snapshots'],
+    [report.orphanedSplitNotes, 'orphaned split notes'],
+    [report.runsMissingEndTime, 'runs missing an end time'],
+    [report.personalBestsFixed, 'personal bests'],
+    [report.goldSplitsFixed, 'gold splits'],
+  ] as const;
+  const fixed = fixes.filter(([count]) => count > 0).map(([count, label]) => `${count} ${label}`);
+  return fixed.length > 0 ? `Fixed ${fixed.join(', ')}` : 'No problems found';
+}
+
+function formatCompactReport(report: CompactReport): string {
+  const mb = (bytes: number) => `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  return (
+    `Pruned ${report.snapshotsPruned} and compressed ${report.snapshotsCompressed} snapshots, ` +
+    `${mb(report.bytesBefore)} to ${mb(report.bytesAfter)}`
+  );
+}
+
+function formatImageCacheStats(stats: ImageCacheStats): string {
+  const lookups = stats.hits + stats.misses;
+  const hitRate = lookups > 0 ? `, ${Math.round((stats.hits / lookups) * 100)}% served from cache this session` : '';
+  return `${stats.entries} icons, ${(stats.sizeBytes / (1024 * 1024)).toFixed(1)} MB${hitRate}`;
+}
+
 function getTypeIcon(type: string): string {
   switch (type) {
     case 'zone': return '📍';
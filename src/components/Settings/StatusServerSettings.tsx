@@ -0,0 +1,88 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { StatusServerInfo } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
+
+/** Enable the read-only phone status page and show its pairing URL */
+export function StatusServerSettings() {
+  const [info, setInfo] = useState<StatusServerInfo | null>(null);
+  const [port, setPort] = useState(8733);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<StatusServerInfo>('get_status_server')
+      .then((loaded) => {
+        setInfo(loaded);
+        setPort(loaded.port);
+      })
+      .catch((err) => setError(getErrorMessage(err)));
+  }, []);
+
+  const apply = useCallback(async (command: string, args?: Record<string, unknown>) => {
+    try {
+      setInfo(await invoke<StatusServerInfo>(command, args));
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    }
+  }, []);
+
+  const enabled = info?.enabled ?? false;
+
+  return (
+    <div className="space-y-3">
+      <div className="flex items-center justify-between">
+        <div>
+          <div className="text-[--color-text]">Phone Status Page</div>
+          <div className="text-xs text-[--color-text-muted]">
+            Read-only timer, delta, and recent runs for a phone on the same network
+          </div>
+        </div>
+        <button
+          onClick={() => apply('set_status_server', { enabled: !enabled, port })}
+          className={`w-12 h-6 rounded-full transition-all duration-150 active:scale-95 ${
+            enabled ? 'bg-[--color-poe-gold]' : 'bg-[--color-surface-elevated]'
+          }`}
+        >
+          <div
+            className={`w-5 h-5 rounded-full bg-white shadow transition-transform duration-150 ${
+              enabled ? 'translate-x-6' : 'translate-x-0.5'
+            }`}
+          />
+        </button>
+      </div>
+
+      <div className="flex items-center gap-2">
+        <span className="text-xs text-[--color-text-muted]">Port</span>
+        <input
+          type="number"
+          min={1024}
+          max={65535}
+          value={port}
+          onChange={(e) => setPort(Number(e.target.value) || 0)}
+          onBlur={() => {
+            if (info && port !== info.port) apply('set_status_server', { enabled, port });
+          }}
+          className="w-24 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+        />
+        <button
+          onClick={() => apply('regenerate_status_token')}
+          className="px-3 py-1 text-xs bg-[--color-surface] text-[--color-text] rounded-md border border-[--color-poe-gold]/40 hover:border-[--color-poe-gold]/70 active:scale-95 transition-all"
+        >
+          New Pairing Token
+        </button>
+      </div>
+
+      {enabled && info && info.urls.length > 0 && (
+        <div className="space-y-1">
+          <div className="text-xs text-[--color-text-muted]">Open on your phone (the token in the URL pairs it):</div>
+          {info.urls.map((url) => (
+            <code key={url} className="block text-xs text-[--color-poe-gold] break-all select-all">{url}</code>
+          ))}
+        </div>
+      )}
+
+      {error && <div className="text-xs text-[--color-timer-behind]">{error}</div>}
+    </div>
+  );
+}
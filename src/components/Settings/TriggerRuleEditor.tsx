@@ -0,0 +1,60 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { Breakpoint, QuestObjective, TriggerRule } from '../../types';
+import { CustomSelect } from '../Shared/CustomSelect';
+
+const ENTRY_OPTIONS = [
+  { value: '', label: 'Any entry' },
+  { value: '1', label: 'First entry only' },
+  { value: '2', label: '2nd entry' },
+  { value: '3', label: '3rd entry' },
+  { value: '4', label: '4th entry' },
+];
+
+/** Which entry into the zone splits, and which quest must be done first (enforced by the breakpoint engine) */
+export function TriggerRuleEditor({
+  breakpoint,
+  onChange,
+}: {
+  breakpoint: Breakpoint;
+  onChange: (rule: TriggerRule | undefined) => void;
+}) {
+  const [objectives, setObjectives] = useState<QuestObjective[]>([]);
+  const rule = breakpoint.trigger.rule ?? {};
+
+  useEffect(() => {
+    invoke<QuestObjective[]>('get_quest_objectives')
+      .then(setObjectives)
+      .catch((error) => console.error('[TriggerRuleEditor] Failed to load quest objectives:', error));
+  }, []);
+
+  const update = (changes: TriggerRule) => {
+    const next = { ...rule, ...changes };
+    onChange(next.entry || next.afterQuest ? next : undefined);
+  };
+
+  const questOptions = [
+    { value: '', label: 'No quest required' },
+    ...objectives.map((o) => ({ value: o.id, label: `After ${o.name} (A${o.act})` })),
+  ];
+
+  return (
+    <div className="flex items-center gap-2 px-3 pb-3">
+      <span className="text-xs text-[--color-text-muted]">Split on</span>
+      {breakpoint.trigger.type === 'zone' && (
+        <CustomSelect
+          value={rule.entry ? String(rule.entry) : ''}
+          onChange={(value) => update({ entry: value ? Number(value) : undefined })}
+          options={ENTRY_OPTIONS}
+          className="w-40"
+        />
+      )}
+      <CustomSelect
+        value={rule.afterQuest ?? ''}
+        onChange={(value) => update({ afterQuest: value || undefined })}
+        options={questOptions}
+        className="w-72"
+      />
+    </div>
+  );
+}
@@ -0,0 +1,48 @@
+import { useSettingsStore } from '../../stores/settingsStore';
+
+const STATUS_LABELS = {
+  public: 'Profile is public',
+  private: 'Profile is private - snapshots will fail',
+  characters_hidden: 'Characters tab is hidden - snapshots will fail',
+};
+
+interface ProfilePrivacyNoticeProps {
+  /** Also confirm a public profile (settings), instead of only warning (timer) */
+  showPublic?: boolean;
+  className?: string;
+}
+
+/** Result of the last `check_profile_privacy`, with the steps to fix a private profile */
+export function ProfilePrivacyNotice({ showPublic = false, className = '' }: ProfilePrivacyNoticeProps) {
+  const { profilePrivacy, profilePrivacyError } = useSettingsStore();
+
+  if (profilePrivacyError && showPublic) {
+    return <div className={`text-xs text-[--color-timer-behind] ${className}`}>{profilePrivacyError}</div>;
+  }
+  if (!profilePrivacy) return null;
+
+  const isPublic = profilePrivacy.status === 'public';
+  if (isPublic && !showPublic && profilePrivacy.hints.length === 0) return null;
+
+  return (
+    <div
+      className={`rounded-lg p-3 text-xs border ${className} ${
+        isPublic
+          ? 'border-[--color-border] bg-[--color-surface-elevated]'
+          : 'border-[--color-timer-behind]/50 bg-[--color-timer-behind]/10'
+      }`}
+    >
+      <div className={isPublic ? 'text-[--color-timer-ahead]' : 'text-[--color-timer-behind] font-semibold'}>
+        {STATUS_LABELS[profilePrivacy.status]}
+        {profilePrivacy.characterCount !== null && ` (${profilePrivacy.characterCount} characters)`}
+      </div>
+      {profilePrivacy.hints.length > 0 && (
+        <ol className="mt-1 list-decimal list-inside space-y-0.5 text-[--color-text-muted]">
+          {profilePrivacy.hints.map((hint) => (
+            <li key={hint}>{hint}</li>
+          ))}
+        </ol>
+      )}
+    </div>
+  );
+}
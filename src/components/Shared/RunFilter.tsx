@@ -1,7 +1,7 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { CustomSelect } from './CustomSelect';
-import type { RunFilters, Run } from '../../types';
+import type { RunFilters, Run, Ruleset } from '../../types';
 
 interface RunFilterProps {
   filters: RunFilters;
@@ -24,6 +24,13 @@ const classAscendancies: Record<string, string[]> = {
 
 const allClasses = Object.keys(classAscendancies);
 
+const rulesets: { value: Ruleset; label: string }[] = [
+  { value: 'SC', label: 'Softcore' },
+  { value: 'HC', label: 'Hardcore' },
+  { value: 'SSF', label: 'SSF' },
+  { value: 'HCSSF', label: 'HC SSF' },
+];
+
 export function RunFilter({
   filters,
   onFiltersChange,
@@ -34,6 +41,8 @@ export function RunFilter({
   const [availableLeagues, setAvailableLeagues] = useState<string[]>([]);
   const [availableCategories, setAvailableCategories] = useState<string[]>([]);
   const [availablePresets, setAvailablePresets] = useState<string[]>([]);
+  const [availableGameVersions, setAvailableGameVersions] = useState<string[]>([]);
+  const [availableTags, setAvailableTags] = useState<string[]>([]);
 
   // Load distinct values from existing runs
   useEffect(() => {
@@ -54,6 +63,12 @@ export function RunFilter({
           runs.map((r) => r.breakpointPreset).filter(Boolean)
         )] as string[];
         setAvailablePresets(presets.sort());
+
+        // Extract unique game versions
+        const gameVersions = [...new Set(runs.map((r) => r.gameVersion).filter(Boolean))] as string[];
+        setAvailableGameVersions(gameVersions.sort());
+
+        setAvailableTags(await invoke<string[]>('get_run_tag_names'));
       } catch (error) {
         console.error('[RunFilter] Failed to load distinct values:', error);
       }
@@ -73,8 +88,13 @@ export function RunFilter({
     filters.ascendancy ||
     filters.category ||
     filters.league ||
+    filters.ruleset ||
+    filters.gameVersion ||
+    filters.tag ||
+    filters.note ||
     filters.breakpointPreset ||
-    filters.isCompleted !== undefined;
+    filters.isCompleted !== undefined ||
+    filters.excludeWarmups;
 
   return (
     <div className="flex flex-wrap items-center gap-3 p-3 bg-[--color-surface] rounded-lg border border-[--color-border]">
@@ -135,6 +155,54 @@ export function RunFilter({
         />
       </div>
 
+      {/* Ruleset filter */}
+      <div className="flex flex-col gap-1">
+        <label className="text-xs text-[--color-text-muted]">Ruleset</label>
+        <CustomSelect
+          value={filters.ruleset || ''}
+          onChange={(v) => onFiltersChange({ ruleset: (v as Ruleset) || undefined })}
+          className="min-w-[100px]"
+          options={[{ value: '', label: 'All' }, ...rulesets]}
+        />
+      </div>
+
+      {/* Game version filter */}
+      <div className="flex flex-col gap-1">
+        <label className="text-xs text-[--color-text-muted]">Version</label>
+        <CustomSelect
+          value={filters.gameVersion || ''}
+          onChange={(v) => onFiltersChange({ gameVersion: v || undefined })}
+          className="min-w-[90px]"
+          options={[
+            { value: '', label: 'All' },
+            ...availableGameVersions.map((version) => ({ value: version, label: version })),
+          ]}
+        />
+      </div>
+
+      {/* Tag filter */}
+      <div className="flex flex-col gap-1">
+        <label className="text-xs text-[--color-text-muted]">Tag</label>
+        <CustomSelect
+          value={filters.tag || ''}
+          onChange={(v) => onFiltersChange({ tag: v || undefined })}
+          className="min-w-[100px]"
+          options={[{ value: '', label: 'All' }, ...availableTags.map((tag) => ({ value: tag, label: tag }))]}
+        />
+      </div>
+
+      {/* Note search */}
+      <div className="flex flex-col gap-1">
+        <label className="text-xs text-[--color-text-muted]">Notes</label>
+        <input
+          type="text"
+          value={filters.note || ''}
+          onChange={(e) => onFiltersChange({ note: e.target.value || undefined })}
+          placeholder="Search notes"
+          className="w-[140px] px-2 py-1.5 text-sm bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text]"
+        />
+      </div>
+
       {/* Preset filter */}
       {showPresetFilter && (
         <div className="flex flex-col gap-1">
@@ -182,6 +250,20 @@ export function RunFilter({
         </div>
       )}
 
+      {/* Warm-up runs toggle */}
+      <div className="flex flex-col gap-1">
+        <label className="text-xs text-[--color-text-muted]">Warm-ups</label>
+        <label className="flex items-center gap-2 px-2 py-1.5">
+          <input
+            type="checkbox"
+            checked={filters.excludeWarmups || false}
+            onChange={(e) => onFiltersChange({ excludeWarmups: e.target.checked })}
+            className="w-4 h-4 rounded border-[--color-border] text-[--color-poe-gold] focus:ring-[--color-poe-gold]"
+          />
+          <span className="text-sm text-[--color-text]">Exclude</span>
+        </label>
+      </div>
+
       {/* Clear filters button */}
       {hasActiveFilters && (
         <button
@@ -0,0 +1,72 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { AppChangelogState } from '../../types';
+
+/** Shown once after an update: the changelog since the previous version and any data migrations */
+export function WhatsNewNotice() {
+  const [state, setState] = useState<AppChangelogState | null>(null);
+
+  useEffect(() => {
+    invoke<AppChangelogState>('get_app_changelog_state')
+      .then(setState)
+      .catch((err) => console.error('[WhatsNewNotice] Failed to load changelog state:', err));
+  }, []);
+
+  if (!state || state.isFirstRun) return null;
+  const notices = state.migrations.filter((migration) => migration.notice);
+  if (state.releases.length === 0 && notices.length === 0) return null;
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
+      <div className="bg-[--color-surface] rounded-lg w-full max-w-xl max-h-[80vh] overflow-hidden flex flex-col">
+        <div className="p-4 border-b border-[--color-border]">
+          <h2 className="text-lg font-semibold text-[--color-text]">What's new in {state.currentVersion}</h2>
+          {state.previousVersion && (
+            <p className="text-sm text-[--color-text-muted]">Updated from {state.previousVersion}</p>
+          )}
+        </div>
+
+        <div className="flex-1 overflow-auto p-4 space-y-4">
+          {notices.length > 0 && (
+            <div className="rounded-lg p-3 text-xs border border-[--color-poe-gold]/40 bg-[--color-poe-gold]/10">
+              <div className="text-[--color-poe-gold] font-semibold">Your data was updated</div>
+              <ul className="mt-1 list-disc pl-4 text-[--color-text-muted] space-y-0.5">
+                {notices.map((migration) => (
+                  <li key={migration.name}>{migration.notice}</li>
+                ))}
+              </ul>
+            </div>
+          )}
+
+          {state.releases.map((release) => (
+            <div key={release.version}>
+              <h3 className="text-sm font-semibold text-[--color-text]">
+                {release.version}
+                {release.date && <span className="ml-2 font-normal text-[--color-text-muted]">{release.date}</span>}
+              </h3>
+              {release.groups.map((group) => (
+                <div key={group.heading} className="mt-2">
+                  {group.heading && <div className="text-xs text-[--color-poe-gold]">{group.heading}</div>}
+                  <ul className="list-disc pl-4 text-sm text-[--color-text-muted] space-y-0.5">
+                    {group.entries.map((entry) => (
+                      <li key={entry}>{entry}</li>
+                    ))}
+                  </ul>
+                </div>
+              ))}
+            </div>
+          ))}
+        </div>
+
+        <div className="p-4 border-t border-[--color-border] flex justify-end">
+          <button
+            onClick={() => setState(null)}
+            className="px-4 py-2 bg-[--color-poe-gold] text-[--color-poe-darker] rounded-lg text-sm font-medium hover:opacity-90 transition-opacity"
+          >
+            Got it
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,81 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { BacktrackReport } from '../../types';
+
+interface BacktrackSummaryProps {
+  runId: number;
+  // Changes (e.g. the split count of an active run) trigger a reload
+  refreshKey?: number;
+}
+
+/** Backtracks into zones already played, with how often each zone was entered */
+export function BacktrackSummary({ runId, refreshKey }: BacktrackSummaryProps) {
+  const [report, setReport] = useState<BacktrackReport | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    invoke<BacktrackReport>('get_backtrack_report', { runId })
+      .then((result) => {
+        if (!cancelled) setReport(result);
+      })
+      .catch((err) => console.error('[BacktrackSummary] Failed to load backtrack report:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [runId, refreshKey]);
+
+  if (!report || report.zones.length === 0) return null;
+
+  const revisited = report.zones.filter((zone) => zone.kind === 'field' && zone.entries > 1);
+
+  return (
+    <div className="mb-6 p-4 bg-[--color-surface] rounded-lg border border-[--color-border]">
+      <div className="flex items-baseline justify-between">
+        <h3 className="text-sm font-semibold text-[--color-text]">Backtracking</h3>
+        <span className={`text-sm ${report.backtracks > 0 ? 'text-[--color-timer-behind]' : 'text-[--color-timer-ahead]'}`}>
+          {report.backtracks === 0
+            ? 'No backtracks'
+            : `${report.backtracks} backtrack${report.backtracks !== 1 ? 's' : ''}, ${formatTime(report.backtrackTimeMs)} spent`}
+        </span>
+      </div>
+      {revisited.length > 0 && (
+        <table className="w-full mt-3 text-sm">
+          <thead>
+            <tr className="text-[--color-text-muted] text-xs">
+              <th className="text-left font-normal">Zone</th>
+              <th className="text-right font-normal">Entries</th>
+              <th className="text-right font-normal">Backtracks</th>
+              <th className="text-right font-normal">Time</th>
+            </tr>
+          </thead>
+          <tbody>
+            {revisited.map((zone) => (
+              <tr key={zone.zoneName}>
+                <td className="text-[--color-text]">{zone.zoneName}</td>
+                <td className="text-right text-[--color-text-muted]">{zone.entries}</td>
+                <td className={`text-right ${zone.backtracks > 0 ? 'text-[--color-timer-behind]' : 'text-[--color-text-muted]'}`}>
+                  {zone.backtracks}
+                </td>
+                <td className="text-right timer-display text-[--color-text-muted]">
+                  {zone.backtracks > 0 ? formatTime(zone.backtrackTimeMs) : '-'}
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      )}
+    </div>
+  );
+}
+
+function formatTime(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+
+  if (hours > 0) {
+    return `${hours}:${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+  }
+  return `${minutes}:${seconds.toString().padStart(2, '0')}`;
+}
@@ -0,0 +1,112 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { SnapshotChanges } from './SnapshotChanges';
+import { getErrorMessage } from '../../utils/errors';
+import type { NinjaBuild, NinjaReference, Run } from '../../types';
+
+interface MetaBuildCompareProps {
+  run: Run;
+  snapshotId: number;
+}
+
+/** Diff a snapshot against a top character of the run's league and class on poe.ninja */
+export function MetaBuildCompare({ run, snapshotId }: MetaBuildCompareProps) {
+  const [league, setLeague] = useState(run.league || 'Standard');
+  const [builds, setBuilds] = useState<NinjaBuild[] | null>(null);
+  const [selected, setSelected] = useState<{ build: NinjaBuild; reference: NinjaReference } | null>(null);
+  const [isLoading, setIsLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const buildClass = run.ascendancy || (run.class !== 'Unknown' ? run.class : null);
+
+  const loadBuilds = async () => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      setBuilds(await invoke<NinjaBuild[]>('fetch_ninja_builds', { league, class: buildClass }));
+    } catch (e) {
+      setError(getErrorMessage(e));
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  const compareWith = async (build: NinjaBuild) => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      const reference = await invoke<NinjaReference>('create_reference_snapshot_from_ninja', {
+        league,
+        accountName: build.accountName,
+        characterName: build.characterName,
+      });
+      setSelected({ build, reference });
+    } catch (e) {
+      setError(getErrorMessage(e));
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  return (
+    <div className="mb-6">
+      {selected ? (
+        <>
+          <SnapshotChanges
+            previousId={snapshotId}
+            snapshotId={selected.reference.snapshotId}
+            title={`Compared with ${selected.build.characterName} (poe.ninja #${selected.build.rank})`}
+          />
+          <button
+            onClick={() => setSelected(null)}
+            className="-mt-4 text-xs text-[--color-poe-gold] hover:text-[--color-poe-gold-light]"
+          >
+            Compare with another build
+          </button>
+        </>
+      ) : (
+        <div className="p-4 bg-[--color-surface] rounded-lg border border-[--color-border] space-y-2">
+          <div className="flex items-center gap-2 text-sm">
+            <span className="text-[--color-text]">Compare with a poe.ninja build</span>
+            <input
+              type="text"
+              value={league}
+              onChange={(e) => setLeague(e.target.value)}
+              className="w-32 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] text-sm"
+            />
+            <button
+              onClick={loadBuilds}
+              disabled={isLoading || !league.trim()}
+              className="px-2 py-1 text-xs bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] hover:border-[--color-poe-gold] disabled:opacity-50"
+            >
+              {isLoading ? 'Loading...' : builds ? 'Refresh' : 'Load builds'}
+            </button>
+            <span className="text-xs text-[--color-text-muted]">{buildClass ?? 'All classes'}</span>
+          </div>
+          {error && <div className="text-sm text-[--color-timer-behind]">{error}</div>}
+          {builds && builds.length === 0 && (
+            <div className="text-sm text-[--color-text-muted]">No characters of this class on poe.ninja</div>
+          )}
+          {builds && builds.length > 0 && (
+            <div className="max-h-[200px] overflow-auto space-y-1">
+              {builds.map((build) => (
+                <button
+                  key={`${build.accountName}-${build.characterName}`}
+                  onClick={() => compareWith(build)}
+                  disabled={isLoading}
+                  className="w-full flex items-center justify-between px-2 py-1 rounded text-sm text-left text-[--color-text] hover:bg-[--color-surface-elevated] disabled:opacity-50"
+                >
+                  <span className="truncate">
+                    #{build.rank} {build.characterName}
+                    <span className="text-[--color-text-muted]"> {build.class}</span>
+                  </span>
+                  <span className="text-[--color-text-muted] shrink-0">Lv {build.level}</span>
+                </button>
+              ))}
+            </div>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,94 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { getErrorMessage } from '../../utils/errors';
+import type { TimelineEntry, TimelineEntryKind } from '../../types';
+
+const KIND_STYLES: Record<TimelineEntryKind, { label: string; className: string }> = {
+  zone_enter: { label: 'Zone', className: 'text-[--color-text-muted]' },
+  level_up: { label: 'Level', className: 'text-[--color-poe-gem]' },
+  death: { label: 'Death', className: 'text-[--color-timer-behind]' },
+  pause: { label: 'Pause', className: 'text-yellow-400/80' },
+  resume: { label: 'Resume', className: 'text-yellow-400/80' },
+  split: { label: 'Split', className: 'text-[--color-poe-gold]' },
+  snapshot: { label: 'Snapshot', className: 'text-[--color-poe-gold-light]' },
+};
+
+interface RunTimelineProps {
+  runId: number;
+  // Changes (e.g. the split count of an active run) trigger a reload
+  refreshKey?: number;
+  onSelectSnapshot?: (id: number) => void;
+}
+
+/** Zone entries, level-ups, deaths, pauses, splits and snapshots of a run in run-clock order */
+export function RunTimeline({ runId, refreshKey, onSelectSnapshot }: RunTimelineProps) {
+  const [entries, setEntries] = useState<TimelineEntry[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    invoke<TimelineEntry[]>('get_run_timeline', { runId })
+      .then((timeline) => {
+        if (!cancelled) {
+          setEntries(timeline);
+          setError(null);
+        }
+      })
+      .catch((err) => {
+        if (!cancelled) setError(getErrorMessage(err));
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [runId, refreshKey]);
+
+  if (error) {
+    return <div className="text-sm text-[--color-timer-behind]">{error}</div>;
+  }
+  if (entries.length === 0) {
+    return (
+      <div className="text-sm text-[--color-text-muted]">
+        No events recorded. Zone entries, level-ups and deaths are kept while the log watcher follows a run.
+      </div>
+    );
+  }
+
+  return (
+    <ol className="space-y-1 text-sm">
+      {entries.map((entry, index) => {
+        const style = KIND_STYLES[entry.kind];
+        const snapshotId = entry.snapshotId;
+        return (
+          <li key={index} className="flex items-baseline gap-3">
+            <span className="timer-display w-16 shrink-0 text-right text-[--color-text-muted]">
+              {formatTime(entry.elapsedMs)}
+            </span>
+            <span className={`w-16 shrink-0 text-xs uppercase ${style.className}`}>{style.label}</span>
+            {snapshotId !== null && onSelectSnapshot ? (
+              <button
+                onClick={() => onSelectSnapshot(snapshotId)}
+                className="text-left text-[--color-text] hover:text-[--color-poe-gold]"
+              >
+                {entry.label}
+              </button>
+            ) : (
+              <span className="text-[--color-text]">{entry.label}</span>
+            )}
+          </li>
+        );
+      })}
+    </ol>
+  );
+}
+
+function formatTime(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+
+  if (hours > 0) {
+    return `${hours}:${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+  }
+  return `${minutes}:${seconds.toString().padStart(2, '0')}`;
+}
@@ -0,0 +1,88 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { SnapshotDiff, SnapshotItemSummary } from '../../types';
+
+interface SnapshotChangesProps {
+  // Earlier snapshot of the same run
+  previousId: number;
+  snapshotId: number;
+  // Heading, for diffs against something other than the previous snapshot
+  title?: string;
+}
+
+/** Gear, gems, passives and levels gained since the previous snapshot */
+export function SnapshotChanges({ previousId, snapshotId, title = 'Since previous snapshot' }: SnapshotChangesProps) {
+  const [diff, setDiff] = useState<SnapshotDiff | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    setDiff(null);
+    invoke<SnapshotDiff>('diff_snapshots', { snapshotA: previousId, snapshotB: snapshotId })
+      .then((result) => {
+        if (!cancelled) setDiff(result);
+      })
+      .catch((err) => console.error('[SnapshotChanges] Failed to diff snapshots:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [previousId, snapshotId]);
+
+  if (!diff) return null;
+
+  const passives = [
+    diff.passivesAllocated.length > 0 && `+${diff.passivesAllocated.length} passives`,
+    diff.passivesRefunded.length > 0 && `-${diff.passivesRefunded.length} refunded`,
+  ].filter(Boolean);
+
+  return (
+    <div className="mb-6 p-4 bg-[--color-surface] rounded-lg border border-[--color-border]">
+      <div className="flex items-baseline justify-between">
+        <h3 className="text-sm font-semibold text-[--color-text]">{title}</h3>
+        <span className="text-sm text-[--color-text-muted]">
+          {diff.levelsGained !== 0 && `+${diff.levelsGained} levels (${diff.levelBefore} → ${diff.levelAfter})`}
+          {diff.levelsGained !== 0 && passives.length > 0 && ', '}
+          {passives.join(', ')}
+        </span>
+      </div>
+      {diff.items.length === 0 && diff.gems.length === 0 ? (
+        <p className="mt-2 text-sm text-[--color-text-muted]">No gear or gem changes</p>
+      ) : (
+        <div className="grid grid-cols-2 gap-6 mt-3 text-sm">
+          <ul className="space-y-1">
+            {diff.items.map((item) => (
+              <li key={item.slot} className="flex gap-2">
+                <span className="text-[--color-text-muted] w-24 shrink-0">{item.slot}</span>
+                <span className={item.change === 'removed' ? 'text-[--color-timer-behind]' : 'text-[--color-text]'}>
+                  {item.change === 'removed'
+                    ? `Removed ${itemName(item.before)}`
+                    : item.change === 'added'
+                      ? itemName(item.after)
+                      : `${itemName(item.before)} → ${itemName(item.after)}`}
+                </span>
+              </li>
+            ))}
+          </ul>
+          <ul className="space-y-1">
+            {diff.gems.map((gem) => (
+              <li key={`${gem.slot}-${gem.name}-${gem.change}`} className="flex justify-between gap-2">
+                <span className={gem.change === 'removed' ? 'text-[--color-timer-behind]' : 'text-[--color-text]'}>
+                  {gem.change === 'added' ? '+ ' : gem.change === 'removed' ? '- ' : ''}
+                  {gem.name}
+                </span>
+                <span className="text-[--color-text-muted]">
+                  {gem.change === 'leveled' ? `${gem.levelBefore} → ${gem.levelAfter}` : gem.slot}
+                </span>
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+    </div>
+  );
+}
+
+function itemName(item: SnapshotItemSummary | null): string {
+  if (!item) return '';
+  const links = item.maxLinks > 1 ? ` (${item.maxLinks}L)` : '';
+  return `${item.name || item.typeLine}${links}`;
+}
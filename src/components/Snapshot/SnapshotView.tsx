@@ -7,11 +7,17 @@ import { EquipmentGrid } from './EquipmentGrid';
 import { SkillsDisplay } from './SkillsDisplay';
 import { PassivesSummary } from './PassivesSummary';
 import { PassiveTree } from './PassiveTree';
+import { RunTimeline } from './RunTimeline';
+import { BacktrackSummary } from './BacktrackSummary';
+import { ZoneTimeBreakdown } from './ZoneTimeBreakdown';
+import { SnapshotChanges } from './SnapshotChanges';
+import { MetaBuildCompare } from './MetaBuildCompare';
 import { exportToPob, shareOnPobbIn, exportAllToPob, shareAllOnPobbIn } from '../../utils/pobExport';
 import { exportRunToJson } from '../../utils/jsonExport';
-import type { Run, Split, Snapshot } from '../../types';
+import { getErrorMessage } from '../../utils/errors';
+import type { Run, Split, Snapshot, SnapshotSummary } from '../../types';
 
-type TabType = 'equipment' | 'passives';
+type TabType = 'equipment' | 'passives' | 'timeline';
 
 export function SnapshotView() {
   const { runs: rawRuns, currentRun } = useRunStore();
@@ -27,8 +33,10 @@ export function SnapshotView() {
   }, [rawRuns]);
   const {
     snapshots,
+    loadedSnapshots,
     selectedSnapshotId,
     pendingCaptures,
+    queuedCaptures,
     failedCaptures,
     isLoading,
     loadSnapshots,
@@ -69,7 +77,7 @@ export function SnapshotView() {
     }
   }, [selectedRunId, loadSnapshots]);
 
-  const selectedSnapshot = snapshots.find((s) => s.id === selectedSnapshotId);
+  const selectedSnapshot = selectedSnapshotId !== null ? loadedSnapshots.get(selectedSnapshotId) : undefined;
 
   const handleDeleteRun = useCallback(async (runId: number) => {
     try {
@@ -77,13 +85,14 @@ export function SnapshotView() {
       // Reload runs from store
       const updatedRuns = await invoke<Run[]>('get_runs');
       useRunStore.getState().setRuns(updatedRuns);
+      useRunStore.getState().loadPbAndGoldSplits();
       // Clear selection if deleted run was selected
       if (selectedRunId === runId) {
         setSelectedRunId(null);
       }
     } catch (error) {
       console.error('Failed to delete run:', error);
-      alert('Failed to delete run: ' + String(error));
+      alert('Failed to delete run: ' + getErrorMessage(error));
     }
   }, [selectedRunId]);
 
@@ -98,10 +107,11 @@ export function SnapshotView() {
       // Reload runs from store
       const updatedRuns = await invoke<Run[]>('get_runs');
       useRunStore.getState().setRuns(updatedRuns);
+      useRunStore.getState().loadPbAndGoldSplits();
       setSelectedRunId(null);
     } catch (error) {
       console.error('Failed to delete all runs:', error);
-      alert('Failed to delete all runs: ' + String(error));
+      alert('Failed to delete all runs: ' + getErrorMessage(error));
     }
   }, [runs, currentRun]);
 
@@ -170,6 +180,7 @@ export function SnapshotView() {
               snapshots={snapshots}
               selectedSnapshot={selectedSnapshot}
               pendingCaptures={pendingCaptures}
+              queuedCaptures={queuedCaptures}
               failedCaptures={failedCaptures}
               isLoading={isLoading}
               onSelectSnapshot={selectSnapshot}
@@ -254,9 +265,10 @@ function RunListItem({ run, isSelected, onClick, onDelete, isActive }: RunListIt
 interface SnapshotDetailProps {
   run: Run;
   splits: Split[];
-  snapshots: Snapshot[];
+  snapshots: SnapshotSummary[];
   selectedSnapshot?: Snapshot;
   pendingCaptures: Set<number>;
+  queuedCaptures: Set<number>;
   failedCaptures: Map<number, string>;
   isLoading: boolean;
   onSelectSnapshot: (id: number | null) => void;
@@ -269,6 +281,7 @@ function SnapshotDetail({
   snapshots,
   selectedSnapshot,
   pendingCaptures,
+  queuedCaptures,
   failedCaptures,
   isLoading,
   onSelectSnapshot,
@@ -317,7 +330,8 @@ function SnapshotDetail({
     if (snapshots.length === 0) return;
     setExportAllStatus('loading');
     try {
-      await exportAllToPob(snapshots, run, splits, accountName);
+      const allSnapshots = await useSnapshotStore.getState().loadAllSnapshots(run.id);
+      await exportAllToPob(allSnapshots, run, splits, accountName);
       setExportAllStatus('success');
       setTimeout(() => setExportAllStatus('idle'), 2000);
     } catch (error) {
@@ -331,7 +345,8 @@ function SnapshotDetail({
     if (snapshots.length === 0) return;
     setShareAllStatus('loading');
     try {
-      const url = await shareAllOnPobbIn(snapshots, run, splits, accountName);
+      const allSnapshots = await useSnapshotStore.getState().loadAllSnapshots(run.id);
+      const url = await shareAllOnPobbIn(allSnapshots, run, splits, accountName);
       setShareAllUrl(url);
       setShareAllStatus('success');
       window.open(url, '_blank');
@@ -352,6 +367,14 @@ function SnapshotDetail({
     return getEquippedItems(items);
   }, [items]);
 
+  // The snapshot taken before the selected one, to show what changed since
+  const previousSnapshot = useMemo(() => {
+    if (!selectedSnapshot) return undefined;
+    return snapshots
+      .filter((s) => s.elapsedTimeMs < selectedSnapshot.elapsedTimeMs)
+      .reduce<SnapshotSummary | undefined>((latest, s) => (!latest || s.elapsedTimeMs > latest.elapsedTimeMs ? s : latest), undefined);
+  }, [snapshots, selectedSnapshot]);
+
   const passives = useMemo(() => {
     if (!selectedSnapshot) return { hashes: [], hashesEx: [], masteryEffects: {} };
     return parsePassives(selectedSnapshot.passiveTreeJson);
@@ -362,19 +385,41 @@ function SnapshotDetail({
     return splits.map((split) => {
       const snapshot = snapshots.find((s) => s.splitId === split.id);
       const isPending = pendingCaptures.has(split.id);
+      const isQueued = isPending && queuedCaptures.has(split.id);
       const failError = failedCaptures.get(split.id);
 
       return {
         split,
         snapshot,
         isPending,
+        isQueued,
         failError,
       };
     });
-  }, [splits, snapshots, pendingCaptures, failedCaptures]);
+  }, [splits, snapshots, pendingCaptures, queuedCaptures, failedCaptures]);
 
   const maxTime = run.totalTimeMs || splits[splits.length - 1]?.splitTimeMs || 1;
 
+  const tabBar = (
+    <div className="px-6 border-b border-[--color-border]">
+      <div className="flex gap-4">
+        {(['equipment', 'passives', 'timeline'] as TabType[]).map((tab) => (
+          <button
+            key={tab}
+            onClick={() => setActiveTab(tab)}
+            className={`pb-2 px-1 text-sm border-b-2 transition-colors capitalize ${
+              activeTab === tab
+                ? 'text-[--color-text] border-[--color-poe-gold]'
+                : 'text-[--color-text-muted] border-transparent hover:text-[--color-text] hover:border-[--color-poe-gold]/50'
+            }`}
+          >
+            {tab === 'equipment' ? 'Gear & Skills' : tab}
+          </button>
+        ))}
+      </div>
+    </div>
+  );
+
   return (
     <div className="flex flex-col h-full">
       {/* Header */}
@@ -432,7 +477,9 @@ function SnapshotDetail({
                   }
                 }}
                 title={`${marker.split.breakpointName}\n${formatTime(marker.split.splitTimeMs)}${
-                  marker.isPending
+                  marker.isQueued
+                    ? '\nQueued...'
+                    : marker.isPending
                     ? '\nCapturing...'
                     : marker.failError
                     ? `\nFailed: ${marker.failError}\nClick to retry`
@@ -443,49 +490,81 @@ function SnapshotDetail({
               />
             );
           })}
+
+          {/* Level milestone snapshots belong to no split */}
+          {snapshots
+            .filter((snapshot) => snapshot.splitId === null)
+            .map((snapshot) => (
+              <button
+                key={`level-${snapshot.id}`}
+                className={`absolute top-1/2 -translate-y-1/2 -translate-x-1/2 w-3 h-3 rotate-45 border-2 transition-all ${
+                  snapshot.id === selectedSnapshot?.id
+                    ? 'bg-[--color-poe-gold] border-[--color-poe-gold-light] scale-125'
+                    : 'bg-[--color-poe-gold]/60 border-[--color-poe-gold-light] hover:scale-110'
+                }`}
+                style={{ left: `${(snapshot.elapsedTimeMs / maxTime) * 100}%` }}
+                onClick={() => onSelectSnapshot(snapshot.id)}
+                title={`Level ${snapshot.characterLevel}\n${formatTime(snapshot.elapsedTimeMs)}`}
+              />
+            ))}
         </div>
 
         {/* Timeline labels */}
         <div className="flex justify-between mt-2 text-xs text-[--color-text-muted]">
           <span>Start</span>
-          <span>{selectedSnapshot ? `${formatTime(selectedSnapshot.elapsedTimeMs)} - Level ${selectedSnapshot.characterLevel}` : ''}</span>
+          <span>
+            {selectedSnapshot
+              ? `${formatTime(selectedSnapshot.elapsedTimeMs)} - Level ${selectedSnapshot.characterLevel}${selectedSnapshot.isFinal ? ' (Final build)' : ''}`
+              : ''}
+          </span>
           <span>{formatTime(maxTime)}</span>
         </div>
       </div>
 
       {/* Content area */}
-      {snapshots.length === 0 && !isLoading ? (
+      {activeTab === 'timeline' ? (
+        <>
+          {tabBar}
+          <div className="flex-1 overflow-auto p-6">
+            <BacktrackSummary runId={run.id} refreshKey={splits.length} />
+            <ZoneTimeBreakdown runId={run.id} refreshKey={splits.length} />
+            <RunTimeline
+              runId={run.id}
+              refreshKey={splits.length}
+              onSelectSnapshot={(id) => {
+                onSelectSnapshot(id);
+                setActiveTab('equipment');
+              }}
+            />
+          </div>
+        </>
+      ) : snapshots.length === 0 && !isLoading ? (
         <div className="flex-1 flex items-center justify-center text-[--color-text-muted]">
           <div className="text-center">
             <p className="mb-2">No snapshots captured yet.</p>
             <p className="text-sm">
               Snapshots are automatically captured at act transitions and boss kills.
             </p>
+            <button
+              onClick={() => setActiveTab('timeline')}
+              className="mt-3 text-sm text-[--color-poe-gold] hover:underline"
+            >
+              View event timeline
+            </button>
           </div>
         </div>
       ) : selectedSnapshot ? (
         <>
-          {/* Tabs */}
-          <div className="px-6 border-b border-[--color-border]">
-            <div className="flex gap-4">
-              {(['equipment', 'passives'] as TabType[]).map((tab) => (
-                <button
-                  key={tab}
-                  onClick={() => setActiveTab(tab)}
-                  className={`pb-2 px-1 text-sm border-b-2 transition-colors capitalize ${
-                    activeTab === tab
-                      ? 'text-[--color-text] border-[--color-poe-gold]'
-                      : 'text-[--color-text-muted] border-transparent hover:text-[--color-text] hover:border-[--color-poe-gold]/50'
-                  }`}
-                >
-                  {tab === 'equipment' ? 'Gear & Skills' : tab}
-                </button>
-              ))}
-            </div>
-          </div>
+          {tabBar}
 
           {/* Tab content */}
           <div className="flex-1 overflow-auto p-6">
+            {activeTab === 'equipment' && previousSnapshot && (
+              <SnapshotChanges previousId={previousSnapshot.id} snapshotId={selectedSnapshot.id} />
+            )}
+            {activeTab === 'equipment' && selectedRun && !selectedRun.isReference && (
+              <MetaBuildCompare key={selectedSnapshot.id} run={selectedRun} snapshotId={selectedSnapshot.id} />
+            )}
             {activeTab === 'equipment' && (
               <div className="grid grid-cols-[auto_1fr_1fr] gap-6">
                 {/* Equipment grid - column 1 */}
@@ -592,9 +671,12 @@ function SnapshotDetail({
           </div>
         </>
       ) : (
-        <div className="flex-1 flex items-center justify-center text-[--color-text-muted]">
-          Select a snapshot from the timeline
-        </div>
+        <>
+          {tabBar}
+          <div className="flex-1 flex items-center justify-center text-[--color-text-muted]">
+            Select a snapshot from the timeline
+          </div>
+        </>
       )}
     </div>
   );
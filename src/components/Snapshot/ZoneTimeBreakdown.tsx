@@ -0,0 +1,76 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { ZoneTimeReport } from '../../types';
+
+interface ZoneTimeBreakdownProps {
+  runId: number;
+  // Changes (e.g. the split count of an active run) trigger a reload
+  refreshKey?: number;
+}
+
+const KIND_COLORS: Record<'town' | 'hideout' | 'field', string> = {
+  town: 'text-[--color-poe-gold]',
+  hideout: 'text-purple-400',
+  field: 'text-[--color-text]',
+};
+
+/** Time spent in each zone between one split and the next */
+export function ZoneTimeBreakdown({ runId, refreshKey }: ZoneTimeBreakdownProps) {
+  const [report, setReport] = useState<ZoneTimeReport | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    invoke<ZoneTimeReport>('get_zone_times', { runId })
+      .then((result) => {
+        if (!cancelled) setReport(result);
+      })
+      .catch((err) => console.error('[ZoneTimeBreakdown] Failed to load zone times:', err));
+    return () => {
+      cancelled = true;
+    };
+  }, [runId, refreshKey]);
+
+  const segments = report?.segments.filter((segment) => segment.zones.length > 0) ?? [];
+  if (segments.length === 0) return null;
+
+  return (
+    <div className="mb-6 p-4 bg-[--color-surface] rounded-lg border border-[--color-border]">
+      <h3 className="text-sm font-semibold text-[--color-text]">Time per Zone</h3>
+      <div className="mt-3 space-y-3">
+        {segments.map((segment) => (
+          <div key={`${segment.breakpointName ?? 'end'}-${segment.startMs}`}>
+            <div className="flex items-baseline justify-between text-xs text-[--color-text-muted]">
+              <span>{segment.breakpointName ? `To ${segment.breakpointName}` : 'After the last split'}</span>
+              <span className="timer-display">{formatTime(segment.endMs - segment.startMs)}</span>
+            </div>
+            <table className="w-full text-sm">
+              <tbody>
+                {segment.zones.map((zone) => (
+                  <tr key={zone.zoneName}>
+                    <td className={`pl-3 ${KIND_COLORS[zone.kind]}`}>{zone.zoneName}</td>
+                    <td className="text-right text-[--color-text-muted] text-xs">
+                      {zone.visits > 1 ? `${zone.visits} visits` : ''}
+                    </td>
+                    <td className="w-20 text-right timer-display text-[--color-text-muted]">{formatTime(zone.timeMs)}</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+function formatTime(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+
+  if (hours > 0) {
+    return `${hours}:${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+  }
+  return `${minutes}:${seconds.toString().padStart(2, '0')}`;
+}
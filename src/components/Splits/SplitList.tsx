@@ -1,10 +1,20 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import { useRunStore } from '../../stores/runStore';
 import { useSettingsStore } from '../../stores/settingsStore';
 import { getWizardCategory } from '../../config/wizardRoutes';
 import { SplitRow } from './SplitRow';
+import { getErrorMessage } from '../../utils/errors';
+import type { ComparisonKind } from '../../types';
+
+const COMPARISONS: { label: string; kind: ComparisonKind }[] = [
+  { label: 'Personal Best', kind: { kind: 'personal_best' } },
+  { label: 'Best Segments', kind: { kind: 'gold' } },
+  { label: 'Average (last 5)', kind: { kind: 'average', count: 5 } },
+];
 
 export function SplitList() {
-  const { timer, currentRun, personalBests } = useRunStore();
+  const { timer, currentRun, personalBests, comparisonKind, comparisonRows, setComparisonKind } = useRunStore();
   const { breakpoints, wizardConfig } = useSettingsStore();
 
   const enabledBreakpoints = breakpoints.filter((bp) => bp.isEnabled);
@@ -15,13 +25,87 @@ export function SplitList() {
     ?? (wizardConfig ? getWizardCategory(wizardConfig) : null);
   const cls = currentRun?.class ?? 'Unknown';
 
+  // Category override for the run being timed
+  const [editingCategory, setEditingCategory] = useState<string | null>(null);
+  const [categoryError, setCategoryError] = useState<string | null>(null);
+
+  const saveCategory = async () => {
+    if (!currentRun?.id || editingCategory === null) return;
+    const next = editingCategory.trim();
+    try {
+      await invoke('set_run_category', { runId: currentRun.id, category: next });
+      useRunStore.setState({ currentRun: { ...currentRun, category: next, categorySource: 'manual' } });
+      useRunStore.getState().loadPbAndGoldSplits();
+      useRunStore.getState().loadComparison();
+      setEditingCategory(null);
+      setCategoryError(null);
+    } catch (error) {
+      setCategoryError(getErrorMessage(error));
+    }
+  };
+
   return (
     <div className="bg-[--color-surface] rounded-lg h-full flex flex-col">
       <div className="p-4 border-b border-[--color-border]">
-        <h2 className="text-lg font-semibold text-[--color-text]">Splits</h2>
+        <div className="flex items-center justify-between">
+          <h2 className="text-lg font-semibold text-[--color-text]">Splits</h2>
+          <select
+            value={COMPARISONS.findIndex((c) => c.kind.kind === comparisonKind.kind)}
+            onChange={(e) => setComparisonKind(COMPARISONS[Number(e.target.value)].kind)}
+            className="px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-xs text-[--color-text]"
+            title="What splits are compared against"
+          >
+            {COMPARISONS.map((c, i) => (
+              <option key={c.kind.kind} value={i}>
+                {c.label}
+              </option>
+            ))}
+            {comparisonKind.kind === 'reference' && <option value={-1}>Reference run</option>}
+          </select>
+        </div>
         <p className="text-xs text-[--color-text-muted] mt-1">
-          {completedSplits.length} / {enabledBreakpoints.length}
+          {timer.currentSplit} / {enabledBreakpoints.length}
         </p>
+        {currentRun?.id && !currentRun.isCompleted && (
+          <div className="mt-2 flex items-center gap-2 text-xs">
+            {editingCategory === null ? (
+              <>
+                <span className="text-[--color-text]">{currentRun.category}</span>
+                {currentRun.categorySource === 'detected' && (
+                  <span
+                    className="px-1.5 py-0.5 bg-[--color-poe-gem]/20 text-[--color-poe-gem] rounded"
+                    title="Set from the run's first log events"
+                  >
+                    auto
+                  </span>
+                )}
+                <button
+                  onClick={() => setEditingCategory(currentRun.category)}
+                  className="text-[--color-text-muted] hover:text-[--color-text]"
+                >
+                  Change
+                </button>
+              </>
+            ) : (
+              <>
+                <input
+                  autoFocus
+                  value={editingCategory}
+                  onChange={(e) => setEditingCategory(e.target.value)}
+                  onKeyDown={(e) => {
+                    if (e.key === 'Enter') saveCategory();
+                    if (e.key === 'Escape') setEditingCategory(null);
+                  }}
+                  className="flex-1 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text]"
+                />
+                <button onClick={saveCategory} className="text-[--color-poe-gold] hover:text-[--color-poe-gold-light]">
+                  Save
+                </button>
+              </>
+            )}
+            {categoryError && <span className="text-[--color-timer-behind]">{categoryError}</span>}
+          </div>
+        )}
       </div>
 
       <div className="flex-1 overflow-auto">
@@ -43,12 +127,18 @@ export function SplitList() {
               </div>
             </div>
             {enabledBreakpoints.map((bp, index) => {
-              const split = completedSplits[index];
-              const isNext = index === completedSplits.length;
-              const isCompleted = index < completedSplits.length;
+              const split = completedSplits.find((s) => s.name === bp.name);
+              const isSkipped = timer.skippedSplits.some((s) => s.breakpointName === bp.name);
+              const isNext = index === timer.currentSplit;
+              const isCompleted = split !== undefined || isSkipped;
 
-              // Look up PB split time for this breakpoint
-              const pbTime = category ? (personalBests.get(`${category}-${cls}-${bp.name}`) ?? null) : null;
+              // The backend's comparison row; before the run has an id, PB split times
+              const row = comparisonRows.find((r) => r.breakpointName === bp.name);
+              const pbTime = row
+                ? row.comparisonSplitMs
+                : category && comparisonKind.kind === 'personal_best'
+                ? (personalBests.get(`${category}-${cls}-${bp.name}`) ?? null)
+                : null;
 
               return (
                 <SplitRow
@@ -57,10 +147,12 @@ export function SplitList() {
                   type={bp.type}
                   splitTime={split?.splitTimeMs ?? null}
                   segmentTime={split?.segmentTimeMs ?? null}
-                  delta={split?.deltaMs ?? null}
+                  delta={row && row.splitTimeMs !== null ? row.deltaMs : (split?.deltaMs ?? null)}
                   isBestSegment={split?.isBestSegment ?? false}
+                  color={row && row.splitTimeMs !== null ? row.color : (split?.color ?? 'neutral')}
                   isNext={isNext}
                   isCompleted={isCompleted}
+                  isSkipped={isSkipped}
                   pbTime={pbTime}
                 />
               );
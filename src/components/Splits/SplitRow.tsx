@@ -1,4 +1,4 @@
-import type { BreakpointType } from '../../types';
+import type { BreakpointType, SplitColor } from '../../types';
 
 interface SplitRowProps {
   name: string;
@@ -7,8 +7,11 @@ interface SplitRowProps {
   segmentTime: number | null;
   delta: number | null;
   isBestSegment: boolean;
+  color: SplitColor;
   isNext: boolean;
   isCompleted: boolean;
+  // Passed over with skip_split: no times of its own
+  isSkipped?: boolean;
   pbTime?: number | null;
 }
 
@@ -19,12 +22,14 @@ export function SplitRow({
   segmentTime,
   delta,
   isBestSegment,
+  color,
   isNext,
   isCompleted,
+  isSkipped = false,
   pbTime,
 }: SplitRowProps) {
   const typeIcon = getTypeIcon(type);
-  const deltaColor = getDeltaColor(delta);
+  const deltaColor = DELTA_COLORS[color];
   const rowBg = isNext
     ? 'bg-[--color-surface-elevated]'
     : isCompleted
@@ -46,7 +51,10 @@ export function SplitRow({
       </div>
 
       {/* Times - right side */}
-      {isCompleted && (
+      {isSkipped && (
+        <span className="text-xs text-[--color-text-muted] uppercase tracking-wide">Skipped</span>
+      )}
+      {isCompleted && !isSkipped && (
         <div className="flex items-center gap-3">
           {/* Segment time - gold color if best segment */}
           <div className="text-right min-w-[50px]">
@@ -110,12 +118,14 @@ function getTypeIcon(type: BreakpointType): string {
   }
 }
 
-function getDeltaColor(delta: number | null): string {
-  if (delta === null) return 'text-[--color-timer-neutral]';
-  if (delta < 0) return 'text-[--color-timer-ahead]';
-  if (delta > 0) return 'text-[--color-timer-behind]';
-  return 'text-[--color-timer-neutral]';
-}
+const DELTA_COLORS: Record<SplitColor, string> = {
+  gold: 'text-[--color-timer-gold]',
+  ahead_gaining: 'text-[--color-timer-ahead]',
+  ahead_losing: 'text-[--color-timer-ahead-losing]',
+  behind_gaining: 'text-[--color-timer-behind-gaining]',
+  behind_losing: 'text-[--color-timer-behind]',
+  neutral: 'text-[--color-timer-neutral]',
+};
 
 function formatDelta(ms: number): string {
   const sign = ms >= 0 ? '+' : '-';
@@ -0,0 +1,48 @@
+import { useRunStore } from '../../stores/runStore';
+
+/** Shown when a character other than the run's levels up while it's timed */
+export function CharacterMismatchNotice({ className = '' }: { className?: string }) {
+  const { characterMismatch, setCharacterMismatch, reassignRunCharacter } = useRunStore();
+
+  if (!characterMismatch) return null;
+
+  return (
+    <div
+      className={`rounded-lg p-3 text-xs border border-[--color-poe-gold]/40 bg-[--color-poe-gold]/10 ${className}`}
+    >
+      <div className="flex items-start justify-between gap-2">
+        <div>
+          <div className="text-[--color-poe-gold] font-semibold">
+            {characterMismatch.character_name} ({characterMismatch.character_class}) reached level{' '}
+            {characterMismatch.level}
+          </div>
+          <div className="text-[--color-text-muted] mt-0.5">
+            This run is timing {characterMismatch.run_character}. If you switched characters, pause the run or move it
+            to the new character; a party member leveling can be dismissed.
+          </div>
+          <div className="flex gap-2 mt-2">
+            <button
+              onClick={() => reassignRunCharacter(false)}
+              className="px-2 py-1 rounded bg-[--color-surface-elevated] text-[--color-text] hover:bg-[--color-border]"
+            >
+              Pause run
+            </button>
+            <button
+              onClick={() => reassignRunCharacter(true)}
+              className="px-2 py-1 rounded bg-[--color-surface-elevated] text-[--color-text] hover:bg-[--color-border]"
+            >
+              Switch to {characterMismatch.character_name}
+            </button>
+          </div>
+        </div>
+        <button
+          onClick={() => setCharacterMismatch(null)}
+          className="text-[--color-text-muted] hover:text-[--color-text]"
+          title="Dismiss"
+        >
+          &times;
+        </button>
+      </div>
+    </div>
+  );
+}
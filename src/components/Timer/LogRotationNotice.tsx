@@ -0,0 +1,33 @@
+import { useSettingsStore } from '../../stores/settingsStore';
+
+/** Shown after Client.txt was truncated or replaced under the watcher */
+export function LogRotationNotice({ className = '' }: { className?: string }) {
+  const { logRotation, setLogRotation } = useSettingsStore();
+
+  if (!logRotation) return null;
+
+  return (
+    <div
+      className={`rounded-lg p-3 text-xs border border-[--color-poe-gold]/40 bg-[--color-poe-gold]/10 ${className}`}
+    >
+      <div className="flex items-start justify-between gap-2">
+        <div>
+          <div className="text-[--color-poe-gold] font-semibold">
+            Client.txt was {logRotation.reason === 'truncated' ? 'cleared' : 'replaced'}
+          </div>
+          <div className="text-[--color-text-muted] mt-0.5">
+            Noticed at {logRotation.timestamp}. Watching continues from the start of the new log, so no restart is
+            needed.
+          </div>
+        </div>
+        <button
+          onClick={() => setLogRotation(null)}
+          className="text-[--color-text-muted] hover:text-[--color-text]"
+          title="Dismiss"
+        >
+          &times;
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,175 @@
+import { useCallback, useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useRunStore } from '../../stores/runStore';
+import { getErrorMessage } from '../../utils/errors';
+import type { Racer, RaceEvent, RaceStatus } from '../../types';
+
+/** Host or join a race lobby; other racers' splits become comparisons with live deltas */
+export function RaceLobby({ className = '' }: { className?: string }) {
+  const { comparisonKind, setComparisonKind } = useRunStore();
+  const [status, setStatus] = useState<RaceStatus | null>(null);
+  const [name, setName] = useState('');
+  const [port, setPort] = useState(8735);
+  const [url, setUrl] = useState('');
+  const [isBusy, setIsBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const refresh = useCallback(() => {
+    invoke<RaceStatus>('get_race_status')
+      .then(setStatus)
+      .catch((e) => setError(getErrorMessage(e)));
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = listen<RaceEvent>('race-event', (event) => {
+      refresh();
+      const store = useRunStore.getState();
+      const current = store.comparisonKind;
+      if (current.kind !== 'reference') return;
+      // A racer's new run replaces the one being compared against
+      if (event.payload.type === 'runStarted') {
+        invoke<RaceStatus>('get_race_status').then((next) => {
+          const racer = next.racers.find((r) => r.name === event.payload.racer);
+          if (racer && racer.runId !== current.runId) store.setComparisonKind({ kind: 'reference', runId: racer.runId });
+        });
+      } else {
+        store.loadComparison();
+      }
+    });
+    // Peers connecting, and a guest's own host dropping, don't send events
+    const interval = setInterval(refresh, 5000);
+    return () => {
+      unlisten.then((fn) => fn());
+      clearInterval(interval);
+    };
+  }, [refresh]);
+
+  const run = async (action: () => Promise<RaceStatus | void>) => {
+    setIsBusy(true);
+    setError(null);
+    try {
+      const next = await action();
+      if (next) setStatus(next);
+      else refresh();
+    } catch (e) {
+      setError(getErrorMessage(e));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const comparingWith = (racer: Racer) => comparisonKind.kind === 'reference' && comparisonKind.runId === racer.runId;
+
+  return (
+    <div className={`bg-[--color-surface] rounded-lg p-4 space-y-3 ${className}`}>
+      <div className="flex items-center justify-between">
+        <h3 className="text-sm font-semibold text-[--color-text]">Race Lobby</h3>
+        {status?.role && (
+          <button
+            onClick={() => run(() => invoke('leave_race'))}
+            disabled={isBusy}
+            className="text-xs text-[--color-text-muted] hover:text-[--color-timer-behind] disabled:opacity-50"
+          >
+            {status.role === 'host' ? 'Close lobby' : 'Leave'}
+          </button>
+        )}
+      </div>
+
+      {!status?.role ? (
+        <div className="space-y-2 text-sm">
+          <input
+            type="text"
+            value={name}
+            onChange={(e) => setName(e.target.value)}
+            placeholder="Racer name"
+            className="w-full px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text]"
+          />
+          <div className="flex items-center gap-2">
+            <input
+              type="number"
+              min={1024}
+              max={65535}
+              value={port}
+              onChange={(e) => setPort(Number(e.target.value) || 0)}
+              className="w-24 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text]"
+            />
+            <button
+              onClick={() => run(() => invoke<RaceStatus>('host_race', { port, name }))}
+              disabled={isBusy || !name.trim()}
+              className="px-2 py-1 text-xs bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] hover:border-[--color-poe-gold] disabled:opacity-50"
+            >
+              Host
+            </button>
+          </div>
+          <div className="flex items-center gap-2">
+            <input
+              type="text"
+              value={url}
+              onChange={(e) => setUrl(e.target.value)}
+              placeholder="ws://192.168.1.5:8735/room-token"
+              className="flex-1 px-2 py-1 bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text]"
+            />
+            <button
+              onClick={() => run(() => invoke<RaceStatus>('join_race', { url, name }))}
+              disabled={isBusy || !name.trim() || !url.trim()}
+              className="px-2 py-1 text-xs bg-[--color-surface-elevated] border border-[--color-border] rounded text-[--color-text] hover:border-[--color-poe-gold] disabled:opacity-50"
+            >
+              Join
+            </button>
+          </div>
+        </div>
+      ) : (
+        <div className="space-y-2 text-sm">
+          <div className="text-xs text-[--color-text-muted]">
+            {status.role === 'host' ? 'Friends join at' : 'Joined'}{' '}
+            <code className="text-[--color-poe-gold] select-all">{status.address}</code> as {status.name}
+            {status.role === 'host' && ` (${status.peers} connected)`}
+          </div>
+          {status.racers.length === 0 && (
+            <div className="text-xs text-[--color-text-muted]">Waiting for another racer to start a run</div>
+          )}
+          {status.racers.map((racer) => (
+            <div key={racer.name} className="flex items-center justify-between gap-2">
+              <div className="min-w-0">
+                <div className="text-[--color-text] truncate">
+                  {racer.name} <span className="text-xs text-[--color-text-muted]">{racer.class}</span>
+                </div>
+                <div className="text-xs text-[--color-text-muted] truncate">
+                  {racer.finished ? 'Finished' : racer.lastSplit ?? 'Started'}
+                  {racer.splitTimeMs !== null && ` at ${formatRaceTime(racer.splitTimeMs)}`}
+                </div>
+              </div>
+              <button
+                onClick={() =>
+                  comparingWith(racer)
+                    ? setComparisonKind({ kind: 'personal_best' })
+                    : setComparisonKind({ kind: 'reference', runId: racer.runId })
+                }
+                className={`px-2 py-1 text-xs rounded border shrink-0 ${
+                  comparingWith(racer)
+                    ? 'border-[--color-poe-gold] text-[--color-poe-gold]'
+                    : 'border-[--color-border] text-[--color-text] hover:border-[--color-poe-gold]'
+                }`}
+              >
+                {comparingWith(racer) ? 'Comparing' : 'Compare'}
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {error && <div className="text-xs text-[--color-timer-behind]">{error}</div>}
+    </div>
+  );
+}
+
+function formatRaceTime(ms: number): string {
+  const totalSeconds = Math.floor(ms / 1000);
+  const hours = Math.floor(totalSeconds / 3600);
+  const minutes = Math.floor((totalSeconds % 3600) / 60);
+  const seconds = totalSeconds % 60;
+  const mmss = `${minutes.toString().padStart(2, '0')}:${seconds.toString().padStart(2, '0')}`;
+  return hours > 0 ? `${hours}:${mmss}` : mmss;
+}
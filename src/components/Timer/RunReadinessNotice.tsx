@@ -0,0 +1,38 @@
+import { useSettingsStore } from '../../stores/settingsStore';
+
+/** Failed checks from the last pre-run checklist, and whether they stopped the run */
+export function RunReadinessNotice({ className = '' }: { className?: string }) {
+  const { runReadiness, clearRunReadiness } = useSettingsStore();
+
+  if (!runReadiness || runReadiness.ready) return null;
+  const failed = runReadiness.checks.filter((check) => !check.passed);
+
+  return (
+    <div
+      className={`rounded-lg p-3 text-xs border border-[--color-timer-behind]/50 bg-[--color-timer-behind]/10 ${className}`}
+    >
+      <div className="flex items-start justify-between gap-2">
+        <div className="text-[--color-timer-behind] font-semibold">
+          {runReadiness.blocked
+            ? 'Run not started - fix these or switch the pre-run checklist to Warn in Settings'
+            : 'Pre-run checklist'}
+        </div>
+        <button
+          onClick={clearRunReadiness}
+          className="text-[--color-text-muted] hover:text-[--color-text]"
+          title="Dismiss"
+        >
+          &times;
+        </button>
+      </div>
+      <ul className="mt-1 space-y-0.5">
+        {failed.map((check) => (
+          <li key={check.id} className="text-[--color-text-muted]">
+            <span className="text-[--color-text]">{check.label}</span>
+            {check.detail && ` - ${check.detail}`}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
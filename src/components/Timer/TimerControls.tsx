@@ -1,13 +1,27 @@
+import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { emit } from '@tauri-apps/api/event';
 import { useRunStore } from '../../stores/runStore';
 import { useSettingsStore } from '../../stores/settingsStore';
+import { nextBreakpoint } from '../../hooks/useHotkeys';
+import { ABANDON_REASON_LABELS } from '../../types';
+import type { AbandonReason } from '../../types';
 
 export function TimerControls() {
-  const { timer, currentRun, startTimer, stopTimer, resetRun, endRun, setRunId } = useRunStore();
+  const { timer, currentRun, startTimer, stopTimer, resetRun, abandonRun, endRun, setRunId, undoLastSplit, skipSplit } = useRunStore();
   const { accountName, testCharacterName, hotkeys } = useSettingsStore();
+  const [pickingReason, setPickingReason] = useState(false);
 
   const handleStart = async () => {
+    const isNewRun = timer.elapsedMs === 0;
+    const { readinessMode, checkRunReadiness } = useSettingsStore.getState();
+
+    // In block mode a new run only starts once the pre-run checklist passes
+    if (isNewRun && readinessMode === 'block') {
+      const readiness = await checkRunReadiness();
+      if (readiness?.blocked) return;
+    }
+
     // Start the timer (creates local run state)
     startTimer();
 
@@ -16,8 +30,18 @@ export function TimerControls() {
     const run = state.currentRun;
 
     // If this is a fresh start (not a resume), create the run in the database
-    if (run && timer.elapsedMs === 0) {
+    if (run && isNewRun) {
       try {
+        // Surface problems now rather than as failed snapshots mid-run
+        if (readinessMode === 'warn') {
+          checkRunReadiness();
+        } else if (readinessMode === 'off') {
+          useSettingsStore.getState().checkProfilePrivacy();
+        }
+
+        // Apply this category's saved defaults before reading breakpoint info
+        await useSettingsStore.getState().applyCategoryDefaults(run.category || 'any%');
+
         // Get breakpoint preset info
         const presetName = useSettingsStore.getState().getCurrentPresetName();
         const enabledBreakpoints = useSettingsStore.getState().getEnabledBreakpointNames();
@@ -46,10 +70,21 @@ export function TimerControls() {
     stopTimer();
   };
 
+  // Resetting an unfinished run asks why it's being given up
   const handleReset = () => {
+    if (currentRun && !currentRun.isCompleted) {
+      setPickingReason(true);
+      return;
+    }
     resetRun();
   };
 
+  const handleAbandon = (reason: AbandonReason | null) => {
+    setPickingReason(false);
+    // Recorded as a reset at the last split even without a reason
+    abandonRun(reason);
+  };
+
   const handleEnd = async () => {
     const state = useRunStore.getState();
     const run = state.currentRun;
@@ -122,17 +157,8 @@ export function TimerControls() {
   const handleManualSplit = () => {
     if (!timer.isRunning) return;
 
-    const { breakpoints } = useSettingsStore.getState();
-    const completedSplits = new Set(timer.splits.map(s => s.name));
-
-    // Find the next enabled breakpoint that hasn't been completed yet
-    for (const bp of breakpoints) {
-      if (!bp.isEnabled) continue;
-      if (completedSplits.has(bp.name)) continue;
-
-      emit('split-trigger', { name: bp.name, type: bp.type });
-      return;
-    }
+    const bp = nextBreakpoint();
+    if (bp) emit('split-trigger', { name: bp.name, type: bp.type });
   };
 
   const handleManualSnapshot = async () => {
@@ -215,6 +241,37 @@ export function TimerControls() {
         Split
       </button>
 
+      {currentRun && !currentRun.isCompleted && (
+        <button
+          onClick={() => undoLastSplit()}
+          disabled={timer.splits.length === 0 && timer.skippedSplits.length === 0}
+          className="py-3 px-4 bg-[--color-surface] text-[--color-text] font-semibold rounded-lg
+                     border-2 border-[--color-border] shadow-md
+                     hover:border-[--color-text-muted] hover:shadow-lg active:scale-95 active:shadow-sm transition-all duration-100
+                     disabled:opacity-50 disabled:cursor-not-allowed disabled:active:scale-100 disabled:shadow-none"
+          title={`Undo the last split or skip (${hotkeys.undoSplit})`}
+        >
+          Undo
+        </button>
+      )}
+
+      {currentRun && !currentRun.isCompleted && (
+        <button
+          onClick={() => {
+            const bp = nextBreakpoint();
+            if (bp) skipSplit(bp.name);
+          }}
+          disabled={!timer.isRunning}
+          className="py-3 px-4 bg-[--color-surface] text-[--color-text] font-semibold rounded-lg
+                     border-2 border-[--color-border] shadow-md
+                     hover:border-[--color-text-muted] hover:shadow-lg active:scale-95 active:shadow-sm transition-all duration-100
+                     disabled:opacity-50 disabled:cursor-not-allowed disabled:active:scale-100 disabled:shadow-none"
+          title={`Skip the next split (${hotkeys.skipSplit})`}
+        >
+          Skip
+        </button>
+      )}
+
       {currentRun && (
         <button
           onClick={handleManualSnapshot}
@@ -251,6 +308,32 @@ export function TimerControls() {
         Reset
       </button>
       </div>
+      {pickingReason && (
+        <div className="flex flex-wrap items-center gap-2 p-2 bg-[--color-surface] border border-[--color-border] rounded-lg">
+          <span className="text-xs text-[--color-text-muted]">Why reset?</span>
+          {(Object.keys(ABANDON_REASON_LABELS) as AbandonReason[]).map((reason) => (
+            <button
+              key={reason}
+              onClick={() => handleAbandon(reason)}
+              className="px-2 py-1 text-xs bg-[--color-surface-elevated] text-[--color-text] rounded border border-[--color-border] hover:border-[--color-timer-behind]"
+            >
+              {ABANDON_REASON_LABELS[reason]}
+            </button>
+          ))}
+          <button
+            onClick={() => handleAbandon(null)}
+            className="px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text]"
+          >
+            Skip
+          </button>
+          <button
+            onClick={() => setPickingReason(false)}
+            className="ml-auto px-2 py-1 text-xs text-[--color-text-muted] hover:text-[--color-text]"
+          >
+            Cancel
+          </button>
+        </div>
+      )}
       <div className="text-center text-xs text-[--color-text-muted]">
         Hotkey: <kbd className="px-1.5 py-0.5 bg-[--color-surface-elevated] rounded text-[--color-text]">{hotkeys.toggleTimer}</kbd> to start/pause
       </div>
@@ -23,6 +23,8 @@ export function TimerDisplay({ elapsedMs, deltaMs }: TimerDisplayProps) {
 }
 
 function formatTime(ms: number): string {
+  // Negative while a start countdown is running
+  if (ms < 0) return `-${formatTime(-ms)}`;
   const totalSeconds = Math.floor(ms / 1000);
   const hours = Math.floor(totalSeconds / 3600);
   const minutes = Math.floor((totalSeconds % 3600) / 60);
@@ -5,11 +5,22 @@ import { useSettingsStore } from '../../stores/settingsStore';
 import { TimerDisplay } from './TimerDisplay';
 import { TimerControls } from './TimerControls';
 import { SplitList } from '../Splits/SplitList';
+import { ProfilePrivacyNotice } from '../Shared/ProfilePrivacyNotice';
+import { RunReadinessNotice } from './RunReadinessNotice';
+import { LogRotationNotice } from './LogRotationNotice';
+import { CharacterMismatchNotice } from './CharacterMismatchNotice';
+import { RaceLobby } from './RaceLobby';
 import type { TimerState } from '../../types';
 
+const POLL_REASONS = {
+  loading: 'loading',
+  breakpoint: 'Kitava split next',
+  manual: 'manual',
+} as const;
+
 export function TimerView() {
   const { timer, updateElapsed, currentRun } = useRunStore();
-  const { overlayOpen, overlayEnabled, setOverlayOpen, hotkeys } = useSettingsStore();
+  const { overlayOpen, overlayEnabled, setOverlayOpen, hotkeys, pollMode } = useSettingsStore();
   const animationRef = useRef<number | null>(null);
 
   const handleToggleOverlay = useCallback(async () => {
@@ -98,6 +109,12 @@ export function TimerView() {
                     {timer.inHideout && ' (Hideout)'}
                   </span>
                 </div>
+                <span
+                  className={`text-xs ${pollMode?.fast ? 'text-[--color-poe-gold]' : 'text-[--color-text-muted]'}`}
+                  title="How often Client.txt is read. Faster during loading screens and before in-zone splits."
+                >
+                  {pollMode?.fast ? `Fast polling (${POLL_REASONS[pollMode.reason ?? 'manual']})` : 'Normal polling'}
+                </span>
               </div>
               <div className="flex gap-6 text-sm">
                 <div className="flex items-center gap-2">
@@ -118,6 +135,12 @@ export function TimerView() {
 
           <TimerControls />
 
+          <RunReadinessNotice className="mt-4" />
+          <CharacterMismatchNotice className="mt-4" />
+          <LogRotationNotice className="mt-4" />
+          <ProfilePrivacyNotice className="mt-4" />
+          <RaceLobby className="mt-4" />
+
           {/* Run info panel */}
           {!currentRun && (
             <div className="mt-6 bg-[--color-surface] rounded-lg p-6">
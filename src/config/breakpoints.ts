@@ -1,4 +1,24 @@
-import type { Breakpoint } from '../types';
+import type { Breakpoint, BreakpointTrigger, Game } from '../types';
+
+/**
+ * Canonical key for the location a breakpoint fires at, independent of its
+ * display name (e.g. `zone:a2:the_southern_forest`, `kitava:a5`, `level:40`).
+ * Gold splits and split stats group by this key so presets can name splits freely.
+ */
+export function getBreakpointKey(trigger: BreakpointTrigger): string | null {
+  const slug = (s: string) => s.toLowerCase().replace(/[^a-z0-9]+/g, '_').replace(/^_+|_+$/g, '');
+  switch (trigger.type) {
+    case 'level':
+      return trigger.level ? `level:${trigger.level}` : null;
+    case 'kitava':
+      return trigger.act ? `kitava:a${trigger.act}` : null;
+    case 'quest':
+      return trigger.quest ? `quest:${trigger.quest}` : null;
+    default:
+      if (!trigger.zoneName) return null;
+      return trigger.act ? `zone:a${trigger.act}:${slug(trigger.zoneName)}` : `zone:${slug(trigger.zoneName)}`;
+  }
+}
 
 // All POE zones organized by act - comprehensive list for speedrunning
 export const defaultBreakpoints: Breakpoint[] = [
@@ -196,6 +216,83 @@ export const defaultBreakpoints: Breakpoint[] = [
   { name: 'Level 90', type: 'level', trigger: { type: 'level', level: 90 }, isEnabled: false, captureSnapshot: false },
 ];
 
+// Path of Exile 2 campaign zones organized by act (the `game` setting picks the list)
+export const poe2Breakpoints: Breakpoint[] = [
+  // ===== ACT 1 =====
+  { name: 'The Riverbank', type: 'zone', trigger: { type: 'zone', zoneName: 'The Riverbank', act: 1 }, isEnabled: true, captureSnapshot: true },
+  { name: 'Clearfell Encampment', type: 'act', trigger: { type: 'zone', zoneName: 'Clearfell Encampment', act: 1 }, isEnabled: true, captureSnapshot: true },
+  { name: 'Clearfell', type: 'zone', trigger: { type: 'zone', zoneName: 'Clearfell', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Mud Burrow', type: 'zone', trigger: { type: 'zone', zoneName: 'Mud Burrow', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Grelwood', type: 'zone', trigger: { type: 'zone', zoneName: 'The Grelwood', act: 1 }, isEnabled: true, captureSnapshot: false },
+  { name: 'The Red Vale', type: 'zone', trigger: { type: 'zone', zoneName: 'The Red Vale', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Grim Tangle', type: 'zone', trigger: { type: 'zone', zoneName: 'The Grim Tangle', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Cemetery of the Eternals', type: 'zone', trigger: { type: 'zone', zoneName: 'Cemetery of the Eternals', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Mausoleum of the Praetor', type: 'zone', trigger: { type: 'zone', zoneName: 'Mausoleum of the Praetor', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Tomb of the Consort', type: 'zone', trigger: { type: 'zone', zoneName: 'Tomb of the Consort', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Hunting Grounds', type: 'zone', trigger: { type: 'zone', zoneName: 'Hunting Grounds', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Freythorn', type: 'zone', trigger: { type: 'zone', zoneName: 'Freythorn', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Ogham Farmlands', type: 'zone', trigger: { type: 'zone', zoneName: 'Ogham Farmlands', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Ogham Village', type: 'zone', trigger: { type: 'zone', zoneName: 'Ogham Village', act: 1 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Manor Ramparts', type: 'zone', trigger: { type: 'zone', zoneName: 'The Manor Ramparts', act: 1 }, isEnabled: false, captureSnapshot: false },
+  // Count Geonor - end of Act 1
+  { name: 'Ogham Manor', type: 'boss', trigger: { type: 'zone', zoneName: 'Ogham Manor', act: 1 }, isEnabled: true, captureSnapshot: false },
+
+  // ===== ACT 2 =====
+  { name: 'Vastiri Outskirts', type: 'zone', trigger: { type: 'zone', zoneName: 'Vastiri Outskirts', act: 2 }, isEnabled: true, captureSnapshot: true },
+  { name: 'The Ardura Caravan', type: 'act', trigger: { type: 'zone', zoneName: 'The Ardura Caravan', act: 2 }, isEnabled: true, captureSnapshot: true },
+  { name: 'Mawdun Quarry', type: 'zone', trigger: { type: 'zone', zoneName: 'Mawdun Quarry', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Mawdun Mine', type: 'zone', trigger: { type: 'zone', zoneName: 'Mawdun Mine', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: "Traitor's Passage", type: 'zone', trigger: { type: 'zone', zoneName: "Traitor's Passage", act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Halani Gates', type: 'zone', trigger: { type: 'zone', zoneName: 'The Halani Gates', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Keth', type: 'zone', trigger: { type: 'zone', zoneName: 'Keth', act: 2 }, isEnabled: true, captureSnapshot: false },
+  { name: 'The Lost City', type: 'zone', trigger: { type: 'zone', zoneName: 'The Lost City', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Buried Shrines', type: 'zone', trigger: { type: 'zone', zoneName: 'Buried Shrines', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Mastodon Badlands', type: 'zone', trigger: { type: 'zone', zoneName: 'Mastodon Badlands', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Bone Pits', type: 'zone', trigger: { type: 'zone', zoneName: 'The Bone Pits', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Valley of the Titans', type: 'zone', trigger: { type: 'zone', zoneName: 'Valley of the Titans', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Titan Grotto', type: 'zone', trigger: { type: 'zone', zoneName: 'The Titan Grotto', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Deshar', type: 'zone', trigger: { type: 'zone', zoneName: 'Deshar', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Path of Mourning', type: 'zone', trigger: { type: 'zone', zoneName: 'Path of Mourning', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Spires of Deshar', type: 'zone', trigger: { type: 'zone', zoneName: 'The Spires of Deshar', act: 2 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Dreadnought', type: 'zone', trigger: { type: 'zone', zoneName: 'The Dreadnought', act: 2 }, isEnabled: false, captureSnapshot: false },
+  // Jamanra - end of Act 2
+  { name: 'Dreadnought Vanguard', type: 'boss', trigger: { type: 'zone', zoneName: 'Dreadnought Vanguard', act: 2 }, isEnabled: true, captureSnapshot: false },
+
+  // ===== ACT 3 =====
+  { name: 'Sandswept Marsh', type: 'zone', trigger: { type: 'zone', zoneName: 'Sandswept Marsh', act: 3 }, isEnabled: true, captureSnapshot: true },
+  { name: 'Ziggurat Encampment', type: 'act', trigger: { type: 'zone', zoneName: 'Ziggurat Encampment', act: 3 }, isEnabled: true, captureSnapshot: true },
+  { name: 'Jungle Ruins', type: 'zone', trigger: { type: 'zone', zoneName: 'Jungle Ruins', act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Venom Crypts', type: 'zone', trigger: { type: 'zone', zoneName: 'The Venom Crypts', act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Chimeral Wetlands', type: 'zone', trigger: { type: 'zone', zoneName: 'Chimeral Wetlands', act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: "Jiquani's Machinarium", type: 'zone', trigger: { type: 'zone', zoneName: "Jiquani's Machinarium", act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: "Jiquani's Sanctum", type: 'zone', trigger: { type: 'zone', zoneName: "Jiquani's Sanctum", act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Azak Bog', type: 'zone', trigger: { type: 'zone', zoneName: 'The Azak Bog', act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: 'The Drowned City', type: 'zone', trigger: { type: 'zone', zoneName: 'The Drowned City', act: 3 }, isEnabled: true, captureSnapshot: false },
+  { name: 'The Molten Vault', type: 'zone', trigger: { type: 'zone', zoneName: 'The Molten Vault', act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Apex of Filth', type: 'zone', trigger: { type: 'zone', zoneName: 'Apex of Filth', act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Temple of Kopec', type: 'zone', trigger: { type: 'zone', zoneName: 'Temple of Kopec', act: 3 }, isEnabled: false, captureSnapshot: false },
+  { name: 'Aggorat', type: 'zone', trigger: { type: 'zone', zoneName: 'Aggorat', act: 3 }, isEnabled: false, captureSnapshot: false },
+  // Doryani - end of Act 3
+  { name: 'The Black Chambers', type: 'boss', trigger: { type: 'zone', zoneName: 'The Black Chambers', act: 3 }, isEnabled: true, captureSnapshot: true },
+
+  // ===== ACT 4 =====
+  // Reaching the Act 4 town completes Act 3
+  { name: 'Kingsmarch', type: 'act', trigger: { type: 'zone', zoneName: 'Kingsmarch', act: 4 }, isEnabled: true, captureSnapshot: true },
+
+  // ===== LEVEL MILESTONES =====
+  ...defaultBreakpoints.filter((bp) => bp.type === 'level'),
+];
+
+// Zone a new character starts in, per game
+export const campaignStartZone: Record<Game, string> = {
+  poe1: 'The Twilight Strand',
+  poe2: 'The Riverbank',
+};
+
+export function defaultBreakpointsFor(game: Game): Breakpoint[] {
+  return game === 'poe2' ? poe2Breakpoints : defaultBreakpoints;
+}
+
 // Town zones for tracking "town time"
 export const townZones: string[] = [
   // Act towns
@@ -206,6 +303,11 @@ export const townZones: string[] = [
   "Overseer's Tower",
   'The Bridge Encampment',
   'Oriath Docks',
+  // Path of Exile 2
+  'Clearfell Encampment',
+  'The Ardura Caravan',
+  'Ziggurat Encampment',
+  'Kingsmarch',
 ];
 
 // Hideout zones
@@ -284,6 +386,27 @@ export const speedrunEnabledBreakpoints = [
   'Kitava (Act 10)',
 ] as const;
 
+// Path of Exile 2 speedrun breakpoints (act towns, bosses and a zone per act)
+export const poe2SpeedrunEnabledBreakpoints = [
+  'The Riverbank',
+  'Clearfell Encampment',
+  'The Grelwood',
+  'Ogham Manor',
+  'Vastiri Outskirts',
+  'The Ardura Caravan',
+  'Keth',
+  'Dreadnought Vanguard',
+  'Sandswept Marsh',
+  'Ziggurat Encampment',
+  'The Drowned City',
+  'The Black Chambers',
+  'Kingsmarch',
+] as const;
+
+export function speedrunBreakpointsFor(game: Game): readonly string[] {
+  return game === 'poe2' ? poe2SpeedrunEnabledBreakpoints : speedrunEnabledBreakpoints;
+}
+
 // Act 1 speedrun order (Tidal Island after Mud Flats and Submerged Passage)
 // This is for reference on typical speedrun routing
 
@@ -301,15 +424,28 @@ export const actFirstZones = [
   'The Cathedral Rooftop (A10)', // Act 10
 ] as const;
 
+// First zone of each Path of Exile 2 act
+export const poe2ActFirstZones = [
+  'The Riverbank',     // Act 1
+  'Vastiri Outskirts', // Act 2
+  'Sandswept Marsh',   // Act 3
+  'Kingsmarch',        // Act 4
+] as const;
+
 function isActFirstZone(name: string): boolean {
-  return (actFirstZones as readonly string[]).includes(name);
+  return (actFirstZones as readonly string[]).includes(name) || (poe2ActFirstZones as readonly string[]).includes(name);
+}
+
+function isSpeedrunBreakpoint(name: string): boolean {
+  return (speedrunEnabledBreakpoints as readonly string[]).includes(name)
+    || (poe2SpeedrunEnabledBreakpoints as readonly string[]).includes(name);
 }
 
 // Preset functions for quick configuration
 export function applySpeedrunPreset(breakpoints: Breakpoint[]): Breakpoint[] {
   return breakpoints.map((bp) => ({
     ...bp,
-    isEnabled: isActFirstZone(bp.name) || speedrunEnabledBreakpoints.includes(bp.name as typeof speedrunEnabledBreakpoints[number]),
+    isEnabled: isActFirstZone(bp.name) || isSpeedrunBreakpoint(bp.name),
     captureSnapshot: isActFirstZone(bp.name) ? true : bp.captureSnapshot,
   }));
 }
@@ -332,6 +468,6 @@ export function applyTownsOnlyPreset(breakpoints: Breakpoint[]): Breakpoint[] {
   }));
 }
 
-export function resetToDefault(): Breakpoint[] {
-  return JSON.parse(JSON.stringify(defaultBreakpoints));
+export function resetToDefault(game: Game = 'poe1'): Breakpoint[] {
+  return JSON.parse(JSON.stringify(defaultBreakpointsFor(game)));
 }
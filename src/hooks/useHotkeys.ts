@@ -3,6 +3,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { useRunStore } from '../stores/runStore';
 import { useSettingsStore } from '../stores/settingsStore';
+import type { Breakpoint } from '../types';
 
 interface HotkeyConfig {
   key: string;
@@ -34,6 +35,13 @@ function parseShortcutToHotkeyConfig(shortcut: string): { key: string; ctrl: boo
   return { key, ctrl, shift, alt };
 }
 
+/** The next enabled breakpoint the current run hasn't split or skipped */
+export function nextBreakpoint(): Breakpoint | undefined {
+  const { timer } = useRunStore.getState();
+  const done = new Set([...timer.splits.map((s) => s.name), ...timer.skippedSplits.map((s) => s.breakpointName)]);
+  return useSettingsStore.getState().breakpoints.find((bp) => bp.isEnabled && !done.has(bp.name));
+}
+
 export function useHotkeys() {
   const { timer, startTimer, stopTimer, resetRun, setRunId } = useRunStore();
   const { accountName, testCharacterName, hotkeys: hotkeyConfig } = useSettingsStore();
@@ -44,6 +52,13 @@ export function useHotkeys() {
       stopTimer();
     } else {
       const isNewRun = timer.elapsedMs === 0;
+      const { readinessMode, checkRunReadiness } = useSettingsStore.getState();
+
+      // In block mode a new run only starts once the pre-run checklist passes
+      if (isNewRun && readinessMode === 'block') {
+        const readiness = await checkRunReadiness();
+        if (readiness?.blocked) return;
+      }
 
       // Start the timer
       startTimer();
@@ -55,6 +70,16 @@ export function useHotkeys() {
 
         if (run) {
           try {
+            // Surface problems now rather than as failed snapshots mid-run
+            if (readinessMode === 'warn') {
+              checkRunReadiness();
+            } else if (readinessMode === 'off') {
+              useSettingsStore.getState().checkProfilePrivacy();
+            }
+
+            // Apply this category's saved defaults before reading breakpoint info
+            await useSettingsStore.getState().applyCategoryDefaults(run.category || 'any%');
+
             // Get breakpoint preset info
             const presetName = useSettingsStore.getState().getCurrentPresetName();
             const enabledBreakpoints = useSettingsStore.getState().getEnabledBreakpointNames();
@@ -84,8 +109,6 @@ export function useHotkeys() {
   // Reset timer
   const resetTimer = useCallback(() => {
     resetRun();
-    // Disable fast polling on reset
-    invoke('set_log_poll_fast', { enabled: false }).catch(() => {});
   }, [resetRun]);
 
   // Manual split - triggers the next expected breakpoint
@@ -93,21 +116,28 @@ export function useHotkeys() {
     const { timer: t } = useRunStore.getState();
     if (!t.isRunning) return;
 
-    const { breakpoints } = useSettingsStore.getState();
-    const completedSplits = new Set(t.splits.map(s => s.name));
+    const bp = nextBreakpoint();
+    if (!bp) return;
 
-    // Find the next enabled breakpoint that hasn't been completed yet
-    for (const bp of breakpoints) {
-      if (!bp.isEnabled) continue;
-      if (completedSplits.has(bp.name)) continue;
+    // Emit a split-trigger event for this breakpoint
+    // The useTauriEvents hook listens for this and handles the actual split logic
+    import('@tauri-apps/api/event').then(({ emit }) => {
+      emit('split-trigger', { name: bp.name, type: bp.type });
+    });
+  }, []);
 
-      // Emit a split-trigger event for this breakpoint
-      // The useTauriEvents hook listens for this and handles the actual split logic
-      import('@tauri-apps/api/event').then(({ emit }) => {
-        emit('split-trigger', { name: bp.name, type: bp.type });
-      });
-      return;
-    }
+  // Undo the last split (or skip)
+  const undoSplit = useCallback(() => {
+    useRunStore.getState().undoLastSplit();
+  }, []);
+
+  // Skip the next expected breakpoint, e.g. one the log watcher missed
+  const skipSplit = useCallback(() => {
+    const { timer: t, skipSplit: skip } = useRunStore.getState();
+    if (!t.isRunning) return;
+
+    const bp = nextBreakpoint();
+    if (bp) skip(bp.name);
   }, []);
 
   // Manual snapshot capture - works whether timer is running or paused
@@ -173,14 +203,18 @@ export function useHotkeys() {
     const resetTimerParsed = parseShortcutToHotkeyConfig(hotkeyConfig.resetTimer);
     const snapshotParsed = parseShortcutToHotkeyConfig(hotkeyConfig.manualSnapshot);
     const splitParsed = parseShortcutToHotkeyConfig(hotkeyConfig.manualSplit);
+    const undoParsed = parseShortcutToHotkeyConfig(hotkeyConfig.undoSplit);
+    const skipParsed = parseShortcutToHotkeyConfig(hotkeyConfig.skipSplit);
 
     return [
       { ...toggleTimerParsed, action: toggleTimer },
       { ...resetTimerParsed, action: resetTimer },
       { ...snapshotParsed, action: captureManualSnapshot },
       { ...splitParsed, action: triggerManualSplit },
+      { ...undoParsed, action: undoSplit },
+      { ...skipParsed, action: skipSplit },
     ];
-  }, [hotkeyConfig.toggleTimer, hotkeyConfig.resetTimer, hotkeyConfig.manualSnapshot, hotkeyConfig.manualSplit, toggleTimer, resetTimer, captureManualSnapshot, triggerManualSplit]);
+  }, [hotkeyConfig.toggleTimer, hotkeyConfig.resetTimer, hotkeyConfig.manualSnapshot, hotkeyConfig.manualSplit, hotkeyConfig.undoSplit, hotkeyConfig.skipSplit, toggleTimer, resetTimer, captureManualSnapshot, triggerManualSplit, undoSplit, skipSplit]);
 
   useEffect(() => {
     const handleKeyDown = (event: KeyboardEvent) => {
@@ -233,6 +267,10 @@ export function useHotkeys() {
         debounced(captureManualSnapshot);
       } else if (event.payload === 'manual-split') {
         debounced(triggerManualSplit);
+      } else if (event.payload === 'undo-split') {
+        debounced(undoSplit);
+      } else if (event.payload === 'skip-split') {
+        debounced(skipSplit);
       } else if (event.payload === 'toggle-overlay') {
         debounced(toggleOverlay);
       }
@@ -241,5 +279,5 @@ export function useHotkeys() {
     return () => {
       unlistenGlobal.then((fn) => fn());
     };
-  }, [toggleTimer, resetTimer, captureManualSnapshot, triggerManualSplit, toggleOverlay, debounced]);
+  }, [toggleTimer, resetTimer, captureManualSnapshot, triggerManualSplit, undoSplit, skipSplit, toggleOverlay, debounced]);
 }
@@ -1,20 +1,22 @@
-import { useEffect, useRef, useCallback } from 'react';
+import { useEffect, useRef, useCallback, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { useRunStore } from '../stores/runStore';
 import { useSettingsStore } from '../stores/settingsStore';
 import { getWizardCategory } from '../config/wizardRoutes';
-import type { TimerState, Breakpoint } from '../types';
+import type { TimerState, Breakpoint, SplitColor, ComparisonRow, LevelPace, LevelSample, SumOfBest, BestPossibleTime, GuideProgress } from '../types';
 
 interface OverlayState {
   startTime: number | null;
   elapsedMs: number;
   isRunning: boolean;
   currentZone: string | null;
+  deaths: number;
   lastSplit: {
     name: string;
     deltaMs: number | null;
     isBestSegment: boolean;
+    color: SplitColor;
     splitTimeMs?: number;
     segmentTimeMs?: number;
     pbSegmentTimeMs?: number | null;
@@ -22,6 +24,15 @@ interface OverlayState {
   } | null;
   upcomingBreakpoints: { name: string; pbTimeMs: number | null; pbSegmentTimeMs: number | null }[];
   opacity: number;
+  // Character level now, and the PB's at the same run time
+  level: number | null;
+  pbLevel: number | null;
+  // Golds of the enabled breakpoints added up, and the run's time so far plus the golds left
+  sumOfBestMs: number | null;
+  bestPossibleMs: number | null;
+  // Leveling guide step the run is on, and the one after it
+  guideStep: string | null;
+  guideNext: string | null;
   // Display config
   scale: 'small' | 'medium' | 'large';
   fontSize: 'small' | 'medium' | 'large';
@@ -55,21 +66,41 @@ interface OverlayConfig {
   overlayLocked: boolean;
 }
 
+interface BestTimes {
+  sumOfBestMs: number | null;
+  bestPossibleMs: number | null;
+}
+
 interface HotkeyLabels {
   hotkeyToggleTimer: string;
   hotkeyToggleOverlay: string;
   hotkeyToggleOverlayLock: string;
 }
 
+// Highest level the PB had reached by elapsedMs
+function levelAt(samples: LevelSample[], elapsedMs: number): number | null {
+  let level: number | null = null;
+  for (const sample of samples) {
+    if (sample.elapsedMs <= elapsedMs && (level === null || sample.level > level)) {
+      level = sample.level;
+    }
+  }
+  return level;
+}
+
 function buildOverlayState(
   timer: TimerState,
   breakpoints: Breakpoint[],
   config: OverlayConfig,
   personalBests: Map<string, number>,
   goldSplits: Map<string, number>,
+  comparisonRows: ComparisonRow[],
   currentRun: { category: string; class: string } | null,
   hotkeyLabels: HotkeyLabels,
   fallbackCategory: string | null,
+  levelPace: LevelPace | null,
+  bestTimes: BestTimes,
+  guide: GuideProgress | null,
 ): OverlayState {
   const lastTimerSplit = timer.splits[timer.splits.length - 1] || null;
   const enabledBreakpoints = breakpoints.filter((bp: Breakpoint) => bp.isEnabled);
@@ -85,6 +116,11 @@ function buildOverlayState(
   const upcomingBreakpoints = enabledBreakpoints
     .slice(hitCount)
     .map((bp: Breakpoint, idx: number) => {
+      // The selected comparison from the backend, once the run has rows
+      const row = comparisonRows.find((r) => r.breakpointName === bp.name);
+      if (row) {
+        return { name: bp.name, pbTimeMs: row.comparisonSplitMs, pbSegmentTimeMs: row.comparisonSegmentMs };
+      }
       const pbTimeMs = category ? (personalBests.get(`${category}-${cls}-${bp.name}`) ?? null) : null;
       // Compute PB segment time: this BP's PB - previous BP's PB
       let pbSegmentTimeMs: number | null = null;
@@ -128,18 +164,22 @@ function buildOverlayState(
     }
   }
 
+  const elapsedMs = timer.isRunning && timer.startTime
+    ? Date.now() - timer.startTime
+    : timer.elapsedMs;
+
   return {
     startTime: timer.startTime,
-    elapsedMs: timer.isRunning && timer.startTime
-      ? Date.now() - timer.startTime
-      : timer.elapsedMs,
+    elapsedMs,
     isRunning: timer.isRunning,
     currentZone: timer.currentZone,
+    deaths: timer.deaths,
     lastSplit: lastTimerSplit
       ? {
           name: lastTimerSplit.name,
           deltaMs: lastTimerSplit.deltaMs,
           isBestSegment: lastTimerSplit.isBestSegment,
+          color: lastTimerSplit.color,
           splitTimeMs: lastTimerSplit.splitTimeMs,
           segmentTimeMs: lastTimerSplit.segmentTimeMs,
           pbSegmentTimeMs,
@@ -148,6 +188,12 @@ function buildOverlayState(
       : null,
     upcomingBreakpoints,
     opacity: config.overlayOpacity,
+    level: levelPace?.level ?? null,
+    pbLevel: levelPace ? levelAt(levelPace.pbLevels, elapsedMs) : null,
+    sumOfBestMs: bestTimes.sumOfBestMs,
+    bestPossibleMs: bestTimes.bestPossibleMs,
+    guideStep: guide?.step?.text ?? null,
+    guideNext: guide?.next?.text ?? null,
     scale: config.overlayScale,
     fontSize: config.overlayFontSize,
     showTimer: config.overlayShowTimer,
@@ -165,6 +211,9 @@ function buildOverlayState(
   };
 }
 
+const HEARTBEAT_RUNNING_MS = 2000;
+const HEARTBEAT_IDLE_MS = 10000;
+
 function sendToOverlay(state: OverlayState) {
   invoke('sync_overlay_state', { state }).catch(() => {
     // Silently ignore - overlay might not be open
@@ -175,6 +224,7 @@ export function useOverlaySync() {
   const timer = useRunStore((state: { timer: TimerState }) => state.timer);
   const personalBests = useRunStore((state) => state.personalBests);
   const goldSplits = useRunStore((state) => state.goldSplits);
+  const comparisonRows = useRunStore((state) => state.comparisonRows);
   const currentRun = useRunStore((state) => state.currentRun);
   const breakpoints = useSettingsStore((state: { breakpoints: Breakpoint[] }) => state.breakpoints);
   const wizardConfig = useSettingsStore((state) => state.wizardConfig);
@@ -207,6 +257,75 @@ export function useOverlaySync() {
     overlayLocked,
   };
 
+  // Level pace of the current run, fetched again after each level-up and split
+  const [levelPace, setLevelPace] = useState<LevelPace | null>(null);
+  const runId = currentRun?.id;
+  const splitCount = timer.splits.length;
+  useEffect(() => {
+    if (!runId) {
+      setLevelPace(null);
+      return;
+    }
+    const refresh = () => {
+      invoke<LevelPace>('get_level_pace', { runId })
+        .then(setLevelPace)
+        .catch(() => setLevelPace(null));
+    };
+    refresh();
+    const unlisten = listen('level-pace-changed', refresh);
+    return () => { unlisten.then((fn) => fn()); };
+  }, [runId, splitCount]);
+
+  // Sum of best for the selected breakpoints, fetched again when golds change
+  const [sumOfBestMs, setSumOfBestMs] = useState<number | null>(null);
+  const presetName = useSettingsStore((state) => state.getCurrentPresetName());
+  const sobCategory = currentRun?.category ?? (wizardConfig ? getWizardCategory(wizardConfig) : null);
+  const sobClass = currentRun?.class ?? null;
+  useEffect(() => {
+    if (!sobCategory) {
+      setSumOfBestMs(null);
+      return;
+    }
+    invoke<SumOfBest>('get_sum_of_best', { category: sobCategory, preset: presetName, class: sobClass })
+      .then((sob) => setSumOfBestMs(sob.sumOfBestMs))
+      .catch(() => setSumOfBestMs(null));
+  }, [sobCategory, sobClass, presetName, goldSplits]);
+
+  // Best possible time, after each split and while the current segment runs
+  // past its gold
+  const [bestPossibleMs, setBestPossibleMs] = useState<number | null>(null);
+  useEffect(() => {
+    if (!runId) {
+      setBestPossibleMs(null);
+      return;
+    }
+    const refresh = () => {
+      invoke<BestPossibleTime>('get_best_possible_time', { runId })
+        .then((best) => setBestPossibleMs(best.bestPossibleMs))
+        .catch(() => setBestPossibleMs(null));
+    };
+    refresh();
+    if (!timer.isRunning) return;
+    const interval = setInterval(refresh, HEARTBEAT_RUNNING_MS);
+    return () => clearInterval(interval);
+  }, [runId, splitCount, timer.isRunning, goldSplits]);
+
+  // Leveling guide step, moved on by the backend as log events arrive
+  const [guide, setGuide] = useState<GuideProgress | null>(null);
+  useEffect(() => {
+    if (!runId) {
+      setGuide(null);
+      return;
+    }
+    invoke<GuideProgress>('get_current_guide_step', { runId })
+      .then(setGuide)
+      .catch(() => setGuide(null));
+    const unlisten = listen<GuideProgress>('guide-step-changed', (event) => {
+      if (event.payload.runId === runId) setGuide(event.payload);
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, [runId]);
+
   // Track previous non-time state to detect meaningful changes
   const prevNonTimeRef = useRef<string>('');
 
@@ -220,10 +339,11 @@ export function useOverlaySync() {
   const syncNow = useCallback(() => {
     const runInfo = currentRun ? { category: currentRun.category, class: currentRun.class } : null;
     const fallbackCategory = wizardConfig ? getWizardCategory(wizardConfig) : null;
-    const state = buildOverlayState(timer, breakpoints, config, personalBests, goldSplits, runInfo, hotkeyLabels, fallbackCategory);
+    const bestTimes = { sumOfBestMs, bestPossibleMs };
+    const state = buildOverlayState(timer, breakpoints, config, personalBests, goldSplits, comparisonRows, runInfo, hotkeyLabels, fallbackCategory, levelPace, bestTimes, guide);
     sendToOverlay(state);
   // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, [timer, breakpoints, overlayOpacity, overlayScale, overlayFontSize, overlayShowTimer, overlayShowZone, overlayShowLastSplit, overlayShowBreakpoints, overlayBreakpointCount, overlayBgOpacity, overlayAccentColor, overlayAlwaysOnTop, overlayLocked, personalBests, goldSplits, currentRun, hotkeys, wizardConfig]);
+  }, [timer, breakpoints, overlayOpacity, overlayScale, overlayFontSize, overlayShowTimer, overlayShowZone, overlayShowLastSplit, overlayShowBreakpoints, overlayBreakpointCount, overlayBgOpacity, overlayAccentColor, overlayAlwaysOnTop, overlayLocked, personalBests, goldSplits, comparisonRows, currentRun, hotkeys, wizardConfig, levelPace, sumOfBestMs, bestPossibleMs, guide]);
 
   // Emit immediately on meaningful state changes (zone, splits, start/stop, config, etc.)
   useEffect(() => {
@@ -231,6 +351,7 @@ export function useOverlaySync() {
       startTime: timer.startTime,
       isRunning: timer.isRunning,
       currentZone: timer.currentZone,
+      deaths: timer.deaths,
       currentSplit: timer.currentSplit,
       splitCount: timer.splits.length,
       lastSplitName: timer.splits[timer.splits.length - 1]?.name,
@@ -248,13 +369,18 @@ export function useOverlaySync() {
       locked: overlayLocked,
       pbCount: personalBests.size,
       goldCount: goldSplits.size,
+      comparisonRows,
+      level: levelPace?.level,
+      sumOfBestMs,
+      bestPossibleMs,
+      guideStep: guide?.stepIndex,
     });
 
     if (nonTimeKey !== prevNonTimeRef.current) {
       prevNonTimeRef.current = nonTimeKey;
       syncNow();
     }
-  }, [timer, overlayOpacity, overlayScale, overlayFontSize, overlayShowTimer, overlayShowZone, overlayShowLastSplit, overlayShowBreakpoints, overlayBreakpointCount, overlayBgOpacity, overlayAccentColor, overlayAlwaysOnTop, overlayLocked, personalBests, goldSplits, syncNow]);
+  }, [timer, overlayOpacity, overlayScale, overlayFontSize, overlayShowTimer, overlayShowZone, overlayShowLastSplit, overlayShowBreakpoints, overlayBreakpointCount, overlayBgOpacity, overlayAccentColor, overlayAlwaysOnTop, overlayLocked, personalBests, goldSplits, comparisonRows, levelPace, sumOfBestMs, bestPossibleMs, guide, syncNow]);
 
   // Listen for overlay-ready signal and immediately sync
   useEffect(() => {
@@ -267,12 +393,15 @@ export function useOverlaySync() {
     };
   }, [syncNow]);
 
-  // Periodic heartbeat so overlay stays in sync even if it opens late
+  // Periodic heartbeat so overlay stays in sync even if it opens late. The
+  // overlay and status page count the time up themselves from startTime, so
+  // this only corrects drift; a stopped timer has nothing to correct.
+  const heartbeatMs = timer.isRunning ? HEARTBEAT_RUNNING_MS : HEARTBEAT_IDLE_MS;
   useEffect(() => {
     const interval = setInterval(() => {
       syncNow();
-    }, 2000);
+    }, heartbeatMs);
 
     return () => clearInterval(interval);
-  }, [syncNow]);
+  }, [syncNow, heartbeatMs]);
 }
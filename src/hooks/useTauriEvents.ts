@@ -4,8 +4,9 @@ import { invoke } from '@tauri-apps/api/core';
 import { useRunStore } from '../stores/runStore';
 import { useSettingsStore } from '../stores/settingsStore';
 import { useSnapshotStore } from '../stores/snapshotStore';
-import { isTownZone, isHideoutZone } from '../config/breakpoints';
-import type { Settings, Snapshot } from '../types';
+import { isTownZone, isHideoutZone, getBreakpointKey } from '../config/breakpoints';
+import { announceSplit } from '../utils/announce';
+import type { AddedSplit, CharacterMismatch, LogRotation, PollMode, Run, Settings, SkippedSplit, Snapshot, Split, SplitAnnouncement, SplitComparison } from '../types';
 
 interface LogEventPayload {
   event_type: string;
@@ -15,53 +16,128 @@ interface LogEventPayload {
   character_class?: string;
   level?: number;
   penalty?: number;
+  version?: string;
+  act?: number;
 }
 
 interface SettingsPayload {
   settings: Settings;
 }
 
+// split_id is null for level milestone snapshots, which no split row tracks
 interface SnapshotCapturingPayload {
-  split_id: number;
+  split_id: number | null;
   breakpoint_name?: string;
+  level?: number;
 }
 
 interface SnapshotCompletePayload {
-  split_id: number;
+  split_id: number | null;
   snapshot_id: number;
   character_level: number;
+  is_final?: boolean;
 }
 
 interface SnapshotFailedPayload {
-  split_id: number;
+  split_id: number | null;
   error: string;
 }
 
+interface SnapshotSkippedPayload {
+  split_id: number | null;
+  superseded_by: number | null;
+}
+
+interface SnapshotCancelledPayload {
+  split_id: number | null;
+}
+
+interface SplitRecordedPayload {
+  run_id: number;
+  split_id: number;
+  breakpoint_name: string;
+  breakpoint_type: string;
+  breakpoint_key: string | null;
+  split_time_ms: number;
+  segment_time_ms: number;
+  town_time_ms: number;
+  hideout_time_ms: number;
+  is_last: boolean;
+  comparison: SplitComparison;
+}
+
+interface RunClassUpdatedPayload {
+  run_id: number;
+  class: string;
+  ascendancy: string | null;
+}
+
+interface RunCategoryUpdatedPayload {
+  run_id: number;
+  category: string;
+}
+
+interface PauseChangedPayload {
+  run_id: number;
+  paused: boolean;
+  elapsed_ms: number;
+  reason: 'manual' | 'afk' | 'disconnect' | null;
+}
+
+// The run being timed, as the backend has it (main-window-restored)
+interface RunResyncPayload {
+  clock: PauseChangedPayload;
+  splits: Split[];
+  skipped_splits: SkippedSplit[];
+}
+
+interface DeathRecordedPayload {
+  run_id: number;
+  death_id: number;
+  zone_name: string | null;
+  elapsed_ms: number;
+  character_level: number | null;
+  deaths: number;
+}
+
+interface RunAutoStartedPayload {
+  run_id: number;
+  zone_name: string;
+  timestamp: string;
+  run: Run;
+}
+
+interface SnapshotQueuePayload {
+  capturing: number | null;
+  queued: number[];
+}
+
 export function useTauriEvents() {
   const { addSplit } = useRunStore();
   const { loadSettings } = useSettingsStore();
-  const { addPendingCapture, addFailedCapture, addSnapshot } = useSnapshotStore();
-
-  // Check if the next uncompleted breakpoint is a kitava trigger and toggle fast polling
-  const updatePollingSpeed = useCallback((completedSplitNames: Set<string>) => {
-    const { breakpoints } = useSettingsStore.getState();
-
-    // Find the next enabled, uncompleted breakpoint
-    for (const bp of breakpoints) {
-      if (!bp.isEnabled) continue;
-      if (completedSplitNames.has(bp.name)) continue;
-
-      // This is the next uncompleted breakpoint
-      const needsFast = bp.trigger.type === 'kitava';
-      invoke('set_log_poll_fast', { enabled: needsFast }).catch(() => {});
-      if (import.meta.env.DEV && needsFast) {
-        console.log('[useTauriEvents] Fast polling enabled - waiting for Kitava split:', bp.name);
-      }
-      return;
+  const { addPendingCapture, removePendingCapture, addFailedCapture, addSnapshot, setCaptureQueue } = useSnapshotStore();
+
+  // End the run after its last breakpoint split
+  const completeRun = useCallback(async (runId: number, splitTimeMs: number) => {
+    // Complete run in database with the accurate split time
+    try {
+      await invoke('complete_run', {
+        runId,
+        totalTimeMs: splitTimeMs,
+      });
+    } catch (error) {
+      console.error('[useTauriEvents] Failed to auto-complete run:', error);
     }
+
+    // Update local state: sync elapsed time then end the run
+    useRunStore.getState().updateElapsed(splitTimeMs);
+    useRunStore.getState().endRun();
+
+    // Reload PB/gold splits so next run shows updated comparisons
+    useRunStore.getState().loadPbAndGoldSplits();
   }, []);
 
-  // Handle triggering a split when a breakpoint matches
+  // Handle a split triggered manually or by a `split-trigger` event
   const triggerSplit = useCallback(async (breakpointName: string, breakpointType: string) => {
     const { timer, currentRun } = useRunStore.getState();
     const { accountName } = useSettingsStore.getState();
@@ -70,8 +146,9 @@ export function useTauriEvents() {
       return;
     }
 
-    // Check if this split was already recorded
-    const alreadyRecorded = timer.splits.some(s => s.name === breakpointName);
+    // Check if this split was already recorded or skipped
+    const alreadyRecorded = timer.splits.some(s => s.name === breakpointName)
+      || timer.skippedSplits.some(s => s.breakpointName === breakpointName);
     if (alreadyRecorded) {
       return;
     }
@@ -80,6 +157,7 @@ export function useTauriEvents() {
     // Check the breakpoint's captureSnapshot setting
     const { breakpoints, testCharacterName } = useSettingsStore.getState();
     const matchedBreakpoint = breakpoints.find(bp => bp.name === breakpointName);
+    const breakpointKey = matchedBreakpoint ? getBreakpointKey(matchedBreakpoint.trigger) : null;
     const detectedCharacter = currentRun?.characterName || currentRun?.character;
     // Use detected character if valid, otherwise fall back to test character name
     const characterName = (detectedCharacter && detectedCharacter !== 'Unknown')
@@ -113,6 +191,7 @@ export function useTauriEvents() {
     addSplit({
       breakpointType: breakpointType as 'zone' | 'level' | 'boss' | 'act' | 'lab' | 'custom',
       breakpointName: breakpointName,
+      breakpointKey,
       splitTimeMs,
       segmentTimeMs,
       deltaMs: null,
@@ -120,20 +199,19 @@ export function useTauriEvents() {
       hideoutTimeMs,
     });
 
-    // Check if next breakpoint needs fast polling
     const completedSplitNames = new Set(timer.splits.map(s => s.name));
     completedSplitNames.add(breakpointName);
-    updatePollingSpeed(completedSplitNames);
 
     // Send to backend with snapshot capture request
     if (currentRun?.id) {
       try {
-        await invoke('add_split', {
+        const added = await invoke<AddedSplit>('add_split', {
           request: {
             split: {
               runId: currentRun.id,
               breakpointType: breakpointType,
               breakpointName: breakpointName,
+              breakpointKey: breakpointKey,
               splitTimeMs: splitTimeMs,
               deltaMs: null,
               segmentTimeMs: segmentTimeMs,
@@ -145,6 +223,7 @@ export function useTauriEvents() {
             character_name: characterName || null,
           },
         });
+        useRunStore.getState().applySplitComparison(breakpointName, added.comparison);
       } catch (error) {
         console.error('[useTauriEvents] Failed to add split to backend:', error);
       }
@@ -154,88 +233,38 @@ export function useTauriEvents() {
         bp.isEnabled && !completedSplitNames.has(bp.name)
       );
       if (!hasRemaining) {
-        // Disable fast polling
-        invoke('set_log_poll_fast', { enabled: false }).catch(() => {});
-
-        // Complete run in database with the accurate split time
-        try {
-          await invoke('complete_run', {
-            runId: currentRun.id,
-            totalTimeMs: splitTimeMs,
-          });
-        } catch (error) {
-          console.error('[useTauriEvents] Failed to auto-complete run:', error);
-        }
-
-        // Update local state: sync elapsed time then end the run
-        useRunStore.getState().updateElapsed(splitTimeMs);
-        useRunStore.getState().endRun();
-
-        // Reload PB/gold splits so next run shows updated comparisons
-        useRunStore.getState().loadPbAndGoldSplits();
+        await completeRun(currentRun.id, splitTimeMs);
       }
     }
-  }, [addSplit, updatePollingSpeed]);
-
-  // Check if a zone matches the NEXT expected breakpoint (sequential matching)
-  const checkZoneBreakpoint = useCallback((zoneName: string) => {
-    const { breakpoints } = useSettingsStore.getState();
-    const { timer } = useRunStore.getState();
-    // Get list of already completed split names
-    const completedSplits = new Set(timer.splits.map(s => s.name));
-
-    // Find the next enabled zone breakpoint that hasn't been completed yet
-    for (const bp of breakpoints) {
-      if (!bp.isEnabled) continue;
-      if (bp.trigger.type !== 'zone') continue;
-      if (completedSplits.has(bp.name)) continue; // Skip already completed
-
-      // This is the next expected breakpoint - check if it matches
-      if (bp.trigger.zoneName?.toLowerCase() === zoneName.toLowerCase()) {
-        triggerSplit(bp.name, bp.type);
-        return;
-      } else {
-        // The next expected breakpoint doesn't match this zone, so don't trigger anything
-        // This prevents skipping ahead (e.g., triggering Act 6 when still in Act 1)
-        return;
-      }
-    }
-  }, [triggerSplit]);
+  }, [addSplit, completeRun]);
 
-  // Check if a level matches any enabled breakpoint
-  const checkLevelBreakpoint = useCallback((level: number) => {
-    const { breakpoints } = useSettingsStore.getState();
-
-    for (const bp of breakpoints) {
-      if (!bp.isEnabled) continue;
-      if (bp.trigger.type !== 'level') continue;
-
-      if (bp.trigger.level === level) {
-        triggerSplit(bp.name, bp.type);
-        return;
-      }
+  // Mirror a split the backend breakpoint engine recorded from the log
+  const handleSplitRecorded = useCallback(async (payload: SplitRecordedPayload) => {
+    const { currentRun } = useRunStore.getState();
+    if (currentRun?.id !== payload.run_id) {
+      return;
     }
-  }, [triggerSplit]);
 
-  // Check if a Kitava affliction matches the next expected kitava breakpoint (sequential matching)
-  const checkKitavaBreakpoint = useCallback((penalty: number) => {
-    const { breakpoints } = useSettingsStore.getState();
-    const { timer } = useRunStore.getState();
-    const completedSplits = new Set(timer.splits.map(s => s.name));
+    if (import.meta.env.DEV) {
+      console.log('[useTauriEvents] Split recorded:', payload.breakpoint_name, 'at', payload.split_time_ms, 'ms');
+    }
 
-    for (const bp of breakpoints) {
-      if (!bp.isEnabled) continue;
-      if (bp.trigger.type !== 'kitava') continue;
-      if (completedSplits.has(bp.name)) continue;
+    addSplit({
+      breakpointType: payload.breakpoint_type as 'zone' | 'level' | 'boss' | 'act' | 'lab' | 'custom',
+      breakpointName: payload.breakpoint_name,
+      breakpointKey: payload.breakpoint_key,
+      splitTimeMs: payload.split_time_ms,
+      segmentTimeMs: payload.segment_time_ms,
+      deltaMs: null,
+      townTimeMs: payload.town_time_ms,
+      hideoutTimeMs: payload.hideout_time_ms,
+    }, payload.comparison);
+    useRunStore.getState().loadComparison();
 
-      if (bp.trigger.penalty === penalty) {
-        triggerSplit(bp.name, bp.type);
-        return;
-      } else {
-        return;
-      }
+    if (payload.is_last) {
+      await completeRun(payload.run_id, payload.split_time_ms);
     }
-  }, [triggerSplit]);
+  }, [addSplit, completeRun]);
 
   // Handle log events
   const handleLogEvent = useCallback((payload: LogEventPayload) => {
@@ -249,8 +278,6 @@ export function useTauriEvents() {
           const isTown = isTownZone(payload.zone_name);
           const isHideout = isHideoutZone(payload.zone_name);
           enterZone(payload.zone_name, isTown, isHideout);
-
-          checkZoneBreakpoint(payload.zone_name);
         }
         break;
 
@@ -265,15 +292,13 @@ export function useTauriEvents() {
             const shouldUpdate = !currentChar || currentChar === 'Unknown' || currentChar === testCharacterName;
             if (currentRun && shouldUpdate) {
               const newCharName = payload.character_name;
-              const newClass = payload.character_class || currentRun.class;
 
-              // Update local state
+              // Update local state; the class follows via run-class-updated
               useRunStore.setState({
                 currentRun: {
                   ...currentRun,
                   character: newCharName,
                   characterName: newCharName,
-                  class: newClass,
                 },
               });
 
@@ -282,26 +307,24 @@ export function useTauriEvents() {
                 invoke('update_run_character', {
                   runId: currentRun.id,
                   characterName: newCharName,
-                  class: newClass,
                 }).catch((err) => {
                   console.error('[useTauriEvents] Failed to update run character in database:', err);
                 });
               }
             }
           }
-
-          checkLevelBreakpoint(payload.level);
         }
         break;
 
-      case 'kitava_affliction':
-        if (payload.penalty) {
-          checkKitavaBreakpoint(payload.penalty);
+      case 'game_version':
+        // A new patch means a different PB set when PBs are separated by patch
+        if (useSettingsStore.getState().separatePbsByPatch) {
+          useRunStore.getState().loadPbAndGoldSplits();
         }
         break;
 
       case 'death':
-        // Could track deaths in run stats
+        // Stored by the backend for the run's character (death-recorded)
         break;
 
       case 'login':
@@ -310,7 +333,7 @@ export function useTauriEvents() {
       default:
         break;
     }
-  }, [checkZoneBreakpoint, checkLevelBreakpoint, checkKitavaBreakpoint]);
+  }, []);
 
   useEffect(() => {
     // Listen for log events from the Rust backend
@@ -318,6 +341,16 @@ export function useTauriEvents() {
       handleLogEvent(event.payload);
     });
 
+    // Client.txt was truncated or replaced; the watcher restarted from its top
+    const unlistenLogRotated = listen<LogRotation>('log-rotated', (event) => {
+      useSettingsStore.getState().setLogRotation(event.payload);
+    });
+
+    // The watcher sped up or slowed its polling of Client.txt
+    const unlistenPollMode = listen<PollMode>('poll-mode-changed', (event) => {
+      useSettingsStore.getState().setPollMode(event.payload);
+    });
+
     // Listen for settings loaded from backend
     const unlistenSettings = listen<SettingsPayload>('settings-loaded', (event) => {
       loadSettings(event.payload.settings);
@@ -328,9 +361,83 @@ export function useTauriEvents() {
       triggerSplit(event.payload.name, event.payload.type);
     });
 
+    // Splits the backend breakpoint engine recorded from log events
+    const unlistenSplitRecorded = listen<SplitRecordedPayload>('split-recorded', (event) => {
+      handleSplitRecorded(event.payload);
+    });
+
+    // The backend corrected the run's class from a level-up line
+    const unlistenRunClass = listen<RunClassUpdatedPayload>('run-class-updated', (event) => {
+      const { currentRun } = useRunStore.getState();
+      if (currentRun && currentRun.id === event.payload.run_id) {
+        useRunStore.setState({
+          currentRun: { ...currentRun, class: event.payload.class, ascendancy: event.payload.ascendancy },
+        });
+      }
+    });
+
+    // Another character leveled up; the timer notice asks whether to pause or switch
+    const unlistenMismatch = listen<CharacterMismatch>('character-mismatch', (event) => {
+      const { currentRun, setCharacterMismatch } = useRunStore.getState();
+      if (currentRun && currentRun.id === event.payload.run_id) {
+        setCharacterMismatch(event.payload);
+      }
+    });
+
+    // The backend relabelled the run from its first log events (new character, map, lab)
+    const unlistenRunCategory = listen<RunCategoryUpdatedPayload>('run-category-updated', (event) => {
+      const { currentRun } = useRunStore.getState();
+      if (currentRun && currentRun.id === event.payload.run_id) {
+        useRunStore.setState({
+          currentRun: { ...currentRun, category: event.payload.category, categorySource: 'detected' },
+        });
+        useRunStore.getState().loadPbAndGoldSplits();
+        useRunStore.getState().loadComparison();
+      }
+    });
+
+    // The backend stored a death of the run's character
+    const unlistenDeath = listen<DeathRecordedPayload>('death-recorded', (event) => {
+      const { currentRun, timer } = useRunStore.getState();
+      if (currentRun && currentRun.id === event.payload.run_id) {
+        useRunStore.setState({ timer: { ...timer, deaths: event.payload.deaths } });
+      }
+    });
+
+    // The backend paused or resumed the timer (pause_run/resume_run, AFK or disconnect lines)
+    const onPauseChanged = (event: { payload: PauseChangedPayload }) => {
+      const { currentRun, applyPauseChange } = useRunStore.getState();
+      if (currentRun && currentRun.id === event.payload.run_id) {
+        applyPauseChange(event.payload.paused, event.payload.elapsed_ms);
+      }
+    };
+    const unlistenPaused = listen<PauseChangedPayload>('run-paused', onPauseChanged);
+    const unlistenResumed = listen<PauseChangedPayload>('run-resumed', onPauseChanged);
+
+    // The main window came back from hidden (background mode, tray). Its
+    // throttled timers may lag and events it missed leave splits behind, so
+    // take the backend's run clock, splits and skipped breakpoints
+    const unlistenRestored = listen<RunResyncPayload | null>('main-window-restored', (event) => {
+      const { currentRun, resyncSplits } = useRunStore.getState();
+      if (!event.payload || currentRun?.id !== event.payload.clock.run_id) return;
+      onPauseChanged({ payload: event.payload.clock });
+      resyncSplits(event.payload.splits, event.payload.skipped_splits);
+    });
+
+    // A new character entered the starting zone with auto-start on
+    const unlistenAutoStart = listen<RunAutoStartedPayload>('run-auto-started', (event) => {
+      const { timer, adoptRun } = useRunStore.getState();
+      if (timer.isRunning) {
+        console.warn('[useTauriEvents] Ignoring auto-started run while the timer is running');
+        return;
+      }
+      console.log('[useTauriEvents] Run auto-started in', event.payload.zone_name);
+      adoptRun(event.payload.run);
+    });
+
     // Listen for snapshot events
     const unlistenSnapshotCapturing = listen<SnapshotCapturingPayload>('snapshot-capturing', (event) => {
-      addPendingCapture(event.payload.split_id);
+      if (event.payload.split_id !== null) addPendingCapture(event.payload.split_id);
     });
 
     const unlistenSnapshotComplete = listen<SnapshotCompletePayload>('snapshot-complete', async (event) => {
@@ -339,6 +446,10 @@ export function useTauriEvents() {
         const snapshot = await invoke<Snapshot | null>('get_snapshot', {
           snapshotId: event.payload.snapshot_id,
         });
+        if (snapshot?.isFinal && !snapshot.pobCode) {
+          // Store a PoB code with the final build so it's shareable straight from history
+          snapshot.pobCode = await invoke<string>('generate_pob_code', { snapshotId: snapshot.id });
+        }
         if (snapshot) {
           addSnapshot(snapshot);
         }
@@ -349,17 +460,52 @@ export function useTauriEvents() {
 
     const unlistenSnapshotFailed = listen<SnapshotFailedPayload>('snapshot-failed', (event) => {
       console.error('[useTauriEvents] Snapshot failed for split', event.payload.split_id, '- Error:', event.payload.error);
-      addFailedCapture(event.payload.split_id, event.payload.error);
+      if (event.payload.split_id !== null) addFailedCapture(event.payload.split_id, event.payload.error);
+    });
+
+    // A newer split's capture replaced this one while it waited on the rate limiter
+    const unlistenSnapshotSkipped = listen<SnapshotSkippedPayload>('snapshot-skipped', (event) => {
+      if (event.payload.split_id !== null) {
+        addFailedCapture(event.payload.split_id, 'Skipped for a newer split while rate limited');
+      }
+    });
+
+    // The capture's run was deleted
+    const unlistenSnapshotCancelled = listen<SnapshotCancelledPayload>('snapshot-cancelled', (event) => {
+      if (event.payload.split_id !== null) removePendingCapture(event.payload.split_id);
+    });
+
+    const unlistenSnapshotQueue = listen<SnapshotQueuePayload>('snapshot-queue', (event) => {
+      setCaptureQueue(event.payload.queued);
+    });
+
+    const unlistenAnnouncement = listen<SplitAnnouncement>('split-announcement', (event) => {
+      announceSplit(event.payload);
     });
 
     // Cleanup listeners on unmount
     return () => {
       unlistenLogEvent.then((fn) => fn());
+      unlistenLogRotated.then((fn) => fn());
+      unlistenMismatch.then((fn) => fn());
+      unlistenPollMode.then((fn) => fn());
       unlistenSettings.then((fn) => fn());
       unlistenSplit.then((fn) => fn());
+      unlistenSplitRecorded.then((fn) => fn());
+      unlistenRunClass.then((fn) => fn());
+      unlistenRunCategory.then((fn) => fn());
+      unlistenDeath.then((fn) => fn());
+      unlistenPaused.then((fn) => fn());
+      unlistenResumed.then((fn) => fn());
+      unlistenRestored.then((fn) => fn());
+      unlistenAutoStart.then((fn) => fn());
       unlistenSnapshotCapturing.then((fn) => fn());
       unlistenSnapshotComplete.then((fn) => fn());
       unlistenSnapshotFailed.then((fn) => fn());
+      unlistenSnapshotSkipped.then((fn) => fn());
+      unlistenSnapshotCancelled.then((fn) => fn());
+      unlistenSnapshotQueue.then((fn) => fn());
+      unlistenAnnouncement.then((fn) => fn());
     };
-  }, [handleLogEvent, loadSettings, triggerSplit, addPendingCapture, addSnapshot, addFailedCapture]);
+  }, [handleLogEvent, loadSettings, triggerSplit, handleSplitRecorded, addPendingCapture, removePendingCapture, addSnapshot, addFailedCapture, setCaptureQueue]);
 }
@@ -1,6 +1,9 @@
-import { useState, useEffect, useCallback, useRef } from 'react';
-import { check, type Update } from '@tauri-apps/plugin-updater';
-import { relaunch } from '@tauri-apps/plugin-process';
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useSettingsStore } from '../stores/settingsStore';
+import type { UpdateInfo } from '../types';
+import { getErrorMessage } from '../utils/errors';
 
 interface UpdateState {
   available: boolean;
@@ -11,7 +14,14 @@ interface UpdateState {
   error: string | null;
 }
 
+// Payload of the backend's update-progress event (see src-tauri/src/updater.rs)
+interface UpdateProgressPayload {
+  downloaded: number;
+  content_length: number | null;
+}
+
 export function useUpdateChecker(autoCheck: boolean) {
+  const updateChannel = useSettingsStore((s) => s.updateChannel);
   const [state, setState] = useState<UpdateState>({
     available: false,
     version: null,
@@ -20,60 +30,48 @@ export function useUpdateChecker(autoCheck: boolean) {
     progress: 0,
     error: null,
   });
-  const updateRef = useRef<Update | null>(null);
 
   const checkForUpdate = useCallback(async () => {
     setState((s) => ({ ...s, checking: true, error: null }));
     try {
-      const update = await check();
-      if (update) {
-        updateRef.current = update;
-        setState((s) => ({
-          ...s,
-          available: true,
-          version: update.version,
-          checking: false,
-        }));
-      } else {
-        setState((s) => ({ ...s, checking: false }));
-      }
+      const update = await invoke<UpdateInfo | null>('check_for_update', { channel: updateChannel });
+      setState((s) => ({
+        ...s,
+        available: update !== null,
+        version: update?.version ?? null,
+        checking: false,
+      }));
     } catch (e) {
       setState((s) => ({
         ...s,
         checking: false,
-        error: e instanceof Error ? e.message : String(e),
+        error: getErrorMessage(e),
       }));
     }
-  }, []);
+  }, [updateChannel]);
 
   const downloadAndInstall = useCallback(async () => {
-    const update = updateRef.current;
-    if (!update) return;
-
     setState((s) => ({ ...s, downloading: true, progress: 0, error: null }));
+    const unlisten = await listen<UpdateProgressPayload>('update-progress', (event) => {
+      const { downloaded, content_length } = event.payload;
+      if (content_length) {
+        setState((s) => ({ ...s, progress: Math.round((downloaded / content_length) * 100) }));
+      }
+    });
     try {
-      let contentLength = 0;
-      let downloaded = 0;
-      await update.downloadAndInstall((event) => {
-        if (event.event === 'Started' && event.data.contentLength) {
-          contentLength = event.data.contentLength;
-        } else if (event.event === 'Progress') {
-          downloaded += event.data.chunkLength;
-          if (contentLength > 0) {
-            setState((s) => ({
-              ...s,
-              progress: Math.round((downloaded / contentLength) * 100),
-            }));
-          }
-        }
-      });
-      await relaunch();
+      // Restarts the app once installed; resolves false when already up to date
+      const installed = await invoke<boolean>('install_update_now');
+      if (!installed) {
+        setState((s) => ({ ...s, downloading: false, available: false }));
+      }
     } catch (e) {
       setState((s) => ({
         ...s,
         downloading: false,
-        error: e instanceof Error ? e.message : String(e),
+        error: getErrorMessage(e),
       }));
+    } finally {
+      unlisten();
     }
   }, []);
 
@@ -1,8 +1,9 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { Run, Split, SplitTime, TimerState, RunFilters, RunStats, SplitStat, PersonalBest, GoldSplit } from '../types';
+import type { AbandonReason, AbandonmentStats, CharacterMismatch, ResetStats, ComparisonKind, ComparisonRow, Run, SkippedSplit, Split, SplitComparison, SplitTime, TimerState, RunFilters, RunStats, SplitStat, ZoneTimeStat, ZoneHeatmapEntry, PersonalBest, GoldSplit } from '../types';
 import { useSettingsStore } from './settingsStore';
 import { getWizardCategory } from '../config/wizardRoutes';
+import { getBreakpointKey } from '../config/breakpoints';
 
 interface RunState {
   // Current run
@@ -17,25 +18,52 @@ interface RunState {
   personalBests: Map<string, number>;
   goldSplits: Map<string, number>;
 
+  // The current run against the chosen comparison (get_comparison)
+  comparisonKind: ComparisonKind;
+  comparisonRows: ComparisonRow[];
+
+  // Another character leveled up during the run (character-mismatch)
+  characterMismatch: CharacterMismatch | null;
+
   // Filtering state
   filters: RunFilters;
   filteredRuns: Run[];
   runStats: RunStats | null;
+  abandonmentStats: AbandonmentStats | null;
+  resetStats: ResetStats | null;
   splitStats: SplitStat[];
+  zoneTimeStats: ZoneTimeStat[];
+  zoneHeatmap: ZoneHeatmapEntry[];
 
   // Actions
   startRun: (run: Omit<Run, 'id' | 'isCompleted' | 'isPersonalBest' | 'endedAt' | 'totalTimeMs'>) => void;
   endRun: () => void;
   resetRun: () => void;
-  addSplit: (split: Omit<Split, 'id' | 'runId'>) => void;
+  // Record why the unfinished run is being given up, then reset
+  abandonRun: (reason: AbandonReason | null) => Promise<void>;
+  // The comparison comes from the backend; splits shown before it answers stay neutral
+  addSplit: (split: Omit<Split, 'id' | 'runId'>, comparison?: SplitComparison) => void;
+  applySplitComparison: (name: string, comparison: SplitComparison) => void;
+  // Replace the splits and skips with the stored ones (main-window-restored)
+  resyncSplits: (splits: Split[], skippedSplits: SkippedSplit[]) => void;
+  // Undoes the last split, or the last skip if that came after it
+  undoLastSplit: () => Promise<void>;
+  skipSplit: (breakpointName: string) => Promise<void>;
 
   // Timer actions
   startTimer: () => void;
   stopTimer: () => void;
   pauseTimer: () => void;
+  // Follow a pause or resume the backend made (run-paused / run-resumed) at its run clock
+  applyPauseChange: (paused: boolean, elapsedMs: number) => void;
   updateElapsed: (ms: number) => void;
   enterZone: (zoneName: string, isTown: boolean, isHideout?: boolean) => void;
   setRunId: (id: number) => void;
+  // Take over a run the backend started (run-auto-started) and start timing it
+  adoptRun: (run: Run) => void;
+  setCharacterMismatch: (mismatch: CharacterMismatch | null) => void;
+  // Pause the run, or with rebind move it to the mismatched character
+  reassignRunCharacter: (rebind: boolean) => Promise<void>;
 
   // Data loading
   setRuns: (runs: Run[]) => void;
@@ -43,6 +71,8 @@ interface RunState {
   setPersonalBests: (pbs: Map<string, number>) => void;
   setGoldSplits: (golds: Map<string, number>) => void;
   loadPbAndGoldSplits: () => Promise<void>;
+  setComparisonKind: (kind: ComparisonKind) => void;
+  loadComparison: () => Promise<void>;
 
   // Filtering actions
   setFilters: (filters: Partial<RunFilters>) => void;
@@ -52,12 +82,29 @@ interface RunState {
   loadSplitStats: () => Promise<void>;
 }
 
+// Elapsed time right now, since timer.elapsedMs is only updated during UI renders
+const liveElapsedMs = (timer: TimerState) =>
+  timer.isRunning && timer.startTime ? Date.now() - timer.startTime : timer.elapsedMs;
+
+// Keep the backend breakpoint engine's clock in step with the timer
+const syncBreakpointEngine = (timer: TimerState) => {
+  invoke('set_breakpoint_engine_running', {
+    running: timer.isRunning,
+    elapsedMs: liveElapsedMs(timer),
+  }).catch((error) => console.error('[RunStore] Failed to sync breakpoint engine:', error));
+};
+
+const stopBreakpointEngine = () => {
+  invoke('stop_breakpoint_engine').catch(() => {});
+};
+
 const initialTimerState: TimerState = {
   isRunning: false,
   startTime: null,
   elapsedMs: 0,
   currentSplit: 0,
   splits: [],
+  skippedSplits: [],
   // Town/Hideout time tracking
   townTimeMs: 0,
   hideoutTimeMs: 0,
@@ -66,6 +113,7 @@ const initialTimerState: TimerState = {
   townEnteredAt: null,
   hideoutEnteredAt: null,
   currentZone: null,
+  deaths: 0,
 };
 
 export const useRunStore = create<RunState>((set, get) => ({
@@ -76,12 +124,19 @@ export const useRunStore = create<RunState>((set, get) => ({
   runs: [],
   personalBests: new Map(),
   goldSplits: new Map(),
+  comparisonKind: { kind: 'personal_best' },
+  comparisonRows: [],
+  characterMismatch: null,
 
   // Filtering state
   filters: {},
   filteredRuns: [],
   runStats: null,
+  abandonmentStats: null,
+  resetStats: null,
   splitStats: [],
+  zoneTimeStats: [],
+  zoneHeatmap: [],
 
   // Run actions
   startRun: (runData) => {
@@ -115,6 +170,7 @@ export const useRunStore = create<RunState>((set, get) => ({
       totalTimeMs: timer.elapsedMs,
     };
 
+    stopBreakpointEngine();
     set((state) => ({
       currentRun: endedRun,
       runs: [...state.runs, endedRun],
@@ -123,22 +179,41 @@ export const useRunStore = create<RunState>((set, get) => ({
   },
 
   resetRun: () => {
+    // Golds set just before resetting an unfinished run are treated as mis-splits
+    const { currentRun, timer } = get();
+    if (currentRun && !currentRun.isCompleted && timer.splits.length > 0) {
+      invoke('revert_provisional_golds', { runId: currentRun.id })
+        .then(() => get().loadPbAndGoldSplits())
+        .catch((error) => console.error('[RunStore] Failed to revert provisional golds:', error));
+    }
+    stopBreakpointEngine();
     set({
       currentRun: null,
       splits: [],
       timer: initialTimerState,
+      comparisonRows: [],
+      characterMismatch: null,
     });
   },
 
-  addSplit: (splitData) => {
-    const { currentRun, goldSplits, personalBests } = get();
-    if (!currentRun) return;
+  abandonRun: async (reason) => {
+    const { currentRun, timer } = get();
+    if (currentRun?.id && !currentRun.isCompleted) {
+      const elapsedMs = timer.isRunning && timer.startTime ? Date.now() - timer.startTime : timer.elapsedMs;
+      try {
+        await invoke('abandon_run', { runId: currentRun.id, reason, abandonedAtMs: elapsedMs });
+      } catch (error) {
+        console.error('[RunStore] Failed to record the reset:', error);
+      }
+    }
+    get().resetRun();
+  },
 
-    const pbTime = personalBests.get(`${currentRun.category}-${currentRun.class}-${splitData.breakpointName}`);
-    const goldTime = goldSplits.get(`${currentRun.category}-${currentRun.class}-${splitData.breakpointName}`);
+  addSplit: (splitData, comparison) => {
+    const { currentRun } = get();
+    if (!currentRun) return;
 
-    const deltaMs = pbTime ? splitData.splitTimeMs - pbTime : null;
-    const isBestSegment = goldTime ? splitData.segmentTimeMs < goldTime : true;
+    const deltaMs = comparison?.deltaMs ?? null;
 
     const split: Split = {
       ...splitData,
@@ -152,7 +227,8 @@ export const useRunStore = create<RunState>((set, get) => ({
       splitTimeMs: split.splitTimeMs,
       segmentTimeMs: split.segmentTimeMs,
       deltaMs,
-      isBestSegment,
+      isBestSegment: comparison?.isGold ?? false,
+      color: comparison?.color ?? 'neutral',
     };
 
     set((state) => ({
@@ -165,11 +241,105 @@ export const useRunStore = create<RunState>((set, get) => ({
     }));
   },
 
+  applySplitComparison: (name, comparison) => {
+    set((state) => ({
+      splits: state.splits.map((s) => (s.breakpointName === name ? { ...s, deltaMs: comparison.deltaMs } : s)),
+      timer: {
+        ...state.timer,
+        splits: state.timer.splits.map((s) =>
+          s.name === name
+            ? { ...s, deltaMs: comparison.deltaMs, isBestSegment: comparison.isGold, color: comparison.color }
+            : s
+        ),
+      },
+    }));
+    get().loadComparison();
+  },
+
+  resyncSplits: (splits, skippedSplits) => {
+    set((state) => {
+      // Splits already shown keep their comparison colors
+      const shown = new Map(state.timer.splits.map((s) => [s.name, s]));
+      return {
+        splits,
+        timer: {
+          ...state.timer,
+          currentSplit: splits.length + skippedSplits.length,
+          splits: splits.map((split): SplitTime => {
+            const existing = shown.get(split.breakpointName);
+            return existing && existing.splitTimeMs === split.splitTimeMs
+              ? existing
+              : {
+                  name: split.breakpointName,
+                  splitTimeMs: split.splitTimeMs,
+                  segmentTimeMs: split.segmentTimeMs,
+                  deltaMs: split.deltaMs,
+                  isBestSegment: false,
+                  color: 'neutral',
+                };
+          }),
+          skippedSplits,
+        },
+      };
+    });
+    get().loadComparison();
+  },
+
+  undoLastSplit: async () => {
+    const { currentRun, timer } = get();
+    if (!currentRun || (timer.splits.length === 0 && timer.skippedSplits.length === 0)) return;
+
+    // Same rule as the backend: a skip taken at or after the last split goes first
+    const lastSplit = timer.splits[timer.splits.length - 1];
+    const lastSkipped = timer.skippedSplits[timer.skippedSplits.length - 1];
+    const undoSkip = lastSkipped && (!lastSplit || lastSkipped.elapsedMs >= lastSplit.splitTimeMs);
+
+    set((state) => ({
+      splits: undoSkip ? state.splits : state.splits.slice(0, -1),
+      timer: {
+        ...state.timer,
+        currentSplit: Math.max(0, state.timer.currentSplit - 1),
+        splits: undoSkip ? state.timer.splits : state.timer.splits.slice(0, -1),
+        skippedSplits: undoSkip ? state.timer.skippedSplits.slice(0, -1) : state.timer.skippedSplits,
+      },
+    }));
+
+    try {
+      // Also reverts golds the split set within the protection window
+      await invoke<number>('undo_last_split', { runId: currentRun.id });
+      await get().loadPbAndGoldSplits();
+      await get().loadComparison();
+    } catch (error) {
+      console.error('[RunStore] Failed to undo split:', error);
+    }
+  },
+
+  skipSplit: async (breakpointName) => {
+    const { currentRun } = get();
+    if (!currentRun) return;
+
+    try {
+      const skipped = await invoke<SkippedSplit>('skip_split', { runId: currentRun.id, breakpointName });
+      set((state) => ({
+        timer: {
+          ...state.timer,
+          currentSplit: state.timer.currentSplit + 1,
+          skippedSplits: [...state.timer.skippedSplits, skipped],
+        },
+      }));
+    } catch (error) {
+      console.error('[RunStore] Failed to skip split:', error);
+    }
+  },
+
   // Timer actions
   startTimer: () => {
     const { currentRun } = get();
     // Get test character name and wizard config from settings store
-    const { testCharacterName, wizardConfig } = useSettingsStore.getState();
+    const { testCharacterName, wizardConfig, timerStartOffsetMs } = useSettingsStore.getState();
+    // A fresh start counts up from minus the configured countdown
+    const startAt = (elapsedMs: number) =>
+      Date.now() - elapsedMs + (elapsedMs === 0 ? timerStartOffsetMs : 0);
 
     // Create a default run if none exists
     if (!currentRun) {
@@ -192,7 +362,7 @@ export const useRunStore = create<RunState>((set, get) => ({
         timer: {
           ...state.timer,
           isRunning: true,
-          startTime: Date.now() - state.timer.elapsedMs,
+          startTime: startAt(state.timer.elapsedMs),
           splits: [],
         },
       }));
@@ -201,9 +371,10 @@ export const useRunStore = create<RunState>((set, get) => ({
         timer: {
           ...state.timer,
           isRunning: true,
-          startTime: Date.now() - state.timer.elapsedMs,
+          startTime: startAt(state.timer.elapsedMs),
         },
       }));
+      syncBreakpointEngine(get().timer);
     }
   },
 
@@ -211,18 +382,56 @@ export const useRunStore = create<RunState>((set, get) => ({
     set((state) => ({
       timer: {
         ...state.timer,
+        elapsedMs: liveElapsedMs(state.timer),
         isRunning: false,
       },
     }));
+    syncBreakpointEngine(get().timer);
   },
 
   pauseTimer: () => {
     set((state) => ({
       timer: {
         ...state.timer,
+        elapsedMs: liveElapsedMs(state.timer),
         isRunning: false,
       },
     }));
+    syncBreakpointEngine(get().timer);
+  },
+
+  applyPauseChange: (paused, elapsedMs) => {
+    set((state) => ({
+      timer: {
+        ...state.timer,
+        elapsedMs,
+        isRunning: !paused,
+        startTime: paused ? state.timer.startTime : Date.now() - elapsedMs,
+      },
+    }));
+  },
+
+  setCharacterMismatch: (mismatch) => set({ characterMismatch: mismatch }),
+
+  reassignRunCharacter: async (rebind) => {
+    const { currentRun } = get();
+    if (!currentRun) return;
+    try {
+      // Pausing comes back through run-paused
+      const run = await invoke<Run>('reassign_run_character', { runId: currentRun.id, rebind });
+      set((state) => ({
+        characterMismatch: null,
+        currentRun: state.currentRun && {
+          ...state.currentRun,
+          characterName: run.characterName,
+          class: run.class,
+          ascendancy: run.ascendancy,
+        },
+      }));
+    } catch (error) {
+      console.error('[RunStore] Failed to reassign run character:', error);
+      set({ characterMismatch: null });
+    }
   },
 
   updateElapsed: (ms) => {
@@ -268,6 +477,41 @@ export const useRunStore = create<RunState>((set, get) => ({
     set((state) => ({
       currentRun: state.currentRun ? { ...state.currentRun, id } : null,
     }));
+
+    // Hand log-triggered splits for this run to the backend breakpoint engine
+    const { timer } = get();
+    const { breakpoints, customPreset, testCharacterName } = useSettingsStore.getState();
+    invoke('start_breakpoint_engine', {
+      runId: id,
+      breakpoints: breakpoints
+        .filter((bp) => bp.isEnabled)
+        .map((bp) => ({ ...bp, key: getBreakpointKey(bp.trigger) })),
+      // A custom preset's breakpoints are loaded by the backend
+      presetId: customPreset?.id ?? null,
+      elapsedMs: liveElapsedMs(timer),
+      fallbackCharacterName: testCharacterName || null,
+    })
+      .then(() => {
+        if (!get().timer.isRunning) syncBreakpointEngine(get().timer);
+      })
+      .catch((error) => console.error('[RunStore] Failed to start breakpoint engine:', error));
+    get().loadComparison();
+  },
+
+  adoptRun: (run) => {
+    const { timerStartOffsetMs } = useSettingsStore.getState();
+    set((state) => ({
+      currentRun: run,
+      splits: [],
+      comparisonRows: [],
+      timer: {
+        ...initialTimerState,
+        currentZone: state.timer.currentZone,
+        isRunning: true,
+        startTime: Date.now() + timerStartOffsetMs,
+      },
+    }));
+    get().setRunId(run.id);
   },
 
   // Data loading
@@ -309,7 +553,8 @@ export const useRunStore = create<RunState>((set, get) => ({
       const golds = await invoke<GoldSplit[]>('get_gold_splits');
       const goldMap = new Map<string, number>();
       for (const gold of golds) {
-        const key = `${gold.category}-${gold.class}-${gold.breakpointName}`;
+        // Golds are matched by canonical key when known, so renamed splits still compare
+        const key = `${gold.category}-${gold.class}-${gold.breakpointKey ?? gold.breakpointName}`;
         goldMap.set(key, gold.bestSegmentMs);
       }
       set({ goldSplits: goldMap });
@@ -318,6 +563,28 @@ export const useRunStore = create<RunState>((set, get) => ({
     }
   },
 
+  setComparisonKind: (kind) => {
+    set({ comparisonKind: kind });
+    get().loadComparison();
+  },
+
+  loadComparison: async () => {
+    const { currentRun, comparisonKind } = get();
+    if (!currentRun?.id) {
+      set({ comparisonRows: [] });
+      return;
+    }
+    try {
+      const rows = await invoke<ComparisonRow[]>('get_comparison', { runId: currentRun.id, comparisonKind });
+      // Drop answers for a run or comparison that changed while loading
+      if (get().currentRun?.id === currentRun.id && get().comparisonKind === comparisonKind) {
+        set({ comparisonRows: rows });
+      }
+    } catch (error) {
+      console.error('[RunStore] Failed to load comparison:', error);
+    }
+  },
+
   // Filtering actions
   setFilters: (newFilters) => set((state) => ({
     filters: { ...state.filters, ...newFilters },
@@ -327,7 +594,11 @@ export const useRunStore = create<RunState>((set, get) => ({
     filters: {},
     filteredRuns: [],
     runStats: null,
+    abandonmentStats: null,
+    resetStats: null,
     splitStats: [],
+    zoneTimeStats: [],
+    zoneHeatmap: [],
   }),
 
   loadFilteredRuns: async () => {
@@ -343,8 +614,12 @@ export const useRunStore = create<RunState>((set, get) => ({
   loadRunStats: async () => {
     try {
       const { filters } = get();
-      const stats = await invoke<RunStats>('get_run_stats', { filters });
-      set({ runStats: stats });
+      const [stats, abandonmentStats, resetStats] = await Promise.all([
+        invoke<RunStats>('get_run_stats', { filters }),
+        invoke<AbandonmentStats>('get_abandonment_stats', { filters }),
+        invoke<ResetStats>('get_reset_stats', { filters }),
+      ]);
+      set({ runStats: stats, abandonmentStats, resetStats });
     } catch (error) {
       console.error('[RunStore] Failed to load run stats:', error);
     }
@@ -353,8 +628,12 @@ export const useRunStore = create<RunState>((set, get) => ({
   loadSplitStats: async () => {
     try {
       const { filters } = get();
-      const stats = await invoke<SplitStat[]>('get_split_stats', { filters });
-      set({ splitStats: stats });
+      const [stats, zoneTimeStats, zoneHeatmap] = await Promise.all([
+        invoke<SplitStat[]>('get_split_stats', { filters }),
+        invoke<ZoneTimeStat[]>('get_zone_time_stats', { filters }),
+        invoke<ZoneHeatmapEntry[]>('get_zone_heatmap', { filters }),
+      ]);
+      set({ splitStats: stats, zoneTimeStats, zoneHeatmap });
     } catch (error) {
       console.error('[RunStore] Failed to load split stats:', error);
     }
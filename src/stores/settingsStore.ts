@@ -1,22 +1,52 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { Breakpoint, Settings, ViewMode, WizardConfig, HotkeySettings } from '../types';
+import type {
+  Breakpoint,
+  BreakpointPreset,
+  CategoryDefaults,
+  Game,
+  Settings,
+  UpdateChannel,
+  SnapshotRetention,
+  ViewMode,
+  WizardConfig,
+  HotkeySettings,
+  LogRotation,
+  PollMode,
+  PresetBreakpoint,
+  ProfilePrivacy,
+  ReadinessMode,
+  RunReadiness,
+  TriggerRule,
+} from '../types';
 import { DEFAULT_HOTKEYS } from '../types';
 import {
   defaultBreakpoints,
   applySpeedrunPreset,
   applyMinimalPreset,
   applyTownsOnlyPreset,
+  campaignStartZone,
   resetToDefault,
-  speedrunEnabledBreakpoints,
+  speedrunBreakpointsFor,
 } from '../config/breakpoints';
-import { generateBreakpoints } from '../config/wizardRoutes';
+import { generateBreakpoints, getWizardCategory } from '../config/wizardRoutes';
+import { getErrorMessage } from '../utils/errors';
+
+const CUSTOM_PRESET_STORAGE_KEY = 'poe-watcher-breakpoint-preset';
 
 interface SettingsState extends Settings {
   // UI state
   currentView: ViewMode;
   // Runtime-only state (not persisted)
   overlayOpen: boolean;
+  profilePrivacy: ProfilePrivacy | null;
+  profilePrivacyError: string | null;
+  runReadiness: RunReadiness | null;
+  logRotation: LogRotation | null;
+  pollMode: PollMode | null;
+  // Breakpoint preset from the database that runs use instead of `breakpoints` (id kept in localStorage)
+  customPreset: BreakpointPreset | null;
+  customPresetBreakpoints: PresetBreakpoint[];
   // Hotkey settings
   hotkeys: HotkeySettings;
   // Actions
@@ -30,6 +60,7 @@ interface SettingsState extends Settings {
   setBreakpoints: (breakpoints: Breakpoint[]) => void;
   toggleBreakpoint: (name: string) => void;
   toggleSnapshotCapture: (name: string) => void;
+  setBreakpointRule: (name: string, rule: TriggerRule | undefined) => void;
   setCurrentView: (view: ViewMode) => void;
   loadSettings: (settings: Partial<Settings>) => void;
   // Breakpoint management
@@ -41,12 +72,25 @@ interface SettingsState extends Settings {
   applyMinimalPreset: () => void;
   applyTownsOnlyPreset: () => void;
   resetBreakpoints: () => void;
+  setCustomPreset: (preset: BreakpointPreset | null, breakpoints?: PresetBreakpoint[]) => void;
   // Wizard
   setWizardConfig: (config: WizardConfig) => void;
   clearWizardConfig: () => void;
   // Preset detection helpers
   getCurrentPresetName: () => string;
   getEnabledBreakpointNames: () => string[];
+  // Per-category defaults
+  saveCategoryDefaults: (category: string) => Promise<void>;
+  applyCategoryDefaults: (category: string) => Promise<void>;
+  // Account privacy
+  checkProfilePrivacy: () => Promise<ProfilePrivacy | null>;
+  // Pre-run checklist
+  checkRunReadiness: () => Promise<RunReadiness | null>;
+  clearRunReadiness: () => void;
+  // Client.txt rotation notice
+  setLogRotation: (rotation: LogRotation | null) => void;
+  // Log watcher poll interval
+  setPollMode: (mode: PollMode) => void;
   // Overlay config
   setOverlayScale: (scale: 'small' | 'medium' | 'large') => void;
   setOverlayFontSize: (size: 'small' | 'medium' | 'large') => void;
@@ -59,6 +103,53 @@ interface SettingsState extends Settings {
   setOverlayAccentColor: (color: string) => void;
   setOverlayAlwaysOnTop: (enabled: boolean) => void;
   setOverlayLocked: (locked: boolean) => void;
+  // Warm-up detection
+  setWarmupDetectionEnabled: (enabled: boolean) => void;
+  setSessionGapMinutes: (minutes: number) => void;
+  // Startup
+  setLaunchOnBoot: (enabled: boolean) => void;
+  setStartMinimized: (enabled: boolean) => void;
+  setMinimizeToTray: (enabled: boolean) => void;
+  setBackgroundMode: (enabled: boolean) => void;
+  setFollowGameProcess: (enabled: boolean) => void;
+  setSeparatePbsByPatch: (enabled: boolean) => void;
+  // Split announcements
+  setAnnounceSound: (enabled: boolean) => void;
+  setAnnounceSpeech: (enabled: boolean) => void;
+  setAnnounceGoldsOnly: (enabled: boolean) => void;
+  setAnnounceMinBehindMs: (ms: number) => void;
+  setAnnounceMinAheadMs: (ms: number) => void;
+  setGoldProtectionSecs: (secs: number) => void;
+  setTimerStartOffsetMs: (ms: number) => void;
+  setRecordApiResponses: (enabled: boolean) => void;
+  setApiRecordingLimit: (limit: number) => void;
+  setLocale: (locale: string) => void;
+  setReadinessMode: (mode: ReadinessMode) => void;
+  setReadinessLogWatcher: (enabled: boolean) => void;
+  setReadinessAccount: (enabled: boolean) => void;
+  setReadinessOverlay: (enabled: boolean) => void;
+  setReadinessPreset: (enabled: boolean) => void;
+  setReadinessProfilePublic: (enabled: boolean) => void;
+  setAutoStartRuns: (enabled: boolean) => void;
+  setAutoStartZone: (zone: string) => void;
+  setAutoPause: (enabled: boolean) => void;
+  setAutoExportDir: (dir: string) => void;
+  setAutoExportFormats: (formats: string) => void;
+  setAutoExportPbsOnly: (enabled: boolean) => void;
+  setWebhookUrl: (url: string) => void;
+  setWebhookSecret: (secret: string) => void;
+  setTwitchToken: (token: string) => void;
+  setTwitchUsername: (username: string) => void;
+  setTwitchChannel: (channel: string) => void;
+  setTwitchPbMessage: (message: string) => void;
+  setTwitchGoldMessage: (message: string) => void;
+  setTwitchRunMessage: (message: string) => void;
+  setUpdateChannel: (channel: UpdateChannel) => void;
+  setSnapshotRetention: (retention: SnapshotRetention) => void;
+  setSnapshotRetentionRuns: (runs: number) => void;
+  setSnapshotLevelInterval: (levels: number) => void;
+  setImageProxyHosts: (hosts: string) => void;
+  setGame: (game: Game) => void;
   setOverlayOpen: (open: boolean) => void;
   // Hotkey actions
   loadHotkeys: () => Promise<void>;
@@ -90,8 +181,71 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
   overlayAccentColor: 'transparent',
   overlayAlwaysOnTop: true,
   overlayLocked: false,
+  // Warm-up detection defaults
+  warmupDetectionEnabled: true,
+  sessionGapMinutes: 120,
+  // Startup defaults
+  launchOnBoot: false,
+  startMinimized: false,
+  minimizeToTray: false,
+  backgroundMode: false,
+  followGameProcess: false,
+  // PB defaults
+  separatePbsByPatch: false,
+  // Split announcement defaults
+  announceSound: false,
+  announceSpeech: false,
+  announceGoldsOnly: false,
+  announceMinBehindMs: 0,
+  announceMinAheadMs: 0,
+  // Gold protection default
+  goldProtectionSecs: 10,
+  // Timer start countdown default
+  timerStartOffsetMs: 0,
+  // API response recording defaults
+  recordApiResponses: false,
+  apiRecordingLimit: 10,
+  locale: 'en-US',
+  // Pre-run checklist defaults
+  readinessMode: 'warn',
+  readinessLogWatcher: true,
+  readinessAccount: true,
+  readinessOverlay: false,
+  readinessPreset: true,
+  readinessProfilePublic: true,
+  // Auto-start defaults
+  autoStartRuns: false,
+  autoStartZone: 'The Twilight Strand',
+  autoPause: false,
+  // Auto-export defaults
+  autoExportDir: '',
+  autoExportFormats: 'json',
+  autoExportPbsOnly: false,
+  // Notification webhook defaults
+  webhookUrl: '',
+  webhookSecret: '',
+  // Twitch chat defaults
+  twitchToken: '',
+  twitchUsername: '',
+  twitchChannel: '',
+  twitchPbMessage: 'New {category} PB: {time}!',
+  twitchGoldMessage: 'New {split} gold: {splitTime}!',
+  twitchRunMessage: '',
+  updateChannel: 'stable',
+  snapshotRetention: 'all',
+  snapshotRetentionRuns: 20,
+  snapshotLevelInterval: 0,
+  imageProxyHosts: 'web.poecdn.com, www.pathofexile.com, pathofexile2.com, cdn.poe2db.tw',
+  game: 'poe1',
   // Runtime-only
   overlayOpen: false,
+  profilePrivacy: null,
+  profilePrivacyError: null,
+  runReadiness: null,
+  logRotation: null,
+  pollMode: null,
+  customPreset: null,
+  customPresetBreakpoints: [],
   // Hotkey settings
   hotkeys: { ...DEFAULT_HOTKEYS },
   // Actions
@@ -116,6 +270,12 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     ),
   })),
 
+  setBreakpointRule: (name, rule) => set((state) => ({
+    breakpoints: state.breakpoints.map((bp) =>
+      bp.name === name ? { ...bp, trigger: { ...bp.trigger, rule } } : bp
+    ),
+  })),
+
   setCurrentView: (view) => set({ currentView: view }),
 
   loadSettings: (settings) => set((state) => ({
@@ -166,7 +326,7 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
     } catch (e) {
       console.error('[Store] Failed to clear localStorage:', e);
     }
-    const defaults = resetToDefault();
+    const defaults = resetToDefault(get().game);
     return set({
       breakpoints: defaults,
     });
@@ -192,16 +352,80 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
   setOverlayAccentColor: (color) => set({ overlayAccentColor: color }),
   setOverlayAlwaysOnTop: (enabled) => set({ overlayAlwaysOnTop: enabled }),
   setOverlayLocked: (locked) => set({ overlayLocked: locked }),
+  setWarmupDetectionEnabled: (enabled) => set({ warmupDetectionEnabled: enabled }),
+  setSessionGapMinutes: (minutes) => set({ sessionGapMinutes: minutes }),
+  setLaunchOnBoot: (enabled) => set({ launchOnBoot: enabled }),
+  setStartMinimized: (enabled) => set({ startMinimized: enabled }),
+  setMinimizeToTray: (enabled) => set({ minimizeToTray: enabled }),
+  setBackgroundMode: (enabled) => set({ backgroundMode: enabled }),
+  setFollowGameProcess: (enabled) => set({ followGameProcess: enabled }),
+  setSeparatePbsByPatch: (enabled) => set({ separatePbsByPatch: enabled }),
+  setAnnounceSound: (enabled) => set({ announceSound: enabled }),
+  setAnnounceSpeech: (enabled) => set({ announceSpeech: enabled }),
+  setAnnounceGoldsOnly: (enabled) => set({ announceGoldsOnly: enabled }),
+  setAnnounceMinBehindMs: (ms) => set({ announceMinBehindMs: ms }),
+  setAnnounceMinAheadMs: (ms) => set({ announceMinAheadMs: ms }),
+  setGoldProtectionSecs: (secs) => set({ goldProtectionSecs: secs }),
+  setTimerStartOffsetMs: (ms) => set({ timerStartOffsetMs: ms }),
+  setRecordApiResponses: (enabled) => set({ recordApiResponses: enabled }),
+  setApiRecordingLimit: (limit) => set({ apiRecordingLimit: limit }),
+  setLocale: (locale) => set({ locale }),
+  setReadinessMode: (mode) => set({ readinessMode: mode }),
+  setReadinessLogWatcher: (enabled) => set({ readinessLogWatcher: enabled }),
+  setReadinessAccount: (enabled) => set({ readinessAccount: enabled }),
+  setReadinessOverlay: (enabled) => set({ readinessOverlay: enabled }),
+  setReadinessPreset: (enabled) => set({ readinessPreset: enabled }),
+  setReadinessProfilePublic: (enabled) => set({ readinessProfilePublic: enabled }),
+  setAutoStartRuns: (enabled) => set({ autoStartRuns: enabled }),
+  setAutoStartZone: (zone) => set({ autoStartZone: zone }),
+  setAutoPause: (enabled) => set({ autoPause: enabled }),
+  setAutoExportDir: (dir) => set({ autoExportDir: dir }),
+  setAutoExportFormats: (formats) => set({ autoExportFormats: formats }),
+  setAutoExportPbsOnly: (enabled) => set({ autoExportPbsOnly: enabled }),
+  setWebhookUrl: (url) => set({ webhookUrl: url }),
+  setWebhookSecret: (secret) => set({ webhookSecret: secret }),
+  setTwitchToken: (token) => set({ twitchToken: token }),
+  setTwitchUsername: (username) => set({ twitchUsername: username }),
+  setTwitchChannel: (channel) => set({ twitchChannel: channel }),
+  setTwitchPbMessage: (message) => set({ twitchPbMessage: message }),
+  setTwitchGoldMessage: (message) => set({ twitchGoldMessage: message }),
+  setTwitchRunMessage: (message) => set({ twitchRunMessage: message }),
+  setUpdateChannel: (channel) => set({ updateChannel: channel }),
+  setSnapshotRetention: (retention) => set({ snapshotRetention: retention }),
+  setSnapshotRetentionRuns: (runs) => set({ snapshotRetentionRuns: runs }),
+  setSnapshotLevelInterval: (levels) => set({ snapshotLevelInterval: levels }),
+  setImageProxyHosts: (hosts) => set({ imageProxyHosts: hosts }),
+  // Switching games swaps in the other campaign's breakpoints (the wizard
+  // routes are Path of Exile 1's), and its starting zone if the default was set
+  setGame: (game) => set((state) => {
+    if (state.game === game) return state;
+    return {
+      game,
+      breakpoints: resetToDefault(game),
+      wizardConfig: undefined,
+      autoStartZone: state.autoStartZone === campaignStartZone[state.game] ? campaignStartZone[game] : state.autoStartZone,
+    };
+  }),
   setOverlayOpen: (open) => set({ overlayOpen: open }),
+  setCustomPreset: (preset, breakpoints = []) => {
+    try {
+      if (preset) localStorage.setItem(CUSTOM_PRESET_STORAGE_KEY, String(preset.id));
+      else localStorage.removeItem(CUSTOM_PRESET_STORAGE_KEY);
+    } catch (e) {
+      console.error('[Store] Failed to save breakpoint preset:', e);
+    }
+    set({ customPreset: preset, customPresetBreakpoints: preset ? breakpoints : [] });
+  },
   // Detect current preset based on enabled breakpoints
   getCurrentPresetName: () => {
     const state = get();
+    if (state.customPreset) return state.customPreset.name;
     const enabledNames = state.breakpoints
       .filter((bp) => bp.isEnabled)
       .map((bp) => bp.name);
 
     // Check if it matches speedrun preset
-    const speedrunSet = new Set(speedrunEnabledBreakpoints);
+    const speedrunSet = new Set(speedrunBreakpointsFor(state.game));
     const enabledSet = new Set(enabledNames);
     if (
       speedrunSet.size === enabledSet.size &&
@@ -233,11 +457,129 @@ export const useSettingsStore = create<SettingsState>((set, get) => ({
   // Get list of enabled breakpoint names
   getEnabledBreakpointNames: () => {
     const state = get();
+    if (state.customPreset) return state.customPresetBreakpoints.map((bp) => bp.name);
     return state.breakpoints
       .filter((bp) => bp.isEnabled)
       .map((bp) => bp.name);
   },
 
+  // Save the current breakpoints, comparison mode and overlay layout for a category
+  saveCategoryDefaults: async (category) => {
+    const state = get();
+    const defaults: CategoryDefaults = {
+      category,
+      breakpointPreset: state.getCurrentPresetName(),
+      enabledBreakpoints: JSON.stringify(state.getEnabledBreakpointNames()),
+      snapshotBreakpoints: JSON.stringify(
+        state.breakpoints.filter((bp) => bp.captureSnapshot).map((bp) => bp.name)
+      ),
+      separatePbsByPatch: state.separatePbsByPatch,
+      overlayLayout: {
+        scale: state.overlayScale,
+        fontSize: state.overlayFontSize,
+        showTimer: state.overlayShowTimer,
+        showZone: state.overlayShowZone,
+        showLastSplit: state.overlayShowLastSplit,
+        showBreakpoints: state.overlayShowBreakpoints,
+        breakpointCount: state.overlayBreakpointCount,
+      },
+    };
+    await invoke('set_category_defaults', { defaults });
+  },
+
+  // Apply saved defaults before a run of the category starts. The backend
+  // applies the comparison mode and overlay layout to the stored settings in
+  // create_run; this mirrors them locally and applies the breakpoint flags.
+  applyCategoryDefaults: async (category) => {
+    let defaults: CategoryDefaults | undefined;
+    try {
+      const all = await invoke<CategoryDefaults[]>('get_category_defaults');
+      defaults = all.find((d) => d.category === category);
+    } catch (error) {
+      console.error('[settingsStore] Failed to load category defaults:', error);
+    }
+    if (!defaults) return;
+
+    const parseNames = (json: string | null) => {
+      try {
+        return json ? new Set<string>(JSON.parse(json)) : null;
+      } catch {
+        return null;
+      }
+    };
+    const enabled = parseNames(defaults.enabledBreakpoints);
+    const snapshots = parseNames(defaults.snapshotBreakpoints);
+    const layout = defaults.overlayLayout;
+
+    set((state) => ({
+      breakpoints: state.breakpoints.map((bp) => ({
+        ...bp,
+        isEnabled: enabled ? enabled.has(bp.name) : bp.isEnabled,
+        captureSnapshot: snapshots ? snapshots.has(bp.name) : bp.captureSnapshot,
+      })),
+      separatePbsByPatch: defaults.separatePbsByPatch ?? state.separatePbsByPatch,
+      ...(layout && {
+        overlayScale: layout.scale,
+        overlayFontSize: layout.fontSize,
+        overlayShowTimer: layout.showTimer,
+        overlayShowZone: layout.showZone,
+        overlayShowLastSplit: layout.showLastSplit,
+        overlayShowBreakpoints: layout.showBreakpoints,
+        overlayBreakpointCount: layout.breakpointCount,
+      }),
+    }));
+  },
+
+  // Probe whether the public API can read the account, so a private profile
+  // shows up on setup or at run start instead of as failed snapshots mid-run
+  checkProfilePrivacy: async () => {
+    const account = get().accountName.trim();
+    if (!account) {
+      set({ profilePrivacy: null, profilePrivacyError: null });
+      return null;
+    }
+    try {
+      const privacy = await invoke<ProfilePrivacy>('check_profile_privacy', { account });
+      set({ profilePrivacy: privacy, profilePrivacyError: null });
+      return privacy;
+    } catch (error) {
+      console.error('[settingsStore] Failed to check profile privacy:', error);
+      set({ profilePrivacy: null, profilePrivacyError: getErrorMessage(error) });
+      return null;
+    }
+  },
+
+  // Evaluate the pre-run checklist for the configured category. Category
+  // defaults are applied first so the preset check sees the splits the run
+  // will actually use.
+  checkRunReadiness: async () => {
+    const { wizardConfig } = get();
+    const category = wizardConfig ? getWizardCategory(wizardConfig) : 'any%';
+    try {
+      await get().applyCategoryDefaults(category);
+      const readiness = await invoke<RunReadiness>('get_run_readiness', {
+        category,
+        breakpointPreset: get().getCurrentPresetName(),
+      });
+      set((state) => ({
+        runReadiness: readiness,
+        profilePrivacy: readiness.profilePrivacy ?? state.profilePrivacy,
+        profilePrivacyError: readiness.profilePrivacy ? null : state.profilePrivacyError,
+      }));
+      return readiness;
+    } catch (error) {
+      console.error('[settingsStore] Failed to check run readiness:', error);
+      set({ runReadiness: null });
+      return null;
+    }
+  },
+
+  clearRunReadiness: () => set({ runReadiness: null }),
+
+  setLogRotation: (rotation) => set({ logRotation: rotation }),
+
+  setPollMode: (mode) => set({ pollMode: mode }),
+
   // Hotkey actions
   loadHotkeys: async () => {
     try {
@@ -1,26 +1,33 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import type { Snapshot, PoeItem } from '../types';
+import type { Snapshot, SnapshotSummary, PoeItem } from '../types';
+import { getErrorMessage } from '../utils/errors';
 
 interface SnapshotState {
-  // Loaded snapshots for selected run
-  snapshots: Snapshot[];
+  // Snapshots of the selected run, without their JSON
+  snapshots: SnapshotSummary[];
+  // Full snapshots loaded so far, by ID; each is fetched when first selected
+  loadedSnapshots: Map<number, Snapshot>;
   // Currently selected snapshot
   selectedSnapshotId: number | null;
   // Split IDs currently being captured
   pendingCaptures: Set<number>;
   // Split IDs that failed to capture
   failedCaptures: Map<number, string>;
+  // Pending split IDs still waiting behind the capture in flight (snapshot-queue event)
+  queuedCaptures: Set<number>;
   // Loading state
   isLoading: boolean;
 
   // Actions
   loadSnapshots: (runId: number) => Promise<void>;
+  loadAllSnapshots: (runId: number) => Promise<Snapshot[]>;
   selectSnapshot: (id: number | null) => void;
   addPendingCapture: (splitId: number) => void;
   removePendingCapture: (splitId: number) => void;
   addFailedCapture: (splitId: number, error: string) => void;
   removeFailedCapture: (splitId: number) => void;
+  setCaptureQueue: (queued: number[]) => void;
   addSnapshot: (snapshot: Snapshot) => void;
   clearSnapshots: () => void;
 
@@ -36,28 +43,45 @@ interface SnapshotState {
 
 export const useSnapshotStore = create<SnapshotState>((set, get) => ({
   snapshots: [],
+  loadedSnapshots: new Map(),
   selectedSnapshotId: null,
   pendingCaptures: new Set(),
   failedCaptures: new Map(),
+  queuedCaptures: new Set(),
   isLoading: false,
 
   loadSnapshots: async (runId: number) => {
     set({ isLoading: true });
     try {
-      const snapshots = await invoke<Snapshot[]>('get_snapshots', { runId });
-      set({
-        snapshots,
-        selectedSnapshotId: snapshots.length > 0 ? snapshots[0].id : null,
-        isLoading: false,
-      });
+      const snapshots = await invoke<SnapshotSummary[]>('get_snapshot_summaries', { runId });
+      set({ snapshots, loadedSnapshots: new Map(), isLoading: false });
+      get().selectSnapshot(snapshots.length > 0 ? snapshots[0].id : null);
     } catch (error) {
       console.error('Failed to load snapshots:', error);
       set({ isLoading: false });
     }
   },
 
+  // Every snapshot of the run in full, for exports that need them all
+  loadAllSnapshots: async (runId: number) => {
+    const snapshots = await invoke<Snapshot[]>('get_snapshots', { runId });
+    set((state) => {
+      const loaded = new Map(state.loadedSnapshots);
+      snapshots.forEach((snapshot) => loaded.set(snapshot.id, snapshot));
+      return { loadedSnapshots: loaded };
+    });
+    return snapshots;
+  },
+
   selectSnapshot: (id: number | null) => {
     set({ selectedSnapshotId: id });
+    if (id === null || get().loadedSnapshots.has(id)) return;
+    invoke<Snapshot | null>('get_snapshot', { snapshotId: id })
+      .then((snapshot) => {
+        if (!snapshot) return;
+        set((state) => ({ loadedSnapshots: new Map(state.loadedSnapshots).set(snapshot.id, snapshot) }));
+      })
+      .catch((error) => console.error('Failed to load snapshot:', error));
   },
 
   addPendingCapture: (splitId: number) => {
@@ -97,17 +121,32 @@ export const useSnapshotStore = create<SnapshotState>((set, get) => ({
     });
   },
 
+  setCaptureQueue: (queued: number[]) => {
+    set({ queuedCaptures: new Set(queued) });
+  },
+
   addSnapshot: (snapshot: Snapshot) => {
     set((state) => {
       // Remove from pending
       const newPending = new Set(state.pendingCaptures);
-      newPending.delete(snapshot.splitId);
+      if (snapshot.splitId !== null) newPending.delete(snapshot.splitId);
       // Add snapshot and sort by elapsed time
-      const newSnapshots = [...state.snapshots, snapshot].sort(
+      const summary: SnapshotSummary = {
+        id: snapshot.id,
+        runId: snapshot.runId,
+        splitId: snapshot.splitId,
+        timestamp: snapshot.timestamp,
+        elapsedTimeMs: snapshot.elapsedTimeMs,
+        characterLevel: snapshot.characterLevel,
+        hasPobCode: snapshot.pobCode !== null,
+        isFinal: snapshot.isFinal,
+      };
+      const newSnapshots = [...state.snapshots, summary].sort(
         (a, b) => a.elapsedTimeMs - b.elapsedTimeMs
       );
       return {
         snapshots: newSnapshots,
+        loadedSnapshots: new Map(state.loadedSnapshots).set(snapshot.id, snapshot),
         pendingCaptures: newPending,
         // Auto-select the new snapshot
         selectedSnapshotId: snapshot.id,
@@ -118,6 +157,7 @@ export const useSnapshotStore = create<SnapshotState>((set, get) => ({
   clearSnapshots: () => {
     set({
       snapshots: [],
+      loadedSnapshots: new Map(),
       selectedSnapshotId: null,
       pendingCaptures: new Set(),
       failedCaptures: new Map(),
@@ -144,7 +184,7 @@ export const useSnapshotStore = create<SnapshotState>((set, get) => ({
       });
     } catch (error) {
       console.error('Failed to retry snapshot capture:', error);
-      get().addFailedCapture(splitId, String(error));
+      get().addFailedCapture(splitId, getErrorMessage(error));
     }
   },
 }));
@@ -9,10 +9,15 @@ export interface Run {
   ascendancy?: string | null;
   league?: string;
   category: string;
+  // Detection from the run's first log events only replaces a 'selected' category
+  categorySource?: 'selected' | 'detected' | 'manual';
   startedAt: string;
   endedAt: string | null;
   totalTimeMs: number | null;
   townTimeMs?: number;
+  // Loading screen time, and the total without it (null until completed)
+  loadTimeMs?: number;
+  loadRemovedTimeMs?: number | null;
   isCompleted: boolean;
   isPersonalBest: boolean;
   // Breakpoint tracking
@@ -21,6 +26,235 @@ export interface Run {
   // Reference run support
   isReference?: boolean;
   sourceName?: string | null;
+  // Review metadata
+  videoUrl?: string | null;
+  vodOffsetMs?: number | null;
+  reviewNotes?: string | null;
+  // First run of a session (excluded from stats with excludeWarmups)
+  isWarmup?: boolean;
+  // Game version detected from Client.txt when the run started
+  gameVersion?: string | null;
+  // From the league name; null until the league is known
+  ruleset?: Ruleset | null;
+  // The user's own note on the run (set_run_note)
+  notes?: string | null;
+  // Countdown the timer started with (split times count from its end)
+  startOffsetMs?: number;
+  // Why the run was reset unfinished (abandon_run), and when on the run clock
+  abandonReason?: AbandonReason | null;
+  abandonedAtMs?: number | null;
+  // Last split reached before the reset; null when reset before the first split
+  resetSplit?: string | null;
+  resetSplitKey?: string | null;
+}
+
+export type AbandonReason = 'death' | 'bad_layout' | 'bricked_gear' | 'irl' | 'server_issues' | 'other';
+
+export const ABANDON_REASON_LABELS: Record<AbandonReason, string> = {
+  death: 'Death',
+  bad_layout: 'Bad layout',
+  bricked_gear: 'Bricked gear',
+  irl: 'IRL',
+  server_issues: 'Server issues',
+  other: 'Other',
+};
+
+// VOD link and review notes (get_run_review / set_run_review)
+// Phone status page (get_status_server / set_status_server)
+export interface StatusServerInfo {
+  enabled: boolean;
+  port: number;
+  token: string;
+  // URLs to open on a phone; empty while disabled
+  urls: string[];
+}
+
+// Extra overlay window; its window label is `overlay-<id>`
+export interface OverlayProfile {
+  id: string;
+  name: string;
+  x: number | null;
+  y: number | null;
+  width: number | null;
+  height: number | null;
+  // null follows the overlay opacity setting
+  opacity: number | null;
+}
+
+// Which parts of the overlay are shown (get_overlay_layout / set_overlay_layout)
+export interface OverlayComponents {
+  timer: boolean;
+  currentSplit: boolean;
+  delta: boolean;
+  nextSplit: boolean;
+  deathCount: boolean;
+  levelPace: boolean;
+}
+
+// Result of get_obs_server / set_obs_server
+export interface ObsServerInfo {
+  enabled: boolean;
+  port: number;
+  // Browser source URL; empty while disabled
+  url: string;
+}
+
+// A remote racer in the race lobby; runId is the reference run their splits go to
+export interface Racer {
+  name: string;
+  runId: number;
+  category: string;
+  class: string;
+  startedAt: string;
+  lastSplit: string | null;
+  splitTimeMs: number | null;
+  finished: boolean;
+}
+
+// Result of get_race_status / host_race / join_race
+export interface RaceStatus {
+  // null outside a lobby
+  role: 'host' | 'guest' | null;
+  name: string;
+  // ws:// address guests join, ending in the room token
+  address: string;
+  peers: number;
+  racers: Racer[];
+}
+
+// Payload of the race-event event, one per remote racer's start, split, finish or leaving
+export type RaceEvent =
+  | { type: 'runStarted'; racer: string; category: string; class: string; startedAt: string }
+  | { type: 'split'; racer: string; breakpointType: string; breakpointName: string; breakpointKey: string | null; splitTimeMs: number }
+  | { type: 'runFinished'; racer: string; totalTimeMs: number }
+  | { type: 'racerLeft'; racer: string };
+
+// Result of import_livesplit
+export interface LiveSplitImport {
+  // Reference run holding the file's Personal Best
+  runId: number;
+  // Best Segments that beat or filled in a gold split
+  goldsImported: number;
+}
+
+// Result of import_run_json
+export interface RunJsonImport {
+  runId: number;
+  // Recorded by another account, so kept out of PBs and golds
+  isReference: boolean;
+  snapshotsImported: number;
+  // Check of the export's run certificate; null when it has none
+  certificate: CertificateStatus | null;
+}
+
+// Tamper-evident run summary from get_run_certificate (see src-tauri/src/certificate.rs)
+export interface RunCertificate {
+  version: number;
+  appVersion: string;
+  character: string;
+  accountName: string;
+  class: string;
+  category: string;
+  league: string;
+  startedAt: string;
+  totalTimeMs: number;
+  splits: { name: string; key: string | null; timeMs: number }[];
+  hash: string;
+}
+
+export type CertificateStatus = 'valid' | 'tampered' | 'unsupported_version';
+
+// Path of Exile OAuth sign-in (poe_login / get_poe_auth_status / poe_logout)
+export interface PoeAuthStatus {
+  connected: boolean;
+  accountName: string | null;
+  // Unix seconds; the backend refreshes the token before it passes
+  expiresAt: number | null;
+}
+
+// Counts of what repair_database fixed
+export interface RepairReport {
+  orphanedSplits: number;
+  orphanedSnapshots: number;
+  orphanedSplitNotes: number;
+  runsMissingEndTime: number;
+  personalBestsFixed: number;
+  goldSplitsFixed: number;
+}
+
+// Result of compact_database: database size in bytes before and after
+export interface CompactReport {
+  snapshotsPruned: number;
+  snapshotsCompressed: number;
+  bytesBefore: number;
+  bytesAfter: number;
+}
+
+// Result of get_image_cache_stats / clear_image_cache: hits and misses since startup
+export interface ImageCacheStats {
+  entries: number;
+  sizeBytes: number;
+  hits: number;
+  misses: number;
+}
+
+// Result of archive_runs_older_than
+export interface ArchiveReport {
+  runsArchived: number;
+  snapshotsArchived: number;
+}
+
+// Result of reprocess_snapshots
+export interface ReprocessReport {
+  runs: number;
+  snapshots: number;
+  updated: number;
+  failed: number;
+}
+
+// Result of diff_snapshots: what changed from snapshot A to snapshot B
+export interface SnapshotItemSummary {
+  name: string;
+  typeLine: string;
+  frameType: number;
+  itemLevel: number;
+  sockets: number;
+  maxLinks: number;
+}
+
+export interface SnapshotItemChange {
+  slot: string;
+  change: 'added' | 'removed' | 'changed';
+  before: SnapshotItemSummary | null;
+  after: SnapshotItemSummary | null;
+}
+
+export interface SnapshotGemChange {
+  name: string;
+  change: 'added' | 'removed' | 'leveled';
+  slot: string;
+  levelBefore: number | null;
+  levelAfter: number | null;
+}
+
+export interface SnapshotDiff {
+  snapshotA: number;
+  snapshotB: number;
+  levelBefore: number;
+  levelAfter: number;
+  levelsGained: number;
+  elapsedTimeMs: number;
+  items: SnapshotItemChange[];
+  gems: SnapshotGemChange[];
+  passivesAllocated: number[];
+  passivesRefunded: number[];
+}
+
+export interface RunReview {
+  videoUrl: string | null;
+  // Position in the VOD where the run timer started
+  vodOffsetMs: number | null;
+  reviewNotes: string | null;
 }
 
 export interface Split {
@@ -28,12 +262,78 @@ export interface Split {
   runId: number;
   breakpointType: BreakpointType;
   breakpointName: string;
+  // Canonical location key shared across presets (null for custom/manual splits)
+  breakpointKey?: string | null;
   splitTimeMs: number;
   deltaMs: number | null;
   segmentTimeMs: number;
   // Town/hideout time tracking (cumulative at this split)
   townTimeMs: number;
   hideoutTimeMs: number;
+  // Town/hideout time within this segment (computed by the backend)
+  segmentTownTimeMs?: number;
+  segmentHideoutTimeMs?: number;
+  // Zone layout RNG: manual tag, or 'fast' | 'typical' | 'slow' when inferred
+  layoutVariant?: string | null;
+  layoutInferred?: boolean;
+  // Character experience from the split's snapshot
+  experience?: number | null;
+  // Loading screen time (cumulative at this split)
+  loadTimeMs?: number;
+}
+
+// Review note on a split, optionally tagged with a mistake type (snake_case)
+export interface SplitNote {
+  id: number;
+  splitId: number;
+  note: string;
+  mistakeType: string | null;
+  createdAt: string;
+}
+
+// Tag on a run, or on one of its splits when splitId is set (lowercase, see add_run_tag)
+export interface RunTag {
+  id: number;
+  runId: number;
+  splitId: number | null;
+  tag: string;
+  createdAt: string;
+}
+
+export type MistakeType = 'death' | 'bad_layout' | 'misclicked_portal' | string;
+
+// Time lost against gold splits, by mistake type (get_time_loss_report)
+export interface TimeLossReport {
+  totalLossMs: number;
+  untaggedLossMs: number;
+  byMistakeType: { mistakeType: MistakeType; lossMs: number; occurrences: number }[];
+}
+
+// Average town/hideout time per act (get_town_time_report)
+export interface ActTownTime {
+  act: number;
+  averageTownTimeMs: number;
+  averageHideoutTimeMs: number;
+  runCount: number;
+}
+
+// Campaign quest progress of a run (get_quest_state)
+export type QuestObjectiveKind = 'skill_point' | 'bandits' | 'labyrinth';
+
+export interface QuestObjective {
+  id: string;
+  name: string;
+  act: number;
+  kind: QuestObjectiveKind;
+  completed: boolean;
+}
+
+export interface QuestState {
+  runId: number;
+  currentAct: number;
+  objectives: QuestObjective[];
+  // Ids of objectives from the current or earlier acts that are still missing
+  missing: string[];
 }
 
 export type BreakpointType = 'zone' | 'level' | 'boss' | 'act' | 'lab' | 'custom';
@@ -41,30 +341,92 @@ export type BreakpointType = 'zone' | 'level' | 'boss' | 'act' | 'lab' | 'custom
 export interface Snapshot {
   id: number;
   runId: number;
-  splitId: number;
+  // null for a snapshot taken at a level milestone (snapshotLevelInterval)
+  splitId: number | null;
   timestamp: string;
   elapsedTimeMs: number;
   characterLevel: number;
+  // Character experience at capture, when the API reported it
+  experience: number | null;
   itemsJson: string;
+  // SkillGroup[] and BuildStats, derived from the items and passives
   skillsJson: string;
   passiveTreeJson: string;
   statsJson: string;
   pobCode: string | null;
+  // Captured automatically when the run completed
+  isFinal: boolean;
+}
+
+// A snapshot without its JSON; get_snapshot loads the rest when it's opened
+export interface SnapshotSummary {
+  id: number;
+  runId: number;
+  splitId: number | null;
+  timestamp: string;
+  elapsedTimeMs: number;
+  characterLevel: number;
+  experience: number | null;
+  hasPobCode: boolean;
+  isFinal: boolean;
+}
+
+export interface SnapshotGem {
+  name: string;
+  level: number;
+  quality: number;
+  isSupport: boolean;
+  // Socket group in its item (gems sharing one are linked); null for old snapshots
+  linkGroup: number | null;
+}
+
+export interface SkillGroup {
+  inventoryId: string;
+  gems: SnapshotGem[];
+}
+
+export interface BuildStats {
+  passivePoints: number;
+  clusterPassives: number;
+  masteries: number;
+  equippedItems: number;
+  uniqueItems: number;
+  socketedGems: number;
+  supportGems: number;
+  maxLinks: number;
 }
 
 export interface PersonalBest {
   id: number;
   category: string;
   class: string;
+  // Ascendancy of the PB run, when it was known
+  ascendancy: string | null;
+  // Each league keeps its own PB
+  league: string;
+  // Major patch (e.g. "3.25"), or empty for the all-time PB
+  patch: string;
   runId: number;
   totalTimeMs: number;
 }
 
+// Payload of the split-announcement event (thresholds are evaluated in the backend)
+export interface SplitAnnouncement {
+  kind: 'gold' | 'ahead' | 'behind';
+  breakpointName: string;
+  deltaMs: number | null;
+  // Text to speak, e.g. "The Coast, 35 seconds behind"
+  message: string;
+  sound: boolean;
+  speech: boolean;
+}
+
 export interface GoldSplit {
   id: number;
   category: string;
   class: string;
   breakpointName: string;
+  breakpointKey: string | null;
   bestSegmentMs: number;
 }
 
@@ -81,7 +443,13 @@ export type LogEventType =
   | 'death'
   | 'instance_details'
   | 'login'
-  | 'kitava_affliction';
+  | 'area_generated'
+  | 'kitava_affliction'
+  | 'game_version'
+  | 'npc_dialogue'
+  | 'act_complete'
+  | 'lab_start'
+  | 'lab_complete';
 
 export interface ZoneEnterEvent {
   zoneName: string;
@@ -110,11 +478,55 @@ export interface Breakpoint {
 }
 
 export interface BreakpointTrigger {
-  type: 'zone' | 'level' | 'boss' | 'kitava';
+  type: 'zone' | 'level' | 'boss' | 'kitava' | 'quest';
   zoneName?: string;
   act?: number;
   level?: number;
   penalty?: number;
+  // QuestObjective.id a quest trigger waits for
+  quest?: string;
+  rule?: TriggerRule;
+}
+
+// Split layouts built in the app and stored in the database (get_breakpoint_presets)
+export interface BreakpointPreset {
+  id: number;
+  name: string;
+  game: Game;
+  createdAt: string;
+  breakpointCount: number;
+}
+
+export type PresetTriggerType = 'zone' | 'level' | 'kitava' | 'quest';
+
+// A breakpoint as add_breakpoint / update_breakpoint take it
+export interface NewPresetBreakpoint {
+  name: string;
+  breakpointType: BreakpointType;
+  triggerType: PresetTriggerType;
+  zoneName: string | null;
+  act: number | null;
+  level: number | null;
+  quest: string | null;
+  // Resistance penalty a kitava trigger splits on: 30 (Act 5) or 60 (Act 10)
+  penalty?: number | null;
+  entry?: number | null;
+  afterQuest?: string | null;
+  captureSnapshot: boolean;
+}
+
+export interface PresetBreakpoint extends NewPresetBreakpoint {
+  id: number;
+  presetId: number;
+  position: number;
+}
+
+// Extra conditions enforced by the backend breakpoint engine, for zones that are re-entered legitimately
+export interface TriggerRule {
+  // Only split on this entry into the zone during the run (1 = first entry only)
+  entry?: number;
+  // Only split once this quest objective (QuestObjective.id) is complete
+  afterQuest?: string;
 }
 
 // Wizard configuration for guided breakpoint setup
@@ -156,9 +568,269 @@ export interface Settings {
   overlayAccentColor: string;
   overlayAlwaysOnTop: boolean;
   overlayLocked: boolean;
+  // Warm-up detection: first run after a gap of sessionGapMinutes is flagged
+  warmupDetectionEnabled: boolean;
+  sessionGapMinutes: number;
+  // Startup: launch with the OS, and start hidden in the tray
+  launchOnBoot: boolean;
+  startMinimized: boolean;
+  // Minimizing the main window hides it in the tray
+  minimizeToTray: boolean;
+  // Closing the main window hides it; the watcher, breakpoint engine and overlays keep the run going
+  backgroundMode: boolean;
+  // Start and stop the log watcher and show and hide the overlays with the game client (Linux, Wine/Proton)
+  followGameProcess: boolean;
+  // Compare against PBs from the current major patch only
+  separatePbsByPatch: boolean;
+  // Split announcements: tone and/or speech, only for golds or deltas past a threshold
+  announceSound: boolean;
+  announceSpeech: boolean;
+  announceGoldsOnly: boolean;
+  announceMinBehindMs: number;
+  announceMinAheadMs: number;
+  // Golds set this many seconds before an undo/reset are reverted with it
+  goldProtectionSecs: number;
+  // Countdown before the run timer reaches zero (timer starts at minus this)
+  timerStartOffsetMs: number;
+  // Keep raw API responses of the last N snapshot attempts for export_diagnostics
+  recordApiResponses: boolean;
+  apiRecordingLimit: number;
+  // Number, time and date conventions for CSV/Markdown/HTML exports
+  locale: string;
+  // Pre-run checklist: what failing checks do, and which checks run
+  readinessMode: ReadinessMode;
+  readinessLogWatcher: boolean;
+  readinessAccount: boolean;
+  readinessOverlay: boolean;
+  readinessPreset: boolean;
+  readinessProfilePublic: boolean;
+  // Start a run when a new character enters the starting zone
+  autoStartRuns: boolean;
+  autoStartZone: string;
+  // Pause the timer on AFK and disconnect lines
+  autoPause: boolean;
+  // Exports written (comma-separated json/csv/lss) when a run completes
+  autoExportDir: string;
+  autoExportFormats: string;
+  autoExportPbsOnly: boolean;
+  // Notifications POSTed on run completion, PBs and gold splits; the secret signs them
+  webhookUrl: string;
+  webhookSecret: string;
+  // Twitch chat posts on PBs, gold splits and completed runs; an empty template posts nothing.
+  // Username is the token's account, the channel's owner when empty
+  twitchToken: string;
+  twitchUsername: string;
+  twitchChannel: string;
+  twitchPbMessage: string;
+  twitchGoldMessage: string;
+  twitchRunMessage: string;
+  // Release channel the updater checks
+  updateChannel: UpdateChannel;
+  // Snapshots compact_database keeps: all, each run's final build, or the last N runs'
+  snapshotRetention: SnapshotRetention;
+  snapshotRetentionRuns: number;
+  // Capture a snapshot every N levels of the run's character, besides the split snapshots; 0 = off
+  snapshotLevelInterval: number;
+  // Hosts proxy_image fetches item and passive tree art from, comma-separated
+  imageProxyHosts: string;
+  // Which game's client is followed: log formats, presets and API realm
+  game: Game;
+}
+
+// Game setting (see src-tauri/src/game.rs)
+export type Game = 'poe1' | 'poe2';
+
+// Snapshot retention setting (see src-tauri/src/db/compact.rs)
+export type SnapshotRetention = 'all' | 'final' | 'recent';
+
+// Update channel setting and check_for_update result (see src-tauri/src/updater.rs)
+export type UpdateChannel = 'stable' | 'beta';
+
+export interface UpdateInfo {
+  version: string;
+  currentVersion: string;
+  channel: UpdateChannel;
+  date: string | null;
+  notes: string | null;
+}
+
+// Result of get_run_readiness (see src-tauri/src/readiness.rs)
+export type ReadinessMode = 'off' | 'warn' | 'block';
+
+export interface ReadinessCheck {
+  id: 'log_watcher' | 'account_verified' | 'overlay_open' | 'preset' | 'profile_public';
+  label: string;
+  passed: boolean;
+  detail: string | null;
+}
+
+export interface RunReadiness {
+  mode: ReadinessMode;
+  checks: ReadinessCheck[];
+  ready: boolean;
+  blocked: boolean;
+  profilePrivacy: ProfilePrivacy | null;
+}
+
+// Entry of get_run_timeline, in run-clock order
+export type TimelineEntryKind = 'zone_enter' | 'level_up' | 'death' | 'pause' | 'resume' | 'split' | 'snapshot';
+
+export interface TimelineEntry {
+  kind: TimelineEntryKind;
+  elapsedMs: number;
+  // Zone, level, breakpoint or character, depending on the kind
+  label: string;
+  splitId: number | null;
+  snapshotId: number | null;
+}
+
+export interface ZoneVisits {
+  zoneName: string;
+  kind: 'town' | 'hideout' | 'field';
+  entries: number;
+  backtracks: number;
+  // Run-clock time spent in the zone after backtracking into it
+  backtrackTimeMs: number;
+}
+
+// Zone entries of a run; re-entering a played zone after another one is a
+// backtrack, going to town and back is not
+export interface BacktrackReport {
+  runId: number;
+  backtracks: number;
+  backtrackTimeMs: number;
+  zones: ZoneVisits[];
+}
+
+// One zone visit of a run at run-clock time; durationMs is null while in progress
+export interface ZoneTime {
+  id: number;
+  runId: number;
+  zoneName: string;
+  enteredAtMs: number;
+  durationMs: number | null;
+  isTown: boolean;
+  isHideout: boolean;
+}
+
+// Zones played between one split and the next (breakpointName null after the last split)
+export interface SegmentZones {
+  breakpointName: string | null;
+  startMs: number;
+  endMs: number;
+  zones: { zoneName: string; kind: 'town' | 'hideout' | 'field'; timeMs: number; visits: number }[];
+}
+
+// Result of get_zone_times
+export interface ZoneTimeReport {
+  runId: number;
+  visits: ZoneTime[];
+  segments: SegmentZones[];
+}
+
+// Average time per zone over filtered runs (get_zone_time_stats)
+export interface ZoneTimeStat {
+  zoneName: string;
+  isTown: boolean;
+  isHideout: boolean;
+  runCount: number;
+  visits: number;
+  averageTimeMs: number;
+  bestTimeMs: number;
+}
+
+// Time and deaths per zone over filtered runs, costliest first (get_zone_heatmap)
+export interface ZoneHeatmapEntry {
+  zoneName: string;
+  isTown: boolean;
+  isHideout: boolean;
+  runCount: number;
+  visits: number;
+  totalTimeMs: number;
+  averageTimeMs: number;
+  deaths: number;
+}
+
+// Endgame mapping session: wall-clock time in maps and between them
+export interface MapSession {
+  id: number;
+  startedAt: string;
+  // null while the session is running
+  endedAt: string | null;
+  mapsRun: number;
+  mapTimeMs: number;
+  hideoutTimeMs: number;
+  townTimeMs: number;
+  // Areas that are neither maps, hideouts nor towns (e.g. the labyrinth)
+  otherTimeMs: number;
+}
+
+// Payload of the log-rotated event: Client.txt was truncated or swapped for a
+// new file while watched, and reading restarted from its top
+export interface LogRotation {
+  timestamp: string;
+  reason: 'truncated' | 'replaced';
+}
+
+// Payload of the character-mismatch event: a character other than the run's
+// leveled up while it was timed (a character switch, or a party member)
+export interface CharacterMismatch {
+  run_id: number;
+  run_character: string;
+  character_name: string;
+  character_class: string;
+  level: number;
+}
+
+// Payload of the poll-mode-changed event: how often the watcher reads
+// Client.txt, and why it is reading fast
+export interface PollMode {
+  fast: boolean;
+  interval_ms: number;
+  reason: 'loading' | 'breakpoint' | 'manual' | null;
+}
+
+// Setup applied when a run of the category starts; null fields leave the current setting alone
+export interface OverlayLayout {
+  scale: 'small' | 'medium' | 'large';
+  fontSize: 'small' | 'medium' | 'large';
+  showTimer: boolean;
+  showZone: boolean;
+  showLastSplit: boolean;
+  showBreakpoints: boolean;
+  breakpointCount: number;
+}
+
+export interface CategoryDefaults {
+  category: string;
+  breakpointPreset: string | null;
+  enabledBreakpoints: string | null; // JSON array of breakpoint names
+  snapshotBreakpoints: string | null; // JSON array of breakpoint names
+  separatePbsByPatch: boolean | null;
+  overlayLayout: OverlayLayout | null;
+}
+
+// Capture listed by get_pending_snapshots
+export interface PendingSnapshot {
+  runId: number;
+  splitId: number | null; // null for a level milestone
+  characterName: string;
+  isFinal: boolean;
+  state: 'capturing' | 'queued';
+  startedAt: string | null; // set for the capture in flight
 }
 
 // POE API types
+// Result of check_profile_privacy (see src-tauri/src/privacy.rs)
+export type PrivacyStatus = 'public' | 'private' | 'characters_hidden';
+
+export interface ProfilePrivacy {
+  accountName: string;
+  status: PrivacyStatus;
+  characterCount: number | null;
+  hints: string[];
+}
+
 export interface PoeCharacter {
   name: string;
   league: string;
@@ -207,6 +879,8 @@ export interface HotkeySettings {
   resetTimer: string;
   manualSnapshot: string;
   manualSplit: string;
+  undoSplit: string;
+  skipSplit: string;
   toggleOverlay: string;
   toggleOverlayLock: string;
 }
@@ -216,10 +890,23 @@ export const DEFAULT_HOTKEYS: HotkeySettings = {
   resetTimer: 'Ctrl+Shift+Space',
   manualSnapshot: 'Ctrl+Alt+Space',
   manualSplit: 'Ctrl+Shift+S',
+  undoSplit: 'Ctrl+Shift+Z',
+  skipSplit: 'Ctrl+Shift+K',
   toggleOverlay: 'Ctrl+O',
   toggleOverlayLock: 'Ctrl+Shift+O',
 };
 
+/** Whether one hotkey is registered as a global shortcut */
+export interface HotkeyRegistration {
+  hotkey: keyof HotkeySettings;
+  shortcut: string;
+  registered: boolean;
+  /** Invalid or repeated shortcut, or one another app holds */
+  error: string | null;
+  /** invalid_input for a bad or repeated shortcut, shortcut_unavailable when the OS refused it */
+  errorCode: AppErrorCode | null;
+}
+
 // Timer state
 export interface TimerState {
   isRunning: boolean;
@@ -227,6 +914,8 @@ export interface TimerState {
   elapsedMs: number;
   currentSplit: number;
   splits: SplitTime[];
+  // Breakpoints passed over with skip_split, in order
+  skippedSplits: SkippedSplit[];
   // Town/Hideout time tracking
   townTimeMs: number;
   hideoutTimeMs: number;
@@ -235,6 +924,131 @@ export interface TimerState {
   townEnteredAt: number | null;
   hideoutEnteredAt: number | null;
   currentZone: string | null;
+  // Deaths of the run's character, from death-recorded
+  deaths: number;
+}
+
+// Result of skip_split
+export interface SkippedSplit {
+  id: number;
+  runId: number;
+  breakpointName: string;
+  // Run clock when skipped
+  elapsedMs: number;
+}
+
+// Result of get_pauses: the run clock stands still while paused
+export interface Pause {
+  id: number;
+  runId: number;
+  // Run clock when paused
+  elapsedMs: number;
+  reason: 'manual' | 'afk' | 'disconnect' | 'character_mismatch';
+  pausedAt: string;
+  // null while paused
+  resumedAt: string | null;
+  durationMs: number | null;
+}
+
+// Leveling guide (src-tauri/src/guide.rs)
+export type StepTrigger =
+  | { type: 'zone'; value: string }
+  | { type: 'area'; value: string }
+  | { type: 'level'; value: number };
+
+export interface GuideStep {
+  section: string;
+  text: string;
+  // null for steps that pass once a later step's trigger is seen
+  trigger: StepTrigger | null;
+}
+
+export interface Guide {
+  name: string;
+  steps: GuideStep[];
+}
+
+// Result of get_current_guide_step and the guide-step-changed event
+export interface GuideProgress {
+  runId: number;
+  guideName: string;
+  stepIndex: number;
+  totalSteps: number;
+  // null once the guide is finished
+  step: GuideStep | null;
+  next: GuideStep | null;
+}
+
+// Result of get_gem_timeline: a gem at one snapshot
+export interface GemAtSplit {
+  splitId: number | null;
+  // null when the snapshot's split was undone, or it had none
+  breakpointName: string | null;
+  elapsedMs: number;
+  level: number;
+  quality: number;
+  inventoryId: string;
+  linkGroup: number | null;
+}
+
+export interface GemHistory {
+  name: string;
+  isSupport: boolean;
+  firstEquippedMs: number;
+  firstBreakpoint: string | null;
+  splits: GemAtSplit[];
+}
+
+// Result of get_experience_curve: one point per snapshot that recorded experience
+export interface ExperiencePoint {
+  elapsedMs: number;
+  level: number;
+  experience: number;
+}
+
+// Result of get_level_pace
+export interface LevelSample {
+  elapsedMs: number;
+  level: number;
+}
+
+export interface SplitLevel {
+  breakpointName: string;
+  // null for a PB breakpoint the run hasn't reached yet
+  splitTimeMs: number | null;
+  level: number | null;
+  pbLevel: number | null;
+  // Breakpoints still ahead: the PB's level there, shifted by the current lead
+  expectedLevel: number | null;
+}
+
+export interface LevelPace {
+  runId: number;
+  elapsedMs: number;
+  level: number | null;
+  pbRunId: number | null;
+  pbLevel: number | null;
+  pbLevels: LevelSample[];
+  xpPerHour: number | null;
+  splits: SplitLevel[];
+  zones: { zoneName: string; elapsedMs: number; level: number | null }[];
+}
+
+// Result of get_sum_of_best
+export interface SumOfBest {
+  // null until every breakpoint has a gold
+  sumOfBestMs: number | null;
+  breakpoints: number;
+  missing: string[];
+}
+
+// Result of get_best_possible_time
+export interface BestPossibleTime {
+  runId: number;
+  elapsedMs: number;
+  completedMs: number;
+  remaining: number;
+  bestPossibleMs: number | null;
 }
 
 export interface SplitTime {
@@ -243,12 +1057,59 @@ export interface SplitTime {
   segmentTimeMs: number;
   deltaMs: number | null;
   isBestSegment: boolean;
+  color: SplitColor;
+}
+
+// LiveSplit's split colors, decided by the backend when the split is recorded
+export type SplitColor = 'gold' | 'ahead_gaining' | 'ahead_losing' | 'behind_gaining' | 'behind_losing' | 'neutral';
+
+// A split against the PB and golds as they stood before it (add_split / split-recorded)
+export interface SplitComparison {
+  deltaMs: number | null;
+  // Time gained (negative) or lost over the segment
+  segmentDeltaMs: number | null;
+  isGold: boolean;
+  color: SplitColor;
+}
+
+// What get_comparison measures a run against
+export type ComparisonKind =
+  | { kind: 'personal_best' }
+  | { kind: 'gold' }
+  | { kind: 'average'; count?: number }
+  | { kind: 'reference'; runId: number };
+
+// One split of get_comparison; splits the run hasn't reached have only comparison times
+export interface ComparisonRow {
+  breakpointName: string;
+  breakpointKey: string | null;
+  splitTimeMs: number | null;
+  segmentTimeMs: number | null;
+  comparisonSplitMs: number | null;
+  comparisonSegmentMs: number | null;
+  deltaMs: number | null;
+  segmentDeltaMs: number | null;
+  isGold: boolean;
+  color: SplitColor;
+  // Split times without loading screens (the comparison's is null for golds)
+  loadRemovedSplitMs: number | null;
+  comparisonLoadRemovedMs: number | null;
+  loadRemovedDeltaMs: number | null;
+}
+
+// Result of add_split
+export interface AddedSplit {
+  splitId: number;
+  comparison: SplitComparison;
 }
 
 // UI state
 export type ViewMode = 'timer' | 'snapshots' | 'comparison' | 'history' | 'settings';
 
 // Filtering and analytics
+// Character ruleset of a run's league (see src-tauri/src/ruleset.rs)
+export type Ruleset = 'SC' | 'HC' | 'SSF' | 'HCSSF';
+
 export interface RunFilters {
   class?: string;
   ascendancy?: string;
@@ -257,6 +1118,19 @@ export interface RunFilters {
   breakpointPreset?: string;
   isCompleted?: boolean;
   includeReference?: boolean;
+  excludeWarmups?: boolean;
+  ruleset?: Ruleset;
+  gameVersion?: string;
+  // Tagged on the run or one of its splits
+  tag?: string;
+  // Text in the run's note or one of its split notes
+  note?: string;
+}
+
+// One page of get_runs_paged or get_archived_runs, and how many runs match in all
+export interface RunPage {
+  runs: Run[];
+  total: number;
 }
 
 export interface RunStats {
@@ -264,14 +1138,65 @@ export interface RunStats {
   completedRuns: number;
   averageTimeMs: number | null;
   bestTimeMs: number | null;
+  averageLoadRemovedMs: number | null;
+  bestLoadRemovedMs: number | null;
+  totalDeaths: number;
+  deathlessRuns: number;
+}
+
+// A death of a run's character (get_deaths)
+export interface Death {
+  id: number;
+  runId: number;
+  // Client.txt timestamp
+  timestamp: string;
+  zoneName: string | null;
+  elapsedMs: number;
+  characterLevel: number | null;
+}
+
+// Abandoned runs by reason (get_abandonment_stats)
+export interface AbandonmentStats {
+  totalRuns: number;
+  completedRuns: number;
+  abandonedRuns: number;
+  // Unfinished runs reset without a reason, or still in progress
+  unexplainedRuns: number;
+  // Most common reason first
+  byReason: { reason: AbandonReason; count: number; share: number; averageAbandonedAtMs: number | null }[];
+}
+
+// Where runs were reset (get_reset_stats), most resets first
+export interface ResetStats {
+  attempts: number;
+  resets: number;
+  // breakpointName is null for runs reset before their first split
+  bySplit: { breakpointName: string | null; breakpointKey: string | null; act: number | null; count: number; share: number }[];
+  byAct: { act: number; count: number; share: number }[];
 }
 
 export interface SplitStat {
   breakpointName: string;
+  breakpointKey: string | null;
   averageTimeMs: number;
   bestTimeMs: number;
+  medianTimeMs: number;
+  p90TimeMs: number;
   averageTownTimeMs: number;
   runCount: number;
+  layouts: { layoutVariant: string; averageSegmentMs: number; runCount: number }[];
+  // Experience gained during the segment (null when no snapshots recorded it)
+  averageSegmentExperience: number | null;
+  experiencePerMinute: number | null;
+}
+
+// One run's time at a breakpoint, for plotting whether it is improving
+export interface SplitTrendPoint {
+  runId: number;
+  startedAt: string;
+  splitTimeMs: number;
+  // Average over this run and the four before it
+  rollingAverageMs: number;
 }
 
 // Reference run data for manual entry
@@ -291,5 +1216,83 @@ export interface ReferenceRunData {
 export interface ReferenceSplitData {
   breakpointName: string;
   breakpointType: string;
+  breakpointKey?: string | null;
   splitTimeMs: number;
 }
+
+// A character on poe.ninja's build overview, from fetch_ninja_builds (see src-tauri/src/poeninja.rs)
+export interface NinjaBuild {
+  rank: number;
+  characterName: string;
+  accountName: string;
+  class: string;
+  level: number;
+}
+
+// Reference run and snapshot create_reference_snapshot_from_ninja saved
+export interface NinjaReference {
+  runId: number;
+  snapshotId: number;
+}
+
+// A ladder character from fetch_ladder (see src-tauri/src/ladder.rs)
+export interface LadderRacer {
+  rank: number;
+  characterName: string;
+  accountName: string;
+  // Ascendancy once ascended, base class before
+  class: string;
+  level: number;
+  experience: number;
+  dead: boolean;
+  // Level milestones timed so far from repeated fetches
+  milestones: number;
+}
+
+// What changed since the app last ran (see src-tauri/src/changelog.rs)
+export interface ChangeGroup {
+  heading: string;
+  entries: string[];
+}
+
+export interface ChangelogRelease {
+  version: string;
+  date: string | null;
+  groups: ChangeGroup[];
+}
+
+export interface MigrationNotice {
+  name: string;
+  notice: string | null;
+}
+
+export interface AppChangelogState {
+  currentVersion: string;
+  previousVersion: string | null;
+  isFirstRun: boolean;
+  isUpgrade: boolean;
+  releases: ChangelogRelease[];
+  migrations: MigrationNotice[];
+}
+
+// Error returned by backend commands (see src-tauri/src/error.rs)
+export type AppErrorCode =
+  | 'profile_private'
+  | 'rate_limited'
+  | 'unauthorized'
+  | 'database_locked'
+  | 'database'
+  | 'not_found'
+  | 'invalid_input'
+  | 'network'
+  | 'external_service'
+  | 'timeout'
+  | 'io'
+  | 'window'
+  | 'shortcut_unavailable'
+  | 'internal';
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+}
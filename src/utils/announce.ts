@@ -0,0 +1,39 @@
+import type { SplitAnnouncement } from '../types';
+
+// Tone pitch per announcement kind (Hz)
+const TONES: Record<SplitAnnouncement['kind'], number> = {
+  gold: 1046,
+  ahead: 784,
+  behind: 392,
+};
+
+let audioContext: AudioContext | null = null;
+
+// Short sine beep; reuses one AudioContext for the whole session
+function playTone(frequency: number) {
+  audioContext ??= new AudioContext();
+  const ctx = audioContext;
+  const oscillator = ctx.createOscillator();
+  const gain = ctx.createGain();
+  oscillator.frequency.value = frequency;
+  gain.gain.setValueAtTime(0.2, ctx.currentTime);
+  gain.gain.exponentialRampToValueAtTime(0.001, ctx.currentTime + 0.3);
+  oscillator.connect(gain).connect(ctx.destination);
+  oscillator.start();
+  oscillator.stop(ctx.currentTime + 0.3);
+}
+
+// Play a split-announcement event; the backend already decided it should fire
+export function announceSplit(announcement: SplitAnnouncement) {
+  try {
+    if (announcement.sound) {
+      playTone(TONES[announcement.kind]);
+    }
+    if (announcement.speech && 'speechSynthesis' in window) {
+      window.speechSynthesis.cancel();
+      window.speechSynthesis.speak(new SpeechSynthesisUtterance(announcement.message));
+    }
+  } catch (error) {
+    console.error('[announce] Failed to announce split:', error);
+  }
+}
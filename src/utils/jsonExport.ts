@@ -1,22 +1,23 @@
 import { invoke } from '@tauri-apps/api/core';
 import { save } from '@tauri-apps/plugin-dialog';
-import type { Run } from '../types';
+import type { Run, RunCertificate, RunFilters } from '../types';
 
 function sanitizeFilename(str: string): string {
   return str.replace(/[^a-zA-Z0-9_-]/g, '_').replace(/_+/g, '_');
 }
 
+function defaultFilename(runId: number, run?: Run): string {
+  if (!run) return `run_${runId}`;
+  const charPart = sanitizeFilename(run.characterName || run.character || 'unknown');
+  const catPart = sanitizeFilename(run.category || 'run');
+  const datePart = run.startedAt
+    ? new Date(run.startedAt).toISOString().slice(0, 10)
+    : new Date().toISOString().slice(0, 10);
+  return `${charPart}_${catPart}_${datePart}`;
+}
+
 export async function exportRunToJson(runId: number, run?: Run): Promise<void> {
-  // Build a suggested filename
-  let defaultName = `run_${runId}`;
-  if (run) {
-    const charPart = sanitizeFilename(run.characterName || run.character || 'unknown');
-    const catPart = sanitizeFilename(run.category || 'run');
-    const datePart = run.startedAt
-      ? new Date(run.startedAt).toISOString().slice(0, 10)
-      : new Date().toISOString().slice(0, 10);
-    defaultName = `${charPart}_${catPart}_${datePart}`;
-  }
+  const defaultName = defaultFilename(runId, run);
 
   const filePath = await save({
     defaultPath: `${defaultName}.json`,
@@ -27,3 +28,84 @@ export async function exportRunToJson(runId: number, run?: Run): Promise<void> {
 
   await invoke('export_run_json', { runId, filePath });
 }
+
+// Splits table as CSV, Markdown or HTML; the backend picks the format from the
+// extension and formats numbers and dates for the locale setting
+export async function exportRunTable(runId: number, run?: Run): Promise<void> {
+  const filePath = await save({
+    defaultPath: `${defaultFilename(runId, run)}_splits.csv`,
+    filters: [
+      { name: 'CSV', extensions: ['csv'] },
+      { name: 'Markdown', extensions: ['md'] },
+      { name: 'HTML', extensions: ['html'] },
+    ],
+  });
+
+  if (!filePath) return; // user cancelled
+
+  await invoke('export_run_table', { runId, filePath });
+}
+
+// LiveSplit splits file with the category PB as the comparison and golds as Best Segments
+export async function exportRunLiveSplit(runId: number, run?: Run): Promise<void> {
+  const filePath = await save({
+    defaultPath: `${defaultFilename(runId, run)}.lss`,
+    filters: [{ name: 'LiveSplit Splits', extensions: ['lss'] }],
+  });
+
+  if (!filePath) return; // user cancelled
+
+  await invoke('export_run_livesplit', { runId, path: filePath });
+}
+
+// Certificate JSON for a completed run, for pasting alongside a leaderboard submission
+export async function copyRunCertificate(runId: number): Promise<void> {
+  const certificate = await invoke<RunCertificate>('get_run_certificate', { runId });
+  await navigator.clipboard.writeText(JSON.stringify(certificate, null, 2));
+}
+
+export async function exportSplitsImage(runId: number, run?: Run): Promise<void> {
+  const filePath = await save({
+    defaultPath: `${defaultFilename(runId, run)}_splits.png`,
+    filters: [{ name: 'PNG Image', extensions: ['png'] }],
+  });
+
+  if (!filePath) return; // user cancelled
+
+  await invoke('render_splits_image', { runId, path: filePath });
+}
+
+export async function exportShareCard(runId: number, run?: Run): Promise<void> {
+  const filePath = await save({
+    defaultPath: `${defaultFilename(runId, run)}_card.png`,
+    filters: [{ name: 'PNG Image', extensions: ['png'] }],
+  });
+
+  if (!filePath) return; // user cancelled
+
+  await invoke('generate_share_card', { runId, path: filePath });
+}
+
+// History as CSV for spreadsheets: one row per filtered run, or one per split of
+// the given runs. Resolves to the number of rows written, or null when cancelled.
+export async function exportRunsCsv(filters: RunFilters): Promise<number | null> {
+  const path = await save({
+    defaultPath: `poe-watcher-runs_${new Date().toISOString().slice(0, 10)}.csv`,
+    filters: [{ name: 'CSV', extensions: ['csv'] }],
+  });
+
+  if (!path) return null; // user cancelled
+
+  return invoke<number>('export_runs_csv', { filters, path });
+}
+
+export async function exportSplitsCsv(runIds: number[]): Promise<number | null> {
+  const path = await save({
+    defaultPath: `poe-watcher-splits_${new Date().toISOString().slice(0, 10)}.csv`,
+    filters: [{ name: 'CSV', extensions: ['csv'] }],
+  });
+
+  if (!path) return null; // user cancelled
+
+  return invoke<number>('export_splits_csv', { runIds, path });
+}
@@ -1090,7 +1090,7 @@ export function createMultiBuildData(snapshots: Snapshot[], run: Run, splits?: S
 
     // Create label: "Zone Name - Level X [MM:SS]" or fallback to time-based
     const timeStr = formatTimeForLabel(snapshot.elapsedTimeMs);
-    const zoneName = splitMap.get(snapshot.splitId);
+    const zoneName = snapshot.splitId !== null ? splitMap.get(snapshot.splitId) : undefined;
     const label = zoneName
       ? `${zoneName} - Level ${snapshot.characterLevel} [${timeStr}]`
       : `${timeStr} - Level ${snapshot.characterLevel}`;